@@ -111,6 +111,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         solutions_dir: None, // Use default solutions directory
@@ -19,6 +19,7 @@ mod api_query_joins_tests {
     use crate::api::shared::error::error_codes;
     use crate::api::shared::extractor::ConfigBody;
     use crate::api::shared::handlers::*;
+    use crate::config::ServerMode;
     use crate::persistence::ConfigPersistence;
     use async_trait::async_trait;
     use axum::Extension;
@@ -91,7 +92,7 @@ mod api_query_joins_tests {
         async fn initialize(&self, _context: SourceRuntimeContext) {}
     }
 
-    async fn create_test_environment() -> (Arc<DrasiLib>, Arc<bool>, Option<Arc<ConfigPersistence>>)
+    async fn create_test_environment() -> (Arc<DrasiLib>, ServerMode, Option<Arc<ConfigPersistence>>)
     {
         // Register stub sources for all sources referenced by queries in these tests.
         // ComponentGraph now validates that referenced sources exist.
@@ -114,10 +115,10 @@ mod api_query_joins_tests {
         // Start the core
         core.start().await.expect("Failed to start core");
 
-        let read_only = Arc::new(false);
+        let mode = ServerMode::ReadWrite;
         let config_persistence: Option<Arc<ConfigPersistence>> = None;
 
-        (core, read_only, config_persistence)
+        (core, mode, config_persistence)
     }
 
     // Helper function to convert QueryConfig to QueryConfigDto for testing
@@ -156,7 +157,7 @@ mod api_query_joins_tests {
 
     #[tokio::test]
     async fn test_create_query_with_single_join_via_api() {
-        let (core, read_only, config_persistence) = create_test_environment().await;
+        let (core, mode, config_persistence) = create_test_environment().await;
 
         // Create a query config with a single join
         let join_config = QueryJoinConfig {
@@ -186,7 +187,7 @@ mod api_query_joins_tests {
         // Call the API handler
         let result = create_query(
             Extension(core.clone()),
-            Extension(read_only),
+            Extension(mode),
             Extension(config_persistence),
             Extension("test-server".to_string()),
             ConfigBody(query_config_to_dto(query_config.clone())),
@@ -202,7 +203,7 @@ mod api_query_joins_tests {
 
     #[tokio::test]
     async fn test_create_query_with_multiple_joins_via_api() {
-        let (core, read_only, config_persistence) = create_test_environment().await;
+        let (core, mode, config_persistence) = create_test_environment().await;
 
         // Create multiple joins
         let restaurant_join = QueryJoinConfig {
@@ -245,7 +246,7 @@ mod api_query_joins_tests {
         // Call the API handler
         let result = create_query(
             Extension(core.clone()),
-            Extension(read_only),
+            Extension(mode),
             Extension(config_persistence),
             Extension("test-server".to_string()),
             ConfigBody(query_config_to_dto(query_config.clone())),
@@ -261,7 +262,7 @@ mod api_query_joins_tests {
 
     #[tokio::test]
     async fn test_query_with_no_joins_via_api() {
-        let (core, read_only, config_persistence) = create_test_environment().await;
+        let (core, mode, config_persistence) = create_test_environment().await;
 
         // Create a query without joins
         let query_config = Query::cypher("simple-query")
@@ -273,7 +274,7 @@ mod api_query_joins_tests {
         // Call the API handler
         let result = create_query(
             Extension(core.clone()),
-            Extension(read_only),
+            Extension(mode),
             Extension(config_persistence),
             Extension("test-server".to_string()),
             ConfigBody(query_config_to_dto(query_config.clone())),
@@ -289,7 +290,7 @@ mod api_query_joins_tests {
 
     #[tokio::test]
     async fn test_query_with_empty_joins_array_via_api() {
-        let (core, read_only, config_persistence) = create_test_environment().await;
+        let (core, mode, config_persistence) = create_test_environment().await;
 
         // Create a query with empty joins array
         let query_config = Query::cypher("empty-joins-query")
@@ -302,7 +303,7 @@ mod api_query_joins_tests {
         // Call the API handler
         let result = create_query(
             Extension(core.clone()),
-            Extension(read_only),
+            Extension(mode),
             Extension(config_persistence),
             Extension("test-server".to_string()),
             ConfigBody(query_config_to_dto(query_config.clone())),
@@ -318,7 +319,7 @@ mod api_query_joins_tests {
 
     #[tokio::test]
     async fn test_get_query_returns_joins_via_api() {
-        let (core, read_only, config_persistence) = create_test_environment().await;
+        let (core, mode, config_persistence) = create_test_environment().await;
 
         // Create a query with joins
         let join_config = QueryJoinConfig {
@@ -346,7 +347,7 @@ mod api_query_joins_tests {
         // Create the query
         let _ = create_query(
             Extension(core.clone()),
-            Extension(read_only),
+            Extension(mode),
             Extension(config_persistence.clone()),
             Extension("test-server".to_string()),
             ConfigBody(query_config_to_dto(query_config.clone())),
@@ -452,7 +453,7 @@ mod api_query_joins_tests {
     async fn test_read_only_mode_blocks_query_creation_with_joins() {
         let (core, _, config_persistence) = create_test_environment().await;
 
-        let read_only = Arc::new(true); // Set read-only mode
+        let mode = ServerMode::ReadOnly;
 
         let join_config = QueryJoinConfig {
             id: "TEST_JOIN".to_string(),
@@ -478,7 +479,7 @@ mod api_query_joins_tests {
         // Try to create query in read-only mode
         let result = create_query(
             Extension(core.clone()),
-            Extension(read_only),
+            Extension(mode),
             Extension(config_persistence),
             Extension("test-server".to_string()),
             ConfigBody(query_config_to_dto(query_config)),
@@ -19,8 +19,10 @@ use super::resolver::{
     ValueResolver,
 };
 use crate::api::models::ConfigValue;
+use drasi_lib::secret_store::SecretStoreProvider;
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Errors that can occur during mapping
@@ -60,7 +62,7 @@ impl DtoMapper {
     pub fn new() -> Self {
         let mut resolvers: HashMap<&'static str, Box<dyn ValueResolver>> = HashMap::new();
         resolvers.insert("EnvironmentVariable", Box::new(EnvironmentVariableResolver));
-        resolvers.insert("Secret", Box::new(SecretResolver));
+        resolvers.insert("Secret", Box::new(SecretResolver::default()));
 
         Self { resolvers }
     }
@@ -77,11 +79,20 @@ impl DtoMapper {
             "EnvironmentVariable",
             Box::new(OverridingEnvResolver::new(overrides)),
         );
-        resolvers.insert("Secret", Box::new(SecretResolver));
+        resolvers.insert("Secret", Box::new(SecretResolver::default()));
 
         Self { resolvers }
     }
 
+    /// Resolve `{kind: Secret}` values through `store`.
+    ///
+    /// Without a store, secret references fail to resolve.
+    pub fn with_secret_store(mut self, store: Arc<dyn SecretStoreProvider>) -> Self {
+        self.resolvers
+            .insert("Secret", Box::new(SecretResolver::new(store)));
+        self
+    }
+
     /// Resolve a ConfigValue<String> to its actual string value
     pub fn resolve_string(&self, value: &ConfigValue<String>) -> Result<String, ResolverError> {
         match value {
@@ -170,9 +181,9 @@ impl DtoMapper {
 
     /// Helper to resolve secret name to string (used by resolve_typed)
     fn resolve_secret_to_string(&self, name: &str) -> Result<String, ResolverError> {
-        Err(ResolverError::NotImplemented(format!(
-            "Secret resolution not yet implemented for '{name}'"
-        )))
+        self.resolve_string(&ConfigValue::Secret {
+            name: name.to_string(),
+        })
     }
 
     /// Map using a config mapper implementation
@@ -15,7 +15,10 @@
 //! Value resolvers for different ConfigValue reference types.
 
 use crate::api::models::ConfigValue;
+use crate::factories::ConfigResolverContext;
+use drasi_lib::secret_store::SecretStoreProvider;
 use std::collections::HashMap;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Errors that can occur during value resolution
@@ -35,6 +38,12 @@ pub enum ResolverError {
 
     #[error("Failed to parse value: {0}")]
     ParseError(String),
+
+    #[error("No secret store is configured to resolve secret '{0}'")]
+    NoSecretStore(String),
+
+    #[error("Failed to resolve secret '{name}': {reason}")]
+    SecretNotResolved { name: String, reason: String },
 }
 
 /// Trait for resolving a specific type of ConfigValue variant
@@ -61,15 +70,38 @@ impl ValueResolver for EnvironmentVariableResolver {
     }
 }
 
-/// Secret resolver (unimplemented)
-pub struct SecretResolver;
+/// Secret resolver backed by a secret store.
+///
+/// Stores are async; resolution blocks on the host's config resolver thread,
+/// the same path plugins use to resolve secrets while their components are
+/// created.
+#[derive(Default)]
+pub struct SecretResolver {
+    store: Option<Arc<dyn SecretStoreProvider>>,
+}
+
+impl SecretResolver {
+    /// Create a resolver that reads secrets from `store`.
+    pub fn new(store: Arc<dyn SecretStoreProvider>) -> Self {
+        Self { store: Some(store) }
+    }
+}
 
 impl ValueResolver for SecretResolver {
     fn resolve_to_string(&self, value: &ConfigValue<String>) -> Result<String, ResolverError> {
         match value {
-            ConfigValue::Secret { name } => Err(ResolverError::NotImplemented(format!(
-                "Secret resolution not yet implemented for '{name}'"
-            ))),
+            ConfigValue::Secret { name } => {
+                let store = self
+                    .store
+                    .clone()
+                    .ok_or_else(|| ResolverError::NoSecretStore(name.clone()))?;
+                ConfigResolverContext::global()
+                    .resolve_secret(store, name)
+                    .map_err(|reason| ResolverError::SecretNotResolved {
+                        name: name.clone(),
+                        reason,
+                    })
+            }
             _ => Err(ResolverError::WrongResolverType),
         }
     }
@@ -179,8 +211,8 @@ mod tests {
     }
 
     #[test]
-    fn test_secret_resolver_not_implemented() {
-        let resolver = SecretResolver;
+    fn test_secret_resolver_without_store() {
+        let resolver = SecretResolver::default();
         let value = ConfigValue::Secret {
             name: "my-secret".to_string(),
         };
@@ -189,7 +221,28 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
-            ResolverError::NotImplemented(_)
+            ResolverError::NoSecretStore(_)
+        ));
+    }
+
+    #[test]
+    fn test_secret_resolver_with_store() {
+        use drasi_lib::secret_store::MemorySecretStoreProvider;
+
+        let store = MemorySecretStoreProvider::new().with_secret("my-secret", "hunter2");
+        let resolver = SecretResolver::new(Arc::new(store));
+
+        let value = ConfigValue::Secret {
+            name: "my-secret".to_string(),
+        };
+        assert_eq!(resolver.resolve_to_string(&value).unwrap(), "hunter2");
+
+        let missing = ConfigValue::Secret {
+            name: "other".to_string(),
+        };
+        assert!(matches!(
+            resolver.resolve_to_string(&missing).unwrap_err(),
+            ResolverError::SecretNotResolved { .. }
         ));
     }
 
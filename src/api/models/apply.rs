@@ -0,0 +1,114 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for declaratively applying a multi-resource manifest to an instance.
+//!
+//! A manifest has the same shape as the `sources`, `queries`, and
+//! `reactions` sections of the server config file. Applying it computes the
+//! create/update/delete actions needed to bring the instance to the declared
+//! state and performs them as one unit.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Desired components of an instance.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ApplyManifest {
+    /// Source configurations (same format as `sources` in the config file)
+    #[serde(default)]
+    pub sources: Vec<serde_json::Value>,
+
+    /// Query configurations (same format as `queries` in the config file)
+    #[serde(default)]
+    pub queries: Vec<serde_json::Value>,
+
+    /// Reaction configurations (same format as `reactions` in the config file)
+    #[serde(default)]
+    pub reactions: Vec<serde_json::Value>,
+}
+
+/// Query parameters for `POST /instances/{instanceId}/apply`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyParams {
+    /// Compute and return the plan without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Delete components that exist in the instance but not in the manifest
+    #[serde(default)]
+    pub prune: bool,
+}
+
+/// What applying the manifest does to one component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ApplyActionKind {
+    /// The component does not exist and is created
+    Create,
+    /// The component exists with a different configuration and is replaced
+    Update,
+    /// The component is not in the manifest and is deleted (`prune=true`)
+    Delete,
+    /// The component already matches the manifest
+    Unchanged,
+}
+
+/// A planned (or performed) action on one component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyAction {
+    /// Component type: "source", "query", or "reaction"
+    pub component_type: String,
+
+    /// Component ID
+    pub id: String,
+
+    /// Action taken on the component
+    pub action: ApplyActionKind,
+}
+
+impl ApplyAction {
+    pub fn new(component_type: &str, id: &str, action: ApplyActionKind) -> Self {
+        Self {
+            component_type: component_type.to_string(),
+            id: id.to_string(),
+            action,
+        }
+    }
+}
+
+/// Result of applying a manifest.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyResponse {
+    /// Whether this was a dry run (nothing was changed)
+    pub dry_run: bool,
+
+    /// Whether the actions were applied. `false` for dry runs; a failed
+    /// apply is rolled back and returned as an error instead.
+    pub applied: bool,
+
+    /// Planned actions, in the order they are performed
+    pub actions: Vec<ApplyAction>,
+
+    /// Components started after the apply (as "type:id")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components_started: Vec<String>,
+
+    /// Errors from starting new components or deprovisioning pruned ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,189 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Archive index retention DTOs.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Limits on the element versions kept in a query's archive index.
+///
+/// Both limits may be set; a version is dropped when either one says so.
+/// The newest version at or before the `maxAgeSecs` cutoff is always kept,
+/// so `past()` lookups anywhere inside the window still find the value that
+/// was in effect at its start.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ArchiveRetentionLimits {
+    /// Drop element versions that were superseded more than this many
+    /// seconds ago.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_secs: Option<u64>,
+    /// Keep at most this many versions of each element (minimum 1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_versions: Option<u32>,
+}
+
+/// Retention policy for an instance's archive index (`enableArchive`).
+///
+/// A background task applies the limits to every persistent query of the
+/// instance each `intervalSecs`. Entries under `queries` override the
+/// instance limits for one query; fields a query leaves unset fall back to
+/// the instance's.
+///
+/// # Example YAML
+///
+/// ```yaml
+/// enableArchive: true
+/// archiveRetention:
+///   maxAgeSecs: 604800
+///   queries:
+///     order-history:
+///       maxVersions: 100
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ArchiveRetentionConfig {
+    /// Drop element versions that were superseded more than this many
+    /// seconds ago.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_secs: Option<u64>,
+    /// Keep at most this many versions of each element (minimum 1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_versions: Option<u32>,
+    /// Seconds between retention passes (default: 300).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+    /// Per-query overrides, keyed by query id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub queries: BTreeMap<String, ArchiveRetentionLimits>,
+}
+
+const DEFAULT_INTERVAL_SECS: u64 = 300;
+
+impl ArchiveRetentionConfig {
+    /// The limits that apply to `query_id`.
+    pub fn limits_for(&self, query_id: &str) -> ArchiveRetentionLimits {
+        let query = self.queries.get(query_id);
+        ArchiveRetentionLimits {
+            max_age_secs: query
+                .and_then(|limits| limits.max_age_secs)
+                .or(self.max_age_secs),
+            max_versions: query
+                .and_then(|limits| limits.max_versions)
+                .or(self.max_versions),
+        }
+    }
+
+    /// Time between retention passes.
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS).max(1))
+    }
+
+    /// Reject settings that would empty the archive.
+    pub fn validate(&self) -> Result<(), String> {
+        let limits = std::iter::once(("archiveRetention".to_string(), self.max_versions)).chain(
+            self.queries.iter().map(|(id, limits)| {
+                (
+                    format!("archiveRetention.queries.{id}"),
+                    limits.max_versions,
+                )
+            }),
+        );
+        for (path, max_versions) in limits {
+            if max_versions == Some(0) {
+                return Err(format!("{path}.maxVersions must be at least 1"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of the most recent retention pass over one query's archive.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryArchiveStatus {
+    pub query_id: String,
+    /// Limits applied to this query.
+    pub limits: ArchiveRetentionLimits,
+    /// When the last pass finished (RFC 3339), if one has run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<String>,
+    /// Versions left in the archive after the last pass.
+    pub versions: u64,
+    /// Key and value bytes of those versions, before RocksDB compression.
+    pub bytes: u64,
+    /// Versions dropped by the last pass.
+    pub last_pruned: u64,
+    /// Versions dropped since the server started.
+    pub total_pruned: u64,
+    /// Error from the last pass, if it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Archive retention state and index disk usage of an instance.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveUsage {
+    pub instance_id: String,
+    /// Retention policy in force, or `null` if the archive is unbounded.
+    pub retention: Option<ArchiveRetentionConfig>,
+    /// Bytes on disk under the instance's persistent index directory, or
+    /// `null` if the instance has no persistent index.
+    pub index_bytes: Option<u64>,
+    /// Per-query retention results. Empty without a retention policy.
+    pub queries: Vec<QueryArchiveStatus>,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_limits_fall_back_to_instance_limits() {
+        let config: ArchiveRetentionConfig = serde_yaml::from_str(
+            "maxAgeSecs: 3600\nmaxVersions: 10\nqueries:\n  q1:\n    maxVersions: 2\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.limits_for("q1"),
+            ArchiveRetentionLimits {
+                max_age_secs: Some(3600),
+                max_versions: Some(2),
+            }
+        );
+        assert_eq!(
+            config.limits_for("other"),
+            ArchiveRetentionLimits {
+                max_age_secs: Some(3600),
+                max_versions: Some(10),
+            }
+        );
+        assert_eq!(config.interval(), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_rejects_zero_max_versions_and_unknown_fields() {
+        let config: ArchiveRetentionConfig =
+            serde_yaml::from_str("queries:\n  q1:\n    maxVersions: 0\n").unwrap();
+        assert_eq!(
+            config.validate().unwrap_err(),
+            "archiveRetention.queries.q1.maxVersions must be at least 1"
+        );
+        assert!(serde_yaml::from_str::<ArchiveRetentionConfig>("maxAge: 10\n").is_err());
+    }
+}
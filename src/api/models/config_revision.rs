@@ -0,0 +1,76 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for inspecting the config revision history and rolling back to an
+//! earlier revision.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::apply::ApplyResponse;
+use crate::config_revisions::ConfigRevision;
+
+/// A config revision together with its changes.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRevisionDiff {
+    /// Revision metadata
+    #[serde(flatten)]
+    pub revision: ConfigRevision,
+
+    /// Unified diff of the config file against the previous revision
+    pub diff: String,
+}
+
+/// Query parameters for `POST /config/revisions/{revision}/rollback`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackParams {
+    /// Compute and return the per-instance plans without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Result of reconciling one instance to a revision.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceRollback {
+    /// Instance ID
+    pub instance_id: String,
+
+    /// Actions planned or performed on the instance's components
+    pub result: ApplyResponse,
+}
+
+/// Result of rolling back to a config revision.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackResponse {
+    /// Revision rolled back to
+    pub revision: u64,
+
+    /// Whether this was a dry run (nothing was changed)
+    pub dry_run: bool,
+
+    /// Whether every instance was reconciled successfully
+    pub applied: bool,
+
+    /// Per-instance results, in revision order
+    pub instances: Vec<InstanceRollback>,
+
+    /// Instances declared in the revision that are not running and were
+    /// therefore not reconciled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_instances: Vec<String>,
+}
@@ -36,16 +36,26 @@
 pub mod bootstrap;
 
 // Organized submodules
+pub mod apply;
+pub mod archive_retention;
+pub mod config_revision;
 pub mod identity_provider;
 pub mod observability;
 pub mod queries;
 pub mod reaction;
+pub mod restart_policy;
 pub mod secret_store;
 pub mod solution;
 pub mod source;
 pub mod state_store;
+pub mod storage;
+pub mod validation;
+pub mod wal;
 
 // Re-export all DTO types for convenient access
+pub use archive_retention::{
+    ArchiveRetentionConfig, ArchiveRetentionLimits, ArchiveUsage, QueryArchiveStatus,
+};
 pub use bootstrap::{
     BootstrapProviderConfig, BootstrapProviderRef, TopLevelBootstrapProviderConfig,
 };
@@ -54,6 +64,15 @@ pub use identity_provider::{IdentityProviderConfig, BUILTIN_PASSWORD_KIND};
 pub use observability::*;
 pub use queries::*;
 pub use reaction::ReactionConfig;
+pub use restart_policy::{RestartMode, RestartPolicy};
 pub use secret_store::SecretStoreConfig;
 pub use source::SourceConfig;
-pub use state_store::{RedbStateStoreConfigDto, StateStoreConfig};
+pub use state_store::{
+    RedbStateStoreConfigDto, SqliteStateStoreConfigDto, StateStoreConfig, StateStoreDump,
+    StateStoreEntry, StateStorePartition, StateStoreResetResponse, StateValueEncoding,
+};
+pub use storage::{InstanceStorageReport, QueryStorageUsage, SourceWalUsage};
+pub use wal::{
+    InstanceWalStatus, QueryWalCheckpoint, ReplayRequest, ReplayResponse, SkippedReplay,
+    SourceWalStatus, WalRetentionConfig,
+};
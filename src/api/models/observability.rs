@@ -108,6 +108,10 @@ pub struct ComponentEventDto {
     pub timestamp: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// Current revision of the component (its `ETag` value, unquoted), so
+    /// clients can detect that their copy of it is stale.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<u64>,
 }
 
 impl From<ComponentEvent> for ComponentEventDto {
@@ -118,6 +122,7 @@ impl From<ComponentEvent> for ComponentEventDto {
             status: ComponentStatusDto::from(event.status),
             timestamp: event.timestamp,
             message: event.message,
+            revision: None,
         }
     }
 }
@@ -15,5 +15,7 @@
 //! Query configuration DTOs
 
 pub mod query;
+pub mod rebootstrap;
 
 pub use query::*;
+pub use rebootstrap::QueryRebootstrapResponse;
@@ -0,0 +1,34 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Query re-bootstrap DTOs
+
+use serde::Serialize;
+
+use crate::api::models::ComponentStatusDto;
+
+/// Outcome of a query re-bootstrap.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRebootstrapResponse {
+    pub query_id: String,
+    /// Whether the query was running and has been restarted to bootstrap
+    /// now. A stopped query was cleared and bootstraps when it is next
+    /// started.
+    pub restarted: bool,
+    /// Status of the query after the request; `Starting` until the
+    /// bootstrap completes.
+    #[schema(value_type = ComponentStatus)]
+    pub status: ComponentStatusDto,
+}
@@ -18,6 +18,8 @@ use serde::de::{self, Deserializer, MapAccess, Visitor};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use super::restart_policy::RestartPolicy;
+
 /// Reaction configuration with kind discriminator.
 ///
 /// A generic struct that holds the plugin kind, common fields (id, queries,
@@ -34,6 +36,13 @@ pub struct ReactionConfig {
     /// attached to the reaction via `Reaction::set_identity_provider` after
     /// construction.
     pub identity_provider: Option<String>,
+    /// Whether (and how) the server restarts this reaction after it enters
+    /// `Error`. `None` behaves like `mode: never`.
+    pub restart_policy: Option<RestartPolicy>,
+    /// Plugin config schema version (`config_version()` of the descriptor)
+    /// that `config` was written against. Used to migrate configs forward
+    /// after a plugin upgrade; `None` for configs that predate versioning.
+    pub config_version: Option<String>,
     pub config: serde_json::Value,
 }
 
@@ -51,6 +60,12 @@ impl Serialize for ReactionConfig {
         if let Some(ip) = &self.identity_provider {
             map.serialize_entry("identityProvider", ip)?;
         }
+        if let Some(rp) = &self.restart_policy {
+            map.serialize_entry("restartPolicy", rp)?;
+        }
+        if let Some(cv) = &self.config_version {
+            map.serialize_entry("configVersion", cv)?;
+        }
         if let serde_json::Value::Object(config_map) = &self.config {
             for (k, v) in config_map {
                 map.serialize_entry(k, v)?;
@@ -84,6 +99,8 @@ impl<'de> Deserialize<'de> for ReactionConfig {
                 let mut queries: Option<Vec<String>> = None;
                 let mut auto_start: Option<bool> = None;
                 let mut identity_provider: Option<String> = None;
+                let mut restart_policy: Option<RestartPolicy> = None;
+                let mut config_version: Option<String> = None;
 
                 // Collect remaining fields for the inner config
                 let mut remaining = serde_json::Map::new();
@@ -120,6 +137,18 @@ impl<'de> Deserialize<'de> for ReactionConfig {
                             }
                             identity_provider = Some(map.next_value()?);
                         }
+                        "restartPolicy" => {
+                            if restart_policy.is_some() {
+                                return Err(de::Error::duplicate_field("restartPolicy"));
+                            }
+                            restart_policy = Some(map.next_value()?);
+                        }
+                        "configVersion" => {
+                            if config_version.is_some() {
+                                return Err(de::Error::duplicate_field("configVersion"));
+                            }
+                            config_version = Some(map.next_value()?);
+                        }
                         // Reject common snake_case misspellings of known fields
                         "auto_start" => {
                             return Err(de::Error::custom(
@@ -131,6 +160,16 @@ impl<'de> Deserialize<'de> for ReactionConfig {
                                 "unknown field `identity_provider`, did you mean `identityProvider`?",
                             ));
                         }
+                        "restart_policy" => {
+                            return Err(de::Error::custom(
+                                "unknown field `restart_policy`, did you mean `restartPolicy`?",
+                            ));
+                        }
+                        "config_version" => {
+                            return Err(de::Error::custom(
+                                "unknown field `config_version`, did you mean `configVersion`?",
+                            ));
+                        }
                         // Collect all other fields for the inner config
                         other => {
                             let value: serde_json::Value = map.next_value()?;
@@ -153,6 +192,8 @@ impl<'de> Deserialize<'de> for ReactionConfig {
                     queries,
                     auto_start,
                     identity_provider,
+                    restart_policy,
+                    config_version,
                     config: remaining_value,
                 })
             }
@@ -184,6 +225,16 @@ impl ReactionConfig {
         self.identity_provider.as_deref()
     }
 
+    /// Get the restart policy, if one is configured.
+    pub fn restart_policy(&self) -> Option<&RestartPolicy> {
+        self.restart_policy.as_ref()
+    }
+
+    /// Get the plugin config schema version this config was written against.
+    pub fn config_version(&self) -> Option<&str> {
+        self.config_version.as_deref()
+    }
+
     /// Get the reaction kind
     pub fn kind(&self) -> &str {
         &self.kind
@@ -542,6 +593,8 @@ autoStart: true
             queries: vec!["q1".to_string(), "q2".to_string()],
             auto_start: false,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({"routes": {}}),
         };
 
@@ -574,4 +627,21 @@ autoStart: true
             "${BASE_URL:-http://localhost:8080}"
         );
     }
+
+    #[test]
+    fn test_reaction_deserialize_restart_policy() {
+        let json = r#"{
+            "kind": "http",
+            "id": "webhook",
+            "queries": ["q1"],
+            "restartPolicy": { "mode": "onFailure", "initialBackoffMs": 250 },
+            "baseUrl": "http://localhost:8080"
+        }"#;
+
+        let reaction: ReactionConfig = serde_json::from_str(json).unwrap();
+        let policy = reaction.restart_policy().expect("Expected restart policy");
+        assert!(policy.restarts_on_failure());
+        assert_eq!(policy.initial_backoff_ms, 250);
+        assert!(reaction.config.get("restartPolicy").is_none());
+    }
 }
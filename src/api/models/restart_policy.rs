@@ -0,0 +1,173 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Restart policy DTO shared by sources and reactions.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// When the server should restart a failed component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum RestartMode {
+    /// Leave the component in `Error` until it is started manually.
+    Never,
+    /// Restart the component with exponential backoff when it enters `Error`.
+    OnFailure,
+}
+
+/// Restart policy for a source or reaction.
+///
+/// The server watches the component event stream and, for components with
+/// `mode: onFailure`, starts them again after they transition to `Error`.
+/// The delay doubles with each consecutive attempt, starting at
+/// `initialBackoffMs` and capped at `maxBackoffMs`. The attempt counter
+/// resets once the component has stayed up for `resetAfterSecs`.
+///
+/// # Example YAML
+///
+/// ```yaml
+/// sources:
+///   - kind: postgres
+///     id: orders-db
+///     restartPolicy:
+///       mode: onFailure
+///       maxAttempts: 10
+///       initialBackoffMs: 500
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    /// Maximum consecutive restart attempts before giving up (0 = unlimited).
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first restart attempt, in milliseconds.
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// Upper bound on the delay between restart attempts, in milliseconds.
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Seconds a component must stay up before its attempt counter resets.
+    #[serde(default = "default_reset_after_secs")]
+    pub reset_after_secs: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_max_backoff_ms() -> u64 {
+    60_000
+}
+
+fn default_reset_after_secs() -> u64 {
+    300
+}
+
+impl RestartPolicy {
+    /// An `onFailure` policy with default backoff settings.
+    pub fn on_failure() -> Self {
+        Self {
+            mode: RestartMode::OnFailure,
+            max_attempts: default_max_attempts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            reset_after_secs: default_reset_after_secs(),
+        }
+    }
+
+    /// Whether this policy restarts failed components at all.
+    pub fn restarts_on_failure(&self) -> bool {
+        self.mode == RestartMode::OnFailure
+    }
+
+    /// Delay before restart attempt `attempt` (1-based).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(63);
+        let delay = self
+            .initial_backoff_ms
+            .saturating_mul(1u64 << exponent)
+            .min(self.max_backoff_ms.max(self.initial_backoff_ms));
+        Duration::from_millis(delay)
+    }
+
+    /// Whether another attempt is allowed after `attempts_made` attempts.
+    pub fn allows_attempt(&self, attempts_made: u32) -> bool {
+        self.max_attempts == 0 || attempts_made < self.max_attempts
+    }
+
+    /// How long a component must stay up before its attempts are forgotten.
+    pub fn reset_after(&self) -> Duration {
+        Duration::from_secs(self.reset_after_secs)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_policy_defaults() {
+        let policy: RestartPolicy = serde_yaml::from_str("mode: onFailure").unwrap();
+        assert_eq!(policy, RestartPolicy::on_failure());
+        assert!(policy.restarts_on_failure());
+
+        let never: RestartPolicy = serde_yaml::from_str("mode: never").unwrap();
+        assert!(!never.restarts_on_failure());
+    }
+
+    #[test]
+    fn test_restart_policy_rejects_unknown_fields() {
+        let result: Result<RestartPolicy, _> =
+            serde_yaml::from_str("mode: onFailure\nmaxRetries: 3");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_restart_policy_backoff_doubles_and_caps() {
+        let policy = RestartPolicy {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1000,
+            ..RestartPolicy::on_failure()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(200), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_restart_policy_max_attempts() {
+        let policy = RestartPolicy {
+            max_attempts: 2,
+            ..RestartPolicy::on_failure()
+        };
+        assert!(policy.allows_attempt(0));
+        assert!(policy.allows_attempt(1));
+        assert!(!policy.allows_attempt(2));
+
+        let unlimited = RestartPolicy {
+            max_attempts: 0,
+            ..RestartPolicy::on_failure()
+        };
+        assert!(unlimited.allows_attempt(u32::MAX - 1));
+    }
+}
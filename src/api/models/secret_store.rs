@@ -15,6 +15,7 @@
 //! Secret store configuration DTOs.
 
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Secret store configuration with kind discriminator and opaque config.
 ///
@@ -36,12 +37,27 @@ pub struct SecretStoreConfig {
     /// The secret store plugin kind (e.g., "file", "keyring", "azure-keyvault")
     pub kind: String,
 
+    /// How often, in seconds, to re-read the secrets components consumed and
+    /// reconfigure the components whose secrets were rotated. Omit (or set
+    /// 0) to resolve secrets only when components are created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_interval_secs: Option<u64>,
+
     /// Opaque configuration passed to the plugin's `create_secret_store()`.
     /// All fields except `kind` are collected here.
     #[serde(flatten)]
     pub config: serde_json::Value,
 }
 
+impl SecretStoreConfig {
+    /// The rotation check interval, if rotation is enabled.
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        self.refresh_interval_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -88,6 +104,7 @@ path: ./secrets.json
     fn test_secret_store_serialize_roundtrip() {
         let original = SecretStoreConfig {
             kind: "file".to_string(),
+            refresh_interval_secs: None,
             config: serde_json::json!({"path": "./secrets.json"}),
         };
 
@@ -97,4 +114,17 @@ path: ./secrets.json
         assert_eq!(original.kind, deserialized.kind);
         assert_eq!(original.config["path"], deserialized.config["path"]);
     }
+
+    #[test]
+    fn test_refresh_interval_is_not_passed_to_the_plugin() {
+        let yaml = r#"
+kind: file
+path: ./secrets.json
+refreshIntervalSecs: 30
+"#;
+
+        let config: SecretStoreConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.refresh_interval_secs, Some(30));
+        assert!(config.config.get("refreshIntervalSecs").is_none());
+    }
 }
@@ -19,6 +19,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 use super::bootstrap::{BootstrapProviderConfig, BootstrapProviderRef};
+use super::restart_policy::RestartPolicy;
 
 /// Source configuration with kind discriminator.
 ///
@@ -53,6 +54,13 @@ pub struct SourceConfig {
     /// attached to the source via `Source::set_identity_provider` after
     /// construction.
     pub identity_provider: Option<String>,
+    /// Whether (and how) the server restarts this source after it enters
+    /// `Error`. `None` behaves like `mode: never`.
+    pub restart_policy: Option<RestartPolicy>,
+    /// Plugin config schema version (`config_version()` of the descriptor)
+    /// that `config` was written against. Used to migrate configs forward
+    /// after a plugin upgrade; `None` for configs that predate versioning.
+    pub config_version: Option<String>,
     pub config: serde_json::Value,
 }
 
@@ -79,6 +87,12 @@ impl Serialize for SourceConfig {
         if let Some(ip) = &self.identity_provider {
             map.serialize_entry("identityProvider", ip)?;
         }
+        if let Some(rp) = &self.restart_policy {
+            map.serialize_entry("restartPolicy", rp)?;
+        }
+        if let Some(cv) = &self.config_version {
+            map.serialize_entry("configVersion", cv)?;
+        }
         if let serde_json::Value::Object(config_map) = &self.config {
             for (k, v) in config_map {
                 map.serialize_entry(k, v)?;
@@ -112,6 +126,8 @@ impl<'de> Deserialize<'de> for SourceConfig {
                 let mut auto_start: Option<bool> = None;
                 let mut bootstrap_provider: Option<serde_json::Value> = None;
                 let mut identity_provider: Option<String> = None;
+                let mut restart_policy: Option<RestartPolicy> = None;
+                let mut config_version: Option<String> = None;
 
                 // Collect remaining fields for the inner config
                 let mut remaining = serde_json::Map::new();
@@ -148,6 +164,18 @@ impl<'de> Deserialize<'de> for SourceConfig {
                             }
                             identity_provider = Some(map.next_value()?);
                         }
+                        "restartPolicy" => {
+                            if restart_policy.is_some() {
+                                return Err(de::Error::duplicate_field("restartPolicy"));
+                            }
+                            restart_policy = Some(map.next_value()?);
+                        }
+                        "configVersion" => {
+                            if config_version.is_some() {
+                                return Err(de::Error::duplicate_field("configVersion"));
+                            }
+                            config_version = Some(map.next_value()?);
+                        }
                         // Reject common snake_case misspellings of known fields
                         "auto_start" => {
                             return Err(de::Error::custom(
@@ -164,6 +192,16 @@ impl<'de> Deserialize<'de> for SourceConfig {
                                 "unknown field `identity_provider`, did you mean `identityProvider`?"
                             ));
                         }
+                        "restart_policy" => {
+                            return Err(de::Error::custom(
+                                "unknown field `restart_policy`, did you mean `restartPolicy`?",
+                            ));
+                        }
+                        "config_version" => {
+                            return Err(de::Error::custom(
+                                "unknown field `config_version`, did you mean `configVersion`?",
+                            ));
+                        }
                         // Collect all other fields for the inner config
                         other => {
                             let value: serde_json::Value = map.next_value()?;
@@ -212,6 +250,8 @@ impl<'de> Deserialize<'de> for SourceConfig {
                     auto_start,
                     bootstrap_provider,
                     identity_provider,
+                    restart_policy,
+                    config_version,
                     config: remaining_value,
                 })
             }
@@ -249,6 +289,16 @@ impl SourceConfig {
         self.identity_provider.as_deref()
     }
 
+    /// Get the restart policy, if one is configured.
+    pub fn restart_policy(&self) -> Option<&RestartPolicy> {
+        self.restart_policy.as_ref()
+    }
+
+    /// Get the plugin config schema version this config was written against.
+    pub fn config_version(&self) -> Option<&str> {
+        self.config_version.as_deref()
+    }
+
     /// Get the source kind
     pub fn kind(&self) -> &str {
         &self.kind
@@ -714,6 +764,8 @@ intervalMs: 1000
             auto_start: false,
             bootstrap_provider: None,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({
                 "dataType": { "type": "sensorReading", "sensorCount": 5 },
                 "intervalMs": 1000
@@ -761,4 +813,65 @@ intervalMs: 1000
         assert_eq!(config["dataType"]["type"], "sensorReading");
         assert_eq!(config["dataType"]["sensorCount"], 10);
     }
+
+    #[test]
+    fn test_source_deserialize_restart_policy() {
+        let yaml = r#"
+kind: postgres
+id: pg-source
+restartPolicy:
+  mode: onFailure
+  maxAttempts: 3
+host: localhost
+"#;
+
+        let source: SourceConfig = serde_yaml::from_str(yaml).unwrap();
+        let policy = source.restart_policy().expect("Expected restart policy");
+        assert!(policy.restarts_on_failure());
+        assert_eq!(policy.max_attempts, 3);
+        // restartPolicy is a common field, not plugin config.
+        assert!(source.config.get("restartPolicy").is_none());
+
+        let json = serde_json::to_value(&source).unwrap();
+        assert_eq!(json["restartPolicy"]["mode"], "onFailure");
+        assert_eq!(json["restartPolicy"]["maxAttempts"], 3);
+    }
+
+    #[test]
+    fn test_source_deserialize_restart_policy_snake_case_rejected() {
+        let json = r#"{
+            "kind": "mock",
+            "id": "test-source",
+            "restart_policy": { "mode": "onFailure" }
+        }"#;
+
+        let err = serde_json::from_str::<SourceConfig>(json)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("restartPolicy"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_source_config_version_round_trip() {
+        let yaml = r#"
+kind: postgres
+id: pg-source
+configVersion: "1.2.0"
+host: localhost
+"#;
+
+        let source: SourceConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(source.config_version(), Some("1.2.0"));
+        assert!(source.config.get("configVersion").is_none());
+
+        let json = serde_json::to_value(&source).unwrap();
+        assert_eq!(json["configVersion"], "1.2.0");
+
+        let err = serde_json::from_str::<SourceConfig>(
+            r#"{"kind": "mock", "id": "s", "config_version": "1.0.0"}"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("configVersion"), "unexpected error: {err}");
+    }
 }
@@ -12,43 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! State store configuration DTOs.
+//! State store configuration and inspection DTOs.
 
+use base64::Engine;
 use drasi_plugin_sdk::config_value::ConfigValue;
-use serde::de::{self, Deserializer, MapAccess, Visitor};
 use serde::{Deserialize, Serialize};
-use std::fmt;
 
-/// State store configuration with kind discriminator.
+use super::ComponentStatusDto;
+
+/// State store configuration with kind discriminator and opaque config.
 ///
 /// State store providers allow plugins (Sources, BootstrapProviders, and Reactions)
 /// to persist runtime state that survives restarts of DrasiLib.
 ///
-/// Uses a custom deserializer to handle the `kind` field and validate unknown fields.
-/// The inner config DTOs use `#[serde(deny_unknown_fields)]` to catch typos.
+/// Like `SecretStoreConfig`, the config is generic: a `kind` string that maps to a
+/// registered `StateStorePluginDescriptor` (see [`crate::state_stores`]), plus an
+/// opaque JSON object that descriptor validates when the store is created.
 ///
 /// # Example YAML
 ///
 /// ```yaml
 /// stateStore:
-///   kind: redb
-///   path: ./data/state.redb
+///   kind: sqlite
+///   path: ./data/state.db
 /// ```
-#[derive(Debug, Clone, Serialize)]
-#[serde(tag = "kind")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "camelCase")]
-pub enum StateStoreConfig {
-    /// REDB-based state store for persistent storage
-    ///
-    /// Uses redb embedded database for file-based persistence.
-    /// Data survives restarts and is stored in a single file.
-    #[serde(rename = "redb")]
-    Redb {
-        /// Path to the redb database file
-        ///
-        /// Supports environment variables: ${STATE_STORE_PATH:-./data/state.redb}
-        path: ConfigValue<String>,
-    },
+pub struct StateStoreConfig {
+    /// The state store kind (e.g., "redb", "sqlite")
+    pub kind: String,
+
+    /// Opaque configuration passed to the kind's descriptor.
+    /// All fields except `kind` are collected here.
+    #[serde(flatten)]
+    pub config: serde_json::Value,
 }
 
 /// Inner configuration DTO for REDB state store with strict field validation.
@@ -57,90 +54,111 @@ pub enum StateStoreConfig {
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct RedbStateStoreConfigDto {
     /// Path to the redb database file
+    ///
+    /// Supports environment variables: ${STATE_STORE_PATH:-./data/state.redb}
     pub path: ConfigValue<String>,
 }
 
-// Known state store kinds for error messages
-const STATE_STORE_KINDS: &[&str] = &["redb"];
-
-impl<'de> Deserialize<'de> for StateStoreConfig {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct StateStoreConfigVisitor;
-
-        impl<'de> Visitor<'de> for StateStoreConfigVisitor {
-            type Value = StateStoreConfig;
-
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("a state store configuration with 'kind' field")
-            }
-
-            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
-            where
-                M: MapAccess<'de>,
-            {
-                // Storage for the kind field
-                let mut kind: Option<String> = None;
-
-                // Collect remaining fields for the inner config
-                let mut remaining = serde_json::Map::new();
-
-                while let Some(key) = map.next_key::<String>()? {
-                    match key.as_str() {
-                        "kind" => {
-                            if kind.is_some() {
-                                return Err(de::Error::duplicate_field("kind"));
-                            }
-                            kind = Some(map.next_value()?);
-                        }
-                        // Collect all other fields for the inner config
-                        other => {
-                            let value: serde_json::Value = map.next_value()?;
-                            remaining.insert(other.to_string(), value);
-                        }
-                    }
-                }
-
-                // Validate required fields
-                let kind = kind.ok_or_else(|| de::Error::missing_field("kind"))?;
-
-                let remaining_value = serde_json::Value::Object(remaining);
-
-                match kind.as_str() {
-                    "redb" => {
-                        let config: RedbStateStoreConfigDto =
-                            serde_json::from_value(remaining_value).map_err(|e| {
-                                de::Error::custom(format!("in stateStore (kind=redb): {e}"))
-                            })?;
-                        Ok(StateStoreConfig::Redb { path: config.path })
-                    }
-                    unknown => Err(de::Error::unknown_variant(unknown, STATE_STORE_KINDS)),
-                }
-            }
-        }
-
-        deserializer.deserialize_map(StateStoreConfigVisitor)
-    }
+/// Inner configuration DTO for SQLite state store with strict field validation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, utoipa::ToSchema)]
+#[schema(as = SqliteStateStoreConfig)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SqliteStateStoreConfigDto {
+    /// Path to the SQLite database file
+    ///
+    /// Supports environment variables: ${STATE_STORE_PATH:-./data/state.db}
+    pub path: ConfigValue<String>,
 }
 
 impl StateStoreConfig {
+    /// Create a state store configuration of `kind` with the given `path`
+    fn with_path(kind: &str, path: String) -> Self {
+        StateStoreConfig {
+            kind: kind.to_string(),
+            config: serde_json::json!({ "path": path }),
+        }
+    }
+
     /// Create a new REDB state store configuration
     pub fn redb(path: impl Into<String>) -> Self {
-        StateStoreConfig::Redb {
-            path: ConfigValue::Static(path.into()),
-        }
+        Self::with_path("redb", path.into())
+    }
+
+    /// Create a new SQLite state store configuration
+    pub fn sqlite(path: impl Into<String>) -> Self {
+        Self::with_path("sqlite", path.into())
     }
+}
+
+/// A component's partition of its instance's state store.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StateStorePartition {
+    /// Partition id; the id of the component that owns it
+    pub store_id: String,
+    /// Type of the owning component (`source` or `reaction`)
+    pub component_type: String,
+    /// Current status of the owning component
+    pub status: ComponentStatusDto,
+    /// Number of keys in the partition
+    pub key_count: usize,
+}
+
+/// How a [`StateStoreEntry`] value is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum StateValueEncoding {
+    /// The value is UTF-8 text, returned as is
+    Utf8,
+    /// The value is binary, returned base64-encoded
+    Base64,
+}
 
-    /// Get a display name for this state store type
-    pub fn kind(&self) -> &str {
-        match self {
-            StateStoreConfig::Redb { .. } => "redb",
+/// One key of a state store partition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StateStoreEntry {
+    pub key: String,
+    pub value: String,
+    pub encoding: StateValueEncoding,
+}
+
+impl StateStoreEntry {
+    /// An entry for `value`, base64-encoded unless it is valid UTF-8.
+    pub fn new(key: String, value: Vec<u8>) -> Self {
+        match String::from_utf8(value) {
+            Ok(value) => Self {
+                key,
+                value,
+                encoding: StateValueEncoding::Utf8,
+            },
+            Err(e) => Self {
+                key,
+                value: base64::engine::general_purpose::STANDARD.encode(e.as_bytes()),
+                encoding: StateValueEncoding::Base64,
+            },
         }
     }
 }
 
+/// Every key of a state store partition.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StateStoreDump {
+    pub store_id: String,
+    /// Entries sorted by key
+    pub entries: Vec<StateStoreEntry>,
+}
+
+/// Result of resetting a state store partition.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StateStoreResetResponse {
+    pub store_id: String,
+    /// Number of keys deleted
+    pub deleted_keys: usize,
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -157,9 +175,8 @@ mod tests {
         }"#;
 
         let config: StateStoreConfig = serde_json::from_str(json).unwrap();
-        assert_eq!(config.kind(), "redb");
-        let StateStoreConfig::Redb { path } = config;
-        assert_eq!(path, ConfigValue::Static("./data/state.redb".to_string()));
+        assert_eq!(config.kind, "redb");
+        assert_eq!(config.config["path"], "./data/state.redb");
     }
 
     #[test]
@@ -170,15 +187,16 @@ mod tests {
         }"#;
 
         let config: StateStoreConfig = serde_json::from_str(json).unwrap();
-        assert_eq!(config.kind(), "redb");
-        let StateStoreConfig::Redb { path } = config;
+        assert_eq!(config.kind, "redb");
+        let dto: RedbStateStoreConfigDto = serde_json::from_value(config.config).unwrap();
         assert!(
             matches!(
-                &path,
+                &dto.path,
                 ConfigValue::EnvironmentVariable { name, default }
                 if name == "STATE_STORE_PATH" && *default == Some("./data/default.redb".to_string())
             ),
-            "Expected EnvironmentVariable variant, got {path:?}"
+            "Expected EnvironmentVariable variant, got {:?}",
+            dto.path
         );
     }
 
@@ -197,6 +215,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_state_store_deserialize_any_kind() {
+        // Kinds are validated when the store is created, against the
+        // registered descriptors, so a plugin kind parses like a built-in one.
+        let json = r#"{
+            "kind": "postgres",
+            "connectionString": "postgres://localhost/state"
+        }"#;
+
+        let config: StateStoreConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.kind, "postgres");
+        assert_eq!(
+            config.config["connectionString"],
+            "postgres://localhost/state"
+        );
+    }
+
+    // Kind and fields are checked against the state store registry rather
+    // than while parsing; `drasi-server validate` reports these errors.
+    fn validate(config: &StateStoreConfig) -> Result<(), String> {
+        crate::state_stores::StateStoreRegistry::new()
+            .validate(config)
+            .map_err(|e| format!("{e:#}"))
+    }
+
     #[test]
     fn test_state_store_deserialize_unknown_kind() {
         let json = r#"{
@@ -204,12 +247,11 @@ mod tests {
             "path": "./data/state.redb"
         }"#;
 
-        let result: Result<StateStoreConfig, _> = serde_json::from_str(json);
-        assert!(result.is_err());
-        let err = result.unwrap_err().to_string();
+        let config: StateStoreConfig = serde_json::from_str(json).unwrap();
+        let err = validate(&config).unwrap_err();
         assert!(
-            err.contains("unknown") || err.contains("variant"),
-            "Error should mention unknown kind: {err}"
+            err.contains("'unknown'") && err.contains("redb"),
+            "Error should name the unknown kind and the available ones: {err}"
         );
     }
 
@@ -221,11 +263,10 @@ mod tests {
             "unknownField": "value"
         }"#;
 
-        let result: Result<StateStoreConfig, _> = serde_json::from_str(json);
-        assert!(result.is_err(), "Unknown field should be rejected");
-        let err = result.unwrap_err().to_string();
+        let config: StateStoreConfig = serde_json::from_str(json).unwrap();
+        let err = validate(&config).unwrap_err();
         assert!(
-            err.contains("unknownField") || err.contains("unknown field"),
+            err.contains("unknownField"),
             "Error should mention unknown field: {err}"
         );
     }
@@ -233,15 +274,14 @@ mod tests {
     #[test]
     fn test_state_store_deserialize_snake_case_rejected() {
         let json = r#"{
-            "kind": "redb",
-            "file_path": "./data/state.redb"
+            "kind": "sqlite",
+            "file_path": "./data/state.db"
         }"#;
 
-        let result: Result<StateStoreConfig, _> = serde_json::from_str(json);
-        assert!(result.is_err(), "snake_case field should be rejected");
-        let err = result.unwrap_err().to_string();
+        let config: StateStoreConfig = serde_json::from_str(json).unwrap();
+        let err = validate(&config).unwrap_err();
         assert!(
-            err.contains("file_path") || err.contains("unknown field"),
+            err.contains("file_path"),
             "Error should mention unknown field: {err}"
         );
     }
@@ -254,48 +294,84 @@ mod tests {
             "unknownField": "value"
         }"#;
 
-        let result: Result<StateStoreConfig, _> = serde_json::from_str(json);
-        assert!(result.is_err());
-        let err = result.unwrap_err().to_string();
+        let config: StateStoreConfig = serde_json::from_str(json).unwrap();
+        let err = validate(&config).unwrap_err();
         assert!(
-            err.contains("stateStore") || err.contains("redb"),
+            err.contains("in stateStore (kind=redb)"),
             "Error should have context about stateStore: {err}"
         );
     }
 
     #[test]
-    fn test_state_store_serialize_deserialize_roundtrip() {
-        let original = StateStoreConfig::redb("./data/roundtrip.redb");
-
-        let json = serde_json::to_string(&original).unwrap();
-        let deserialized: StateStoreConfig = serde_json::from_str(&json).unwrap();
-
-        assert_eq!(original.kind(), deserialized.kind());
-        let StateStoreConfig::Redb { path: p1 } = &original;
-        let StateStoreConfig::Redb { path: p2 } = &deserialized;
-        assert_eq!(p1, p2);
-    }
-
-    #[test]
-    fn test_state_store_deserialize_yaml_format() {
+    fn test_state_store_yaml_unknown_field_rejected() {
         let yaml = r#"
 kind: redb
 path: ./data/state.redb
+unknownField: value
 "#;
 
         let config: StateStoreConfig = serde_yaml::from_str(yaml).unwrap();
-        assert_eq!(config.kind(), "redb");
+        assert!(
+            validate(&config).is_err(),
+            "Unknown field in YAML should be rejected"
+        );
     }
 
     #[test]
-    fn test_state_store_yaml_unknown_field_rejected() {
+    fn test_state_store_builtin_kinds_valid() {
+        assert_eq!(validate(&StateStoreConfig::redb("./data/state.redb")), Ok(()));
+        assert_eq!(validate(&StateStoreConfig::sqlite("./data/state.db")), Ok(()));
+    }
+
+    #[test]
+    fn test_redb_dto_unknown_field_rejected() {
+        let config = StateStoreConfig {
+            kind: "redb".to_string(),
+            config: serde_json::json!({
+                "path": "./data/state.redb",
+                "unknownField": "value"
+            }),
+        };
+
+        let result: Result<RedbStateStoreConfigDto, _> = serde_json::from_value(config.config);
+        let err = result.unwrap_err().to_string();
+        assert!(
+            err.contains("unknownField"),
+            "Error should mention unknown field: {err}"
+        );
+    }
+
+    #[test]
+    fn test_state_store_serialize_deserialize_roundtrip() {
+        for original in [
+            StateStoreConfig::redb("./data/roundtrip.redb"),
+            StateStoreConfig::sqlite("./data/roundtrip.db"),
+        ] {
+            let json = serde_json::to_string(&original).unwrap();
+            let deserialized: StateStoreConfig = serde_json::from_str(&json).unwrap();
+            assert_eq!(original, deserialized);
+        }
+    }
+
+    #[test]
+    fn test_state_store_entry_encodes_binary_values() {
+        let text = StateStoreEntry::new("lsn".to_string(), b"0/16B3748".to_vec());
+        assert_eq!(text.value, "0/16B3748");
+        assert_eq!(text.encoding, StateValueEncoding::Utf8);
+
+        let binary = StateStoreEntry::new("cursor".to_string(), vec![0xff, 0x00, 0x01]);
+        assert_eq!(binary.value, "/wAB");
+        assert_eq!(binary.encoding, StateValueEncoding::Base64);
+    }
+
+    #[test]
+    fn test_state_store_deserialize_yaml_format() {
         let yaml = r#"
-kind: redb
-path: ./data/state.redb
-unknownField: value
+kind: sqlite
+path: ./data/state.db
 "#;
 
-        let result: Result<StateStoreConfig, _> = serde_yaml::from_str(yaml);
-        assert!(result.is_err(), "Unknown field in YAML should be rejected");
+        let config: StateStoreConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config, StateStoreConfig::sqlite("./data/state.db"));
     }
 }
@@ -0,0 +1,78 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage usage DTOs.
+
+use serde::Serialize;
+
+/// Disk usage of an instance's index, archive, WAL and state store.
+///
+/// Sizes the server cannot measure are `null`: the index and archive of an
+/// instance without `persistIndex`, the in-memory state store, and all files
+/// of instances the server did not build.
+#[derive(Debug, Clone, Default, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceStorageReport {
+    pub instance_id: String,
+    /// Bytes on disk under the instance's persistent index directory.
+    pub index_bytes: Option<u64>,
+    /// Key and value bytes of all archived element versions, before RocksDB
+    /// compression. Included in `indexBytes`.
+    pub archive_bytes: Option<u64>,
+    /// Bytes on disk under the instance's WAL directory.
+    pub wal_bytes: Option<u64>,
+    /// Bytes of the state store's files.
+    pub state_store_bytes: Option<u64>,
+    /// Per-query index usage, ordered by query id.
+    pub queries: Vec<QueryStorageUsage>,
+    /// Per-source WAL usage of the sources that write a WAL, ordered by
+    /// source id.
+    pub sources: Vec<SourceWalUsage>,
+}
+
+/// Index usage of one query.
+#[derive(Debug, Clone, Default, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStorageUsage {
+    pub query_id: String,
+    /// Whether the query's index is persisted with RocksDB.
+    pub persistent: bool,
+    /// Elements in the query's index; `null` for in-memory indexes.
+    pub element_count: Option<u64>,
+    /// Current results of the query; `null` if the query has no metrics.
+    pub result_count: Option<u64>,
+    /// Archived element versions; `null` without an archive.
+    pub archive_versions: Option<u64>,
+    /// Key and value bytes of those versions, before RocksDB compression.
+    pub archive_bytes: Option<u64>,
+    /// Why the index could not be read, if it could not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// WAL usage of one source.
+#[derive(Debug, Clone, Default, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceWalUsage {
+    pub source_id: String,
+    /// Bytes of the source's WAL file.
+    pub bytes: u64,
+    /// Sequence of the newest event written.
+    pub head_sequence: u64,
+    /// Sequence of the oldest retained event; `null` if the WAL is empty.
+    pub oldest_sequence: Option<u64>,
+    /// Events retained because a subscribed query has not checkpointed
+    /// past them yet, or because `walRetention` keeps them.
+    pub backlog: u64,
+}
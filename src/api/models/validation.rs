@@ -0,0 +1,59 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for validating how an instance's components fit together.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::config::SemanticIssue;
+
+/// Query parameters for `POST /instances/{instanceId}/validate`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateParams {
+    /// Validate the manifest on its own, as after an apply with `prune=true`,
+    /// instead of merged with the instance's current components
+    #[serde(default)]
+    pub prune: bool,
+}
+
+/// Result of validating an instance's pipeline.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceValidationResponse {
+    /// `true` when there are no errors (warnings are allowed)
+    pub valid: bool,
+
+    /// Number of issues with severity `error`
+    pub errors: usize,
+
+    /// Number of issues with severity `warning`
+    pub warnings: usize,
+
+    /// Every issue found, errors and warnings
+    pub issues: Vec<SemanticIssue>,
+}
+
+impl InstanceValidationResponse {
+    pub fn new(issues: Vec<SemanticIssue>) -> Self {
+        let errors = issues.iter().filter(|issue| issue.is_error()).count();
+        Self {
+            valid: errors == 0,
+            errors,
+            warnings: issues.len() - errors,
+            issues,
+        }
+    }
+}
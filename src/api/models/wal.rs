@@ -0,0 +1,122 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Source WAL DTOs: retention, inspection and replay.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Events to keep in each source's WAL after every subscribed query has
+/// checkpointed past them, so they stay available to replay.
+///
+/// Without retention a source prunes its WAL as soon as all of its queries
+/// have processed an event. Either limit holds an event back; an event is
+/// pruned only once neither does. Retained events still count towards the
+/// source's `maxEvents` capacity, so `keepEvents` should stay well below it.
+///
+/// # Example YAML
+///
+/// ```yaml
+/// walRetention:
+///   keepEvents: 1000
+///   keepSecs: 3600
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WalRetentionConfig {
+    /// Keep at least this many of the newest events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_events: Option<u64>,
+    /// Keep events appended within this many seconds. Events appended
+    /// before the server started count as older than any window.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_secs: Option<u64>,
+}
+
+/// WAL state of an instance's sources.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceWalStatus {
+    pub instance_id: String,
+    /// Retention in force, or `null` if sources prune as soon as their
+    /// queries have checkpointed.
+    pub retention: Option<WalRetentionConfig>,
+    /// Sources that write a WAL, ordered by source id.
+    pub sources: Vec<SourceWalStatus>,
+}
+
+/// WAL state of one source.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceWalStatus {
+    pub source_id: String,
+    /// Bytes of the source's WAL file.
+    pub bytes: u64,
+    /// Sequence of the newest event written.
+    pub head_sequence: u64,
+    /// Sequence of the oldest retained event; `null` if the WAL is empty.
+    pub oldest_sequence: Option<u64>,
+    /// Transaction time of the oldest retained event.
+    pub oldest_timestamp: Option<DateTime<Utc>>,
+    /// Events retained.
+    pub event_count: u64,
+    /// Last sequence each persistent query subscribed to the source has
+    /// checkpointed, ordered by query id.
+    pub checkpoints: Vec<QueryWalCheckpoint>,
+}
+
+/// Position of one query in a source's WAL.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryWalCheckpoint {
+    pub query_id: String,
+    pub sequence: u64,
+}
+
+/// Where a replay starts. Exactly one field must be set.
+#[derive(Debug, Clone, Default, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ReplayRequest {
+    /// First WAL sequence to re-deliver.
+    #[serde(default)]
+    pub from_sequence: Option<u64>,
+    /// Re-deliver from the first retained event whose transaction time is at
+    /// or after this instant.
+    #[serde(default)]
+    pub from_timestamp: Option<DateTime<Utc>>,
+}
+
+/// Outcome of a replay.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResponse {
+    pub source_id: String,
+    /// First sequence re-delivered.
+    pub from_sequence: u64,
+    /// WAL head when the replay was requested; events after it arrive as
+    /// usual.
+    pub to_sequence: u64,
+    /// Queries rewound to `fromSequence`.
+    pub replayed: Vec<String>,
+    /// Subscribed queries that were left alone, with the reason.
+    pub skipped: Vec<SkippedReplay>,
+}
+
+/// A subscribed query a replay did not rewind.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedReplay {
+    pub query_id: String,
+    pub reason: String,
+}
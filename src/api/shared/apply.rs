@@ -0,0 +1,1111 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarative apply of a multi-resource manifest to an instance.
+//!
+//! Three phases, mirroring solution deployment:
+//! 1. Validate every component in the manifest and its references against
+//!    the state the instance will end up in.
+//! 2. Plan create/update/delete actions by comparing the manifest with the
+//!    instance's current configuration.
+//! 3. Perform the actions (sources → queries → reactions, then deletions in
+//!    reverse order). If any action fails, the completed ones are undone in
+//!    reverse so the instance is left as it was.
+//!
+//! [`validate_instance`] runs the semantic checks of
+//! [`crate::config::semantic_validation`] on the state a manifest would
+//! produce, without the other phases.
+
+use axum::Json;
+use drasi_lib::config::QueryConfig;
+use drasi_lib::DrasiLib;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::api::mappings::{DtoMapper, QueryConfigMapper};
+use crate::api::models::apply::{
+    ApplyAction, ApplyActionKind, ApplyManifest, ApplyParams, ApplyResponse,
+};
+use crate::api::models::validation::{InstanceValidationResponse, ValidateParams};
+use crate::api::models::{BootstrapProviderConfig, QueryConfigDto, ReactionConfig, SourceConfig};
+use crate::api::shared::error::{error_codes, ErrorDetail, ErrorResponse};
+use crate::api::shared::handlers::persist_after_operation;
+use crate::api::shared::ApiResponse;
+use crate::component_revisions::ComponentKind;
+use crate::config::migration::{migrate_submitted_reaction, migrate_submitted_source};
+use crate::config::validate_pipeline;
+use crate::factories::{
+    create_reaction_locked, create_source_locked, resolve_source_bootstrap_provider,
+};
+use crate::instance_registry::InstanceRegistry;
+use crate::persistence::{ComponentConfigs, ConfigPersistence};
+use crate::plugin_isolation::PluginCircuitBreakers;
+use crate::plugin_registry::PluginRegistry;
+use crate::secret_rotation::SecretConsumers;
+
+/// A manifest whose components all parsed successfully.
+#[derive(Debug, Default)]
+struct DesiredState {
+    sources: Vec<SourceConfig>,
+    queries: Vec<(QueryConfigDto, QueryConfig)>,
+    reactions: Vec<ReactionConfig>,
+}
+
+/// Apply a manifest to an instance and persist the result.
+pub async fn apply_manifest(
+    registry: InstanceRegistry,
+    persistence: Option<Arc<ConfigPersistence>>,
+    plugin_registry: &RwLock<PluginRegistry>,
+    instance_id: &str,
+    params: ApplyParams,
+    manifest: ApplyManifest,
+) -> Result<Json<ApiResponse<ApplyResponse>>, ErrorResponse> {
+    let response = reconcile_instance(
+        &registry,
+        &persistence,
+        plugin_registry,
+        instance_id,
+        params,
+        manifest,
+    )
+    .await?;
+    if has_changes(&response) {
+        persist_after_operation(&persistence, "applying manifest").await?;
+    }
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Whether an apply changed the instance (and so needs persisting).
+pub fn has_changes(response: &ApplyResponse) -> bool {
+    response.applied
+        && response
+            .actions
+            .iter()
+            .any(|a| a.action != ApplyActionKind::Unchanged)
+}
+
+/// Validate how an instance's components fit together, as they would be
+/// after applying `manifest`.
+///
+/// Manifest components replace the instance's components with the same id;
+/// with `prune` only the manifest is validated. Unparseable manifest
+/// entries are an `INVALID_REQUEST` error, like for an apply. Nothing is
+/// changed.
+pub async fn validate_instance(
+    registry: InstanceRegistry,
+    persistence: Option<Arc<ConfigPersistence>>,
+    plugin_registry: &RwLock<PluginRegistry>,
+    instance_id: &str,
+    params: ValidateParams,
+    manifest: ApplyManifest,
+) -> Result<Json<ApiResponse<InstanceValidationResponse>>, ErrorResponse> {
+    let core = registry.get(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::INSTANCE_NOT_FOUND,
+            format!("Instance '{instance_id}' not found"),
+        )
+    })?;
+    let desired = parse_manifest(&manifest, plugin_registry)
+        .await
+        .map_err(validation_error)?;
+
+    let mut components = ComponentConfigs {
+        sources: desired.sources,
+        queries: desired.queries.into_iter().map(|(dto, _)| dto).collect(),
+        reactions: desired.reactions,
+    };
+    if !params.prune {
+        let snapshot = core.snapshot_configuration().await.map_err(|e| {
+            ErrorResponse::new(
+                error_codes::INTERNAL_ERROR,
+                format!("Failed to capture snapshot of instance '{instance_id}': {e}"),
+            )
+        })?;
+        let current = match &persistence {
+            Some(p) => p.component_configs(instance_id, &snapshot).await,
+            None => ComponentConfigs::from_snapshot(
+                &snapshot,
+                &registry.restart_policies(instance_id).await,
+            ),
+        };
+        let source_ids: HashSet<String> = components.sources.iter().map(|s| s.id.clone()).collect();
+        let query_ids: HashSet<String> = components.queries.iter().map(|q| q.id.clone()).collect();
+        let reaction_ids: HashSet<String> =
+            components.reactions.iter().map(|r| r.id.clone()).collect();
+        components.sources.extend(
+            current
+                .sources
+                .into_iter()
+                .filter(|s| !source_ids.contains(&s.id)),
+        );
+        components.queries.extend(
+            current
+                .queries
+                .into_iter()
+                .filter(|q| !query_ids.contains(&q.id)),
+        );
+        components.reactions.extend(
+            current
+                .reactions
+                .into_iter()
+                .filter(|r| !reaction_ids.contains(&r.id)),
+        );
+    }
+
+    let issues = validate_pipeline(
+        &components.sources,
+        &components.queries,
+        &components.reactions,
+    );
+    Ok(Json(ApiResponse::success(InstanceValidationResponse::new(
+        issues,
+    ))))
+}
+
+/// Bring one instance's components to the state declared by `manifest`,
+/// without persisting the config file.
+///
+/// Validation problems are returned as an `INVALID_REQUEST` error. A failed
+/// action rolls back the completed ones and is returned as an `APPLY_FAILED`
+/// error whose details name the failed component and the rollback outcome.
+/// Pruned sources and reactions are only deprovisioned once every action
+/// has succeeded.
+pub async fn reconcile_instance(
+    registry: &InstanceRegistry,
+    persistence: &Option<Arc<ConfigPersistence>>,
+    plugin_registry: &RwLock<PluginRegistry>,
+    instance_id: &str,
+    params: ApplyParams,
+    manifest: ApplyManifest,
+) -> Result<ApplyResponse, ErrorResponse> {
+    let core = registry.get(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::INSTANCE_NOT_FOUND,
+            format!("Instance '{instance_id}' not found"),
+        )
+    })?;
+
+    // ===== PHASE 1: VALIDATION =====
+    let desired = parse_manifest(&manifest, plugin_registry)
+        .await
+        .map_err(validation_error)?;
+
+    let snapshot = core.snapshot_configuration().await.map_err(|e| {
+        ErrorResponse::new(
+            error_codes::INTERNAL_ERROR,
+            format!("Failed to capture snapshot of instance '{instance_id}': {e}"),
+        )
+    })?;
+    let current = match persistence {
+        Some(p) => p.component_configs(instance_id, &snapshot).await,
+        None => ComponentConfigs::from_snapshot(
+            &snapshot,
+            &registry.restart_policies(instance_id).await,
+        ),
+    };
+    let current_queries: HashMap<String, QueryConfig> = snapshot
+        .queries
+        .iter()
+        .map(|q| (q.id.clone(), q.config.clone()))
+        .collect();
+
+    let providers = registry.bootstrap_providers(instance_id).await;
+    let mut errors = check_references(&desired, &current, params.prune);
+    for source in &desired.sources {
+        if let Err(e) = resolve_source_bootstrap_provider(source.clone(), &providers) {
+            errors.push(format!("in source '{}': {e}", source.id));
+        }
+    }
+    if !errors.is_empty() {
+        return Err(validation_error(errors));
+    }
+
+    // ===== PHASE 2: PLAN =====
+    let actions = plan_actions(&desired, &current, params.prune);
+
+    if params.dry_run {
+        return Ok(ApplyResponse {
+            dry_run: true,
+            applied: false,
+            actions,
+            components_started: Vec::new(),
+            errors: Vec::new(),
+        });
+    }
+
+    // ===== PHASE 3: APPLY =====
+    let mut applier = Applier {
+        core: &core,
+        instance_id,
+        plugin_registry,
+        secret_consumers: registry.secret_consumers(),
+        plugin_breakers: registry.plugin_breakers(),
+        providers: &providers,
+        journal: Vec::new(),
+        pruned: Vec::new(),
+        components_started: Vec::new(),
+        errors: Vec::new(),
+    };
+    if let Err((failed, e)) = applier
+        .run(&desired, &current, &current_queries, &actions)
+        .await
+    {
+        log::warn!("Apply to instance '{instance_id}' failed, rolling back: {e}");
+        let undone = applier.journal.len();
+        let rollback_errors = applier.rollback().await;
+        let mut rollback = format!("rolled back {undone} completed action(s)");
+        if !rollback_errors.is_empty() {
+            rollback = format!(
+                "{rollback}; {} could not be undone: {}",
+                rollback_errors.len(),
+                rollback_errors.join("; ")
+            );
+        }
+        return Err(ErrorResponse::new(
+            error_codes::APPLY_FAILED,
+            format!("Apply failed and was rolled back: {e}"),
+        )
+        .with_details(ErrorDetail {
+            component_type: Some(failed.component_type),
+            component_id: Some(failed.id),
+            technical_details: Some(rollback),
+        }));
+    }
+    applier.deprovision_pruned().await;
+    let components_started = std::mem::take(&mut applier.components_started);
+    let errors = std::mem::take(&mut applier.errors);
+
+    // Keep the restart supervisor, persistence tracking, and component
+    // revisions in step with the components, as the single-resource handlers
+    // do.
+    for action in &actions {
+        if action.action != ApplyActionKind::Unchanged {
+            if let Some(kind) = ComponentKind::from_type_name(&action.component_type) {
+                registry.revisions().bump(instance_id, kind, &action.id);
+            }
+        }
+        match (action.component_type.as_str(), action.action) {
+            ("source", ApplyActionKind::Create | ApplyActionKind::Update) => {
+                if let Some(source) = desired.sources.iter().find(|s| s.id == action.id) {
+                    registry
+                        .set_source_restart_policy(
+                            instance_id,
+                            &source.id,
+                            source.restart_policy().cloned(),
+                        )
+                        .await;
+                    if let Some(p) = persistence {
+                        p.register_source_identity_provider(
+                            instance_id,
+                            &source.id,
+                            source.identity_provider(),
+                        )
+                        .await;
+                        p.register_source_bootstrap_provider(
+                            instance_id,
+                            &source.id,
+                            source.bootstrap_provider(),
+                        )
+                        .await;
+                    }
+                }
+            }
+            ("source", ApplyActionKind::Delete) => {
+                registry
+                    .set_source_restart_policy(instance_id, &action.id, None)
+                    .await;
+                if let Some(p) = persistence {
+                    p.unregister_source_identity_provider(instance_id, &action.id)
+                        .await;
+                    p.unregister_source_bootstrap_provider(instance_id, &action.id)
+                        .await;
+                }
+            }
+            ("reaction", ApplyActionKind::Create | ApplyActionKind::Update) => {
+                if let Some(reaction) = desired.reactions.iter().find(|r| r.id == action.id) {
+                    registry
+                        .set_reaction_restart_policy(
+                            instance_id,
+                            &reaction.id,
+                            reaction.restart_policy().cloned(),
+                        )
+                        .await;
+                    if let Some(p) = persistence {
+                        p.register_reaction_identity_provider(
+                            instance_id,
+                            &reaction.id,
+                            reaction.identity_provider(),
+                        )
+                        .await;
+                    }
+                }
+            }
+            ("reaction", ApplyActionKind::Delete) => {
+                registry
+                    .set_reaction_restart_policy(instance_id, &action.id, None)
+                    .await;
+                if let Some(p) = persistence {
+                    p.unregister_reaction_identity_provider(instance_id, &action.id)
+                        .await;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ApplyResponse {
+        dry_run: false,
+        applied: true,
+        actions,
+        components_started,
+        errors,
+    })
+}
+
+fn validation_error(errors: Vec<String>) -> ErrorResponse {
+    ErrorResponse::new(
+        error_codes::INVALID_REQUEST,
+        format!("Manifest validation failed: {}", errors.join("; ")),
+    )
+}
+
+/// Parse every manifest entry, collecting all errors so they can be fixed
+/// at once.
+async fn parse_manifest(
+    manifest: &ApplyManifest,
+    plugin_registry: &RwLock<PluginRegistry>,
+) -> Result<DesiredState, Vec<String>> {
+    let mut desired = DesiredState::default();
+    let mut errors = Vec::new();
+    let entry_id = |value: &serde_json::Value| {
+        value
+            .get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string()
+    };
+
+    {
+        let reg = plugin_registry.read().await;
+        for value in &manifest.sources {
+            match serde_json::from_value::<SourceConfig>(value.clone()) {
+                // Migrate before diffing so an old `configVersion` is
+                // compared in the form the running source reports.
+                Ok(mut config) => match reg.get_source(config.kind()) {
+                    None => errors.push(format!(
+                        "in source '{}': unknown source kind '{}'",
+                        config.id,
+                        config.kind()
+                    )),
+                    Some(descriptor) => {
+                        match migrate_submitted_source(descriptor.as_ref(), &mut config) {
+                            Ok(_) => desired.sources.push(config),
+                            Err(e) => errors.push(format!("in source '{}': {e}", config.id)),
+                        }
+                    }
+                },
+                Err(e) => errors.push(format!("in source '{}': {e}", entry_id(value))),
+            }
+        }
+        for value in &manifest.reactions {
+            match serde_json::from_value::<ReactionConfig>(value.clone()) {
+                Ok(mut config) => match reg.get_reaction(config.kind()) {
+                    None => errors.push(format!(
+                        "in reaction '{}': unknown reaction kind '{}'",
+                        config.id,
+                        config.kind()
+                    )),
+                    Some(descriptor) => {
+                        match migrate_submitted_reaction(descriptor.as_ref(), &mut config) {
+                            Ok(_) => desired.reactions.push(config),
+                            Err(e) => errors.push(format!("in reaction '{}': {e}", config.id)),
+                        }
+                    }
+                },
+                Err(e) => errors.push(format!("in reaction '{}': {e}", entry_id(value))),
+            }
+        }
+    }
+
+    let mapper = DtoMapper::default();
+    for value in &manifest.queries {
+        let dto = match serde_json::from_value::<QueryConfigDto>(value.clone()) {
+            Ok(dto) => dto,
+            Err(e) => {
+                errors.push(format!("in query '{}': {e}", entry_id(value)));
+                continue;
+            }
+        };
+        match mapper.map_with(&dto, &QueryConfigMapper) {
+            // Compare in the form the running query reports, so that
+            // equivalent spellings (e.g. of `joins`) are not seen as changes.
+            Ok(config) => {
+                let dto = QueryConfigDto::try_from(config.clone()).unwrap_or(dto);
+                desired.queries.push((dto, config));
+            }
+            Err(e) => errors.push(format!("in query '{}': {e}", dto.id)),
+        }
+    }
+
+    let ids_by_kind: [(&str, Vec<&str>); 3] = [
+        (
+            "source",
+            desired.sources.iter().map(|s| s.id.as_str()).collect(),
+        ),
+        (
+            "query",
+            desired.queries.iter().map(|(q, _)| q.id.as_str()).collect(),
+        ),
+        (
+            "reaction",
+            desired.reactions.iter().map(|r| r.id.as_str()).collect(),
+        ),
+    ];
+    for (kind, ids) in ids_by_kind {
+        let mut seen = HashSet::new();
+        for id in ids {
+            if !seen.insert(id) {
+                errors.push(format!("duplicate {kind} id '{id}'"));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(desired)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Check that every query source and reaction query exists in the state the
+/// instance will have after the apply.
+fn check_references(
+    desired: &DesiredState,
+    current: &ComponentConfigs,
+    prune: bool,
+) -> Vec<String> {
+    let mut source_ids: HashSet<&str> = desired.sources.iter().map(|s| s.id.as_str()).collect();
+    let mut query_ids: HashSet<&str> = desired.queries.iter().map(|(q, _)| q.id.as_str()).collect();
+    if !prune {
+        source_ids.extend(current.sources.iter().map(|s| s.id.as_str()));
+        query_ids.extend(current.queries.iter().map(|q| q.id.as_str()));
+    }
+
+    let mut errors = Vec::new();
+    for (dto, _) in &desired.queries {
+        for subscription in &dto.sources {
+            if !source_ids.contains(subscription.source_id.as_str()) {
+                errors.push(format!(
+                    "in query '{}': source '{}' does not exist",
+                    dto.id, subscription.source_id
+                ));
+            }
+        }
+    }
+    for reaction in &desired.reactions {
+        for query_id in reaction.queries() {
+            if !query_ids.contains(query_id.as_str()) {
+                errors.push(format!(
+                    "in reaction '{}': query '{query_id}' does not exist",
+                    reaction.id
+                ));
+            }
+        }
+    }
+    errors
+}
+
+/// Plan the actions that bring `current` to `desired`, in execution order:
+/// sources, queries, and reactions from the manifest, then (with `prune`)
+/// deletion of reactions, queries, and sources missing from it.
+fn plan_actions(
+    desired: &DesiredState,
+    current: &ComponentConfigs,
+    prune: bool,
+) -> Vec<ApplyAction> {
+    let mut actions = Vec::new();
+    let action_for = |existing: Option<bool>| match existing {
+        None => ApplyActionKind::Create,
+        Some(true) => ApplyActionKind::Unchanged,
+        Some(false) => ApplyActionKind::Update,
+    };
+
+    for source in &desired.sources {
+        let existing = current.sources.iter().find(|s| s.id == source.id);
+        let action = action_for(existing.map(|c| source_matches(source, c)));
+        actions.push(ApplyAction::new("source", &source.id, action));
+    }
+    for (dto, _) in &desired.queries {
+        let existing = current.queries.iter().find(|q| q.id == dto.id);
+        let action = action_for(existing.map(|c| c == dto));
+        actions.push(ApplyAction::new("query", &dto.id, action));
+    }
+    for reaction in &desired.reactions {
+        let existing = current.reactions.iter().find(|r| r.id == reaction.id);
+        let action = action_for(existing.map(|c| reaction_matches(reaction, c)));
+        actions.push(ApplyAction::new("reaction", &reaction.id, action));
+    }
+
+    if prune {
+        for reaction in &current.reactions {
+            if !desired.reactions.iter().any(|r| r.id == reaction.id) {
+                actions.push(ApplyAction::new(
+                    "reaction",
+                    &reaction.id,
+                    ApplyActionKind::Delete,
+                ));
+            }
+        }
+        for query in &current.queries {
+            if !desired.queries.iter().any(|(q, _)| q.id == query.id) {
+                actions.push(ApplyAction::new(
+                    "query",
+                    &query.id,
+                    ApplyActionKind::Delete,
+                ));
+            }
+        }
+        for source in &current.sources {
+            if !desired.sources.iter().any(|s| s.id == source.id) {
+                actions.push(ApplyAction::new(
+                    "source",
+                    &source.id,
+                    ApplyActionKind::Delete,
+                ));
+            }
+        }
+    }
+    actions
+}
+
+/// Plugin config with `null` treated as an empty object, so a component
+/// declared without properties matches one that reports none.
+fn plugin_config(config: &serde_json::Value) -> serde_json::Value {
+    match config {
+        serde_json::Value::Null => serde_json::Value::Object(serde_json::Map::new()),
+        other => other.clone(),
+    }
+}
+
+fn source_matches(desired: &SourceConfig, current: &SourceConfig) -> bool {
+    desired.kind == current.kind
+        && desired.auto_start == current.auto_start
+        && desired.identity_provider == current.identity_provider
+        && desired.restart_policy == current.restart_policy
+        && desired.bootstrap_provider == current.bootstrap_provider
+        && plugin_config(&desired.config) == plugin_config(&current.config)
+}
+
+fn reaction_matches(desired: &ReactionConfig, current: &ReactionConfig) -> bool {
+    desired.kind == current.kind
+        && desired.queries == current.queries
+        && desired.auto_start == current.auto_start
+        && desired.identity_provider == current.identity_provider
+        && desired.restart_policy == current.restart_policy
+        && plugin_config(&desired.config) == plugin_config(&current.config)
+}
+
+/// How to undo one completed action.
+enum Undo {
+    RemoveSource(String),
+    RestoreSource(SourceConfig),
+    RemoveQuery(String),
+    RestoreQuery(QueryConfig),
+    ReAddQuery(QueryConfig),
+    RemoveReaction(String),
+    RestoreReaction(ReactionConfig),
+}
+
+/// Performs planned actions and records how to undo each one.
+struct Applier<'a> {
+    core: &'a Arc<DrasiLib>,
+    instance_id: &'a str,
+    plugin_registry: &'a RwLock<PluginRegistry>,
+    secret_consumers: &'a SecretConsumers,
+    plugin_breakers: &'a PluginCircuitBreakers,
+    providers: &'a HashMap<String, BootstrapProviderConfig>,
+    journal: Vec<Undo>,
+    /// Sources and reactions removed without cleanup, to be deprovisioned
+    /// once the whole apply has succeeded.
+    pruned: Vec<Pruned>,
+    components_started: Vec<String>,
+    /// Failures to start new components or deprovision pruned ones. These
+    /// do not roll back the apply.
+    errors: Vec<String>,
+}
+
+/// A component removed by `prune`, kept so it can be deprovisioned.
+enum Pruned {
+    Source(SourceConfig),
+    Reaction(ReactionConfig),
+}
+
+impl Applier<'_> {
+    async fn run(
+        &mut self,
+        desired: &DesiredState,
+        current: &ComponentConfigs,
+        current_queries: &HashMap<String, QueryConfig>,
+        actions: &[ApplyAction],
+    ) -> Result<(), (ApplyAction, anyhow::Error)> {
+        for action in actions {
+            self.perform(action, desired, current, current_queries)
+                .await
+                .map_err(|e| (action.clone(), e))?;
+        }
+        Ok(())
+    }
+
+    async fn perform(
+        &mut self,
+        action: &ApplyAction,
+        desired: &DesiredState,
+        current: &ComponentConfigs,
+        current_queries: &HashMap<String, QueryConfig>,
+    ) -> anyhow::Result<()> {
+        let id = action.id.as_str();
+        match (action.component_type.as_str(), action.action) {
+            (_, ApplyActionKind::Unchanged) => {}
+            ("source", kind) => {
+                let previous = current.sources.iter().find(|s| s.id == id);
+                match (kind, desired.sources.iter().find(|s| s.id == id)) {
+                    (ApplyActionKind::Create, Some(source)) => {
+                        self.add_source(source.clone()).await?;
+                        self.journal.push(Undo::RemoveSource(id.to_string()));
+                        if source.auto_start {
+                            self.start("source", id).await;
+                        }
+                    }
+                    (ApplyActionKind::Update, Some(source)) => {
+                        self.update_source(source.clone()).await?;
+                        if let Some(previous) = previous {
+                            self.journal.push(Undo::RestoreSource(previous.clone()));
+                        }
+                    }
+                    (ApplyActionKind::Delete, _) => {
+                        // Keep the source's state until the apply succeeds.
+                        self.core
+                            .remove_source(id, false)
+                            .await
+                            .map_err(|e| anyhow::anyhow!("failed to delete source '{id}': {e}"))?;
+                        if let Some(previous) = previous {
+                            self.journal.push(Undo::RestoreSource(previous.clone()));
+                            self.pruned.push(Pruned::Source(previous.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            ("query", kind) => {
+                let previous = current_queries.get(id);
+                match (kind, desired.queries.iter().find(|(q, _)| q.id == id)) {
+                    (ApplyActionKind::Create, Some((_, config))) => {
+                        self.core
+                            .add_query(config.clone())
+                            .await
+                            .map_err(|e| anyhow::anyhow!("failed to create query '{id}': {e}"))?;
+                        self.journal.push(Undo::RemoveQuery(id.to_string()));
+                    }
+                    (ApplyActionKind::Update, Some((_, config))) => {
+                        self.core
+                            .update_query(id, config.clone())
+                            .await
+                            .map_err(|e| anyhow::anyhow!("failed to update query '{id}': {e}"))?;
+                        if let Some(previous) = previous {
+                            self.journal.push(Undo::RestoreQuery(previous.clone()));
+                        }
+                    }
+                    (ApplyActionKind::Delete, _) => {
+                        self.core
+                            .remove_query(id)
+                            .await
+                            .map_err(|e| anyhow::anyhow!("failed to delete query '{id}': {e}"))?;
+                        if let Some(previous) = previous {
+                            self.journal.push(Undo::ReAddQuery(previous.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            ("reaction", kind) => {
+                let previous = current.reactions.iter().find(|r| r.id == id);
+                match (kind, desired.reactions.iter().find(|r| r.id == id)) {
+                    (ApplyActionKind::Create, Some(reaction)) => {
+                        self.add_reaction(reaction.clone()).await?;
+                        self.journal.push(Undo::RemoveReaction(id.to_string()));
+                        if reaction.auto_start {
+                            self.start("reaction", id).await;
+                        }
+                    }
+                    (ApplyActionKind::Update, Some(reaction)) => {
+                        self.update_reaction(reaction.clone()).await?;
+                        if let Some(previous) = previous {
+                            self.journal.push(Undo::RestoreReaction(previous.clone()));
+                        }
+                    }
+                    (ApplyActionKind::Delete, _) => {
+                        self.core.remove_reaction(id, false).await.map_err(|e| {
+                            anyhow::anyhow!("failed to delete reaction '{id}': {e}")
+                        })?;
+                        if let Some(previous) = previous {
+                            self.journal.push(Undo::RestoreReaction(previous.clone()));
+                            self.pruned.push(Pruned::Reaction(previous.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Undo completed actions in reverse order, returning the steps that
+    /// failed. A failed step does not stop the remaining ones.
+    async fn rollback(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        while let Some(undo) = self.journal.pop() {
+            let result = match undo {
+                Undo::RemoveSource(id) => self
+                    .core
+                    .remove_source(&id, false)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("source '{id}': {e}")),
+                Undo::RestoreSource(config) => self.restore_source(config).await,
+                Undo::RemoveQuery(id) => self
+                    .core
+                    .remove_query(&id)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("query '{id}': {e}")),
+                Undo::RestoreQuery(config) => {
+                    let id = config.id.clone();
+                    self.core
+                        .update_query(&id, config)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("query '{id}': {e}"))
+                }
+                Undo::ReAddQuery(config) => {
+                    let id = config.id.clone();
+                    self.core
+                        .add_query(config)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("query '{id}': {e}"))
+                }
+                Undo::RemoveReaction(id) => self
+                    .core
+                    .remove_reaction(&id, false)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("reaction '{id}': {e}")),
+                Undo::RestoreReaction(config) => self.restore_reaction(config).await,
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to roll back {e}");
+                errors.push(e.to_string());
+            }
+        }
+        errors
+    }
+
+    /// Release the state and external resources of pruned components. They
+    /// were removed without cleanup so a rollback could restore them; each
+    /// is re-created unstarted (reactions without their queries, which may
+    /// be pruned too) and removed again with cleanup.
+    async fn deprovision_pruned(&mut self) {
+        for pruned in std::mem::take(&mut self.pruned) {
+            let result = match pruned {
+                Pruned::Source(config) => {
+                    let id = config.id.clone();
+                    let config = SourceConfig {
+                        auto_start: false,
+                        ..config
+                    };
+                    match self.add_source(config).await {
+                        Ok(()) => self
+                            .core
+                            .remove_source(&id, true)
+                            .await
+                            .map_err(|e| anyhow::anyhow!("source '{id}': {e}")),
+                        Err(e) => Err(e),
+                    }
+                }
+                Pruned::Reaction(config) => {
+                    let id = config.id.clone();
+                    let config = ReactionConfig {
+                        auto_start: false,
+                        queries: Vec::new(),
+                        ..config
+                    };
+                    match self.add_reaction(config).await {
+                        Ok(()) => self
+                            .core
+                            .remove_reaction(&id, true)
+                            .await
+                            .map_err(|e| anyhow::anyhow!("reaction '{id}': {e}")),
+                        Err(e) => Err(e),
+                    }
+                }
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to deprovision pruned {e}");
+                self.errors
+                    .push(format!("failed to deprovision pruned {e}"));
+            }
+        }
+    }
+
+    async fn add_source(&self, config: SourceConfig) -> anyhow::Result<()> {
+        let id = config.id.clone();
+        let config = resolve_source_bootstrap_provider(config, self.providers)?;
+        let (source, plugin_meta) = create_source_locked(
+            self.plugin_registry,
+            self.instance_id,
+            self.secret_consumers,
+            self.plugin_breakers,
+            config,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to create source '{id}': {e}"))?;
+        self.core
+            .add_source_with_metadata(source, plugin_meta)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to add source '{id}': {e}"))
+    }
+
+    async fn update_source(&self, config: SourceConfig) -> anyhow::Result<()> {
+        let id = config.id.clone();
+        let config = resolve_source_bootstrap_provider(config, self.providers)?;
+        let (source, _meta) = create_source_locked(
+            self.plugin_registry,
+            self.instance_id,
+            self.secret_consumers,
+            self.plugin_breakers,
+            config,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to create source '{id}': {e}"))?;
+        self.core
+            .update_source(&id, source)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to update source '{id}': {e}"))
+    }
+
+    /// Put a source back to `config`, re-adding it if it was deleted.
+    async fn restore_source(&self, config: SourceConfig) -> anyhow::Result<()> {
+        let id = config.id.clone();
+        if self.core.get_source_status(&id).await.is_ok() {
+            return self.update_source(config).await;
+        }
+        let auto_start = config.auto_start;
+        self.add_source(config).await?;
+        if auto_start {
+            self.core.start_source(&id).await?;
+        }
+        Ok(())
+    }
+
+    async fn add_reaction(&self, config: ReactionConfig) -> anyhow::Result<()> {
+        let id = config.id.clone();
+        let (reaction, plugin_meta) = create_reaction_locked(
+            self.plugin_registry,
+            self.instance_id,
+            self.secret_consumers,
+            self.plugin_breakers,
+            config,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to create reaction '{id}': {e}"))?;
+        self.core
+            .add_reaction_with_metadata(reaction, plugin_meta)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to add reaction '{id}': {e}"))
+    }
+
+    async fn update_reaction(&self, config: ReactionConfig) -> anyhow::Result<()> {
+        let id = config.id.clone();
+        let (reaction, _meta) = create_reaction_locked(
+            self.plugin_registry,
+            self.instance_id,
+            self.secret_consumers,
+            self.plugin_breakers,
+            config,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("failed to create reaction '{id}': {e}"))?;
+        self.core
+            .update_reaction(&id, reaction)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to update reaction '{id}': {e}"))
+    }
+
+    /// Put a reaction back to `config`, re-adding it if it was deleted.
+    async fn restore_reaction(&self, config: ReactionConfig) -> anyhow::Result<()> {
+        let id = config.id.clone();
+        if self.core.get_reaction_status(&id).await.is_ok() {
+            return self.update_reaction(config).await;
+        }
+        let auto_start = config.auto_start;
+        self.add_reaction(config).await?;
+        if auto_start {
+            self.core.start_reaction(&id).await?;
+        }
+        Ok(())
+    }
+
+    /// Start a newly created component. Start failures do not roll back the
+    /// apply; they are reported alongside the result.
+    async fn start(&mut self, component_type: &str, id: &str) {
+        let result = match component_type {
+            "source" => self.core.start_source(id).await,
+            _ => self.core.start_reaction(id).await,
+        };
+        match result {
+            Ok(()) => self
+                .components_started
+                .push(format!("{component_type}:{id}")),
+            Err(e) => self
+                .errors
+                .push(format!("failed to start {component_type} '{id}': {e}")),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn source(id: &str, config: serde_json::Value) -> SourceConfig {
+        let mut value = json!({"kind": "mock", "id": id});
+        if let (Some(map), serde_json::Value::Object(extra)) = (value.as_object_mut(), config) {
+            map.extend(extra);
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn query(id: &str, source_id: &str) -> (QueryConfigDto, QueryConfig) {
+        let dto: QueryConfigDto = serde_json::from_value(json!({
+            "id": id,
+            "query": "MATCH (n) RETURN n",
+            "sources": [{"sourceId": source_id}],
+        }))
+        .unwrap();
+        let config = DtoMapper::default()
+            .map_with(&dto, &QueryConfigMapper)
+            .unwrap();
+        (dto, config)
+    }
+
+    fn reaction(id: &str, queries: &[&str]) -> ReactionConfig {
+        serde_json::from_value(json!({"kind": "log", "id": id, "queries": queries})).unwrap()
+    }
+
+    fn actions(list: &[ApplyAction]) -> Vec<(String, String, ApplyActionKind)> {
+        list.iter()
+            .map(|a| (a.component_type.clone(), a.id.clone(), a.action))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_creates_updates_and_keeps_unchanged() {
+        let current = ComponentConfigs {
+            sources: vec![
+                source("same", json!({"interval": 5})),
+                source("changed", json!({"interval": 5})),
+            ],
+            queries: vec![query("q1", "same").0],
+            reactions: Vec::new(),
+        };
+        let desired = DesiredState {
+            sources: vec![
+                source("same", json!({"interval": 5})),
+                source("changed", json!({"interval": 10})),
+                source("new", json!({})),
+            ],
+            queries: vec![query("q1", "same")],
+            reactions: vec![reaction("r1", &["q1"])],
+        };
+
+        assert_eq!(
+            actions(&plan_actions(&desired, &current, false)),
+            vec![
+                ("source".into(), "same".into(), ApplyActionKind::Unchanged),
+                ("source".into(), "changed".into(), ApplyActionKind::Update),
+                ("source".into(), "new".into(), ApplyActionKind::Create),
+                ("query".into(), "q1".into(), ApplyActionKind::Unchanged),
+                ("reaction".into(), "r1".into(), ApplyActionKind::Create),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_prunes_in_reverse_dependency_order() {
+        let current = ComponentConfigs {
+            sources: vec![source("old-src", json!({}))],
+            queries: vec![query("old-q", "old-src").0],
+            reactions: vec![reaction("old-r", &["old-q"])],
+        };
+        let desired = DesiredState::default();
+
+        assert!(plan_actions(&desired, &current, false).is_empty());
+        assert_eq!(
+            actions(&plan_actions(&desired, &current, true)),
+            vec![
+                ("reaction".into(), "old-r".into(), ApplyActionKind::Delete),
+                ("query".into(), "old-q".into(), ApplyActionKind::Delete),
+                ("source".into(), "old-src".into(), ApplyActionKind::Delete),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_references_accounts_for_prune() {
+        let current = ComponentConfigs {
+            sources: vec![source("existing", json!({}))],
+            queries: Vec::new(),
+            reactions: Vec::new(),
+        };
+        let desired = DesiredState {
+            sources: Vec::new(),
+            queries: vec![query("q1", "existing")],
+            reactions: vec![reaction("r1", &["q1", "missing"])],
+        };
+
+        let errors = check_references(&desired, &current, false);
+        assert_eq!(
+            errors,
+            vec!["in reaction 'r1': query 'missing' does not exist".to_string()]
+        );
+
+        // With prune, the existing source is deleted, so the query's
+        // subscription no longer resolves.
+        let errors = check_references(&desired, &current, true);
+        assert!(errors
+            .iter()
+            .any(|e| e == "in query 'q1': source 'existing' does not exist"));
+    }
+
+    #[test]
+    fn test_plugin_config_treats_null_as_empty() {
+        assert_eq!(plugin_config(&serde_json::Value::Null), json!({}));
+        assert!(source_matches(
+            &source("s", json!({})),
+            &SourceConfig {
+                config: serde_json::Value::Null,
+                ..source("s", json!({}))
+            }
+        ));
+    }
+}
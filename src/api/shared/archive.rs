@@ -0,0 +1,50 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Archive retention status and index disk usage of an instance; see
+//! [`crate::archive_retention`].
+
+use axum::Json;
+
+use crate::api::models::ArchiveUsage;
+use crate::api::shared::error::{error_codes, ErrorResponse};
+use crate::api::shared::responses::ApiResponse;
+use crate::index_provider::instance_index_dir;
+use crate::instance_registry::InstanceRegistry;
+use crate::storage_usage::directory_size;
+
+/// Report an instance's archive retention results and index size.
+pub async fn archive_usage(
+    registry: &InstanceRegistry,
+    instance_id: &str,
+) -> Result<Json<ApiResponse<ArchiveUsage>>, ErrorResponse> {
+    if !registry.contains(instance_id).await {
+        return Err(ErrorResponse::new(
+            error_codes::INSTANCE_NOT_FOUND,
+            format!("Instance '{instance_id}' not found"),
+        ));
+    }
+    let retention = registry.archive_retention(instance_id).await;
+    let index_dir = instance_index_dir(instance_id);
+    let index_bytes = tokio::task::spawn_blocking(move || directory_size(&index_dir))
+        .await
+        .unwrap_or_default();
+
+    Ok(Json(ApiResponse::success(ArchiveUsage {
+        instance_id: instance_id.to_string(),
+        retention: retention.as_ref().map(|task| task.config().clone()),
+        index_bytes,
+        queries: retention.map(|task| task.statuses()).unwrap_or_default(),
+    })))
+}
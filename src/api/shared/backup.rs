@@ -0,0 +1,115 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backup of an instance's durable data over the API; see [`crate::backup`].
+
+use axum::body::Body;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use bytes::BytesMut;
+use futures_util::stream;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::AsyncReadExt;
+use tokio::sync::RwLock;
+
+use crate::api::shared::config_export::{
+    export_document, instance_config, running_config, SecretFields,
+};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorResponse};
+use crate::backup::{create_backup, DATA_ROOT};
+use crate::config::{ModeOperation, ServerMode};
+use crate::instance_registry::InstanceRegistry;
+use crate::persistence::ConfigPersistence;
+use crate::plugin_registry::PluginRegistry;
+
+/// Bytes read from the archive file per response chunk.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Back up an instance and stream the archive as `application/gzip`.
+pub async fn backup_instance(
+    registry: &InstanceRegistry,
+    mode: ServerMode,
+    persistence: Option<Arc<ConfigPersistence>>,
+    plugin_registry: &RwLock<PluginRegistry>,
+    instance_id: &str,
+) -> Result<Response, ErrorResponse> {
+    // Sources are stopped while the copy is taken.
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "back up instances")?;
+    let core = registry.get(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::INSTANCE_NOT_FOUND,
+            format!("Instance '{instance_id}' not found"),
+        )
+    })?;
+    let backup_error = |e: anyhow::Error| {
+        ErrorResponse::new(
+            error_codes::BACKUP_FAILED,
+            format!("Failed to back up instance '{instance_id}': {e}"),
+        )
+    };
+
+    let config = running_config(registry, persistence.as_deref())
+        .await
+        .map_err(backup_error)?;
+    let config = instance_config(&config, instance_id).ok_or_else(|| {
+        backup_error(anyhow::anyhow!(
+            "the configuration does not contain the instance"
+        ))
+    })?;
+    let secrets = SecretFields::from_registry(&*plugin_registry.read().await);
+    let document = export_document(&config, &secrets).map_err(backup_error)?;
+    let config = serde_yaml::to_string(&document).map_err(|e| backup_error(e.into()))?;
+
+    // Nothing else may start, stop or change the instance's components, or
+    // prune its archive, while the sources are stopped for the copy.
+    let _write_guard = registry.revisions().lock_writes(instance_id).await;
+    let retention = registry.archive_retention(instance_id).await;
+    let _retention_paused = match &retention {
+        Some(retention) => Some(retention.pause().await),
+        None => None,
+    };
+    let storage = registry.storage(instance_id).await;
+    let archive = create_backup(
+        instance_id,
+        &core,
+        storage.as_deref(),
+        registry.state_store(instance_id).await,
+        config,
+        Path::new(DATA_ROOT),
+    )
+    .await
+    .map_err(backup_error)?;
+
+    // The temporary file is removed once the stream drops it.
+    let chunks = stream::unfold(tokio::fs::File::from_std(archive), |mut file| async move {
+        let mut chunk = BytesMut::with_capacity(CHUNK_SIZE);
+        match file.read_buf(&mut chunk).await {
+            Ok(0) => None,
+            Ok(_) => Some((Ok(chunk.freeze()), file)),
+            Err(e) => Some((Err(e), file)),
+        }
+    });
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"backup.tar.gz\"",
+            ),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
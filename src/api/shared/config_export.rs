@@ -0,0 +1,724 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export of the running configuration as a config file.
+//!
+//! The exported document has the shape `load_config_file` accepts, so it can
+//! be saved and started elsewhere. Secrets are never exported: every field a
+//! plugin schema marks `writeOnly` or `format: password`, and the `password`
+//! of the built-in password identity provider, is replaced with a `${VAR}`
+//! reference named after the component and field (for example
+//! `${SOURCES_ORDERS_DB_PASSWORD}`). For a kind no installed plugin describes,
+//! every field whose name looks secret (see [`is_secret_name`]) is replaced.
+//! Values that already are references are kept as they are.
+
+use axum::http::{header, HeaderMap};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::{Map, Value as JsonValue};
+use serde_yaml::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::api::models::{ConfigValue, BUILTIN_PASSWORD_KIND};
+use crate::api::shared::error::{error_codes, ErrorResponse};
+use crate::api::shared::extractor::is_yaml_content_type;
+use crate::config::layers::collect_leaves;
+use crate::config::{DrasiLibInstanceConfig, DrasiServerConfig};
+use crate::instance_registry::InstanceRegistry;
+use crate::persistence::{ComponentConfigs, ConfigPersistence};
+use crate::plugin_registry::{PluginKindInfo, PluginRegistry};
+
+/// Prefix of `$ref`s in plugin config schemas.
+const SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+
+/// How deep nested objects and `$ref`s are followed when looking for secret
+/// fields; guards against self-referencing schemas.
+const MAX_SCHEMA_DEPTH: usize = 8;
+
+/// The component lists a secret field can appear in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Category {
+    Source,
+    Reaction,
+    Bootstrap,
+    Identity,
+    SecretStore,
+}
+
+/// Paths of the secret fields of each plugin kind, taken from the installed
+/// plugins' config schemas. Kinds with no secret fields are recorded too, so
+/// an unknown kind can be told apart.
+#[derive(Debug, Default)]
+pub struct SecretFields {
+    paths: HashMap<(Category, String), Vec<Vec<String>>>,
+}
+
+impl SecretFields {
+    /// Collect the secret fields of every installed plugin kind.
+    pub fn from_registry(registry: &PluginRegistry) -> Self {
+        let mut fields = Self::default();
+        for (category, infos) in [
+            (Category::Source, registry.source_plugin_infos()),
+            (Category::Reaction, registry.reaction_plugin_infos()),
+            (Category::Bootstrap, registry.bootstrapper_plugin_infos()),
+            (
+                Category::Identity,
+                registry.identity_provider_plugin_infos(),
+            ),
+            (Category::SecretStore, registry.secret_store_plugin_infos()),
+        ] {
+            for info in &infos {
+                fields.insert_plugin(category, info);
+            }
+        }
+        fields.paths.insert(
+            (Category::Identity, BUILTIN_PASSWORD_KIND.to_string()),
+            vec![vec!["password".to_string()]],
+        );
+        fields
+    }
+
+    fn insert_plugin(&mut self, category: Category, info: &PluginKindInfo) {
+        let schemas: Map<String, JsonValue> = match serde_json::from_str(&info.config_schema_json) {
+            Ok(schemas) => schemas,
+            Err(e) => {
+                log::warn!(
+                    "Failed to parse config schema of plugin kind '{}': {e}",
+                    info.kind
+                );
+                return;
+            }
+        };
+        let Some(schema) = schemas.get(&info.config_schema_name) else {
+            return;
+        };
+        let mut paths = Vec::new();
+        collect_secret_paths(&schemas, schema, &mut Vec::new(), &mut paths, 0);
+        self.paths.insert((category, info.kind.clone()), paths);
+    }
+
+    /// Secret field paths of a kind, or `None` if no installed plugin (or
+    /// readable schema) describes it.
+    fn get(&self, category: Category, kind: &str) -> Option<&[Vec<String>]> {
+        self.paths
+            .get(&(category, kind.to_string()))
+            .map(Vec::as_slice)
+    }
+}
+
+/// Whether a field name suggests it holds a secret: it contains `password`,
+/// `passphrase`, `secret`, or `token`, or ends in `key` (`apiKey`,
+/// `privateKey`). Matched ignoring case.
+pub fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    ["password", "passphrase", "secret", "token"]
+        .iter()
+        .any(|word| name.contains(word))
+        || name.ends_with("key")
+}
+
+/// Whether a property schema marks its value as a secret.
+fn is_secret_schema(schema: &JsonValue) -> bool {
+    schema.get("writeOnly").and_then(JsonValue::as_bool) == Some(true)
+        || schema.get("format").and_then(JsonValue::as_str) == Some("password")
+}
+
+/// Walk `schema`, following `$ref`s into `schemas`, and record the path of
+/// every secret property.
+fn collect_secret_paths(
+    schemas: &Map<String, JsonValue>,
+    schema: &JsonValue,
+    prefix: &mut Vec<String>,
+    out: &mut Vec<Vec<String>>,
+    depth: usize,
+) {
+    if depth > MAX_SCHEMA_DEPTH {
+        return;
+    }
+    if let Some(reference) = schema.get("$ref").and_then(JsonValue::as_str) {
+        if let Some(target) = reference
+            .strip_prefix(SCHEMA_REF_PREFIX)
+            .and_then(|name| schemas.get(name))
+        {
+            collect_secret_paths(schemas, target, prefix, out, depth + 1);
+        }
+    }
+    for combinator in ["allOf", "oneOf", "anyOf"] {
+        if let Some(JsonValue::Array(variants)) = schema.get(combinator) {
+            for variant in variants {
+                collect_secret_paths(schemas, variant, prefix, out, depth + 1);
+            }
+        }
+    }
+    if let Some(JsonValue::Object(properties)) = schema.get("properties") {
+        for (name, property) in properties {
+            prefix.push(name.clone());
+            if is_secret_schema(property) {
+                if !out.contains(prefix) {
+                    out.push(prefix.clone());
+                }
+            } else {
+                collect_secret_paths(schemas, property, prefix, out, depth + 1);
+            }
+            prefix.pop();
+        }
+    }
+}
+
+/// The configuration the server is running, in config file form.
+///
+/// When the server was started from a config file this is the loaded config
+/// with runtime changes merged in, exactly what a save would write, whether
+/// or not saving is enabled. Without a config file the components are rebuilt
+/// from each instance's runtime snapshot and server settings are left at
+/// their defaults.
+pub async fn running_config(
+    registry: &InstanceRegistry,
+    persistence: Option<&ConfigPersistence>,
+) -> anyhow::Result<DrasiServerConfig> {
+    if let Some(persistence) = persistence {
+        return persistence.current_config().await;
+    }
+
+    let mut instances = Vec::new();
+    for (id, core) in registry.list().await {
+        let snapshot = core
+            .snapshot_configuration()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to snapshot instance '{id}': {e}"))?;
+        let restart_policies = registry.restart_policies(&id).await;
+        let ComponentConfigs {
+            sources,
+            queries,
+            reactions,
+        } = ComponentConfigs::from_snapshot(&snapshot, &restart_policies);
+        instances.push(DrasiLibInstanceConfig {
+            id: ConfigValue::Static(id),
+            persist_index: false,
+            enable_archive: false,
+            archive_retention: None,
+            wal_retention: None,
+            state_store: None,
+            secret_store: None,
+            default_priority_queue_capacity: None,
+            default_dispatch_buffer_capacity: None,
+            sources,
+            queries,
+            reactions,
+            identity_providers: Vec::new(),
+            bootstrap_providers: Vec::new(),
+        });
+    }
+
+    if instances.len() == 1 {
+        let instance = instances.remove(0);
+        return Ok(DrasiServerConfig {
+            id: instance.id,
+            sources: instance.sources,
+            queries: instance.queries,
+            reactions: instance.reactions,
+            ..DrasiServerConfig::default()
+        });
+    }
+    Ok(DrasiServerConfig {
+        id: instances
+            .first()
+            .map(|instance| instance.id.clone())
+            .unwrap_or_default(),
+        instances,
+        ..DrasiServerConfig::default()
+    })
+}
+
+/// One instance of `config` as a single-instance config file, keeping the
+/// server settings. `None` if `config` does not contain the instance.
+pub fn instance_config(config: &DrasiServerConfig, instance_id: &str) -> Option<DrasiServerConfig> {
+    let is_instance =
+        |id: &ConfigValue<String>| matches!(id, ConfigValue::Static(id) if id == instance_id);
+    if config.instances.is_empty() {
+        return is_instance(&config.id).then(|| config.clone());
+    }
+    let instance = config
+        .instances
+        .iter()
+        .find(|instance| is_instance(&instance.id))?
+        .clone();
+    Some(DrasiServerConfig {
+        id: instance.id,
+        persist_index: instance.persist_index,
+        enable_archive: instance.enable_archive,
+        archive_retention: instance.archive_retention,
+        wal_retention: instance.wal_retention,
+        state_store: instance.state_store,
+        secret_store: instance.secret_store,
+        default_priority_queue_capacity: instance.default_priority_queue_capacity,
+        default_dispatch_buffer_capacity: instance.default_dispatch_buffer_capacity,
+        sources: instance.sources,
+        queries: instance.queries,
+        reactions: instance.reactions,
+        identity_providers: instance.identity_providers,
+        bootstrap_providers: instance.bootstrap_providers,
+        instances: Vec::new(),
+        ..config.clone()
+    })
+}
+
+/// Serialize `config` and replace its secrets with `${VAR}` references.
+pub fn export_document(
+    config: &DrasiServerConfig,
+    secrets: &SecretFields,
+) -> anyhow::Result<Value> {
+    let mut document = serde_yaml::to_value(config)?;
+    redact_document(&mut document, secrets);
+    Ok(document)
+}
+
+/// Replace the secrets of a config document with `${VAR}` references.
+pub fn redact_document(document: &mut Value, secrets: &SecretFields) {
+    redact_instance(document, secrets, None);
+    if let Some(Value::Sequence(instances)) = document.get_mut("instances") {
+        for instance in instances {
+            let id = string_field(instance, "id");
+            redact_instance(instance, secrets, id.as_deref());
+        }
+    }
+}
+
+/// Paths of the secret values of a config document, in the form
+/// [`ConfigLayers::value_origins`](crate::config::ConfigLayers::value_origins)
+/// reports them (`sources.orders-db.password`).
+pub fn secret_value_paths(document: &Value, secrets: &SecretFields) -> HashSet<String> {
+    let mut redacted = document.clone();
+    redact_document(&mut redacted, secrets);
+    let mut before = Vec::new();
+    collect_leaves(document, "", &mut before);
+    let mut after = Vec::new();
+    collect_leaves(&redacted, "", &mut after);
+    let after: HashMap<String, Value> = after.into_iter().collect();
+    before
+        .into_iter()
+        .filter(|(path, value)| after.get(path) != Some(value))
+        .map(|(path, _)| path)
+        .collect()
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Redact the component lists and secret store of one instance, either the
+/// top level of the document or an `instances` entry.
+fn redact_instance(instance: &mut Value, secrets: &SecretFields, instance_id: Option<&str>) {
+    let scope: Vec<&str> = instance_id.into_iter().collect();
+    for (list, category) in [
+        ("sources", Category::Source),
+        ("reactions", Category::Reaction),
+        ("bootstrapProviders", Category::Bootstrap),
+        ("identityProviders", Category::Identity),
+    ] {
+        let Some(Value::Sequence(components)) = instance.get_mut(list) else {
+            continue;
+        };
+        for component in components {
+            let id = string_field(component, "id").unwrap_or_default();
+            let mut name = scope.clone();
+            name.extend([list, id.as_str()]);
+            redact_component(component, secrets, category, &name);
+            if category == Category::Source {
+                if let Some(bootstrap) = component
+                    .get_mut("bootstrapProvider")
+                    .filter(|b| b.is_mapping())
+                {
+                    name.push("bootstrapProvider");
+                    redact_component(bootstrap, secrets, Category::Bootstrap, &name);
+                }
+            }
+        }
+    }
+    if let Some(store) = instance.get_mut("secretStore") {
+        let mut name = scope;
+        name.push("secretStore");
+        redact_component(store, secrets, Category::SecretStore, &name);
+    }
+}
+
+fn redact_component(
+    component: &mut Value,
+    secrets: &SecretFields,
+    category: Category,
+    name: &[&str],
+) {
+    let Some(kind) = string_field(component, "kind") else {
+        return;
+    };
+    match secrets.get(category, &kind) {
+        Some(paths) => {
+            for path in paths {
+                redact_path(component, path, name);
+            }
+        }
+        None => redact_by_name(component, name),
+    }
+}
+
+/// Redact every field of an unknown kind whose name looks secret, at any
+/// depth.
+fn redact_by_name(value: &mut Value, name: &[&str]) {
+    let Value::Mapping(map) = value else {
+        return;
+    };
+    for (key, child) in map.iter_mut() {
+        let Some(field) = key.as_str() else {
+            continue;
+        };
+        if field == "id" || field == "kind" {
+            continue;
+        }
+        let mut segments = name.to_vec();
+        segments.push(field);
+        if is_secret_name(field) && !child.is_mapping() {
+            if !is_reference(child) {
+                *child = Value::String(format!("${{{}}}", env_var_name(&segments)));
+            }
+        } else {
+            redact_by_name(child, &segments);
+        }
+    }
+}
+
+fn redact_path(value: &mut Value, path: &[String], name: &[&str]) {
+    let Some((field, rest)) = path.split_first() else {
+        return;
+    };
+    let Some(child) = value.get_mut(field.as_str()) else {
+        return;
+    };
+    if !rest.is_empty() {
+        let mut name = name.to_vec();
+        name.push(field);
+        redact_path(child, rest, &name);
+        return;
+    }
+    if is_reference(child) {
+        return;
+    }
+    let mut segments = name.to_vec();
+    segments.push(field);
+    *child = Value::String(format!("${{{}}}", env_var_name(&segments)));
+}
+
+/// Whether a value is empty or already points somewhere else instead of
+/// holding the secret: a `${VAR}` string or a `{kind: Secret | EnvironmentVariable}`
+/// object.
+fn is_reference(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.starts_with("${") && s.ends_with('}'),
+        Value::Mapping(_) => matches!(
+            value.get("kind").and_then(Value::as_str),
+            Some("Secret" | "EnvironmentVariable")
+        ),
+        _ => false,
+    }
+}
+
+/// An environment variable name from name segments: camelCase words are
+/// split, everything is uppercased, and other characters become `_`.
+fn env_var_name(segments: &[&str]) -> String {
+    let mut name = String::new();
+    for segment in segments.iter().filter(|s| !s.is_empty()) {
+        if !name.is_empty() {
+            name.push('_');
+        }
+        let mut previous_lower = false;
+        for c in segment.chars() {
+            if c.is_ascii_uppercase() && previous_lower {
+                name.push('_');
+            }
+            previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+            name.push(if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            });
+        }
+    }
+    name
+}
+
+/// Render an exported document as YAML when `Accept` asks for it, JSON
+/// otherwise.
+pub fn render_document(document: &Value, headers: &HeaderMap) -> Result<Response, ErrorResponse> {
+    let wants_yaml = headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(is_yaml_content_type);
+    if !wants_yaml {
+        return Ok(Json(document).into_response());
+    }
+    let yaml = serde_yaml::to_string(document).map_err(|e| {
+        ErrorResponse::new(
+            error_codes::INTERNAL_ERROR,
+            format!("Failed to serialize configuration: {e}"),
+        )
+    })?;
+    Ok(([(header::CONTENT_TYPE, "application/yaml")], yaml).into_response())
+}
+
+/// Export the running configuration, or one instance of it, redacted and
+/// rendered as `Accept` asks.
+pub async fn export_config(
+    registry: &InstanceRegistry,
+    persistence: Option<Arc<ConfigPersistence>>,
+    plugin_registry: &RwLock<PluginRegistry>,
+    instance_id: Option<&str>,
+    headers: &HeaderMap,
+) -> Result<Response, ErrorResponse> {
+    let instance_not_found = |id: &str| {
+        ErrorResponse::new(
+            error_codes::INSTANCE_NOT_FOUND,
+            format!("Instance '{id}' not found"),
+        )
+    };
+    if let Some(id) = instance_id {
+        if !registry.contains(id).await {
+            return Err(instance_not_found(id));
+        }
+    }
+
+    let export_error = |e: anyhow::Error| {
+        ErrorResponse::new(
+            error_codes::INTERNAL_ERROR,
+            format!("Failed to export configuration: {e}"),
+        )
+    };
+    let mut config = running_config(registry, persistence.as_deref())
+        .await
+        .map_err(export_error)?;
+    if let Some(id) = instance_id {
+        config = instance_config(&config, id).ok_or_else(|| instance_not_found(id))?;
+    }
+    let secrets = SecretFields::from_registry(&*plugin_registry.read().await);
+    let document = export_document(&config, &secrets).map_err(export_error)?;
+    render_document(&document, headers)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(category: Category, kind: &str, paths: &[&[&str]]) -> SecretFields {
+        let mut fields = SecretFields::default();
+        fields.paths.insert(
+            (category, kind.to_string()),
+            paths
+                .iter()
+                .map(|path| path.iter().map(|s| s.to_string()).collect())
+                .collect(),
+        );
+        fields
+    }
+
+    #[test]
+    fn test_secret_paths_follow_refs_and_nested_objects() {
+        let schemas: Map<String, JsonValue> = serde_json::from_value(json!({
+            "PgConfig": {
+                "type": "object",
+                "properties": {
+                    "host": { "type": "string" },
+                    "password": { "type": "string", "format": "password" },
+                    "tls": { "$ref": "#/components/schemas/Tls" }
+                }
+            },
+            "Tls": {
+                "type": "object",
+                "properties": {
+                    "keyPassphrase": { "type": "string", "writeOnly": true },
+                    "caFile": { "type": "string" }
+                }
+            }
+        }))
+        .unwrap();
+        let mut paths = Vec::new();
+        collect_secret_paths(
+            &schemas,
+            &schemas["PgConfig"],
+            &mut Vec::new(),
+            &mut paths,
+            0,
+        );
+        assert_eq!(
+            paths,
+            vec![
+                vec!["password".to_string()],
+                vec!["tls".to_string(), "keyPassphrase".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_replaces_secrets_with_env_references() {
+        let config: DrasiServerConfig = serde_yaml::from_str(
+            r#"
+id: server
+sources:
+  - kind: postgres
+    id: orders-db
+    host: db.local
+    password: hunter2
+    tls:
+      keyPassphrase: ${TLS_PASS}
+identityProviders:
+  - kind: password
+    id: pg-creds
+    username: app
+    password: s3cret
+"#,
+        )
+        .unwrap();
+        let mut secrets = fields(
+            Category::Source,
+            "postgres",
+            &[&["password"], &["tls", "keyPassphrase"]],
+        );
+        secrets.paths.insert(
+            (Category::Identity, BUILTIN_PASSWORD_KIND.to_string()),
+            vec![vec!["password".to_string()]],
+        );
+
+        let document = export_document(&config, &secrets).unwrap();
+        let source = &document["sources"][0];
+        assert_eq!(source["password"], "${SOURCES_ORDERS_DB_PASSWORD}");
+        assert_eq!(source["tls"]["keyPassphrase"], "${TLS_PASS}");
+        assert_eq!(source["host"], "db.local");
+        let identity = &document["identityProviders"][0];
+        assert_eq!(
+            identity["password"],
+            "${IDENTITY_PROVIDERS_PG_CREDS_PASSWORD}"
+        );
+        assert_eq!(identity["username"], "app");
+
+        // The export is itself a loadable config.
+        let yaml = serde_yaml::to_string(&document).unwrap();
+        serde_yaml::from_str::<DrasiServerConfig>(&yaml).unwrap();
+    }
+
+    #[test]
+    fn test_secret_value_paths() {
+        let document: Value = serde_yaml::from_str(
+            r#"
+instances:
+  - id: prod
+    sources:
+      - {kind: postgres, id: orders-db, host: db.local, password: hunter2}
+      - {kind: postgres, id: other-db, password: "${OTHER_PASS}"}
+"#,
+        )
+        .unwrap();
+        let secrets = fields(Category::Source, "postgres", &[&["password"]]);
+        assert_eq!(
+            secret_value_paths(&document, &secrets),
+            HashSet::from(["instances.prod.sources.orders-db.password".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_unknown_kinds_are_redacted_by_field_name() {
+        let config: DrasiServerConfig = serde_yaml::from_str(
+            r#"
+id: server
+sources:
+  - kind: custom
+    id: feed
+    apiKey: abc123
+    endpoint: https://feed.example
+    auth:
+      clientSecret: s3cret
+      refreshToken: "${FEED_TOKEN}"
+  - kind: postgres
+    id: orders-db
+    apiKey: not-a-secret-here
+"#,
+        )
+        .unwrap();
+        let secrets = fields(Category::Source, "postgres", &[]);
+
+        let document = export_document(&config, &secrets).unwrap();
+        let feed = &document["sources"][0];
+        assert_eq!(feed["apiKey"], "${SOURCES_FEED_API_KEY}");
+        assert_eq!(feed["endpoint"], "https://feed.example");
+        assert_eq!(
+            feed["auth"]["clientSecret"],
+            "${SOURCES_FEED_AUTH_CLIENT_SECRET}"
+        );
+        assert_eq!(feed["auth"]["refreshToken"], "${FEED_TOKEN}");
+        assert_eq!(
+            document["sources"][1]["apiKey"], "not-a-secret-here",
+            "a known kind is redacted by its schema only"
+        );
+        assert!(is_secret_name("keyPassphrase"));
+        assert!(!is_secret_name("keyspace"));
+    }
+
+    #[test]
+    fn test_instance_config_extracts_one_instance() {
+        let config: DrasiServerConfig = serde_yaml::from_str(
+            r#"
+port: 9000
+instances:
+  - id: first
+    queries:
+      - id: q1
+        query: MATCH (n) RETURN n
+  - id: second
+"#,
+        )
+        .unwrap();
+        let first = instance_config(&config, "first").unwrap();
+        assert!(first.instances.is_empty());
+        assert_eq!(first.queries.len(), 1);
+        assert_eq!(first.port, ConfigValue::Static(9000));
+        assert!(instance_config(&config, "third").is_none());
+    }
+
+    #[test]
+    fn test_env_var_names_split_camel_case() {
+        assert_eq!(
+            env_var_name(&["prod", "secretStore", "clientSecret"]),
+            "PROD_SECRET_STORE_CLIENT_SECRET"
+        );
+        assert_eq!(env_var_name(&["", "sources", "my.pg"]), "SOURCES_MY_PG");
+    }
+
+    #[test]
+    fn test_render_document_negotiates_yaml() {
+        let document: Value = serde_yaml::from_str("id: server").unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT,
+            "application/json;q=0.5, application/yaml".parse().unwrap(),
+        );
+        let response = render_document(&document, &headers).unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/yaml");
+
+        let response = render_document(&document, &HeaderMap::new()).unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Config revision history and rollback.
+//!
+//! Rolling back reconciles every running instance declared in the revision
+//! to that revision's sources, queries, and reactions (an apply with
+//! `prune=true`), then saves the result as a new revision. All instances are
+//! validated before any is changed; each instance is then applied as its own
+//! unit, so one instance failing does not undo another. Each instance is
+//! planned and applied while holding its write lock (see
+//! [`crate::component_revisions::ComponentRevisions::lock_writes`]), so
+//! rollback does not interleave with API writes to that instance.
+
+use axum::Json;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::api::models::apply::{ApplyManifest, ApplyParams};
+use crate::api::models::config_revision::{
+    ConfigRevisionDiff, InstanceRollback, RollbackParams, RollbackResponse,
+};
+use crate::api::models::ConfigValue;
+use crate::api::shared::apply::{has_changes, reconcile_instance};
+use crate::api::shared::config_export::{redact_document, SecretFields};
+use crate::api::shared::error::{error_codes, ErrorResponse};
+use crate::api::shared::handlers::persist_after_operation;
+use crate::api::shared::ApiResponse;
+use crate::config::DrasiServerConfig;
+use crate::config_revisions::ConfigRevision;
+use crate::instance_registry::InstanceRegistry;
+use crate::persistence::ConfigPersistence;
+use crate::plugin_registry::PluginRegistry;
+
+fn require_persistence(
+    persistence: &Option<Arc<ConfigPersistence>>,
+) -> Result<&Arc<ConfigPersistence>, ErrorResponse> {
+    persistence
+        .as_ref()
+        .filter(|persistence| persistence.persists())
+        .ok_or_else(|| {
+            ErrorResponse::new(
+                error_codes::PERSISTENCE_DISABLED,
+                "Configuration persistence is disabled, so no config revisions are recorded",
+            )
+        })
+}
+
+fn revision_not_found(revision: u64) -> ErrorResponse {
+    ErrorResponse::new(
+        error_codes::CONFIG_REVISION_NOT_FOUND,
+        format!("Config revision {revision} not found"),
+    )
+}
+
+fn history_error(e: anyhow::Error) -> ErrorResponse {
+    ErrorResponse::new(
+        error_codes::INTERNAL_ERROR,
+        format!("Failed to read config revision history: {e}"),
+    )
+}
+
+/// List the retained config revisions, newest first.
+pub async fn list_revisions(
+    persistence: Option<Arc<ConfigPersistence>>,
+) -> Result<Json<ApiResponse<Vec<ConfigRevision>>>, ErrorResponse> {
+    let persistence = require_persistence(&persistence)?;
+    let mut revisions = persistence.revisions().list().map_err(history_error)?;
+    revisions.reverse();
+    Ok(Json(ApiResponse::success(revisions)))
+}
+
+/// Get the diff a revision made to the config file, with secrets replaced
+/// by `${VAR}` references as in a config export.
+pub async fn get_revision_diff(
+    persistence: Option<Arc<ConfigPersistence>>,
+    plugin_registry: &RwLock<PluginRegistry>,
+    revision: u64,
+) -> Result<Json<ApiResponse<ConfigRevisionDiff>>, ErrorResponse> {
+    let persistence = require_persistence(&persistence)?;
+    let store = persistence.revisions();
+    let metadata = store
+        .get(revision)
+        .map_err(history_error)?
+        .ok_or_else(|| revision_not_found(revision))?;
+    let secrets = SecretFields::from_registry(&*plugin_registry.read().await);
+    let diff = store
+        .redacted_diff(revision, |content| {
+            let mut document: serde_yaml::Value = serde_yaml::from_str(content)?;
+            redact_document(&mut document, &secrets);
+            Ok(serde_yaml::to_string(&document)?)
+        })
+        .map_err(history_error)?
+        .ok_or_else(|| revision_not_found(revision))?;
+    Ok(Json(ApiResponse::success(ConfigRevisionDiff {
+        revision: metadata,
+        diff,
+    })))
+}
+
+/// Reconcile the running instances to a revision and save the result.
+pub async fn rollback_to_revision(
+    registry: InstanceRegistry,
+    persistence: Option<Arc<ConfigPersistence>>,
+    plugin_registry: &RwLock<PluginRegistry>,
+    revision: u64,
+    params: RollbackParams,
+) -> Result<Json<ApiResponse<RollbackResponse>>, ErrorResponse> {
+    let store = require_persistence(&persistence)?.revisions();
+    let content = store
+        .content(revision)
+        .map_err(history_error)?
+        .ok_or_else(|| revision_not_found(revision))?;
+    let config: DrasiServerConfig = serde_yaml::from_str(&content).map_err(|e| {
+        ErrorResponse::new(
+            error_codes::INVALID_REQUEST,
+            format!("Config revision {revision} cannot be parsed: {e}"),
+        )
+    })?;
+
+    let mut manifests = Vec::new();
+    let mut skipped_instances = Vec::new();
+    for (instance_id, manifest) in instance_manifests(&config).map_err(|e| {
+        ErrorResponse::new(
+            error_codes::INVALID_REQUEST,
+            format!("Config revision {revision} cannot be applied: {e}"),
+        )
+    })? {
+        if registry.get(&instance_id).await.is_some() {
+            manifests.push((instance_id, manifest));
+        } else {
+            skipped_instances.push(instance_id);
+        }
+    }
+
+    // Validate and plan every instance before changing any of them.
+    let mut instances = Vec::new();
+    for (instance_id, manifest) in &manifests {
+        let _write_guard = registry.revisions().lock_writes(instance_id).await;
+        let plan = reconcile_instance(
+            &registry,
+            &persistence,
+            plugin_registry,
+            instance_id,
+            ApplyParams {
+                dry_run: true,
+                prune: true,
+            },
+            manifest.clone(),
+        )
+        .await
+        .map_err(|mut e| {
+            e.message = format!("Instance '{instance_id}': {}", e.message);
+            e
+        })?;
+        instances.push(InstanceRollback {
+            instance_id: instance_id.clone(),
+            result: plan,
+        });
+    }
+    if params.dry_run {
+        return Ok(Json(ApiResponse::success(RollbackResponse {
+            revision,
+            dry_run: true,
+            applied: false,
+            instances,
+            skipped_instances,
+        })));
+    }
+
+    let mut instances = Vec::new();
+    for (instance_id, manifest) in manifests {
+        // Held until the instance's changes and revision bumps are done.
+        let _write_guard = registry.revisions().lock_writes(&instance_id).await;
+        let result = match reconcile_instance(
+            &registry,
+            &persistence,
+            plugin_registry,
+            &instance_id,
+            ApplyParams {
+                dry_run: false,
+                prune: true,
+            },
+            manifest,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(mut e) => {
+                // The failed instance was rolled back; the ones before it
+                // were changed and must still be saved.
+                if instances.iter().any(|i| has_changes(&i.result)) {
+                    persist_after_operation(
+                        &persistence,
+                        &format!("partially rolling back to config revision {revision}"),
+                    )
+                    .await?;
+                }
+                e.message = format!("Instance '{instance_id}': {}", e.message);
+                return Err(e);
+            }
+        };
+        instances.push(InstanceRollback {
+            instance_id,
+            result,
+        });
+    }
+
+    if instances.iter().any(|i| has_changes(&i.result)) {
+        persist_after_operation(
+            &persistence,
+            &format!("rolling back to config revision {revision}"),
+        )
+        .await?;
+    }
+
+    Ok(Json(ApiResponse::success(RollbackResponse {
+        revision,
+        dry_run: false,
+        applied: instances.iter().all(|i| i.result.applied),
+        instances,
+        skipped_instances,
+    })))
+}
+
+/// The components of each instance in `config` as an apply manifest, keyed
+/// by instance id.
+fn instance_manifests(config: &DrasiServerConfig) -> anyhow::Result<Vec<(String, ApplyManifest)>> {
+    let static_id = |id: &ConfigValue<String>| match id {
+        ConfigValue::Static(id) => Ok(id.clone()),
+        _ => Err(anyhow::anyhow!(
+            "instance ids must be literal values to roll back"
+        )),
+    };
+
+    if config.instances.is_empty() {
+        return Ok(vec![(
+            static_id(&config.id)?,
+            ApplyManifest {
+                sources: serialize_all(&config.sources)?,
+                queries: serialize_all(&config.queries)?,
+                reactions: serialize_all(&config.reactions)?,
+            },
+        )]);
+    }
+    config
+        .instances
+        .iter()
+        .map(|instance| {
+            Ok((
+                static_id(&instance.id)?,
+                ApplyManifest {
+                    sources: serialize_all(&instance.sources)?,
+                    queries: serialize_all(&instance.queries)?,
+                    reactions: serialize_all(&instance.reactions)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn serialize_all<T: serde::Serialize>(items: &[T]) -> anyhow::Result<Vec<serde_json::Value>> {
+    items
+        .iter()
+        .map(|item| serde_json::to_value(item).map_err(anyhow::Error::from))
+        .collect()
+}
@@ -29,6 +29,7 @@
 //! - **Layer 3 (drasi-lib):** Returns `DrasiError` which is converted to
 //!   `ErrorResponse` via `From<DrasiError>` with proper status code mapping.
 
+use crate::config::{ModeOperation, ServerMode};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use drasi_lib::DrasiError;
@@ -56,7 +57,12 @@ pub mod error_codes {
     pub const REACTION_STOP_FAILED: &str = "REACTION_STOP_FAILED";
     pub const REACTION_DELETE_FAILED: &str = "REACTION_DELETE_FAILED";
 
+    /// Returned when the server runs in `readOnly` mode, which rejects
+    /// every change.
     pub const CONFIG_READ_ONLY: &str = "CONFIG_READ_ONLY";
+    /// Returned when the server runs in `locked` mode, which rejects
+    /// creating, updating and deleting but still allows start/stop.
+    pub const SERVER_LOCKED: &str = "SERVER_LOCKED";
     pub const DUPLICATE_RESOURCE: &str = "DUPLICATE_RESOURCE";
     pub const INVALID_REQUEST: &str = "INVALID_REQUEST";
     pub const INTERNAL_ERROR: &str = "INTERNAL_ERROR";
@@ -65,10 +71,38 @@ pub mod error_codes {
     /// on-disk YAML has not. Operators should retry the operation or restart
     /// the server after fixing the underlying persistence issue.
     pub const PERSISTENCE_FAILED: &str = "PERSISTENCE_FAILED";
+    /// Returned by config history endpoints when the server does not persist
+    /// its configuration, so there is no history to read or roll back to.
+    pub const PERSISTENCE_DISABLED: &str = "PERSISTENCE_DISABLED";
+    pub const CONFIG_REVISION_NOT_FOUND: &str = "CONFIG_REVISION_NOT_FOUND";
+    /// Returned when an `If-Match` header does not match the current
+    /// revision of the resource being changed.
+    pub const PRECONDITION_FAILED: &str = "PRECONDITION_FAILED";
+    /// Returned when an action of an apply failed and the completed ones
+    /// were rolled back.
+    pub const APPLY_FAILED: &str = "APPLY_FAILED";
 
     pub const INSTANCE_NOT_FOUND: &str = "INSTANCE_NOT_FOUND";
     pub const INSTANCE_CREATE_FAILED: &str = "INSTANCE_CREATE_FAILED";
 
+    /// Returned when the server did not build the instance, so it has no
+    /// handle on the instance's state store.
+    pub const STATE_STORE_UNAVAILABLE: &str = "STATE_STORE_UNAVAILABLE";
+    pub const STATE_STORE_NOT_FOUND: &str = "STATE_STORE_NOT_FOUND";
+    pub const STATE_KEY_NOT_FOUND: &str = "STATE_KEY_NOT_FOUND";
+    pub const STATE_STORE_FAILED: &str = "STATE_STORE_FAILED";
+    /// Returned when changing a component's state while it runs.
+    pub const COMPONENT_RUNNING: &str = "COMPONENT_RUNNING";
+    /// Returned when an instance's durable data could not be archived.
+    pub const BACKUP_FAILED: &str = "BACKUP_FAILED";
+    /// Returned when the server did not build the instance, so it has no
+    /// handle on the instance's WAL.
+    pub const WAL_UNAVAILABLE: &str = "WAL_UNAVAILABLE";
+    pub const REPLAY_FAILED: &str = "REPLAY_FAILED";
+    /// Returned when the server did not build the instance, so it has no
+    /// handle on the instance's query indexes.
+    pub const INDEX_UNAVAILABLE: &str = "INDEX_UNAVAILABLE";
+
     pub const PLUGIN_NOT_FOUND: &str = "PLUGIN_NOT_FOUND";
     pub const PLUGIN_LOAD_FAILED: &str = "PLUGIN_LOAD_FAILED";
     pub const PLUGIN_INSTALL_FAILED: &str = "PLUGIN_INSTALL_FAILED";
@@ -215,17 +249,30 @@ fn status_from_code(code: &str) -> StatusCode {
         | error_codes::INSTANCE_NOT_FOUND
         | error_codes::PLUGIN_NOT_FOUND
         | error_codes::PLUGIN_FILE_NOT_FOUND
-        | error_codes::PLUGIN_KIND_NOT_FOUND => StatusCode::NOT_FOUND,
+        | error_codes::PLUGIN_KIND_NOT_FOUND
+        | error_codes::CONFIG_REVISION_NOT_FOUND
+        | error_codes::STATE_STORE_NOT_FOUND
+        | error_codes::STATE_KEY_NOT_FOUND => StatusCode::NOT_FOUND,
+
+        error_codes::CONFIG_READ_ONLY
+        | error_codes::SERVER_LOCKED
+        | error_codes::DUPLICATE_RESOURCE
+        | error_codes::COMPONENT_RUNNING => StatusCode::CONFLICT,
 
-        error_codes::CONFIG_READ_ONLY | error_codes::DUPLICATE_RESOURCE => StatusCode::CONFLICT,
+        error_codes::PRECONDITION_FAILED => StatusCode::PRECONDITION_FAILED,
 
         error_codes::INVALID_REQUEST
         | error_codes::PLUGIN_INVALID_PATH
         | error_codes::PLUGIN_INVALID_CATEGORY => StatusCode::BAD_REQUEST,
 
-        // The server was not started with a plugins directory, so the
-        // requested plugin operation is unavailable on this instance.
-        error_codes::PLUGIN_NO_DIRECTORY => StatusCode::SERVICE_UNAVAILABLE,
+        // The server was not started with a plugins directory (or with config
+        // persistence, or did not build the instance), so the requested
+        // operation is unavailable.
+        error_codes::PLUGIN_NO_DIRECTORY
+        | error_codes::PERSISTENCE_DISABLED
+        | error_codes::STATE_STORE_UNAVAILABLE
+        | error_codes::WAL_UNAVAILABLE
+        | error_codes::INDEX_UNAVAILABLE => StatusCode::SERVICE_UNAVAILABLE,
 
         // Plugin install/search talk to an external OCI registry; surface
         // upstream failures as 502 Bad Gateway rather than 500.
@@ -240,6 +287,33 @@ fn status_from_code(code: &str) -> StatusCode {
     }
 }
 
+/// Reject a request unless the server's mode allows `operation`.
+///
+/// `action` completes "Cannot ..." in the message, e.g. `"create sources"`.
+/// The code is `CONFIG_READ_ONLY` in `readOnly` mode and `SERVER_LOCKED` in
+/// `locked` mode; the details say what the mode still accepts.
+pub fn ensure_mode_allows(
+    mode: ServerMode,
+    operation: ModeOperation,
+    action: &str,
+) -> Result<(), ErrorResponse> {
+    if mode.allows(operation) {
+        return Ok(());
+    }
+    let (code, name) = match mode {
+        ServerMode::Locked => (error_codes::SERVER_LOCKED, "locked"),
+        _ => (error_codes::CONFIG_READ_ONLY, "read-only"),
+    };
+    Err(
+        ErrorResponse::new(code, format!("Server is in {name} mode. Cannot {action}."))
+            .with_details(ErrorDetail {
+                component_type: None,
+                component_id: None,
+                technical_details: Some(format!("mode '{mode}': {}", mode.summary())),
+            }),
+    )
+}
+
 /// Convert DrasiError to ErrorResponse
 impl From<DrasiError> for ErrorResponse {
     fn from(err: DrasiError) -> Self {
@@ -471,12 +545,45 @@ mod tests {
             status_from_code(error_codes::CONFIG_READ_ONLY),
             StatusCode::CONFLICT
         );
+        assert_eq!(
+            status_from_code(error_codes::SERVER_LOCKED),
+            StatusCode::CONFLICT
+        );
         assert_eq!(
             status_from_code(error_codes::DUPLICATE_RESOURCE),
             StatusCode::CONFLICT
         );
     }
 
+    #[test]
+    fn test_ensure_mode_allows_explains_the_mode() {
+        assert!(ensure_mode_allows(
+            ServerMode::Locked,
+            ModeOperation::Lifecycle,
+            "start sources"
+        )
+        .is_ok());
+
+        let locked = ensure_mode_allows(
+            ServerMode::Locked,
+            ModeOperation::Configure,
+            "delete sources",
+        )
+        .unwrap_err();
+        assert_eq!(locked.code, error_codes::SERVER_LOCKED);
+        assert_eq!(
+            locked.message,
+            "Server is in locked mode. Cannot delete sources."
+        );
+        let details = locked.details.unwrap().technical_details.unwrap();
+        assert!(details.starts_with("mode 'locked'"), "{details}");
+
+        let read_only =
+            ensure_mode_allows(ServerMode::ReadOnly, ModeOperation::Plugins, "load plugins")
+                .unwrap_err();
+        assert_eq!(read_only.code, error_codes::CONFIG_READ_ONLY);
+    }
+
     #[test]
     fn test_status_from_code_bad_request() {
         assert_eq!(
@@ -702,6 +809,7 @@ mod tests {
             error_codes::REACTION_STOP_FAILED,
             error_codes::REACTION_DELETE_FAILED,
             error_codes::CONFIG_READ_ONLY,
+            error_codes::SERVER_LOCKED,
             error_codes::DUPLICATE_RESOURCE,
             error_codes::INVALID_REQUEST,
             error_codes::INTERNAL_ERROR,
@@ -20,16 +20,30 @@ use tokio::sync::RwLock;
 
 use super::persist_after_operation;
 use crate::api::models::ConfigValue;
-use crate::api::models::{BootstrapProviderConfig, BootstrapProviderRef};
-use crate::api::shared::error::{error_codes, ErrorDetail, ErrorResponse};
+use crate::api::models::{
+    ArchiveRetentionConfig, BootstrapProviderConfig, BootstrapProviderRef, WalRetentionConfig,
+};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorDetail, ErrorResponse};
 use crate::api::shared::extractor::ConfigBody;
 use crate::api::shared::responses::{ApiResponse, StatusResponse};
-use crate::config::{DrasiLibInstanceConfig, ReactionConfig, SourceConfig};
-use crate::factories::{create_reaction_locked, create_source_locked};
+use crate::archive_retention::{applicable_retention, ArchiveRetention};
+use crate::config::{
+    DrasiLibInstanceConfig, ModeOperation, ReactionConfig, SecretStoreConfig, ServerMode,
+    SourceConfig,
+};
+use crate::factories::{
+    create_reaction_locked, create_secret_store_from_registry, create_source_locked,
+    ConfigResolverContext,
+};
+use crate::index_provider::IndexDatabases;
 use crate::instance_paths::instance_storage_key;
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
 use crate::plugin_registry::PluginRegistry;
+use crate::secret_rotation::SecretRotationWatcher;
+use crate::source_wal::WalEventTimes;
+use crate::storage_usage::{IndexCountCache, InstanceStorage};
+use drasi_lib::state_store::{MemoryStateStoreProvider, StateStoreProvider};
 use drasi_lib::{ConfigurationSnapshot, DrasiLib};
 
 /// Request body for creating a new instance
@@ -49,6 +63,16 @@ pub struct CreateInstanceRequest {
     #[serde(default)]
     pub enable_archive: Option<bool>,
 
+    /// Age and version limits for the archive index. Requires
+    /// `persistIndex` and `enableArchive`.
+    #[serde(default)]
+    pub archive_retention: Option<ArchiveRetentionConfig>,
+
+    /// Events to keep in each source's WAL for replay after the queries
+    /// have processed them.
+    #[serde(default)]
+    pub wal_retention: Option<WalRetentionConfig>,
+
     /// Default capacity for priority queues (cascades to queries/reactions)
     #[serde(default)]
     pub default_priority_queue_capacity: Option<usize>,
@@ -56,25 +80,31 @@ pub struct CreateInstanceRequest {
     /// Default capacity for dispatch buffers (cascades to queries/reactions)
     #[serde(default)]
     pub default_dispatch_buffer_capacity: Option<usize>,
+
+    /// Secret store that resolves `Secret` references in this instance's
+    /// components. Other instances' secret stores are never consulted.
+    #[serde(default)]
+    pub secret_store: Option<SecretStoreConfig>,
 }
 
 /// Create a new DrasiLib instance
 pub async fn create_instance(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
     ConfigBody(request): ConfigBody<CreateInstanceRequest>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot create instances.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "create instances")?;
 
     let instance_id = request.id.clone();
     let persist_index = request.persist_index.unwrap_or(false);
     let enable_archive = request.enable_archive.unwrap_or(false);
+    if let Some(retention) = &request.archive_retention {
+        retention
+            .validate()
+            .map_err(|e| ErrorResponse::new(error_codes::INVALID_REQUEST, e))?;
+    }
 
     // Check if instance already exists
     if registry.contains(&instance_id).await {
@@ -101,21 +131,58 @@ pub async fn create_instance(
 
     // Register the persistent RocksDB index provider as the instance default
     // when requested.
-    if persist_index {
-        builder = crate::index_provider::apply_rocksdb_index(builder, &instance_id, enable_archive);
+    let index_databases = persist_index.then(IndexDatabases::new);
+    if let Some(databases) = &index_databases {
+        builder = crate::index_provider::apply_rocksdb_index(
+            builder,
+            &instance_id,
+            enable_archive,
+            databases.clone(),
+        );
     }
 
     // WAL provider for durable source event persistence
-    {
-        let wal_path = PathBuf::from(format!("./data/{safe_id}/wal"));
-        log::info!(
-            "Enabling WAL provider for instance '{}' at: {}",
-            instance_id,
-            wal_path.display()
-        );
-        let wal_provider = Arc::new(drasi_wal_redb::RedbWalProvider::new(&wal_path));
-        builder = builder.with_wal_provider(wal_provider);
-    }
+    let wal_path = PathBuf::from(format!("./data/{safe_id}/wal"));
+    log::info!(
+        "Enabling WAL provider for instance '{}' at: {}",
+        instance_id,
+        wal_path.display()
+    );
+    let wal_times = WalEventTimes::new();
+    let wal_provider = crate::source_wal::with_retention(
+        &instance_id,
+        Arc::new(drasi_wal_redb::RedbWalProvider::new(&wal_path)),
+        request.wal_retention.as_ref(),
+        &wal_times,
+    );
+    builder = builder.with_wal_provider(wal_provider.clone());
+
+    // Created explicitly, rather than left to DrasiLib, so the REST API can
+    // inspect it.
+    let state_store: Arc<dyn StateStoreProvider> = Arc::new(MemoryStateStoreProvider::new());
+    builder = builder.with_state_store_provider(state_store.clone());
+
+    let secret_store = match &request.secret_store {
+        Some(config) => {
+            let provider = create_secret_store_from_registry(&plugin_registry, config)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to create secret store for instance '{instance_id}': {e}");
+                    ErrorResponse::new(
+                        error_codes::INSTANCE_CREATE_FAILED,
+                        format!("Failed to create secret store: {e}"),
+                    )
+                    .with_details(ErrorDetail {
+                        component_type: Some("secretStore".to_string()),
+                        component_id: Some(config.kind.clone()),
+                        technical_details: Some(format!("{e:#}")),
+                    })
+                })?;
+            builder = builder.with_secret_store_provider(provider.clone());
+            Some(provider)
+        }
+        None => None,
+    };
 
     let core = builder.build().await.map_err(|e| {
         log::error!("Failed to create instance: {e}");
@@ -137,10 +204,58 @@ pub async fn create_instance(
     }
 
     // Add to registry
-    if let Err(e) = registry.add(instance_id.clone(), core).await {
+    if let Err(e) = registry.add(instance_id.clone(), core.clone()).await {
         log::error!("Failed to register instance: {e}");
         return Err(ErrorResponse::new(error_codes::INSTANCE_CREATE_FAILED, e));
     }
+    registry
+        .set_state_store(instance_id.clone(), state_store)
+        .await;
+    if let Some(provider) = secret_store {
+        ConfigResolverContext::global().set_instance_store(&instance_id, provider);
+    }
+    if let Some(interval) = request
+        .secret_store
+        .as_ref()
+        .and_then(|store| store.refresh_interval())
+    {
+        let watcher = SecretRotationWatcher::spawn(
+            &core,
+            &instance_id,
+            plugin_registry.clone(),
+            registry.revisions().clone(),
+            registry.secret_consumers().clone(),
+            registry.plugin_breakers().clone(),
+            interval,
+        );
+        registry
+            .set_secret_rotation_watcher(instance_id.clone(), Arc::new(watcher))
+            .await;
+    }
+    let retention = applicable_retention(
+        &instance_id,
+        persist_index,
+        enable_archive,
+        request.archive_retention.as_ref(),
+    );
+    if let (Some(retention), Some(databases)) = (retention, &index_databases) {
+        let retention = ArchiveRetention::spawn(&instance_id, retention, databases.clone());
+        registry
+            .set_archive_retention(instance_id.clone(), Arc::new(retention))
+            .await;
+    }
+    let storage = InstanceStorage {
+        index: index_databases,
+        wal: wal_provider,
+        wal_times,
+        wal_dir: wal_path,
+        wal_retention: request.wal_retention.clone(),
+        state_store_files: None,
+        index_counts: IndexCountCache::new(),
+    };
+    registry
+        .set_storage(instance_id.clone(), Arc::new(storage))
+        .await;
 
     log::info!("Instance '{instance_id}' created successfully");
 
@@ -150,8 +265,10 @@ pub async fn create_instance(
             id: ConfigValue::Static(instance_id.clone()),
             persist_index,
             enable_archive,
+            archive_retention: request.archive_retention.clone(),
+            wal_retention: request.wal_retention.clone(),
             state_store: None,
-            secret_store: None,
+            secret_store: request.secret_store.clone(),
             default_priority_queue_capacity: request
                 .default_priority_queue_capacity
                 .map(ConfigValue::Static),
@@ -211,18 +328,13 @@ pub struct CloneInstanceResponse {
 /// On failure, already-created components are rolled back.
 pub async fn clone_instance(
     registry: InstanceRegistry,
-    read_only: Arc<bool>,
+    mode: ServerMode,
     plugin_registry: Arc<RwLock<PluginRegistry>>,
     config_persistence: Option<Arc<ConfigPersistence>>,
     target_instance_id: &str,
     source_instance_id: &str,
 ) -> Result<Json<ApiResponse<CloneInstanceResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot clone instances.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "clone instances")?;
 
     // Get source instance and take snapshot
     let source_core = registry.get(source_instance_id).await.ok_or_else(|| {
@@ -277,24 +389,33 @@ pub async fn clone_instance(
             auto_start: false,
             bootstrap_provider,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: properties_json,
         };
 
-        let (source, plugin_meta) =
-            match create_source_locked(&plugin_registry, source_config.clone()).await {
-                Ok(s) => s,
-                Err(e) => {
-                    log::error!("Clone: failed to create source '{}': {e}", src_snap.id);
-                    let rb = rollback_sources(&target_core, &sources_created).await;
-                    return Err(clone_error(
-                        error_codes::SOURCE_CREATE_FAILED,
-                        format!("Failed to create source '{}': {e}", src_snap.id),
-                        "source",
-                        &src_snap.id,
-                        rb,
-                    ));
-                }
-            };
+        let (source, plugin_meta) = match create_source_locked(
+            &plugin_registry,
+            target_instance_id,
+            registry.secret_consumers(),
+            registry.plugin_breakers(),
+            source_config.clone(),
+        )
+        .await
+        {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Clone: failed to create source '{}': {e}", src_snap.id);
+                let rb = rollback_sources(&target_core, &sources_created).await;
+                return Err(clone_error(
+                    error_codes::SOURCE_CREATE_FAILED,
+                    format!("Failed to create source '{}': {e}", src_snap.id),
+                    "source",
+                    &src_snap.id,
+                    rb,
+                ));
+            }
+        };
 
         if let Err(e) = target_core
             .add_source_with_metadata(source, plugin_meta)
@@ -367,26 +488,35 @@ pub async fn clone_instance(
             queries: rx_snap.queries.clone(),
             auto_start: false,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: properties_json,
         };
 
-        let (reaction, plugin_meta) =
-            match create_reaction_locked(&plugin_registry, reaction_config.clone()).await {
-                Ok(r) => r,
-                Err(e) => {
-                    log::error!("Clone: failed to create reaction '{}': {e}", rx_snap.id);
-                    let mut rb = rollback_reactions(&target_core, &reactions_created).await;
-                    rb.extend(rollback_queries(&target_core, &queries_created).await);
-                    rb.extend(rollback_sources(&target_core, &sources_created).await);
-                    return Err(clone_error(
-                        error_codes::REACTION_CREATE_FAILED,
-                        format!("Failed to create reaction '{}': {e}", rx_snap.id),
-                        "reaction",
-                        &rx_snap.id,
-                        rb,
-                    ));
-                }
-            };
+        let (reaction, plugin_meta) = match create_reaction_locked(
+            &plugin_registry,
+            target_instance_id,
+            registry.secret_consumers(),
+            registry.plugin_breakers(),
+            reaction_config.clone(),
+        )
+        .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Clone: failed to create reaction '{}': {e}", rx_snap.id);
+                let mut rb = rollback_reactions(&target_core, &reactions_created).await;
+                rb.extend(rollback_queries(&target_core, &queries_created).await);
+                rb.extend(rollback_sources(&target_core, &sources_created).await);
+                return Err(clone_error(
+                    error_codes::REACTION_CREATE_FAILED,
+                    format!("Failed to create reaction '{}': {e}", rx_snap.id),
+                    "reaction",
+                    &rx_snap.id,
+                    rb,
+                ));
+            }
+        };
 
         if let Err(e) = target_core
             .add_reaction_with_metadata(reaction, plugin_meta)
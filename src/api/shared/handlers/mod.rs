@@ -40,9 +40,11 @@ use super::error::{error_codes, ErrorDetail, ErrorResponse};
 use super::responses::{
     ApiResponse, ApiVersionsResponse, ComponentLinks, HealthResponse, InstanceListItem,
 };
+use crate::api::models::{ComponentEventDto, ComponentTypeDto};
+use crate::component_revisions::ComponentKind;
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
-use drasi_lib::DrasiLib;
+use drasi_lib::{ComponentEvent, DrasiLib};
 
 /// The URL path prefix for the current API version (e.g., `/api/v1`).
 ///
@@ -154,6 +156,28 @@ pub(crate) async fn sse_event_async<T: Serialize>(payload: T) -> Option<Result<E
     sse_event(payload)
 }
 
+/// Returns a converter from component events to DTOs that carry the
+/// component's current revision.
+pub(crate) fn event_annotator(
+    registry: &InstanceRegistry,
+    instance_id: String,
+) -> impl Fn(ComponentEvent) -> ComponentEventDto + Clone + Send + Sync + 'static {
+    let revisions = registry.revisions().clone();
+    move |event| {
+        let mut dto = ComponentEventDto::from(event);
+        let kind = match dto.component_type {
+            ComponentTypeDto::Source => Some(ComponentKind::Source),
+            ComponentTypeDto::Query => Some(ComponentKind::Query),
+            ComponentTypeDto::Reaction => Some(ComponentKind::Reaction),
+            ComponentTypeDto::BootstrapProvider | ComponentTypeDto::IdentityProvider => None,
+        };
+        if let Some(kind) = kind {
+            dto.revision = Some(revisions.component(&instance_id, kind, &dto.component_id));
+        }
+        dto
+    }
+}
+
 /// Helper to persist configuration after a successful in-memory mutation.
 ///
 /// **Important contract:** the in-memory state has already been mutated
@@ -178,7 +202,7 @@ pub async fn persist_after_operation(
     let Some(persistence) = config_persistence else {
         return Ok(());
     };
-    match persistence.save().await {
+    match persistence.save_for(operation).await {
         Ok(()) => Ok(()),
         Err(e) => {
             log::error!("Failed to persist configuration after {operation}: {e}");
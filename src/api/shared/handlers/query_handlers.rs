@@ -23,14 +23,16 @@ use std::convert::Infallible;
 use std::sync::Arc;
 
 use super::{
-    apply_limit, component_links, persist_after_operation, sse_event_async, ApiPrefix,
-    ComponentViewQuery, ObservabilityQuery,
+    apply_limit, component_links, event_annotator, persist_after_operation, sse_event_async,
+    ApiPrefix, ComponentViewQuery, ObservabilityQuery,
 };
 use crate::api::mappings::{DtoMapper, QueryConfigMapper};
 use crate::api::models::{ComponentEventDto, LogMessageDto, QueryConfigDto};
-use crate::api::shared::error::{error_codes, ErrorResponse};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorResponse};
 use crate::api::shared::extractor::ConfigBody;
 use crate::api::shared::responses::{ApiResponse, ComponentListItem, StatusResponse};
+use crate::config::{ModeOperation, ServerMode};
+use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
 use drasi_lib::{channels::ComponentStatus, queries::LabelExtractor};
 use drasi_reaction_application::subscription::SubscriptionOptions;
@@ -75,17 +77,12 @@ pub async fn list_queries(
 /// Create a new query
 pub async fn create_query(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(_instance_id): Extension<String>,
     ConfigBody(config_dto): ConfigBody<QueryConfigDto>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot create queries.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "create queries")?;
 
     let query_id = config_dto.id.clone();
 
@@ -217,6 +214,8 @@ pub async fn get_query(
 /// Get query lifecycle events (snapshot).
 pub async fn get_query_events(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(instance_id): Extension<String>,
     Path(id): Path<String>,
     Query(query): Query<ObservabilityQuery>,
 ) -> Result<Json<ApiResponse<Vec<ComponentEventDto>>>, ErrorResponse> {
@@ -228,7 +227,7 @@ pub async fn get_query_events(
         .await
         .map_err(ErrorResponse::from)?;
     let collected = events
-        .map(ComponentEventDto::from)
+        .map(event_annotator(&registry, instance_id))
         .collect::<Vec<_>>()
         .await;
     let data = apply_limit(collected, query.limit);
@@ -238,6 +237,8 @@ pub async fn get_query_events(
 /// Stream query lifecycle events as SSE.
 pub async fn stream_query_events(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(instance_id): Extension<String>,
     Path(id): Path<String>,
 ) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>>, ErrorResponse> {
     core.get_query_info(&id)
@@ -247,17 +248,19 @@ pub async fn stream_query_events(
         .subscribe_query_events(&id)
         .await
         .map_err(ErrorResponse::from)?;
+    let annotate = event_annotator(&registry, instance_id);
     let history_stream =
-        stream::iter(history.into_iter().map(ComponentEventDto::from)).filter_map(sse_event_async);
+        stream::iter(history.into_iter().map(annotate.clone())).filter_map(sse_event_async);
     let live_stream = stream::unfold(receiver, |mut receiver| async move {
         loop {
             match receiver.recv().await {
-                Ok(event) => return Some((ComponentEventDto::from(event), receiver)),
+                Ok(event) => return Some((event, receiver)),
                 Err(broadcast::error::RecvError::Closed) => return None,
                 Err(broadcast::error::RecvError::Lagged(_)) => continue,
             }
         }
     })
+    .map(annotate)
     .filter_map(sse_event_async);
     let stream = history_stream.chain(live_stream);
     Ok(Sse::new(stream))
@@ -314,17 +317,12 @@ pub async fn stream_query_logs(
 /// Delete a query
 pub async fn delete_query(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(_instance_id): Extension<String>,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot delete queries.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "delete queries")?;
 
     match core.remove_query(&id).await {
         Ok(_) => {
@@ -347,8 +345,10 @@ pub async fn delete_query(
 /// Start a query
 pub async fn start_query(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(mode): Extension<ServerMode>,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "start queries")?;
     match core.start_query(&id).await {
         Ok(_) => Ok(Json(ApiResponse::success(StatusResponse {
             message: "Query started successfully".to_string(),
@@ -360,8 +360,10 @@ pub async fn start_query(
 /// Stop a query
 pub async fn stop_query(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(mode): Extension<ServerMode>,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "stop queries")?;
     match core.stop_query(&id).await {
         Ok(_) => Ok(Json(ApiResponse::success(StatusResponse {
             message: "Query stopped successfully".to_string(),
@@ -24,15 +24,16 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use super::{
-    apply_limit, component_links, persist_after_operation, sse_event_async, ApiPrefix,
-    ComponentViewQuery, ObservabilityQuery,
+    apply_limit, component_links, event_annotator, persist_after_operation, sse_event_async,
+    ApiPrefix, ComponentViewQuery, ObservabilityQuery,
 };
 use crate::api::models::{ComponentEventDto, LogMessageDto};
-use crate::api::shared::error::{error_codes, ErrorResponse};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorResponse};
 use crate::api::shared::extractor::ConfigBody;
 use crate::api::shared::responses::{ApiResponse, ComponentListItem, StatusResponse};
-use crate::config::ReactionConfig;
+use crate::config::{ModeOperation, ReactionConfig, ServerMode};
 use crate::factories::create_reaction_locked;
+use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
 use crate::plugin_registry::PluginRegistry;
 use drasi_lib::channels::ComponentStatus;
@@ -75,18 +76,14 @@ pub async fn list_reactions(
 /// Create a new reaction
 pub async fn create_reaction_handler(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(instance_id): Extension<String>,
     Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+    Extension(instance_registry): Extension<InstanceRegistry>,
     ConfigBody(config_json): ConfigBody<serde_json::Value>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot create reactions.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "create reactions")?;
 
     let config: ReactionConfig = serde_json::from_value(config_json).map_err(|e| {
         log::error!("Failed to parse reaction config: {e}");
@@ -99,20 +96,34 @@ pub async fn create_reaction_handler(
     let reaction_id = config.id().to_string();
     let auto_start = config.auto_start();
 
-    let (reaction, plugin_meta) = create_reaction_locked(&plugin_registry, config.clone())
-        .await
-        .map_err(|e| {
-            log::error!("Failed to create reaction instance: {e}");
-            ErrorResponse::new(
-                error_codes::REACTION_CREATE_FAILED,
-                format!("Failed to create reaction: {e}"),
-            )
-        })?;
+    let (reaction, plugin_meta) = create_reaction_locked(
+        &plugin_registry,
+        &instance_id,
+        instance_registry.secret_consumers(),
+        instance_registry.plugin_breakers(),
+        config.clone(),
+    )
+    .await
+    .map_err(|e| {
+        log::error!("Failed to create reaction instance: {e}");
+        ErrorResponse::new(
+            error_codes::REACTION_CREATE_FAILED,
+            format!("Failed to create reaction: {e}"),
+        )
+    })?;
 
     match core.add_reaction_with_metadata(reaction, plugin_meta).await {
         Ok(_) => {
             log::info!("Reaction '{reaction_id}' created successfully");
 
+            instance_registry
+                .set_reaction_restart_policy(
+                    &instance_id,
+                    &reaction_id,
+                    config.restart_policy().cloned(),
+                )
+                .await;
+
             if auto_start {
                 if let Err(e) = core.start_reaction(&reaction_id).await {
                     log::warn!("Failed to auto-start reaction '{reaction_id}': {e}");
@@ -153,21 +164,18 @@ pub async fn create_reaction_handler(
 }
 
 /// Upsert a reaction (create or update)
+#[allow(clippy::too_many_arguments)]
 pub async fn upsert_reaction_handler(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(instance_id): Extension<String>,
     Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+    Extension(instance_registry): Extension<InstanceRegistry>,
     Path(path_id): Path<String>,
     ConfigBody(config_json): ConfigBody<serde_json::Value>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot create or update reactions.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "create or update reactions")?;
 
     let config: ReactionConfig = serde_json::from_value(config_json).map_err(|e| {
         log::error!("Failed to parse reaction config: {e}");
@@ -195,15 +203,21 @@ pub async fn upsert_reaction_handler(
 
     if exists {
         // Create a new reaction instance and use update_reaction to replace in place
-        let (new_reaction, _meta) = create_reaction_locked(&plugin_registry, config.clone())
-            .await
-            .map_err(|e| {
-                log::error!("Failed to create reaction instance for update: {e}");
-                ErrorResponse::new(
-                    error_codes::REACTION_CREATE_FAILED,
-                    format!("Failed to create reaction for update: {e}"),
-                )
-            })?;
+        let (new_reaction, _meta) = create_reaction_locked(
+            &plugin_registry,
+            &instance_id,
+            instance_registry.secret_consumers(),
+            instance_registry.plugin_breakers(),
+            config.clone(),
+        )
+        .await
+        .map_err(|e| {
+            log::error!("Failed to create reaction instance for update: {e}");
+            ErrorResponse::new(
+                error_codes::REACTION_CREATE_FAILED,
+                format!("Failed to create reaction for update: {e}"),
+            )
+        })?;
         if let Err(e) = core.update_reaction(&reaction_id, new_reaction).await {
             log::error!("Failed to update reaction '{reaction_id}': {e}");
             return Err(ErrorResponse::new(
@@ -214,6 +228,14 @@ pub async fn upsert_reaction_handler(
 
         log::info!("Reaction '{reaction_id}' updated successfully");
 
+        instance_registry
+            .set_reaction_restart_policy(
+                &instance_id,
+                &reaction_id,
+                config.restart_policy().cloned(),
+            )
+            .await;
+
         if let Some(p) = &config_persistence {
             p.register_reaction_identity_provider(
                 &instance_id,
@@ -230,20 +252,34 @@ pub async fn upsert_reaction_handler(
         })));
     }
 
-    let (reaction, plugin_meta) = create_reaction_locked(&plugin_registry, config.clone())
-        .await
-        .map_err(|e| {
-            log::error!("Failed to create reaction instance: {e}");
-            ErrorResponse::new(
-                error_codes::REACTION_CREATE_FAILED,
-                format!("Failed to create reaction: {e}"),
-            )
-        })?;
+    let (reaction, plugin_meta) = create_reaction_locked(
+        &plugin_registry,
+        &instance_id,
+        instance_registry.secret_consumers(),
+        instance_registry.plugin_breakers(),
+        config.clone(),
+    )
+    .await
+    .map_err(|e| {
+        log::error!("Failed to create reaction instance: {e}");
+        ErrorResponse::new(
+            error_codes::REACTION_CREATE_FAILED,
+            format!("Failed to create reaction: {e}"),
+        )
+    })?;
 
     match core.add_reaction_with_metadata(reaction, plugin_meta).await {
         Ok(_) => {
             log::info!("Reaction '{reaction_id}' created successfully");
 
+            instance_registry
+                .set_reaction_restart_policy(
+                    &instance_id,
+                    &reaction_id,
+                    config.restart_policy().cloned(),
+                )
+                .await;
+
             if auto_start {
                 if let Err(e) = core.start_reaction(&reaction_id).await {
                     log::warn!("Failed to auto-start reaction '{reaction_id}': {e}");
@@ -328,6 +364,8 @@ pub async fn get_reaction(
 /// Get reaction lifecycle events (snapshot).
 pub async fn get_reaction_events(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(instance_id): Extension<String>,
     Path(id): Path<String>,
     Query(query): Query<ObservabilityQuery>,
 ) -> Result<Json<ApiResponse<Vec<ComponentEventDto>>>, ErrorResponse> {
@@ -339,7 +377,7 @@ pub async fn get_reaction_events(
         .await
         .map_err(ErrorResponse::from)?;
     let collected = events
-        .map(ComponentEventDto::from)
+        .map(event_annotator(&registry, instance_id))
         .collect::<Vec<_>>()
         .await;
     let data = apply_limit(collected, query.limit);
@@ -349,6 +387,8 @@ pub async fn get_reaction_events(
 /// Stream reaction lifecycle events as SSE.
 pub async fn stream_reaction_events(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(instance_id): Extension<String>,
     Path(id): Path<String>,
 ) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>>, ErrorResponse> {
     core.get_reaction_info(&id)
@@ -358,17 +398,19 @@ pub async fn stream_reaction_events(
         .subscribe_reaction_events(&id)
         .await
         .map_err(ErrorResponse::from)?;
+    let annotate = event_annotator(&registry, instance_id);
     let history_stream =
-        stream::iter(history.into_iter().map(ComponentEventDto::from)).filter_map(sse_event_async);
+        stream::iter(history.into_iter().map(annotate.clone())).filter_map(sse_event_async);
     let live_stream = stream::unfold(receiver, |mut receiver| async move {
         loop {
             match receiver.recv().await {
-                Ok(event) => return Some((ComponentEventDto::from(event), receiver)),
+                Ok(event) => return Some((event, receiver)),
                 Err(broadcast::error::RecvError::Closed) => return None,
                 Err(broadcast::error::RecvError::Lagged(_)) => continue,
             }
         }
     })
+    .map(annotate)
     .filter_map(sse_event_async);
     let stream = history_stream.chain(live_stream);
     Ok(Sse::new(stream))
@@ -425,20 +467,19 @@ pub async fn stream_reaction_logs(
 /// Delete a reaction
 pub async fn delete_reaction(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(instance_id): Extension<String>,
+    Extension(instance_registry): Extension<InstanceRegistry>,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot delete reactions.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "delete reactions")?;
 
     match core.remove_reaction(&id, true).await {
         Ok(_) => {
+            instance_registry
+                .set_reaction_restart_policy(&instance_id, &id, None)
+                .await;
             if let Some(p) = &config_persistence {
                 p.unregister_reaction_identity_provider(&instance_id, &id)
                     .await;
@@ -462,8 +503,10 @@ pub async fn delete_reaction(
 /// Start a reaction
 pub async fn start_reaction(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(mode): Extension<ServerMode>,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "start reactions")?;
     match core.start_reaction(&id).await {
         Ok(_) => Ok(Json(ApiResponse::success(StatusResponse {
             message: "Reaction started successfully".to_string(),
@@ -475,8 +518,10 @@ pub async fn start_reaction(
 /// Stop a reaction
 pub async fn stop_reaction(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(mode): Extension<ServerMode>,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "stop reactions")?;
     match core.stop_reaction(&id).await {
         Ok(_) => Ok(Json(ApiResponse::success(StatusResponse {
             message: "Reaction stopped successfully".to_string(),
@@ -492,18 +537,21 @@ pub async fn stop_reaction(
 /// removals) as SSE events. Used by the UI to reactively update without polling.
 pub async fn stream_all_component_events(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(instance_id): Extension<String>,
 ) -> Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>> {
     let receiver = core.subscribe_all_component_events();
 
     let live_stream = stream::unfold(receiver, |mut receiver| async move {
         loop {
             match receiver.recv().await {
-                Ok(event) => return Some((ComponentEventDto::from(event), receiver)),
+                Ok(event) => return Some((event, receiver)),
                 Err(broadcast::error::RecvError::Closed) => return None,
                 Err(broadcast::error::RecvError::Lagged(_)) => continue,
             }
         }
     })
+    .map(event_annotator(&registry, instance_id))
     .filter_map(sse_event_async);
 
     Sse::new(live_stream).keep_alive(
@@ -24,14 +24,14 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use super::{
-    apply_limit, component_links, persist_after_operation, sse_event_async, ApiPrefix,
-    ComponentViewQuery, ObservabilityQuery,
+    apply_limit, component_links, event_annotator, persist_after_operation, sse_event_async,
+    ApiPrefix, ComponentViewQuery, ObservabilityQuery,
 };
 use crate::api::models::{ComponentEventDto, LogMessageDto};
-use crate::api::shared::error::{error_codes, ErrorDetail, ErrorResponse};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorDetail, ErrorResponse};
 use crate::api::shared::extractor::ConfigBody;
 use crate::api::shared::responses::{ApiResponse, ComponentListItem, StatusResponse};
-use crate::config::SourceConfig;
+use crate::config::{ModeOperation, ServerMode, SourceConfig};
 use crate::factories::{create_source_locked, resolve_source_bootstrap_provider};
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
@@ -110,19 +110,14 @@ async fn resolve_source_bootstrap_ref(
 /// Create a new source
 pub async fn create_source_handler(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(instance_id): Extension<String>,
     Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
     Extension(instance_registry): Extension<InstanceRegistry>,
     ConfigBody(config_json): ConfigBody<serde_json::Value>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot create sources.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "create sources")?;
 
     let config: SourceConfig = serde_json::from_value(config_json).map_err(|e| {
         log::error!("Failed to parse source config: {e}");
@@ -141,20 +136,34 @@ pub async fn create_source_handler(
     let create_config =
         resolve_source_bootstrap_ref(&instance_registry, &instance_id, &config).await?;
 
-    let (source, plugin_meta) = create_source_locked(&plugin_registry, create_config)
-        .await
-        .map_err(|e| {
-            log::error!("Failed to create source instance: {e}");
-            ErrorResponse::new(
-                error_codes::SOURCE_CREATE_FAILED,
-                format!("Failed to create source: {e}"),
-            )
-        })?;
+    let (source, plugin_meta) = create_source_locked(
+        &plugin_registry,
+        &instance_id,
+        instance_registry.secret_consumers(),
+        instance_registry.plugin_breakers(),
+        create_config,
+    )
+    .await
+    .map_err(|e| {
+        log::error!("Failed to create source instance: {e}");
+        ErrorResponse::new(
+            error_codes::SOURCE_CREATE_FAILED,
+            format!("Failed to create source: {e}"),
+        )
+    })?;
 
     match core.add_source_with_metadata(source, plugin_meta).await {
         Ok(_) => {
             log::info!("Source '{source_id}' created successfully");
 
+            instance_registry
+                .set_source_restart_policy(
+                    &instance_id,
+                    &source_id,
+                    config.restart_policy().cloned(),
+                )
+                .await;
+
             if auto_start {
                 if let Err(e) = core.start_source(&source_id).await {
                     log::warn!("Failed to auto-start source '{source_id}': {e}");
@@ -209,7 +218,7 @@ pub async fn create_source_handler(
 #[allow(clippy::too_many_arguments)]
 pub async fn upsert_source_handler(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(instance_id): Extension<String>,
     Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
@@ -217,12 +226,7 @@ pub async fn upsert_source_handler(
     Path(path_id): Path<String>,
     ConfigBody(config_json): ConfigBody<serde_json::Value>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot create or update sources.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "create or update sources")?;
 
     let config: SourceConfig = serde_json::from_value(config_json).map_err(|e| {
         log::error!("Failed to parse source config: {e}");
@@ -255,15 +259,21 @@ pub async fn upsert_source_handler(
 
     if exists {
         // Create a new source instance and use update_source to replace in place
-        let (new_source, _meta) = create_source_locked(&plugin_registry, create_config)
-            .await
-            .map_err(|e| {
-                log::error!("Failed to create source instance for update: {e}");
-                ErrorResponse::new(
-                    error_codes::SOURCE_CREATE_FAILED,
-                    format!("Failed to create source for update: {e}"),
-                )
-            })?;
+        let (new_source, _meta) = create_source_locked(
+            &plugin_registry,
+            &instance_id,
+            instance_registry.secret_consumers(),
+            instance_registry.plugin_breakers(),
+            create_config,
+        )
+        .await
+        .map_err(|e| {
+            log::error!("Failed to create source instance for update: {e}");
+            ErrorResponse::new(
+                error_codes::SOURCE_CREATE_FAILED,
+                format!("Failed to create source for update: {e}"),
+            )
+        })?;
         if let Err(e) = core.update_source(&source_id, new_source).await {
             log::error!("Failed to update source '{source_id}': {e}");
             return Err(ErrorResponse::new(
@@ -274,6 +284,10 @@ pub async fn upsert_source_handler(
 
         log::info!("Source '{source_id}' updated successfully");
 
+        instance_registry
+            .set_source_restart_policy(&instance_id, &source_id, config.restart_policy().cloned())
+            .await;
+
         if let Some(p) = &config_persistence {
             p.register_source_identity_provider(
                 &instance_id,
@@ -296,20 +310,34 @@ pub async fn upsert_source_handler(
         })));
     }
 
-    let (source, plugin_meta) = create_source_locked(&plugin_registry, create_config)
-        .await
-        .map_err(|e| {
-            log::error!("Failed to create source instance: {e}");
-            ErrorResponse::new(
-                error_codes::SOURCE_CREATE_FAILED,
-                format!("Failed to create source: {e}"),
-            )
-        })?;
+    let (source, plugin_meta) = create_source_locked(
+        &plugin_registry,
+        &instance_id,
+        instance_registry.secret_consumers(),
+        instance_registry.plugin_breakers(),
+        create_config,
+    )
+    .await
+    .map_err(|e| {
+        log::error!("Failed to create source instance: {e}");
+        ErrorResponse::new(
+            error_codes::SOURCE_CREATE_FAILED,
+            format!("Failed to create source: {e}"),
+        )
+    })?;
 
     match core.add_source_with_metadata(source, plugin_meta).await {
         Ok(_) => {
             log::info!("Source '{source_id}' created successfully");
 
+            instance_registry
+                .set_source_restart_policy(
+                    &instance_id,
+                    &source_id,
+                    config.restart_policy().cloned(),
+                )
+                .await;
+
             if auto_start {
                 if let Err(e) = core.start_source(&source_id).await {
                     log::warn!("Failed to auto-start source '{source_id}': {e}");
@@ -391,6 +419,8 @@ pub async fn get_source(
 /// Get source lifecycle events (snapshot).
 pub async fn get_source_events(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(instance_id): Extension<String>,
     Path(id): Path<String>,
     Query(query): Query<ObservabilityQuery>,
 ) -> Result<Json<ApiResponse<Vec<ComponentEventDto>>>, ErrorResponse> {
@@ -402,7 +432,7 @@ pub async fn get_source_events(
         .await
         .map_err(ErrorResponse::from)?;
     let collected = events
-        .map(ComponentEventDto::from)
+        .map(event_annotator(&registry, instance_id))
         .collect::<Vec<_>>()
         .await;
     let data = apply_limit(collected, query.limit);
@@ -412,6 +442,8 @@ pub async fn get_source_events(
 /// Stream source lifecycle events as SSE.
 pub async fn stream_source_events(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(instance_id): Extension<String>,
     Path(id): Path<String>,
 ) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>>, ErrorResponse> {
     core.get_source_info(&id)
@@ -421,17 +453,19 @@ pub async fn stream_source_events(
         .subscribe_source_events(&id)
         .await
         .map_err(ErrorResponse::from)?;
+    let annotate = event_annotator(&registry, instance_id);
     let history_stream =
-        stream::iter(history.into_iter().map(ComponentEventDto::from)).filter_map(sse_event_async);
+        stream::iter(history.into_iter().map(annotate.clone())).filter_map(sse_event_async);
     let live_stream = stream::unfold(receiver, |mut receiver| async move {
         loop {
             match receiver.recv().await {
-                Ok(event) => return Some((ComponentEventDto::from(event), receiver)),
+                Ok(event) => return Some((event, receiver)),
                 Err(broadcast::error::RecvError::Closed) => return None,
                 Err(broadcast::error::RecvError::Lagged(_)) => continue,
             }
         }
     })
+    .map(annotate)
     .filter_map(sse_event_async);
     let stream = history_stream.chain(live_stream);
     Ok(Sse::new(stream))
@@ -488,20 +522,19 @@ pub async fn stream_source_logs(
 /// Delete a source
 pub async fn delete_source(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(instance_id): Extension<String>,
+    Extension(instance_registry): Extension<InstanceRegistry>,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot delete sources.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "delete sources")?;
 
     match core.remove_source(&id, true).await {
         Ok(_) => {
+            instance_registry
+                .set_source_restart_policy(&instance_id, &id, None)
+                .await;
             if let Some(p) = &config_persistence {
                 p.unregister_source_identity_provider(&instance_id, &id)
                     .await;
@@ -527,8 +560,10 @@ pub async fn delete_source(
 /// Start a source
 pub async fn start_source(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(mode): Extension<ServerMode>,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "start sources")?;
     match core.start_source(&id).await {
         Ok(_) => Ok(Json(ApiResponse::success(StatusResponse {
             message: "Source started successfully".to_string(),
@@ -540,8 +575,10 @@ pub async fn start_source(
 /// Stop a source
 pub async fn stop_source(
     Extension(core): Extension<Arc<drasi_lib::DrasiLib>>,
+    Extension(mode): Extension<ServerMode>,
     Path(id): Path<String>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "stop sources")?;
     match core.stop_source(&id).await {
         Ok(_) => Ok(Json(ApiResponse::success(StatusResponse {
             message: "Source stopped successfully".to_string(),
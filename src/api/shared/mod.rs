@@ -17,11 +17,21 @@
 //! This module contains error types, common response structures, and utilities
 //! that are shared between API versions (v1, v2, etc.).
 
+pub mod apply;
+pub mod archive;
+pub mod backup;
+pub mod config_export;
+pub mod config_revisions;
 pub mod error;
 pub mod extractor;
 pub mod handlers;
+pub mod preconditions;
+pub mod rebootstrap;
 pub mod responses;
 pub mod solutions;
+pub mod state_stores;
+pub mod storage;
+pub mod wal;
 
 pub use error::*;
 pub use extractor::*;
@@ -0,0 +1,330 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `ETag` / `If-Match` handling for component and instance routes.
+//!
+//! Applied as a route layer, so it sees the matched route and its path
+//! parameters:
+//!
+//! - `GET /sources/{id}` (and queries, reactions) and `GET /snapshot`
+//!   return the component's or instance's revision as a strong `ETag`.
+//! - `PUT` / `DELETE` on a component and `POST /apply` on an instance are
+//!   rejected with `412 Precondition Failed` when an `If-Match` header does
+//!   not match the current revision. A successful write moves the target to
+//!   a new revision, returned as the `ETag` of the response.
+//! - Other successful writes to an instance (creating components, deploying
+//!   solutions, cloning) move the instance revision.
+//!
+//! Writes to one instance are serialized while this layer checks and
+//! applies them, so two clients sending the same `If-Match` cannot both
+//! succeed. Starting, stopping, re-bootstrapping and replaying a component
+//! are serialized with them too, without moving any revision, so a handler
+//! holding the instance's write lock knows no component changes status
+//! under it.
+
+use axum::extract::{Extension, MatchedPath, RawPathParams, Request};
+use axum::http::header::{ETAG, IF_MATCH};
+use axum::http::{HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::api::shared::error::{error_codes, ErrorResponse};
+use crate::component_revisions::{ComponentKind, ComponentRevisions};
+use crate::instance_registry::InstanceRegistry;
+
+/// What a request addresses, as far as revisions are concerned.
+#[derive(Debug, PartialEq, Eq)]
+enum Target {
+    /// A single source, query, or reaction
+    Component {
+        instance_id: String,
+        kind: ComponentKind,
+        id: String,
+    },
+    /// The instance as a whole (`/snapshot`, `/apply`)
+    Instance { instance_id: String },
+    /// A route that adds components to the instance (`POST /sources`,
+    /// `/solutions`, `/clone`, ...)
+    Collection { instance_id: String },
+    /// Starting, stopping, re-bootstrapping or replaying a component:
+    /// serialized with writes, but not a change to any revision
+    Lifecycle { instance_id: String },
+}
+
+impl Target {
+    /// Classify a matched route such as `/instances/:instanceId/sources/:id`.
+    /// `default_instance` is the instance injected for the convenience
+    /// routes, which have no `instanceId` parameter.
+    fn resolve(
+        matched_path: &str,
+        params: &[(&str, &str)],
+        default_instance: Option<&String>,
+    ) -> Option<Self> {
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        };
+        let instance_id = param("instanceId").or_else(|| default_instance.cloned())?;
+
+        let segments: Vec<&str> = matched_path.trim_end_matches('/').split('/').collect();
+        match segments.as_slice() {
+            [.., collection, ":id"] => Some(Self::Component {
+                instance_id,
+                kind: ComponentKind::from_collection(collection)?,
+                id: param("id")?,
+            }),
+            [.., collection, ":id", "start" | "stop" | "rebootstrap" | "replay"] => {
+                ComponentKind::from_collection(collection)?;
+                Some(Self::Lifecycle { instance_id })
+            }
+            // Saving a solution template reads the instance; it does not change it.
+            [.., "catalog", "solutions"] => None,
+            [.., "snapshot" | "apply"] => Some(Self::Instance { instance_id }),
+            [.., "sources" | "queries" | "reactions" | "solutions" | "clone"] => {
+                Some(Self::Collection { instance_id })
+            }
+            _ => None,
+        }
+    }
+
+    fn instance_id(&self) -> &str {
+        match self {
+            Self::Component { instance_id, .. }
+            | Self::Instance { instance_id }
+            | Self::Collection { instance_id }
+            | Self::Lifecycle { instance_id } => instance_id,
+        }
+    }
+
+    fn revision(&self, revisions: &ComponentRevisions) -> u64 {
+        match self {
+            Self::Component {
+                instance_id,
+                kind,
+                id,
+            } => revisions.component(instance_id, *kind, id),
+            Self::Instance { instance_id }
+            | Self::Collection { instance_id }
+            | Self::Lifecycle { instance_id } => revisions.instance(instance_id),
+        }
+    }
+
+    /// Current revision, or `None` if the target does not exist.
+    async fn current_revision(&self, registry: &InstanceRegistry) -> Option<u64> {
+        let core = registry.get(self.instance_id()).await?;
+        if let Self::Component { kind, id, .. } = self {
+            let exists = match kind {
+                ComponentKind::Source => core.get_source_status(id).await.is_ok(),
+                ComponentKind::Query => core.get_query_status(id).await.is_ok(),
+                ComponentKind::Reaction => core.get_reaction_status(id).await.is_ok(),
+            };
+            if !exists {
+                return None;
+            }
+        }
+        Some(self.revision(registry.revisions()))
+    }
+}
+
+/// Strong `ETag` value for a revision.
+pub fn etag(revision: u64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{revision}\""))
+        .unwrap_or_else(|_| HeaderValue::from_static("\"0\""))
+}
+
+/// Whether an `If-Match` header value (a list of entity tags, or `*`) matches
+/// a resource at `current`. Nothing matches a resource that does not exist,
+/// and weak tags never match.
+fn if_match_satisfied(header: &str, current: Option<u64>) -> bool {
+    let Some(current) = current else {
+        return false;
+    };
+    let expected = format!("\"{current}\"");
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag == expected)
+}
+
+fn precondition_failed(current: Option<u64>) -> Response {
+    let message = match current {
+        Some(revision) => format!(
+            "The resource has been modified (current revision {revision}). \
+             Fetch it again and retry with its current ETag."
+        ),
+        None => "The resource does not exist".to_string(),
+    };
+    let mut response =
+        ErrorResponse::new(error_codes::PRECONDITION_FAILED, message).into_response();
+    if let Some(revision) = current {
+        response.headers_mut().insert(ETAG, etag(revision));
+    }
+    response
+}
+
+/// Route layer implementing `ETag` / `If-Match`. See the module docs.
+pub async fn revision_preconditions(
+    Extension(registry): Extension<InstanceRegistry>,
+    matched_path: MatchedPath,
+    params: Option<RawPathParams>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let params: Vec<(&str, &str)> = params
+        .as_ref()
+        .map(|p| p.iter().collect())
+        .unwrap_or_default();
+    let Some(target) = Target::resolve(
+        matched_path.as_str(),
+        &params,
+        request.extensions().get::<String>(),
+    ) else {
+        return next.run(request).await;
+    };
+    let revisions = registry.revisions();
+    let method = request.method().clone();
+
+    if method == Method::GET {
+        if matches!(target, Target::Collection { .. } | Target::Lifecycle { .. }) {
+            return next.run(request).await;
+        }
+        let mut response = next.run(request).await;
+        if response.status().is_success() {
+            response
+                .headers_mut()
+                .insert(ETAG, etag(target.revision(revisions)));
+        }
+        return response;
+    }
+    if !matches!(method, Method::PUT | Method::DELETE | Method::POST) {
+        return next.run(request).await;
+    }
+
+    let _write_guard = revisions.lock_writes(target.instance_id()).await;
+    if !matches!(target, Target::Collection { .. } | Target::Lifecycle { .. }) {
+        if let Some(if_match) = request.headers().get(IF_MATCH) {
+            let current = target.current_revision(&registry).await;
+            let satisfied = if_match
+                .to_str()
+                .map(|value| if_match_satisfied(value, current))
+                .unwrap_or(false);
+            if !satisfied {
+                return precondition_failed(current);
+            }
+        }
+    }
+
+    let mut response = next.run(request).await;
+    if !response.status().is_success() {
+        return response;
+    }
+    let new_revision = match &target {
+        Target::Component {
+            instance_id,
+            kind,
+            id,
+        } => revisions.bump(instance_id, *kind, id),
+        // Apply moves each changed component (and so the instance) itself.
+        Target::Instance { instance_id } => revisions.instance(instance_id),
+        Target::Collection { instance_id } => revisions.touch_instance(instance_id),
+        Target::Lifecycle { .. } => return response,
+    };
+    if method != Method::DELETE {
+        response.headers_mut().insert(ETAG, etag(new_revision));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_target_from_matched_path() {
+        let params = [("instanceId", "prod"), ("id", "orders")];
+        assert_eq!(
+            Target::resolve("/instances/:instanceId/sources/:id", &params, None),
+            Some(Target::Component {
+                instance_id: "prod".into(),
+                kind: ComponentKind::Source,
+                id: "orders".into(),
+            })
+        );
+        assert_eq!(
+            Target::resolve("/instances/:instanceId/apply", &params[..1], None),
+            Some(Target::Instance {
+                instance_id: "prod".into()
+            })
+        );
+        assert_eq!(
+            Target::resolve("/instances/:instanceId/sources/:id/start", &params, None),
+            Some(Target::Lifecycle {
+                instance_id: "prod".into()
+            })
+        );
+        assert_eq!(
+            Target::resolve(
+                "/instances/:instanceId/queries/:id/rebootstrap",
+                &params,
+                None
+            ),
+            Some(Target::Lifecycle {
+                instance_id: "prod".into()
+            })
+        );
+        assert_eq!(
+            Target::resolve("/instances/:instanceId/sources/:id/replay", &params, None),
+            Some(Target::Lifecycle {
+                instance_id: "prod".into()
+            })
+        );
+        assert_eq!(
+            Target::resolve("/instances/:instanceId/sources/:id/events", &params, None),
+            None
+        );
+
+        // Convenience routes take the instance from the request extensions.
+        let default_instance = "default".to_string();
+        assert_eq!(
+            Target::resolve(
+                "/reactions/:id",
+                &[("id", "alerts")],
+                Some(&default_instance)
+            ),
+            Some(Target::Component {
+                instance_id: "default".into(),
+                kind: ComponentKind::Reaction,
+                id: "alerts".into(),
+            })
+        );
+        assert_eq!(
+            Target::resolve("/queries", &[], Some(&default_instance)),
+            Some(Target::Collection {
+                instance_id: "default".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_if_match() {
+        assert!(if_match_satisfied("\"7\"", Some(7)));
+        assert!(if_match_satisfied("\"3\", \"7\"", Some(7)));
+        assert!(if_match_satisfied("*", Some(7)));
+        assert!(!if_match_satisfied("\"6\"", Some(7)));
+        assert!(!if_match_satisfied("W/\"7\"", Some(7)));
+        assert!(!if_match_satisfied("*", None));
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! On-demand query re-bootstrap over the API.
+
+use axum::Json;
+use drasi_lib::{ComponentStatus, StorageBackendRef};
+use log::info;
+
+use crate::api::models::{ComponentStatusDto, QueryRebootstrapResponse};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorResponse};
+use crate::api::shared::responses::ApiResponse;
+use crate::config::{ModeOperation, ServerMode};
+use crate::index_provider::{instance_index_dir, PERSISTENT_INDEX_PROVIDER_NAME};
+use crate::instance_registry::InstanceRegistry;
+
+/// Discard a query's index and bootstrap it again from its sources'
+/// bootstrap providers.
+///
+/// The query is stopped if it runs, and its persistent index and checkpoints
+/// are cleared on disk, so it bootstraps as on its first run. An in-memory
+/// index is rebuilt empty on every start anyway. A running query is started
+/// again at once; a stopped one bootstraps when next started, also after a
+/// server restart. Queries with their own persistent `storageBackend` are
+/// refused, as the server holds no handle on that index.
+///
+/// The route holds the instance's write lock (see
+/// [`crate::api::shared::preconditions`]), so no other request starts, stops
+/// or changes the query between the status check and the restart.
+///
+/// Progress shows in the query's component events: `Starting` while
+/// bootstrapping, then `Running`, or `Error` if the bootstrap fails.
+/// Reactions stay subscribed throughout and receive the bootstrapped results
+/// as additions.
+pub async fn rebootstrap_query(
+    registry: &InstanceRegistry,
+    mode: ServerMode,
+    instance_id: &str,
+    query_id: &str,
+) -> Result<Json<ApiResponse<QueryRebootstrapResponse>>, ErrorResponse> {
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "re-bootstrap queries")?;
+    let core = registry.get(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::INSTANCE_NOT_FOUND,
+            format!("Instance '{instance_id}' not found"),
+        )
+    })?;
+    let storage = registry.storage(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::INDEX_UNAVAILABLE,
+            format!(
+                "The server did not build instance '{instance_id}' and has no handle on its \
+                 indexes"
+            ),
+        )
+    })?;
+    let config = core
+        .get_query_config(query_id)
+        .await
+        .map_err(ErrorResponse::from)?;
+    if !config.enable_bootstrap {
+        return Err(ErrorResponse::new(
+            error_codes::INVALID_REQUEST,
+            format!("Query '{query_id}' has bootstrap disabled"),
+        ));
+    }
+
+    // Whether the query is backed by the instance's persistent index.
+    let instance_index = match &config.storage_backend {
+        None => true,
+        Some(StorageBackendRef::Named(name)) if name == PERSISTENT_INDEX_PROVIDER_NAME => true,
+        Some(StorageBackendRef::Inline(spec)) if spec.is_volatile() => false,
+        Some(_) => {
+            return Err(ErrorResponse::new(
+                error_codes::INVALID_REQUEST,
+                format!(
+                    "Query '{query_id}' has its own persistent storageBackend, whose index \
+                     the server cannot clear"
+                ),
+            ));
+        }
+    };
+
+    let running = matches!(
+        core.get_query_status(query_id).await,
+        Ok(ComponentStatus::Running | ComponentStatus::Starting)
+    );
+    if running {
+        core.stop_query(query_id)
+            .await
+            .map_err(ErrorResponse::from)?;
+    }
+    if let Some(index) = storage.index.as_ref().filter(|_| instance_index) {
+        index
+            .clear_query(&instance_index_dir(instance_id), query_id)
+            .await
+            .map_err(|e| {
+                ErrorResponse::new(
+                    error_codes::INTERNAL_ERROR,
+                    format!("Failed to clear the index of query '{query_id}': {e}"),
+                )
+            })?;
+    }
+    if running {
+        core.start_query(query_id)
+            .await
+            .map_err(ErrorResponse::from)?;
+    }
+    info!(
+        "Re-bootstrapping query '{query_id}' of instance '{instance_id}'{}",
+        if running { "" } else { " on its next start" }
+    );
+
+    let status = core
+        .get_query_status(query_id)
+        .await
+        .map_err(ErrorResponse::from)?;
+    Ok(Json(ApiResponse::success(QueryRebootstrapResponse {
+        query_id: query_id.to_string(),
+        restarted: running,
+        status: ComponentStatusDto::from(status),
+    })))
+}
@@ -36,7 +36,7 @@ use crate::api::models::solution::{
 use crate::api::models::{QueryConfigDto, ReactionConfig, SourceConfig};
 use crate::api::shared::error::{error_codes, ErrorResponse};
 use crate::api::shared::ApiResponse;
-use crate::factories::{create_reaction_locked, create_source_locked};
+use crate::factories::{create_reaction_locked, create_source_locked, ConfigResolverContext};
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
 use crate::plugin_registry::PluginRegistry;
@@ -341,6 +341,8 @@ pub async fn create_solution_template(
                 auto_start: source_snap.auto_start,
                 bootstrap_provider,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: properties_json,
             };
 
@@ -390,6 +392,8 @@ pub async fn create_solution_template(
                 queries: reaction_snap.queries.clone(),
                 auto_start: reaction_snap.auto_start,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: properties_json,
             };
 
@@ -637,8 +641,12 @@ pub async fn deploy_solution(
         ));
     };
 
-    // Create a DtoMapper with the user's variable overrides
-    let mapper = DtoMapper::with_overrides(request.variables.clone());
+    // Create a DtoMapper with the user's variable overrides; secrets resolve
+    // through the target instance's secret store.
+    let mut mapper = DtoMapper::with_overrides(request.variables.clone());
+    if let Some(store) = ConfigResolverContext::global().instance_store(instance_id) {
+        mapper = mapper.with_secret_store(store);
+    }
 
     // Resolve variables in the YAML content
     let resolved_yaml = resolve_yaml_variables(&yaml_content, &request.variables);
@@ -864,22 +872,29 @@ pub async fn deploy_solution(
         // Force autoStart to false for initial creation
         source_config.set_auto_start(false);
 
-        let (source, _plugin_meta) =
-            match create_source_locked(plugin_registry, source_config.clone()).await {
-                Ok(s) => s,
-                Err(e) => {
-                    creation_errors.push(SolutionDeployError::creation(
-                        "source",
-                        &source_id,
-                        e.to_string(),
-                    ));
-                    // Rollback already-created sources
-                    rollback_sources(&core, &created_sources).await;
-                    return Ok(Json(ApiResponse::success(SolutionDeployResponse::failed(
-                        creation_errors,
-                    ))));
-                }
-            };
+        let (source, _plugin_meta) = match create_source_locked(
+            plugin_registry,
+            instance_id,
+            registry.secret_consumers(),
+            registry.plugin_breakers(),
+            source_config.clone(),
+        )
+        .await
+        {
+            Ok(s) => s,
+            Err(e) => {
+                creation_errors.push(SolutionDeployError::creation(
+                    "source",
+                    &source_id,
+                    e.to_string(),
+                ));
+                // Rollback already-created sources
+                rollback_sources(&core, &created_sources).await;
+                return Ok(Json(ApiResponse::success(SolutionDeployResponse::failed(
+                    creation_errors,
+                ))));
+            }
+        };
 
         if let Err(e) = core.add_source(source).await {
             creation_errors.push(SolutionDeployError::creation(
@@ -951,23 +966,30 @@ pub async fn deploy_solution(
         // Force autoStart to false for initial creation
         reaction_config.set_auto_start(false);
 
-        let (reaction, _plugin_meta) =
-            match create_reaction_locked(plugin_registry, reaction_config.clone()).await {
-                Ok(r) => r,
-                Err(e) => {
-                    creation_errors.push(SolutionDeployError::creation(
-                        "reaction",
-                        &reaction_id,
-                        e.to_string(),
-                    ));
-                    rollback_reactions(&core, &created_reactions).await;
-                    rollback_queries(&core, &created_queries).await;
-                    rollback_sources(&core, &created_sources).await;
-                    return Ok(Json(ApiResponse::success(SolutionDeployResponse::failed(
-                        creation_errors,
-                    ))));
-                }
-            };
+        let (reaction, _plugin_meta) = match create_reaction_locked(
+            plugin_registry,
+            instance_id,
+            registry.secret_consumers(),
+            registry.plugin_breakers(),
+            reaction_config.clone(),
+        )
+        .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                creation_errors.push(SolutionDeployError::creation(
+                    "reaction",
+                    &reaction_id,
+                    e.to_string(),
+                ));
+                rollback_reactions(&core, &created_reactions).await;
+                rollback_queries(&core, &created_queries).await;
+                rollback_sources(&core, &created_sources).await;
+                return Ok(Json(ApiResponse::success(SolutionDeployResponse::failed(
+                    creation_errors,
+                ))));
+            }
+        };
 
         if let Err(e) = core.add_reaction(reaction).await {
             creation_errors.push(SolutionDeployError::creation(
@@ -1033,7 +1055,7 @@ pub async fn deploy_solution(
 
     // Persist changes
     if let Some(p) = &persistence {
-        if let Err(e) = p.save().await {
+        if let Err(e) = p.save_for("deploying solution").await {
             log::warn!("Failed to persist config after solution deployment: {e}");
         }
     }
@@ -0,0 +1,216 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Inspection and repair of the state components persist in their
+//! instance's state store.
+//!
+//! Each source and reaction owns the partition named after its id. A
+//! partition can only be changed while its component is not running;
+//! otherwise the component would keep working from (and write back) the
+//! state it already loaded. The instance's write lock is held from the
+//! status check until the change is made, so the component cannot be
+//! started or restarted in between.
+
+use axum::Json;
+use drasi_lib::channels::ComponentStatus;
+use drasi_lib::state_store::{StateStoreError, StateStoreProvider};
+use drasi_lib::DrasiLib;
+use std::sync::Arc;
+use tokio::sync::OwnedMutexGuard;
+
+use crate::api::models::{
+    StateStoreDump, StateStoreEntry, StateStorePartition, StateStoreResetResponse,
+};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorDetail, ErrorResponse};
+use crate::api::shared::responses::{ApiResponse, StatusResponse};
+use crate::config::{ModeOperation, ServerMode};
+use crate::instance_registry::InstanceRegistry;
+
+/// The instance and its state store.
+async fn instance_state_store(
+    registry: &InstanceRegistry,
+    instance_id: &str,
+) -> Result<(Arc<DrasiLib>, Arc<dyn StateStoreProvider>), ErrorResponse> {
+    let core = registry.get(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::INSTANCE_NOT_FOUND,
+            format!("Instance '{instance_id}' not found"),
+        )
+    })?;
+    let store = registry.state_store(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::STATE_STORE_UNAVAILABLE,
+            format!("The state store of instance '{instance_id}' is not accessible"),
+        )
+    })?;
+    Ok((core, store))
+}
+
+fn store_error(store_id: &str, e: StateStoreError) -> ErrorResponse {
+    ErrorResponse::new(
+        error_codes::STATE_STORE_FAILED,
+        format!("State store operation on '{store_id}' failed: {e}"),
+    )
+}
+
+/// The type and status of the component that owns partition `store_id`.
+async fn owner(core: &DrasiLib, store_id: &str) -> Option<(&'static str, ComponentStatus)> {
+    if let Ok(status) = core.get_source_status(store_id).await {
+        return Some(("source", status));
+    }
+    if let Ok(status) = core.get_reaction_status(store_id).await {
+        return Some(("reaction", status));
+    }
+    None
+}
+
+/// Reject changes to `store_id` unless the server's mode allows them and its
+/// component (if any) is not running. Returns the instance's write lock;
+/// hold it until the change is made.
+async fn ensure_changeable(
+    registry: &InstanceRegistry,
+    core: &DrasiLib,
+    mode: ServerMode,
+    instance_id: &str,
+    store_id: &str,
+) -> Result<OwnedMutexGuard<()>, ErrorResponse> {
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "change component state")?;
+    let guard = registry.revisions().lock_writes(instance_id).await;
+    match owner(core, store_id).await {
+        Some((_, ComponentStatus::Added | ComponentStatus::Stopped | ComponentStatus::Error))
+        | None => Ok(guard),
+        Some((component_type, status)) => Err(ErrorResponse::new(
+            error_codes::COMPONENT_RUNNING,
+            format!("Stop {component_type} '{store_id}' before changing its state"),
+        )
+        .with_details(ErrorDetail {
+            component_type: Some(component_type.to_string()),
+            component_id: Some(store_id.to_string()),
+            technical_details: Some(format!("status: {status:?}")),
+        })),
+    }
+}
+
+/// List the state store partition of every source and reaction.
+pub async fn list_state_stores(
+    registry: &InstanceRegistry,
+    instance_id: &str,
+) -> Result<Json<ApiResponse<Vec<StateStorePartition>>>, ErrorResponse> {
+    let (core, store) = instance_state_store(registry, instance_id).await?;
+    let sources = core.list_sources().await.map_err(ErrorResponse::from)?;
+    let reactions = core.list_reactions().await.map_err(ErrorResponse::from)?;
+
+    let mut partitions = Vec::new();
+    for (component_type, components) in [("source", sources), ("reaction", reactions)] {
+        for (id, status) in components {
+            let key_count = store
+                .key_count(&id)
+                .await
+                .map_err(|e| store_error(&id, e))?;
+            partitions.push(StateStorePartition {
+                store_id: id,
+                component_type: component_type.to_string(),
+                status: status.into(),
+                key_count,
+            });
+        }
+    }
+    Ok(Json(ApiResponse::success(partitions)))
+}
+
+/// Dump every key and value of a partition.
+pub async fn get_state_store(
+    registry: &InstanceRegistry,
+    instance_id: &str,
+    store_id: &str,
+) -> Result<Json<ApiResponse<StateStoreDump>>, ErrorResponse> {
+    let (core, store) = instance_state_store(registry, instance_id).await?;
+    let mut keys = store
+        .list_keys(store_id)
+        .await
+        .map_err(|e| store_error(store_id, e))?;
+    if keys.is_empty() && owner(&core, store_id).await.is_none() {
+        return Err(ErrorResponse::new(
+            error_codes::STATE_STORE_NOT_FOUND,
+            format!("No state stored under '{store_id}' in instance '{instance_id}'"),
+        ));
+    }
+    keys.sort();
+
+    let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+    let mut values = store
+        .get_many(store_id, &key_refs)
+        .await
+        .map_err(|e| store_error(store_id, e))?;
+    // A key deleted between the two reads is left out.
+    let entries = keys
+        .into_iter()
+        .filter_map(|key| {
+            let value = values.remove(&key)?;
+            Some(StateStoreEntry::new(key, value))
+        })
+        .collect();
+    Ok(Json(ApiResponse::success(StateStoreDump {
+        store_id: store_id.to_string(),
+        entries,
+    })))
+}
+
+/// Delete one key of a partition.
+pub async fn delete_state_key(
+    registry: &InstanceRegistry,
+    mode: ServerMode,
+    instance_id: &str,
+    store_id: &str,
+    key: &str,
+) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
+    let (core, store) = instance_state_store(registry, instance_id).await?;
+    let _guard = ensure_changeable(registry, &core, mode, instance_id, store_id).await?;
+    let deleted = store
+        .delete(store_id, key)
+        .await
+        .map_err(|e| store_error(store_id, e))?;
+    if !deleted {
+        return Err(ErrorResponse::new(
+            error_codes::STATE_KEY_NOT_FOUND,
+            format!("Key '{key}' not found in state of '{store_id}'"),
+        ));
+    }
+    log::info!("Deleted state key '{key}' of '{store_id}' in instance '{instance_id}'");
+    Ok(Json(ApiResponse::success(StatusResponse {
+        message: format!("Key '{key}' deleted"),
+    })))
+}
+
+/// Delete every key of a partition, so its component starts from scratch.
+pub async fn reset_state_store(
+    registry: &InstanceRegistry,
+    mode: ServerMode,
+    instance_id: &str,
+    store_id: &str,
+) -> Result<Json<ApiResponse<StateStoreResetResponse>>, ErrorResponse> {
+    let (core, store) = instance_state_store(registry, instance_id).await?;
+    let _guard = ensure_changeable(registry, &core, mode, instance_id, store_id).await?;
+    let deleted_keys = store
+        .clear_store(store_id)
+        .await
+        .map_err(|e| store_error(store_id, e))?;
+    log::info!(
+        "Reset state of '{store_id}' in instance '{instance_id}' ({deleted_keys} keys deleted)"
+    );
+    Ok(Json(ApiResponse::success(StateStoreResetResponse {
+        store_id: store_id.to_string(),
+        deleted_keys,
+    })))
+}
@@ -0,0 +1,370 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage usage of instances, as JSON and in the Prometheus text format;
+//! see [`crate::storage_usage`].
+//!
+//! The JSON report scans each persistent query's index when requested.
+//! `/metrics` is scraped every few seconds, so it serves the counts cached by
+//! the latest background scan and, where archive retention runs, the archive
+//! totals of its latest pass.
+
+use std::fmt::Write;
+
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use drasi_lib::wal::WalError;
+use drasi_lib::DrasiLib;
+
+use crate::api::models::{InstanceStorageReport, QueryStorageUsage, SourceWalUsage};
+use crate::api::shared::error::{error_codes, ErrorResponse};
+use crate::api::shared::responses::ApiResponse;
+use crate::archive_retention::ArchiveRetention;
+use crate::index_provider::instance_index_dir;
+use crate::instance_registry::InstanceRegistry;
+use crate::storage_usage::{count_index, directory_size, files_size, InstanceStorage};
+
+/// Report an instance's storage usage.
+pub async fn instance_storage(
+    registry: &InstanceRegistry,
+    instance_id: &str,
+) -> Result<Json<ApiResponse<InstanceStorageReport>>, ErrorResponse> {
+    let core = registry.get(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::INSTANCE_NOT_FOUND,
+            format!("Instance '{instance_id}' not found"),
+        )
+    })?;
+    let storage = registry.storage(instance_id).await;
+    let report = storage_report(&core, instance_id, storage.as_deref(), IndexCounting::Scan).await;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// Storage usage of every instance in the Prometheus text format.
+pub async fn storage_metrics(registry: &InstanceRegistry) -> Response {
+    let mut reports = Vec::new();
+    for (instance_id, core) in registry.list().await {
+        let storage = registry.storage(&instance_id).await;
+        let retention = registry.archive_retention(&instance_id).await;
+        let counting = IndexCounting::Cached(retention.as_deref());
+        reports.push(storage_report(&core, &instance_id, storage.as_deref(), counting).await);
+    }
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render_metrics(&reports),
+    )
+        .into_response()
+}
+
+/// Where a report's index counts come from.
+enum IndexCounting<'a> {
+    /// Scan each persistent query's index now.
+    Scan,
+    /// Use the latest background scan, with the archive totals of the
+    /// instance's retention task if it has one.
+    Cached(Option<&'a ArchiveRetention>),
+}
+
+/// Measure an instance's storage. Without a storage record only the result
+/// counts DrasiLib tracks are known.
+async fn storage_report(
+    core: &DrasiLib,
+    instance_id: &str,
+    storage: Option<&InstanceStorage>,
+    counting: IndexCounting<'_>,
+) -> InstanceStorageReport {
+    let mut report = InstanceStorageReport {
+        instance_id: instance_id.to_string(),
+        ..Default::default()
+    };
+
+    let databases = storage.and_then(|storage| storage.index.as_ref());
+    let archive_statuses = match counting {
+        IndexCounting::Cached(retention) => {
+            if let (Some(storage), Some(databases)) = (storage, databases) {
+                storage.index_counts.refresh(databases, retention.is_none());
+            }
+            retention
+                .map(ArchiveRetention::statuses)
+                .unwrap_or_default()
+        }
+        IndexCounting::Scan => Vec::new(),
+    };
+    let mut queries = core.list_queries().await.unwrap_or_default();
+    queries.sort_by(|a, b| a.0.cmp(&b.0));
+    for (query_id, _) in queries {
+        let mut usage = QueryStorageUsage {
+            query_id: query_id.clone(),
+            result_count: core
+                .get_query_output_metrics(&query_id)
+                .await
+                .ok()
+                .map(|metrics| metrics.live_results_count as u64),
+            ..Default::default()
+        };
+        if let Some(db) = databases.and_then(|databases| databases.get(&query_id)) {
+            usage.persistent = true;
+            let counts = match (&counting, storage) {
+                (IndexCounting::Cached(_), Some(storage)) => storage.index_counts.get(&query_id),
+                _ => Some(
+                    tokio::task::spawn_blocking(move || count_index(&db))
+                        .await
+                        .map_err(anyhow::Error::from)
+                        .and_then(|result| result)
+                        .map_err(|e| e.to_string()),
+                ),
+            };
+            match counts {
+                Some(Ok(counts)) => {
+                    usage.element_count = Some(counts.elements);
+                    usage.archive_versions = counts.archive_versions;
+                    usage.archive_bytes = counts.archive_bytes;
+                }
+                Some(Err(e)) => usage.error = Some(e),
+                // Not counted yet.
+                None => {}
+            }
+            let pass = archive_statuses
+                .iter()
+                .find(|status| status.query_id == query_id && status.last_error.is_none());
+            if let Some(pass) = pass {
+                usage.archive_versions = Some(pass.versions);
+                usage.archive_bytes = Some(pass.bytes);
+            }
+        }
+        report.queries.push(usage);
+    }
+    report.archive_bytes = report
+        .queries
+        .iter()
+        .filter_map(|query| query.archive_bytes)
+        .reduce(|a, b| a + b);
+
+    let Some(storage) = storage else {
+        return report;
+    };
+
+    let mut sources = core.list_sources().await.unwrap_or_default();
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut wal_files = Vec::new();
+    for (source_id, _) in sources {
+        let head_sequence = match storage.wal.head_sequence(&source_id).await {
+            Ok(head) => head,
+            // The source does not write a WAL.
+            Err(WalError::SourceNotRegistered(_)) => continue,
+            Err(e) => {
+                log::debug!("Cannot read the WAL of source '{source_id}': {e}");
+                continue;
+            }
+        };
+        wal_files.push(storage.wal_file(&source_id));
+        report.sources.push(SourceWalUsage {
+            bytes: 0,
+            head_sequence,
+            oldest_sequence: storage.wal.oldest_sequence(&source_id).await.ok().flatten(),
+            backlog: storage.wal.event_count(&source_id).await.unwrap_or(0),
+            source_id,
+        });
+    }
+
+    let index_dir = storage
+        .index
+        .is_some()
+        .then(|| instance_index_dir(instance_id));
+    let wal_dir = storage.wal_dir.clone();
+    let state_store_files = storage.state_store_files.clone();
+    let sizes = tokio::task::spawn_blocking(move || {
+        (
+            index_dir.and_then(|dir| directory_size(&dir)),
+            directory_size(&wal_dir).unwrap_or(0),
+            state_store_files.map(|files| files_size(&files)),
+            wal_files
+                .iter()
+                .map(|file| files_size(std::slice::from_ref(file)))
+                .collect::<Vec<_>>(),
+        )
+    })
+    .await;
+    let (index_bytes, wal_bytes, state_store_bytes, source_bytes) = sizes.unwrap_or_default();
+    for (source, bytes) in report.sources.iter_mut().zip(source_bytes) {
+        source.bytes = bytes;
+    }
+    report.index_bytes = index_bytes;
+    report.wal_bytes = Some(wal_bytes);
+    report.state_store_bytes = state_store_bytes;
+    report
+}
+
+type Gauge<T> = (&'static str, &'static str, fn(&T) -> Option<u64>);
+
+const INSTANCE_GAUGES: [Gauge<InstanceStorageReport>; 4] = [
+    (
+        "drasi_index_bytes",
+        "Bytes on disk under the instance's persistent index directory.",
+        |report| report.index_bytes,
+    ),
+    (
+        "drasi_archive_bytes",
+        "Uncompressed key and value bytes of the instance's archived element versions.",
+        |report| report.archive_bytes,
+    ),
+    (
+        "drasi_wal_bytes",
+        "Bytes on disk under the instance's WAL directory.",
+        |report| report.wal_bytes,
+    ),
+    (
+        "drasi_state_store_bytes",
+        "Bytes of the instance's state store files.",
+        |report| report.state_store_bytes,
+    ),
+];
+
+const QUERY_GAUGES: [Gauge<QueryStorageUsage>; 3] = [
+    (
+        "drasi_query_elements",
+        "Elements in the query's persistent index.",
+        |query| query.element_count,
+    ),
+    (
+        "drasi_query_results",
+        "Current results of the query.",
+        |query| query.result_count,
+    ),
+    (
+        "drasi_query_archive_versions",
+        "Archived element versions of the query.",
+        |query| query.archive_versions,
+    ),
+];
+
+const SOURCE_GAUGES: [Gauge<SourceWalUsage>; 2] = [
+    (
+        "drasi_source_wal_bytes",
+        "Bytes of the source's WAL file.",
+        |source| Some(source.bytes),
+    ),
+    (
+        "drasi_source_wal_backlog",
+        "Events retained in the source's WAL.",
+        |source| Some(source.backlog),
+    ),
+];
+
+/// Render `reports` in the Prometheus text exposition format.
+pub fn render_metrics(reports: &[InstanceStorageReport]) -> String {
+    let mut out = String::new();
+    for (name, help, value) in INSTANCE_GAUGES {
+        let samples = reports.iter().filter_map(|report| {
+            value(report).map(|v| (format!("instance=\"{}\"", label(&report.instance_id)), v))
+        });
+        write_gauge(&mut out, name, help, samples);
+    }
+    for (name, help, value) in QUERY_GAUGES {
+        let samples = reports.iter().flat_map(|report| {
+            report.queries.iter().filter_map(move |query| {
+                value(query).map(|v| {
+                    let labels = format!(
+                        "instance=\"{}\",query=\"{}\"",
+                        label(&report.instance_id),
+                        label(&query.query_id)
+                    );
+                    (labels, v)
+                })
+            })
+        });
+        write_gauge(&mut out, name, help, samples);
+    }
+    for (name, help, value) in SOURCE_GAUGES {
+        let samples = reports.iter().flat_map(|report| {
+            report.sources.iter().filter_map(move |source| {
+                value(source).map(|v| {
+                    let labels = format!(
+                        "instance=\"{}\",source=\"{}\"",
+                        label(&report.instance_id),
+                        label(&source.source_id)
+                    );
+                    (labels, v)
+                })
+            })
+        });
+        write_gauge(&mut out, name, help, samples);
+    }
+    out
+}
+
+/// Append one gauge family; families without samples are left out.
+fn write_gauge(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    samples: impl Iterator<Item = (String, u64)>,
+) {
+    let mut samples = samples.peekable();
+    if samples.peek().is_none() {
+        return;
+    }
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{name}{{{labels}}} {value}");
+    }
+}
+
+/// Escape a label value.
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let report = InstanceStorageReport {
+            instance_id: "a\"b".to_string(),
+            index_bytes: Some(4096),
+            wal_bytes: Some(512),
+            queries: vec![QueryStorageUsage {
+                query_id: "q1".to_string(),
+                persistent: true,
+                element_count: Some(7),
+                result_count: Some(3),
+                ..Default::default()
+            }],
+            sources: vec![SourceWalUsage {
+                source_id: "s1".to_string(),
+                bytes: 512,
+                head_sequence: 10,
+                oldest_sequence: Some(8),
+                backlog: 3,
+            }],
+            ..Default::default()
+        };
+        let text = render_metrics(&[report]);
+
+        assert!(text.contains("# TYPE drasi_index_bytes gauge\n"));
+        assert!(text.contains("drasi_index_bytes{instance=\"a\\\"b\"} 4096\n"));
+        assert!(text.contains("drasi_query_elements{instance=\"a\\\"b\",query=\"q1\"} 7\n"));
+        assert!(text.contains("drasi_source_wal_backlog{instance=\"a\\\"b\",source=\"s1\"} 3\n"));
+        // Unknown values are left out rather than reported as zero.
+        assert!(!text.contains("drasi_state_store_bytes"));
+        assert!(!text.contains("drasi_query_archive_versions"));
+    }
+}
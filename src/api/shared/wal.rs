@@ -0,0 +1,309 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Source WAL inspection and replay over the API; see
+//! [`crate::source_wal`].
+
+use std::sync::Arc;
+
+use axum::Json;
+use chrono::DateTime;
+use drasi_lib::wal::WalError;
+use drasi_lib::{ComponentStatus, DrasiLib};
+use log::{info, warn};
+
+use crate::api::models::{
+    InstanceWalStatus, QueryWalCheckpoint, ReplayRequest, ReplayResponse, SkippedReplay,
+    SourceWalStatus,
+};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorResponse};
+use crate::api::shared::responses::ApiResponse;
+use crate::config::{ModeOperation, ServerMode};
+use crate::instance_registry::InstanceRegistry;
+use crate::source_wal::{oldest_event_time, read_checkpoint, rewind_checkpoint, sequence_since};
+use crate::storage_usage::{files_size, InstanceStorage};
+
+/// Report the WAL of each of an instance's sources.
+pub async fn instance_wal(
+    registry: &InstanceRegistry,
+    instance_id: &str,
+) -> Result<Json<ApiResponse<InstanceWalStatus>>, ErrorResponse> {
+    let (core, storage) = instance(registry, instance_id).await?;
+    let wal_error = |e: WalError| {
+        ErrorResponse::new(
+            error_codes::INTERNAL_ERROR,
+            format!("Failed to read the WAL of instance '{instance_id}': {e}"),
+        )
+    };
+
+    let mut sources = core.list_sources().await.unwrap_or_default();
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut statuses = Vec::new();
+    for (source_id, _) in sources {
+        let head_sequence = match storage.wal.head_sequence(&source_id).await {
+            Ok(head) => head,
+            // The source does not write a WAL.
+            Err(WalError::SourceNotRegistered(_)) => continue,
+            Err(e) => return Err(wal_error(e)),
+        };
+        let oldest_timestamp =
+            oldest_event_time(storage.wal.as_ref(), &storage.wal_times, &source_id)
+                .await
+                .map_err(wal_error)?
+                .and_then(|millis| DateTime::from_timestamp_millis(millis as i64));
+        let mut checkpoints = Vec::new();
+        for query_id in subscribed_queries(&core, &source_id).await {
+            let Some(db) = storage
+                .index
+                .as_ref()
+                .and_then(|index| index.get(&query_id))
+            else {
+                continue;
+            };
+            match read_checkpoint(&db, &source_id).await {
+                Ok(Some(sequence)) => checkpoints.push(QueryWalCheckpoint { query_id, sequence }),
+                Ok(None) => {}
+                Err(e) => warn!("Cannot read the checkpoint of query '{query_id}': {e}"),
+            }
+        }
+        statuses.push(SourceWalStatus {
+            bytes: files_size(&[storage.wal_file(&source_id)]),
+            head_sequence,
+            oldest_sequence: storage
+                .wal
+                .oldest_sequence(&source_id)
+                .await
+                .map_err(wal_error)?,
+            oldest_timestamp,
+            event_count: storage
+                .wal
+                .event_count(&source_id)
+                .await
+                .map_err(wal_error)?,
+            checkpoints,
+            source_id,
+        });
+    }
+
+    Ok(Json(ApiResponse::success(InstanceWalStatus {
+        instance_id: instance_id.to_string(),
+        retention: storage.wal_retention.clone(),
+        sources: statuses,
+    })))
+}
+
+/// Re-deliver a source's retained WAL events to its subscribed persistent
+/// queries.
+///
+/// Each query is stopped if it runs, its checkpoint for the source is moved
+/// back to just before the replay position, and it is started again, which
+/// makes the source replay everything after the checkpoint. The events are
+/// applied on top of the query's current index. Queries that were stopped
+/// stay stopped and replay when next started. A query whose checkpoint is
+/// already before the replay position is skipped; it still has those events
+/// to process. The route holds the instance's write lock (see
+/// [`crate::api::shared::preconditions`]), so no other request starts or
+/// changes a query while it is stopped for the rewind.
+pub async fn replay_source(
+    registry: &InstanceRegistry,
+    mode: ServerMode,
+    instance_id: &str,
+    source_id: &str,
+    request: ReplayRequest,
+) -> Result<Json<ApiResponse<ReplayResponse>>, ErrorResponse> {
+    ensure_mode_allows(mode, ModeOperation::Lifecycle, "replay source events")?;
+    let (core, storage) = instance(registry, instance_id).await?;
+    if core.get_source_status(source_id).await.is_err() {
+        return Err(ErrorResponse::new(
+            error_codes::SOURCE_NOT_FOUND,
+            format!("Source '{source_id}' not found"),
+        ));
+    }
+    let invalid = |message: String| ErrorResponse::new(error_codes::INVALID_REQUEST, message);
+    let wal_error = |e: WalError| match e {
+        WalError::SourceNotRegistered(_) => {
+            invalid(format!("Source '{source_id}' does not write a WAL"))
+        }
+        e => ErrorResponse::new(
+            error_codes::REPLAY_FAILED,
+            format!("Failed to read the WAL of source '{source_id}': {e}"),
+        ),
+    };
+
+    let head = storage
+        .wal
+        .head_sequence(source_id)
+        .await
+        .map_err(wal_error)?;
+    let oldest = storage
+        .wal
+        .oldest_sequence(source_id)
+        .await
+        .map_err(wal_error)?
+        .ok_or_else(|| invalid(format!("The WAL of source '{source_id}' is empty")))?;
+    let from = match (request.from_sequence, request.from_timestamp) {
+        (Some(sequence), None) => sequence,
+        (None, Some(timestamp)) => sequence_since(
+            storage.wal.as_ref(),
+            &storage.wal_times,
+            source_id,
+            timestamp.timestamp_millis().max(0) as u64,
+        )
+        .await
+        .map_err(wal_error)?
+        .ok_or_else(|| {
+            invalid(format!(
+                "No retained event of source '{source_id}' is at or after {timestamp}"
+            ))
+        })?,
+        _ => {
+            return Err(invalid(
+                "Exactly one of fromSequence and fromTimestamp must be set".to_string(),
+            ))
+        }
+    };
+    if from < oldest || from > head {
+        return Err(invalid(format!(
+            "Sequence {from} is outside the retained events {oldest}..={head} of source \
+             '{source_id}'"
+        )));
+    }
+
+    let mut response = ReplayResponse {
+        source_id: source_id.to_string(),
+        from_sequence: from,
+        to_sequence: head,
+        replayed: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for query_id in subscribed_queries(&core, source_id).await {
+        match rewind_query(
+            &core,
+            &storage,
+            &query_id,
+            source_id,
+            from.saturating_sub(1),
+        )
+        .await
+        {
+            Ok(()) => response.replayed.push(query_id),
+            Err(reason) => response.skipped.push(SkippedReplay { query_id, reason }),
+        }
+    }
+    info!(
+        "Replaying source '{source_id}' of instance '{instance_id}' from sequence {from} to {} \
+         queries",
+        response.replayed.len()
+    );
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Rewind one query's checkpoint of `source_id` to `sequence`, restarting
+/// the query if it runs. Returns why the query was left alone otherwise,
+/// including when its checkpoint is already at or before `sequence`.
+async fn rewind_query(
+    core: &DrasiLib,
+    storage: &InstanceStorage,
+    query_id: &str,
+    source_id: &str,
+    sequence: u64,
+) -> Result<(), String> {
+    let Some(index) = &storage.index else {
+        return Err("in-memory index; it re-bootstraps whenever it starts".to_string());
+    };
+    let no_index = || "no open persistent index".to_string();
+    let db = index.get(query_id).ok_or_else(no_index)?;
+    let checkpoint = read_checkpoint(&db, source_id)
+        .await
+        .map_err(|e| format!("failed to read the checkpoint: {e}"))?;
+    drop(db);
+    match checkpoint {
+        None => return Err("no checkpoint for the source yet".to_string()),
+        Some(current) if current <= sequence => {
+            return Err(format!(
+                "its checkpoint {current} is already before the replay position"
+            ))
+        }
+        Some(_) => {}
+    }
+
+    let running = matches!(
+        core.get_query_status(query_id).await,
+        Ok(ComponentStatus::Running | ComponentStatus::Starting)
+    );
+    if running {
+        core.stop_query(query_id)
+            .await
+            .map_err(|e| format!("failed to stop the query: {e}"))?;
+    }
+    // The query keeps its database open while stopped. The handle must be
+    // released before the query starts again, which reopens it. The
+    // checkpoint may have moved while the query ran; a rewind never moves it
+    // forward.
+    let rewound = match index.get(query_id) {
+        Some(db) => rewind_checkpoint(&db, source_id, sequence)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("failed to rewind the checkpoint: {e}")),
+        None => Err(no_index()),
+    };
+    if running {
+        if let Err(e) = core.start_query(query_id).await {
+            warn!("Failed to restart query '{query_id}' after rewinding it: {e}");
+            return Err(format!("failed to restart the query: {e}"));
+        }
+    }
+    rewound
+}
+
+/// Ids of the queries subscribed to `source_id`, in order.
+async fn subscribed_queries(core: &DrasiLib, source_id: &str) -> Vec<String> {
+    let mut subscribed = Vec::new();
+    for (query_id, _) in core.list_queries().await.unwrap_or_default() {
+        let Ok(config) = core.get_query_config(&query_id).await else {
+            continue;
+        };
+        if config
+            .sources
+            .iter()
+            .any(|source| source.source_id == source_id)
+        {
+            subscribed.push(query_id);
+        }
+    }
+    subscribed.sort();
+    subscribed
+}
+
+/// The instance and the storage record the server keeps for it.
+async fn instance(
+    registry: &InstanceRegistry,
+    instance_id: &str,
+) -> Result<(Arc<DrasiLib>, Arc<InstanceStorage>), ErrorResponse> {
+    let core = registry.get(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::INSTANCE_NOT_FOUND,
+            format!("Instance '{instance_id}' not found"),
+        )
+    })?;
+    let storage = registry.storage(instance_id).await.ok_or_else(|| {
+        ErrorResponse::new(
+            error_codes::WAL_UNAVAILABLE,
+            format!(
+                "The server did not build instance '{instance_id}' and has no handle on its WAL"
+            ),
+        )
+    })?;
+    Ok((core, storage))
+}
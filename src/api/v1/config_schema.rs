@@ -0,0 +1,532 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Standalone JSON Schema for the server config file.
+//!
+//! The OpenAPI spec describes components as they travel over the REST API,
+//! where plugin-specific fields are opaque. Editors need the opposite: a
+//! single draft-07 document for `server.yaml` in which every `sources`,
+//! `reactions`, `bootstrapProviders` and `identityProviders` entry is a
+//! `oneOf` over the installed plugins, selected by its `kind`.
+//!
+//! The schema is served at `/api/v1/config/schema` and printed by
+//! `drasi-server schema`.
+
+use serde_json::{json, Map, Value};
+use utoipa::OpenApi;
+
+use super::openapi::{inject_plugin_schemas, ApiDocV1};
+use crate::api::models::BUILTIN_PASSWORD_KIND;
+use crate::plugin_registry::{PluginKindInfo, PluginRegistry};
+
+/// JSON Schema dialect of the generated document.
+pub const JSON_SCHEMA_DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+const OPENAPI_REF_PREFIX: &str = "#/components/schemas/";
+const DEFINITIONS_REF_PREFIX: &str = "#/definitions/";
+
+/// Matches `${VAR}` / `${VAR:-default}` references accepted by every
+/// `ConfigValue` field regardless of its static type.
+const ENV_REFERENCE_PATTERN: &str = r"^\$\{[^}]+\}$";
+
+/// Build the JSON Schema for `DrasiServerConfig` covering every plugin in
+/// `registry`.
+pub fn config_json_schema(registry: &PluginRegistry) -> Value {
+    let mut openapi = ApiDocV1::openapi();
+    inject_plugin_schemas(&mut openapi, registry);
+
+    let mut definitions = match serde_json::to_value(&openapi.components) {
+        Ok(Value::Object(mut components)) => match components.remove("schemas") {
+            Some(Value::Object(schemas)) => schemas,
+            _ => Map::new(),
+        },
+        _ => Map::new(),
+    };
+
+    // Identity provider plugins are not part of the REST API surface, so
+    // their schemas are only added here.
+    let identity_infos = registry.identity_provider_plugin_infos();
+    for info in &identity_infos {
+        insert_plugin_schemas(&mut definitions, info);
+    }
+
+    for schema in definitions.values_mut() {
+        to_json_schema(schema);
+    }
+    allow_env_references(&mut definitions);
+
+    let source_common = source_common_properties();
+    let reaction_common = reaction_common_properties();
+    let bootstrap_common = json!({ "kind": { "type": "string" } });
+    let identity_common = json!({
+        "kind": { "type": "string" },
+        "id": { "type": "string", "description": "Identifier referenced by `identityProvider`" },
+    });
+
+    let sources = component_schema(
+        &definitions,
+        &registry.source_plugin_infos(),
+        &source_common,
+        &["id", "kind"],
+        "Source configuration. `kind` selects the source plugin.",
+    );
+    let reactions = component_schema(
+        &definitions,
+        &registry.reaction_plugin_infos(),
+        &reaction_common,
+        &["id", "kind", "queries"],
+        "Reaction configuration. `kind` selects the reaction plugin.",
+    );
+    let bootstrap_infos = registry.bootstrapper_plugin_infos();
+    let bootstrap = component_schema(
+        &definitions,
+        &bootstrap_infos,
+        &bootstrap_common,
+        &["kind"],
+        "Inline bootstrap provider configuration. `kind` selects the bootstrap plugin.",
+    );
+    let mut top_level_common = bootstrap_common.clone();
+    top_level_common["id"] =
+        json!({ "type": "string", "description": "Identifier referenced by `bootstrapProvider`" });
+    let top_level_bootstrap = component_schema(
+        &definitions,
+        &bootstrap_infos,
+        &top_level_common,
+        &["id", "kind"],
+        "Top-level bootstrap provider configuration referenced by sources.",
+    );
+
+    let mut identity_variants = vec![password_identity_variant(&identity_common)];
+    identity_variants.extend(
+        identity_infos
+            .iter()
+            .filter(|info| info.kind != BUILTIN_PASSWORD_KIND)
+            .map(|info| plugin_variant(&definitions, info, &identity_common, &["id", "kind"])),
+    );
+    let identity = json!({
+        "description": "Identity provider configuration. `kind` selects the provider.",
+        "oneOf": identity_variants,
+    });
+
+    definitions.insert("SourceConfig".to_string(), sources);
+    definitions.insert("ReactionConfig".to_string(), reactions);
+    definitions.insert("BootstrapProviderConfig".to_string(), bootstrap);
+    definitions.insert(
+        "TopLevelBootstrapProviderConfig".to_string(),
+        top_level_bootstrap,
+    );
+    definitions.insert("IdentityProviderConfig".to_string(), identity);
+
+    for name in ["DrasiServerConfig", "DrasiLibInstanceConfig"] {
+        if let Some(schema) = definitions.get_mut(name) {
+            link_component_lists(schema);
+        }
+    }
+    fill_missing_definitions(&mut definitions);
+
+    let mut root = match definitions.get("DrasiServerConfig") {
+        Some(Value::Object(schema)) => schema.clone(),
+        _ => Map::new(),
+    };
+    root.insert("$schema".to_string(), json!(JSON_SCHEMA_DRAFT));
+    root.insert("title".to_string(), json!("Drasi Server configuration"));
+    root.insert("definitions".to_string(), Value::Object(definitions));
+    Value::Object(root)
+}
+
+/// Add the schemas a plugin ships (its config DTO and anything it
+/// references) to `definitions`.
+fn insert_plugin_schemas(definitions: &mut Map<String, Value>, info: &PluginKindInfo) {
+    let schemas: Map<String, Value> = match serde_json::from_str(&info.config_schema_json) {
+        Ok(schemas) => schemas,
+        Err(e) => {
+            log::warn!(
+                "Failed to parse config schema of plugin kind '{}': {e}",
+                info.kind
+            );
+            return;
+        }
+    };
+    definitions.extend(schemas);
+}
+
+/// Convert an OpenAPI 3.0 schema object into draft-07 JSON Schema in place:
+/// `$ref`s point at `definitions`, `nullable` becomes a `null` alternative,
+/// and OpenAPI-only keywords are dropped.
+fn to_json_schema(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                if let Some(name) = reference.strip_prefix(OPENAPI_REF_PREFIX) {
+                    let converted = format!("{DEFINITIONS_REF_PREFIX}{name}");
+                    *reference = converted;
+                }
+            }
+            map.remove("discriminator");
+            for child in map.values_mut() {
+                to_json_schema(child);
+            }
+            if map.remove("nullable") == Some(Value::Bool(true)) {
+                match map.get_mut("type") {
+                    Some(Value::String(ty)) => {
+                        let ty = std::mem::take(ty);
+                        map.insert("type".to_string(), json!([ty, "null"]));
+                    }
+                    _ => {
+                        let inner = std::mem::take(map);
+                        map.insert(
+                            "anyOf".to_string(),
+                            json!([Value::Object(inner), { "type": "null" }]),
+                        );
+                    }
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(to_json_schema),
+        _ => {}
+    }
+}
+
+/// `ConfigValue<T>` fields accept a `${VAR}` reference in place of any `T`;
+/// widen the non-string wrappers so editors do not flag them.
+fn allow_env_references(definitions: &mut Map<String, Value>) {
+    for (name, schema) in definitions.iter_mut() {
+        if !name.starts_with("ConfigValue") || name == "ConfigValueString" || name == "ConfigValue"
+        {
+            continue;
+        }
+        let original = std::mem::take(schema);
+        *schema = json!({
+            "anyOf": [
+                original,
+                { "type": "string", "pattern": ENV_REFERENCE_PATTERN },
+            ]
+        });
+    }
+}
+
+fn source_common_properties() -> Value {
+    json!({
+        "kind": { "type": "string" },
+        "id": { "type": "string", "description": "Unique source identifier" },
+        "autoStart": { "type": "boolean", "default": true },
+        "bootstrapProvider": {
+            "description": "Id of a top-level bootstrap provider, or an inline definition",
+            "oneOf": [
+                { "type": "string" },
+                { "$ref": "#/definitions/BootstrapProviderConfig" },
+            ]
+        },
+        "identityProvider": {
+            "type": "string",
+            "description": "Id of an entry in `identityProviders`"
+        },
+        "restartPolicy": { "$ref": "#/definitions/RestartPolicy" },
+        "configVersion": {
+            "type": "string",
+            "description": "Plugin config version this entry was written against"
+        },
+    })
+}
+
+fn reaction_common_properties() -> Value {
+    json!({
+        "kind": { "type": "string" },
+        "id": { "type": "string", "description": "Unique reaction identifier" },
+        "queries": {
+            "type": "array",
+            "items": { "type": "string" },
+            "description": "Ids of the queries this reaction subscribes to"
+        },
+        "autoStart": { "type": "boolean", "default": true },
+        "identityProvider": {
+            "type": "string",
+            "description": "Id of an entry in `identityProviders`"
+        },
+        "restartPolicy": { "$ref": "#/definitions/RestartPolicy" },
+        "configVersion": {
+            "type": "string",
+            "description": "Plugin config version this entry was written against"
+        },
+    })
+}
+
+/// A `oneOf` over `infos`, one variant per plugin kind. Without any
+/// installed plugin of the category the entry is left open apart from the
+/// common fields (an empty `oneOf` would reject everything).
+fn component_schema(
+    definitions: &Map<String, Value>,
+    infos: &[PluginKindInfo],
+    common: &Value,
+    required: &[&str],
+    description: &str,
+) -> Value {
+    if infos.is_empty() {
+        return json!({
+            "type": "object",
+            "description": description,
+            "properties": common,
+            "required": required,
+        });
+    }
+    let variants: Vec<Value> = infos
+        .iter()
+        .map(|info| plugin_variant(definitions, info, common, required))
+        .collect();
+    json!({ "description": description, "oneOf": variants })
+}
+
+/// One `oneOf` variant: the common fields plus the plugin's own config,
+/// with `kind` pinned to the plugin kind.
+///
+/// Plain object schemas are merged into a single object so that plugins
+/// declaring `additionalProperties: false` still accept the common fields;
+/// anything else is combined with `allOf`.
+fn plugin_variant(
+    definitions: &Map<String, Value>,
+    info: &PluginKindInfo,
+    common: &Value,
+    required: &[&str],
+) -> Value {
+    let mut properties = common.as_object().cloned().unwrap_or_default();
+    properties.insert("kind".to_string(), json!({ "const": info.kind }));
+    let mut required: Vec<Value> = required.iter().map(|r| json!(r)).collect();
+
+    let plugin = definitions
+        .get(&info.config_schema_name)
+        .and_then(Value::as_object)
+        .filter(|schema| {
+            !schema.contains_key("$ref")
+                && !schema.contains_key("allOf")
+                && !schema.contains_key("oneOf")
+                && !schema.contains_key("anyOf")
+        });
+    let Some(plugin) = plugin else {
+        let mut variant = json!({
+            "title": info.kind,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        });
+        if definitions.contains_key(&info.config_schema_name) {
+            variant = json!({
+                "title": info.kind,
+                "allOf": [
+                    variant,
+                    { "$ref": format!("{DEFINITIONS_REF_PREFIX}{}", info.config_schema_name) },
+                ]
+            });
+        }
+        return variant;
+    };
+
+    let mut variant = plugin.clone();
+    if let Some(Value::Object(plugin_properties)) = variant.remove("properties") {
+        for (name, schema) in plugin_properties {
+            if name != "kind" {
+                properties.entry(name).or_insert(schema);
+            }
+        }
+    }
+    if let Some(Value::Array(plugin_required)) = variant.remove("required") {
+        for name in plugin_required {
+            if !required.contains(&name) {
+                required.push(name);
+            }
+        }
+    }
+    variant.insert("title".to_string(), json!(info.kind));
+    variant.insert("type".to_string(), json!("object"));
+    variant.insert("properties".to_string(), Value::Object(properties));
+    variant.insert("required".to_string(), Value::Array(required));
+    Value::Object(variant)
+}
+
+/// The built-in `password` identity provider, which has no plugin schema.
+fn password_identity_variant(common: &Value) -> Value {
+    let mut properties = common.as_object().cloned().unwrap_or_default();
+    properties.insert(
+        "kind".to_string(),
+        json!({ "const": BUILTIN_PASSWORD_KIND }),
+    );
+    properties.insert(
+        "username".to_string(),
+        json!({ "$ref": "#/definitions/ConfigValueString" }),
+    );
+    properties.insert(
+        "password".to_string(),
+        json!({ "$ref": "#/definitions/ConfigValueString" }),
+    );
+    json!({
+        "title": BUILTIN_PASSWORD_KIND,
+        "type": "object",
+        "properties": properties,
+        "required": ["id", "kind", "username", "password"],
+    })
+}
+
+/// Point the opaque component lists of a config schema at the per-kind
+/// definitions.
+fn link_component_lists(schema: &mut Value) {
+    let Some(properties) = schema.get_mut("properties").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for (list, definition) in [
+        ("sources", "SourceConfig"),
+        ("reactions", "ReactionConfig"),
+        ("identityProviders", "IdentityProviderConfig"),
+        ("bootstrapProviders", "TopLevelBootstrapProviderConfig"),
+    ] {
+        if let Some(Value::Object(property)) = properties.get_mut(list) {
+            property.insert("type".to_string(), json!("array"));
+            property.insert(
+                "items".to_string(),
+                json!({ "$ref": format!("{DEFINITIONS_REF_PREFIX}{definition}") }),
+            );
+        }
+    }
+}
+
+/// Types without a `ToSchema` impl (e.g. drasi-lib's `StateStoreConfig`)
+/// are referenced by name only. Accept anything for them rather than
+/// leaving a dangling `$ref` that editors report as an error.
+fn fill_missing_definitions(definitions: &mut Map<String, Value>) {
+    let mut targets = Vec::new();
+    for schema in definitions.values() {
+        collect_refs(schema, &mut targets);
+    }
+    for target in targets {
+        if let Some(name) = target.strip_prefix(DEFINITIONS_REF_PREFIX) {
+            if !definitions.contains_key(name) {
+                definitions.insert(name.to_string(), json!({}));
+            }
+        }
+    }
+}
+
+/// Collect every `$ref` target in `value`.
+fn collect_refs(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get("$ref") {
+                out.push(reference.clone());
+            }
+            map.values().for_each(|v| collect_refs(v, out));
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_refs(v, out)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn core_schema() -> Value {
+        let mut registry = PluginRegistry::new();
+        crate::server::register_core_plugins(&mut registry);
+        config_json_schema(&registry)
+    }
+
+    #[test]
+    fn test_schema_root_describes_server_config() {
+        let schema = core_schema();
+        assert_eq!(schema["$schema"], JSON_SCHEMA_DRAFT);
+        assert!(schema["properties"]["port"].is_object());
+        assert_eq!(
+            schema["properties"]["reactions"]["items"]["$ref"],
+            "#/definitions/ReactionConfig"
+        );
+        assert_eq!(
+            schema["definitions"]["DrasiLibInstanceConfig"]["properties"]["sources"]["items"]
+                ["$ref"],
+            "#/definitions/SourceConfig"
+        );
+    }
+
+    #[test]
+    fn test_component_variants_are_selected_by_kind() {
+        let schema = core_schema();
+        let definitions = &schema["definitions"];
+
+        let reactions = definitions["ReactionConfig"]["oneOf"].as_array().unwrap();
+        let application = reactions
+            .iter()
+            .find(|v| v["properties"]["kind"]["const"] == "application")
+            .unwrap();
+        assert!(application["properties"]["queries"].is_object());
+        let required = application["required"].as_array().unwrap();
+        assert!(required.contains(&json!("id")));
+        assert!(required.contains(&json!("kind")));
+
+        let bootstrap_kinds: Vec<&Value> = definitions["BootstrapProviderConfig"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| &v["properties"]["kind"]["const"])
+            .collect();
+        assert!(bootstrap_kinds.contains(&&json!("noop")));
+        assert!(bootstrap_kinds.contains(&&json!("application")));
+
+        let identity = definitions["IdentityProviderConfig"]["oneOf"]
+            .as_array()
+            .unwrap();
+        assert_eq!(identity[0]["properties"]["kind"]["const"], "password");
+
+        // No source plugins are built in: entries stay open but keep the
+        // common fields.
+        assert_eq!(definitions["SourceConfig"]["type"], "object");
+        assert!(definitions["SourceConfig"]["properties"]["bootstrapProvider"].is_object());
+    }
+
+    #[test]
+    fn test_schema_is_self_contained_json_schema() {
+        let schema = core_schema();
+        let text = schema.to_string();
+        assert!(!text.contains(OPENAPI_REF_PREFIX));
+        assert!(!text.contains("\"discriminator\""));
+        assert!(!text.contains("\"nullable\""));
+
+        let mut targets = Vec::new();
+        collect_refs(&schema, &mut targets);
+        for target in targets {
+            let name = target.strip_prefix(DEFINITIONS_REF_PREFIX).unwrap();
+            assert!(
+                schema["definitions"].get(name).is_some(),
+                "dangling reference {target}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_json_schema_converts_nullable() {
+        let mut typed = json!({ "type": "string", "nullable": true });
+        to_json_schema(&mut typed);
+        assert_eq!(typed, json!({ "type": ["string", "null"] }));
+
+        let mut reference = json!({
+            "allOf": [{ "$ref": "#/components/schemas/RestartPolicy" }],
+            "nullable": true
+        });
+        to_json_schema(&mut reference);
+        assert_eq!(
+            reference,
+            json!({ "anyOf": [
+                { "allOf": [{ "$ref": "#/definitions/RestartPolicy" }] },
+                { "type": "null" }
+            ]})
+        );
+    }
+}
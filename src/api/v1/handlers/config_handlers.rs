@@ -0,0 +1,209 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Config export, schema and revision history v1 API handler wrappers.
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::HeaderMap,
+    response::{Json, Response},
+};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::api::models::config_revision::{ConfigRevisionDiff, RollbackParams, RollbackResponse};
+use crate::api::shared::config_export;
+use crate::api::shared::config_revisions;
+use crate::api::shared::error::{ensure_mode_allows, ErrorResponse};
+use crate::api::shared::ApiResponse;
+use crate::api::v1::config_schema::config_json_schema;
+use crate::config::{ModeOperation, ServerMode};
+use crate::config_revisions::ConfigRevision;
+use crate::instance_registry::InstanceRegistry;
+use crate::persistence::ConfigPersistence;
+use crate::plugin_registry::PluginRegistry;
+
+/// Export the running configuration
+///
+/// Returns the running configuration of every instance as a config file
+/// that can be loaded as-is by another server. Secret fields (those a
+/// plugin schema marks `writeOnly` or `format: password`) are replaced with
+/// `${VAR}` references named after the component and field. The response
+/// is YAML when `Accept` names a YAML type and JSON otherwise.
+#[utoipa::path(
+    get,
+    path = "/api/v1/config",
+    responses(
+        (status = 200, description = "Running configuration in config file format", body = serde_json::Value,
+            content_type = ["application/json", "application/yaml"]),
+        (status = 500, description = "Failed to export the configuration"),
+    ),
+    tag = "Config"
+)]
+pub async fn export_config(
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(persistence): Extension<Option<Arc<ConfigPersistence>>>,
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+    headers: HeaderMap,
+) -> Result<Response, ErrorResponse> {
+    config_export::export_config(&registry, persistence, &plugin_registry, None, &headers).await
+}
+
+/// Export the running configuration of an instance
+///
+/// Like `GET /api/v1/config`, but returns only this instance, in
+/// single-instance config file format.
+#[utoipa::path(
+    get,
+    path = "/api/v1/instances/{instanceId}/config",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID")
+    ),
+    responses(
+        (status = 200, description = "Instance configuration in config file format", body = serde_json::Value,
+            content_type = ["application/json", "application/yaml"]),
+        (status = 404, description = "Instance not found"),
+        (status = 500, description = "Failed to export the configuration"),
+    ),
+    tag = "Instances"
+)]
+pub async fn export_instance_config(
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(persistence): Extension<Option<Arc<ConfigPersistence>>>,
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+    Path(instance_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ErrorResponse> {
+    config_export::export_config(
+        &registry,
+        persistence,
+        &plugin_registry,
+        Some(&instance_id),
+        &headers,
+    )
+    .await
+}
+
+/// Get the JSON Schema of the config file
+///
+/// Returns a draft-07 JSON Schema for the server config file. Each
+/// `sources`, `reactions`, `bootstrapProviders` and `identityProviders`
+/// entry is a `oneOf` over the currently installed plugins, selected by
+/// `kind`, so YAML editors can offer completion and validation.
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/schema",
+    responses(
+        (status = 200, description = "JSON Schema for the server config file", body = serde_json::Value),
+    ),
+    tag = "Config"
+)]
+pub async fn get_config_schema(
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+) -> Json<serde_json::Value> {
+    let registry = plugin_registry.read().await;
+    Json(config_json_schema(&registry))
+}
+
+/// List config revisions
+///
+/// Every save of the config file is recorded as a numbered revision with
+/// its timestamp, the operation that caused it, and its line changes.
+/// Only the most recent revisions are retained.
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/revisions",
+    responses(
+        (status = 200, description = "Config revisions, newest first", body = ApiResponse<Vec<ConfigRevision>>),
+        (status = 503, description = "Configuration persistence is disabled"),
+    ),
+    tag = "Config"
+)]
+pub async fn list_config_revisions(
+    Extension(persistence): Extension<Option<Arc<ConfigPersistence>>>,
+) -> Result<Json<ApiResponse<Vec<ConfigRevision>>>, ErrorResponse> {
+    config_revisions::list_revisions(persistence).await
+}
+
+/// Get the diff of a config revision
+///
+/// Returns the unified diff between the revision and the one before it.
+/// Secret fields are replaced with `${VAR}` references, as in an export.
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/revisions/{revision}/diff",
+    params(
+        ("revision" = u64, Path, description = "Revision number"),
+    ),
+    responses(
+        (status = 200, description = "Revision and its diff", body = ApiResponse<ConfigRevisionDiff>),
+        (status = 404, description = "Revision not found"),
+        (status = 503, description = "Configuration persistence is disabled"),
+    ),
+    tag = "Config"
+)]
+pub async fn get_config_revision_diff(
+    Extension(persistence): Extension<Option<Arc<ConfigPersistence>>>,
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+    Path(revision): Path<u64>,
+) -> Result<Json<ApiResponse<ConfigRevisionDiff>>, ErrorResponse> {
+    config_revisions::get_revision_diff(persistence, &plugin_registry, revision).await
+}
+
+/// Roll back to a config revision
+///
+/// Reconciles the sources, queries, and reactions of every running instance
+/// in the revision to match it, deleting components the revision does not
+/// declare. The result is saved as a new revision. With `dryRun=true` only
+/// the per-instance plans are returned.
+#[utoipa::path(
+    post,
+    path = "/api/v1/config/revisions/{revision}/rollback",
+    params(
+        ("revision" = u64, Path, description = "Revision number to roll back to"),
+        ("dryRun" = Option<bool>, Query, description = "Return the plans without applying them"),
+    ),
+    responses(
+        (status = 200, description = "Planned or applied rollback", body = ApiResponse<RollbackResponse>),
+        (status = 400, description = "Revision cannot be applied"),
+        (status = 404, description = "Revision not found"),
+        (status = 409, description = "Server mode does not allow changes"),
+        (status = 503, description = "Configuration persistence is disabled"),
+    ),
+    tag = "Config"
+)]
+pub async fn rollback_config_revision(
+    Extension(mode): Extension<ServerMode>,
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(persistence): Extension<Option<Arc<ConfigPersistence>>>,
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+    Path(revision): Path<u64>,
+    Query(params): Query<RollbackParams>,
+) -> Result<Json<ApiResponse<RollbackResponse>>, ErrorResponse> {
+    if !params.dry_run {
+        ensure_mode_allows(
+            mode,
+            ModeOperation::Configure,
+            "roll back the configuration",
+        )?;
+    }
+    config_revisions::rollback_to_revision(
+        registry,
+        persistence,
+        &plugin_registry,
+        revision,
+        params,
+    )
+    .await
+}
@@ -18,31 +18,41 @@
 //! path annotations for OpenAPI documentation. The actual business logic
 //! is implemented in the shared handlers module.
 
+mod config_handlers;
 mod query_handlers;
 mod reaction_handlers;
 mod solution_handlers;
 mod source_handlers;
+mod state_store_handlers;
+mod wal_handlers;
 
+pub use config_handlers::*;
 pub use query_handlers::*;
 pub use reaction_handlers::*;
 pub use solution_handlers::*;
 pub use source_handlers::*;
+pub use state_store_handlers::*;
+pub use wal_handlers::*;
 
 use axum::{
     extract::{Extension, Path},
     http::StatusCode,
-    response::Json,
+    response::{Json, Response},
 };
 use serde::Deserialize;
 use std::sync::Arc;
+use tokio::sync::RwLock;
 
+use crate::api::models::{ArchiveUsage, InstanceStorageReport};
 use crate::api::shared::extractor::ConfigBody;
 use crate::api::shared::handlers as shared;
 use crate::api::shared::{
     ApiResponse, ApiVersionsResponse, HealthResponse, InstanceListItem, StatusResponse,
 };
+use crate::config::ServerMode;
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
+use crate::plugin_registry::PluginRegistry;
 
 /// Path parameter for instance-specific routes
 #[derive(Debug, Deserialize)]
@@ -128,14 +138,16 @@ pub async fn list_instances(
 )]
 pub async fn create_instance(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
     ConfigBody(request): ConfigBody<shared::CreateInstanceRequest>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, crate::api::shared::error::ErrorResponse> {
     shared::create_instance(
         Extension(registry),
-        Extension(read_only),
+        Extension(mode),
         Extension(config_persistence),
+        Extension(plugin_registry),
         ConfigBody(request),
     )
     .await
@@ -153,7 +165,9 @@ pub async fn create_instance(
         ("instanceId" = String, Path, description = "DrasiLib instance ID")
     ),
     responses(
-        (status = 200, description = "Configuration snapshot"),
+        (status = 200, description = "Configuration snapshot",
+            headers(("ETag" = String, description = "Current instance revision")),
+        ),
         (status = 404, description = "Instance not found"),
     ),
     tag = "Instances"
@@ -171,3 +185,104 @@ pub async fn get_instance_snapshot(
         )),
     }
 }
+
+/// Get archive retention status and index disk usage
+///
+/// Reports the bytes on disk under the instance's persistent index and,
+/// when the instance has an `archiveRetention` policy, the result of the
+/// last retention pass over each query's archive.
+#[utoipa::path(
+    get,
+    path = "/api/v1/instances/{instanceId}/archive",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID")
+    ),
+    responses(
+        (status = 200, description = "Archive retention status", body = ApiResponse<ArchiveUsage>),
+        (status = 404, description = "Instance not found"),
+    ),
+    tag = "Instances"
+)]
+pub async fn get_instance_archive(
+    Extension(registry): Extension<InstanceRegistry>,
+    Path(InstancePath { instance_id }): Path<InstancePath>,
+) -> Result<Json<ApiResponse<ArchiveUsage>>, crate::api::shared::error::ErrorResponse> {
+    crate::api::shared::archive::archive_usage(&registry, &instance_id).await
+}
+
+/// Get storage usage of an instance
+///
+/// Reports bytes on disk for the instance's index, archive, WAL and state
+/// store, the element and result counts of each query, and the WAL backlog
+/// of each source.
+#[utoipa::path(
+    get,
+    path = "/api/v1/instances/{instanceId}/storage",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID")
+    ),
+    responses(
+        (status = 200, description = "Storage usage", body = ApiResponse<InstanceStorageReport>),
+        (status = 404, description = "Instance not found"),
+    ),
+    tag = "Instances"
+)]
+pub async fn get_instance_storage(
+    Extension(registry): Extension<InstanceRegistry>,
+    Path(InstancePath { instance_id }): Path<InstancePath>,
+) -> Result<Json<ApiResponse<InstanceStorageReport>>, crate::api::shared::error::ErrorResponse> {
+    crate::api::shared::storage::instance_storage(&registry, &instance_id).await
+}
+
+/// Export storage usage of all instances as Prometheus metrics
+///
+/// The same data as `GET /api/v1/instances/{instanceId}/storage`, for every
+/// instance, in the Prometheus text exposition format.
+#[utoipa::path(
+    get,
+    path = "/api/v1/metrics",
+    responses(
+        (status = 200, description = "Prometheus metrics", content_type = "text/plain", body = String),
+    ),
+    tag = "Instances"
+)]
+pub async fn get_storage_metrics(Extension(registry): Extension<InstanceRegistry>) -> Response {
+    crate::api::shared::storage::storage_metrics(&registry).await
+}
+
+/// Back up an instance's durable data
+///
+/// Returns a gzipped tarball with the instance's index, WAL, state store
+/// contents and config section (secrets redacted). Running sources are
+/// stopped while the copy is taken and started again afterwards. Restore it
+/// with `drasi-server restore`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/instances/{instanceId}/backup",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID")
+    ),
+    responses(
+        (status = 200, description = "Backup archive", content_type = "application/gzip", body = Vec<u8>),
+        (status = 404, description = "Instance not found"),
+        (status = 409, description = "The server mode forbids stopping sources"),
+        (status = 500, description = "The backup could not be taken"),
+    ),
+    tag = "Instances"
+)]
+pub async fn backup_instance(
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
+    Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+    Path(InstancePath { instance_id }): Path<InstancePath>,
+) -> Result<Response, crate::api::shared::error::ErrorResponse> {
+    crate::api::shared::backup::backup_instance(
+        &registry,
+        mode,
+        config_persistence,
+        &plugin_registry,
+        &instance_id,
+    )
+    .await
+}
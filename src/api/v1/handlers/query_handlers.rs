@@ -22,11 +22,15 @@ use axum::{
 use std::convert::Infallible;
 use std::sync::Arc;
 
-use crate::api::models::{ComponentEventDto, LogMessageDto, QueryConfigDto};
+use crate::api::models::{
+    ComponentEventDto, LogMessageDto, QueryConfigDto, QueryRebootstrapResponse,
+};
 use crate::api::shared::error::{error_codes, ErrorResponse};
 use crate::api::shared::extractor::ConfigBody;
 use crate::api::shared::handlers::{ComponentViewQuery, ObservabilityQuery};
+use crate::api::shared::rebootstrap;
 use crate::api::shared::{ApiResponse, ComponentListItem, StatusResponse};
+use crate::config::ServerMode;
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
 
@@ -78,7 +82,7 @@ pub async fn list_queries(
 )]
 pub async fn create_query(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Path(InstancePath { instance_id }): Path<InstancePath>,
     ConfigBody(config): ConfigBody<QueryConfigDto>,
@@ -89,7 +93,7 @@ pub async fn create_query(
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
     shared::create_query(
         Extension(core),
-        Extension(read_only),
+        Extension(mode),
         Extension(config_persistence),
         Extension(instance_id),
         ConfigBody(config),
@@ -107,7 +111,9 @@ pub async fn create_query(
         ("view" = Option<String>, Query, description = "Use view=full to include config")
     ),
     responses(
-        (status = 200, description = "Query found", body = ApiResponse),
+        (status = 200, description = "Query found", body = ApiResponse,
+            headers(("ETag" = String, description = "Current revision")),
+        ),
         (status = 404, description = "Query not found"),
     ),
     tag = "Queries"
@@ -158,7 +164,14 @@ pub async fn get_query_events(
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::get_query_events(Extension(core), Path(id), Query(query)).await
+    shared::get_query_events(
+        Extension(core),
+        Extension(registry),
+        Extension(instance_id),
+        Path(id),
+        Query(query),
+    )
+    .await
 }
 
 /// Stream query lifecycle events as SSE
@@ -186,7 +199,13 @@ pub async fn stream_query_events(
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::stream_query_events(Extension(core), Path(id)).await
+    shared::stream_query_events(
+        Extension(core),
+        Extension(registry),
+        Extension(instance_id),
+        Path(id),
+    )
+    .await
 }
 
 /// Get query logs (snapshot)
@@ -250,16 +269,18 @@ pub async fn stream_query_logs(
     path = "/api/v1/instances/{instanceId}/queries/{id}",
     params(
         ("instanceId" = String, Path, description = "DrasiLib instance ID"),
-        ("id" = String, Path, description = "Query ID")
+        ("id" = String, Path, description = "Query ID"),
+        ("If-Match" = Option<String>, Header, description = "Only apply the change if the current ETag matches")
     ),
     responses(
         (status = 200, description = "Query deleted successfully", body = ApiResponse),
+        (status = 412, description = "If-Match does not match the current revision"),
     ),
     tag = "Queries"
 )]
 pub async fn delete_query(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
@@ -269,7 +290,7 @@ pub async fn delete_query(
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
     shared::delete_query(
         Extension(core),
-        Extension(read_only),
+        Extension(mode),
         Extension(config_persistence),
         Extension(instance_id),
         Path(id),
@@ -288,19 +309,21 @@ pub async fn delete_query(
     responses(
         (status = 200, description = "Query started successfully", body = ApiResponse),
         (status = 404, description = "Query not found"),
+        (status = 409, description = "Server is in read-only mode"),
         (status = 500, description = "Internal server error"),
     ),
     tag = "Queries"
 )]
 pub async fn start_query(
     Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
     Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
     let core = registry
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::start_query(Extension(core), Path(id)).await
+    shared::start_query(Extension(core), Extension(mode), Path(id)).await
 }
 
 /// Stop a query
@@ -314,19 +337,54 @@ pub async fn start_query(
     responses(
         (status = 200, description = "Query stopped successfully", body = ApiResponse),
         (status = 404, description = "Query not found"),
+        (status = 409, description = "Server is in read-only mode"),
         (status = 500, description = "Internal server error"),
     ),
     tag = "Queries"
 )]
 pub async fn stop_query(
     Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
     Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
     let core = registry
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::stop_query(Extension(core), Path(id)).await
+    shared::stop_query(Extension(core), Extension(mode), Path(id)).await
+}
+
+/// Re-bootstrap a query
+///
+/// Discards the query's index, persistent or in-memory, and bootstraps it
+/// again from its sources' bootstrap providers. A running query is restarted
+/// at once; a stopped one bootstraps when next started. Queries with their
+/// own persistent `storageBackend` are refused. Follow the query's
+/// events for progress: it reports `Starting` until the bootstrap completes
+/// and `Running` afterwards. Subscribed reactions are left in place and
+/// receive the bootstrapped results.
+#[utoipa::path(
+    post,
+    path = "/api/v1/instances/{instanceId}/queries/{id}/rebootstrap",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID"),
+        ("id" = String, Path, description = "Query ID")
+    ),
+    responses(
+        (status = 200, description = "Re-bootstrap started or scheduled", body = ApiResponse<QueryRebootstrapResponse>),
+        (status = 400, description = "The query has bootstrap disabled or its own persistent storage backend"),
+        (status = 404, description = "Instance or query not found"),
+        (status = 409, description = "The server mode forbids restarting queries"),
+        (status = 503, description = "The server did not build the instance"),
+    ),
+    tag = "Queries"
+)]
+pub async fn rebootstrap_query(
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
+    Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
+) -> Result<Json<ApiResponse<QueryRebootstrapResponse>>, ErrorResponse> {
+    rebootstrap::rebootstrap_query(&registry, mode, &instance_id, &id).await
 }
 
 /// Get current results of a query
@@ -28,6 +28,7 @@ use crate::api::shared::error::{error_codes, ErrorResponse};
 use crate::api::shared::extractor::ConfigBody;
 use crate::api::shared::handlers::{ComponentViewQuery, ObservabilityQuery};
 use crate::api::shared::{ApiResponse, ComponentListItem, StatusResponse};
+use crate::config::ServerMode;
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
 use crate::plugin_registry::PluginRegistry;
@@ -95,7 +96,7 @@ pub async fn list_reactions(
 )]
 pub async fn create_reaction_handler(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
     Path(InstancePath { instance_id }): Path<InstancePath>,
@@ -107,10 +108,11 @@ pub async fn create_reaction_handler(
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
     shared::create_reaction_handler(
         Extension(core),
-        Extension(read_only),
+        Extension(mode),
         Extension(config_persistence),
         Extension(instance_id),
         Extension(plugin_registry),
+        Extension(registry),
         ConfigBody(config_json),
     )
     .await
@@ -135,19 +137,23 @@ pub async fn create_reaction_handler(
     path = "/api/v1/instances/{instanceId}/reactions/{id}",
     params(
         ("instanceId" = String, Path, description = "DrasiLib instance ID"),
-        ("id" = String, Path, description = "Reaction ID")
+        ("id" = String, Path, description = "Reaction ID"),
+        ("If-Match" = Option<String>, Header, description = "Only apply the change if the current ETag matches")
     ),
     request_body = ref("#/components/schemas/ReactionConfig"),
     responses(
-        (status = 200, description = "Reaction created or updated successfully", body = ApiResponse),
+        (status = 200, description = "Reaction created or updated successfully", body = ApiResponse,
+            headers(("ETag" = String, description = "Current revision")),
+        ),
         (status = 400, description = "Invalid reaction configuration"),
         (status = 500, description = "Internal server error"),
+        (status = 412, description = "If-Match does not match the current revision"),
     ),
     tag = "Reactions"
 )]
 pub async fn upsert_reaction_handler(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
     Path(path): Path<ResourcePath>,
@@ -159,10 +165,11 @@ pub async fn upsert_reaction_handler(
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
     shared::upsert_reaction_handler(
         Extension(core),
-        Extension(read_only),
+        Extension(mode),
         Extension(config_persistence),
         Extension(path.instance_id),
         Extension(plugin_registry),
+        Extension(registry),
         Path(path.id),
         ConfigBody(config_json),
     )
@@ -181,7 +188,9 @@ pub async fn upsert_reaction_handler(
         ("view" = Option<String>, Query, description = "Use view=full to include config")
     ),
     responses(
-        (status = 200, description = "Reaction found", body = ApiResponse),
+        (status = 200, description = "Reaction found", body = ApiResponse,
+            headers(("ETag" = String, description = "Current revision")),
+        ),
         (status = 404, description = "Reaction not found"),
     ),
     tag = "Reactions"
@@ -232,7 +241,14 @@ pub async fn get_reaction_events(
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::get_reaction_events(Extension(core), Path(id), Query(query)).await
+    shared::get_reaction_events(
+        Extension(core),
+        Extension(registry),
+        Extension(instance_id),
+        Path(id),
+        Query(query),
+    )
+    .await
 }
 
 /// Stream reaction lifecycle events as SSE
@@ -260,7 +276,13 @@ pub async fn stream_reaction_events(
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::stream_reaction_events(Extension(core), Path(id)).await
+    shared::stream_reaction_events(
+        Extension(core),
+        Extension(registry),
+        Extension(instance_id),
+        Path(id),
+    )
+    .await
 }
 
 /// Get reaction logs (snapshot)
@@ -324,16 +346,18 @@ pub async fn stream_reaction_logs(
     path = "/api/v1/instances/{instanceId}/reactions/{id}",
     params(
         ("instanceId" = String, Path, description = "DrasiLib instance ID"),
-        ("id" = String, Path, description = "Reaction ID")
+        ("id" = String, Path, description = "Reaction ID"),
+        ("If-Match" = Option<String>, Header, description = "Only apply the change if the current ETag matches")
     ),
     responses(
         (status = 200, description = "Reaction deleted successfully", body = ApiResponse),
+        (status = 412, description = "If-Match does not match the current revision"),
     ),
     tag = "Reactions"
 )]
 pub async fn delete_reaction(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
@@ -343,9 +367,10 @@ pub async fn delete_reaction(
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
     shared::delete_reaction(
         Extension(core),
-        Extension(read_only),
+        Extension(mode),
         Extension(config_persistence),
         Extension(instance_id),
+        Extension(registry),
         Path(id),
     )
     .await
@@ -362,19 +387,21 @@ pub async fn delete_reaction(
     responses(
         (status = 200, description = "Reaction started successfully", body = ApiResponse),
         (status = 404, description = "Reaction not found"),
+        (status = 409, description = "Server is in read-only mode"),
         (status = 500, description = "Internal server error"),
     ),
     tag = "Reactions"
 )]
 pub async fn start_reaction(
     Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
     Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
     let core = registry
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::start_reaction(Extension(core), Path(id)).await
+    shared::start_reaction(Extension(core), Extension(mode), Path(id)).await
 }
 
 /// Stop a reaction
@@ -388,19 +415,21 @@ pub async fn start_reaction(
     responses(
         (status = 200, description = "Reaction stopped successfully", body = ApiResponse),
         (status = 404, description = "Reaction not found"),
+        (status = 409, description = "Server is in read-only mode"),
         (status = 500, description = "Internal server error"),
     ),
     tag = "Reactions"
 )]
 pub async fn stop_reaction(
     Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
     Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
     let core = registry
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::stop_reaction(Extension(core), Path(id)).await
+    shared::stop_reaction(Extension(core), Extension(mode), Path(id)).await
 }
 
 // ============================================================================
@@ -431,5 +460,10 @@ pub async fn stream_all_component_events(
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    Ok(shared::stream_all_component_events(Extension(core)).await)
+    Ok(shared::stream_all_component_events(
+        Extension(core),
+        Extension(registry),
+        Extension(instance_id),
+    )
+    .await)
 }
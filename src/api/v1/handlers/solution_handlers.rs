@@ -15,21 +15,24 @@
 //! Solution template v1 API handler wrappers.
 
 use axum::{
-    extract::{Extension, Path},
+    extract::{Extension, Path, Query},
     response::Json,
 };
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::api::models::apply::{ApplyManifest, ApplyParams, ApplyResponse};
 use crate::api::models::solution::{
     CreateSolutionTemplateRequest, CreateSolutionTemplateResponse, SolutionDeployRequest,
     SolutionDeployResponse, SolutionTemplateDetail, SolutionTemplateSummary,
 };
-use crate::api::shared::error::{error_codes, ErrorResponse};
+use crate::api::models::validation::{InstanceValidationResponse, ValidateParams};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorResponse};
 use crate::api::shared::extractor::ConfigBody;
 use crate::api::shared::handlers as shared;
-use crate::api::shared::solutions;
 use crate::api::shared::ApiResponse;
+use crate::api::shared::{apply, solutions};
+use crate::config::{ModeOperation, ServerMode};
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
 use crate::plugin_registry::PluginRegistry;
@@ -88,19 +91,14 @@ pub async fn get_solution(
     tag = "Catalog"
 )]
 pub async fn create_solution_template(
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(registry): Extension<InstanceRegistry>,
     Extension(persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(solutions_dir): Extension<Option<String>>,
     Path(InstancePath { instance_id }): Path<InstancePath>,
     ConfigBody(request): ConfigBody<CreateSolutionTemplateRequest>,
 ) -> Result<Json<ApiResponse<CreateSolutionTemplateResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot create solution templates.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "create solution templates")?;
     let core = match registry.get(&instance_id).await {
         Some(c) => c,
         None => {
@@ -131,7 +129,7 @@ pub async fn create_solution_template(
     tag = "Solutions"
 )]
 pub async fn deploy_solution(
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(registry): Extension<InstanceRegistry>,
     Extension(persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(solutions_dir): Extension<Option<String>>,
@@ -139,12 +137,7 @@ pub async fn deploy_solution(
     Path(InstancePath { instance_id }): Path<InstancePath>,
     ConfigBody(request): ConfigBody<SolutionDeployRequest>,
 ) -> Result<Json<ApiResponse<SolutionDeployResponse>>, ErrorResponse> {
-    if *read_only {
-        return Err(ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot deploy solutions.",
-        ));
-    }
+    ensure_mode_allows(mode, ModeOperation::Configure, "deploy solutions")?;
     solutions::deploy_solution(
         registry,
         persistence,
@@ -156,6 +149,101 @@ pub async fn deploy_solution(
     .await
 }
 
+/// Apply a manifest of sources, queries, and reactions to an instance
+///
+/// Compares the manifest (same shape as the `sources`, `queries`, and
+/// `reactions` sections of the config file) with the instance's current
+/// components and creates, updates, or (with `prune=true`) deletes
+/// components to match. With `dryRun=true` only the plan is returned. If an
+/// action fails, completed actions are rolled back and an `APPLY_FAILED`
+/// error is returned.
+#[utoipa::path(
+    post,
+    path = "/api/v1/instances/{instanceId}/apply",
+    params(
+        ("instanceId" = String, Path, description = "Target instance ID"),
+        ("dryRun" = Option<bool>, Query, description = "Return the plan without applying it"),
+        ("prune" = Option<bool>, Query, description = "Delete components missing from the manifest"),
+        ("If-Match" = Option<String>, Header, description = "Only apply if the instance's current ETag (from GET /snapshot) matches"),
+    ),
+    request_body = ApplyManifest,
+    responses(
+        (status = 200, description = "Planned or applied actions", body = ApiResponse<ApplyResponse>,
+            headers(("ETag" = String, description = "Instance revision after the apply")),
+        ),
+        (status = 400, description = "Invalid manifest"),
+        (status = 403, description = "Server is in read-only mode"),
+        (status = 404, description = "Instance not found"),
+        (status = 412, description = "If-Match does not match the instance's current revision"),
+        (status = 500, description = "An action failed and the completed ones were rolled back"),
+    ),
+    tag = "Instances"
+)]
+pub async fn apply_manifest(
+    Extension(mode): Extension<ServerMode>,
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(persistence): Extension<Option<Arc<ConfigPersistence>>>,
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+    Path(InstancePath { instance_id }): Path<InstancePath>,
+    Query(params): Query<ApplyParams>,
+    ConfigBody(manifest): ConfigBody<ApplyManifest>,
+) -> Result<Json<ApiResponse<ApplyResponse>>, ErrorResponse> {
+    if !params.dry_run {
+        ensure_mode_allows(mode, ModeOperation::Configure, "apply manifests")?;
+    }
+    apply::apply_manifest(
+        registry,
+        persistence,
+        &plugin_registry,
+        &instance_id,
+        params,
+        manifest,
+    )
+    .await
+}
+
+/// Validate how an instance's components fit together
+///
+/// Parses every query and checks that query subscriptions, `pipeline`
+/// middleware and reaction `queries` refer to declared components, that
+/// labels and joins match the declared `nodes` / `relations` filters, and
+/// that every component is used. Components in the body (same shape as for
+/// `apply`) are validated as they would be after applying them; send `{}`
+/// to validate the instance as it is. Nothing is changed.
+#[utoipa::path(
+    post,
+    path = "/api/v1/instances/{instanceId}/validate",
+    params(
+        ("instanceId" = String, Path, description = "Target instance ID"),
+        ("prune" = Option<bool>, Query, description = "Validate the manifest alone, ignoring the instance's current components"),
+    ),
+    request_body = ApplyManifest,
+    responses(
+        (status = 200, description = "Validation issues", body = ApiResponse<InstanceValidationResponse>),
+        (status = 400, description = "Invalid manifest"),
+        (status = 404, description = "Instance not found"),
+    ),
+    tag = "Instances"
+)]
+pub async fn validate_instance(
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(persistence): Extension<Option<Arc<ConfigPersistence>>>,
+    Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
+    Path(InstancePath { instance_id }): Path<InstancePath>,
+    Query(params): Query<ValidateParams>,
+    ConfigBody(manifest): ConfigBody<ApplyManifest>,
+) -> Result<Json<ApiResponse<InstanceValidationResponse>>, ErrorResponse> {
+    apply::validate_instance(
+        registry,
+        persistence,
+        &plugin_registry,
+        &instance_id,
+        params,
+        manifest,
+    )
+    .await
+}
+
 /// Clone another instance's configuration into this instance
 ///
 /// Takes an atomic snapshot of the source instance and recreates all
@@ -177,7 +265,7 @@ pub async fn deploy_solution(
 )]
 pub async fn clone_instance(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Path(InstancePath { instance_id }): Path<InstancePath>,
@@ -185,7 +273,7 @@ pub async fn clone_instance(
 ) -> Result<Json<ApiResponse<shared::CloneInstanceResponse>>, ErrorResponse> {
     shared::clone_instance(
         registry,
-        read_only,
+        mode,
         plugin_registry,
         config_persistence,
         &instance_id,
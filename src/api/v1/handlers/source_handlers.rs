@@ -28,6 +28,7 @@ use crate::api::shared::error::{error_codes, ErrorResponse};
 use crate::api::shared::extractor::ConfigBody;
 use crate::api::shared::handlers::{ComponentViewQuery, ObservabilityQuery};
 use crate::api::shared::{ApiResponse, ComponentListItem, StatusResponse};
+use crate::config::ServerMode;
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
 use crate::plugin_registry::PluginRegistry;
@@ -95,7 +96,7 @@ pub async fn list_sources(
 )]
 pub async fn create_source_handler(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
     Path(InstancePath { instance_id }): Path<InstancePath>,
@@ -107,7 +108,7 @@ pub async fn create_source_handler(
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
     shared::create_source_handler(
         Extension(core),
-        Extension(read_only),
+        Extension(mode),
         Extension(config_persistence),
         Extension(instance_id),
         Extension(plugin_registry),
@@ -137,19 +138,23 @@ pub async fn create_source_handler(
     path = "/api/v1/instances/{instanceId}/sources/{id}",
     params(
         ("instanceId" = String, Path, description = "DrasiLib instance ID"),
-        ("id" = String, Path, description = "Source ID")
+        ("id" = String, Path, description = "Source ID"),
+        ("If-Match" = Option<String>, Header, description = "Only apply the change if the current ETag matches")
     ),
     request_body = ref("#/components/schemas/SourceConfig"),
     responses(
-        (status = 200, description = "Source created or updated successfully", body = ApiResponse),
+        (status = 200, description = "Source created or updated successfully", body = ApiResponse,
+            headers(("ETag" = String, description = "Current revision")),
+        ),
         (status = 400, description = "Invalid source configuration"),
         (status = 500, description = "Internal server error"),
+        (status = 412, description = "If-Match does not match the current revision"),
     ),
     tag = "Sources"
 )]
 pub async fn upsert_source_handler(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Extension(plugin_registry): Extension<Arc<RwLock<PluginRegistry>>>,
     Path(path): Path<ResourcePath>,
@@ -161,7 +166,7 @@ pub async fn upsert_source_handler(
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
     shared::upsert_source_handler(
         Extension(core),
-        Extension(read_only),
+        Extension(mode),
         Extension(config_persistence),
         Extension(path.instance_id),
         Extension(plugin_registry),
@@ -184,7 +189,9 @@ pub async fn upsert_source_handler(
         ("view" = Option<String>, Query, description = "Use view=full to include config")
     ),
     responses(
-        (status = 200, description = "Source found", body = ApiResponse),
+        (status = 200, description = "Source found", body = ApiResponse,
+            headers(("ETag" = String, description = "Current revision")),
+        ),
         (status = 404, description = "Source not found"),
     ),
     tag = "Sources"
@@ -235,7 +242,14 @@ pub async fn get_source_events(
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::get_source_events(Extension(core), Path(id), Query(query)).await
+    shared::get_source_events(
+        Extension(core),
+        Extension(registry),
+        Extension(instance_id),
+        Path(id),
+        Query(query),
+    )
+    .await
 }
 
 /// Stream source lifecycle events as SSE
@@ -263,7 +277,13 @@ pub async fn stream_source_events(
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::stream_source_events(Extension(core), Path(id)).await
+    shared::stream_source_events(
+        Extension(core),
+        Extension(registry),
+        Extension(instance_id),
+        Path(id),
+    )
+    .await
 }
 
 /// Get source logs (snapshot)
@@ -327,16 +347,18 @@ pub async fn stream_source_logs(
     path = "/api/v1/instances/{instanceId}/sources/{id}",
     params(
         ("instanceId" = String, Path, description = "DrasiLib instance ID"),
-        ("id" = String, Path, description = "Source ID")
+        ("id" = String, Path, description = "Source ID"),
+        ("If-Match" = Option<String>, Header, description = "Only apply the change if the current ETag matches")
     ),
     responses(
         (status = 200, description = "Source deleted successfully", body = ApiResponse),
+        (status = 412, description = "If-Match does not match the current revision"),
     ),
     tag = "Sources"
 )]
 pub async fn delete_source(
     Extension(registry): Extension<InstanceRegistry>,
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(config_persistence): Extension<Option<Arc<ConfigPersistence>>>,
     Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
@@ -346,9 +368,10 @@ pub async fn delete_source(
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
     shared::delete_source(
         Extension(core),
-        Extension(read_only),
+        Extension(mode),
         Extension(config_persistence),
         Extension(instance_id),
+        Extension(registry),
         Path(id),
     )
     .await
@@ -365,19 +388,21 @@ pub async fn delete_source(
     responses(
         (status = 200, description = "Source started successfully", body = ApiResponse),
         (status = 404, description = "Source not found"),
+        (status = 409, description = "Server is in read-only mode"),
         (status = 500, description = "Internal server error"),
     ),
     tag = "Sources"
 )]
 pub async fn start_source(
     Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
     Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
     let core = registry
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::start_source(Extension(core), Path(id)).await
+    shared::start_source(Extension(core), Extension(mode), Path(id)).await
 }
 
 /// Stop a source
@@ -391,19 +416,21 @@ pub async fn start_source(
     responses(
         (status = 200, description = "Source stopped successfully", body = ApiResponse),
         (status = 404, description = "Source not found"),
+        (status = 409, description = "Server is in read-only mode"),
         (status = 500, description = "Internal server error"),
     ),
     tag = "Sources"
 )]
 pub async fn stop_source(
     Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
     Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
 ) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
     let core = registry
         .get(&instance_id)
         .await
         .ok_or_else(|| ErrorResponse::new(error_codes::INTERNAL_ERROR, "Instance not found"))?;
-    shared::stop_source(Extension(core), Path(id)).await
+    shared::stop_source(Extension(core), Extension(mode), Path(id)).await
 }
 
 /// Push data to a source's listening port (proxy to avoid CORS)
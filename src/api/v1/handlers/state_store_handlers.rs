@@ -0,0 +1,148 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! State store inspection v1 API handler wrappers.
+
+use axum::{
+    extract::{Extension, Path},
+    response::Json,
+};
+use serde::Deserialize;
+
+use crate::api::models::{StateStoreDump, StateStorePartition, StateStoreResetResponse};
+use crate::api::shared::error::ErrorResponse;
+use crate::api::shared::state_stores;
+use crate::api::shared::{ApiResponse, StatusResponse};
+use crate::config::ServerMode;
+use crate::instance_registry::InstanceRegistry;
+
+use super::{InstancePath, ResourcePath};
+
+/// Path parameters for a key of a state store partition
+#[derive(Debug, Deserialize)]
+pub struct StateKeyPath {
+    #[serde(rename = "instanceId")]
+    pub instance_id: String,
+    pub id: String,
+    pub key: String,
+}
+
+/// List state store partitions
+///
+/// Returns the partition of every source and reaction in the instance,
+/// with the component's status and the number of keys it has stored.
+#[utoipa::path(
+    get,
+    path = "/api/v1/instances/{instanceId}/state",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID")
+    ),
+    responses(
+        (status = 200, description = "State store partitions", body = ApiResponse<Vec<StateStorePartition>>),
+        (status = 404, description = "Instance not found"),
+        (status = 503, description = "The instance's state store is not accessible"),
+    ),
+    tag = "State"
+)]
+pub async fn list_state_stores(
+    Extension(registry): Extension<InstanceRegistry>,
+    Path(InstancePath { instance_id }): Path<InstancePath>,
+) -> Result<Json<ApiResponse<Vec<StateStorePartition>>>, ErrorResponse> {
+    state_stores::list_state_stores(&registry, &instance_id).await
+}
+
+/// Dump a state store partition
+///
+/// Returns every key of the partition with its value. UTF-8 values are
+/// returned as is; other values are base64-encoded.
+#[utoipa::path(
+    get,
+    path = "/api/v1/instances/{instanceId}/state/{id}",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID"),
+        ("id" = String, Path, description = "Partition ID (the owning component's ID)")
+    ),
+    responses(
+        (status = 200, description = "Keys and values", body = ApiResponse<StateStoreDump>),
+        (status = 404, description = "Instance or partition not found"),
+        (status = 503, description = "The instance's state store is not accessible"),
+    ),
+    tag = "State"
+)]
+pub async fn get_state_store(
+    Extension(registry): Extension<InstanceRegistry>,
+    Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
+) -> Result<Json<ApiResponse<StateStoreDump>>, ErrorResponse> {
+    state_stores::get_state_store(&registry, &instance_id, &id).await
+}
+
+/// Delete a key from a state store partition
+///
+/// The owning component must not be running. URL-encode keys that contain
+/// `/`.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/instances/{instanceId}/state/{id}/keys/{key}",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID"),
+        ("id" = String, Path, description = "Partition ID (the owning component's ID)"),
+        ("key" = String, Path, description = "Key to delete")
+    ),
+    responses(
+        (status = 200, description = "Key deleted", body = ApiResponse<StatusResponse>),
+        (status = 404, description = "Instance or key not found"),
+        (status = 409, description = "The owning component is running, or the server mode forbids changes"),
+        (status = 503, description = "The instance's state store is not accessible"),
+    ),
+    tag = "State"
+)]
+pub async fn delete_state_key(
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
+    Path(StateKeyPath {
+        instance_id,
+        id,
+        key,
+    }): Path<StateKeyPath>,
+) -> Result<Json<ApiResponse<StatusResponse>>, ErrorResponse> {
+    state_stores::delete_state_key(&registry, mode, &instance_id, &id, &key).await
+}
+
+/// Reset a state store partition
+///
+/// Deletes every key of the partition, so its component starts from
+/// scratch the next time it starts (e.g. re-reads a CDC stream from its
+/// initial position). The owning component must not be running.
+#[utoipa::path(
+    post,
+    path = "/api/v1/instances/{instanceId}/state/{id}/reset",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID"),
+        ("id" = String, Path, description = "Partition ID (the owning component's ID)")
+    ),
+    responses(
+        (status = 200, description = "Partition reset", body = ApiResponse<StateStoreResetResponse>),
+        (status = 404, description = "Instance not found"),
+        (status = 409, description = "The owning component is running, or the server mode forbids changes"),
+        (status = 503, description = "The instance's state store is not accessible"),
+    ),
+    tag = "State"
+)]
+pub async fn reset_state_store(
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
+    Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
+) -> Result<Json<ApiResponse<StateStoreResetResponse>>, ErrorResponse> {
+    state_stores::reset_state_store(&registry, mode, &instance_id, &id).await
+}
@@ -0,0 +1,87 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Source WAL v1 API handler wrappers.
+
+use axum::{
+    extract::{Extension, Path},
+    response::Json,
+};
+
+use crate::api::models::{InstanceWalStatus, ReplayRequest, ReplayResponse};
+use crate::api::shared::error::ErrorResponse;
+use crate::api::shared::extractor::ConfigBody;
+use crate::api::shared::wal;
+use crate::api::shared::ApiResponse;
+use crate::config::ServerMode;
+use crate::instance_registry::InstanceRegistry;
+
+use super::{InstancePath, ResourcePath};
+
+/// Get the WAL state of an instance's sources
+///
+/// For each source that writes a WAL: its head and oldest retained sequence,
+/// the time of the oldest retained event, the file size, and how far each
+/// subscribed persistent query has checkpointed.
+#[utoipa::path(
+    get,
+    path = "/api/v1/instances/{instanceId}/wal",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID")
+    ),
+    responses(
+        (status = 200, description = "WAL state", body = ApiResponse<InstanceWalStatus>),
+        (status = 404, description = "Instance not found"),
+        (status = 503, description = "The server did not build the instance"),
+    ),
+    tag = "Sources"
+)]
+pub async fn get_instance_wal(
+    Extension(registry): Extension<InstanceRegistry>,
+    Path(InstancePath { instance_id }): Path<InstancePath>,
+) -> Result<Json<ApiResponse<InstanceWalStatus>>, ErrorResponse> {
+    wal::instance_wal(&registry, &instance_id).await
+}
+
+/// Replay a source's WAL to its subscribed queries
+///
+/// Rewinds every subscribed persistent query to the given sequence, or to
+/// the first retained event at or after the given time, and restarts the
+/// running ones so the source re-delivers the events after it. In-memory
+/// queries and queries without a checkpoint are reported as skipped.
+#[utoipa::path(
+    post,
+    path = "/api/v1/instances/{instanceId}/sources/{id}/replay",
+    params(
+        ("instanceId" = String, Path, description = "DrasiLib instance ID"),
+        ("id" = String, Path, description = "Source ID")
+    ),
+    request_body = ReplayRequest,
+    responses(
+        (status = 200, description = "Queries rewound", body = ApiResponse<ReplayResponse>),
+        (status = 400, description = "The position is not retained, or the source writes no WAL"),
+        (status = 404, description = "Instance or source not found"),
+        (status = 409, description = "The server mode forbids restarting queries"),
+        (status = 503, description = "The server did not build the instance"),
+    ),
+    tag = "Sources"
+)]
+pub async fn replay_source(
+    Extension(registry): Extension<InstanceRegistry>,
+    Extension(mode): Extension<ServerMode>,
+    Path(ResourcePath { instance_id, id }): Path<ResourcePath>,
+    ConfigBody(request): ConfigBody<ReplayRequest>,
+) -> Result<Json<ApiResponse<ReplayResponse>>, ErrorResponse> {
+    wal::replay_source(&registry, mode, &instance_id, &id, request).await
+}
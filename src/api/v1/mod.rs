@@ -49,11 +49,13 @@
 //! - `/api/v1/queries` - Queries of the first instance
 //! - `/api/v1/reactions` - Reactions of the first instance
 
+pub mod config_schema;
 pub mod handlers;
 pub mod openapi;
 pub mod plugin_handlers;
 pub mod routes;
 
+pub use config_schema::config_json_schema;
 pub use handlers::*;
 pub use openapi::inject_plugin_schemas;
 pub use openapi::ApiDocV1;
@@ -20,16 +20,21 @@
 
 use utoipa::OpenApi;
 
+use crate::api::models::apply::{ApplyAction, ApplyActionKind, ApplyManifest, ApplyResponse};
+use crate::api::models::config_revision::{ConfigRevisionDiff, InstanceRollback, RollbackResponse};
 use crate::api::models::solution::{
     CreateSolutionTemplateRequest, CreateSolutionTemplateResponse, DeployPhase,
     SolutionDeployError, SolutionDeployRequest, SolutionDeployResponse, SolutionTemplateDetail,
     SolutionTemplateMetadata, SolutionTemplateSummary, SolutionVariable,
 };
+use crate::api::models::validation::InstanceValidationResponse;
 use crate::api::models::{
-    ComponentEventDto, ComponentStatusDto, ComponentTypeDto, ConfigValueBoolSchema,
-    ConfigValueStringSchema, ConfigValueU16Schema, ConfigValueU32Schema, ConfigValueU64Schema,
-    ConfigValueUsizeSchema, LogLevelDto, LogMessageDto, QueryConfigDto, RedbStateStoreConfigDto,
-    SourceMiddlewareConfigDto, SourceSubscriptionConfigDto,
+    ArchiveRetentionConfig, ArchiveRetentionLimits, ArchiveUsage, ComponentEventDto,
+    ComponentStatusDto, ComponentTypeDto, ConfigValueBoolSchema, ConfigValueStringSchema,
+    ConfigValueU16Schema, ConfigValueU32Schema, ConfigValueU64Schema, ConfigValueUsizeSchema,
+    LogLevelDto, LogMessageDto, QueryConfigDto, RedbStateStoreConfigDto, SourceMiddlewareConfigDto,
+    SourceSubscriptionConfigDto, SqliteStateStoreConfigDto, StateStoreDump, StateStoreEntry,
+    StateStorePartition, StateStoreResetResponse, StateValueEncoding,
 };
 use crate::api::shared::handlers::CreateInstanceRequest;
 use crate::api::shared::handlers::{CloneInstanceRequest, CloneInstanceResponse};
@@ -37,7 +42,8 @@ use crate::api::shared::{
     ApiResponseSchema, ApiVersionsResponse, ComponentListItem, ErrorDetail, ErrorResponse,
     HealthResponse, InstanceLinks, InstanceListItem, StatusResponse,
 };
-use crate::config::{DrasiLibInstanceConfig, DrasiServerConfig};
+use crate::config::{DrasiLibInstanceConfig, DrasiServerConfig, IssueSeverity, SemanticIssue};
+use crate::config_revisions::ConfigRevision;
 use crate::plugin_registry::PluginRegistry;
 use std::collections::BTreeMap;
 use utoipa::openapi::schema::{AllOf, Discriminator, ObjectBuilder, OneOf, Ref, Schema};
@@ -51,6 +57,12 @@ use utoipa::openapi::RefOr;
         super::handlers::list_instances,
         super::handlers::create_instance,
         super::handlers::get_instance_snapshot,
+        super::handlers::get_instance_archive,
+        super::handlers::get_instance_storage,
+        super::handlers::get_storage_metrics,
+        super::handlers::get_instance_wal,
+        super::handlers::backup_instance,
+        super::handlers::export_instance_config,
         super::handlers::list_sources,
         super::handlers::create_source_handler,
         super::handlers::upsert_source_handler,
@@ -62,6 +74,7 @@ use utoipa::openapi::RefOr;
         super::handlers::delete_source,
         super::handlers::start_source,
         super::handlers::stop_source,
+        super::handlers::replay_source,
         super::handlers::list_queries,
         super::handlers::create_query,
         super::handlers::get_query,
@@ -72,6 +85,7 @@ use utoipa::openapi::RefOr;
         super::handlers::delete_query,
         super::handlers::start_query,
         super::handlers::stop_query,
+        super::handlers::rebootstrap_query,
         super::handlers::get_query_results,
         super::handlers::attach_query_stream,
         super::handlers::list_reactions,
@@ -90,6 +104,19 @@ use utoipa::openapi::RefOr;
         super::handlers::create_solution_template,
         super::handlers::deploy_solution,
         super::handlers::clone_instance,
+        super::handlers::apply_manifest,
+        super::handlers::validate_instance,
+        // State store inspection
+        super::handlers::list_state_stores,
+        super::handlers::get_state_store,
+        super::handlers::delete_state_key,
+        super::handlers::reset_state_store,
+        // Config schema and revision history
+        super::handlers::export_config,
+        super::handlers::get_config_schema,
+        super::handlers::list_config_revisions,
+        super::handlers::get_config_revision_diff,
+        super::handlers::rollback_config_revision,
         // Plugin management
         super::plugin_handlers::list_plugins,
         super::plugin_handlers::get_plugin,
@@ -113,6 +140,17 @@ use utoipa::openapi::RefOr;
             CreateInstanceRequest,
             CloneInstanceRequest,
             CloneInstanceResponse,
+            ApplyManifest,
+            ApplyAction,
+            ApplyActionKind,
+            ApplyResponse,
+            InstanceValidationResponse,
+            SemanticIssue,
+            IssueSeverity,
+            ConfigRevision,
+            ConfigRevisionDiff,
+            InstanceRollback,
+            RollbackResponse,
             ApiVersionsResponse,
             ErrorResponse,
             ErrorDetail,
@@ -123,10 +161,34 @@ use utoipa::openapi::RefOr;
             LogMessageDto,
             DrasiServerConfig,
             DrasiLibInstanceConfig,
+            crate::config::ServerMode,
             QueryConfigDto,
             SourceSubscriptionConfigDto,
             SourceMiddlewareConfigDto,
+            crate::api::models::QueryRebootstrapResponse,
+            crate::api::models::RestartPolicy,
+            crate::api::models::RestartMode,
             RedbStateStoreConfigDto,
+            SqliteStateStoreConfigDto,
+            StateStorePartition,
+            StateStoreEntry,
+            StateValueEncoding,
+            StateStoreDump,
+            StateStoreResetResponse,
+            ArchiveRetentionConfig,
+            ArchiveRetentionLimits,
+            ArchiveUsage,
+            crate::api::models::QueryArchiveStatus,
+            crate::api::models::InstanceStorageReport,
+            crate::api::models::QueryStorageUsage,
+            crate::api::models::SourceWalUsage,
+            crate::api::models::WalRetentionConfig,
+            crate::api::models::InstanceWalStatus,
+            crate::api::models::SourceWalStatus,
+            crate::api::models::QueryWalCheckpoint,
+            crate::api::models::ReplayRequest,
+            crate::api::models::ReplayResponse,
+            crate::api::models::SkippedReplay,
             ConfigValueStringSchema,
             ConfigValueU16Schema,
             ConfigValueU32Schema,
@@ -148,6 +210,8 @@ use utoipa::openapi::RefOr;
             super::plugin_handlers::PluginListResponse,
             super::plugin_handlers::PluginInfoDto,
             super::plugin_handlers::PluginKindDto,
+            crate::plugin_isolation::CircuitBreakerStatus,
+            crate::plugin_isolation::CircuitState,
             super::plugin_handlers::PluginKindsResponse,
             super::plugin_handlers::PluginKindInfoDto,
             super::plugin_handlers::PluginDependentsResponse,
@@ -166,6 +230,8 @@ use utoipa::openapi::RefOr;
         (name = "Solutions", description = "Deploy solution templates to instances"),
         (name = "Catalog", description = "Browse solution templates and other reusable configurations"),
         (name = "Plugins", description = "Plugin management — load, install, and inspect plugins"),
+        (name = "Config", description = "Config file schema, revision history and rollback"),
+        (name = "State", description = "Inspect and repair state persisted by sources and reactions"),
     ),
     info(
         title = "Drasi Server API",
@@ -24,8 +24,9 @@ use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
 
-use crate::api::shared::error::{error_codes, ErrorResponse};
+use crate::api::shared::error::{ensure_mode_allows, error_codes, ErrorResponse};
 use crate::api::shared::extractor::ConfigBody;
+use crate::config::{ModeOperation, ServerMode};
 use crate::instance_registry::InstanceRegistry;
 use crate::plugin_orchestrator::PluginOrchestrator;
 
@@ -49,6 +50,8 @@ pub struct PluginInfoDto {
     pub loaded_at: String,
     pub dependent_count: usize,
     pub kinds: Vec<PluginKindDto>,
+    /// Crash-loop circuit breaker state for the plugin's components.
+    pub circuit_breaker: crate::plugin_isolation::CircuitBreakerStatus,
 }
 
 /// A single kind provided by a plugin.
@@ -189,16 +192,12 @@ pub async fn list_kinds(
 )]
 /// Load a plugin shared library from the plugins directory by filename.
 pub async fn load_plugin(
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(orchestrator): Extension<Arc<PluginOrchestrator>>,
     ConfigBody(body): ConfigBody<LoadPluginRequest>,
 ) -> impl IntoResponse {
-    if *read_only {
-        return ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot load plugins.",
-        )
-        .into_json_response();
+    if let Err(e) = ensure_mode_allows(mode, ModeOperation::Plugins, "load plugins") {
+        return e.into_json_response();
     }
     let plugins_dir = match orchestrator.plugins_dir() {
         Some(dir) => dir.to_path_buf(),
@@ -266,16 +265,12 @@ pub async fn load_plugin(
 )]
 /// Download and load a plugin from an OCI registry.
 pub async fn install_plugin(
-    Extension(read_only): Extension<Arc<bool>>,
+    Extension(mode): Extension<ServerMode>,
     Extension(orchestrator): Extension<Arc<PluginOrchestrator>>,
     ConfigBody(body): ConfigBody<InstallPluginRequest>,
 ) -> impl IntoResponse {
-    if *read_only {
-        return ErrorResponse::new(
-            error_codes::CONFIG_READ_ONLY,
-            "Server is in read-only mode. Cannot install plugins.",
-        )
-        .into_json_response();
+    if let Err(e) = ensure_mode_allows(mode, ModeOperation::Plugins, "install plugins") {
+        return e.into_json_response();
     }
     // Atomic install + verify + load via the orchestrator
     match orchestrator
@@ -552,7 +547,7 @@ pub async fn search_registry(
 /// same place as the routes. Callers using `plugin_routes()` directly must add
 /// every extension that any plugin handler extracts, including
 /// `Extension<Arc<PluginOrchestrator>>`, `Extension<InstanceRegistry>`, and
-/// `Extension<Arc<bool>>` (the read-only flag used by `load_plugin` /
+/// `Extension<ServerMode>` (the mode checked by `load_plugin` /
 /// `install_plugin`).
 pub fn plugin_routes() -> axum::Router {
     // Schema subrouter — needs to be separate to avoid {plugin_id} conflict
@@ -585,10 +580,10 @@ pub fn plugin_routes() -> axum::Router {
 pub fn build_plugin_router(
     orchestrator: Arc<PluginOrchestrator>,
     instances: InstanceRegistry,
-    read_only: Arc<bool>,
+    mode: ServerMode,
 ) -> axum::Router {
     plugin_routes()
         .layer(Extension(orchestrator))
         .layer(Extension(instances))
-        .layer(Extension(read_only))
+        .layer(Extension(mode))
 }
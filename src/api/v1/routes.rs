@@ -29,7 +29,9 @@ use tokio::sync::RwLock;
 use super::handlers;
 use crate::api::shared::handlers as shared;
 use crate::api::shared::handlers::ApiPrefix;
+use crate::api::shared::preconditions::revision_preconditions;
 use crate::api::version::ApiVersion;
+use crate::config::ServerMode;
 use crate::instance_registry::InstanceRegistry;
 use crate::persistence::ConfigPersistence;
 use crate::plugin_registry::PluginRegistry;
@@ -39,7 +41,7 @@ use crate::plugin_registry::PluginRegistry;
 /// Uses `:instanceId` path parameter for dynamic instance lookup at request time.
 pub fn build_v1_router(
     registry: InstanceRegistry,
-    read_only: Arc<bool>,
+    mode: ServerMode,
     config_persistence: Option<Arc<ConfigPersistence>>,
     plugin_registry: Arc<RwLock<PluginRegistry>>,
     solutions_dir: Option<String>,
@@ -60,13 +62,32 @@ pub fn build_v1_router(
         .route("/catalog/solutions", get(handlers::list_solutions))
         .route("/catalog/solutions/:id", get(handlers::get_solution));
 
+    // Config export, schema and revision history (server-wide)
+    let config_routes = Router::new()
+        .route("/config", get(handlers::export_config))
+        .route("/config/schema", get(handlers::get_config_schema))
+        .route("/config/revisions", get(handlers::list_config_revisions))
+        .route(
+            "/config/revisions/:revision/diff",
+            get(handlers::get_config_revision_diff),
+        )
+        .route(
+            "/config/revisions/:revision/rollback",
+            post(handlers::rollback_config_revision),
+        );
+
+    // Storage usage of all instances in the Prometheus text format
+    let metrics_routes = Router::new().route("/metrics", get(handlers::get_storage_metrics));
+
     Router::new()
         .merge(instance_routes)
         .merge(catalog_routes)
+        .merge(config_routes)
+        .merge(metrics_routes)
         .nest("/instances/:instanceId", instance_resource_routes)
         .merge(default_routes)
         .layer(Extension(registry))
-        .layer(Extension(read_only))
+        .layer(Extension(mode))
         .layer(Extension(config_persistence))
         .layer(Extension(plugin_registry))
         .layer(Extension(solutions_dir))
@@ -82,6 +103,8 @@ fn build_dynamic_instance_router() -> Router {
     Router::new()
         // Instance snapshot
         .route("/snapshot", get(handlers::get_instance_snapshot))
+        // Running configuration in config file format, secrets redacted
+        .route("/config", get(handlers::export_instance_config))
         // Source routes
         .route("/sources", get(handlers::list_sources))
         .route("/sources", post(handlers::create_source_handler))
@@ -100,6 +123,7 @@ fn build_dynamic_instance_router() -> Router {
         .route("/sources/:id", delete(handlers::delete_source))
         .route("/sources/:id/start", post(handlers::start_source))
         .route("/sources/:id/stop", post(handlers::stop_source))
+        .route("/sources/:id/replay", post(handlers::replay_source))
         // Query routes
         .route("/queries", get(handlers::list_queries))
         .route("/queries", post(handlers::create_query))
@@ -114,6 +138,10 @@ fn build_dynamic_instance_router() -> Router {
         .route("/queries/:id", delete(handlers::delete_query))
         .route("/queries/:id/start", post(handlers::start_query))
         .route("/queries/:id/stop", post(handlers::stop_query))
+        .route(
+            "/queries/:id/rebootstrap",
+            post(handlers::rebootstrap_query),
+        )
         .route("/queries/:id/results", get(handlers::get_query_results))
         .route("/queries/:id/attach", get(handlers::attach_query_stream))
         // Reaction routes
@@ -140,13 +168,32 @@ fn build_dynamic_instance_router() -> Router {
         .route("/sources/:id/push", post(handlers::push_source_data))
         // Solution deployment to this instance
         .route("/solutions", post(handlers::deploy_solution))
+        // Declarative apply of a sources/queries/reactions manifest
+        .route("/apply", post(handlers::apply_manifest))
+        // Semantic validation of the instance's pipeline
+        .route("/validate", post(handlers::validate_instance))
+        // Archive index retention status and index disk usage
+        .route("/archive", get(handlers::get_instance_archive))
+        // Disk usage of the index, archive, WAL and state store
+        .route("/storage", get(handlers::get_instance_storage))
+        // Per-source WAL positions, size and checkpoints
+        .route("/wal", get(handlers::get_instance_wal))
+        // Archive the instance's index, WAL, state and config
+        .route("/backup", post(handlers::backup_instance))
         // Clone another instance's configuration into this instance
         .route("/clone", post(handlers::clone_instance))
+        // State components persisted in the instance's state store
+        .route("/state", get(handlers::list_state_stores))
+        .route("/state/:id", get(handlers::get_state_store))
+        .route("/state/:id/keys/:key", delete(handlers::delete_state_key))
+        .route("/state/:id/reset", post(handlers::reset_state_store))
         // Create solution template from this instance's components
         .route(
             "/catalog/solutions",
             post(handlers::create_solution_template),
         )
+        // ETag / If-Match on components and the instance as a whole
+        .route_layer(middleware::from_fn(revision_preconditions))
 }
 
 /// Build convenience routes that operate on the default (first) instance.
@@ -209,6 +256,8 @@ fn build_default_instance_router() -> Router {
         .route("/events", get(shared::stream_all_component_events))
         // Source data push proxy (default instance)
         .route("/sources/:id/push", post(shared::push_source_data))
+        // ETag / If-Match on components
+        .route_layer(middleware::from_fn(revision_preconditions))
         // Apply middleware that resolves the default instance
         .layer(middleware::from_fn(resolve_default_instance))
 }
@@ -0,0 +1,380 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Retention for the archive index.
+//!
+//! With `enableArchive`, every persistent query writes each version of each
+//! element to the `archive` column family of its RocksDB database, keyed by
+//! the 16-byte element hash followed by the version's `effective_from`
+//! (big-endian milliseconds). Nothing in the index removes those versions.
+//!
+//! When an instance sets `archiveRetention`, an [`ArchiveRetention`] task
+//! periodically deletes the versions the policy no longer keeps from the
+//! query databases its index provider recorded in [`IndexDatabases`].
+//!
+//! A pass cannot compact the archive afterwards: drasi-index-rocksdb opens
+//! each query database as a `TransactionDB`, which in `rocksdb` 0.22 offers
+//! no `compact_range_cf` (nor flush or range deletes). The space is
+//! reclaimed only as RocksDB's background compactions reach the files
+//! holding the tombstones, so `bytes` in the archive status reports the
+//! versions kept, not the size on disk.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use chrono::Utc;
+use drasi_index_rocksdb::IndexDb;
+use log::{debug, info, warn};
+use rocksdb::{IteratorMode, WriteBatchWithTransaction};
+use tokio::task::JoinHandle;
+
+use crate::api::models::{ArchiveRetentionConfig, ArchiveRetentionLimits, QueryArchiveStatus};
+use crate::index_provider::IndexDatabases;
+
+/// Column family of the archive index in `drasi-index-rocksdb`.
+pub(crate) const ARCHIVE_CF: &str = "archive";
+
+/// Length of the element hash that prefixes every archive key.
+const ELEMENT_KEY_LEN: usize = 16;
+
+/// Deletes per write batch.
+const DELETE_BATCH_SIZE: usize = 10_000;
+
+/// The `archiveRetention` to enforce for an instance, or `None` when there
+/// is no policy or no persistent archive for it to apply to.
+pub(crate) fn applicable_retention(
+    instance_id: &str,
+    persist_index: bool,
+    enable_archive: bool,
+    retention: Option<&ArchiveRetentionConfig>,
+) -> Option<ArchiveRetentionConfig> {
+    let retention = retention?;
+    if !(persist_index && enable_archive) {
+        warn!(
+            "Ignoring archiveRetention for instance '{instance_id}': it only applies with \
+             persistIndex and enableArchive"
+        );
+        return None;
+    }
+    Some(retention.clone())
+}
+
+/// Result of one pass over a query's archive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneStats {
+    /// Versions left in the archive.
+    pub versions: u64,
+    /// Key and value bytes of the versions left.
+    pub bytes: u64,
+    /// Versions deleted.
+    pub pruned: u64,
+}
+
+/// Background task that enforces an instance's `archiveRetention`.
+///
+/// Stops when dropped.
+pub struct ArchiveRetention {
+    config: ArchiveRetentionConfig,
+    statuses: Arc<Mutex<BTreeMap<String, QueryArchiveStatus>>>,
+    /// Held for reading by each pass; [`Self::pause`] holds it for writing.
+    passes: Arc<tokio::sync::RwLock<()>>,
+    task: JoinHandle<()>,
+}
+
+impl ArchiveRetention {
+    /// Start enforcing `config` on the queries tracked in `databases`.
+    pub fn spawn(
+        instance_id: &str,
+        config: ArchiveRetentionConfig,
+        databases: IndexDatabases,
+    ) -> Self {
+        info!(
+            "Enforcing archive retention for instance '{instance_id}' every {}s",
+            config.interval().as_secs()
+        );
+        let statuses = Arc::new(Mutex::new(BTreeMap::new()));
+        let passes = Arc::new(tokio::sync::RwLock::new(()));
+        let task = tokio::spawn(run(
+            instance_id.to_string(),
+            config.clone(),
+            databases,
+            statuses.clone(),
+            passes.clone(),
+        ));
+        Self {
+            config,
+            statuses,
+            passes,
+            task,
+        }
+    }
+
+    /// Wait for the pass in progress, if any, and start no other until the
+    /// returned guard is dropped. Used while the index is copied.
+    pub async fn pause(&self) -> tokio::sync::OwnedRwLockWriteGuard<()> {
+        self.passes.clone().write_owned().await
+    }
+
+    /// The policy this task enforces.
+    pub fn config(&self) -> &ArchiveRetentionConfig {
+        &self.config
+    }
+
+    /// Outcome of the latest pass over each query, ordered by query id.
+    pub fn statuses(&self) -> Vec<QueryArchiveStatus> {
+        self.statuses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+impl Drop for ArchiveRetention {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(
+    instance_id: String,
+    config: ArchiveRetentionConfig,
+    databases: IndexDatabases,
+    statuses: Arc<Mutex<BTreeMap<String, QueryArchiveStatus>>>,
+    passes: Arc<tokio::sync::RwLock<()>>,
+) {
+    let mut ticker = tokio::time::interval(config.interval());
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let _pass = passes.read().await;
+        let query_ids = databases.query_ids();
+        statuses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|id, _| query_ids.contains(id));
+        for query_id in query_ids {
+            let limits = config.limits_for(&query_id);
+            // Hold the database only for the duration of this query's pass.
+            let Some(db) = databases.get(&query_id) else {
+                continue;
+            };
+            let now_ms = u64::try_from(Utc::now().timestamp_millis()).unwrap_or(0);
+            let pass_limits = limits.clone();
+            let result =
+                tokio::task::spawn_blocking(move || prune_archive(&db, &pass_limits, now_ms))
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|result| result);
+
+            let mut statuses = statuses.lock().unwrap_or_else(|e| e.into_inner());
+            let status = statuses
+                .entry(query_id.clone())
+                .or_insert_with(|| QueryArchiveStatus {
+                    query_id: query_id.clone(),
+                    limits: limits.clone(),
+                    last_run: None,
+                    versions: 0,
+                    bytes: 0,
+                    last_pruned: 0,
+                    total_pruned: 0,
+                    last_error: None,
+                });
+            status.limits = limits;
+            status.last_run = Some(Utc::now().to_rfc3339());
+            match result {
+                Ok(stats) => {
+                    if stats.pruned > 0 {
+                        debug!(
+                            "Pruned {} archived versions of query '{query_id}' in instance \
+                             '{instance_id}'",
+                            stats.pruned
+                        );
+                    }
+                    status.versions = stats.versions;
+                    status.bytes = stats.bytes;
+                    status.last_pruned = stats.pruned;
+                    status.total_pruned += stats.pruned;
+                    status.last_error = None;
+                }
+                Err(e) => {
+                    warn!(
+                        "Archive retention failed for query '{query_id}' in instance \
+                         '{instance_id}': {e}"
+                    );
+                    status.last_pruned = 0;
+                    status.last_error = Some(e.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Delete the archived versions `limits` does not keep, as of `now_ms`.
+///
+/// Databases opened without the archive have nothing to prune.
+pub fn prune_archive(
+    db: &IndexDb,
+    limits: &ArchiveRetentionLimits,
+    now_ms: u64,
+) -> Result<PruneStats> {
+    let Some(cf) = db.cf_handle(ARCHIVE_CF) else {
+        return Ok(PruneStats::default());
+    };
+    let cutoff = limits
+        .max_age_secs
+        .map(|secs| now_ms.saturating_sub(secs.saturating_mul(1000)));
+
+    let mut stats = PruneStats::default();
+    let mut batch = WriteBatchWithTransaction::<true>::default();
+    // Versions of the current element, oldest first: (key, timestamp, size).
+    let mut versions: Vec<(Box<[u8]>, u64, u64)> = Vec::new();
+
+    let flush = |versions: &mut Vec<(Box<[u8]>, u64, u64)>,
+                 batch: &mut WriteBatchWithTransaction<true>,
+                 stats: &mut PruneStats| {
+        let timestamps: Vec<u64> = versions.iter().map(|(_, ts, _)| *ts).collect();
+        let dropped = versions_to_drop(&timestamps, limits, cutoff);
+        for (index, (key, _, size)) in versions.drain(..).enumerate() {
+            if index < dropped {
+                batch.delete_cf(&cf, key);
+                stats.pruned += 1;
+            } else {
+                stats.versions += 1;
+                stats.bytes += size;
+            }
+        }
+    };
+
+    for item in db.iterator_cf(&cf, IteratorMode::Start) {
+        let (key, value) = item?;
+        let size = (key.len() + value.len()) as u64;
+        let Some(ts) = key
+            .get(ELEMENT_KEY_LEN..ELEMENT_KEY_LEN + 8)
+            .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+            .map(u64::from_be_bytes)
+        else {
+            // Not a version key; leave it alone.
+            stats.versions += 1;
+            stats.bytes += size;
+            continue;
+        };
+        let same_element = versions
+            .first()
+            .is_some_and(|(first, _, _)| first[..ELEMENT_KEY_LEN] == key[..ELEMENT_KEY_LEN]);
+        if !same_element {
+            flush(&mut versions, &mut batch, &mut stats);
+            if batch.len() >= DELETE_BATCH_SIZE {
+                db.write(std::mem::take(&mut batch))?;
+            }
+        }
+        versions.push((key, ts, size));
+    }
+    flush(&mut versions, &mut batch, &mut stats);
+    if !batch.is_empty() {
+        db.write(batch)?;
+    }
+    Ok(stats)
+}
+
+/// How many of an element's oldest versions to drop.
+///
+/// `timestamps` are the element's version timestamps in ascending order.
+/// The newest version at or before `cutoff` is kept because it is the value
+/// in effect at the cutoff.
+fn versions_to_drop(
+    timestamps: &[u64],
+    limits: &ArchiveRetentionLimits,
+    cutoff: Option<u64>,
+) -> usize {
+    let by_count = limits
+        .max_versions
+        .map(|max| timestamps.len().saturating_sub(max.max(1) as usize))
+        .unwrap_or(0);
+    let by_age = cutoff
+        .map(|cutoff| {
+            timestamps
+                .iter()
+                .take_while(|ts| **ts <= cutoff)
+                .count()
+                .saturating_sub(1)
+        })
+        .unwrap_or(0);
+    by_count.max(by_age)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use drasi_index_rocksdb::{open_unified_db, RocksDbMemoryBudget, RocksIndexOptions};
+
+    fn limits(max_age_secs: Option<u64>, max_versions: Option<u32>) -> ArchiveRetentionLimits {
+        ArchiveRetentionLimits {
+            max_age_secs,
+            max_versions,
+        }
+    }
+
+    fn archive_key(element: u8, ts: u64) -> Vec<u8> {
+        let mut key = vec![element; ELEMENT_KEY_LEN];
+        key.extend_from_slice(&ts.to_be_bytes());
+        key
+    }
+
+    #[test]
+    fn test_versions_to_drop() {
+        let ts = [10, 20, 30, 40];
+        assert_eq!(versions_to_drop(&ts, &limits(None, None), None), 0);
+        assert_eq!(versions_to_drop(&ts, &limits(None, Some(3)), None), 1);
+        assert_eq!(versions_to_drop(&ts, &limits(None, Some(10)), None), 0);
+        // Version 20 was in effect at the cutoff, so only 10 goes.
+        assert_eq!(versions_to_drop(&ts, &limits(Some(1), None), Some(25)), 1);
+        assert_eq!(versions_to_drop(&ts, &limits(Some(1), None), Some(5)), 0);
+        // The newest version is never dropped by age.
+        assert_eq!(versions_to_drop(&ts, &limits(Some(1), None), Some(100)), 3);
+        // Whichever limit drops more wins.
+        assert_eq!(
+            versions_to_drop(&ts, &limits(Some(1), Some(1)), Some(25)),
+            3
+        );
+    }
+
+    #[test]
+    fn test_prune_archive_applies_limits_per_element() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let options = RocksIndexOptions::new(true, false, RocksDbMemoryBudget::default());
+        let db = open_unified_db(dir.path().to_str().unwrap(), "q1", &options).unwrap();
+        let cf = db.cf_handle(ARCHIVE_CF).unwrap();
+        for ts in [1_000, 2_000, 3_000] {
+            db.put_cf(&cf, archive_key(1, ts), b"v").unwrap();
+        }
+        db.put_cf(&cf, archive_key(2, 500), b"v").unwrap();
+
+        let stats = prune_archive(&db, &limits(None, Some(2)), 10_000).unwrap();
+        assert_eq!(stats.pruned, 1);
+        assert_eq!(stats.versions, 3);
+
+        // Both elements keep their newest version at or before the cutoff.
+        let stats = prune_archive(&db, &limits(Some(5), None), 10_000).unwrap();
+        assert_eq!(stats.pruned, 1);
+        assert_eq!(stats.versions, 2);
+
+        assert!(db.get_cf(&cf, archive_key(1, 2_000)).unwrap().is_none());
+        assert!(db.get_cf(&cf, archive_key(1, 3_000)).unwrap().is_some());
+        assert!(db.get_cf(&cf, archive_key(2, 500)).unwrap().is_some());
+    }
+}
@@ -0,0 +1,732 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Backup and restore of an instance's durable data.
+//!
+//! A backup is a gzipped tarball holding:
+//!
+//! - `manifest.json`: the format version, instance id and creation time
+//! - `config.yaml`: the instance's section of the config file, with secrets
+//!   replaced by `${VAR}` references as in a config export
+//! - `index/`: the instance's RocksDB index directory, if it has one
+//! - `wal/`: the instance's redb WAL directory, if it has one
+//! - `state.json`: every key its sources and reactions hold in the state
+//!   store, base64-encoded, so it restores into any state store kind
+//!
+//! The database of each running query is read at a single RocksDB snapshot
+//! and written to a fresh database, which is what the archive holds; the
+//! databases of other queries and the WAL are copied file by file, which is
+//! only consistent while nothing writes to them. [`create_backup`] therefore
+//! stops the running sources, waits until the queries have processed what
+//! was already in flight, takes the copy, and starts the sources again, so
+//! the index and the WAL agree. Its caller holds the instance's write lock
+//! and pauses its archive retention for the duration.
+//!
+//! The archive is written to an unnamed temporary file under the data root
+//! rather than memory, since an index can be large.
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use drasi_index_rocksdb::IndexDb;
+use drasi_lib::state_store::StateStoreProvider;
+use drasi_lib::{ComponentStatus, DrasiLib};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::index_provider::IndexDatabases;
+use crate::instance_paths::instance_data_dir;
+pub use crate::instance_paths::DATA_ROOT;
+use crate::source_wal::read_checkpoint;
+use crate::storage_usage::InstanceStorage;
+
+/// Version of the archive layout written by [`create_backup`].
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const CONFIG_ENTRY: &str = "config.yaml";
+const STATE_ENTRY: &str = "state.json";
+const INDEX_DIR: &str = "index";
+const WAL_DIR: &str = "wal";
+
+/// How often query progress is sampled while waiting for queries to go idle.
+const QUIESCE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to wait for queries to go idle before failing the backup.
+const QUIESCE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Entries written per batch when copying a database at a snapshot.
+const COPY_BATCH_ENTRIES: usize = 10_000;
+
+/// What a backup archive contains.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub instance_id: String,
+    pub created_at: DateTime<Utc>,
+    /// Version of the server that wrote the backup.
+    pub server_version: String,
+    /// Whether the archive holds the instance's index.
+    pub index: bool,
+    /// Whether the archive holds the instance's WAL.
+    pub wal: bool,
+    /// Whether the instance's state store was accessible and dumped.
+    pub state: bool,
+}
+
+/// Keys and values of each state store partition.
+type StateDump = BTreeMap<String, BTreeMap<String, Vec<u8>>>;
+
+/// Back up instance `instance_id`: its index and WAL under `data_root`, the
+/// state its sources and reactions keep in `state_store`, and `config`.
+/// `storage` is the instance's storage record, used to tell when its
+/// queries have caught up with their sources' WALs.
+///
+/// Returns the archive as a temporary file, rewound to its start, that is
+/// removed once closed. Sources that were running are stopped for the
+/// duration of the copy and started again afterwards, even if it fails.
+/// The caller must hold the instance's write lock (see
+/// [`crate::component_revisions::ComponentRevisions::lock_writes`]), so
+/// nothing else starts or changes components meanwhile, and pause its
+/// archive retention (see [`crate::archive_retention::ArchiveRetention::pause`]).
+pub async fn create_backup(
+    instance_id: &str,
+    core: &DrasiLib,
+    storage: Option<&InstanceStorage>,
+    state_store: Option<Arc<dyn StateStoreProvider>>,
+    config: String,
+    data_root: &Path,
+) -> Result<File> {
+    let stopped = stop_running_sources(core).await?;
+    let result = async {
+        wait_for_queries_idle(core, storage).await?;
+        let state = match &state_store {
+            Some(store) => Some(dump_state(core, store.as_ref()).await?),
+            None => None,
+        };
+        let dir = instance_data_dir(data_root, instance_id);
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            instance_id: instance_id.to_string(),
+            created_at: Utc::now(),
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            index: dir.join(INDEX_DIR).is_dir(),
+            wal: dir.join(WAL_DIR).is_dir(),
+            state: state.is_some(),
+        };
+        let databases = storage.and_then(|storage| storage.index.clone());
+        let data_root = data_root.to_path_buf();
+        tokio::task::spawn_blocking(move || -> Result<File> {
+            std::fs::create_dir_all(&data_root)?;
+            let scratch = tempfile::tempdir_in(&data_root)?;
+            let mut file = write_archive(
+                tempfile::tempfile_in(&data_root)?,
+                &manifest,
+                &config,
+                state.as_ref(),
+                &dir,
+                databases.as_ref(),
+                scratch.path(),
+            )?;
+            file.rewind()?;
+            Ok(file)
+        })
+        .await
+        .context("Backup task failed")?
+    }
+    .await;
+
+    for id in stopped {
+        if let Err(e) = core.start_source(&id).await {
+            warn!("Failed to restart source '{id}' after backing up instance '{instance_id}': {e}");
+        }
+    }
+    if result.is_ok() {
+        info!("Backed up instance '{instance_id}'");
+    }
+    result
+}
+
+/// Stop every running or starting source, returning their ids.
+async fn stop_running_sources(core: &DrasiLib) -> Result<Vec<String>> {
+    let mut stopped = Vec::new();
+    for (id, status) in core.list_sources().await? {
+        if !matches!(status, ComponentStatus::Running | ComponentStatus::Starting) {
+            continue;
+        }
+        if let Err(e) = core.stop_source(&id).await {
+            for id in &stopped {
+                let _ = core.start_source(id).await;
+            }
+            return Err(anyhow!("Failed to stop source '{id}' for backup: {e}"));
+        }
+        stopped.push(id);
+    }
+    Ok(stopped)
+}
+
+/// Wait until the queries have processed what their sources already sent.
+///
+/// A running persistent query has caught up with a source that writes a WAL
+/// once its checkpoint for the source reaches the WAL's head. Sources
+/// without a WAL give no such signal; for them, a query counts as done once
+/// its output has not changed for two consecutive polls. Fails after
+/// [`QUIESCE_TIMEOUT`].
+async fn wait_for_queries_idle(core: &DrasiLib, storage: Option<&InstanceStorage>) -> Result<()> {
+    let deadline = Instant::now() + QUIESCE_TIMEOUT;
+    let mut previous = None;
+    loop {
+        let lagging = match storage {
+            Some(storage) => lagging_queries(core, storage).await,
+            None => Vec::new(),
+        };
+        let mut progress = Vec::new();
+        if let Ok(queries) = core.list_queries().await {
+            for (id, _) in queries {
+                if let Ok(metrics) = core.get_query_output_metrics(&id).await {
+                    progress.push((id, metrics.outbox_latest_seq));
+                }
+            }
+        }
+        if lagging.is_empty() && previous.as_ref() == Some(&progress) {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            if lagging.is_empty() {
+                bail!("queries were still producing results after {QUIESCE_TIMEOUT:?}");
+            }
+            bail!(
+                "queries {} had not caught up with their sources after {QUIESCE_TIMEOUT:?}",
+                lagging.join(", ")
+            );
+        }
+        previous = Some(progress);
+        tokio::time::sleep(QUIESCE_POLL_INTERVAL).await;
+    }
+}
+
+/// Running persistent queries whose checkpoint for one of their sources is
+/// behind that source's WAL head.
+async fn lagging_queries(core: &DrasiLib, storage: &InstanceStorage) -> Vec<String> {
+    let Some(index) = &storage.index else {
+        return Vec::new();
+    };
+    let mut lagging = Vec::new();
+    for (query_id, status) in core.list_queries().await.unwrap_or_default() {
+        if status != ComponentStatus::Running {
+            continue;
+        }
+        let (Some(db), Ok(config)) = (index.get(&query_id), core.get_query_config(&query_id).await)
+        else {
+            continue;
+        };
+        for source in &config.sources {
+            // Sources that do not write a WAL report no head.
+            let Ok(head) = storage.wal.head_sequence(&source.source_id).await else {
+                continue;
+            };
+            let checkpoint = match read_checkpoint(&db, &source.source_id).await {
+                Ok(checkpoint) => checkpoint.unwrap_or(0),
+                Err(e) => {
+                    warn!("Cannot read the checkpoint of query '{query_id}': {e}");
+                    0
+                }
+            };
+            if checkpoint < head {
+                lagging.push(format!("'{query_id}'"));
+                break;
+            }
+        }
+    }
+    lagging
+}
+
+/// Every key and value of the partitions owned by the instance's sources and
+/// reactions.
+async fn dump_state(core: &DrasiLib, store: &dyn StateStoreProvider) -> Result<StateDump> {
+    store.sync().await?;
+    let mut owners: Vec<String> = core
+        .list_sources()
+        .await?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    owners.extend(core.list_reactions().await?.into_iter().map(|(id, _)| id));
+
+    let mut dump = StateDump::new();
+    for store_id in owners {
+        let keys = store.list_keys(&store_id).await?;
+        if keys.is_empty() {
+            continue;
+        }
+        let key_refs: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let values = store.get_many(&store_id, &key_refs).await?;
+        dump.insert(store_id, values.into_iter().collect());
+    }
+    Ok(dump)
+}
+
+/// Write the archive to `out`. The index of each query whose database is
+/// open in `databases` is copied at a snapshot into `scratch` first.
+fn write_archive<W: Write>(
+    out: W,
+    manifest: &BackupManifest,
+    config: &str,
+    state: Option<&StateDump>,
+    dir: &Path,
+    databases: Option<&IndexDatabases>,
+    scratch: &Path,
+) -> Result<W> {
+    let mut archive = tar::Builder::new(GzEncoder::new(out, Compression::default()));
+    append_file(
+        &mut archive,
+        MANIFEST_ENTRY,
+        &serde_json::to_vec_pretty(manifest)?,
+    )?;
+    append_file(&mut archive, CONFIG_ENTRY, config.as_bytes())?;
+    if let Some(state) = state {
+        let encoded: BTreeMap<&String, BTreeMap<&String, String>> = state
+            .iter()
+            .map(|(store_id, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        (key, base64::engine::general_purpose::STANDARD.encode(value))
+                    })
+                    .collect();
+                (store_id, entries)
+            })
+            .collect();
+        append_file(&mut archive, STATE_ENTRY, &serde_json::to_vec(&encoded)?)?;
+    }
+    if manifest.index {
+        append_index(&mut archive, &dir.join(INDEX_DIR), databases, scratch)?;
+    }
+    if manifest.wal {
+        archive
+            .append_dir_all(WAL_DIR, dir.join(WAL_DIR))
+            .with_context(|| format!("Failed to archive {}", dir.join(WAL_DIR).display()))?;
+    }
+    Ok(archive.into_inner()?.finish()?)
+}
+
+/// Append the database of each query under `index_dir`. An open database
+/// keeps taking writes, so it is copied at a snapshot into `scratch` and the
+/// copy is archived; a closed one is archived as it is.
+fn append_index<W: Write>(
+    archive: &mut tar::Builder<W>,
+    index_dir: &Path,
+    databases: Option<&IndexDatabases>,
+    scratch: &Path,
+) -> Result<()> {
+    archive.append_dir(INDEX_DIR, index_dir)?;
+    for entry in std::fs::read_dir(index_dir)? {
+        let entry = entry?;
+        let query_id = entry.file_name().to_string_lossy().into_owned();
+        let name = format!("{INDEX_DIR}/{query_id}");
+        if !entry.file_type()?.is_dir() {
+            archive.append_path_with_name(entry.path(), &name)?;
+            continue;
+        }
+        match databases.and_then(|databases| databases.get(&query_id)) {
+            Some(db) => {
+                let copy = scratch.join(&query_id);
+                copy_at_snapshot(&db, &entry.path(), &copy)
+                    .with_context(|| format!("Failed to copy the index of query '{query_id}'"))?;
+                archive.append_dir_all(&name, &copy)?;
+                std::fs::remove_dir_all(&copy)?;
+            }
+            None => archive
+                .append_dir_all(&name, entry.path())
+                .with_context(|| format!("Failed to archive {}", entry.path().display()))?,
+        }
+    }
+    Ok(())
+}
+
+/// Copy every column family of `db`, stored at `path`, into a new database
+/// at `target`, reading all of them at one snapshot.
+fn copy_at_snapshot(db: &IndexDb, path: &Path, target: &Path) -> Result<()> {
+    let names = IndexDb::list_cf(&rocksdb::Options::default(), path)?;
+    let mut options = rocksdb::Options::default();
+    options.create_if_missing(true);
+    options.create_missing_column_families(true);
+    let copy = rocksdb::DB::open_cf(&options, target, &names)?;
+
+    let snapshot = db.snapshot();
+    for name in &names {
+        let (Some(from), Some(to)) = (db.cf_handle(name), copy.cf_handle(name)) else {
+            bail!("column family '{name}' is not open");
+        };
+        let mut read = rocksdb::ReadOptions::default();
+        read.set_total_order_seek(true);
+        let mut batch = rocksdb::WriteBatch::default();
+        for item in snapshot.iterator_cf_opt(&from, read, rocksdb::IteratorMode::Start) {
+            let (key, value) = item?;
+            batch.put_cf(&to, key, value);
+            if batch.len() >= COPY_BATCH_ENTRIES {
+                copy.write(std::mem::take(&mut batch))?;
+            }
+        }
+        copy.write(batch)?;
+        copy.flush_cf(&to)?;
+    }
+    Ok(())
+}
+
+fn append_file<W: std::io::Write>(
+    archive: &mut tar::Builder<W>,
+    name: &str,
+    data: &[u8],
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    archive.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// A backup archive read back from disk.
+#[derive(Debug)]
+pub struct BackupArchive {
+    path: PathBuf,
+    pub manifest: BackupManifest,
+    /// The instance's config section, as written to `config.yaml`.
+    pub config: String,
+    state: Option<StateDump>,
+}
+
+impl BackupArchive {
+    /// Read the manifest, config and state of the archive at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open backup '{}'", path.display()))?;
+        let mut archive = tar::Archive::new(GzDecoder::new(file));
+        let mut manifest = None;
+        let mut config = None;
+        let mut state = None;
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            let mut read = || -> Result<Vec<u8>> {
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data)?;
+                Ok(data)
+            };
+            match name.as_str() {
+                MANIFEST_ENTRY => {
+                    manifest = Some(serde_json::from_slice::<BackupManifest>(&read()?)?)
+                }
+                CONFIG_ENTRY => config = Some(String::from_utf8(read()?)?),
+                STATE_ENTRY => state = Some(decode_state(&read()?)?),
+                _ => {}
+            }
+        }
+        let manifest =
+            manifest.ok_or_else(|| anyhow!("'{}' is not a backup archive", path.display()))?;
+        if manifest.format_version > BACKUP_FORMAT_VERSION {
+            bail!(
+                "Backup '{}' has format version {}; this server reads up to {BACKUP_FORMAT_VERSION}",
+                path.display(),
+                manifest.format_version
+            );
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            manifest,
+            config: config.unwrap_or_default(),
+            state,
+        })
+    }
+
+    /// The directory under `data_root` that [`Self::restore_data`] writes to.
+    pub fn data_dir(&self, data_root: &Path) -> PathBuf {
+        instance_data_dir(data_root, &self.manifest.instance_id)
+    }
+
+    /// Unpack the index and WAL into the instance's directory under
+    /// `data_root`. Existing index and WAL directories are replaced when
+    /// `force` is set and are an error otherwise.
+    pub fn restore_data(&self, data_root: &Path, force: bool) -> Result<()> {
+        let dir = self.data_dir(data_root);
+        for name in [INDEX_DIR, WAL_DIR] {
+            let target = dir.join(name);
+            if !target.exists() {
+                continue;
+            }
+            if !force {
+                bail!(
+                    "'{}' already exists; stop the server and pass --force to replace it",
+                    target.display()
+                );
+            }
+            std::fs::remove_dir_all(&target)
+                .with_context(|| format!("Failed to remove '{}'", target.display()))?;
+        }
+        std::fs::create_dir_all(&dir)?;
+
+        let file = File::open(&self.path)?;
+        let mut archive = tar::Archive::new(GzDecoder::new(file));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.into_owned();
+            let in_data_dir = path.starts_with(INDEX_DIR) || path.starts_with(WAL_DIR);
+            // `unpack_in` refuses entries that would land outside `dir`.
+            if in_data_dir && !entry.unpack_in(&dir)? {
+                bail!(
+                    "Backup entry '{}' escapes the data directory",
+                    path.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the backed-up state into `store`, replacing what its
+    /// partitions held. Returns the number of keys written, or `None` if the
+    /// backup holds no state.
+    pub async fn restore_state(&self, store: &dyn StateStoreProvider) -> Result<Option<usize>> {
+        let Some(state) = &self.state else {
+            return Ok(None);
+        };
+        let mut written = 0;
+        for (store_id, entries) in state {
+            store.clear_store(store_id).await?;
+            let entries: Vec<(&str, &[u8])> = entries
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_slice()))
+                .collect();
+            store.set_many(store_id, &entries).await?;
+            written += entries.len();
+        }
+        store.sync().await?;
+        Ok(Some(written))
+    }
+}
+
+fn decode_state(data: &[u8]) -> Result<StateDump> {
+    let encoded: BTreeMap<String, BTreeMap<String, String>> = serde_json::from_slice(data)?;
+    encoded
+        .into_iter()
+        .map(|(store_id, entries)| {
+            let entries = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = base64::engine::general_purpose::STANDARD
+                        .decode(value)
+                        .with_context(|| format!("Invalid state value '{store_id}/{key}'"))?;
+                    Ok((key, value))
+                })
+                .collect::<Result<_>>()?;
+            Ok((store_id, entries))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use drasi_index_rocksdb::{open_unified_db, RocksDbMemoryBudget, RocksIndexOptions};
+    use drasi_lib::state_store::MemoryStateStoreProvider;
+    use tempfile::TempDir;
+
+    async fn empty_core(id: &str) -> DrasiLib {
+        let core = DrasiLib::builder().with_id(id).build().await.unwrap();
+        core.start().await.unwrap();
+        core
+    }
+
+    fn write_backup(dir: &TempDir, name: &str, mut archive: impl Read) -> PathBuf {
+        let path = dir.path().join(name);
+        std::io::copy(&mut archive, &mut File::create(&path).unwrap()).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_backup_round_trips_index_wal_and_config() {
+        let source_root = TempDir::new().unwrap();
+        let data_dir = instance_data_dir(source_root.path(), "orders");
+        std::fs::create_dir_all(data_dir.join("index/q1")).unwrap();
+        std::fs::write(data_dir.join("index/q1/CURRENT"), "MANIFEST-000001").unwrap();
+        std::fs::create_dir_all(data_dir.join("wal")).unwrap();
+        std::fs::write(data_dir.join("wal/src.redb"), [1u8, 2, 3]).unwrap();
+
+        let core = empty_core("orders").await;
+        let store: Arc<dyn StateStoreProvider> = Arc::new(MemoryStateStoreProvider::new());
+        let archive = create_backup(
+            "orders",
+            &core,
+            None,
+            Some(store),
+            "id: orders\n".to_string(),
+            source_root.path(),
+        )
+        .await
+        .unwrap();
+
+        let files = TempDir::new().unwrap();
+        let backup = BackupArchive::open(&write_backup(&files, "b.tar.gz", archive)).unwrap();
+        assert_eq!(backup.manifest.instance_id, "orders");
+        assert!(backup.manifest.index);
+        assert!(backup.manifest.wal);
+        assert!(backup.manifest.state);
+        assert_eq!(backup.config, "id: orders\n");
+
+        let target_root = TempDir::new().unwrap();
+        backup.restore_data(target_root.path(), false).unwrap();
+        let restored = backup.data_dir(target_root.path());
+        assert_eq!(
+            std::fs::read_to_string(restored.join("index/q1/CURRENT")).unwrap(),
+            "MANIFEST-000001"
+        );
+        assert_eq!(
+            std::fs::read(restored.join("wal/src.redb")).unwrap(),
+            [1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_backup_copies_open_databases_at_a_snapshot() {
+        let source_root = TempDir::new().unwrap();
+        let dir = instance_data_dir(source_root.path(), "orders");
+        let index_dir = dir.join(INDEX_DIR);
+        let options = RocksIndexOptions::new(false, false, RocksDbMemoryBudget::default());
+        let db = open_unified_db(index_dir.to_str().unwrap(), "q1", &options).unwrap();
+        db.put("marker", b"v1").unwrap();
+        let databases = IndexDatabases::new();
+        databases.track("q1", &db, &options);
+
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            instance_id: "orders".to_string(),
+            created_at: Utc::now(),
+            server_version: "test".to_string(),
+            index: true,
+            wal: false,
+            state: false,
+        };
+        let scratch = TempDir::new().unwrap();
+        let archive = write_archive(
+            Vec::new(),
+            &manifest,
+            "",
+            None,
+            &dir,
+            Some(&databases),
+            scratch.path(),
+        )
+        .unwrap();
+        let files = TempDir::new().unwrap();
+        let backup =
+            BackupArchive::open(&write_backup(&files, "b.tar.gz", archive.as_slice())).unwrap();
+
+        // The copy opens as an index database without the original's lock.
+        let target_root = TempDir::new().unwrap();
+        backup.restore_data(target_root.path(), false).unwrap();
+        let restored_dir = backup.data_dir(target_root.path()).join(INDEX_DIR);
+        let restored = open_unified_db(restored_dir.to_str().unwrap(), "q1", &options).unwrap();
+        assert_eq!(restored.get("marker").unwrap(), Some(b"v1".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_restore_refuses_existing_data_without_force() {
+        let source_root = TempDir::new().unwrap();
+        std::fs::create_dir_all(instance_data_dir(source_root.path(), "orders").join("wal"))
+            .unwrap();
+        let core = empty_core("orders").await;
+        let archive = create_backup(
+            "orders",
+            &core,
+            None,
+            None,
+            String::new(),
+            source_root.path(),
+        )
+        .await
+        .unwrap();
+
+        let files = TempDir::new().unwrap();
+        let backup = BackupArchive::open(&write_backup(&files, "b.tar.gz", archive)).unwrap();
+        assert!(!backup.manifest.state);
+
+        // The source data root already holds the WAL directory.
+        let err = backup.restore_data(source_root.path(), false).unwrap_err();
+        assert!(err.to_string().contains("--force"), "{err}");
+        backup.restore_data(source_root.path(), true).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_state_replaces_partitions() {
+        let mut state = StateDump::new();
+        state.insert(
+            "src".to_string(),
+            BTreeMap::from([("cursor".to_string(), vec![0xff, 0])]),
+        );
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            instance_id: "orders".to_string(),
+            created_at: Utc::now(),
+            server_version: "test".to_string(),
+            index: false,
+            wal: false,
+            state: true,
+        };
+        let archive = write_archive(
+            Vec::new(),
+            &manifest,
+            "",
+            Some(&state),
+            Path::new("/nonexistent"),
+            None,
+            Path::new("/nonexistent"),
+        )
+        .unwrap();
+        let files = TempDir::new().unwrap();
+        let backup =
+            BackupArchive::open(&write_backup(&files, "b.tar.gz", archive.as_slice())).unwrap();
+
+        let store = MemoryStateStoreProvider::new();
+        store.set("src", "stale", b"x".to_vec()).await.unwrap();
+        assert_eq!(backup.restore_state(&store).await.unwrap(), Some(1));
+        assert_eq!(
+            store.get("src", "cursor").await.unwrap(),
+            Some(vec![0xff, 0])
+        );
+        assert_eq!(store.get("src", "stale").await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_open_rejects_non_backup_archives() {
+        let files = TempDir::new().unwrap();
+        let mut archive = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        append_file(&mut archive, "other.txt", b"hello").unwrap();
+        let data = archive.into_inner().unwrap().finish().unwrap();
+        let err =
+            BackupArchive::open(&write_backup(&files, "x.tar.gz", data.as_slice())).unwrap_err();
+        assert!(err.to_string().contains("not a backup archive"), "{err}");
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Thin HTTP client for the v1 REST API.
+
+use anyhow::{anyhow, Result};
+use reqwest::{Method, StatusCode};
+use serde_json::Value;
+
+use drasi_server::api::version::ApiVersion;
+
+use super::context::{ContextsFile, SERVER_ENV};
+use super::ClientOptions;
+
+/// Client bound to one server and (optionally) one instance.
+pub struct ApiClient {
+    http: reqwest::Client,
+    server: String,
+    instance: Option<String>,
+}
+
+impl ApiClient {
+    /// Build a client from command-line options and the contexts file.
+    pub fn from_options(options: &ClientOptions) -> Result<Self> {
+        let contexts = ContextsFile::load(&ContextsFile::path())?;
+        let server_env = std::env::var(SERVER_ENV).ok();
+        let (server, instance) = contexts.resolve(
+            options.server.as_deref(),
+            options.context.as_deref(),
+            options.instance.as_deref(),
+            server_env.as_deref(),
+        )?;
+        Ok(Self {
+            http: reqwest::Client::new(),
+            server,
+            instance,
+        })
+    }
+
+    /// The instance to operate on, if one was chosen.
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
+    /// URL of a server-wide API path (e.g. `/instances`).
+    pub fn api_url(&self, path: &str) -> String {
+        format!("{}{}{path}", self.server, ApiVersion::V1.path_prefix())
+    }
+
+    /// URL of an instance-scoped API path (e.g. `/sources/orders`). Without
+    /// an explicit instance this targets the server's default instance.
+    pub fn instance_url(&self, path: &str) -> String {
+        match &self.instance {
+            Some(instance) => self.api_url(&format!("/instances/{instance}{path}")),
+            None => self.api_url(path),
+        }
+    }
+
+    /// Send a request and return the `data` of the API response.
+    pub async fn request(&self, method: Method, url: &str, body: Option<&Value>) -> Result<Value> {
+        let mut request = self.http.request(method, url);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("failed to reach {}: {e}", self.server))?;
+        let status = response.status();
+        let text = response.text().await?;
+        let body: Value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        parse_response(status, body)
+    }
+
+    pub async fn get(&self, url: &str) -> Result<Value> {
+        self.request(Method::GET, url, None).await
+    }
+
+    pub async fn post(&self, url: &str, body: Option<&Value>) -> Result<Value> {
+        self.request(Method::POST, url, body).await
+    }
+
+    pub async fn put(&self, url: &str, body: &Value) -> Result<Value> {
+        self.request(Method::PUT, url, Some(body)).await
+    }
+
+    pub async fn delete(&self, url: &str) -> Result<Value> {
+        self.request(Method::DELETE, url, None).await
+    }
+
+    /// Send a `POST` to an endpoint that answers with a file and return its
+    /// bytes.
+    pub async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .http
+            .post(url)
+            .send()
+            .await
+            .map_err(|e| anyhow!("failed to reach {}: {e}", self.server))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            let body: Value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+            return parse_response(status, body).map(|_| Vec::new());
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Stream a Server-Sent Events endpoint, calling `on_event` with the
+    /// parsed `data` of each event until the server closes the stream.
+    pub async fn stream(&self, url: &str, mut on_event: impl FnMut(Value)) -> Result<()> {
+        let mut response = self
+            .http
+            .get(url)
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| anyhow!("failed to reach {}: {e}", self.server))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            let body: Value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+            return parse_response(status, body).map(|_| ());
+        }
+
+        let mut parser = SseParser::default();
+        while let Some(chunk) = response.chunk().await? {
+            for data in parser.push(&chunk) {
+                on_event(serde_json::from_str(&data).unwrap_or(Value::String(data)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Unwrap an `ApiResponse` envelope, turning error envelopes and
+/// `ErrorResponse` bodies into errors.
+fn parse_response(status: StatusCode, body: Value) -> Result<Value> {
+    let error_message = body
+        .get("message")
+        .or_else(|| body.get("error"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let reported_failure = body.get("success").and_then(Value::as_bool) == Some(false);
+    if !status.is_success() || reported_failure {
+        let message = error_message.unwrap_or_else(|| match &body {
+            Value::String(text) if !text.is_empty() => text.clone(),
+            _ => status
+                .canonical_reason()
+                .unwrap_or("request failed")
+                .to_string(),
+        });
+        return Err(anyhow!("{message} (HTTP {})", status.as_u16()));
+    }
+    Ok(match body {
+        Value::Object(mut map) if map.contains_key("success") => {
+            map.remove("data").unwrap_or(Value::Null)
+        }
+        other => other,
+    })
+}
+
+/// Incremental parser for `text/event-stream` bodies. Yields the joined
+/// `data:` lines of each complete event.
+///
+/// Chunks are buffered as bytes and an event is decoded only once its blank
+/// line has arrived, so a chunk boundary inside a multi-byte character does
+/// not corrupt it.
+#[derive(Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some((end, next)) = event_end(&self.buffer) {
+            let raw: Vec<u8> = self.buffer.drain(..next).collect();
+            let raw = String::from_utf8_lossy(&raw[..end]);
+            let data: Vec<&str> = raw
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|d| d.strip_prefix(' ').unwrap_or(d))
+                .collect();
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+        events
+    }
+}
+
+/// Where the first complete event in `buffer` ends, and where the next one
+/// starts after its blank line (`\n\n` or `\n\r\n`).
+fn event_end(buffer: &[u8]) -> Option<(usize, usize)> {
+    buffer
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == b'\n')
+        .find_map(|(i, _)| match &buffer[i + 1..] {
+            [b'\n', ..] => Some((i + 1, i + 2)),
+            [b'\r', b'\n', ..] => Some((i + 1, i + 3)),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_sse_parser_handles_split_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"data: {\"a\":").is_empty());
+        let events = parser.push(b"1}\n\n: keep-alive\n\ndata: x\ndata: y\r\n\r\n");
+        assert_eq!(events, vec!["{\"a\":1}".to_string(), "x\ny".to_string()]);
+    }
+
+    #[test]
+    fn test_sse_parser_handles_a_character_split_across_chunks() {
+        let mut parser = SseParser::default();
+        let event = "data: caf\u{e9} \u{1f600}\r\n\r\n".as_bytes();
+        // Split inside the two-byte `é`, then inside the four-byte emoji.
+        let (first, rest) = event.split_at(10);
+        let (second, third) = rest.split_at(3);
+        assert!(parser.push(first).is_empty());
+        assert!(parser.push(second).is_empty());
+        assert_eq!(parser.push(third), vec!["caf\u{e9} \u{1f600}".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_response_unwraps_data() {
+        let data = parse_response(
+            StatusCode::OK,
+            json!({"success": true, "data": [{"id": "s1"}], "error": null}),
+        )
+        .unwrap();
+        assert_eq!(data, json!([{"id": "s1"}]));
+    }
+
+    #[test]
+    fn test_parse_response_reports_errors() {
+        let err = parse_response(
+            StatusCode::NOT_FOUND,
+            json!({"code": "NOT_FOUND", "message": "Source 'x' not found"}),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Source 'x' not found (HTTP 404)");
+
+        let err = parse_response(
+            StatusCode::OK,
+            json!({"success": false, "data": null, "error": "read-only"}),
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("read-only"));
+    }
+}
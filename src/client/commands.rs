@@ -0,0 +1,676 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Read;
+use std::path::Path;
+
+use super::api::ApiClient;
+use super::context::{ClientContext, ContextsFile};
+use super::output::{cell, render_document, render_rows, render_table, OutputFormat};
+use super::{ClientOptions, ContextAction, ResourceKind};
+use crate::cli_styles;
+
+/// Number of recent events shown by `describe`.
+const DESCRIBE_EVENT_LIMIT: usize = 10;
+
+/// `get`: list a resource collection or show one resource.
+pub async fn get(
+    options: &ClientOptions,
+    resource: ResourceKind,
+    name: Option<&str>,
+) -> Result<()> {
+    let client = ApiClient::from_options(options)?;
+    let data = match (resource, name) {
+        (ResourceKind::Instances, name) => {
+            let instances = client.get(&client.api_url("/instances")).await?;
+            match name {
+                None => instances,
+                Some(name) => find_by_id(instances, name)
+                    .ok_or_else(|| anyhow!("instance '{name}' not found"))?,
+            }
+        }
+        (kind, None) => {
+            client
+                .get(&client.instance_url(&format!("/{}", kind.collection())))
+                .await?
+        }
+        (kind, Some(name)) => {
+            client
+                .get(&client.instance_url(&format!("/{}/{name}", kind.collection())))
+                .await?
+        }
+    };
+
+    if options.output != OutputFormat::Table {
+        println!("{}", render_document(options.output, &data)?);
+        return Ok(());
+    }
+    let items = match data {
+        Value::Array(items) => items,
+        single => vec![single],
+    };
+    let table = if resource == ResourceKind::Instances {
+        let rows: Vec<Vec<String>> = items
+            .iter()
+            .map(|i| {
+                vec![
+                    cell(i.get("id")),
+                    cell(i.get("source_count")),
+                    cell(i.get("query_count")),
+                    cell(i.get("reaction_count")),
+                ]
+            })
+            .collect();
+        render_table(&["id", "sources", "queries", "reactions"], &rows)
+    } else {
+        let rows: Vec<Vec<String>> = items
+            .iter()
+            .map(|i| {
+                vec![
+                    cell(i.get("id")),
+                    cell(i.get("status")),
+                    cell(i.get("error_message")),
+                ]
+            })
+            .collect();
+        render_table(&["name", "status", "error"], &rows)
+    };
+    println!("{table}");
+    Ok(())
+}
+
+/// `describe`: configuration, status, and recent lifecycle events.
+pub async fn describe(options: &ClientOptions, resource: ResourceKind, name: &str) -> Result<()> {
+    let client = ApiClient::from_options(options)?;
+    if resource == ResourceKind::Instances {
+        let instances = client.get(&client.api_url("/instances")).await?;
+        let instance =
+            find_by_id(instances, name).ok_or_else(|| anyhow!("instance '{name}' not found"))?;
+        println!("{}", render_document(options.output, &instance)?);
+        return Ok(());
+    }
+
+    let path = format!("/{}/{name}", resource.collection());
+    let item = client
+        .get(&client.instance_url(&format!("{path}?view=full")))
+        .await?;
+    let events = client
+        .get(&client.instance_url(&format!("{path}/events?limit={DESCRIBE_EVENT_LIMIT}")))
+        .await?;
+
+    if options.output != OutputFormat::Table {
+        let document = serde_json::json!({
+            "id": item.get("id"),
+            "status": item.get("status"),
+            "errorMessage": item.get("error_message"),
+            "config": item.get("config"),
+            "events": events,
+        });
+        println!("{}", render_document(options.output, &document)?);
+        return Ok(());
+    }
+
+    println!("Name:    {}", cell(item.get("id")));
+    println!("Type:    {}", resource.singular());
+    println!("Status:  {}", cell(item.get("status")));
+    if let Some(error) = item.get("error_message").filter(|e| !e.is_null()) {
+        println!("Error:   {}", cell(Some(error)));
+    }
+    println!("Config:");
+    if let Some(config) = item.get("config").filter(|c| !c.is_null()) {
+        for line in serde_yaml::to_string(config)?.lines() {
+            println!("  {line}");
+        }
+    }
+    println!("Events:");
+    let rows = event_rows(&events);
+    if rows.is_empty() {
+        println!("  <none>");
+    } else {
+        for line in render_table(&["time", "status", "message"], &rows).lines() {
+            println!("  {line}");
+        }
+    }
+    Ok(())
+}
+
+/// Resources declared in an `apply -f` file. Uses the same list layout as
+/// the server config file, so a config file can be applied directly; other
+/// top-level keys are ignored.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ApplyDocument {
+    #[serde(default)]
+    sources: Vec<Value>,
+    #[serde(default)]
+    queries: Vec<Value>,
+    #[serde(default)]
+    reactions: Vec<Value>,
+}
+
+/// `apply -f`: send the file to the instance's `apply` endpoint, which
+/// creates, updates, and (with `prune`) deletes components as one unit.
+pub async fn apply(options: &ClientOptions, file: &Path, dry_run: bool, prune: bool) -> Result<()> {
+    let content = if file == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(file)
+            .with_context(|| format!("failed to read {}", file.display()))?
+    };
+    let document: ApplyDocument = serde_yaml::from_str(&content)
+        .with_context(|| format!("failed to parse {}", file.display()))?;
+    if document.sources.is_empty() && document.queries.is_empty() && document.reactions.is_empty() {
+        bail!(
+            "{} declares no sources, queries, or reactions",
+            file.display()
+        );
+    }
+
+    let client = ApiClient::from_options(options)?;
+    let instance = target_instance(&client).await?;
+    let url = client.api_url(&format!(
+        "/instances/{instance}/apply?dryRun={dry_run}&prune={prune}"
+    ));
+    // A failed apply is rolled back by the server and reported as an error.
+    let response = client
+        .post(&url, Some(&serde_json::to_value(&document)?))
+        .await?;
+
+    if options.output != OutputFormat::Table {
+        println!("{}", render_document(options.output, &response)?);
+    } else {
+        let suffix = if dry_run { " (dry run)" } else { "" };
+        for action in response
+            .get("actions")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let outcome = match action.get("action").and_then(Value::as_str) {
+                Some("create") => "created",
+                Some("update") => "configured",
+                Some("delete") => "deleted",
+                Some("unchanged") => "unchanged",
+                _ => "?",
+            };
+            println!(
+                "{}",
+                cli_styles::success(&format!(
+                    "{}/{} {outcome}{suffix}",
+                    cell(action.get("componentType")),
+                    cell(action.get("id"))
+                ))
+            );
+        }
+    }
+
+    // Errors on an applied manifest come from starting its components.
+    let errors: Vec<&str> = response
+        .get("errors")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    for error in &errors {
+        println!("{}", cli_styles::error(error));
+    }
+    if !errors.is_empty() {
+        bail!("{} component(s) failed to start", errors.len());
+    }
+    Ok(())
+}
+
+/// `delete`: remove a source, query, or reaction.
+pub async fn delete(options: &ClientOptions, resource: ResourceKind, name: &str) -> Result<()> {
+    require_component(resource)?;
+    let client = ApiClient::from_options(options)?;
+    client
+        .delete(&client.instance_url(&format!("/{}/{name}", resource.collection())))
+        .await?;
+    println!(
+        "{}",
+        cli_styles::success(&format!("{}/{name} deleted", resource.singular()))
+    );
+    Ok(())
+}
+
+/// `start` / `stop`: change a component's lifecycle state.
+pub async fn lifecycle(
+    options: &ClientOptions,
+    resource: ResourceKind,
+    name: &str,
+    action: &str,
+) -> Result<()> {
+    require_component(resource)?;
+    let client = ApiClient::from_options(options)?;
+    client
+        .post(
+            &client.instance_url(&format!("/{}/{name}/{action}", resource.collection())),
+            None,
+        )
+        .await?;
+    let past = if action == "start" {
+        "started"
+    } else {
+        "stopped"
+    };
+    println!(
+        "{}",
+        cli_styles::success(&format!("{}/{name} {past}", resource.singular()))
+    );
+    Ok(())
+}
+
+/// `logs`: print (or follow) a component's log messages.
+pub async fn logs(
+    options: &ClientOptions,
+    resource: ResourceKind,
+    name: &str,
+    follow: bool,
+    tail: Option<usize>,
+) -> Result<()> {
+    require_component(resource)?;
+    let client = ApiClient::from_options(options)?;
+    let path = format!("/{}/{name}/logs", resource.collection());
+    let format = options.output;
+
+    if follow {
+        return client
+            .stream(&client.instance_url(&format!("{path}/stream")), |message| {
+                print_log(format, &message)
+            })
+            .await;
+    }
+
+    let url = match tail {
+        Some(limit) => client.instance_url(&format!("{path}?limit={limit}")),
+        None => client.instance_url(&path),
+    };
+    let messages = client.get(&url).await?;
+    if format != OutputFormat::Table {
+        println!("{}", render_document(format, &messages)?);
+        return Ok(());
+    }
+    for message in messages.as_array().into_iter().flatten() {
+        print_log(format, message);
+    }
+    Ok(())
+}
+
+/// `events`: print (or follow) a component's lifecycle events, or stream
+/// the events of every component in the instance.
+pub async fn events(
+    options: &ClientOptions,
+    resource: Option<ResourceKind>,
+    name: Option<&str>,
+    follow: bool,
+) -> Result<()> {
+    let client = ApiClient::from_options(options)?;
+    let format = options.output;
+
+    let Some(resource) = resource else {
+        // Instance-wide events are only available as a stream.
+        return client
+            .stream(&client.instance_url("/events"), |event| {
+                print_event(format, &event, true)
+            })
+            .await;
+    };
+    require_component(resource)?;
+    let name = name.ok_or_else(|| anyhow!("specify the {} id", resource.singular()))?;
+    let path = format!("/{}/{name}/events", resource.collection());
+
+    if follow {
+        return client
+            .stream(&client.instance_url(&format!("{path}/stream")), |event| {
+                print_event(format, &event, false)
+            })
+            .await;
+    }
+
+    let events = client.get(&client.instance_url(&path)).await?;
+    if format != OutputFormat::Table {
+        println!("{}", render_document(format, &events)?);
+        return Ok(());
+    }
+    println!(
+        "{}",
+        render_table(&["time", "status", "message"], &event_rows(&events))
+    );
+    Ok(())
+}
+
+/// `results`: print a query's current results, or follow result changes.
+pub async fn results(options: &ClientOptions, query: &str, follow: bool) -> Result<()> {
+    let client = ApiClient::from_options(options)?;
+    let format = options.output;
+
+    if follow {
+        return client
+            .stream(
+                &client.instance_url(&format!("/queries/{query}/attach")),
+                |change| println!("{change}"),
+            )
+            .await;
+    }
+
+    let rows = client
+        .get(&client.instance_url(&format!("/queries/{query}/results")))
+        .await?;
+    match (format, rows.as_array()) {
+        (OutputFormat::Table, Some(rows)) if !rows.is_empty() => {
+            println!("{}", render_rows(rows))
+        }
+        (OutputFormat::Table, _) => println!("No results"),
+        _ => println!("{}", render_document(format, &rows)?),
+    }
+    Ok(())
+}
+
+/// `backup`: download a backup of an instance to `file`.
+pub async fn backup(options: &ClientOptions, file: &Path) -> Result<()> {
+    let client = ApiClient::from_options(options)?;
+    let instance = target_instance(&client).await?;
+    let archive = client
+        .download(&client.api_url(&format!("/instances/{instance}/backup")))
+        .await?;
+    std::fs::write(file, &archive)
+        .with_context(|| format!("failed to write {}", file.display()))?;
+    println!(
+        "{}",
+        cli_styles::success(&format!(
+            "instance/{instance} backed up to {}",
+            file.display()
+        ))
+    );
+    Ok(())
+}
+
+/// `context`: manage the contexts file.
+pub fn context(action: ContextAction) -> Result<()> {
+    let path = ContextsFile::path();
+    let mut file = ContextsFile::load(&path)?;
+    match action {
+        ContextAction::List => {
+            if file.contexts.is_empty() {
+                println!(
+                    "{}",
+                    cli_styles::skip(&format!("No contexts defined in {}", path.display()))
+                );
+                return Ok(());
+            }
+            let rows: Vec<Vec<String>> = file
+                .contexts
+                .iter()
+                .map(|(name, ctx)| {
+                    let current = file.current_context.as_deref() == Some(name.as_str());
+                    vec![
+                        if current { "*" } else { "" }.to_string(),
+                        name.clone(),
+                        ctx.server.clone(),
+                        ctx.instance.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            println!(
+                "{}",
+                render_table(&["current", "name", "server", "instance"], &rows)
+            );
+        }
+        ContextAction::Use { name } => {
+            if !file.contexts.contains_key(&name) {
+                bail!("context '{name}' not found in {}", path.display());
+            }
+            file.current_context = Some(name.clone());
+            file.save(&path)?;
+            println!(
+                "{}",
+                cli_styles::success(&format!("Switched to context '{name}'"))
+            );
+        }
+        ContextAction::Set {
+            name,
+            server,
+            instance,
+        } => {
+            file.contexts
+                .insert(name.clone(), ClientContext { server, instance });
+            if file.current_context.is_none() {
+                file.current_context = Some(name.clone());
+            }
+            file.save(&path)?;
+            println!(
+                "{}",
+                cli_styles::success(&format!("Context '{name}' saved to {}", path.display()))
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The instance chosen by the options, or the server's default (first)
+/// instance for endpoints that are only served per instance.
+async fn target_instance(client: &ApiClient) -> Result<String> {
+    match client.instance() {
+        Some(instance) => Ok(instance.to_string()),
+        None => client
+            .get(&client.api_url("/instances"))
+            .await?
+            .get(0)
+            .and_then(|instance| instance.get("id"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("the server has no instances")),
+    }
+}
+
+fn require_component(resource: ResourceKind) -> Result<()> {
+    if resource == ResourceKind::Instances {
+        bail!("this command applies to sources, queries, and reactions");
+    }
+    Ok(())
+}
+
+fn find_by_id(items: Value, id: &str) -> Option<Value> {
+    match items {
+        Value::Array(items) => items
+            .into_iter()
+            .find(|i| i.get("id").and_then(Value::as_str) == Some(id)),
+        _ => None,
+    }
+}
+
+fn event_rows(events: &Value) -> Vec<Vec<String>> {
+    events
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|e| {
+            vec![
+                cell(e.get("timestamp")),
+                cell(e.get("status")),
+                cell(e.get("message")),
+            ]
+        })
+        .collect()
+}
+
+fn print_log(format: OutputFormat, message: &Value) {
+    if format == OutputFormat::Table {
+        println!(
+            "{} {:<5} {}",
+            cell(message.get("timestamp")),
+            cell(message.get("level")),
+            cell(message.get("message"))
+        );
+    } else {
+        println!("{message}");
+    }
+}
+
+fn print_event(format: OutputFormat, event: &Value, with_component: bool) {
+    if format != OutputFormat::Table {
+        println!("{event}");
+    } else if with_component {
+        println!(
+            "{} {}/{} {} {}",
+            cell(event.get("timestamp")),
+            cell(event.get("componentType")),
+            cell(event.get("componentId")),
+            cell(event.get("status")),
+            cell(event.get("message"))
+        );
+    } else {
+        println!(
+            "{} {} {}",
+            cell(event.get("timestamp")),
+            cell(event.get("status")),
+            cell(event.get("message"))
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn options(server: &MockServer, instance: Option<&str>) -> ClientOptions {
+        ClientOptions {
+            server: Some(server.uri()),
+            context: None,
+            instance: instance.map(str::to_string),
+            output: OutputFormat::Table,
+        }
+    }
+
+    fn write_file(dir: &tempfile::TempDir, content: &str) -> std::path::PathBuf {
+        let file = dir.path().join("pipeline.yaml");
+        std::fs::write(&file, content).unwrap();
+        file
+    }
+
+    #[tokio::test]
+    async fn test_apply_posts_manifest_to_apply_endpoint() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/instances/prod/apply"))
+            .and(query_param("dryRun", "false"))
+            .and(query_param("prune", "true"))
+            .and(body_json(json!({
+                "sources": [{"kind": "mock", "id": "orders"}],
+                "queries": [],
+                "reactions": [],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true,
+                "data": {
+                    "dryRun": false,
+                    "applied": true,
+                    "actions": [
+                        {"componentType": "source", "id": "orders", "action": "create"},
+                        {"componentType": "query", "id": "old", "action": "delete"},
+                    ],
+                },
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // Config-file keys other than the component lists are not sent.
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = write_file(
+            &dir,
+            "id: server\nport: 8080\nsources:\n  - kind: mock\n    id: orders\n",
+        );
+        apply(&options(&server, Some("prod")), &file, false, true)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_apply_targets_default_instance_and_reports_failure() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/instances"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true,
+                "data": [{"id": "first"}, {"id": "second"}],
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/instances/first/apply"))
+            .respond_with(ResponseTemplate::new(500).set_body_json(json!({
+                "code": "APPLY_FAILED",
+                "message": "Apply failed and was rolled back: source 'orders' is invalid",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = write_file(&dir, "sources:\n  - kind: mock\n    id: orders\n");
+        let err = apply(&options(&server, None), &file, false, false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("rolled back"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_apply_fails_when_components_do_not_start() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/instances/prod/apply"))
+            .and(query_param("dryRun", "false"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "success": true,
+                "data": {
+                    "dryRun": false,
+                    "applied": true,
+                    "actions": [{"componentType": "query", "id": "q", "action": "update"}],
+                    "errors": ["query:q: failed to start"],
+                },
+            })))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = write_file(&dir, "queries:\n  - id: q\n    query: MATCH (n) RETURN n\n");
+        let err = apply(&options(&server, Some("prod")), &file, false, false)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "1 component(s) failed to start");
+    }
+
+    #[tokio::test]
+    async fn test_apply_rejects_file_without_components() {
+        let server = MockServer::start().await;
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = write_file(&dir, "id: server\n");
+        let err = apply(&options(&server, Some("prod")), &file, true, false)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("declares no sources"));
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+}
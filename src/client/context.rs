@@ -0,0 +1,191 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Named server contexts for client subcommands.
+//!
+//! Contexts live in `~/.drasi/contexts.yaml` (or the file named by
+//! `DRASI_CONTEXTS`):
+//!
+//! ```yaml
+//! currentContext: staging
+//! contexts:
+//!   local:
+//!     server: http://localhost:8080
+//!   staging:
+//!     server: https://drasi.staging.example.com
+//!     instance: orders
+//! ```
+
+use anyhow::{anyhow, Context as _, Result};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the contexts file location.
+pub const CONTEXTS_ENV: &str = "DRASI_CONTEXTS";
+
+/// Environment variable naming the server when no context is configured.
+pub const SERVER_ENV: &str = "DRASI_SERVER";
+
+/// Server used when nothing else is configured.
+pub const DEFAULT_SERVER: &str = "http://localhost:8080";
+
+/// A single named context.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientContext {
+    pub server: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+}
+
+/// Contents of the contexts file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextsFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_context: Option<String>,
+    #[serde(default)]
+    pub contexts: IndexMap<String, ClientContext>,
+}
+
+impl ContextsFile {
+    /// Location of the contexts file.
+    pub fn path() -> PathBuf {
+        if let Ok(path) = std::env::var(CONTEXTS_ENV) {
+            return PathBuf::from(path);
+        }
+        let home = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home).join(".drasi").join("contexts.yaml")
+    }
+
+    /// Read the contexts file; a missing file yields no contexts.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    /// Write the contexts file, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_yaml::to_string(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Resolve the server URL and instance for a command.
+    ///
+    /// Precedence: `--server`/`--instance` flags, then the `--context` (or
+    /// current) context, then `DRASI_SERVER`, then `http://localhost:8080`.
+    pub fn resolve(
+        &self,
+        server_flag: Option<&str>,
+        context_flag: Option<&str>,
+        instance_flag: Option<&str>,
+        server_env: Option<&str>,
+    ) -> Result<(String, Option<String>)> {
+        let context = match context_flag.or(self.current_context.as_deref()) {
+            Some(name) => Some(self.contexts.get(name).ok_or_else(|| {
+                anyhow!("context '{name}' not found in {}", Self::path().display())
+            })?),
+            None => None,
+        };
+        let server = server_flag
+            .or(context.map(|c| c.server.as_str()))
+            .or(server_env)
+            .unwrap_or(DEFAULT_SERVER)
+            .trim_end_matches('/')
+            .to_string();
+        let instance = instance_flag
+            .map(str::to_string)
+            .or_else(|| context.and_then(|c| c.instance.clone()));
+        Ok((server, instance))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn contexts() -> ContextsFile {
+        serde_yaml::from_str(
+            r#"
+currentContext: staging
+contexts:
+  local:
+    server: http://localhost:8080
+  staging:
+    server: https://drasi.example.com/
+    instance: orders
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_uses_current_context() {
+        let (server, instance) = contexts().resolve(None, None, None, None).unwrap();
+        assert_eq!(server, "https://drasi.example.com");
+        assert_eq!(instance.as_deref(), Some("orders"));
+    }
+
+    #[test]
+    fn test_resolve_flags_override_context() {
+        let file = contexts();
+        let (server, instance) = file
+            .resolve(Some("http://other:9000"), None, Some("iot"), None)
+            .unwrap();
+        assert_eq!(server, "http://other:9000");
+        assert_eq!(instance.as_deref(), Some("iot"));
+
+        let (server, instance) = file.resolve(None, Some("local"), None, None).unwrap();
+        assert_eq!(server, "http://localhost:8080");
+        assert_eq!(instance, None);
+
+        assert!(file.resolve(None, Some("missing"), None, None).is_err());
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_env_and_default() {
+        let empty = ContextsFile::default();
+        let (server, _) = empty
+            .resolve(None, None, None, Some("http://env:8080"))
+            .unwrap();
+        assert_eq!(server, "http://env:8080");
+        let (server, _) = empty.resolve(None, None, None, None).unwrap();
+        assert_eq!(server, DEFAULT_SERVER);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("contexts.yaml");
+        let file = contexts();
+        file.save(&path).unwrap();
+        assert_eq!(ContextsFile::load(&path).unwrap(), file);
+        assert_eq!(
+            ContextsFile::load(&dir.path().join("absent.yaml")).unwrap(),
+            ContextsFile::default()
+        );
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client subcommands that operate on a running server through the v1 REST
+//! API (`get`, `describe`, `apply`, `delete`, `start`, `stop`, `logs`,
+//! `events`, `results`, `backup`, `context`).
+
+mod api;
+mod commands;
+mod context;
+mod output;
+
+use anyhow::Result;
+use clap::{Args, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use output::OutputFormat;
+
+/// Connection and output options shared by all client subcommands.
+#[derive(Args, Debug, Clone)]
+pub struct ClientOptions {
+    /// Server URL (e.g., "http://localhost:8080"); overrides the context
+    #[arg(short, long)]
+    server: Option<String>,
+
+    /// Named context from the contexts file to use instead of the current one
+    #[arg(long)]
+    context: Option<String>,
+
+    /// Instance to operate on (defaults to the context's instance, then the
+    /// server's default instance)
+    #[arg(short, long)]
+    instance: Option<String>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+}
+
+/// Resource types addressable by client subcommands.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    #[value(alias = "instance")]
+    Instances,
+    #[value(alias = "source", alias = "src")]
+    Sources,
+    #[value(alias = "query", alias = "q")]
+    Queries,
+    #[value(alias = "reaction", alias = "rx")]
+    Reactions,
+}
+
+impl ResourceKind {
+    /// Path segment of the resource collection in the REST API.
+    fn collection(self) -> &'static str {
+        match self {
+            ResourceKind::Instances => "instances",
+            ResourceKind::Sources => "sources",
+            ResourceKind::Queries => "queries",
+            ResourceKind::Reactions => "reactions",
+        }
+    }
+
+    /// Singular name used in messages (`source/orders configured`).
+    fn singular(self) -> &'static str {
+        match self {
+            ResourceKind::Instances => "instance",
+            ResourceKind::Sources => "source",
+            ResourceKind::Queries => "query",
+            ResourceKind::Reactions => "reaction",
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum ClientCommand {
+    /// List resources on a running server, or show one
+    Get {
+        resource: ResourceKind,
+        /// Resource id (omit to list all)
+        name: Option<String>,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Show a resource's configuration, status, and recent events
+    Describe {
+        resource: ResourceKind,
+        name: String,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Create or update the sources, queries, and reactions in a file
+    Apply {
+        /// YAML or JSON file with `sources`, `queries`, and/or `reactions`
+        /// lists ("-" reads stdin)
+        #[arg(short, long)]
+        file: PathBuf,
+        /// Print the planned actions without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Delete components that are not in the file
+        #[arg(long)]
+        prune: bool,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Delete a source, query, or reaction
+    Delete {
+        resource: ResourceKind,
+        name: String,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Start a source, query, or reaction
+    Start {
+        resource: ResourceKind,
+        name: String,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Stop a source, query, or reaction
+    Stop {
+        resource: ResourceKind,
+        name: String,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Print a component's logs
+    Logs {
+        resource: ResourceKind,
+        name: String,
+        /// Keep streaming new log messages
+        #[arg(short, long)]
+        follow: bool,
+        /// Only print the most recent N messages
+        #[arg(long)]
+        tail: Option<usize>,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Print lifecycle events of a component, or stream all events
+    Events {
+        /// Resource type (omit to stream events of every component)
+        resource: Option<ResourceKind>,
+        name: Option<String>,
+        /// Keep streaming new events
+        #[arg(short, long)]
+        follow: bool,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Print the current result set of a query
+    Results {
+        query: String,
+        /// Stream result changes instead of printing the current results
+        #[arg(short, long)]
+        follow: bool,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Back up an instance's index, WAL, state and config to a tarball
+    Backup {
+        /// File to write the backup to
+        #[arg(short, long, default_value = "backup.tar.gz")]
+        file: PathBuf,
+        #[command(flatten)]
+        client: ClientOptions,
+    },
+
+    /// Manage named server contexts for client subcommands
+    Context {
+        #[command(subcommand)]
+        action: ContextAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ContextAction {
+    /// List contexts (the current one is marked with *)
+    List,
+    /// Make a context the current one
+    Use { name: String },
+    /// Create or update a context
+    Set {
+        name: String,
+        /// Server URL
+        #[arg(long)]
+        server: String,
+        /// Default instance for this context
+        #[arg(long)]
+        instance: Option<String>,
+    },
+}
+
+/// Handle client subcommands.
+pub async fn run_client_command(command: ClientCommand) -> Result<()> {
+    match command {
+        ClientCommand::Get {
+            resource,
+            name,
+            client,
+        } => commands::get(&client, resource, name.as_deref()).await,
+        ClientCommand::Describe {
+            resource,
+            name,
+            client,
+        } => commands::describe(&client, resource, &name).await,
+        ClientCommand::Apply {
+            file,
+            dry_run,
+            prune,
+            client,
+        } => commands::apply(&client, &file, dry_run, prune).await,
+        ClientCommand::Delete {
+            resource,
+            name,
+            client,
+        } => commands::delete(&client, resource, &name).await,
+        ClientCommand::Start {
+            resource,
+            name,
+            client,
+        } => commands::lifecycle(&client, resource, &name, "start").await,
+        ClientCommand::Stop {
+            resource,
+            name,
+            client,
+        } => commands::lifecycle(&client, resource, &name, "stop").await,
+        ClientCommand::Logs {
+            resource,
+            name,
+            follow,
+            tail,
+            client,
+        } => commands::logs(&client, resource, &name, follow, tail).await,
+        ClientCommand::Events {
+            resource,
+            name,
+            follow,
+            client,
+        } => commands::events(&client, resource, name.as_deref(), follow).await,
+        ClientCommand::Results {
+            query,
+            follow,
+            client,
+        } => commands::results(&client, &query, follow).await,
+        ClientCommand::Backup { file, client } => commands::backup(&client, &file).await,
+        ClientCommand::Context { action } => commands::context(action),
+    }
+}
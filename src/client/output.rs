@@ -0,0 +1,123 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Table, YAML, and JSON rendering for client subcommands.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde_json::Value;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Yaml,
+    Json,
+}
+
+/// Render `value` as YAML or pretty JSON. Table output is handled by the
+/// caller, which knows the columns.
+pub fn render_document(format: OutputFormat, value: &Value) -> Result<String> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_string_pretty(value)?,
+        OutputFormat::Yaml | OutputFormat::Table => serde_yaml::to_string(value)?,
+    })
+}
+
+/// Render rows as a left-aligned table with upper-cased headers.
+pub fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let format_row = |cells: Vec<String>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("   ")
+            .trim_end()
+            .to_string()
+    };
+    let mut lines = vec![format_row(
+        headers.iter().map(|h| h.to_uppercase()).collect(),
+    )];
+    lines.extend(rows.iter().map(|row| format_row(row.clone())));
+    lines.join("\n")
+}
+
+/// Plain-text form of a JSON value for a table cell.
+pub fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Render query results as a table whose columns are the union of the
+/// row keys, in first-seen order.
+pub fn render_rows(rows: &[Value]) -> String {
+    let mut columns: Vec<String> = Vec::new();
+    for row in rows {
+        if let Value::Object(map) = row {
+            for key in map.keys() {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+    }
+    let headers: Vec<&str> = columns.iter().map(String::as_str).collect();
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| columns.iter().map(|c| cell(row.get(c))).collect())
+        .collect();
+    render_table(&headers, &cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_table_aligns_columns() {
+        let table = render_table(
+            &["name", "status"],
+            &[
+                vec!["orders-db".to_string(), "Running".to_string()],
+                vec!["s".to_string(), "Error".to_string()],
+            ],
+        );
+        assert_eq!(
+            table,
+            "NAME        STATUS\norders-db   Running\ns           Error"
+        );
+    }
+
+    #[test]
+    fn test_render_rows_unions_columns() {
+        let table = render_rows(&[
+            json!({"id": 1, "name": "a"}),
+            json!({"id": 2, "total": 9.5}),
+        ]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "ID   NAME   TOTAL");
+        assert_eq!(lines[1], "1    a");
+        assert_eq!(lines[2], "2           9.5");
+    }
+}
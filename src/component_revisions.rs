@@ -0,0 +1,225 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Revision numbers for components and instances, used for optimistic
+//! concurrency (`ETag` / `If-Match`) in the REST API.
+//!
+//! All revisions are drawn from one server-wide counter, so a number is
+//! never reused: every configuration change — including deleting a
+//! component — moves the component (and its instance) to a fresh revision.
+//! A client holding the revision of a component that has since been
+//! changed, or deleted and re-created, can therefore never match it.
+//!
+//! Components that have not changed since startup get a revision the first
+//! time one is asked for.
+//!
+//! Revisions are held in memory. The counter starts at the process's boot
+//! time in microseconds, so revisions keep increasing across restarts and an
+//! `ETag` read from an earlier process never matches one of this process.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Kind of a revisioned component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentKind {
+    Source,
+    Query,
+    Reaction,
+}
+
+impl ComponentKind {
+    /// Parse a singular component type ("source", "query", "reaction").
+    pub fn from_type_name(name: &str) -> Option<Self> {
+        match name {
+            "source" => Some(Self::Source),
+            "query" => Some(Self::Query),
+            "reaction" => Some(Self::Reaction),
+            _ => None,
+        }
+    }
+
+    /// Parse a REST collection name ("sources", "queries", "reactions").
+    pub fn from_collection(name: &str) -> Option<Self> {
+        match name {
+            "sources" => Some(Self::Source),
+            "queries" => Some(Self::Query),
+            "reactions" => Some(Self::Reaction),
+            _ => None,
+        }
+    }
+}
+
+struct State {
+    last: u64,
+    components: HashMap<(String, ComponentKind, String), u64>,
+    instances: HashMap<String, u64>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            last: boot_epoch(),
+            components: HashMap::new(),
+            instances: HashMap::new(),
+        }
+    }
+}
+
+/// Microseconds since the Unix epoch, the first revision of this process.
+fn boot_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX / 2)
+        })
+}
+
+impl State {
+    fn next(&mut self) -> u64 {
+        self.last += 1;
+        self.last
+    }
+}
+
+/// Shared revision counters. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct ComponentRevisions {
+    state: Arc<Mutex<State>>,
+    /// Per-instance locks serializing conditional writes, so an `If-Match`
+    /// check and the change it guards cannot interleave with another write.
+    write_locks: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+impl ComponentRevisions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        // The state is a plain map of counters; it stays consistent even if
+        // a holder panicked.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Current revision of a component.
+    pub fn component(&self, instance_id: &str, kind: ComponentKind, id: &str) -> u64 {
+        let mut state = self.state();
+        let key = (instance_id.to_string(), kind, id.to_string());
+        if let Some(revision) = state.components.get(&key) {
+            return *revision;
+        }
+        let revision = state.next();
+        state.components.insert(key, revision);
+        revision
+    }
+
+    /// Record a change to a component (create, update, or delete) and
+    /// return its new revision. The instance's revision moves with it.
+    pub fn bump(&self, instance_id: &str, kind: ComponentKind, id: &str) -> u64 {
+        let mut state = self.state();
+        let revision = state.next();
+        state
+            .components
+            .insert((instance_id.to_string(), kind, id.to_string()), revision);
+        state.instances.insert(instance_id.to_string(), revision);
+        revision
+    }
+
+    /// Current revision of an instance: the revision of its most recent
+    /// component change.
+    pub fn instance(&self, instance_id: &str) -> u64 {
+        let mut state = self.state();
+        if let Some(revision) = state.instances.get(instance_id) {
+            return *revision;
+        }
+        let revision = state.next();
+        state.instances.insert(instance_id.to_string(), revision);
+        revision
+    }
+
+    /// Record a change to an instance that is not tied to one component
+    /// (for example deploying a solution) and return its new revision.
+    pub fn touch_instance(&self, instance_id: &str) -> u64 {
+        let mut state = self.state();
+        let revision = state.next();
+        state.instances.insert(instance_id.to_string(), revision);
+        revision
+    }
+
+    /// Forget an instance and its components (the instance was removed).
+    pub fn remove_instance(&self, instance_id: &str) {
+        let mut state = self.state();
+        state.instances.remove(instance_id);
+        state
+            .components
+            .retain(|(instance, _, _), _| instance != instance_id);
+    }
+
+    /// Lock held while a write to `instance_id` is checked and applied.
+    pub async fn lock_writes(&self, instance_id: &str) -> tokio::sync::OwnedMutexGuard<()> {
+        let lock = self
+            .write_locks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(instance_id.to_string())
+            .or_default()
+            .clone();
+        lock.lock_owned().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revisions_are_stable_until_changed() {
+        let revisions = ComponentRevisions::new();
+        let first = revisions.component("a", ComponentKind::Source, "s1");
+        assert_eq!(revisions.component("a", ComponentKind::Source, "s1"), first);
+
+        let bumped = revisions.bump("a", ComponentKind::Source, "s1");
+        assert!(bumped > first);
+        assert_eq!(
+            revisions.component("a", ComponentKind::Source, "s1"),
+            bumped
+        );
+        assert_eq!(revisions.instance("a"), bumped);
+    }
+
+    #[test]
+    fn test_revisions_are_never_reused() {
+        let revisions = ComponentRevisions::new();
+        let source = revisions.component("a", ComponentKind::Source, "x");
+        let query = revisions.component("a", ComponentKind::Query, "x");
+        let other_instance = revisions.component("b", ComponentKind::Source, "x");
+        assert_ne!(source, query);
+        assert_ne!(source, other_instance);
+
+        revisions.remove_instance("a");
+        assert!(revisions.component("a", ComponentKind::Source, "x") > other_instance);
+    }
+
+    #[test]
+    fn test_revisions_continue_across_restarts() {
+        let before_restart = ComponentRevisions::new();
+        let old = before_restart.bump("a", ComponentKind::Source, "s1");
+        // A restarted process starts from a later boot epoch.
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let after_restart = ComponentRevisions::new();
+        assert!(after_restart.component("a", ComponentKind::Source, "s1") > old);
+    }
+}
@@ -0,0 +1,1143 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Configuration split across several files.
+//!
+//! The main config file is merged with further files, in this order:
+//!
+//! 1. the main file itself
+//! 2. each entry of its `include:` list — a file, or a directory whose
+//!    `*.yaml`, `*.yml`, and `*.json` files are taken in name order.
+//!    Relative paths are resolved against the including file, and included
+//!    files may include further files (each right after its includer).
+//! 3. the files of a `conf.d/` directory next to the main file, in name order
+//! 4. with `--env <name>`, the overlay `<stem>.<name>.<ext>` next to the main
+//!    file (e.g. `server.prod.yaml` for `server.yaml`)
+//!
+//! Later files win. Mappings are merged key by key, and lists whose entries
+//! all have an `id` (sources, queries, reactions, instances, ...) are merged
+//! entry by entry, so an overlay only needs to name a component and the
+//! fields it changes. Any other value is replaced. A field set to
+//! `!unset` is removed from the files below.
+//!
+//! Each component is owned by the first file that defines it.
+//! [`ConfigLayers::write_back`] writes API changes to a component to the last
+//! file that defines or patches it, so an overlay keeps winning and the files
+//! below it are left as they are.
+
+use super::loader::ConfigError;
+use super::overrides::{AppliedOverride, ConfigOverrides, OverrideSource};
+use super::types::DrasiServerConfig;
+use serde_yaml::value::{Tag, TaggedValue};
+use serde_yaml::{Mapping, Sequence, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Top-level key listing the files a config file includes.
+pub const INCLUDE_KEY: &str = "include";
+
+/// Directory next to the main config file whose files are merged into it.
+pub const CONF_D_DIR: &str = "conf.d";
+
+/// Component lists whose entries are tracked per file.
+const COMPONENT_LISTS: [&str; 3] = ["sources", "queries", "reactions"];
+
+/// Tag of a field an overlay removes from the files below it.
+const UNSET_TAG: &str = "unset";
+
+/// Extensions of the files taken from an included directory or `conf.d/`.
+const CONFIG_EXTENSIONS: [&str; 3] = ["yaml", "yml", "json"];
+
+/// A source, query, or reaction of an instance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ComponentKey {
+    pub instance_id: String,
+    /// `sources`, `queries`, or `reactions`
+    pub list: &'static str,
+    pub id: String,
+}
+
+/// One file of a layered configuration.
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    pub path: PathBuf,
+    /// The file's content, without its `include` key.
+    pub value: Value,
+}
+
+/// The files a configuration was loaded from, in merge order.
+#[derive(Debug, Clone)]
+pub struct ConfigLayers {
+    layers: Vec<ConfigLayer>,
+    /// Instance id of components declared at the top level (single-instance
+    /// format).
+    top_level_id: String,
+    /// Index of the layer that first defines each component.
+    origins: HashMap<ComponentKey, usize>,
+    /// Overrides applied on top of the merged files.
+    overrides: ConfigOverrides,
+    /// The keys the overrides set.
+    applied: Vec<AppliedOverride>,
+}
+
+/// Where the value of a config key came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueOrigin<'a> {
+    File(&'a Path),
+    Override(&'a OverrideSource),
+}
+
+impl fmt::Display for ValueOrigin<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Override(source) => write!(f, "{source}"),
+        }
+    }
+}
+
+impl ConfigLayers {
+    /// Load `path` with its includes, `conf.d/` files, and the overlay for
+    /// `env`. The overlay must exist when `env` is given.
+    pub fn load(path: &Path, env: Option<&str>) -> Result<Self, ConfigError> {
+        let mut loader = LayerLoader::default();
+        loader.add(path)?;
+
+        let conf_d = path.parent().unwrap_or(Path::new("")).join(CONF_D_DIR);
+        if conf_d.is_dir() {
+            for file in config_files_in(&conf_d)? {
+                loader.add(&file)?;
+            }
+        }
+
+        if let Some(env) = env {
+            let overlay = overlay_path(path, env);
+            if !overlay.is_file() {
+                return Err(ConfigError::IncludeError(format!(
+                    "overlay '{}' for environment '{env}' does not exist",
+                    overlay.display()
+                )));
+            }
+            loader.add(&overlay)?;
+        }
+
+        let mut layers = Self {
+            layers: loader.layers,
+            top_level_id: String::new(),
+            origins: HashMap::new(),
+            overrides: ConfigOverrides::default(),
+            applied: Vec::new(),
+        };
+        if let Some(id) = layers.merged().get("id").and_then(Value::as_str) {
+            layers.top_level_id = id.to_string();
+        }
+        layers.index_origins();
+        Ok(layers)
+    }
+
+    /// Whether the configuration spans more than one file.
+    pub fn is_layered(&self) -> bool {
+        self.layers.len() > 1
+    }
+
+    /// Whether saves must update the files in place rather than rewrite the
+    /// main file: when there are several files, or when overrides changed
+    /// values that are not in any file.
+    pub fn writes_in_place(&self) -> bool {
+        self.is_layered() || !self.applied.is_empty()
+    }
+
+    /// Apply `overrides` to the merged files, returning the result.
+    pub fn apply_overrides(&mut self, overrides: ConfigOverrides) -> Result<Value, ConfigError> {
+        let mut resolved = self.merged();
+        self.applied = overrides.apply(&mut resolved)?;
+        self.overrides = overrides;
+        Ok(resolved)
+    }
+
+    /// The keys set by overrides, in the order they were applied.
+    pub fn overrides(&self) -> &[AppliedOverride] {
+        &self.applied
+    }
+
+    /// The merged files with the overrides applied.
+    pub fn resolved(&self) -> Value {
+        let mut resolved = self.merged();
+        // The overrides applied cleanly when they were loaded.
+        let _ = self.overrides.apply(&mut resolved);
+        resolved
+    }
+
+    /// Every key of the resolved configuration with its value and where the
+    /// value came from: the last file that sets it, or the override that
+    /// replaced it.
+    ///
+    /// Entries of id-keyed lists are addressed by id, as in override paths
+    /// (`sources.my-pg.host`).
+    pub fn value_origins(&self) -> Vec<(String, Value, ValueOrigin<'_>)> {
+        let mut file_origins: HashMap<String, usize> = HashMap::new();
+        for (index, layer) in self.layers.iter().enumerate() {
+            let mut keys = Vec::new();
+            collect_leaves(&layer.value, "", &mut keys);
+            for (path, _) in keys {
+                file_origins.insert(path, index);
+            }
+        }
+
+        let mut leaves = Vec::new();
+        collect_leaves(&self.resolved(), "", &mut leaves);
+        leaves
+            .into_iter()
+            .filter_map(|(path, value)| {
+                let overridden = self.applied.iter().rev().find(|applied| {
+                    path == applied.path || path.starts_with(&format!("{}.", applied.path))
+                });
+                let origin = match overridden {
+                    Some(applied) => ValueOrigin::Override(&applied.source),
+                    None => {
+                        // A key inside a value a file sets as a whole
+                        // belongs to that file.
+                        let index = std::iter::successors(Some(path.as_str()), |p| {
+                            p.rsplit_once('.').map(|(parent, _)| parent)
+                        })
+                        .find_map(|p| file_origins.get(p))?;
+                        ValueOrigin::File(&self.layers[*index].path)
+                    }
+                };
+                Some((path, value, origin))
+            })
+            .collect()
+    }
+
+    /// The files, in merge order. The first is the main config file.
+    pub fn layers(&self) -> &[ConfigLayer] {
+        &self.layers
+    }
+
+    /// All files merged into one document.
+    pub fn merged(&self) -> Value {
+        let mut merged = Value::Mapping(Mapping::new());
+        for layer in &self.layers {
+            merge_value(&mut merged, layer.value.clone());
+        }
+        merged
+    }
+
+    /// Use the id the merged config resolved to for top-level components
+    /// (it is generated when the files do not set one).
+    pub fn set_top_level_id(&mut self, id: &str) {
+        if self.top_level_id != id {
+            self.top_level_id = id.to_string();
+            self.index_origins();
+        }
+    }
+
+    /// The file that first defines a component.
+    pub fn origin(&self, key: &ComponentKey) -> Option<&Path> {
+        self.origins
+            .get(key)
+            .map(|index| self.layers[*index].path.as_path())
+    }
+
+    /// Components of the merged configuration.
+    pub fn components(&self) -> HashMap<ComponentKey, Value> {
+        components_of(&self.merged(), &self.top_level_id)
+            .into_iter()
+            .collect()
+    }
+
+    fn index_origins(&mut self) {
+        self.origins.clear();
+        for (index, layer) in self.layers.iter().enumerate() {
+            for (key, _) in components_of(&layer.value, &self.top_level_id) {
+                self.origins.entry(key).or_insert(index);
+            }
+        }
+    }
+
+    /// Work out the new content of each file for the configuration `saved`.
+    ///
+    /// `baseline` holds the components as they were when the files were last
+    /// written; only components that differ from it are touched, so every
+    /// other entry of every file is kept as the user wrote it:
+    ///
+    /// - a changed component is written to the last file that defines or
+    ///   patches it: in full if no earlier file has it, otherwise by
+    ///   merging the fields that changed from `baseline` into that file's
+    ///   patch. Earlier files are left untouched.
+    /// - a new component goes to the file that declares its instance
+    /// - a deleted component is removed from every file
+    ///
+    /// New instances are added to the main file. If that would mix the
+    /// single- and multi-instance formats, the main file is rewritten with
+    /// the whole configuration instead and the other files keep only their
+    /// settings.
+    ///
+    /// Files are re-read so edits made since startup are kept. Returns the
+    /// files whose content changed.
+    pub fn write_back(
+        &self,
+        saved: &DrasiServerConfig,
+        baseline: &HashMap<ComponentKey, Value>,
+    ) -> Result<Vec<(PathBuf, Value)>, ConfigError> {
+        let saved_value = serde_yaml::to_value(saved)?;
+        let saved_top_level_id = saved_value
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let saved_components = components_of(&saved_value, &saved_top_level_id);
+
+        let saved_keys: HashSet<&ComponentKey> = saved_components.iter().map(|(k, _)| k).collect();
+        let changed: HashSet<&ComponentKey> = saved_components
+            .iter()
+            .filter(|(key, value)| baseline.get(key) != Some(value))
+            .map(|(key, _)| key)
+            .collect();
+        let removed: HashSet<&ComponentKey> = baseline
+            .keys()
+            .filter(|key| !saved_keys.contains(key))
+            .collect();
+
+        let originals = self
+            .layers
+            .iter()
+            .map(|layer| read_value(&layer.path))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut docs = originals.clone();
+
+        // Where each changed component is written, and what is written there.
+        let layer_components: Vec<HashMap<ComponentKey, Value>> = originals
+            .iter()
+            .map(|doc| components_of(doc, &self.top_level_id).into_iter().collect())
+            .collect();
+        let targets: HashMap<&ComponentKey, (usize, Value)> = saved_components
+            .iter()
+            .filter(|(key, _)| changed.contains(key))
+            .filter_map(|(key, value)| {
+                let (target, current) = layer_components
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .find_map(|(index, components)| Some((index, components.get(key)?)))?;
+                let patched = layer_components[..target]
+                    .iter()
+                    .any(|components| components.contains_key(key));
+                let entry = match (patched, baseline.get(key)) {
+                    (true, Some(before)) => {
+                        let mut entry = current.clone();
+                        if let Some(diff) = diff_value(before, value) {
+                            merge_layers(&mut entry, diff, false);
+                        }
+                        entry
+                    }
+                    _ => value.clone(),
+                };
+                Some((key, (target, entry)))
+            })
+            .collect();
+
+        let files_multi = docs.iter().any(|doc| !instance_entries(doc).is_empty());
+        let saved_multi = !instance_entries(&saved_value).is_empty();
+        let saved_instances: Vec<(String, Value)> = match saved_value.get("instances") {
+            Some(Value::Sequence(entries)) if saved_multi => entries
+                .iter()
+                .filter_map(|entry| Some((entry_id(entry)?.to_string(), entry.clone())))
+                .collect(),
+            _ => vec![(saved_top_level_id.clone(), Value::Null)],
+        };
+        let declared: HashSet<String> = if files_multi {
+            docs.iter()
+                .flat_map(|doc| instance_entries(doc).into_iter().map(str::to_string))
+                .collect()
+        } else {
+            HashSet::from([self.top_level_id.clone()])
+        };
+        let new_instances: Vec<&(String, Value)> = saved_instances
+            .iter()
+            .filter(|(id, _)| !declared.contains(id))
+            .collect();
+
+        if !new_instances.is_empty() && !(files_multi && saved_multi) {
+            rewrite_into_main(&mut docs, saved_value);
+        } else {
+            let saved_ids: HashSet<&str> =
+                saved_instances.iter().map(|(id, _)| id.as_str()).collect();
+            let mut placed: HashSet<ComponentKey> = HashSet::new();
+
+            for (index, doc) in docs.iter_mut().enumerate() {
+                if files_multi {
+                    if let Some(Value::Sequence(entries)) = doc.get_mut("instances") {
+                        entries.retain(|entry| {
+                            entry_id(entry).is_none_or(|id| saved_ids.contains(id))
+                        });
+                    }
+                }
+                for_each_list_mut(doc, &self.top_level_id, |instance_id, list, items| {
+                    items.retain_mut(|item| {
+                        let Some(id) = entry_id(item) else {
+                            return true;
+                        };
+                        let key = ComponentKey {
+                            instance_id: instance_id.to_string(),
+                            list,
+                            id: id.to_string(),
+                        };
+                        if removed.contains(&key) {
+                            return false;
+                        }
+                        if !changed.contains(&key) {
+                            return true;
+                        }
+                        if let Some((target, value)) = targets.get(&key) {
+                            if *target == index {
+                                *item = value.clone();
+                                placed.insert(key);
+                            }
+                        }
+                        true
+                    });
+                });
+            }
+
+            for (id, entry) in new_instances {
+                push_instance(&mut docs[0], entry.clone());
+                placed.extend(
+                    saved_components
+                        .iter()
+                        .filter(|(key, _)| key.instance_id == *id)
+                        .map(|(key, _)| key.clone()),
+                );
+            }
+
+            for (key, value) in &saved_components {
+                if changed.contains(key) && !placed.contains(key) {
+                    let home = self.instance_home(&docs, &key.instance_id);
+                    push_component(&mut docs[home], &self.top_level_id, key, value.clone());
+                }
+            }
+        }
+
+        Ok(self
+            .layers
+            .iter()
+            .zip(docs.into_iter().zip(originals))
+            .filter(|(_, (doc, original))| doc != original)
+            .map(|(layer, (doc, _))| (layer.path.clone(), doc))
+            .collect())
+    }
+
+    /// Index of the file that declares an instance: the first with an
+    /// `instances` entry for it, otherwise the main file.
+    fn instance_home(&self, docs: &[Value], instance_id: &str) -> usize {
+        docs.iter()
+            .position(|doc| instance_entries(doc).contains(&instance_id))
+            .unwrap_or(0)
+    }
+}
+
+/// Components of a config, as [`ConfigLayers::write_back`] compares them.
+pub fn config_components(
+    config: &DrasiServerConfig,
+) -> Result<HashMap<ComponentKey, Value>, ConfigError> {
+    let value = serde_yaml::to_value(config)?;
+    let top_level_id = value
+        .get("id")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    Ok(components_of(&value, &top_level_id).into_iter().collect())
+}
+
+#[derive(Default)]
+struct LayerLoader {
+    layers: Vec<ConfigLayer>,
+    seen: HashSet<PathBuf>,
+}
+
+impl LayerLoader {
+    fn add(&mut self, path: &Path) -> Result<(), ConfigError> {
+        let canonical = fs::canonicalize(path).map_err(|source| ConfigError::FileReadError {
+            path: path.display().to_string(),
+            source,
+        })?;
+        if !self.seen.insert(canonical) {
+            return Err(ConfigError::IncludeError(format!(
+                "'{}' is included more than once",
+                path.display()
+            )));
+        }
+
+        let mut value = read_value(path)?;
+        let includes = take_includes(&mut value, path)?;
+        self.layers.push(ConfigLayer {
+            path: path.to_path_buf(),
+            value,
+        });
+
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        for include in includes {
+            let target = base_dir.join(include);
+            if target.is_dir() {
+                for file in config_files_in(&target)? {
+                    self.add(&file)?;
+                }
+            } else {
+                self.add(&target)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse a config file as YAML, or JSON if that fails. An empty file is an
+/// empty mapping.
+pub(crate) fn read_value(path: &Path) -> Result<Value, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|source| ConfigError::FileReadError {
+        path: path.display().to_string(),
+        source,
+    })?;
+    let value = match serde_yaml::from_str::<Value>(&content) {
+        Ok(value) => value,
+        Err(yaml_err) => match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(json) => serde_yaml::to_value(json)?,
+            Err(json_err) => {
+                return Err(ConfigError::ParseError {
+                    path: path.display().to_string(),
+                    yaml_err: yaml_err.to_string(),
+                    json_err: json_err.to_string(),
+                })
+            }
+        },
+    };
+    match value {
+        Value::Null => Ok(Value::Mapping(Mapping::new())),
+        Value::Mapping(_) => Ok(value),
+        _ => Err(ConfigError::IncludeError(format!(
+            "'{}' must contain a mapping of config keys",
+            path.display()
+        ))),
+    }
+}
+
+/// Remove the `include` key, returning the paths it lists.
+fn take_includes(value: &mut Value, path: &Path) -> Result<Vec<String>, ConfigError> {
+    let Some(include) = value
+        .as_mapping_mut()
+        .and_then(|map| map.shift_remove(INCLUDE_KEY))
+    else {
+        return Ok(Vec::new());
+    };
+    let invalid = || {
+        ConfigError::IncludeError(format!(
+            "'{INCLUDE_KEY}' in '{}' must be a path or a list of paths",
+            path.display()
+        ))
+    };
+    match include {
+        Value::String(single) => Ok(vec![single]),
+        Value::Sequence(items) => items
+            .into_iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(s),
+                _ => Err(invalid()),
+            })
+            .collect(),
+        _ => Err(invalid()),
+    }
+}
+
+/// Config files directly inside `dir`, in name order. Hidden files are
+/// skipped.
+fn config_files_in(dir: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    let entries = fs::read_dir(dir).map_err(|source| ConfigError::FileReadError {
+        path: dir.display().to_string(),
+        source,
+    })?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            let hidden = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with('.'));
+            let config_ext = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| CONFIG_EXTENSIONS.contains(&e));
+            !hidden && config_ext
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// `server.yaml` + `prod` → `server.prod.yaml`
+pub fn overlay_path(path: &Path, env: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}.{env}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{env}"),
+    };
+    path.with_file_name(name)
+}
+
+/// Merge `overlay` into `base`. See the module docs.
+pub fn merge_value(base: &mut Value, overlay: Value) {
+    merge_layers(base, overlay, true);
+}
+
+/// Merge `overlay` into `base`, removing the fields it marks `!unset` when
+/// `apply_unset`, and otherwise keeping the markers, so that an overlay
+/// merged into another still removes them from the files below.
+fn merge_layers(base: &mut Value, overlay: Value, apply_unset: bool) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                if apply_unset && is_unset(&value) {
+                    base.shift_remove(&key);
+                    continue;
+                }
+                match base.get_mut(&key) {
+                    Some(existing) => merge_layers(existing, value, apply_unset),
+                    None => {
+                        base.insert(key, without_unset(value, apply_unset));
+                    }
+                }
+            }
+        }
+        (Value::Sequence(base), Value::Sequence(overlay))
+            if is_keyed(base) && is_keyed(&overlay) =>
+        {
+            for item in overlay {
+                match base
+                    .iter_mut()
+                    .find(|existing| entry_id(existing) == entry_id(&item))
+                {
+                    Some(existing) => merge_layers(existing, item, apply_unset),
+                    None => base.push(without_unset(item, apply_unset)),
+                }
+            }
+        }
+        (base, overlay) => *base = without_unset(overlay, apply_unset),
+    }
+}
+
+/// The `!unset` marker [`diff_value`] gives a removed field.
+fn unset_marker() -> Value {
+    Value::Tagged(Box::new(TaggedValue {
+        tag: Tag::new(UNSET_TAG),
+        value: Value::Null,
+    }))
+}
+
+fn is_unset(value: &Value) -> bool {
+    matches!(value, Value::Tagged(tagged) if tagged.tag == UNSET_TAG)
+}
+
+/// `value` without the fields it marks `!unset`, when `apply_unset`.
+fn without_unset(value: Value, apply_unset: bool) -> Value {
+    if !apply_unset {
+        return value;
+    }
+    match value {
+        Value::Mapping(fields) => Value::Mapping(
+            fields
+                .into_iter()
+                .filter(|(_, value)| !is_unset(value))
+                .map(|(key, value)| (key, without_unset(value, true)))
+                .collect(),
+        ),
+        Value::Sequence(items) => Value::Sequence(
+            items
+                .into_iter()
+                .map(|item| without_unset(item, true))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// What `saved` changes relative to `lower`, as an overlay for
+/// [`merge_value`]: `None` when they are equal. Fields missing from `saved`
+/// are marked `!unset`, and a keyed list that lost entries is given in full.
+fn diff_value(lower: &Value, saved: &Value) -> Option<Value> {
+    match (lower, saved) {
+        (Value::Mapping(lower), Value::Mapping(saved)) => {
+            let mut diff = Mapping::new();
+            for (key, value) in saved {
+                let changed = match lower.get(key) {
+                    Some(existing) => diff_value(existing, value),
+                    None => Some(value.clone()),
+                };
+                if let Some(changed) = changed {
+                    diff.insert(key.clone(), changed);
+                }
+            }
+            for key in lower.keys().filter(|key| !saved.contains_key(*key)) {
+                diff.insert(key.clone(), unset_marker());
+            }
+            (!diff.is_empty()).then_some(Value::Mapping(diff))
+        }
+        (Value::Sequence(lower), Value::Sequence(saved)) if is_keyed(lower) && is_keyed(saved) => {
+            let saved_ids: HashSet<&str> = saved.iter().filter_map(entry_id).collect();
+            if lower
+                .iter()
+                .filter_map(entry_id)
+                .any(|id| !saved_ids.contains(id))
+            {
+                return (lower != saved).then(|| Value::Sequence(saved.clone()));
+            }
+            let diff: Sequence = saved
+                .iter()
+                .filter_map(|item| {
+                    let Some(existing) = lower.iter().find(|e| entry_id(e) == entry_id(item))
+                    else {
+                        return Some(item.clone());
+                    };
+                    let changed = diff_value(existing, item)?;
+                    Some(with_id(entry_id(item)?, Some(changed)))
+                })
+                .collect();
+            (!diff.is_empty()).then_some(Value::Sequence(diff))
+        }
+        (lower, saved) if lower == saved => None,
+        (_, saved) => Some(saved.clone()),
+    }
+}
+
+/// A keyed-list entry `{id, ...fields of diff}`.
+fn with_id(id: &str, diff: Option<Value>) -> Value {
+    let mut entry = Mapping::new();
+    entry.insert(Value::from("id"), Value::from(id));
+    if let Some(Value::Mapping(fields)) = diff {
+        entry.extend(fields);
+    }
+    Value::Mapping(entry)
+}
+
+fn entry_id(value: &Value) -> Option<&str> {
+    value.as_mapping()?.get("id")?.as_str()
+}
+
+/// Paths and values of the scalar (and other non-nested) values of a
+/// document. Entries of id-keyed lists are addressed by id.
+pub(crate) fn collect_leaves(value: &Value, prefix: &str, out: &mut Vec<(String, Value)>) {
+    let child = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+    match value {
+        Value::Mapping(map) if !map.is_empty() => {
+            for (key, item) in map {
+                if let Some(key) = key.as_str() {
+                    collect_leaves(item, &child(key), out);
+                }
+            }
+        }
+        Value::Sequence(items) if !items.is_empty() && is_keyed(items) => {
+            for item in items {
+                if let Some(id) = entry_id(item) {
+                    collect_leaves(item, &child(id), out);
+                }
+            }
+        }
+        _ if prefix.is_empty() => {}
+        _ => out.push((prefix.to_string(), value.clone())),
+    }
+}
+
+fn is_keyed(items: &Sequence) -> bool {
+    items.iter().all(|item| entry_id(item).is_some())
+}
+
+/// Ids of a document's `instances` entries.
+fn instance_entries(doc: &Value) -> Vec<&str> {
+    match doc.get("instances") {
+        Some(Value::Sequence(entries)) => entries.iter().filter_map(entry_id).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Every component of a document, in order.
+fn components_of(doc: &Value, top_level_id: &str) -> Vec<(ComponentKey, Value)> {
+    let mut components = Vec::new();
+    let mut collect = |instance_id: &str, container: &Value| {
+        for list in COMPONENT_LISTS {
+            let Some(Value::Sequence(items)) = container.get(list) else {
+                continue;
+            };
+            for item in items {
+                if let Some(id) = entry_id(item) {
+                    let key = ComponentKey {
+                        instance_id: instance_id.to_string(),
+                        list,
+                        id: id.to_string(),
+                    };
+                    components.push((key, item.clone()));
+                }
+            }
+        }
+    };
+    collect(top_level_id, doc);
+    if let Some(Value::Sequence(entries)) = doc.get("instances") {
+        for entry in entries {
+            if let Some(id) = entry_id(entry) {
+                collect(id, entry);
+            }
+        }
+    }
+    components
+}
+
+/// Call `f` with each component list of a document and its instance id.
+fn for_each_list_mut(
+    doc: &mut Value,
+    top_level_id: &str,
+    mut f: impl FnMut(&str, &'static str, &mut Sequence),
+) {
+    let mut visit = |instance_id: &str, container: &mut Value| {
+        for list in COMPONENT_LISTS {
+            if let Some(Value::Sequence(items)) = container.get_mut(list) {
+                f(instance_id, list, items);
+            }
+        }
+    };
+    visit(top_level_id, doc);
+    if let Some(Value::Sequence(entries)) = doc.get_mut("instances") {
+        for entry in entries {
+            if let Some(id) = entry_id(entry).map(str::to_string) {
+                visit(&id, entry);
+            }
+        }
+    }
+}
+
+/// Append a component to the instance's entry in `doc`, or to the top-level
+/// lists when `doc` declares the instance there.
+fn push_component(doc: &mut Value, top_level_id: &str, key: &ComponentKey, value: Value) {
+    let has_entry = instance_entries(doc).contains(&key.instance_id.as_str());
+    let container = if has_entry {
+        doc.get_mut("instances")
+            .and_then(Value::as_sequence_mut)
+            .and_then(|entries| {
+                entries
+                    .iter_mut()
+                    .find(|entry| entry_id(entry) == Some(key.instance_id.as_str()))
+            })
+    } else if key.instance_id == top_level_id {
+        Some(doc)
+    } else {
+        None
+    };
+    let Some(Value::Mapping(container)) = container else {
+        return;
+    };
+    let list = container
+        .entry(Value::String(key.list.to_string()))
+        .or_insert_with(|| Value::Sequence(Sequence::new()));
+    if let Value::Sequence(items) = list {
+        items.push(value);
+    }
+}
+
+fn push_instance(doc: &mut Value, entry: Value) {
+    let Value::Mapping(map) = doc else {
+        return;
+    };
+    let instances = map
+        .entry(Value::String("instances".to_string()))
+        .or_insert_with(|| Value::Sequence(Sequence::new()));
+    if let Value::Sequence(entries) = instances {
+        entries.push(entry);
+    }
+}
+
+/// Put the whole configuration in the main file, keeping its `include`
+/// list, and leave the other files only their settings.
+fn rewrite_into_main(docs: &mut [Value], saved: Value) {
+    let include = docs.first().and_then(|doc| doc.get(INCLUDE_KEY)).cloned();
+    for doc in docs.iter_mut().skip(1) {
+        if let Value::Mapping(map) = doc {
+            map.shift_remove("instances");
+            for list in COMPONENT_LISTS {
+                map.shift_remove(list);
+            }
+        }
+    }
+    if let Some(main) = docs.first_mut() {
+        *main = saved;
+        if let (Some(include), Value::Mapping(map)) = (include, main) {
+            map.insert(Value::String(INCLUDE_KEY.to_string()), include);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn yaml(s: &str) -> Value {
+        serde_yaml::from_str(s).unwrap()
+    }
+
+    fn ids(value: &Value, list: &str) -> Vec<String> {
+        value[list]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .map(|item| entry_id(item).unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_by_id() {
+        let mut base = yaml(
+            "port: 8080\nsources:\n  - {id: pg, kind: postgres, host: localhost, port: 5432}\n  - {id: mock, kind: mock}",
+        );
+        merge_value(
+            &mut base,
+            yaml("port: 9090\nsources:\n  - {id: pg, host: db.prod}\n  - {id: extra, kind: mock}"),
+        );
+        assert_eq!(base["port"], yaml("9090"));
+        assert_eq!(ids(&base, "sources"), vec!["pg", "mock", "extra"]);
+        assert_eq!(base["sources"][0]["host"], yaml("db.prod"));
+        assert_eq!(base["sources"][0]["port"], yaml("5432"));
+
+        // Lists without ids are replaced.
+        let mut base = yaml("corsAllowedOrigins: [a, b]");
+        merge_value(&mut base, yaml("corsAllowedOrigins: [c]"));
+        assert_eq!(base["corsAllowedOrigins"], yaml("[c]"));
+
+        // `!unset` removes a field, and is dropped where there is none.
+        let mut base = yaml("sources:\n  - {id: pg, host: localhost, sslMode: require}");
+        merge_value(
+            &mut base,
+            yaml("sources:\n  - {id: pg, sslMode: !unset}\n  - {id: new, tls: !unset}"),
+        );
+        assert_eq!(
+            base,
+            yaml("sources:\n  - {id: pg, host: localhost}\n  - {id: new}")
+        );
+    }
+
+    #[test]
+    fn test_load_order_and_origins() {
+        let dir = TempDir::new().unwrap();
+        let main = write(
+            dir.path(),
+            "server.yaml",
+            "id: main\nlogLevel: info\ninclude: [sources.yaml, more]\nsources:\n  - {id: a, kind: mock}",
+        );
+        write(
+            dir.path(),
+            "sources.yaml",
+            "sources:\n  - {id: b, kind: mock}",
+        );
+        write(
+            dir.path(),
+            "more/2.yaml",
+            "queries:\n  - {id: q2, query: 'MATCH (n) RETURN n'}",
+        );
+        write(
+            dir.path(),
+            "more/1.yaml",
+            "queries:\n  - {id: q1, query: 'MATCH (n) RETURN n'}",
+        );
+        write(dir.path(), "more/notes.txt", "ignored");
+        write(
+            dir.path(),
+            "conf.d/10-reactions.yaml",
+            "reactions:\n  - {id: r, kind: log, queries: [q1]}",
+        );
+        write(
+            dir.path(),
+            "server.prod.yaml",
+            "logLevel: warn\nsources:\n  - {id: a, auto: false}",
+        );
+
+        let layers = ConfigLayers::load(&main, Some("prod")).unwrap();
+        let names: Vec<String> = layers
+            .layers()
+            .iter()
+            .map(|l| {
+                l.path
+                    .strip_prefix(dir.path())
+                    .unwrap()
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "server.yaml",
+                "sources.yaml",
+                "more/1.yaml",
+                "more/2.yaml",
+                "conf.d/10-reactions.yaml",
+                "server.prod.yaml"
+            ]
+        );
+
+        let merged = layers.merged();
+        assert!(merged.get(INCLUDE_KEY).is_none());
+        assert_eq!(merged["logLevel"], yaml("warn"));
+        assert_eq!(ids(&merged, "sources"), vec!["a", "b"]);
+        assert_eq!(ids(&merged, "queries"), vec!["q1", "q2"]);
+
+        // The overlay patches `a`, but `a` still belongs to the main file.
+        let key = |list, id: &str| ComponentKey {
+            instance_id: "main".to_string(),
+            list,
+            id: id.to_string(),
+        };
+        assert_eq!(layers.origin(&key("sources", "a")), Some(main.as_path()));
+        assert_eq!(
+            layers.origin(&key("reactions", "r")),
+            Some(dir.path().join("conf.d/10-reactions.yaml").as_path())
+        );
+    }
+
+    #[test]
+    fn test_include_errors() {
+        let dir = TempDir::new().unwrap();
+        let main = write(dir.path(), "server.yaml", "include: other.yaml");
+        write(dir.path(), "other.yaml", "include: server.yaml");
+        let err = ConfigLayers::load(&main, None).unwrap_err();
+        assert!(err.to_string().contains("more than once"), "{err}");
+
+        let main = write(dir.path(), "single.yaml", "port: 1");
+        let err = ConfigLayers::load(&main, Some("staging")).unwrap_err();
+        assert!(err.to_string().contains("single.staging.yaml"), "{err}");
+    }
+
+    #[test]
+    fn test_write_back_touches_only_changed_components() {
+        let dir = TempDir::new().unwrap();
+        let main = write(
+            dir.path(),
+            "server.yaml",
+            "id: main\nport: 8080\nsources:\n  - {id: a, kind: mock}\n",
+        );
+        let included = write(
+            dir.path(),
+            "conf.d/sources.yaml",
+            "# managed by ops\nsources:\n  - {id: b, kind: mock}\n  - {id: c, kind: mock}\n",
+        );
+        let overlay = write(
+            dir.path(),
+            "server.prod.yaml",
+            "port: 9090\nsources:\n  - {id: b, note: prod}\n",
+        );
+        let layers = ConfigLayers::load(&main, Some("prod")).unwrap();
+        let config = |sources: &str| -> DrasiServerConfig {
+            serde_yaml::from_str(&format!("id: main\nsources:\n{sources}")).unwrap()
+        };
+        let baseline = config_components(&config(
+            "  - {id: a, kind: mock}\n  - {id: b, kind: mock, note: prod}\n  - {id: c, kind: mock}",
+        ))
+        .unwrap();
+
+        // Update b, delete c, add d.
+        let saved = config(
+            "  - {id: a, kind: mock}\n  - {id: b, kind: mock, note: edited}\n  - {id: d, kind: mock}",
+        );
+
+        let written: HashMap<PathBuf, Value> = layers
+            .write_back(&saved, &baseline)
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        // b's change goes to the overlay that patches it and the file
+        // defining b keeps its entry; c is gone; d is new and goes to the
+        // main file. Settings are left alone.
+        let included_doc = &written[&included];
+        assert_eq!(ids(included_doc, "sources"), vec!["b"]);
+        assert_eq!(included_doc["sources"][0], yaml("{id: b, kind: mock}"));
+        assert_eq!(
+            written[&overlay]["sources"],
+            yaml("[{id: b, note: edited}]")
+        );
+        assert_eq!(written[&overlay]["port"], yaml("9090"));
+        let main_doc = &written[&main];
+        assert_eq!(ids(main_doc, "sources"), vec!["a", "d"]);
+        assert_eq!(main_doc["port"], yaml("8080"));
+    }
+
+    #[test]
+    fn test_write_back_patches_overlay_with_diff() {
+        let dir = TempDir::new().unwrap();
+        let main = write(
+            dir.path(),
+            "server.yaml",
+            "id: main\nsources:\n  - id: a\n    kind: mock\n    \
+             properties: {host: localhost, port: 5432, tls: true}\n",
+        );
+        let overlay = write(
+            dir.path(),
+            "server.prod.yaml",
+            "sources:\n  - id: a\n    properties: {host: db.prod}\n",
+        );
+        let layers = ConfigLayers::load(&main, Some("prod")).unwrap();
+        let merged: DrasiServerConfig = serde_yaml::from_value(layers.merged()).unwrap();
+        let baseline = config_components(&merged).unwrap();
+
+        // Change the port and drop tls through the API.
+        let saved: DrasiServerConfig = serde_yaml::from_str(
+            "id: main\nsources:\n  - id: a\n    kind: mock\n    \
+             properties: {host: db.prod, port: 6543}\n",
+        )
+        .unwrap();
+        let written: HashMap<PathBuf, Value> = layers
+            .write_back(&saved, &baseline)
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        assert!(!written.contains_key(&main), "base file was rewritten");
+        assert_eq!(
+            written[&overlay]["sources"],
+            yaml("[{id: a, properties: {host: db.prod, port: 6543, tls: !unset null}}]")
+        );
+
+        let mut effective = read_value(&main).unwrap();
+        merge_value(&mut effective, written[&overlay].clone());
+        assert_eq!(
+            effective["sources"][0]["properties"],
+            yaml("{host: db.prod, port: 6543}")
+        );
+    }
+}
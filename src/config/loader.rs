@@ -16,7 +16,10 @@
 //!
 //! This module provides the primary interface for loading Drasi Server configuration files.
 
+use super::layers::ConfigLayers;
+use super::overrides::ConfigOverrides;
 use super::types::DrasiServerConfig;
+use crate::api::models::ConfigValue;
 use serde::de::DeserializeOwned;
 use std::fs;
 use std::path::Path;
@@ -47,6 +50,27 @@ pub enum ConfigError {
 
     #[error("Unknown fields in configuration: {0}")]
     FieldValidationError(#[from] super::validation::ValidationError),
+
+    #[error("Failed to read '{path}': {source}")]
+    FileReadError {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid config include: {0}")]
+    IncludeError(String),
+
+    #[error("Invalid config override: {0}")]
+    OverrideError(String),
+}
+
+/// How [`load_config_layers`] assembles the configuration.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Environment whose overlay is layered on the config file (`--env`).
+    pub env: Option<String>,
+    /// Keys overridden by `DRASI__*` environment variables and `--set` flags.
+    pub overrides: ConfigOverrides,
 }
 
 /// Deserialize YAML.
@@ -86,7 +110,8 @@ pub fn from_json_str<T: DeserializeOwned>(s: &str) -> Result<T, ConfigError> {
 /// Load DrasiServerConfig from a file.
 ///
 /// This is the primary function for loading Drasi Server configuration. It:
-/// 1. Reads the file
+/// 1. Reads the file, along with the files it includes and any `conf.d/`
+///    files next to it (see [`super::layers`])
 /// 2. Tries to parse as YAML, falls back to JSON if that fails
 /// 3. Validates the configuration
 ///
@@ -114,7 +139,47 @@ pub fn from_json_str<T: DeserializeOwned>(s: &str) -> Result<T, ConfigError> {
 /// println!("Server configuration loaded successfully");
 /// ```
 pub fn load_config_file<P: AsRef<Path>>(path: P) -> Result<DrasiServerConfig, ConfigError> {
+    load_config_layers(path, &LoadOptions::default()).map(|(config, _)| config)
+}
+
+/// Load DrasiServerConfig from a file and the files layered on it, applying
+/// the overlay for `options.env` (e.g. `server.prod.yaml` for `server.yaml`
+/// and `prod`) and then `options.overrides`.
+///
+/// Returns the merged configuration and where it came from, which
+/// [`crate::persistence::ConfigPersistence`] uses to write changes back to
+/// the file that defines each component.
+///
+/// # Errors
+///
+/// Returns an error if any file cannot be read or parsed, an include is
+/// invalid or cyclic, the overlay for `env` does not exist, an override
+/// names a key that cannot be set, or the merged configuration fails
+/// validation.
+pub fn load_config_layers<P: AsRef<Path>>(
+    path: P,
+    options: &LoadOptions,
+) -> Result<(DrasiServerConfig, ConfigLayers), ConfigError> {
     let path_ref = path.as_ref();
+    let mut layers = ConfigLayers::load(path_ref, options.env.as_deref())?;
+
+    let config = if layers.is_layered() || !options.overrides.is_empty() {
+        let merged = layers.apply_overrides(options.overrides.clone())?;
+        super::validation::validate_config(&merged)?;
+        let config: DrasiServerConfig = serde_yaml::from_value(merged)?;
+        config.validate()?;
+        config
+    } else {
+        load_single_file(path_ref)?
+    };
+
+    if let ConfigValue::Static(id) = &config.id {
+        layers.set_top_level_id(id);
+    }
+    Ok((config, layers))
+}
+
+fn load_single_file(path_ref: &Path) -> Result<DrasiServerConfig, ConfigError> {
     let content = fs::read_to_string(path_ref)?;
 
     // First, try to parse as YAML Value to validate field names
@@ -232,4 +297,48 @@ reactions: []
         );
         assert_eq!(config.port, crate::api::models::ConfigValue::Static(8080));
     }
+
+    #[test]
+    fn test_load_applies_overrides_before_validation() {
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(
+            temp_file.path(),
+            "id: test-server-id\nport: 8080\nsources:\n  - {kind: mock, id: my-src}\n",
+        )
+        .unwrap();
+
+        let options = LoadOptions {
+            env: None,
+            overrides: ConfigOverrides::from_vars([(
+                "DRASI__PORT".to_string(),
+                "9090".to_string(),
+            )])
+            .with_sets(["sources.my-src.autoStart=false"])
+            .unwrap(),
+        };
+        let (config, layers) = load_config_layers(temp_file.path(), &options).unwrap();
+        assert_eq!(config.port, crate::api::models::ConfigValue::Static(9090));
+        assert!(!config.sources[0].auto_start);
+        assert!(layers.writes_in_place());
+
+        let origins: Vec<(String, String)> = layers
+            .value_origins()
+            .into_iter()
+            .map(|(path, _, origin)| (path, origin.to_string()))
+            .collect();
+        assert!(origins.contains(&("port".to_string(), "env DRASI__PORT".to_string())));
+        assert!(origins.contains(&(
+            "sources.my-src.kind".to_string(),
+            temp_file.path().display().to_string()
+        )));
+
+        // The overridden config is what gets validated.
+        let options = LoadOptions {
+            env: None,
+            overrides: ConfigOverrides::default()
+                .with_sets(["port=not-a-port"])
+                .unwrap(),
+        };
+        assert!(load_config_layers(temp_file.path(), &options).is_err());
+    }
 }
@@ -0,0 +1,764 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Forward migration of plugin component configs.
+//!
+//! Sources and reactions record the plugin schema version their config was
+//! written against in `configVersion`. When a plugin upgrade bumps its
+//! `config_version()`, the steps shipped with the plugin rewrite the old
+//! config into the new shape before it is validated and instantiated.
+//!
+//! Plugins ship their migrations with the plugin itself, as an
+//! `x-drasi-config-migrations` extension on the root schema returned by
+//! `config_schema_json()`:
+//!
+//! ```json
+//! {
+//!   "source.postgres.PostgresSourceConfig": {
+//!     "type": "object",
+//!     "x-drasi-config-migrations": [
+//!       {
+//!         "from": "1.0.0",
+//!         "to": "2.0.0",
+//!         "steps": [
+//!           { "op": "rename", "from": "sslmode", "to": "sslMode" },
+//!           { "op": "setDefault", "field": "tableKeys", "value": [] },
+//!           { "op": "replaceValue", "field": "sslMode", "from": "disable", "to": "disabled" },
+//!           { "op": "remove", "field": "legacyPolling" }
+//!         ]
+//!       }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! Field paths are dot-separated (`replication.slotName`). Steps whose field
+//! is absent are skipped, so migrations are safe to apply to partial configs.
+//! A rename onto a field that is already set fails the migration rather than
+//! overwrite it.
+//! Configs without a `configVersion` predate versioning and are assumed to
+//! match the installed plugin.
+//!
+//! Configs are migrated at startup ([`ConfigMigrations::migrate_config`]) and
+//! whenever a component is created or replaced at runtime
+//! ([`migrate_submitted_source`], [`migrate_submitted_reaction`]).
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::api::models::{ReactionConfig, SourceConfig};
+use crate::config::types::DrasiServerConfig;
+use crate::plugin_registry::PluginRegistry;
+use drasi_plugin_sdk::{ReactionPluginDescriptor, SourcePluginDescriptor};
+
+/// Schema extension under which a plugin lists its config migrations.
+pub const MIGRATIONS_EXTENSION: &str = "x-drasi-config-migrations";
+
+/// Upper bound on chained migrations, to stop cycles in malformed files.
+const MAX_CHAIN_LENGTH: usize = 64;
+
+/// A single rewrite applied to a component config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum MigrationStep {
+    /// Move a field to a new path that is not yet set.
+    Rename { from: String, to: String },
+    /// Drop a field that the new schema no longer accepts.
+    Remove { field: String },
+    /// Add a field the new schema requires, unless already present.
+    SetDefault { field: String, value: Value },
+    /// Replace a specific value of a field (e.g. a renamed enum variant).
+    ReplaceValue {
+        field: String,
+        from: Value,
+        to: Value,
+    },
+}
+
+impl MigrationStep {
+    /// Apply the step, returning a description of the change if one was made.
+    fn apply(&self, config: &mut Value) -> Result<Option<String>> {
+        if let MigrationStep::Rename { from, to } = self {
+            if get_path(config, from).is_some() && get_path(config, to).is_some() {
+                return Err(anyhow!(
+                    "cannot rename '{from}' to '{to}': '{to}' is already set"
+                ));
+            }
+        }
+        Ok(self.apply_unchecked(config))
+    }
+
+    fn apply_unchecked(&self, config: &mut Value) -> Option<String> {
+        match self {
+            MigrationStep::Rename { from, to } => {
+                let value = take_path(config, from)?;
+                set_path(config, to, value);
+                Some(format!("renamed '{from}' to '{to}'"))
+            }
+            MigrationStep::Remove { field } => {
+                take_path(config, field)?;
+                Some(format!("removed '{field}'"))
+            }
+            MigrationStep::SetDefault { field, value } => {
+                if get_path(config, field).is_some() {
+                    return None;
+                }
+                set_path(config, field, value.clone());
+                Some(format!("set '{field}' to {value}"))
+            }
+            MigrationStep::ReplaceValue { field, from, to } => {
+                let current = get_path_mut(config, field)?;
+                if current != from {
+                    return None;
+                }
+                *current = to.clone();
+                Some(format!("changed '{field}' from {from} to {to}"))
+            }
+        }
+    }
+}
+
+/// Steps that migrate a config from one schema version to the next.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Migration {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub steps: Vec<MigrationStep>,
+}
+
+/// Migrations for one plugin kind, as shipped in its config schema (see
+/// [`shipped_migrations`]).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginMigrations {
+    /// `source` or `reaction`.
+    pub category: String,
+    pub kind: String,
+    pub migrations: Vec<Migration>,
+}
+
+/// All known migrations, keyed by `(category, kind)`.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigMigrations {
+    by_kind: HashMap<(String, String), Vec<Migration>>,
+}
+
+impl ConfigMigrations {
+    /// Collect the migrations shipped by every registered source and
+    /// reaction plugin. Malformed migration lists are logged and skipped.
+    pub fn from_registry(registry: &PluginRegistry) -> Self {
+        let mut migrations = Self::default();
+        for kind in registry.source_kinds() {
+            if let Some(descriptor) = registry.get_source(kind) {
+                migrations.register_shipped(
+                    "source",
+                    kind,
+                    &descriptor.config_schema_json(),
+                    descriptor.config_schema_name(),
+                );
+            }
+        }
+        for kind in registry.reaction_kinds() {
+            if let Some(descriptor) = registry.get_reaction(kind) {
+                migrations.register_shipped(
+                    "reaction",
+                    kind,
+                    &descriptor.config_schema_json(),
+                    descriptor.config_schema_name(),
+                );
+            }
+        }
+        migrations
+    }
+
+    fn register_shipped(
+        &mut self,
+        category: &str,
+        kind: &str,
+        schema_map_json: &str,
+        entry_name: &str,
+    ) {
+        match shipped_migrations(schema_map_json, entry_name) {
+            Ok(list) if list.is_empty() => {}
+            Ok(list) => {
+                debug!(
+                    "Loaded {} config migration(s) shipped by {category} plugin '{kind}'",
+                    list.len()
+                );
+                self.register(PluginMigrations {
+                    category: category.to_string(),
+                    kind: kind.to_string(),
+                    migrations: list,
+                });
+            }
+            Err(e) => warn!("Ignoring config migrations of {category} plugin '{kind}': {e:#}"),
+        }
+    }
+
+    /// Register the migrations for one plugin kind.
+    pub fn register(&mut self, plugin: PluginMigrations) {
+        self.by_kind
+            .entry((plugin.category, plugin.kind))
+            .or_default()
+            .extend(plugin.migrations);
+    }
+
+    /// Whether no migrations are registered.
+    pub fn is_empty(&self) -> bool {
+        self.by_kind.is_empty()
+    }
+
+    /// Migrate `config` from version `from` to `to`, returning the change
+    /// descriptions. Fails when no chain of migrations connects the versions.
+    pub fn migrate(
+        &self,
+        category: &str,
+        kind: &str,
+        from: &str,
+        to: &str,
+        config: &mut Value,
+    ) -> Result<Vec<String>> {
+        let available = self
+            .by_kind
+            .get(&(category.to_string(), kind.to_string()))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let mut changes = Vec::new();
+        let mut current = from.to_string();
+        for _ in 0..MAX_CHAIN_LENGTH {
+            if current == to {
+                return Ok(changes);
+            }
+            let next = available
+                .iter()
+                .find(|m| m.from == current)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "no migration for {category} kind '{kind}' from config version \
+                         {current} (installed plugin expects {to})"
+                    )
+                })?;
+            for step in &next.steps {
+                changes.extend(step.apply(config)?);
+            }
+            current.clone_from(&next.to);
+        }
+        Err(anyhow!(
+            "migrations for {category} kind '{kind}' do not reach config version {to} \
+             within {MAX_CHAIN_LENGTH} steps"
+        ))
+    }
+
+    /// Migrate every source and reaction in `config` whose `configVersion`
+    /// differs from the registered plugin's `config_version()`, and stamp
+    /// each component with the version it now matches.
+    ///
+    /// Components whose plugin is not registered are left untouched. Every
+    /// component is attempted; failures are collected into the report.
+    pub fn migrate_config(
+        &self,
+        config: &mut DrasiServerConfig,
+        registry: &PluginRegistry,
+    ) -> MigrationReport {
+        let mut report = MigrationReport::default();
+        let sources = config.sources.iter_mut().chain(
+            config
+                .instances
+                .iter_mut()
+                .flat_map(|i| i.sources.iter_mut()),
+        );
+        for source in sources {
+            if let Some(descriptor) = registry.get_source(&source.kind) {
+                let target = descriptor.config_version().to_string();
+                self.migrate_source(source, &target, &mut report);
+            }
+        }
+        let reactions = config.reactions.iter_mut().chain(
+            config
+                .instances
+                .iter_mut()
+                .flat_map(|i| i.reactions.iter_mut()),
+        );
+        for reaction in reactions {
+            if let Some(descriptor) = registry.get_reaction(&reaction.kind) {
+                let target = descriptor.config_version().to_string();
+                self.migrate_reaction(reaction, &target, &mut report);
+            }
+        }
+        report
+    }
+
+    /// Migrate a single source config to `target`. See [`Self::migrate_config`].
+    pub fn migrate_source(
+        &self,
+        source: &mut SourceConfig,
+        target: &str,
+        report: &mut MigrationReport,
+    ) {
+        self.migrate_component(
+            "source",
+            &source.id,
+            &source.kind,
+            &mut source.config_version,
+            &mut source.config,
+            target,
+            report,
+        );
+    }
+
+    /// Migrate a single reaction config to `target`. See [`Self::migrate_config`].
+    pub fn migrate_reaction(
+        &self,
+        reaction: &mut ReactionConfig,
+        target: &str,
+        report: &mut MigrationReport,
+    ) {
+        self.migrate_component(
+            "reaction",
+            &reaction.id,
+            &reaction.kind,
+            &mut reaction.config_version,
+            &mut reaction.config,
+            target,
+            report,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn migrate_component(
+        &self,
+        category: &str,
+        id: &str,
+        kind: &str,
+        config_version: &mut Option<String>,
+        config: &mut Value,
+        target: &str,
+        report: &mut MigrationReport,
+    ) {
+        let Some(from) = config_version.clone() else {
+            *config_version = Some(target.to_string());
+            return;
+        };
+        if from == target {
+            return;
+        }
+        // Work on a copy so a failed chain leaves the config untouched.
+        let mut migrated = config.clone();
+        match self.migrate(category, kind, &from, target, &mut migrated) {
+            Ok(changes) => {
+                *config = migrated;
+                *config_version = Some(target.to_string());
+                report.migrated.push(ComponentMigration {
+                    component_type: category.to_string(),
+                    component_id: id.to_string(),
+                    kind: kind.to_string(),
+                    from_version: from,
+                    to_version: target.to_string(),
+                    changes,
+                });
+            }
+            Err(e) => report.failed.push(ComponentMigrationError {
+                component_type: category.to_string(),
+                component_id: id.to_string(),
+                kind: kind.to_string(),
+                message: e.to_string(),
+            }),
+        }
+    }
+}
+
+/// Read the migrations listed under [`MIGRATIONS_EXTENSION`] on the root
+/// schema (`entry_name`) of a plugin's `config_schema_json()` map.
+pub fn shipped_migrations(schema_map_json: &str, entry_name: &str) -> Result<Vec<Migration>> {
+    let schema_map: Value =
+        serde_json::from_str(schema_map_json).context("config schema is not valid JSON")?;
+    match schema_map
+        .get(entry_name)
+        .and_then(|entry| entry.get(MIGRATIONS_EXTENSION))
+    {
+        Some(list) => serde_json::from_value(list.clone())
+            .with_context(|| format!("invalid '{MIGRATIONS_EXTENSION}' on '{entry_name}'")),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Migrate a source config submitted at runtime (create, upsert or apply) to
+/// `descriptor`'s config version, returning the migration performed, if any.
+pub fn migrate_submitted_source(
+    descriptor: &dyn SourcePluginDescriptor,
+    source: &mut SourceConfig,
+) -> Result<Option<ComponentMigration>> {
+    let mut migrations = ConfigMigrations::default();
+    migrations.register_shipped(
+        "source",
+        descriptor.kind(),
+        &descriptor.config_schema_json(),
+        descriptor.config_schema_name(),
+    );
+    let mut report = MigrationReport::default();
+    migrations.migrate_source(source, descriptor.config_version(), &mut report);
+    report.into_outcome()
+}
+
+/// Migrate a reaction config submitted at runtime. See
+/// [`migrate_submitted_source`].
+pub fn migrate_submitted_reaction(
+    descriptor: &dyn ReactionPluginDescriptor,
+    reaction: &mut ReactionConfig,
+) -> Result<Option<ComponentMigration>> {
+    let mut migrations = ConfigMigrations::default();
+    migrations.register_shipped(
+        "reaction",
+        descriptor.kind(),
+        &descriptor.config_schema_json(),
+        descriptor.config_schema_name(),
+    );
+    let mut report = MigrationReport::default();
+    migrations.migrate_reaction(reaction, descriptor.config_version(), &mut report);
+    report.into_outcome()
+}
+
+/// A component config that was migrated to a newer schema version.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentMigration {
+    pub component_type: String,
+    pub component_id: String,
+    pub kind: String,
+    pub from_version: String,
+    pub to_version: String,
+    /// Human-readable description of each change.
+    pub changes: Vec<String>,
+}
+
+/// A component config that could not be migrated.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentMigrationError {
+    pub component_type: String,
+    pub component_id: String,
+    pub kind: String,
+    pub message: String,
+}
+
+/// Outcome of migrating a server config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub migrated: Vec<ComponentMigration>,
+    pub failed: Vec<ComponentMigrationError>,
+}
+
+impl MigrationReport {
+    /// Whether nothing was migrated and nothing failed.
+    pub fn is_empty(&self) -> bool {
+        self.migrated.is_empty() && self.failed.is_empty()
+    }
+
+    /// Whether any component could not be migrated.
+    pub fn has_errors(&self) -> bool {
+        !self.failed.is_empty()
+    }
+
+    /// The single migration of a one-component report, or its failure.
+    fn into_outcome(mut self) -> Result<Option<ComponentMigration>> {
+        match self.failed.pop() {
+            Some(e) => Err(anyhow!(e.message)),
+            None => Ok(self.migrated.pop()),
+        }
+    }
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for m in &self.migrated {
+            writeln!(
+                f,
+                "{} '{}' ({}): {} -> {}",
+                m.component_type, m.component_id, m.kind, m.from_version, m.to_version
+            )?;
+            if m.changes.is_empty() {
+                writeln!(f, "  - no changes needed")?;
+            }
+            for change in &m.changes {
+                writeln!(f, "  - {change}")?;
+            }
+        }
+        for e in &self.failed {
+            writeln!(
+                f,
+                "{} '{}' ({}): {}",
+                e.component_type, e.component_id, e.kind, e.message
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn get_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(value, |current, segment| current.get(segment))
+}
+
+fn get_path_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.')
+        .try_fold(value, |current, segment| current.get_mut(segment))
+}
+
+fn take_path(value: &mut Value, path: &str) -> Option<Value> {
+    let (parent, field) = match path.rsplit_once('.') {
+        Some((parent, field)) => (get_path_mut(value, parent)?, field),
+        None => (value, path),
+    };
+    parent.as_object_mut()?.remove(field)
+}
+
+/// Set `path` to `new_value`, creating intermediate objects as needed.
+fn set_path(value: &mut Value, path: &str, new_value: Value) {
+    let mut current = value;
+    let mut segments = path.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        if !current.is_object() {
+            *current = Value::Object(serde_json::Map::new());
+        }
+        let Value::Object(map) = current else {
+            return;
+        };
+        if segments.peek().is_none() {
+            map.insert(segment.to_string(), new_value);
+            return;
+        }
+        current = map
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(serde_json::Map::new()));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn postgres_migrations() -> ConfigMigrations {
+        let plugin: PluginMigrations = serde_yaml::from_str(
+            r#"
+category: source
+kind: postgres
+migrations:
+  - from: "1.0.0"
+    to: "1.1.0"
+    steps:
+      - op: rename
+        from: sslmode
+        to: tls.mode
+  - from: "1.1.0"
+    to: "2.0.0"
+    steps:
+      - op: replaceValue
+        field: tls.mode
+        from: disable
+        to: disabled
+      - op: setDefault
+        field: tableKeys
+        value: []
+      - op: remove
+        field: legacyPolling
+"#,
+        )
+        .unwrap();
+        let mut migrations = ConfigMigrations::default();
+        migrations.register(plugin);
+        migrations
+    }
+
+    #[test]
+    fn test_migrate_chains_versions() {
+        let migrations = postgres_migrations();
+        let mut config = json!({"host": "db", "sslmode": "disable", "legacyPolling": true});
+        let changes = migrations
+            .migrate("source", "postgres", "1.0.0", "2.0.0", &mut config)
+            .unwrap();
+        assert_eq!(
+            config,
+            json!({"host": "db", "tls": {"mode": "disabled"}, "tableKeys": []})
+        );
+        assert_eq!(changes.len(), 4);
+        assert_eq!(changes[0], "renamed 'sslmode' to 'tls.mode'");
+    }
+
+    #[test]
+    fn test_migrate_skips_absent_fields() {
+        let migrations = postgres_migrations();
+        let mut config = json!({"host": "db", "tableKeys": [{"table": "t"}]});
+        let changes = migrations
+            .migrate("source", "postgres", "1.0.0", "2.0.0", &mut config)
+            .unwrap();
+        assert!(changes.is_empty());
+        assert_eq!(config, json!({"host": "db", "tableKeys": [{"table": "t"}]}));
+    }
+
+    #[test]
+    fn test_rename_onto_a_set_field_fails() {
+        let migrations = postgres_migrations();
+        let mut config = json!({"sslmode": "disable", "tls": {"mode": "require"}});
+        let err = migrations
+            .migrate("source", "postgres", "1.0.0", "2.0.0", &mut config)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot rename 'sslmode' to 'tls.mode': 'tls.mode' is already set"
+        );
+    }
+
+    #[test]
+    fn test_migrate_without_path_fails() {
+        let migrations = postgres_migrations();
+        let mut config = json!({});
+        let err = migrations
+            .migrate("source", "postgres", "0.9.0", "2.0.0", &mut config)
+            .unwrap_err();
+        assert!(err.to_string().contains("from config version 0.9.0"));
+        assert!(migrations
+            .migrate("reaction", "postgres", "1.0.0", "2.0.0", &mut config)
+            .is_err());
+    }
+
+    #[test]
+    fn test_migrate_source_stamps_and_reports() {
+        let migrations = postgres_migrations();
+        let mut source: SourceConfig = serde_yaml::from_str(
+            "kind: postgres\nid: orders\nconfigVersion: \"1.0.0\"\nsslmode: disable\n",
+        )
+        .unwrap();
+        let mut report = MigrationReport::default();
+        migrations.migrate_source(&mut source, "2.0.0", &mut report);
+        assert_eq!(source.config_version(), Some("2.0.0"));
+        assert_eq!(source.config["tls"]["mode"], "disabled");
+        assert_eq!(report.migrated.len(), 1);
+        assert!(report
+            .to_string()
+            .contains("source 'orders' (postgres): 1.0.0 -> 2.0.0"));
+
+        // Unversioned configs are stamped without being rewritten.
+        let mut legacy: SourceConfig =
+            serde_yaml::from_str("kind: postgres\nid: legacy\nsslmode: disable\n").unwrap();
+        let mut report = MigrationReport::default();
+        migrations.migrate_source(&mut legacy, "2.0.0", &mut report);
+        assert_eq!(legacy.config_version(), Some("2.0.0"));
+        assert_eq!(legacy.config["sslmode"], "disable");
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn test_failed_migration_leaves_config_untouched() {
+        let migrations = postgres_migrations();
+        let mut source: SourceConfig = serde_yaml::from_str(
+            "kind: postgres\nid: orders\nconfigVersion: \"3.0.0\"\nsslmode: disable\n",
+        )
+        .unwrap();
+        let mut report = MigrationReport::default();
+        migrations.migrate_source(&mut source, "2.0.0", &mut report);
+        assert!(report.has_errors());
+        assert_eq!(source.config_version(), Some("3.0.0"));
+        assert_eq!(source.config["sslmode"], "disable");
+    }
+
+    struct HttpReactionDesc;
+
+    #[async_trait::async_trait]
+    impl ReactionPluginDescriptor for HttpReactionDesc {
+        fn kind(&self) -> &str {
+            "http"
+        }
+        fn config_version(&self) -> &str {
+            "2"
+        }
+        fn config_schema_json(&self) -> String {
+            json!({
+                "reaction.http.HttpReactionConfig": {
+                    "type": "object",
+                    MIGRATIONS_EXTENSION: [{
+                        "from": "1",
+                        "to": "2",
+                        "steps": [{"op": "rename", "from": "url", "to": "baseUrl"}]
+                    }]
+                }
+            })
+            .to_string()
+        }
+        fn config_schema_name(&self) -> &str {
+            "reaction.http.HttpReactionConfig"
+        }
+        async fn create_reaction(
+            &self,
+            _id: &str,
+            _query_ids: Vec<String>,
+            _config_json: &Value,
+            _auto_start: bool,
+        ) -> anyhow::Result<Box<dyn drasi_lib::reactions::Reaction>> {
+            anyhow::bail!("mock")
+        }
+    }
+
+    #[test]
+    fn test_from_registry_reads_shipped_migrations() {
+        let mut registry = PluginRegistry::new();
+        registry.register_reaction(std::sync::Arc::new(HttpReactionDesc));
+
+        let migrations = ConfigMigrations::from_registry(&registry);
+        let mut config = json!({"url": "http://x"});
+        migrations
+            .migrate("reaction", "http", "1", "2", &mut config)
+            .unwrap();
+        assert_eq!(config, json!({"baseUrl": "http://x"}));
+    }
+
+    #[test]
+    fn test_shipped_migrations_rejects_malformed_list() {
+        let schema = json!({"Config": {MIGRATIONS_EXTENSION: [{"from": 1}]}}).to_string();
+        assert!(shipped_migrations(&schema, "Config").is_err());
+        let schema = json!({"Config": {"type": "object"}}).to_string();
+        assert!(shipped_migrations(&schema, "Config").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_submitted_reaction() {
+        let mut reaction: ReactionConfig = serde_yaml::from_str(
+            "kind: http\nid: notify\nqueries: [q]\nconfigVersion: \"1\"\nurl: http://x\n",
+        )
+        .unwrap();
+        let migration = migrate_submitted_reaction(&HttpReactionDesc, &mut reaction)
+            .unwrap()
+            .unwrap();
+        assert_eq!(migration.changes, vec!["renamed 'url' to 'baseUrl'"]);
+        assert_eq!(reaction.config_version(), Some("2"));
+        assert_eq!(reaction.config["baseUrl"], "http://x");
+
+        // Already current: nothing to do.
+        assert!(migrate_submitted_reaction(&HttpReactionDesc, &mut reaction)
+            .unwrap()
+            .is_none());
+
+        let mut newer: ReactionConfig =
+            serde_yaml::from_str("kind: http\nid: notify\nqueries: [q]\nconfigVersion: \"3\"\n")
+                .unwrap();
+        assert!(migrate_submitted_reaction(&HttpReactionDesc, &mut newer).is_err());
+    }
+}
@@ -16,7 +16,8 @@
 //!
 //! This module provides comprehensive configuration handling including:
 //! - Type-safe configuration structures
-//! - YAML and JSON file loading
+//! - YAML and JSON file loading, with includes, `conf.d/`, and environment overlays
+//! - Overrides of individual keys from `DRASI__*` environment variables and `--set`
 //! - Configuration validation
 //!
 //! # Examples
@@ -32,23 +33,38 @@
 //! println!("Server configuration loaded successfully");
 //! ```
 
+pub mod layers;
 pub mod loader;
+pub mod migration;
+pub mod mode;
+pub mod overrides;
 pub mod plugin_validation;
 pub mod schema_validation;
+pub mod semantic_validation;
 pub mod types;
 pub mod validation;
 
 // Re-export commonly used types
-pub use loader::{from_json_str, from_yaml_str, load_config_file, save_config_file, ConfigError};
+pub use layers::{ComponentKey, ConfigLayer, ConfigLayers, ValueOrigin};
+pub use loader::{
+    from_json_str, from_yaml_str, load_config_file, load_config_layers, save_config_file,
+    ConfigError, LoadOptions,
+};
+pub use migration::{ConfigMigrations, MigrationReport};
+pub use mode::{ModeOperation, ServerMode};
+pub use overrides::{AppliedOverride, ConfigOverrides, OverrideSource};
 pub use plugin_validation::{
     check_config_references, check_plugin_availability, extract_plugin_requirements,
     validate_with_plugins, ComponentValidationReport, FieldError, FullValidationResult,
     MissingPlugin, PluginRequirement, ReferenceWarning,
 };
 pub use schema_validation::validate_component_configs;
+pub use semantic_validation::{
+    validate_pipeline, validate_semantics, IssueSeverity, SemanticIssue,
+};
 pub use types::{
-    default_plugin_registry, DrasiLibInstanceConfig, DrasiServerConfig, PluginDependency,
-    ResolvedInstanceConfig, TrustedIdentity,
+    default_plugin_registry, DrasiLibInstanceConfig, DrasiServerConfig, PluginCircuitBreakerConfig,
+    PluginDependency, ResolvedInstanceConfig, TrustedIdentity, TrustedKey,
 };
 pub use validation::{validate_config, ValidationError};
 
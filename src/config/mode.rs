@@ -0,0 +1,155 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The server's `mode`: which changes the REST API accepts.
+//!
+//! | Mode        | Create / update / delete | Start / stop | Load / install plugins |
+//! |-------------|--------------------------|--------------|------------------------|
+//! | `readWrite` | yes                      | yes          | yes                    |
+//! | `locked`    | no                       | yes          | no                     |
+//! | `readOnly`  | no                       | no           | no                     |
+//!
+//! Reads are never restricted, so a frozen server stays observable. The mode
+//! is independent of `persistConfig`, which only decides whether accepted
+//! changes are saved to the config file. A config file the server cannot
+//! write locks a read-write server, so `locked` keeps plugins as fixed as
+//! the components.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Which changes the REST API accepts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ServerMode {
+    /// Every change is accepted.
+    #[default]
+    ReadWrite,
+    /// Components can be started and stopped, but nothing can be created,
+    /// updated or deleted, and no plugin loaded or installed.
+    Locked,
+    /// No changes are accepted.
+    ReadOnly,
+}
+
+/// A kind of change that a [`ServerMode`] may forbid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeOperation {
+    /// Creating, updating, deleting or cloning components and instances,
+    /// applying manifests, deploying solutions and rolling back revisions.
+    Configure,
+    /// Starting and stopping components, and repairing the state they
+    /// persisted.
+    Lifecycle,
+    /// Loading and installing plugins.
+    Plugins,
+}
+
+impl ServerMode {
+    /// Whether the mode accepts `operation`.
+    pub fn allows(self, operation: ModeOperation) -> bool {
+        match self {
+            Self::ReadWrite => true,
+            Self::Locked => operation == ModeOperation::Lifecycle,
+            Self::ReadOnly => false,
+        }
+    }
+
+    /// Whether this is the default, unrestricted mode.
+    pub fn is_read_write(&self) -> bool {
+        *self == Self::ReadWrite
+    }
+
+    /// The name used in config files and on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadWrite => "readWrite",
+            Self::Locked => "locked",
+            Self::ReadOnly => "readOnly",
+        }
+    }
+
+    /// What the mode still accepts, for error details and logs.
+    pub fn summary(self) -> &'static str {
+        match self {
+            Self::ReadWrite => "all changes are accepted",
+            Self::Locked => {
+                "components can be started and stopped, but nothing can be \
+                 created, updated or deleted and no plugin loaded"
+            }
+            Self::ReadOnly => "no changes are accepted; the API only serves reads",
+        }
+    }
+}
+
+impl fmt::Display for ServerMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ServerMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Self::ReadWrite, Self::Locked, Self::ReadOnly]
+            .into_iter()
+            .find(|mode| mode.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown mode '{s}' (expected readWrite, locked or readOnly)"))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locked_allows_lifecycle_only() {
+        let mode = ServerMode::Locked;
+        assert!(!mode.allows(ModeOperation::Configure));
+        assert!(mode.allows(ModeOperation::Lifecycle));
+        assert!(!mode.allows(ModeOperation::Plugins));
+    }
+
+    #[test]
+    fn test_read_only_allows_nothing_and_read_write_everything() {
+        for operation in [
+            ModeOperation::Configure,
+            ModeOperation::Lifecycle,
+            ModeOperation::Plugins,
+        ] {
+            assert!(!ServerMode::ReadOnly.allows(operation));
+            assert!(ServerMode::ReadWrite.allows(operation));
+        }
+    }
+
+    #[test]
+    fn test_parses_and_serializes_camel_case_names() {
+        assert_eq!(
+            "readOnly".parse::<ServerMode>().unwrap(),
+            ServerMode::ReadOnly
+        );
+        assert_eq!("LOCKED".parse::<ServerMode>().unwrap(), ServerMode::Locked);
+        assert!("frozen".parse::<ServerMode>().is_err());
+
+        let mode: ServerMode = serde_yaml::from_str("readWrite").unwrap();
+        assert_eq!(mode, ServerMode::ReadWrite);
+        assert_eq!(
+            serde_yaml::to_string(&ServerMode::Locked).unwrap().trim(),
+            "locked"
+        );
+    }
+}
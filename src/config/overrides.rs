@@ -0,0 +1,417 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Overrides of individual config keys from environment variables and
+//! `--set` flags.
+//!
+//! An override names a key by its path from the top of the config file.
+//! Inside `sources`, `queries`, `reactions`, `instances`, and other lists of
+//! entries with an `id`, a path segment selects the entry with that id; in
+//! other lists it is an index.
+//!
+//! - `--set sources.my-pg.host=db.internal` uses `.` between segments and
+//!   the key names as written in the file.
+//! - `DRASI__SOURCES__my-pg__HOST=db.internal` uses `__` between segments
+//!   after the `DRASI__` prefix. Key names are matched ignoring case and `_`,
+//!   so `DRASI__LOG_LEVEL` and `DRASI__LOGLEVEL` both set `logLevel`.
+//!
+//! Values are parsed as YAML, so `8080` is a number, `true` a boolean, and
+//! `[a, b]` a list; anything else is a string. A value that replaces a string
+//! in the config stays a string, so `password=null` or `version=1e3` do not
+//! change the key's type; elsewhere, quote a value to keep it a string
+//! (`--set 'tag="1e3"'`). Overrides are applied after
+//! the config files are merged and before the result is validated, in order:
+//! environment variables (by name), then `--set` flags as given.
+
+use super::loader::ConfigError;
+use serde_yaml::{Mapping, Value};
+use std::fmt;
+
+/// Prefix of environment variables that override config keys.
+pub const ENV_PREFIX: &str = "DRASI__";
+
+/// Separator between path segments in an environment variable name.
+const ENV_SEPARATOR: &str = "__";
+
+/// Where an override came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverrideSource {
+    /// An environment variable, by name
+    Env(String),
+    /// A `--set path=value` flag, as given
+    Flag(String),
+}
+
+impl fmt::Display for OverrideSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Env(name) => write!(f, "env {name}"),
+            Self::Flag(flag) => write!(f, "--set {flag}"),
+        }
+    }
+}
+
+/// One overridden key.
+#[derive(Debug, Clone)]
+pub struct ConfigOverride {
+    pub path: Vec<String>,
+    pub value: Value,
+    /// The value as given, used when the key it replaces holds a string.
+    pub raw: String,
+    pub source: OverrideSource,
+}
+
+/// The overrides to apply to a configuration, in order.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    overrides: Vec<ConfigOverride>,
+}
+
+/// An override as applied: the path of the key it set, using the key names
+/// found in the config, and where the override came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedOverride {
+    pub path: String,
+    pub source: OverrideSource,
+}
+
+impl ConfigOverrides {
+    /// Overrides from the `DRASI__*` variables of the process environment.
+    pub fn from_env() -> Self {
+        Self::from_vars(std::env::vars())
+    }
+
+    /// Overrides from `DRASI__*` variables among `vars`, ordered by name.
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut vars: Vec<(String, String)> = vars
+            .into_iter()
+            .filter(|(name, _)| name.len() > ENV_PREFIX.len() && name.starts_with(ENV_PREFIX))
+            .collect();
+        vars.sort();
+        let overrides = vars
+            .into_iter()
+            .map(|(name, raw)| ConfigOverride {
+                path: name[ENV_PREFIX.len()..]
+                    .split(ENV_SEPARATOR)
+                    .map(str::to_string)
+                    .collect(),
+                value: parse_value(&raw),
+                raw,
+                source: OverrideSource::Env(name),
+            })
+            .collect();
+        Self { overrides }
+    }
+
+    /// Add `--set path=value` flags, applied after the overrides already
+    /// present.
+    pub fn with_sets<S: AsRef<str>>(
+        mut self,
+        sets: impl IntoIterator<Item = S>,
+    ) -> Result<Self, ConfigError> {
+        for set in sets {
+            self.overrides.push(parse_set(set.as_ref())?);
+        }
+        Ok(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ConfigOverride> {
+        self.overrides.iter()
+    }
+
+    /// Apply the overrides to a config document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a path is empty, names an entry that does not
+    /// exist in a list, or goes through a value that is not a mapping or list.
+    pub fn apply(&self, value: &mut Value) -> Result<Vec<AppliedOverride>, ConfigError> {
+        self.overrides
+            .iter()
+            .map(|o| {
+                let path = set_path(value, o)?;
+                Ok(AppliedOverride {
+                    path,
+                    source: o.source.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse `path=value`.
+fn parse_set(set: &str) -> Result<ConfigOverride, ConfigError> {
+    let invalid = |reason: &str| ConfigError::OverrideError(format!("--set '{set}': {reason}"));
+    let (path, raw) = set
+        .split_once('=')
+        .ok_or_else(|| invalid("expected <path>=<value>"))?;
+    let path: Vec<String> = path.trim().split('.').map(str::to_string).collect();
+    if path.iter().any(String::is_empty) {
+        return Err(invalid("the path has an empty segment"));
+    }
+    Ok(ConfigOverride {
+        path,
+        value: parse_value(raw),
+        raw: raw.to_string(),
+        source: OverrideSource::Flag(set.to_string()),
+    })
+}
+
+/// Read an override value as YAML, falling back to the raw string.
+fn parse_value(raw: &str) -> Value {
+    if raw.trim().is_empty() {
+        return Value::String(raw.to_string());
+    }
+    serde_yaml::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
+
+/// Set the key an override names, creating missing mappings on the way.
+/// Returns the path with the key names it resolved to.
+fn set_path(root: &mut Value, o: &ConfigOverride) -> Result<String, ConfigError> {
+    let fail = |reason: String| ConfigError::OverrideError(format!("{}: {reason}", o.source));
+    if o.path.iter().any(String::is_empty) {
+        return Err(fail("the path has an empty segment".to_string()));
+    }
+    let from_env = matches!(o.source, OverrideSource::Env(_));
+
+    let mut resolved: Vec<String> = Vec::with_capacity(o.path.len());
+    let mut current = root;
+    for segment in &o.path {
+        if current.is_null() {
+            *current = Value::Mapping(Mapping::new());
+        }
+        current = match current {
+            Value::Mapping(map) => {
+                let key = mapping_key(map, segment, from_env);
+                resolved.push(key.clone());
+                map.entry(Value::String(key)).or_insert(Value::Null)
+            }
+            Value::Sequence(items) => {
+                let index = items
+                    .iter()
+                    .position(|item| entry_id(item) == Some(segment.as_str()))
+                    .or_else(|| {
+                        items.iter().position(|item| {
+                            entry_id(item).is_some_and(|id| id.eq_ignore_ascii_case(segment))
+                        })
+                    })
+                    .or_else(|| segment.parse::<usize>().ok().filter(|i| *i < items.len()))
+                    .ok_or_else(|| {
+                        fail(format!(
+                            "'{}' has no entry '{segment}'",
+                            display_path(&resolved)
+                        ))
+                    })?;
+                resolved.push(entry_id(&items[index]).unwrap_or(segment).to_string());
+                &mut items[index]
+            }
+            _ => {
+                return Err(fail(format!(
+                    "'{}' is not a mapping or list",
+                    display_path(&resolved)
+                )))
+            }
+        };
+    }
+    *current = match (&*current, &o.value) {
+        // Keep a string a string: `null`, `~`, `true`, or `1e3` replacing a
+        // password or version is meant literally.
+        (Value::String(_), Value::Null | Value::Bool(_) | Value::Number(_)) => {
+            Value::String(o.raw.clone())
+        }
+        _ => o.value.clone(),
+    };
+    Ok(resolved.join("."))
+}
+
+/// The key of `map` a path segment refers to. An existing key is matched
+/// exactly, then ignoring case, `_`, and `-`; a new key from an environment
+/// variable is converted from `SNAKE_CASE` to `camelCase`.
+fn mapping_key(map: &Mapping, segment: &str, from_env: bool) -> String {
+    let keys = || map.keys().filter_map(Value::as_str);
+    if let Some(key) = keys().find(|key| *key == segment) {
+        return key.to_string();
+    }
+    let normalized = normalize(segment);
+    if let Some(key) = keys().find(|key| normalize(key) == normalized) {
+        return key.to_string();
+    }
+    if from_env {
+        camel_case(segment)
+    } else {
+        segment.to_string()
+    }
+}
+
+fn normalize(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// `PERSIST_INDEX` → `persistIndex`
+fn camel_case(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for (i, word) in segment.split('_').filter(|w| !w.is_empty()).enumerate() {
+        let word = word.to_lowercase();
+        if i == 0 {
+            out.push_str(&word);
+        } else {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                out.extend(first.to_uppercase());
+                out.push_str(chars.as_str());
+            }
+        }
+    }
+    out
+}
+
+fn entry_id(value: &Value) -> Option<&str> {
+    value.as_mapping()?.get("id")?.as_str()
+}
+
+fn display_path(path: &[String]) -> String {
+    if path.is_empty() {
+        "<root>".to_string()
+    } else {
+        path.join(".")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn yaml(s: &str) -> Value {
+        serde_yaml::from_str(s).unwrap()
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let mut config = yaml(
+            "port: 8080\nlogLevel: info\nsources:\n  - {id: my-pg, kind: postgres, host: localhost}",
+        );
+        let overrides = ConfigOverrides::from_vars(vars(&[
+            ("DRASI__PORT", "9090"),
+            ("DRASI__LOG_LEVEL", "debug"),
+            ("DRASI__PERSIST_INDEX", "true"),
+            ("DRASI__SOURCES__my-pg__HOST", "db.internal"),
+            ("DRASI_ENV", "prod"),
+            ("PATH", "/usr/bin"),
+        ]));
+        let applied = overrides.apply(&mut config).unwrap();
+
+        assert_eq!(config["port"], yaml("9090"));
+        assert_eq!(config["logLevel"], yaml("debug"));
+        assert_eq!(config["persistIndex"], yaml("true"));
+        assert_eq!(config["sources"][0]["host"], yaml("db.internal"));
+        let paths: Vec<&str> = applied.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["logLevel", "persistIndex", "port", "sources.my-pg.host"]
+        );
+        assert_eq!(
+            applied[0].source.to_string(),
+            "env DRASI__LOG_LEVEL",
+            "variables apply in name order"
+        );
+    }
+
+    #[test]
+    fn test_set_flags_apply_after_env() {
+        let mut config = yaml("port: 8080\ninstances:\n  - id: prod\n    queries:\n      - {id: q1, query: 'MATCH (n) RETURN n'}");
+        let overrides = ConfigOverrides::from_vars(vars(&[("DRASI__PORT", "9090")]))
+            .with_sets([
+                "port=7070",
+                "instances.prod.queries.q1.autoStart=false",
+                "instances.0.persistIndex=true",
+                "corsAllowedOrigins=[https://a.example, https://b.example]",
+            ])
+            .unwrap();
+        let applied = overrides.apply(&mut config).unwrap();
+
+        assert_eq!(config["port"], yaml("7070"));
+        assert_eq!(
+            config["instances"][0]["queries"][0]["autoStart"],
+            yaml("false")
+        );
+        assert_eq!(config["instances"][0]["persistIndex"], yaml("true"));
+        assert_eq!(config["corsAllowedOrigins"].as_sequence().unwrap().len(), 2);
+        assert_eq!(applied[3].path, "instances.prod.persistIndex");
+        assert_eq!(
+            applied[1].source,
+            OverrideSource::Flag("port=7070".to_string())
+        );
+    }
+
+    #[test]
+    fn test_string_keys_stay_strings() {
+        let mut config =
+            yaml("port: 8080\nsources:\n  - {id: pg, kind: postgres, password: x, version: '1'}");
+        let overrides =
+            ConfigOverrides::from_vars(vars(&[("DRASI__SOURCES__pg__PASSWORD", "null")]))
+                .with_sets([
+                    "sources.pg.version=1e3",
+                    "sources.pg.tag=~",
+                    "sources.pg.label=\"1e3\"",
+                    "port=9090",
+                ])
+                .unwrap();
+        overrides.apply(&mut config).unwrap();
+
+        let source = &config["sources"][0];
+        assert_eq!(source["password"], yaml("'null'"));
+        assert_eq!(source["version"], yaml("'1e3'"));
+        assert_eq!(source["tag"], Value::Null, "new keys are parsed as YAML");
+        assert_eq!(source["label"], yaml("'1e3'"), "quoted values are strings");
+        assert_eq!(config["port"], yaml("9090"));
+    }
+
+    #[test]
+    fn test_override_errors() {
+        assert!(ConfigOverrides::default().with_sets(["port"]).is_err());
+        assert!(ConfigOverrides::default()
+            .with_sets(["sources..host=x"])
+            .is_err());
+
+        let mut config = yaml("sources:\n  - {id: a, kind: mock}\nport: 8080");
+        let err = ConfigOverrides::default()
+            .with_sets(["sources.missing.host=x"])
+            .unwrap()
+            .apply(&mut config)
+            .unwrap_err();
+        assert!(err.to_string().contains("has no entry 'missing'"), "{err}");
+
+        let err = ConfigOverrides::default()
+            .with_sets(["port.value=1"])
+            .unwrap()
+            .apply(&mut config)
+            .unwrap_err();
+        assert!(err.to_string().contains("'port' is not a mapping"), "{err}");
+    }
+}
@@ -19,6 +19,8 @@
 //! - Checking plugin availability in the registry
 //! - Walking env var references and reporting missing ones
 //! - Validating component configs against plugin OpenAPI schemas
+//! - Checking that queries, sources and reactions fit together (see
+//!   [`super::semantic_validation`])
 //!
 //! The main entry point is [`validate_with_plugins`], which runs all validation
 //! steps and returns a comprehensive [`FullValidationResult`].
@@ -28,6 +30,7 @@ use serde_json::Value;
 use std::path::Path;
 
 use super::schema_validation::validate_component_configs;
+use super::semantic_validation::{validate_semantics, SemanticIssue};
 use crate::config::types::DrasiServerConfig;
 use crate::plugin_registry::PluginRegistry;
 
@@ -117,13 +120,30 @@ pub struct FullValidationResult {
     pub plugins_loaded: usize,
     /// `true` when no plugins directory was found or it was empty.
     pub plugins_not_loaded: bool,
+    /// Compatibility of the plugins in the plugins directory with this host.
+    pub compatibility: Option<crate::plugin_compatibility::CompatibilityReport>,
+    /// Component configs migrated to their plugin's current schema version
+    /// before validation, and those that could not be migrated.
+    pub migrations: crate::config::migration::MigrationReport,
+    /// Problems with how queries, sources and reactions fit together.
+    pub semantic_issues: Vec<SemanticIssue>,
+    /// Secret fields of the loaded plugins, for redacting displayed values.
+    pub secret_fields: crate::api::shared::config_export::SecretFields,
 }
 
 impl FullValidationResult {
-    /// Returns `true` when there are hard errors (config errors or missing
-    /// env vars without defaults).
+    /// Returns `true` when there are hard errors (config errors, missing
+    /// env vars without defaults, incompatible plugins, configs that could
+    /// not be migrated, or broken references between components).
     pub fn has_errors(&self) -> bool {
-        !self.env_warnings.is_empty() || !self.config_errors.is_empty()
+        !self.env_warnings.is_empty()
+            || !self.config_errors.is_empty()
+            || self.migrations.has_errors()
+            || self.semantic_issues.iter().any(SemanticIssue::is_error)
+            || self
+                .compatibility
+                .as_ref()
+                .is_some_and(|report| report.has_errors())
     }
 }
 
@@ -387,8 +407,10 @@ fn check_handlebars_env_refs(s: &str, path: &str, warnings: &mut Vec<ReferenceWa
 /// 1. Checks env var references in source/reaction configs
 /// 2. Attempts to load plugins from `plugins_dir` (if provided and exists)
 /// 3. Checks plugin availability
-/// 4. Validates component configs against schemas
-/// 5. Returns a comprehensive [`FullValidationResult`]
+/// 4. Migrates component configs to their plugin's current `configVersion`
+///    and validates the result against the plugin schemas
+/// 5. Checks each `stateStore` against the built-in state store kinds
+/// 6. Returns a comprehensive [`FullValidationResult`]
 ///
 /// Gracefully degrades when plugins aren't available.
 pub fn validate_with_plugins(
@@ -402,14 +424,18 @@ pub fn validate_with_plugins(
     let mut registry = PluginRegistry::new();
     crate::server::register_core_plugins(&mut registry);
 
+    let mut state_stores = crate::state_stores::StateStoreRegistry::new();
     let mut plugins_loaded: usize = 0;
     let mut plugins_not_loaded = true;
+    let mut compatibility = None;
 
     if let Some(dir) = plugins_dir {
         if dir.exists() {
             match crate::dynamic_loading::load_plugins(dir, &mut registry, None, None) {
                 Ok(stats) => {
                     plugins_loaded = stats.plugins_loaded;
+                    stats.register_state_stores(&mut state_stores);
+                    compatibility = Some(stats.compatibility);
                 }
                 Err(e) => {
                     warn!("Failed to load plugins from {}: {e}", dir.display());
@@ -424,8 +450,19 @@ pub fn validate_with_plugins(
     let requirements = extract_plugin_requirements(config);
     let (_found, missing_plugins) = check_plugin_availability(&requirements, &registry);
 
-    // 4. Validate component configs against schemas
-    let config_errors = validate_component_configs(config, &registry);
+    // 4. Migrate component configs written against older plugin schemas,
+    //    then validate the migrated configs against the current schemas
+    let migrations = crate::config::migration::ConfigMigrations::from_registry(&registry);
+    let mut migrated = config.clone();
+    let migration_report = migrations.migrate_config(&mut migrated, &registry);
+    let mut config_errors = validate_component_configs(&migrated, &registry);
+
+    // 5. Check state store kinds and fields, which are no longer checked
+    //    when the config is parsed
+    config_errors.extend(validate_state_store_configs(config, &state_stores));
+
+    // 6. Check that the pipeline fits together
+    let semantic_issues = validate_semantics(&migrated);
 
     FullValidationResult {
         env_warnings,
@@ -433,9 +470,52 @@ pub fn validate_with_plugins(
         config_errors,
         plugins_loaded,
         plugins_not_loaded,
+        compatibility,
+        migrations: migration_report,
+        semantic_issues,
+        secret_fields: crate::api::shared::config_export::SecretFields::from_registry(&registry),
     }
 }
 
+/// Check the server's and each instance's `stateStore` against `registry`.
+///
+/// Reports are keyed by the instance the store belongs to, or `server` for
+/// the top-level store.
+pub fn validate_state_store_configs(
+    config: &DrasiServerConfig,
+    registry: &crate::state_stores::StateStoreRegistry,
+) -> Vec<ComponentValidationReport> {
+    let mapper = crate::api::mappings::DtoMapper::new();
+    let stores = std::iter::once(("server".to_string(), config.state_store.as_ref())).chain(
+        config.instances.iter().enumerate().map(|(index, instance)| {
+            let instance_id = mapper
+                .resolve_string(&instance.id)
+                .unwrap_or_else(|_| format!("instances[{index}]"));
+            (instance_id, instance.state_store.as_ref())
+        }),
+    );
+
+    let mut reports = Vec::new();
+    for (owner, store) in stores {
+        let Some(store) = store else {
+            continue;
+        };
+        if let Err(e) = registry.validate(store) {
+            reports.push(ComponentValidationReport {
+                component_type: "stateStore".to_string(),
+                component_id: owner,
+                plugin_kind: store.kind.clone(),
+                errors: vec![FieldError {
+                    field: "stateStore".to_string(),
+                    message: format!("{e:#}"),
+                    code: None,
+                }],
+            });
+        }
+    }
+    reports
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -616,6 +696,8 @@ mod tests {
             auto_start: true,
             bootstrap_provider: None,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({}),
         }
     }
@@ -626,6 +708,8 @@ mod tests {
             id: id.to_string(),
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             bootstrap_provider: Some(BootstrapProviderRef::Inline(BootstrapProviderConfig {
                 kind: bp_kind.to_string(),
                 config: serde_json::json!({}),
@@ -641,6 +725,8 @@ mod tests {
             queries: vec![],
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({}),
         }
     }
@@ -813,6 +899,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({"host": "localhost"}),
             }],
             vec![],
@@ -836,6 +924,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "host": {
                         "kind": "EnvironmentVariable",
@@ -866,6 +956,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "password": {
                         "kind": "EnvironmentVariable",
@@ -893,6 +985,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "host": {
                         "kind": "EnvironmentVariable",
@@ -919,6 +1013,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "password": "${DRASI_TEST_HBS_MISSING}"
                 }),
@@ -942,6 +1038,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "password": "${DRASI_TEST_HBS_DEFAULT:-secret}"
                 }),
@@ -966,6 +1064,25 @@ mod tests {
         assert!(result.env_warnings.is_empty());
     }
 
+    #[test]
+    fn test_validate_with_plugins_reports_state_store_errors() {
+        let mut config = config_with(vec![], vec![]);
+        config.state_store = Some(crate::api::models::StateStoreConfig {
+            kind: "redb".to_string(),
+            config: serde_json::json!({ "file_path": "./data/state.redb" }),
+        });
+
+        let result = validate_with_plugins(&config, None);
+        assert!(result.has_errors());
+        let report = &result.config_errors[0];
+        assert_eq!(report.component_type, "stateStore");
+        assert_eq!(report.component_id, "server");
+        assert!(report.errors[0].message.contains("file_path"));
+
+        config.state_store = Some(crate::api::models::StateStoreConfig::sqlite("./state.db"));
+        assert!(!validate_with_plugins(&config, None).has_errors());
+    }
+
     #[test]
     fn test_validate_with_plugins_nonexistent_dir() {
         let config = config_with(vec![source("mock", "s1")], vec![]);
@@ -381,6 +381,8 @@ mod tests {
             auto_start: true,
             bootstrap_provider: None,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({}),
         }
     }
@@ -459,6 +461,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "host": "localhost",
                     "database": "mydb"
@@ -481,6 +485,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "host": "localhost"
                     // missing "database" which is required
@@ -509,6 +515,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "host": "localhost",
                     "database": "mydb",
@@ -533,6 +541,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "host": 12345,  // should be string
                     "database": "mydb"
@@ -556,6 +566,8 @@ mod tests {
                 auto_start: true,
                 bootstrap_provider: None,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "host": "localhost",
                     "database": "mydb",
@@ -594,6 +606,8 @@ mod tests {
                 queries: vec![],
                 auto_start: true,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "level": "info"
                 }),
@@ -615,6 +629,8 @@ mod tests {
                 queries: vec![],
                 auto_start: true,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 config: serde_json::json!({
                     "level": "verbose"  // not in enum
                 }),
@@ -635,6 +651,8 @@ mod tests {
                 id: "pg1".to_string(),
                 auto_start: true,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 bootstrap_provider: Some(BootstrapProviderRef::Inline(BootstrapProviderConfig {
                     kind: "scriptfile".to_string(),
                     config: serde_json::json!({
@@ -662,6 +680,8 @@ mod tests {
                 id: "pg1".to_string(),
                 auto_start: true,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 bootstrap_provider: Some(BootstrapProviderRef::Inline(BootstrapProviderConfig {
                     kind: "scriptfile".to_string(),
                     config: serde_json::json!({}), // missing filePaths
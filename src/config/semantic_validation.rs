@@ -0,0 +1,590 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Semantic validation of an instance's pipeline.
+//!
+//! Schema validation checks each component on its own. This module checks
+//! that the components fit together:
+//! - every query parses, and its subscriptions name declared sources and
+//!   middleware
+//! - every reaction subscribes to declared queries
+//! - labels a query matches are produced by its subscriptions' `nodes` /
+//!   `relations` filters, when those filters are declared
+//! - joins refer to labels the query matches
+//! - nothing is declared without being used
+//!
+//! Broken references are errors; everything else is a warning, since the
+//! pipeline still starts.
+
+use drasi_lib::config::QueryJoinConfig;
+use drasi_lib::queries::LabelExtractor;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+
+use crate::api::mappings::DtoMapper;
+use crate::api::models::{
+    IdentityProviderConfig, QueryConfigDto, ReactionConfig, SourceConfig,
+    TopLevelBootstrapProviderConfig,
+};
+use crate::config::types::DrasiServerConfig;
+
+/// Stable codes identifying the kind of a [`SemanticIssue`].
+pub mod semantic_codes {
+    /// The query text could not be parsed.
+    pub const INVALID_QUERY: &str = "INVALID_QUERY";
+    /// A query subscribes to a source that is not declared.
+    pub const UNKNOWN_SOURCE: &str = "UNKNOWN_SOURCE";
+    /// A reaction subscribes to a query that is not declared.
+    pub const UNKNOWN_QUERY: &str = "UNKNOWN_QUERY";
+    /// A subscription `pipeline` names middleware the query does not declare.
+    pub const UNKNOWN_MIDDLEWARE: &str = "UNKNOWN_MIDDLEWARE";
+    /// A label the query matches is missing from every declared label filter.
+    pub const UNMATCHED_LABEL: &str = "UNMATCHED_LABEL";
+    /// A join refers to a label the query does not match.
+    pub const JOIN_LABEL_MISSING: &str = "JOIN_LABEL_MISSING";
+    /// A component is declared but nothing uses it.
+    pub const UNUSED_COMPONENT: &str = "UNUSED_COMPONENT";
+}
+
+/// How serious a [`SemanticIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    /// The pipeline will not work as declared.
+    Error,
+    /// The pipeline starts, but probably not as intended.
+    Warning,
+}
+
+/// A problem with how an instance's components fit together.
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticIssue {
+    pub severity: IssueSeverity,
+    /// Instance the component belongs to. `None` for the top-level
+    /// components of a single-instance config.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<String>,
+    /// "source", "query", "reaction", "middleware", "bootstrapProvider" or
+    /// "identityProvider".
+    pub component_type: String,
+    pub component_id: String,
+    /// One of [`semantic_codes`].
+    #[schema(value_type = String)]
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl SemanticIssue {
+    fn new(
+        severity: IssueSeverity,
+        component_type: &str,
+        component_id: &str,
+        code: &'static str,
+        message: String,
+    ) -> Self {
+        Self {
+            severity,
+            instance_id: None,
+            component_type: component_type.to_string(),
+            component_id: component_id.to_string(),
+            code,
+            message,
+        }
+    }
+
+    fn error(
+        component_type: &str,
+        component_id: &str,
+        code: &'static str,
+        message: String,
+    ) -> Self {
+        Self::new(
+            IssueSeverity::Error,
+            component_type,
+            component_id,
+            code,
+            message,
+        )
+    }
+
+    fn warning(
+        component_type: &str,
+        component_id: &str,
+        code: &'static str,
+        message: String,
+    ) -> Self {
+        Self::new(
+            IssueSeverity::Warning,
+            component_type,
+            component_id,
+            code,
+            message,
+        )
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+/// Validate the pipeline of every instance in `config`.
+///
+/// Like [`DrasiServerConfig::resolved_instances`], the top-level components
+/// form the only instance when `instances` is empty.
+pub fn validate_semantics(config: &DrasiServerConfig) -> Vec<SemanticIssue> {
+    if config.instances.is_empty() {
+        return validate_instance(
+            &config.sources,
+            &config.queries,
+            &config.reactions,
+            &config.bootstrap_providers,
+            &config.identity_providers,
+        );
+    }
+
+    let mapper = DtoMapper::new();
+    let mut issues = Vec::new();
+    for (index, instance) in config.instances.iter().enumerate() {
+        let instance_id = mapper
+            .resolve_string(&instance.id)
+            .unwrap_or_else(|_| format!("instances[{index}]"));
+        issues.extend(
+            validate_instance(
+                &instance.sources,
+                &instance.queries,
+                &instance.reactions,
+                &instance.bootstrap_providers,
+                &instance.identity_providers,
+            )
+            .into_iter()
+            .map(|issue| SemanticIssue {
+                instance_id: Some(instance_id.clone()),
+                ..issue
+            }),
+        );
+    }
+    issues
+}
+
+fn validate_instance(
+    sources: &[SourceConfig],
+    queries: &[QueryConfigDto],
+    reactions: &[ReactionConfig],
+    bootstrap_providers: &[TopLevelBootstrapProviderConfig],
+    identity_providers: &[IdentityProviderConfig],
+) -> Vec<SemanticIssue> {
+    let mut issues = validate_pipeline(sources, queries, reactions);
+
+    let bootstrap_refs: HashSet<&str> = sources
+        .iter()
+        .filter_map(|s| s.bootstrap_provider().and_then(|r| r.as_reference()))
+        .collect();
+    for provider in bootstrap_providers {
+        if !bootstrap_refs.contains(provider.id()) {
+            issues.push(SemanticIssue::warning(
+                "bootstrapProvider",
+                provider.id(),
+                semantic_codes::UNUSED_COMPONENT,
+                "no source references this bootstrap provider".to_string(),
+            ));
+        }
+    }
+
+    let identity_refs: HashSet<&str> = sources
+        .iter()
+        .filter_map(|s| s.identity_provider())
+        .chain(reactions.iter().filter_map(|r| r.identity_provider()))
+        .collect();
+    for provider in identity_providers {
+        if !identity_refs.contains(provider.id()) {
+            issues.push(SemanticIssue::warning(
+                "identityProvider",
+                provider.id(),
+                semantic_codes::UNUSED_COMPONENT,
+                "no source or reaction references this identity provider".to_string(),
+            ));
+        }
+    }
+    issues
+}
+
+/// Validate how one instance's sources, queries and reactions fit together.
+pub fn validate_pipeline(
+    sources: &[SourceConfig],
+    queries: &[QueryConfigDto],
+    reactions: &[ReactionConfig],
+) -> Vec<SemanticIssue> {
+    let mut issues = Vec::new();
+    let source_ids: HashSet<&str> = sources.iter().map(|s| s.id.as_str()).collect();
+    let query_ids: HashSet<&str> = queries.iter().map(|q| q.id.as_str()).collect();
+
+    for query in queries {
+        validate_query(query, &source_ids, &mut issues);
+    }
+
+    for reaction in reactions {
+        for query_id in reaction.queries() {
+            if !query_ids.contains(query_id.as_str()) {
+                issues.push(SemanticIssue::error(
+                    "reaction",
+                    &reaction.id,
+                    semantic_codes::UNKNOWN_QUERY,
+                    format!("subscribes to query '{query_id}', which is not declared"),
+                ));
+            }
+        }
+    }
+
+    let subscribed_sources: HashSet<&str> = queries
+        .iter()
+        .flat_map(|q| q.sources.iter().map(|s| s.source_id.as_str()))
+        .collect();
+    for source in sources {
+        if !subscribed_sources.contains(source.id.as_str()) {
+            issues.push(SemanticIssue::warning(
+                "source",
+                &source.id,
+                semantic_codes::UNUSED_COMPONENT,
+                "no query subscribes to this source".to_string(),
+            ));
+        }
+    }
+
+    let subscribed_queries: HashSet<&str> = reactions
+        .iter()
+        .flat_map(|r| r.queries().iter().map(String::as_str))
+        .collect();
+    for query in queries {
+        if !subscribed_queries.contains(query.id.as_str()) {
+            issues.push(SemanticIssue::warning(
+                "query",
+                &query.id,
+                semantic_codes::UNUSED_COMPONENT,
+                "no reaction subscribes to this query".to_string(),
+            ));
+        }
+    }
+
+    issues
+}
+
+fn validate_query(
+    query: &QueryConfigDto,
+    source_ids: &HashSet<&str>,
+    issues: &mut Vec<SemanticIssue>,
+) {
+    let id = query.id.as_str();
+    let middleware: HashSet<&str> = query.middleware.iter().map(|m| m.name.as_str()).collect();
+    let mut used_middleware = HashSet::new();
+
+    for subscription in &query.sources {
+        if !source_ids.contains(subscription.source_id.as_str()) {
+            issues.push(SemanticIssue::error(
+                "query",
+                id,
+                semantic_codes::UNKNOWN_SOURCE,
+                format!(
+                    "subscribes to source '{}', which is not declared",
+                    subscription.source_id
+                ),
+            ));
+        }
+        for name in &subscription.pipeline {
+            used_middleware.insert(name.as_str());
+            if !middleware.contains(name.as_str()) {
+                issues.push(SemanticIssue::error(
+                    "query",
+                    id,
+                    semantic_codes::UNKNOWN_MIDDLEWARE,
+                    format!(
+                        "pipeline of source '{}' uses middleware '{name}', which is not declared in `middleware`",
+                        subscription.source_id
+                    ),
+                ));
+            }
+        }
+    }
+    for declared in &query.middleware {
+        if !used_middleware.contains(declared.name.as_str()) {
+            issues.push(SemanticIssue::warning(
+                "middleware",
+                &declared.name,
+                semantic_codes::UNUSED_COMPONENT,
+                format!("middleware of query '{id}' is not used in any source pipeline"),
+            ));
+        }
+    }
+
+    let labels = match LabelExtractor::extract_labels(&query.query, &query.query_language) {
+        Ok(labels) => labels,
+        Err(e) => {
+            issues.push(SemanticIssue::error(
+                "query",
+                id,
+                semantic_codes::INVALID_QUERY,
+                format!("failed to parse {:?} query: {e}", query.query_language),
+            ));
+            return;
+        }
+    };
+    let node_labels: BTreeSet<&str> = labels.node_labels.iter().map(String::as_str).collect();
+    let relation_labels: BTreeSet<&str> =
+        labels.relation_labels.iter().map(String::as_str).collect();
+
+    // Joins synthesize relations, so their ids are never produced by a source.
+    let joins: Vec<QueryJoinConfig> = query
+        .joins
+        .clone()
+        .and_then(|joins| serde_json::from_value(joins).ok())
+        .unwrap_or_default();
+    let join_ids: HashSet<&str> = joins.iter().map(|j| j.id.as_str()).collect();
+
+    for join in &joins {
+        if !relation_labels.contains(join.id.as_str()) {
+            issues.push(SemanticIssue::warning(
+                "query",
+                id,
+                semantic_codes::JOIN_LABEL_MISSING,
+                format!(
+                    "join '{}' does not appear as a relation label in the query",
+                    join.id
+                ),
+            ));
+        }
+        for key in &join.keys {
+            if !node_labels.contains(key.label.as_str()) {
+                issues.push(SemanticIssue::warning(
+                    "query",
+                    id,
+                    semantic_codes::JOIN_LABEL_MISSING,
+                    format!(
+                        "join '{}' keys on label '{}', which the query does not match",
+                        join.id, key.label
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Labels missing from every filter are only sent to sources that do not
+    // filter, so when all subscriptions filter they never arrive.
+    let subscriptions = &query.sources;
+    if !subscriptions.is_empty() && subscriptions.iter().all(|s| !s.nodes.is_empty()) {
+        let declared: HashSet<&str> = subscriptions
+            .iter()
+            .flat_map(|s| s.nodes.iter().map(String::as_str))
+            .collect();
+        for label in node_labels.iter().filter(|l| !declared.contains(*l)) {
+            issues.push(SemanticIssue::warning(
+                "query",
+                id,
+                semantic_codes::UNMATCHED_LABEL,
+                format!("node label '{label}' is not in the `nodes` of any subscribed source"),
+            ));
+        }
+    }
+    if !subscriptions.is_empty() && subscriptions.iter().all(|s| !s.relations.is_empty()) {
+        let declared: HashSet<&str> = subscriptions
+            .iter()
+            .flat_map(|s| s.relations.iter().map(String::as_str))
+            .collect();
+        for label in relation_labels
+            .iter()
+            .filter(|l| !declared.contains(*l) && !join_ids.contains(*l))
+        {
+            issues.push(SemanticIssue::warning(
+                "query",
+                id,
+                semantic_codes::UNMATCHED_LABEL,
+                format!(
+                    "relation label '{label}' is not in the `relations` of any subscribed source"
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn config(yaml: &str) -> DrasiServerConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn codes(issues: &[SemanticIssue]) -> Vec<(&str, &str, &str)> {
+        issues
+            .iter()
+            .map(|i| (i.component_type.as_str(), i.component_id.as_str(), i.code))
+            .collect()
+    }
+
+    #[test]
+    fn test_consistent_pipeline_has_no_issues() {
+        let issues = validate_semantics(&config(
+            r#"
+sources:
+  - kind: mock
+    id: sensors
+queries:
+  - id: hot
+    query: "MATCH (s:Sensor) WHERE s.temp > 80 RETURN s.id"
+    queryLanguage: Cypher
+    sources:
+      - sourceId: sensors
+        nodes: [Sensor]
+reactions:
+  - kind: log
+    id: logger
+    queries: [hot]
+"#,
+        ));
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn test_reports_broken_references() {
+        let issues = validate_semantics(&config(
+            r#"
+sources:
+  - kind: mock
+    id: sensors
+queries:
+  - id: hot
+    query: "MATCH (s:Sensor) RETURN s.id"
+    queryLanguage: Cypher
+    middleware:
+      - kind: map
+        name: unused-map
+    sources:
+      - sourceId: sensors
+      - sourceId: missing
+        pipeline: [decode]
+  - id: broken
+    query: "MATCH (s:Sensor RETURN"
+    queryLanguage: Cypher
+    sources:
+      - sourceId: sensors
+reactions:
+  - kind: log
+    id: logger
+    queries: [hot, broken, ghost]
+"#,
+        ));
+        let found = codes(&issues);
+        for expected in [
+            ("query", "hot", semantic_codes::UNKNOWN_SOURCE),
+            ("query", "hot", semantic_codes::UNKNOWN_MIDDLEWARE),
+            ("middleware", "unused-map", semantic_codes::UNUSED_COMPONENT),
+            ("query", "broken", semantic_codes::INVALID_QUERY),
+            ("reaction", "logger", semantic_codes::UNKNOWN_QUERY),
+        ] {
+            assert!(
+                found.contains(&expected),
+                "missing {expected:?} in {found:?}"
+            );
+        }
+        assert_eq!(issues.iter().filter(|i| i.is_error()).count(), 4);
+    }
+
+    #[test]
+    fn test_reports_unmatched_and_join_labels() {
+        let issues = validate_semantics(&config(
+            r#"
+sources:
+  - kind: mock
+    id: shop
+queries:
+  - id: orders
+    query: "MATCH (o:Order)-[:PLACED_BY]->(c:Customer) RETURN o.id, c.name"
+    queryLanguage: Cypher
+    sources:
+      - sourceId: shop
+        nodes: [Order]
+        relations: [SHIPPED_TO]
+    joins:
+      - id: PLACED_BY
+        keys:
+          - label: Order
+            property: customer_id
+          - label: Client
+            property: id
+"#,
+        ));
+        let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).collect();
+        assert!(
+            messages
+                .contains(&"node label 'Customer' is not in the `nodes` of any subscribed source"),
+            "{messages:?}"
+        );
+        assert!(
+            messages.contains(
+                &"join 'PLACED_BY' keys on label 'Client', which the query does not match"
+            ),
+            "{messages:?}"
+        );
+        // The join synthesizes PLACED_BY, so it need not be in `relations`.
+        assert!(!messages
+            .iter()
+            .any(|m| m.contains("relation label 'PLACED_BY'")));
+        assert!(issues.iter().all(|i| !i.is_error()));
+    }
+
+    #[test]
+    fn test_reports_unused_components_per_instance() {
+        let issues = validate_semantics(&config(
+            r#"
+instances:
+  - id: edge
+    sources:
+      - kind: mock
+        id: idle
+    bootstrapProviders:
+      - kind: noop
+        id: spare
+    identityProviders:
+      - kind: password
+        id: creds
+        username: drasi
+        password: secret
+    queries:
+      - id: lonely
+        query: "MATCH (n:Thing) RETURN n"
+        queryLanguage: Cypher
+"#,
+        ));
+        assert_eq!(
+            codes(&issues),
+            vec![
+                ("source", "idle", semantic_codes::UNUSED_COMPONENT),
+                ("query", "lonely", semantic_codes::UNUSED_COMPONENT),
+                (
+                    "bootstrapProvider",
+                    "spare",
+                    semantic_codes::UNUSED_COMPONENT
+                ),
+                (
+                    "identityProvider",
+                    "creds",
+                    semantic_codes::UNUSED_COMPONENT
+                ),
+            ]
+        );
+        assert!(issues
+            .iter()
+            .all(|i| i.instance_id.as_deref() == Some("edge")));
+    }
+}
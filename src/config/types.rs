@@ -20,11 +20,13 @@ use std::net::IpAddr;
 use std::path::Path;
 use std::str::FromStr;
 
+use super::mode::ServerMode;
 // Import the config enums from api::models
 use crate::api::mappings::{DtoMapper, QueryConfigMapper};
 use crate::api::models::{
-    ConfigValue, IdentityProviderConfig, QueryConfigDto, ReactionConfig, SecretStoreConfig,
-    SourceConfig, StateStoreConfig, TopLevelBootstrapProviderConfig,
+    ArchiveRetentionConfig, ConfigValue, IdentityProviderConfig, QueryConfigDto, ReactionConfig,
+    SecretStoreConfig, SourceConfig, StateStoreConfig, TopLevelBootstrapProviderConfig,
+    WalRetentionConfig,
 };
 use drasi_lib::config::QueryConfig;
 
@@ -61,15 +63,28 @@ pub struct DrasiServerConfig {
     /// Enable automatic persistence of API changes to config file
     #[serde(default = "default_persist_config")]
     pub persist_config: bool,
+    /// Which changes the REST API accepts: `readWrite` (default), `locked`
+    /// (start/stop and plugin loads only) or `readOnly` (none). Independent
+    /// of `persistConfig`, which only decides whether changes are saved.
+    #[serde(default, skip_serializing_if = "ServerMode::is_read_write")]
+    pub mode: ServerMode,
     /// Enable persistent indexing using RocksDB (default: false uses in-memory indexes)
     #[serde(default = "default_persist_index")]
     pub persist_index: bool,
     /// Enable the archive index (element version history for past() functions)
     /// on persistent queries. Default: false. Every element update is also
-    /// written to the archive when enabled, and the archive has no retention,
-    /// so leave this off unless queries use the past() functions.
+    /// written to the archive when enabled, and the archive keeps every
+    /// version unless `archiveRetention` bounds it.
     #[serde(default = "default_enable_archive")]
     pub enable_archive: bool,
+    /// Age and version limits for the archive index, enforced by a
+    /// background task. Unset keeps every version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_retention: Option<ArchiveRetentionConfig>,
+    /// Events to keep in each source's WAL for replay after the queries
+    /// have processed them. Unset prunes them right away.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_retention: Option<WalRetentionConfig>,
     /// Enable the web UI at /ui (default: true)
     #[serde(default = "default_enable_ui")]
     pub enable_ui: bool,
@@ -120,6 +135,15 @@ pub struct DrasiServerConfig {
     /// When provided, only listed identities are trusted (no implicit default).
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub trusted_identities: Vec<TrustedIdentity>,
+    /// Public keys for offline plugin signature verification.
+    /// Plugins with a valid key signature are trusted without contacting the
+    /// registry; remaining plugins fall back to keyless `trustedIdentities`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_keys: Vec<TrustedKey>,
+    /// Crash-loop protection for cdylib plugins. When omitted, a plugin's
+    /// circuit breaker opens after 5 failures within 300 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin_circuit_breaker: Option<PluginCircuitBreakerConfig>,
     /// Enable filesystem watching for plugin changes (default: false, OFF by default for stability)
     #[serde(default)]
     pub hot_reload_plugins: bool,
@@ -166,8 +190,11 @@ impl Default for DrasiServerConfig {
             port: ConfigValue::Static(8080),
             log_level: ConfigValue::Static("info".to_string()),
             persist_config: true,
+            mode: ServerMode::ReadWrite,
             persist_index: false,
             enable_archive: false,
+            archive_retention: None,
+            wal_retention: None,
             enable_ui: true,
             solutions_dir: None,
             state_store: None,
@@ -179,6 +206,8 @@ impl Default for DrasiServerConfig {
             plugins: Vec::new(),
             verify_plugins: true,
             trusted_identities: Vec::new(),
+            trusted_keys: Vec::new(),
+            plugin_circuit_breaker: None,
             hot_reload_plugins: false,
             hot_reload_debounce_ms: 2000,
             cors_allowed_origins: Vec::new(),
@@ -260,6 +289,80 @@ pub struct TrustedIdentity {
     pub subject_pattern: String,
 }
 
+/// A locally configured public key for offline plugin signature verification.
+///
+/// Plugins signed with the matching private key (e.g. `cosign sign-blob --key`)
+/// are verified against a `<plugin>.sig` file next to the plugin, or a
+/// `key_signature` entry in `plugins.lock`, without contacting the registry.
+/// Exactly one of `publicKey` or `publicKeyPath` must be set.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TrustedKey {
+    /// Name used to identify the key in logs.
+    pub name: String,
+    /// Inline PEM-encoded public key (RSA, ECDSA or Ed25519).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// Path to a PEM-encoded public key file, relative to the config file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key_path: Option<String>,
+}
+
+/// Per-plugin circuit breaker thresholds.
+///
+/// Panics, timeouts and start failures of components created from a plugin
+/// count as failures. Once `maxFailures` failures occur within `windowSecs`,
+/// the plugin's components are no longer started until `windowSecs` has
+/// passed since the breaker opened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PluginCircuitBreakerConfig {
+    /// Failures within the window that open the breaker (default: 5)
+    #[serde(default = "default_breaker_max_failures")]
+    pub max_failures: u32,
+    /// Sliding window and open period in seconds (default: 300)
+    #[serde(default = "default_breaker_window_secs")]
+    pub window_secs: u64,
+    /// Timeout for plugin start, stop, subscribe and deprovision calls in
+    /// seconds (default: 60)
+    #[serde(default = "default_breaker_call_timeout_secs")]
+    pub call_timeout_secs: u64,
+}
+
+impl Default for PluginCircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            max_failures: default_breaker_max_failures(),
+            window_secs: default_breaker_window_secs(),
+            call_timeout_secs: default_breaker_call_timeout_secs(),
+        }
+    }
+}
+
+impl PluginCircuitBreakerConfig {
+    /// The failure window as a [`std::time::Duration`].
+    pub fn window(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.window_secs)
+    }
+
+    /// The plugin call timeout as a [`std::time::Duration`].
+    pub fn call_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.call_timeout_secs)
+    }
+}
+
+fn default_breaker_max_failures() -> u32 {
+    5
+}
+
+fn default_breaker_window_secs() -> u64 {
+    300
+}
+
+fn default_breaker_call_timeout_secs() -> u64 {
+    60
+}
+
 fn default_enable_ui() -> bool {
     true
 }
@@ -283,6 +386,14 @@ pub struct DrasiLibInstanceConfig {
     /// persistent queries (default: false).
     #[serde(default = "default_enable_archive")]
     pub enable_archive: bool,
+    /// Age and version limits for this instance's archive index. Unset
+    /// keeps every version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_retention: Option<ArchiveRetentionConfig>,
+    /// Events to keep in this instance's source WALs for replay. Unset
+    /// prunes them once the queries have processed them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_retention: Option<WalRetentionConfig>,
     /// Optional state store provider configuration for plugin state persistence
     ///
     /// When set, plugins (Sources, BootstrapProviders, Reactions) can persist
@@ -328,6 +439,8 @@ pub struct ResolvedInstanceConfig {
     pub id: String,
     pub persist_index: bool,
     pub enable_archive: bool,
+    pub archive_retention: Option<ArchiveRetentionConfig>,
+    pub wal_retention: Option<WalRetentionConfig>,
     pub state_store: Option<StateStoreConfig>,
     pub secret_store: Option<SecretStoreConfig>,
     pub default_priority_queue_capacity: Option<usize>,
@@ -384,6 +497,8 @@ impl DrasiServerConfig {
                 id: self.id.clone(),
                 persist_index: self.persist_index,
                 enable_archive: self.enable_archive,
+                archive_retention: self.archive_retention.clone(),
+                wal_retention: self.wal_retention.clone(),
                 state_store: self.state_store.clone(),
                 secret_store: self.secret_store.clone(),
                 default_priority_queue_capacity: self.default_priority_queue_capacity.clone(),
@@ -424,6 +539,12 @@ impl DrasiServerConfig {
                     None
                 };
 
+            if let Some(retention) = &instance.archive_retention {
+                retention
+                    .validate()
+                    .map_err(|e| anyhow::anyhow!("Instance '{id}': {e}"))?;
+            }
+
             // Map query DTOs to QueryConfig
             let query_mapper = QueryConfigMapper;
             let queries: Vec<QueryConfig> = instance
@@ -461,6 +582,8 @@ impl DrasiServerConfig {
                 id,
                 persist_index: instance.persist_index,
                 enable_archive: instance.enable_archive,
+                archive_retention: instance.archive_retention.clone(),
+                wal_retention: instance.wal_retention.clone(),
                 state_store: instance.state_store.clone(),
                 secret_store: instance.secret_store.clone(),
                 default_priority_queue_capacity,
@@ -778,7 +901,7 @@ mod tests {
         let config: DrasiServerConfig = serde_yaml::from_str(yaml).unwrap();
         assert!(config.state_store.is_some());
         let state_store = config.state_store.unwrap();
-        assert_eq!(state_store.kind(), "redb");
+        assert_eq!(state_store.kind, "redb");
     }
 
     #[test]
@@ -819,7 +942,7 @@ mod tests {
             deserialized.state_store.is_some(),
             "Deserialized config should have state_store"
         );
-        assert_eq!(deserialized.state_store.as_ref().unwrap().kind(), "redb");
+        assert_eq!(deserialized.state_store.as_ref().unwrap().kind, "redb");
     }
 
     #[test]
@@ -890,7 +1013,7 @@ mod tests {
         // First instance has state_store
         assert!(config.instances[0].state_store.is_some());
         assert_eq!(
-            config.instances[0].state_store.as_ref().unwrap().kind(),
+            config.instances[0].state_store.as_ref().unwrap().kind,
             "redb"
         );
 
@@ -915,7 +1038,7 @@ mod tests {
 
         assert_eq!(resolved.len(), 1);
         assert!(resolved[0].state_store.is_some());
-        assert_eq!(resolved[0].state_store.as_ref().unwrap().kind(), "redb");
+        assert_eq!(resolved[0].state_store.as_ref().unwrap().kind, "redb");
     }
 
     #[test]
@@ -1062,4 +1185,24 @@ mod tests {
         assert!(!config.auto_install_plugins);
         assert!(config.plugins.is_empty());
     }
+
+    #[test]
+    fn test_plugin_circuit_breaker_parsed_from_yaml() {
+        let yaml = r#"
+            id: test-server
+            host: 0.0.0.0
+            port: 8080
+            pluginCircuitBreaker:
+              maxFailures: 3
+        "#;
+
+        let config: DrasiServerConfig = serde_yaml::from_str(yaml).unwrap();
+        let breaker = config.plugin_circuit_breaker.unwrap();
+        assert_eq!(breaker.max_failures, 3);
+        assert_eq!(breaker.window_secs, 300);
+        assert_eq!(breaker.call_timeout_secs, 60);
+        assert!(DrasiServerConfig::default()
+            .plugin_circuit_breaker
+            .is_none());
+    }
 }
@@ -0,0 +1,526 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bounded, numbered history of the server config file.
+//!
+//! Every time [`crate::persistence::ConfigPersistence`] writes the config
+//! file, the new content is recorded here as a revision together with the
+//! operation that caused the write and a line diff against the previous
+//! revision. Revisions live in a `<config file>.revisions/` directory next to
+//! the config file:
+//!
+//! ```text
+//! server.yaml
+//! server.yaml.revisions/
+//!   000001.yaml   # full config content of revision 1
+//!   000001.json   # metadata and diff of revision 1
+//!   000002.yaml
+//!   000002.json
+//! ```
+//!
+//! Only the newest [`DEFAULT_MAX_REVISIONS`] revisions are kept. Numbers are
+//! never reused, so a revision number always refers to the same content.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use utoipa::ToSchema;
+
+/// Number of revisions kept before the oldest are pruned.
+pub const DEFAULT_MAX_REVISIONS: usize = 50;
+
+/// Lines of unchanged context shown around each change in a diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Metadata of one recorded config revision.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRevision {
+    /// Revision number (increases by one per save, never reused)
+    pub revision: u64,
+
+    /// When the config file was written
+    pub timestamp: DateTime<Utc>,
+
+    /// Operation that caused the save (e.g. "creating source")
+    pub operation: String,
+
+    /// Lines added relative to the previous revision
+    pub lines_added: usize,
+
+    /// Lines removed relative to the previous revision
+    pub lines_removed: usize,
+}
+
+/// On-disk metadata file: the revision summary plus its diff.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RevisionRecord {
+    #[serde(flatten)]
+    revision: ConfigRevision,
+    diff: String,
+}
+
+/// Revision history for one config file.
+pub struct RevisionStore {
+    dir: PathBuf,
+    max_revisions: usize,
+    /// Serializes `record` so concurrent saves get distinct numbers.
+    lock: Mutex<()>,
+}
+
+impl RevisionStore {
+    /// Store for `config_file`, kept in `<config_file>.revisions/`.
+    pub fn for_config_file(config_file: &Path) -> Self {
+        let mut dir = config_file.as_os_str().to_owned();
+        dir.push(".revisions");
+        Self {
+            dir: PathBuf::from(dir),
+            max_revisions: DEFAULT_MAX_REVISIONS,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Keep at most `max_revisions` revisions (minimum 1).
+    pub fn with_max_revisions(mut self, max_revisions: usize) -> Self {
+        self.max_revisions = max_revisions.max(1);
+        self
+    }
+
+    /// Directory holding the revision files.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Record `content` as a new revision.
+    ///
+    /// `previous` is the config file content before this write; it is used
+    /// as the diff base (and recorded as an "initial" revision) when the
+    /// history is empty, so the config the server started with can be rolled
+    /// back to. Returns `None` without recording anything when `content` is
+    /// identical to the latest revision.
+    pub fn record(
+        &self,
+        content: &str,
+        operation: &str,
+        previous: Option<&str>,
+    ) -> Result<Option<ConfigRevision>> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| anyhow!("Config revision store lock poisoned"))?;
+        std::fs::create_dir_all(&self.dir).with_context(|| {
+            format!(
+                "Failed to create config revision directory {}",
+                self.dir.display()
+            )
+        })?;
+
+        let numbers = self.revision_numbers()?;
+        let (base, next) = match numbers.last() {
+            Some(&latest) => (self.read_content(latest)?, latest + 1),
+            None => match previous {
+                Some(previous) => {
+                    self.write_revision(1, previous, "initial", "")?;
+                    (previous.to_string(), 2)
+                }
+                None => (String::new(), 1),
+            },
+        };
+        if base == content && next > 1 {
+            return Ok(None);
+        }
+
+        let diff = unified_diff(&base, content);
+        let revision = self.write_revision(next, content, operation, &diff)?;
+        self.prune()?;
+        Ok(Some(revision))
+    }
+
+    /// All retained revisions, oldest first.
+    pub fn list(&self) -> Result<Vec<ConfigRevision>> {
+        self.revision_numbers()?
+            .into_iter()
+            .map(|n| self.read_record(n).map(|r| r.revision))
+            .collect()
+    }
+
+    /// Metadata of revision `n`, or `None` if it was never recorded or has
+    /// been pruned.
+    pub fn get(&self, n: u64) -> Result<Option<ConfigRevision>> {
+        if !self.content_path(n).exists() {
+            return Ok(None);
+        }
+        self.read_record(n).map(|r| Some(r.revision))
+    }
+
+    /// Unified diff of revision `n` against the revision before it.
+    pub fn diff(&self, n: u64) -> Result<Option<String>> {
+        if !self.content_path(n).exists() {
+            return Ok(None);
+        }
+        self.read_record(n).map(|r| Some(r.diff))
+    }
+
+    /// [`Self::diff`] recomputed with both revisions passed through `redact`
+    /// first, so values it hides never show up. When the revision before `n`
+    /// has been pruned, all of revision `n` is shown as added.
+    pub fn redacted_diff(
+        &self,
+        n: u64,
+        redact: impl Fn(&str) -> Result<String>,
+    ) -> Result<Option<String>> {
+        let Some(diff) = self.diff(n)? else {
+            return Ok(None);
+        };
+        if diff.is_empty() {
+            return Ok(Some(diff));
+        }
+        let previous = match n.checked_sub(1).map(|p| self.content(p)).transpose()? {
+            Some(Some(previous)) => redact(&previous)?,
+            _ => String::new(),
+        };
+        let current = redact(&self.read_content(n)?)?;
+        Ok(Some(unified_diff(&previous, &current)))
+    }
+
+    /// Full config file content of revision `n`.
+    pub fn content(&self, n: u64) -> Result<Option<String>> {
+        if !self.content_path(n).exists() {
+            return Ok(None);
+        }
+        self.read_content(n).map(Some)
+    }
+
+    fn write_revision(
+        &self,
+        n: u64,
+        content: &str,
+        operation: &str,
+        diff: &str,
+    ) -> Result<ConfigRevision> {
+        let (lines_added, lines_removed) = diff_stats(diff);
+        let record = RevisionRecord {
+            revision: ConfigRevision {
+                revision: n,
+                timestamp: Utc::now(),
+                operation: operation.to_string(),
+                lines_added,
+                lines_removed,
+            },
+            diff: diff.to_string(),
+        };
+        std::fs::write(self.content_path(n), content)
+            .with_context(|| format!("Failed to write config revision {n}"))?;
+        std::fs::write(self.record_path(n), serde_json::to_vec_pretty(&record)?)
+            .with_context(|| format!("Failed to write config revision {n} metadata"))?;
+        Ok(record.revision)
+    }
+
+    fn prune(&self) -> Result<()> {
+        let numbers = self.revision_numbers()?;
+        if numbers.len() <= self.max_revisions {
+            return Ok(());
+        }
+        for n in &numbers[..numbers.len() - self.max_revisions] {
+            let _ = std::fs::remove_file(self.record_path(*n));
+            std::fs::remove_file(self.content_path(*n))
+                .with_context(|| format!("Failed to prune config revision {n}"))?;
+        }
+        Ok(())
+    }
+
+    /// Numbers of the retained revisions, ascending.
+    fn revision_numbers(&self) -> Result<Vec<u64>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(anyhow!(
+                    "Failed to read config revision directory {}: {e}",
+                    self.dir.display()
+                ))
+            }
+        };
+        let mut numbers: Vec<u64> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("yaml") {
+                    return None;
+                }
+                path.file_stem()?.to_str()?.parse().ok()
+            })
+            .collect();
+        numbers.sort_unstable();
+        Ok(numbers)
+    }
+
+    fn read_content(&self, n: u64) -> Result<String> {
+        std::fs::read_to_string(self.content_path(n))
+            .with_context(|| format!("Failed to read config revision {n}"))
+    }
+
+    fn read_record(&self, n: u64) -> Result<RevisionRecord> {
+        let bytes = std::fs::read(self.record_path(n))
+            .with_context(|| format!("Failed to read config revision {n} metadata"))?;
+        serde_json::from_slice(&bytes)
+            .with_context(|| format!("Invalid metadata for config revision {n}"))
+    }
+
+    fn content_path(&self, n: u64) -> PathBuf {
+        self.dir.join(format!("{n:06}.yaml"))
+    }
+
+    fn record_path(&self, n: u64) -> PathBuf {
+        self.dir.join(format!("{n:06}.json"))
+    }
+}
+
+/// Counts of added and removed lines in a unified diff.
+fn diff_stats(diff: &str) -> (usize, usize) {
+    diff.lines()
+        .filter(|line| !line.starts_with("@@"))
+        .fold((0, 0), |(added, removed), line| {
+            if line.starts_with('+') {
+                (added + 1, removed)
+            } else if line.starts_with('-') {
+                (added, removed + 1)
+            } else {
+                (added, removed)
+            }
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Keep,
+    Remove,
+    Add,
+}
+
+/// Line-based unified diff of `old` → `new` with [`DIFF_CONTEXT_LINES`]
+/// lines of context. Returns an empty string when they are identical.
+pub fn unified_diff(old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_ops(&old_lines, &new_lines);
+
+    // Each op with the line numbers (0-based) it sits at in old and new.
+    let mut positioned = Vec::with_capacity(ops.len());
+    let (mut i, mut j) = (0, 0);
+    for op in ops {
+        positioned.push((op, i, j));
+        match op {
+            DiffOp::Keep => {
+                i += 1;
+                j += 1;
+            }
+            DiffOp::Remove => i += 1,
+            DiffOp::Add => j += 1,
+        }
+    }
+
+    let changes: Vec<usize> = positioned
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _, _))| *op != DiffOp::Keep)
+        .map(|(k, _)| k)
+        .collect();
+    let Some(&first) = changes.first() else {
+        return String::new();
+    };
+
+    // Group changes whose context windows touch into hunks.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    let mut start = first.saturating_sub(DIFF_CONTEXT_LINES);
+    let mut end = first;
+    for &k in &changes[1..] {
+        if k - end > 2 * DIFF_CONTEXT_LINES {
+            hunks.push((start, (end + DIFF_CONTEXT_LINES).min(positioned.len() - 1)));
+            start = k - DIFF_CONTEXT_LINES;
+        }
+        end = k;
+    }
+    hunks.push((start, (end + DIFF_CONTEXT_LINES).min(positioned.len() - 1)));
+
+    let mut out = String::new();
+    for (start, end) in hunks {
+        let slice = &positioned[start..=end];
+        let old_count = slice.iter().filter(|(op, _, _)| *op != DiffOp::Add).count();
+        let new_count = slice
+            .iter()
+            .filter(|(op, _, _)| *op != DiffOp::Remove)
+            .count();
+        let (_, old_start, new_start) = slice[0];
+        // Unified diff numbers lines from 1, and uses the preceding line
+        // number for an empty range.
+        let old_start = if old_count == 0 {
+            old_start
+        } else {
+            old_start + 1
+        };
+        let new_start = if new_count == 0 {
+            new_start
+        } else {
+            new_start + 1
+        };
+        out.push_str(&format!(
+            "@@ -{old_start},{old_count} +{new_start},{new_count} @@\n"
+        ));
+        for &(op, i, j) in slice {
+            let (prefix, line) = match op {
+                DiffOp::Keep => (' ', old_lines[i]),
+                DiffOp::Remove => ('-', old_lines[i]),
+                DiffOp::Add => ('+', new_lines[j]),
+            };
+            out.push(prefix);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Edit script turning `old` into `new`, from a longest common subsequence
+/// of lines. Common leading and trailing lines are matched up front so the
+/// quadratic table only covers the changed region.
+fn diff_ops(old: &[&str], new: &[&str]) -> Vec<DiffOp> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = vec![DiffOp::Keep; prefix];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            ops.push(DiffOp::Keep);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(DiffOp::Remove);
+            i += 1;
+        } else {
+            ops.push(DiffOp::Add);
+            j += 1;
+        }
+    }
+    ops.resize(ops.len() + a.len() - i, DiffOp::Remove);
+    ops.resize(ops.len() + b.len() - j, DiffOp::Add);
+    ops.resize(ops.len() + suffix, DiffOp::Keep);
+    ops
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store(dir: &TempDir) -> RevisionStore {
+        RevisionStore::for_config_file(&dir.path().join("server.yaml"))
+    }
+
+    #[test]
+    fn test_unified_diff_marks_changed_lines() {
+        let old = "a\nb\nc\nd\n";
+        let new = "a\nb\nX\nd\ne\n";
+        assert_eq!(
+            unified_diff(old, new),
+            "@@ -1,4 +1,5 @@\n a\n b\n-c\n+X\n d\n+e\n"
+        );
+        assert_eq!(unified_diff(old, old), "");
+    }
+
+    #[test]
+    fn test_unified_diff_splits_distant_changes_into_hunks() {
+        let old: String = (1..=20).map(|n| format!("line{n}\n")).collect();
+        let new = old
+            .replace("line2\n", "two\n")
+            .replace("line18\n", "eighteen\n");
+        let diff = unified_diff(&old, &new);
+        let headers: Vec<&str> = diff.lines().filter(|l| l.starts_with("@@")).collect();
+        assert_eq!(headers, vec!["@@ -1,5 +1,5 @@", "@@ -15,6 +15,6 @@"]);
+    }
+
+    #[test]
+    fn test_unified_diff_from_empty() {
+        assert_eq!(unified_diff("", "a\nb\n"), "@@ -0,0 +1,2 @@\n+a\n+b\n");
+    }
+
+    #[test]
+    fn test_record_numbers_revisions_and_skips_unchanged_content() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir);
+
+        let first = store
+            .record("id: a\n", "creating source", Some("id: original\n"))
+            .unwrap()
+            .unwrap();
+        // The pre-existing file becomes revision 1.
+        assert_eq!(first.revision, 2);
+        assert_eq!(first.operation, "creating source");
+        assert_eq!((first.lines_added, first.lines_removed), (1, 1));
+        assert_eq!(store.content(1).unwrap().unwrap(), "id: original\n");
+        assert_eq!(store.get(1).unwrap().unwrap().operation, "initial");
+
+        assert!(store.record("id: a\n", "saving", None).unwrap().is_none());
+
+        let second = store
+            .record("id: b\n", "deleting source", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.revision, 3);
+        assert_eq!(
+            store.diff(3).unwrap().unwrap(),
+            "@@ -1,1 +1,1 @@\n-id: a\n+id: b\n"
+        );
+        let numbers: Vec<u64> = store.list().unwrap().iter().map(|r| r.revision).collect();
+        assert_eq!(numbers, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_record_prunes_oldest_revisions() {
+        let dir = TempDir::new().unwrap();
+        let store = store(&dir).with_max_revisions(2);
+        for n in 0..4 {
+            store.record(&format!("n: {n}\n"), "save", None).unwrap();
+        }
+        let numbers: Vec<u64> = store.list().unwrap().iter().map(|r| r.revision).collect();
+        assert_eq!(numbers, vec![3, 4]);
+        assert!(store.get(1).unwrap().is_none());
+        assert!(store.content(2).unwrap().is_none());
+        assert_eq!(store.content(4).unwrap().unwrap(), "n: 3\n");
+    }
+}
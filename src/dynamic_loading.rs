@@ -20,7 +20,11 @@
 //! Each plugin is fully self-contained and communicates through a stable C ABI.
 //! No shared runtime, `RTLD_GLOBAL`, or identical compiler versions are required.
 
+use crate::plugin_compatibility::{
+    self, CompatibilityReport, HostCompatibility, PluginBuildInfo, PluginCompatibility,
+};
 use crate::plugin_registry::PluginRegistry;
+use crate::state_stores::{StateStorePluginDescriptor, StateStoreRegistry};
 use anyhow::Result;
 use drasi_host_sdk::callbacks::{self, CallbackContext};
 use drasi_host_sdk::loader::{PluginLoader, PluginLoaderConfig};
@@ -32,6 +36,7 @@ use drasi_plugin_sdk::{
     SourcePluginDescriptor,
 };
 use log::{debug, info, warn};
+use std::collections::HashSet;
 use std::ffi::c_void;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -47,12 +52,16 @@ const PLUGIN_FILE_PATTERNS: &[&str] = &[
     "libdrasi_secret_store_*",
     "libdrasi_secret-store_*",
     "libdrasi_identity_*",
+    "libdrasi_state_store_*",
+    "libdrasi_state-store_*",
     "drasi_source_*",
     "drasi_reaction_*",
     "drasi_bootstrap_*",
     "drasi_secret_store_*",
     "drasi_secret-store_*",
     "drasi_identity_*",
+    "drasi_state_store_*",
+    "drasi_state-store_*",
 ];
 
 /// Statistics from a cdylib plugin loading operation.
@@ -65,11 +74,29 @@ pub struct PluginLoadStats {
     pub bootstrap_descriptors: usize,
     pub secret_store_descriptors: usize,
     pub identity_provider_descriptors: usize,
+    pub state_store_descriptors: usize,
     /// Per-plugin information for orchestrator registration.
     pub loaded_plugins: Vec<StartupPluginRecord>,
+    /// Compatibility of loaded plugins, plus the incompatible ones that were
+    /// skipped before loading.
+    pub compatibility: CompatibilityReport,
     /// Config resolver injection handles for all loaded plugin cdylibs.
     /// Stored as raw fn ptrs because `LoadedPlugin` is consumed during registration.
     config_resolver_injectors: Vec<ConfigResolverInjector>,
+    /// State store kinds exported by loaded plugins, registered into the
+    /// server's `StateStoreRegistry` rather than the `PluginRegistry`.
+    state_stores: Vec<PluginStateStore>,
+}
+
+/// A state store kind exported by a loaded plugin.
+struct PluginStateStore(Arc<dyn StateStorePluginDescriptor>);
+
+impl std::fmt::Debug for PluginStateStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PluginStateStore")
+            .field(&self.0.kind())
+            .finish()
+    }
 }
 
 /// Saved config resolver injection handle for a single loaded plugin cdylib.
@@ -95,6 +122,13 @@ impl PluginLoadStats {
             (injector.set_fn)(ctx, callback);
         }
     }
+
+    /// Register the state store kinds the loaded plugins export.
+    pub fn register_state_stores(&self, registry: &mut StateStoreRegistry) {
+        for PluginStateStore(descriptor) in &self.state_stores {
+            registry.register_state_store(descriptor.clone());
+        }
+    }
 }
 
 /// Information about a single plugin loaded at startup.
@@ -119,11 +153,17 @@ pub struct StartupPluginRecord {
 /// When `allowed_files` is `Some`, only plugins whose filename matches the
 /// allowlist will be loaded. This is used when `--skip-verification` is NOT set
 /// to ensure only verified plugins are loaded.
+///
+/// Plugins whose `plugins.lock` entry shows an incompatible build (SDK, core
+/// or lib version outside the host's `major.minor`, or a foreign platform)
+/// are skipped before they are opened. Plugins whose load-time metadata shows
+/// one are opened but not registered. Both are reported in
+/// [`PluginLoadStats::compatibility`].
 pub fn load_plugins(
     dir: &Path,
     registry: &mut PluginRegistry,
     callback_context: Option<Arc<CallbackContext>>,
-    allowed_files: Option<&std::collections::HashSet<String>>,
+    allowed_files: Option<&HashSet<String>>,
 ) -> Result<PluginLoadStats> {
     if !dir.exists() {
         debug!("cdylib plugin directory does not exist: {}", dir.display());
@@ -132,23 +172,52 @@ pub fn load_plugins(
 
     info!("Loading cdylib plugins from: {}", dir.display());
 
+    // Pre-flight: skip plugins the lockfile already shows to be incompatible.
+    let host = HostCompatibility::current();
+    let locked = plugin_compatibility::lockfile_build_infos(dir);
+    let preflight = CompatibilityReport::check(host.clone(), locked.clone());
+    let incompatible: HashSet<String> = preflight.incompatible_files().into_iter().collect();
+    for plugin in preflight.incompatible() {
+        warn!(
+            "Skipping incompatible plugin: {} ({})",
+            plugin.plugin.name,
+            plugin.summary()
+        );
+    }
+
     let config = if let Some(allowed) = allowed_files {
         // When an allowlist is provided, only load verified plugins.
         // Warn about any plugin files on disk that are being skipped.
         if let Ok(entries) = std::fs::read_dir(dir) {
             for entry in entries.flatten() {
                 let name = entry.file_name().to_string_lossy().to_string();
-                let is_plugin = PLUGIN_FILE_PATTERNS
-                    .iter()
-                    .any(|pat| matches_glob(pat, &name));
-                if is_plugin && !allowed.contains(&name) {
+                if is_plugin_file_name(&name) && !allowed.contains(&name) {
                     warn!("Skipping unverified plugin: {name} (plugin verification is enabled)",);
                 }
             }
         }
         PluginLoaderConfig {
             plugin_dir: dir.to_path_buf(),
-            file_patterns: allowed.iter().cloned().collect(),
+            file_patterns: allowed
+                .iter()
+                .filter(|name| !incompatible.contains(*name))
+                .cloned()
+                .collect(),
+        }
+    } else if !incompatible.is_empty() {
+        // Glob patterns can't exclude files, so list the compatible ones.
+        let mut files = Vec::new();
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if is_plugin_file_name(&name) && !incompatible.contains(&name) {
+                    files.push(name);
+                }
+            }
+        }
+        PluginLoaderConfig {
+            plugin_dir: dir.to_path_buf(),
+            file_patterns: files,
         }
     } else {
         PluginLoaderConfig {
@@ -171,11 +240,39 @@ pub fn load_plugins(
         callbacks::default_lifecycle_callback_fn(),
     )?;
 
-    let mut stats = PluginLoadStats::default();
+    let mut stats = PluginLoadStats {
+        plugins_failed: incompatible.len(),
+        ..Default::default()
+    };
+    let locked_by_file = plugin_compatibility::by_filename(&locked);
+    let mut loaded_build_infos = Vec::new();
 
     for mut plugin in loaded {
         let meta = plugin.metadata_info.as_deref().unwrap_or("no metadata");
 
+        let file_name = plugin
+            .file_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut build_info = PluginBuildInfo::from_metadata(&file_name, meta);
+        if let Some(entry) = locked_by_file.get(&file_name) {
+            build_info.merge_missing(entry);
+        }
+        // Plugins missing from the lockfile are only checked here, once
+        // their metadata is known; an incompatible one is not registered.
+        let verdict = PluginCompatibility::check(&host, build_info.clone());
+        loaded_build_infos.push(build_info);
+        if !verdict.is_compatible() {
+            warn!(
+                "Skipping incompatible plugin: {} ({})",
+                verdict.plugin.name,
+                verdict.summary()
+            );
+            stats.plugins_failed += 1;
+            continue;
+        }
+
         // Parse version info from metadata string (format: "sdk=X core=Y plugin=Z target=...")
         let plugin_version = meta
             .split_whitespace()
@@ -285,6 +382,26 @@ pub fn load_plugins(
             stats.identity_provider_descriptors += 1;
         }
 
+        // State stores are exported outside the SDK registration, and
+        // `PluginCategory` has no state store variant to record them under.
+        match crate::state_stores::plugin::load_state_store_plugins(&plugin.file_path) {
+            Ok(descriptors) => {
+                for descriptor in descriptors {
+                    let kind = descriptor.kind().to_string();
+                    if plugin_id_parts.is_empty() {
+                        plugin_id_parts.push(format!("state_store/{kind}"));
+                    }
+                    info!("  [cdylib] state_store: {kind} ({meta})");
+                    stats.state_stores.push(PluginStateStore(descriptor));
+                    stats.state_store_descriptors += 1;
+                }
+            }
+            Err(e) => warn!(
+                "Failed to read state stores from {}: {e}",
+                plugin.file_path.display()
+            ),
+        }
+
         let derived_plugin_id = plugin_id_parts
             .into_iter()
             .next()
@@ -301,15 +418,24 @@ pub fn load_plugins(
         stats.plugins_loaded += 1;
     }
 
+    // Report every opened plugin, including those skipped above for core/lib
+    // drift or rustc differences found in their metadata.
+    let mut compatibility = CompatibilityReport::check(host, loaded_build_infos);
+    compatibility
+        .plugins
+        .extend(preflight.plugins.into_iter().filter(|p| !p.is_compatible()));
+    stats.compatibility = compatibility;
+
     let total_descriptors = stats.source_descriptors
         + stats.reaction_descriptors
         + stats.bootstrap_descriptors
         + stats.secret_store_descriptors
-        + stats.identity_provider_descriptors;
+        + stats.identity_provider_descriptors
+        + stats.state_store_descriptors;
 
     if stats.plugins_loaded > 0 {
         info!(
-            "cdylib plugin loading complete: {} loaded, {} descriptors ({} sources, {} reactions, {} bootstraps, {} secret_stores, {} identity providers)",
+            "cdylib plugin loading complete: {} loaded, {} descriptors ({} sources, {} reactions, {} bootstraps, {} secret_stores, {} identity providers, {} state_stores)",
             stats.plugins_loaded,
             total_descriptors,
             stats.source_descriptors,
@@ -317,6 +443,7 @@ pub fn load_plugins(
             stats.bootstrap_descriptors,
             stats.secret_store_descriptors,
             stats.identity_provider_descriptors,
+            stats.state_store_descriptors,
         );
     } else {
         debug!("No cdylib plugins found in '{}'", dir.display());
@@ -325,6 +452,20 @@ pub fn load_plugins(
     Ok(stats)
 }
 
+/// Whether a file name in the plugins directory is a plugin library.
+///
+/// Detached signature files (`<plugin>.sig`) share the plugin's prefix and
+/// are excluded explicitly.
+pub(crate) fn is_plugin_file_name(name: &str) -> bool {
+    let is_signature = Path::new(name)
+        .extension()
+        .is_some_and(|ext| ext == crate::plugin_key_verification::SIGNATURE_FILE_EXTENSION);
+    !is_signature
+        && PLUGIN_FILE_PATTERNS
+            .iter()
+            .any(|pat| matches_glob(pat, name))
+}
+
 /// Simple glob pattern matching for plugin file patterns (e.g., `libdrasi_source_*`).
 fn matches_glob(pattern: &str, name: &str) -> bool {
     if let Some(prefix) = pattern.strip_suffix('*') {
@@ -23,7 +23,7 @@ use drasi_lib::secret_store::SecretStoreProvider;
 use drasi_lib::state_store::StateStoreProvider;
 use drasi_lib::{Reaction, Source};
 use log::info;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::ffi::c_void;
 use std::sync::Arc;
 
@@ -32,8 +32,13 @@ use crate::api::models::{
     BootstrapProviderConfig, ConfigValue, IdentityProviderConfig, TopLevelBootstrapProviderConfig,
     BUILTIN_PASSWORD_KIND,
 };
+use crate::config::migration::{migrate_submitted_reaction, migrate_submitted_source};
 use crate::config::{ReactionConfig, SecretStoreConfig, SourceConfig, StateStoreConfig};
+use crate::plugin_isolation::{IsolatedReaction, IsolatedSource, PluginCircuitBreakers};
 use crate::plugin_registry::PluginRegistry;
+use crate::secret_rotation::{secret_digest, SecretConsumer, SecretConsumers, SecretDigests};
+use crate::secret_stores::{create_builtin_secret_store, BUILTIN_KINDS};
+use crate::state_stores::StateStoreRegistry;
 
 use drasi_host_sdk::{ConfigResolverFn, SecretStoreValueResolverAdapter};
 use drasi_plugin_sdk::ffi::secret_store::FfiGetSecretResult;
@@ -47,24 +52,377 @@ use drasi_plugin_sdk::ConfigValue as SdkConfigValue;
 
 /// Context passed to the host config resolver callback.
 ///
-/// Contains a channel sender to dispatch resolution requests to a dedicated
-/// resolver thread that owns the SDK resolvers (EnvironmentVariableResolver,
-/// SecretStoreValueResolverAdapter).
+/// Holds the secret store of each instance that configures one. Plugins
+/// resolve `ConfigValue::Secret` references while their components are
+/// created, but the callback only carries the secret's name, not which
+/// component is asking. So the host creates each component inside a
+/// [`SecretScope`] that records the secret names its config references, and
+/// the callback resolves a name through the store of the instance whose
+/// components reference it. When components of several instances reference
+/// the same name, the one the host is currently calling into (creating,
+/// starting or stopping) decides; a request that still matches more than one
+/// instance is rejected rather than guessed. The component holds its scope
+/// for as long as it exists, so the plugin can also resolve secrets later,
+/// such as when it starts or reconnects; the scope ends when the component
+/// is deleted or replaced. A name no open scope references is rejected.
+///
+/// Resolution itself runs on a single dedicated resolver thread that owns the
+/// SDK resolvers (EnvironmentVariableResolver,
+/// SecretStoreValueResolverAdapter), because the callback is invoked
+/// synchronously from plugin code; requests from all instances are served
+/// one at a time.
 pub struct ConfigResolverContext {
     resolver_tx: std::sync::mpsc::SyncSender<ResolveRequest>,
+    /// Secret store of each instance that configures one.
+    stores: std::sync::RwLock<HashMap<String, Arc<dyn SecretStoreProvider>>>,
+    /// Open scopes, by id: creations in progress and the components they
+    /// created.
+    scopes: std::sync::Mutex<HashMap<u64, ActiveScope>>,
+    next_scope: std::sync::atomic::AtomicU64,
+}
+
+/// An open scope: whose secrets it resolves, the secret names its
+/// component's config references, how many host calls into the component
+/// are in progress, and the digest of each secret it resolved and not yet
+/// reported by [`SecretScope::consumed`], by name.
+struct ActiveScope {
+    instance_id: String,
+    secrets: HashSet<String>,
+    active: usize,
+    consumed: SecretDigests,
 }
 
 /// A request sent to the dedicated resolver thread.
 struct ResolveRequest {
     config_value: SdkConfigValue<String>,
+    /// The store that resolves a `Secret`; `None` for other variants.
+    store: Option<Arc<dyn SecretStoreProvider>>,
     response_tx: std::sync::mpsc::SyncSender<Result<String, String>>,
 }
 
+/// One component of an instance. Secrets referenced by a config passed
+/// through [`SecretScope::register`] are resolved by that instance's store
+/// until the scope is dropped, so the component created from the config
+/// keeps it (see [`IsolatedSource::with_secret_scope`]).
+pub struct SecretScope<'a> {
+    context: &'a ConfigResolverContext,
+    id: u64,
+}
+
+impl SecretScope<'_> {
+    /// Record the `{kind: Secret, name}` references in `config`, which is
+    /// handed to the plugin unchanged.
+    pub fn register(&self, config: &serde_json::Value) {
+        let mut names = HashSet::new();
+        secret_names(config, &mut names);
+        if let Some(scope) = self
+            .context
+            .scopes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(&self.id)
+        {
+            scope.secrets.extend(names);
+        }
+    }
+
+    /// Mark a host call into the component (its creation, start or stop) as
+    /// in progress until the returned guard is dropped, so secrets the plugin
+    /// resolves meanwhile are attributed to this scope's instance.
+    pub fn enter(&self) -> ScopeCall<'_> {
+        self.context.adjust_active(self.id, true);
+        ScopeCall { scope: self }
+    }
+
+    /// The secrets resolved in the scope since the last call, so rotation
+    /// can later find the components that use them. The scope stays open.
+    pub fn consumed(&self) -> SecretDigests {
+        self.context
+            .scopes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(&self.id)
+            .map(|scope| std::mem::take(&mut scope.consumed))
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for SecretScope<'_> {
+    fn drop(&mut self) {
+        self.context
+            .scopes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.id);
+    }
+}
+
+/// A host call into a scope's component; see [`SecretScope::enter`].
+pub struct ScopeCall<'a> {
+    scope: &'a SecretScope<'a>,
+}
+
+impl Drop for ScopeCall<'_> {
+    fn drop(&mut self) {
+        self.scope.context.adjust_active(self.scope.id, false);
+    }
+}
+
+/// Collect the name of every `{kind: Secret, name}` reference in `value`.
+fn secret_names(value: &serde_json::Value, names: &mut HashSet<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if map.get("kind").and_then(serde_json::Value::as_str) == Some("Secret") {
+                if let Some(serde_json::Value::String(name)) = map.get("name") {
+                    names.insert(name.clone());
+                    return;
+                }
+            }
+            for child in map.values() {
+                secret_names(child, names);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                secret_names(item, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl ConfigResolverContext {
+    /// The process-wide context. Plugins keep the pointer they are given for
+    /// the life of the process, so there is exactly one.
+    pub fn global() -> &'static Self {
+        static CONTEXT: std::sync::OnceLock<ConfigResolverContext> = std::sync::OnceLock::new();
+        CONTEXT.get_or_init(Self::spawn)
+    }
+
+    fn spawn() -> Self {
+        let (tx, rx) = std::sync::mpsc::sync_channel::<ResolveRequest>(64);
+
+        // Spawn a dedicated OS thread that runs resolution using the SDK
+        // resolvers on its own runtime; the callback blocks plugin code, which
+        // may itself be running on a runtime worker.
+        std::thread::Builder::new()
+            .name("config-resolver".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        log::error!("Failed to start config resolver runtime: {e}");
+                        return;
+                    }
+                };
+                let env_resolver = EnvironmentVariableResolver;
+                while let Ok(req) = rx.recv() {
+                    let result = match (&req.config_value, req.store) {
+                        (SdkConfigValue::EnvironmentVariable { .. }, _) => runtime
+                            .block_on(env_resolver.resolve_to_string(&req.config_value))
+                            .map_err(|e| e.to_string()),
+                        (SdkConfigValue::Secret { .. }, Some(store)) => runtime
+                            .block_on(
+                                SecretStoreValueResolverAdapter::new(store)
+                                    .resolve_to_string(&req.config_value),
+                            )
+                            .map_err(|e| e.to_string()),
+                        (SdkConfigValue::Secret { name }, None) => {
+                            Err(format!("No secret store to resolve secret '{name}'"))
+                        }
+                        (SdkConfigValue::Static(s), _) => Ok(s.clone()),
+                    };
+                    let _ = req.response_tx.send(result);
+                }
+            })
+            .expect("Failed to spawn config-resolver thread");
+
+        Self {
+            resolver_tx: tx,
+            stores: std::sync::RwLock::new(HashMap::new()),
+            scopes: std::sync::Mutex::new(HashMap::new()),
+            next_scope: std::sync::atomic::AtomicU64::new(1),
+        }
+    }
+
+    /// Pointer to inject into plugins alongside [`config_resolver_callback`].
+    pub fn as_ptr(&'static self) -> *mut c_void {
+        self as *const Self as *mut c_void
+    }
+
+    /// Use `provider` to resolve the secrets of `instance_id`'s components.
+    pub fn set_instance_store(&self, instance_id: &str, provider: Arc<dyn SecretStoreProvider>) {
+        self.stores
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(instance_id.to_string(), provider);
+    }
+
+    /// Forget the secret store of `instance_id`.
+    pub fn remove_instance_store(&self, instance_id: &str) {
+        self.stores
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(instance_id);
+    }
+
+    /// The secret store of `instance_id`, if it configures one.
+    pub fn instance_store(&self, instance_id: &str) -> Option<Arc<dyn SecretStoreProvider>> {
+        self.stores
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(instance_id)
+            .cloned()
+    }
+
+    /// Start creating a component of `instance_id`. Pass its config through
+    /// [`SecretScope::register`] before handing it to the plugin.
+    pub fn scope(&self, instance_id: &str) -> SecretScope<'_> {
+        let id = self
+            .next_scope
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.scopes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(
+                id,
+                ActiveScope {
+                    instance_id: instance_id.to_string(),
+                    secrets: HashSet::new(),
+                    active: 0,
+                    consumed: SecretDigests::new(),
+                },
+            );
+        SecretScope { context: self, id }
+    }
+
+    /// Count a host call into scope `scope_id`'s component in or out.
+    fn adjust_active(&self, scope_id: u64, entering: bool) {
+        if let Some(scope) = self
+            .scopes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(&scope_id)
+        {
+            scope.active = if entering {
+                scope.active + 1
+            } else {
+                scope.active.saturating_sub(1)
+            };
+        }
+    }
+
+    /// Remember that the scopes in `scope_ids` consumed secret `name`.
+    fn record_consumed(&self, scope_ids: &[u64], name: &str, value: &str) {
+        let mut scopes = self.scopes.lock().unwrap_or_else(|e| e.into_inner());
+        for scope_id in scope_ids {
+            if let Some(scope) = scopes.get_mut(scope_id) {
+                scope
+                    .consumed
+                    .insert(name.to_string(), secret_digest(value));
+            }
+        }
+    }
+
+    /// Resolve secret `name` through `store`, blocking until the resolver
+    /// thread answers. Used by the host's own [`DtoMapper`].
+    pub fn resolve_secret(
+        &self,
+        store: Arc<dyn SecretStoreProvider>,
+        name: &str,
+    ) -> Result<String, String> {
+        self.resolve(
+            SdkConfigValue::Secret {
+                name: name.to_string(),
+            },
+            Some(store),
+        )
+    }
+
+    /// A mapper that resolves secrets through `instance_id`'s store.
+    pub fn instance_mapper(&self, instance_id: &str) -> DtoMapper {
+        match self.instance_store(instance_id) {
+            Some(store) => DtoMapper::new().with_secret_store(store),
+            None => DtoMapper::new(),
+        }
+    }
+
+    fn resolve(
+        &self,
+        config_value: SdkConfigValue<String>,
+        store: Option<Arc<dyn SecretStoreProvider>>,
+    ) -> Result<String, String> {
+        let (response_tx, response_rx) = std::sync::mpsc::sync_channel(1);
+        let request = ResolveRequest {
+            config_value,
+            store,
+            response_tx,
+        };
+        self.resolver_tx
+            .send(request)
+            .map_err(|_| "Config resolver thread is no longer running".to_string())?;
+        response_rx
+            .recv()
+            .map_err(|_| "Config resolver thread dropped response channel".to_string())?
+    }
+
+    /// Find the instance whose components reference secret `name`, its
+    /// store, and the scopes of those components.
+    fn scoped_store(&self, name: &str) -> Result<(Vec<u64>, Arc<dyn SecretStoreProvider>), String> {
+        let (instance_id, scope_ids) = {
+            let scopes = self.scopes.lock().unwrap_or_else(|e| e.into_inner());
+            let referencing: Vec<(u64, &ActiveScope)> = scopes
+                .iter()
+                .filter(|(_, scope)| scope.secrets.contains(name))
+                .map(|(id, scope)| (*id, scope))
+                .collect();
+            let instances = |active_only: bool| -> BTreeSet<&str> {
+                referencing
+                    .iter()
+                    .filter(|(_, scope)| !active_only || scope.active > 0)
+                    .map(|(_, scope)| scope.instance_id.as_str())
+                    .collect()
+            };
+            let all = instances(false);
+            let instance_id = match all.len() {
+                0 => return Err("no component of any instance references it".to_string()),
+                1 => all.into_iter().next(),
+                _ => {
+                    let active = instances(true);
+                    if active.len() == 1 {
+                        active.into_iter().next()
+                    } else {
+                        return Err(format!(
+                            "components of instances {all:?} reference it, and the request cannot be attributed to one of them"
+                        ));
+                    }
+                }
+            }
+            .unwrap_or_default()
+            .to_string();
+            let scope_ids: Vec<u64> = referencing
+                .iter()
+                .filter(|(_, scope)| scope.instance_id == instance_id)
+                .map(|(id, _)| *id)
+                .collect();
+            (instance_id, scope_ids)
+        };
+        let store = self
+            .instance_store(&instance_id)
+            .ok_or_else(|| format!("Instance '{instance_id}' has no secretStore configured"))?;
+        Ok((scope_ids, store))
+    }
+}
+
 /// Host-side `extern "C"` callback that plugins invoke (via `DtoMapper`) to
 /// resolve `ConfigValue` references (secrets, env vars) back through the host.
 ///
 /// The plugin serializes the `ConfigValue` to JSON and passes it here.
-/// The host deserializes it and dispatches to the appropriate SDK resolver.
+/// The host deserializes it and dispatches to the appropriate SDK resolver;
+/// secrets go to the store of the instance whose components reference them.
 pub extern "C" fn host_resolve_config_value(
     ctx: *const c_void,
     config_value_json: FfiStr,
@@ -89,64 +447,32 @@ pub extern "C" fn host_resolve_config_value(
         return FfiGetSecretResult::ok(s.clone());
     }
 
-    // Dispatch to the resolver thread for Secret and EnvironmentVariable variants.
-    let (response_tx, response_rx) = std::sync::mpsc::sync_channel(1);
-    let request = ResolveRequest {
-        config_value,
-        response_tx,
+    let (config_value, store, consumer) = match config_value {
+        SdkConfigValue::Secret { name } => match context.scoped_store(&name) {
+            Ok((scope_ids, store)) => (
+                SdkConfigValue::Secret { name: name.clone() },
+                Some(store),
+                Some((scope_ids, name)),
+            ),
+            Err(e) => {
+                return FfiGetSecretResult::err(format!("Cannot resolve secret '{name}': {e}"));
+            }
+        },
+        other => (other, None, None),
     };
 
-    if context.resolver_tx.send(request).is_err() {
-        return FfiGetSecretResult::err("Config resolver thread is no longer running".to_string());
-    }
-
-    match response_rx.recv() {
-        Ok(Ok(value)) => FfiGetSecretResult::ok(value),
-        Ok(Err(e)) => FfiGetSecretResult::err(e),
-        Err(_) => {
-            FfiGetSecretResult::err("Config resolver thread dropped response channel".to_string())
+    // Dispatch to the resolver thread for Secret and EnvironmentVariable variants.
+    match context.resolve(config_value, store) {
+        Ok(value) => {
+            if let Some((scope_ids, name)) = consumer {
+                context.record_consumed(&scope_ids, &name, &value);
+            }
+            FfiGetSecretResult::ok(value)
         }
+        Err(e) => FfiGetSecretResult::err(e),
     }
 }
 
-/// Build a leaked `ConfigResolverContext` pointer for injection into plugins.
-///
-/// Spawns a dedicated resolver thread that uses the SDK's `ValueResolver`
-/// implementations to handle all `ConfigValue` variants. The returned pointer
-/// is intentionally leaked (process-lifetime) because plugins store it globally.
-pub fn build_config_resolver_context(
-    provider: Arc<dyn SecretStoreProvider>,
-    runtime_handle: tokio::runtime::Handle,
-) -> *mut c_void {
-    let (tx, rx) = std::sync::mpsc::sync_channel::<ResolveRequest>(64);
-
-    // Build the SDK resolvers
-    let env_resolver = EnvironmentVariableResolver;
-    let secret_resolver = SecretStoreValueResolverAdapter::new(provider);
-
-    // Spawn a dedicated OS thread that runs resolution using the SDK resolvers.
-    std::thread::Builder::new()
-        .name("config-resolver".to_string())
-        .spawn(move || {
-            while let Ok(req) = rx.recv() {
-                let result = match &req.config_value {
-                    SdkConfigValue::EnvironmentVariable { .. } => runtime_handle
-                        .block_on(env_resolver.resolve_to_string(&req.config_value))
-                        .map_err(|e| e.to_string()),
-                    SdkConfigValue::Secret { .. } => runtime_handle
-                        .block_on(secret_resolver.resolve_to_string(&req.config_value))
-                        .map_err(|e| e.to_string()),
-                    SdkConfigValue::Static(s) => Ok(s.clone()),
-                };
-                let _ = req.response_tx.send(result);
-            }
-        })
-        .expect("Failed to spawn config-resolver thread");
-
-    let ctx = Box::new(ConfigResolverContext { resolver_tx: tx });
-    Box::into_raw(ctx) as *mut c_void
-}
-
 /// Get the config resolver callback function pointer.
 pub fn config_resolver_callback() -> ConfigResolverFn {
     host_resolve_config_value
@@ -158,7 +484,8 @@ pub fn config_resolver_callback() -> ConfigResolverFn {
 /// then creates instances without holding a borrow on the registry across await
 /// points. Callers that hold an `Arc<RwLock<PluginRegistry>>` should use
 /// [`create_source_locked`] instead to avoid holding the lock across async
-/// creation calls.
+/// creation calls. A plugin source gets a circuit breaker of its own rather
+/// than sharing one with the other components of its plugin.
 pub async fn create_source(
     registry: &PluginRegistry,
     config: SourceConfig,
@@ -189,6 +516,11 @@ pub async fn create_source(
         None
     };
 
+    let plugin_id = registry
+        .get_source_registration(&config.kind)
+        .map(|r| r.plugin_id.clone())
+        .unwrap_or_default();
+
     // All registry borrows are done — create instances without holding the registry
     let source = descriptor
         .create_source(&config.id, &config.config, config.auto_start)
@@ -208,14 +540,26 @@ pub async fn create_source(
         source.set_bootstrap_provider(provider).await;
     }
 
-    Ok(source)
+    Ok(isolate_source(
+        source,
+        &plugin_id,
+        &PluginCircuitBreakers::default(),
+        None,
+    ))
 }
 
 /// Create a source from config, acquiring and releasing the registry lock
 /// internally so the caller never holds a read guard across await points.
+///
+/// Secrets in the config are resolved by `instance_id`'s secret store, and
+/// the ones the source consumed are recorded in `consumers`. A plugin
+/// source's failures are recorded in `breakers`.
 pub async fn create_source_locked(
     registry: &tokio::sync::RwLock<PluginRegistry>,
-    config: SourceConfig,
+    instance_id: &str,
+    consumers: &SecretConsumers,
+    breakers: &PluginCircuitBreakers,
+    mut config: SourceConfig,
 ) -> Result<(Box<dyn Source + 'static>, HashMap<String, String>)> {
     let (descriptor, bootstrap_descriptor, plugin_meta) = {
         let reg = registry.read().await;
@@ -246,25 +590,87 @@ pub async fn create_source_locked(
         (desc, bp_desc, meta)
     }; // lock dropped here
 
+    // Configs submitted at runtime may predate the installed plugin's schema.
+    let migration = migrate_submitted_source(descriptor.as_ref(), &mut config)
+        .map_err(|e| anyhow::anyhow!("Cannot migrate config of source '{}': {e}", config.id))?;
+    if let Some(m) = migration {
+        info!(
+            "Migrated config of source '{}' from {} to {}",
+            m.component_id, m.from_version, m.to_version
+        );
+    }
+
+    let plugin_id = plugin_meta.get("pluginId").cloned().unwrap_or_default();
+    // Only loaded plugins resolve secrets through the host callback, so only
+    // their scopes record the secrets they reference.
+    let scope = ConfigResolverContext::global().scope(instance_id);
+    let inline_bootstrap = config
+        .bootstrap_provider
+        .as_ref()
+        .and_then(|r| r.as_inline());
+    if !plugin_id.is_empty() {
+        scope.register(&config.config);
+        if let Some(bootstrap_config) = inline_bootstrap {
+            scope.register(&bootstrap_config.config);
+        }
+    }
+    let creating = scope.enter();
     let source = descriptor
         .create_source(&config.id, &config.config, config.auto_start)
         .await?;
-
-    if let (Some(bootstrap_config), Some(bp_descriptor)) = (
-        config
-            .bootstrap_provider
-            .as_ref()
-            .and_then(|r| r.as_inline()),
-        bootstrap_descriptor,
-    ) {
+    if let (Some(bootstrap_config), Some(bp_descriptor)) = (inline_bootstrap, bootstrap_descriptor)
+    {
         let provider = bp_descriptor
             .create_bootstrap_provider(&bootstrap_config.config, &config.config)
             .await?;
         info!("Setting bootstrap provider for source '{}'", config.id());
         source.set_bootstrap_provider(provider).await;
     }
+    drop(creating);
+    let secrets = scope.consumed();
+    consumers.record(instance_id, SecretConsumer::Source(config), secrets);
+
+    Ok((
+        isolate_source(source, &plugin_id, breakers, Some(scope)),
+        plugin_meta,
+    ))
+}
+
+/// Wrap a plugin-provided source so its panics, hangs and repeated start
+/// failures are contained (see [`crate::plugin_isolation`]), and so it keeps
+/// the scope that resolves its secrets. Built-in components (empty
+/// `plugin_id`) resolve secrets on the host and are returned unchanged.
+fn isolate_source(
+    source: Box<dyn Source + 'static>,
+    plugin_id: &str,
+    breakers: &PluginCircuitBreakers,
+    scope: Option<SecretScope<'static>>,
+) -> Box<dyn Source + 'static> {
+    if plugin_id.is_empty() {
+        return source;
+    }
+    let mut source = IsolatedSource::new(source, plugin_id, breakers);
+    if let Some(scope) = scope {
+        source = source.with_secret_scope(scope);
+    }
+    Box::new(source)
+}
 
-    Ok((source, plugin_meta))
+/// Reaction counterpart of [`isolate_source`].
+fn isolate_reaction(
+    reaction: Box<dyn Reaction + 'static>,
+    plugin_id: &str,
+    breakers: &PluginCircuitBreakers,
+    scope: Option<SecretScope<'static>>,
+) -> Box<dyn Reaction + 'static> {
+    if plugin_id.is_empty() {
+        return reaction;
+    }
+    let mut reaction = IsolatedReaction::new(reaction, plugin_id, breakers);
+    if let Some(scope) = scope {
+        reaction = reaction.with_secret_scope(scope);
+    }
+    Box::new(reaction)
 }
 
 /// Create a bootstrap provider from configuration using the plugin registry.
@@ -359,7 +765,8 @@ pub fn resolve_source_bootstrap_provider(
 /// Looks up the reaction descriptor under the registry reference, then creates
 /// the instance. Callers that hold an `Arc<RwLock<PluginRegistry>>` should use
 /// [`create_reaction_locked`] instead to avoid holding the lock across async
-/// creation calls.
+/// creation calls. A plugin reaction gets a circuit breaker of its own rather
+/// than sharing one with the other components of its plugin.
 pub async fn create_reaction(
     registry: &PluginRegistry,
     config: ReactionConfig,
@@ -374,22 +781,40 @@ pub async fn create_reaction(
                 registry.reaction_kinds()
             )
         })?;
+    let plugin_id = registry
+        .get_reaction_registration(&config.kind)
+        .map(|r| r.plugin_id.clone())
+        .unwrap_or_default();
 
-    descriptor
+    let reaction = descriptor
         .create_reaction(
             &config.id,
             config.queries.clone(),
             &config.config,
             config.auto_start,
         )
-        .await
+        .await?;
+
+    Ok(isolate_reaction(
+        reaction,
+        &plugin_id,
+        &PluginCircuitBreakers::default(),
+        None,
+    ))
 }
 
 /// Create a reaction from config, acquiring and releasing the registry lock
 /// internally so the caller never holds a read guard across await points.
+///
+/// Secrets in the config are resolved by `instance_id`'s secret store, and
+/// the ones the reaction consumed are recorded in `consumers`. A plugin
+/// reaction's failures are recorded in `breakers`.
 pub async fn create_reaction_locked(
     registry: &tokio::sync::RwLock<PluginRegistry>,
-    config: ReactionConfig,
+    instance_id: &str,
+    consumers: &SecretConsumers,
+    breakers: &PluginCircuitBreakers,
+    mut config: ReactionConfig,
 ) -> Result<(Box<dyn Reaction + 'static>, HashMap<String, String>)> {
     let (descriptor, plugin_meta) = {
         let reg = registry.read().await;
@@ -404,6 +829,21 @@ pub async fn create_reaction_locked(
         (desc, meta)
     }; // lock dropped here
 
+    let migration = migrate_submitted_reaction(descriptor.as_ref(), &mut config)
+        .map_err(|e| anyhow::anyhow!("Cannot migrate config of reaction '{}': {e}", config.id))?;
+    if let Some(m) = migration {
+        info!(
+            "Migrated config of reaction '{}' from {} to {}",
+            m.component_id, m.from_version, m.to_version
+        );
+    }
+
+    let plugin_id = plugin_meta.get("pluginId").cloned().unwrap_or_default();
+    let scope = ConfigResolverContext::global().scope(instance_id);
+    if !plugin_id.is_empty() {
+        scope.register(&config.config);
+    }
+    let creating = scope.enter();
     let reaction = descriptor
         .create_reaction(
             &config.id,
@@ -412,45 +852,79 @@ pub async fn create_reaction_locked(
             config.auto_start,
         )
         .await?;
-
-    Ok((reaction, plugin_meta))
+    drop(creating);
+    let secrets = scope.consumed();
+    consumers.record(instance_id, SecretConsumer::Reaction(config), secrets);
+
+    Ok((
+        isolate_reaction(reaction, &plugin_id, breakers, Some(scope)),
+        plugin_meta,
+    ))
 }
 
-/// Create a state store provider from a StateStoreConfig.
+/// Create a state store provider of a built-in kind (`redb` or `sqlite`)
+/// from a StateStoreConfig. Kinds provided by plugins are only in the
+/// server's registry; use [`create_state_store_from_registry`] for those.
 pub fn create_state_store_provider(
     config: StateStoreConfig,
 ) -> Result<Arc<dyn StateStoreProvider + Send + Sync + 'static>> {
-    let mapper = DtoMapper::new();
-
-    match config {
-        StateStoreConfig::Redb { path } => {
-            use drasi_state_store_redb::RedbStateStoreProvider;
+    create_state_store_from_registry(&StateStoreRegistry::new(), &config)
+}
 
-            let resolved_path: String = mapper.resolve_typed(&path)?;
-            info!("Creating REDB state store provider with path: {resolved_path}");
+/// Create a state store provider from a StateStoreConfig using a state store
+/// registry.
+///
+/// Looks up the `StateStorePluginDescriptor` by kind from the registry, then
+/// calls `create_state_store()` with every field of the config except `kind`.
+pub fn create_state_store_from_registry(
+    registry: &StateStoreRegistry,
+    config: &StateStoreConfig,
+) -> Result<Arc<dyn StateStoreProvider>> {
+    let descriptor = registry.get_state_store(&config.kind).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown state store kind: '{}'. Available: {:?}",
+            config.kind,
+            registry.state_store_kinds()
+        )
+    })?;
 
-            let provider = RedbStateStoreProvider::new(&resolved_path)?;
-            Ok(Arc::new(provider))
-        }
-    }
+    info!(
+        "Creating state store provider (kind: {}, config_version: {})",
+        descriptor.kind(),
+        descriptor.config_version()
+    );
+    descriptor
+        .create_state_store(&config.config)
+        .with_context(|| format!("in stateStore (kind={})", config.kind))
 }
 
 /// Create a secret store provider from a SecretStoreConfig using the plugin registry.
 ///
-/// Looks up the `SecretStorePluginDescriptor` by kind from the registry,
-/// then calls `create_secret_store()` with the config JSON.
+/// Built-in kinds (`file`, `directory`, `env`) are created directly; see
+/// [`crate::secret_stores`]. Other kinds look up the
+/// `SecretStorePluginDescriptor` by kind from the registry, then call
+/// `create_secret_store()` with the config JSON.
 pub async fn create_secret_store_from_registry(
     registry: &tokio::sync::RwLock<PluginRegistry>,
     config: &SecretStoreConfig,
 ) -> Result<Arc<dyn SecretStoreProvider>> {
+    if let Some(provider) = create_builtin_secret_store(config)? {
+        info!(
+            "Creating built-in secret store provider (kind: {})",
+            config.kind
+        );
+        return Ok(provider);
+    }
+
     let descriptor = {
         let reg = registry.read().await;
         reg.get_secret_store(&config.kind).cloned().ok_or_else(|| {
             anyhow::anyhow!(
                 "No secret store plugin registered for kind '{}'. \
-                     Available: {:?}. Make sure the plugin is loaded.",
+                     Available: {:?} (built in: {:?}). Make sure the plugin is loaded.",
                 config.kind,
-                reg.secret_store_kinds()
+                reg.secret_store_kinds(),
+                BUILTIN_KINDS
             )
         })?
     };
@@ -523,45 +997,8 @@ pub async fn create_identity_provider(
     config: &IdentityProviderConfig,
 ) -> Result<Arc<dyn IdentityProvider>> {
     if config.kind == BUILTIN_PASSWORD_KIND {
-        // Deserialize the inner config into a typed DTO so that `username` and
-        // `password` participate in the `ConfigValue` envelope system. This
-        // allows them to be supplied as plain strings, `${ENV_VAR}` POSIX
-        // references, or structured `{kind: Secret, name: ...}` /
-        // `{kind: EnvironmentVariable, ...}` objects — same as every other
-        // plugin-provided config field. Without this, secrets and env vars
-        // would be read as their literal text.
-        #[derive(serde::Deserialize)]
-        struct PasswordIdpDto {
-            username: ConfigValue<String>,
-            password: ConfigValue<String>,
-        }
-
-        let dto: PasswordIdpDto =
-            serde_json::from_value(config.config.clone()).with_context(|| {
-                format!(
-                    "identity provider '{}': invalid 'password' configuration \
-                     (expected 'username' and 'password' fields)",
-                    config.id
-                )
-            })?;
-
-        let mapper = DtoMapper::new();
-        let username = mapper.resolve_string(&dto.username).with_context(|| {
-            format!(
-                "identity provider '{}': failed to resolve 'username'",
-                config.id
-            )
-        })?;
-        let password = mapper.resolve_string(&dto.password).with_context(|| {
-            format!(
-                "identity provider '{}': failed to resolve 'password'",
-                config.id
-            )
-        })?;
-
-        return Ok(Arc::new(PasswordIdentityProvider::new(
-            &username, &password,
-        )));
+        let (provider, _) = password_identity_provider(config, &DtoMapper::new())?;
+        return Ok(provider);
     }
 
     let descriptor = registry
@@ -588,14 +1025,89 @@ pub async fn create_identity_provider(
     Ok(Arc::from(provider))
 }
 
+/// Build the built-in `password` identity provider, resolving its
+/// `username` and `password` with `mapper`. Also returns the digest of each
+/// secret it resolved.
+fn password_identity_provider(
+    config: &IdentityProviderConfig,
+    mapper: &DtoMapper,
+) -> Result<(Arc<dyn IdentityProvider>, SecretDigests)> {
+    // Deserialize the inner config into a typed DTO so that `username` and
+    // `password` participate in the `ConfigValue` envelope system. This
+    // allows them to be supplied as plain strings, `${ENV_VAR}` POSIX
+    // references, or structured `{kind: Secret, name: ...}` /
+    // `{kind: EnvironmentVariable, ...}` objects — same as every other
+    // plugin-provided config field. Without this, secrets and env vars
+    // would be read as their literal text.
+    #[derive(serde::Deserialize)]
+    struct PasswordIdpDto {
+        username: ConfigValue<String>,
+        password: ConfigValue<String>,
+    }
+
+    let dto: PasswordIdpDto = serde_json::from_value(config.config.clone()).with_context(|| {
+        format!(
+            "identity provider '{}': invalid 'password' configuration \
+                 (expected 'username' and 'password' fields)",
+            config.id
+        )
+    })?;
+
+    let username = mapper.resolve_string(&dto.username).with_context(|| {
+        format!(
+            "identity provider '{}': failed to resolve 'username'",
+            config.id
+        )
+    })?;
+    let password = mapper.resolve_string(&dto.password).with_context(|| {
+        format!(
+            "identity provider '{}': failed to resolve 'password'",
+            config.id
+        )
+    })?;
+
+    let mut secrets = SecretDigests::new();
+    for (field, value) in [(&dto.username, &username), (&dto.password, &password)] {
+        if let ConfigValue::Secret { name } = field {
+            secrets.insert(name.clone(), secret_digest(value));
+        }
+    }
+
+    Ok((
+        Arc::new(PasswordIdentityProvider::new(&username, &password)),
+        secrets,
+    ))
+}
+
 /// Acquire the registry read lock and create a single identity provider.
+/// The secrets it resolved are recorded in `consumers`, so the provider is
+/// rebuilt when they rotate.
 pub async fn create_identity_provider_locked(
     registry: &tokio::sync::RwLock<PluginRegistry>,
+    instance_id: &str,
+    consumers: &SecretConsumers,
     config: &IdentityProviderConfig,
 ) -> Result<Arc<dyn IdentityProvider>> {
+    let (provider, secrets) =
+        create_identity_provider_unrecorded(registry, instance_id, config).await?;
+    consumers.record(
+        instance_id,
+        SecretConsumer::IdentityProvider(config.clone()),
+        secrets,
+    );
+    Ok(provider)
+}
+
+/// Like [`create_identity_provider_locked`], but returns the digests of the
+/// secrets the provider resolved instead of recording them.
+pub(crate) async fn create_identity_provider_unrecorded(
+    registry: &tokio::sync::RwLock<PluginRegistry>,
+    instance_id: &str,
+    config: &IdentityProviderConfig,
+) -> Result<(Arc<dyn IdentityProvider>, SecretDigests)> {
     if config.kind == BUILTIN_PASSWORD_KIND {
-        let reg = registry.read().await;
-        return create_identity_provider(&reg, config).await;
+        let mapper = ConfigResolverContext::global().instance_mapper(instance_id);
+        return password_identity_provider(config, &mapper);
     }
 
     // For plugin-backed providers, clone the descriptor under the lock and
@@ -613,6 +1125,9 @@ pub async fn create_identity_provider_locked(
             })?
     };
 
+    let scope = ConfigResolverContext::global().scope(instance_id);
+    scope.register(&config.config);
+    let creating = scope.enter();
     let provider = descriptor
         .create_identity_provider(&config.config)
         .await
@@ -622,8 +1137,41 @@ pub async fn create_identity_provider_locked(
                 config.id, config.kind,
             )
         })?;
+    drop(creating);
+
+    let secrets = scope.consumed();
+    Ok((
+        Arc::new(ScopedIdentityProvider {
+            inner: provider,
+            scope: Arc::new(scope),
+        }),
+        secrets,
+    ))
+}
 
-    Ok(Arc::from(provider))
+/// A plugin-backed identity provider together with the scope that resolves
+/// its secrets, so it can resolve them for as long as it is in use.
+struct ScopedIdentityProvider {
+    inner: Box<dyn IdentityProvider>,
+    scope: Arc<SecretScope<'static>>,
+}
+
+#[async_trait::async_trait]
+impl IdentityProvider for ScopedIdentityProvider {
+    async fn get_credentials(
+        &self,
+        context: &drasi_lib::identity::CredentialContext,
+    ) -> Result<drasi_lib::identity::Credentials> {
+        let _call = self.scope.enter();
+        self.inner.get_credentials(context).await
+    }
+
+    fn clone_box(&self) -> Box<dyn IdentityProvider> {
+        Box::new(Self {
+            inner: self.inner.clone_box(),
+            scope: self.scope.clone(),
+        })
+    }
 }
 
 /// Build a `{id -> provider}` map from a slice of identity-provider configs.
@@ -631,6 +1179,8 @@ pub async fn create_identity_provider_locked(
 /// Fails on duplicate ids or if any plugin-backed kind is not registered.
 pub async fn build_identity_provider_map(
     registry: &tokio::sync::RwLock<PluginRegistry>,
+    instance_id: &str,
+    consumers: &SecretConsumers,
     configs: &[IdentityProviderConfig],
 ) -> Result<HashMap<String, Arc<dyn IdentityProvider>>> {
     let mut map: HashMap<String, Arc<dyn IdentityProvider>> = HashMap::new();
@@ -641,7 +1191,8 @@ pub async fn build_identity_provider_map(
                 cfg.id
             ));
         }
-        let provider = create_identity_provider_locked(registry, cfg).await?;
+        let provider =
+            create_identity_provider_locked(registry, instance_id, consumers, cfg).await?;
         info!(
             "Configured identity provider '{}' (kind '{}')",
             cfg.id, cfg.kind
@@ -672,9 +1223,7 @@ mod tests {
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         let path = temp_dir.path().join("state.redb");
 
-        let config = StateStoreConfig::Redb {
-            path: crate::api::models::ConfigValue::Static(path.to_string_lossy().to_string()),
-        };
+        let config = StateStoreConfig::redb(path.to_string_lossy());
 
         let provider = create_state_store_provider(config).expect("Failed to create REDB provider");
         assert!(std::sync::Arc::strong_count(&provider) >= 1);
@@ -685,9 +1234,7 @@ mod tests {
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         let path = temp_dir.path().join("test_store.redb");
 
-        let config = StateStoreConfig::Redb {
-            path: crate::api::models::ConfigValue::Static(path.to_string_lossy().to_string()),
-        };
+        let config = StateStoreConfig::redb(path.to_string_lossy());
 
         let _provider = create_state_store_provider(config).expect("Failed to create provider");
         assert!(path.exists(), "REDB file should be created");
@@ -770,6 +1317,8 @@ mod tests {
                 "pg-bootstrap".to_string(),
             )),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({}),
         };
 
@@ -802,6 +1351,8 @@ mod tests {
                 "does-not-exist".to_string(),
             )),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({}),
         };
 
@@ -825,6 +1376,8 @@ mod tests {
                 },
             )),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({}),
         };
 
@@ -850,6 +1403,8 @@ mod tests {
             auto_start: true,
             bootstrap_provider: None,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({}),
         };
 
@@ -871,6 +1426,8 @@ mod tests {
             queries: vec![],
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: serde_json::json!({}),
         };
 
@@ -989,4 +1546,111 @@ mod tests {
             "Unexpected error: {msg}"
         );
     }
+
+    // ==========================================================================
+    // Per-instance secret resolution
+    // ==========================================================================
+
+    fn resolve_through_host(name: &str) -> Result<String> {
+        let json = serde_json::json!({ "kind": "Secret", "name": name }).to_string();
+        let context = ConfigResolverContext::global();
+        let result = host_resolve_config_value(context.as_ptr(), FfiStr::from_str(&json));
+        unsafe { result.into_result() }
+    }
+
+    fn secret_ref(name: &str) -> serde_json::Value {
+        serde_json::json!({ "password": { "kind": "Secret", "name": name } })
+    }
+
+    #[tokio::test]
+    async fn test_secrets_resolve_through_the_instance_referencing_them() {
+        use drasi_lib::secret_store::MemorySecretStoreProvider;
+
+        let context = ConfigResolverContext::global();
+        for tenant in ["resolver-tenant-a", "resolver-tenant-b"] {
+            context.set_instance_store(
+                tenant,
+                Arc::new(
+                    MemorySecretStoreProvider::new()
+                        .with_secret("RESOLVER_DB_PASSWORD", tenant)
+                        .with_secret("RESOLVER_API_KEY", tenant),
+                ),
+            );
+        }
+
+        // Both components reference the same name; plugins see it unchanged.
+        let scope_a = context.scope("resolver-tenant-a");
+        let scope_b = context.scope("resolver-tenant-b");
+        scope_a.register(&secret_ref("RESOLVER_DB_PASSWORD"));
+        scope_a.register(&secret_ref("RESOLVER_API_KEY"));
+        scope_b.register(&secret_ref("RESOLVER_DB_PASSWORD"));
+
+        // The component the host is calling into decides.
+        let call = scope_b.enter();
+        assert_eq!(
+            resolve_through_host("RESOLVER_DB_PASSWORD").expect("tenant b's secret"),
+            "resolver-tenant-b"
+        );
+        drop(call);
+        let call = scope_a.enter();
+        assert_eq!(
+            resolve_through_host("RESOLVER_DB_PASSWORD").expect("tenant a's secret"),
+            "resolver-tenant-a"
+        );
+        drop(call);
+        assert_eq!(
+            scope_a.consumed().get("RESOLVER_DB_PASSWORD"),
+            Some(&secret_digest("resolver-tenant-a"))
+        );
+        assert_eq!(scope_b.consumed().len(), 1);
+
+        // Outside of a host call, a name only one instance references still
+        // resolves, but a shared one is not guessed.
+        assert_eq!(
+            resolve_through_host("RESOLVER_API_KEY").expect("only tenant a references it"),
+            "resolver-tenant-a"
+        );
+        let err = resolve_through_host("RESOLVER_DB_PASSWORD").expect_err("ambiguous name");
+        assert!(err.to_string().contains("cannot be attributed"), "{err}");
+        drop(scope_a);
+        drop(scope_b);
+
+        // A name no open scope references, or an instance without a store,
+        // resolves nothing.
+        let err = resolve_through_host("RESOLVER_API_KEY").expect_err("scope has ended");
+        assert!(
+            err.to_string().contains("no component of any instance"),
+            "{err}"
+        );
+        let scope_c = context.scope("resolver-tenant-c");
+        scope_c.register(&secret_ref("RESOLVER_DB_PASSWORD"));
+        let err = resolve_through_host("RESOLVER_DB_PASSWORD")
+            .expect_err("an instance without a store must not borrow another's");
+        assert!(
+            err.to_string()
+                .contains("'resolver-tenant-c' has no secretStore"),
+            "Unexpected error: {err}"
+        );
+        assert!(err.to_string().contains("'RESOLVER_DB_PASSWORD'"), "{err}");
+        drop(scope_c);
+
+        context.remove_instance_store("resolver-tenant-a");
+        context.remove_instance_store("resolver-tenant-b");
+        assert!(context.instance_store("resolver-tenant-a").is_none());
+    }
+
+    #[test]
+    fn test_secret_names_collects_nested_references() {
+        let config = serde_json::json!({
+            "host": "db",
+            "auth": [secret_ref("DB@PASSWORD"), { "kind": "Secret" }],
+            "nested": { "token": { "kind": "Secret", "name": "TOKEN" } },
+        });
+        let mut names = HashSet::new();
+        secret_names(&config, &mut names);
+        assert_eq!(
+            names,
+            HashSet::from(["DB@PASSWORD".to_string(), "TOKEN".to_string()])
+        );
+    }
 }
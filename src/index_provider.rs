@@ -20,10 +20,23 @@
 //! stay in sync. The builder imports the name from here rather than the other
 //! way around, keeping the dependency direction sensible.
 
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
 
-use drasi_index_rocksdb::RocksDbIndexProvider;
+use async_trait::async_trait;
+use drasi_core::interface::{
+    AccumulatorIndex, CheckpointStore, CreatedIndexes, ElementArchiveIndex, ElementIndex,
+    FutureQueue, IndexBackendPlugin, IndexError, IndexSet,
+};
+use drasi_index_rocksdb::element_index::RocksDbElementIndex;
+use drasi_index_rocksdb::future_queue::RocksDbFutureQueue;
+use drasi_index_rocksdb::result_index::RocksDbResultIndex;
+use drasi_index_rocksdb::{
+    open_unified_db, IndexDb, RocksDbCheckpointStore, RocksDbIndexProvider,
+    RocksDbLiveResultsWriter, RocksDbMemoryBudget, RocksDbOutboxWriter, RocksDbSessionControl,
+    RocksDbSessionState, RocksIndexOptions,
+};
 use drasi_lib::DrasiLibBuilder;
 use log::info;
 
@@ -48,17 +61,109 @@ pub(crate) fn instance_index_dir(instance_id: &str) -> PathBuf {
     PathBuf::from(format!("./data/{safe_id}/index"))
 }
 
+/// A query database recorded in [`IndexDatabases`], with the options it was
+/// opened with.
+struct TrackedDb {
+    db: Weak<IndexDb>,
+    options: RocksIndexOptions,
+}
+
+/// RocksDB databases of an instance's persistent queries, keyed by query id.
+///
+/// Recorded by the instance's index provider so the server can inspect and
+/// maintain the indexes. Only weak references are kept, so a deleted
+/// query's database still closes when DrasiLib drops its indexes.
+#[derive(Clone, Default)]
+pub struct IndexDatabases {
+    databases: Arc<Mutex<BTreeMap<String, TrackedDb>>>,
+}
+
+impl IndexDatabases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the database opened for `query_id` with `options`, replacing
+    /// any earlier one.
+    pub fn track(&self, query_id: &str, db: &Arc<IndexDb>, options: &RocksIndexOptions) {
+        self.lock().insert(
+            query_id.to_string(),
+            TrackedDb {
+                db: Arc::downgrade(db),
+                options: options.clone(),
+            },
+        );
+    }
+
+    /// Ids of the tracked queries whose database is still open.
+    pub fn query_ids(&self) -> Vec<String> {
+        let mut databases = self.lock();
+        databases.retain(|_, tracked| tracked.db.strong_count() > 0);
+        databases.keys().cloned().collect()
+    }
+
+    /// The open database of `query_id`.
+    pub fn get(&self, query_id: &str) -> Option<Arc<IndexDb>> {
+        self.lock()
+            .get(query_id)
+            .and_then(|tracked| tracked.db.upgrade())
+    }
+
+    /// Clear the index, checkpoints and config hash of the stopped query
+    /// `query_id`, so it bootstraps again as on its first run when next
+    /// started.
+    ///
+    /// An open database is cleared through the drasi-index-rocksdb stores.
+    /// One this process has not opened, or has closed since, is deleted from
+    /// `index_dir` instead. Either way the change is on disk and survives a
+    /// server restart.
+    pub async fn clear_query(&self, index_dir: &Path, query_id: &str) -> Result<(), IndexError> {
+        let open = self
+            .lock()
+            .get(query_id)
+            .and_then(|tracked| tracked.db.upgrade().map(|db| (db, tracked.options.clone())));
+        let Some((db, options)) = open else {
+            let path = index_dir.join(query_id);
+            if path.exists() {
+                std::fs::remove_dir_all(&path).map_err(IndexError::other)?;
+            }
+            return Ok(());
+        };
+
+        let session_state = Arc::new(RocksDbSessionState::new(db.clone()));
+        RocksDbCheckpointStore::new(db.clone(), session_state.clone())
+            .clear_checkpoints()
+            .await?;
+        let element_index =
+            RocksDbElementIndex::new(db.clone(), options.clone(), session_state.clone());
+        ElementIndex::clear(&element_index).await?;
+        ElementArchiveIndex::clear(&element_index).await?;
+        RocksDbResultIndex::new(db.clone(), session_state.clone(), options.clone())
+            .clear()
+            .await?;
+        RocksDbFutureQueue::new(db, session_state, options)
+            .clear()
+            .await
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, TrackedDb>> {
+        self.databases.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
 /// Register the persistent RocksDB index provider as the instance default on
 /// `builder`.
 ///
 /// Centralizes the id sanitization, path construction, and provider wiring used
 /// by both server startup and the create-instance API handler. Every query in
 /// the instance without an explicit `storageBackend` is persisted to
-/// `./data/<instanceId>/index` (see [`instance_index_dir`]).
+/// `./data/<instanceId>/index` (see [`instance_index_dir`]), and its database
+/// is recorded in `databases`.
 pub(crate) fn apply_rocksdb_index(
     builder: DrasiLibBuilder,
     instance_id: &str,
     enable_archive: bool,
+    databases: IndexDatabases,
 ) -> DrasiLibBuilder {
     let index_path = instance_index_dir(instance_id);
     info!(
@@ -66,6 +171,152 @@ pub(crate) fn apply_rocksdb_index(
         index_path.display()
     );
     let direct_io = false; // use OS page cache
-    let provider = RocksDbIndexProvider::new(index_path, enable_archive, direct_io);
+    let provider = TrackedRocksDbIndexProvider {
+        upstream: RocksDbIndexProvider::new(index_path, enable_archive, direct_io)
+            .with_memory_budget(RocksDbMemoryBudget::default()),
+        databases,
+    };
     builder.with_default_index_provider(PERSISTENT_INDEX_PROVIDER_NAME, Arc::new(provider))
 }
+
+/// [`RocksDbIndexProvider`] that also records each query's database in
+/// [`IndexDatabases`].
+///
+/// The path and options are those of the wrapped provider. Its
+/// `create_indexes` keeps the database to itself, so the indexes are
+/// assembled here from the same drasi-index-rocksdb constructors;
+/// `test_indexes_match_upstream_provider` fails if the two drift apart.
+struct TrackedRocksDbIndexProvider {
+    upstream: RocksDbIndexProvider,
+    databases: IndexDatabases,
+}
+
+#[async_trait]
+impl IndexBackendPlugin for TrackedRocksDbIndexProvider {
+    async fn create_indexes(&self, query_id: &str) -> Result<CreatedIndexes, IndexError> {
+        let path = self.upstream.path().to_string_lossy().to_string();
+        let options = RocksIndexOptions::new(
+            self.upstream.is_archive_enabled(),
+            self.upstream.is_direct_io_enabled(),
+            self.upstream.memory_budget().clone(),
+        );
+        let db = open_unified_db(&path, query_id, &options)?;
+        self.databases.track(query_id, &db, &options);
+
+        let session_state = Arc::new(RocksDbSessionState::new(db.clone()));
+        let session_control = Arc::new(RocksDbSessionControl::new(session_state.clone()));
+        let element_index = Arc::new(RocksDbElementIndex::new(
+            db.clone(),
+            options.clone(),
+            session_state.clone(),
+        ));
+        let result_index = Arc::new(RocksDbResultIndex::new(
+            db.clone(),
+            session_state.clone(),
+            options.clone(),
+        ));
+        let future_queue = Arc::new(RocksDbFutureQueue::new(
+            db.clone(),
+            session_state.clone(),
+            options,
+        ));
+        let checkpoint_store = Arc::new(RocksDbCheckpointStore::new(db.clone(), session_state));
+        let outbox_writer = Arc::new(RocksDbOutboxWriter::new(db.clone()));
+        let live_results_writer = Arc::new(RocksDbLiveResultsWriter::new(db));
+
+        Ok(CreatedIndexes {
+            set: IndexSet {
+                element_index: element_index.clone(),
+                archive_index: element_index,
+                result_index,
+                future_queue,
+                session_control,
+            },
+            checkpoint_store: Some(checkpoint_store),
+            outbox_writer: Some(outbox_writer),
+            live_results_writer: Some(live_results_writer),
+        })
+    }
+
+    fn is_volatile(&self) -> bool {
+        self.upstream.is_volatile()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn provider(dir: &tempfile::TempDir, databases: IndexDatabases) -> TrackedRocksDbIndexProvider {
+        TrackedRocksDbIndexProvider {
+            upstream: RocksDbIndexProvider::new(dir.path(), false, false),
+            databases,
+        }
+    }
+
+    fn column_families(path: &Path) -> Vec<String> {
+        let mut names = IndexDb::list_cf(&rocksdb::Options::default(), path).unwrap();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_indexes_match_upstream_provider() {
+        for enable_archive in [false, true] {
+            let upstream_dir = tempfile::TempDir::new().unwrap();
+            let tracked_dir = tempfile::TempDir::new().unwrap();
+            let upstream = RocksDbIndexProvider::new(upstream_dir.path(), enable_archive, false);
+            let tracked = TrackedRocksDbIndexProvider {
+                upstream: RocksDbIndexProvider::new(tracked_dir.path(), enable_archive, false),
+                databases: IndexDatabases::new(),
+            };
+            assert_eq!(tracked.is_volatile(), upstream.is_volatile());
+
+            let expected = upstream.create_indexes("q1").await.unwrap();
+            let actual = tracked.create_indexes("q1").await.unwrap();
+            assert_eq!(
+                actual.checkpoint_store.is_some(),
+                expected.checkpoint_store.is_some()
+            );
+            assert_eq!(
+                actual.outbox_writer.is_some(),
+                expected.outbox_writer.is_some()
+            );
+            assert_eq!(
+                actual.live_results_writer.is_some(),
+                expected.live_results_writer.is_some()
+            );
+            drop((expected, actual));
+
+            assert_eq!(
+                column_families(&tracked_dir.path().join("q1")),
+                column_families(&upstream_dir.path().join("q1")),
+                "archive: {enable_archive}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_clear_query() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let databases = IndexDatabases::new();
+        let provider = provider(&dir, databases.clone());
+
+        let created = provider.create_indexes("q1").await.unwrap();
+        let checkpoints = created.checkpoint_store.clone().unwrap();
+        created.set.session_control.begin().await.unwrap();
+        checkpoints.stage_checkpoint("s1", 3, None).await.unwrap();
+        created.set.session_control.commit().await.unwrap();
+        checkpoints.write_config_hash(7).await.unwrap();
+
+        databases.clear_query(dir.path(), "q1").await.unwrap();
+        assert!(checkpoints.read_all_checkpoints().await.unwrap().is_empty());
+        assert_eq!(checkpoints.read_config_hash().await.unwrap(), None);
+
+        drop((created, checkpoints));
+        assert!(dir.path().join("q1").exists());
+        databases.clear_query(dir.path(), "q1").await.unwrap();
+        assert!(!dir.path().join("q1").exists());
+    }
+}
@@ -20,6 +20,7 @@ use drasi_lib::config::QueryLanguage;
 use drasi_server::api::models::{
     ConfigValue, QueryConfigDto, ReactionConfig, SourceConfig, SourceSubscriptionConfigDto,
 };
+use drasi_server::config::ServerMode;
 use drasi_server::DrasiServerConfig;
 
 use super::prompts::ServerSettings;
@@ -67,12 +68,15 @@ pub fn build_config(
 
     DrasiServerConfig {
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         api_version: None,
         id: ConfigValue::Static(server_id),
         host: ConfigValue::Static(server_settings.host),
         port: ConfigValue::Static(server_settings.port),
         log_level: ConfigValue::Static(server_settings.log_level),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: server_settings.persist_index,
         enable_ui: true,     // Enable web UI by default
         solutions_dir: None, // Use default
@@ -85,6 +89,8 @@ pub fn build_config(
         plugins: Vec::new(),
         verify_plugins: server_settings.verify_plugins,
         trusted_identities: Vec::new(),
+        trusted_keys: Vec::new(),
+        plugin_circuit_breaker: None,
         hot_reload_plugins: server_settings.hot_reload_plugins,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -149,6 +155,8 @@ mod tests {
             auto_start: true,
             bootstrap_provider: None,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: json!({"dataType": {"type": "generic"}, "intervalMs": 5000}),
         }
     }
@@ -161,6 +169,8 @@ mod tests {
             auto_start: true,
             bootstrap_provider: None,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: json!({"host": "0.0.0.0", "port": 9000, "timeoutMs": 10000}),
         }
     }
@@ -173,6 +183,8 @@ mod tests {
             queries: vec!["my-query".to_string()],
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: json!({"routes": {}}),
         }
     }
@@ -185,6 +197,8 @@ mod tests {
             queries: vec!["my-query".to_string()],
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: json!({"host": "0.0.0.0", "port": 8081, "ssePath": "/events", "heartbeatIntervalMs": 30000, "routes": {}}),
         }
     }
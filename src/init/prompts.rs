@@ -145,6 +145,7 @@ impl std::fmt::Display for BootstrapType {
 pub enum StateStoreType {
     None,
     Redb,
+    Sqlite,
 }
 
 impl std::fmt::Display for StateStoreType {
@@ -152,6 +153,10 @@ impl std::fmt::Display for StateStoreType {
         match self {
             StateStoreType::None => write!(f, "None - In-memory state (lost on restart)"),
             StateStoreType::Redb => write!(f, "REDB - Persistent file-based state"),
+            StateStoreType::Sqlite => write!(
+                f,
+                "SQLite - Persistent state, readable by other tools while running"
+            ),
         }
     }
 }
@@ -220,7 +225,11 @@ pub fn prompt_server_settings() -> Result<ServerSettings> {
 
 /// Prompt for state store configuration.
 fn prompt_state_store() -> Result<Option<StateStoreConfig>> {
-    let state_store_types = vec![StateStoreType::None, StateStoreType::Redb];
+    let state_store_types = vec![
+        StateStoreType::None,
+        StateStoreType::Redb,
+        StateStoreType::Sqlite,
+    ];
 
     hint("Allows plugins to persist runtime state that survives restarts");
     let selected = Select::new(
@@ -239,6 +248,14 @@ fn prompt_state_store() -> Result<Option<StateStoreConfig>> {
 
             Ok(Some(StateStoreConfig::redb(path)))
         }
+        StateStoreType::Sqlite => {
+            hint("Path to SQLite database file for state persistence");
+            let path = Text::new("State store file path:")
+                .with_default("./data/state.db")
+                .prompt()?;
+
+            Ok(Some(StateStoreConfig::sqlite(path)))
+        }
     }
 }
 
@@ -298,6 +315,8 @@ fn prompt_postgres_source() -> Result<SourceConfig> {
         auto_start: true,
         bootstrap_provider: bootstrap_provider.map(BootstrapProviderRef::Inline),
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({
             "host": host,
             "port": port,
@@ -437,6 +456,8 @@ fn prompt_http_source() -> Result<SourceConfig> {
         auto_start: true,
         bootstrap_provider: bootstrap_provider.map(BootstrapProviderRef::Inline),
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({
             "host": host,
             "port": port,
@@ -469,6 +490,8 @@ fn prompt_grpc_source() -> Result<SourceConfig> {
         auto_start: true,
         bootstrap_provider: bootstrap_provider.map(BootstrapProviderRef::Inline),
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({
             "host": host,
             "port": port,
@@ -515,6 +538,8 @@ fn prompt_mock_source() -> Result<SourceConfig> {
         auto_start: true,
         bootstrap_provider: None,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({
             "intervalMs": interval_ms,
             "dataType": data_type
@@ -570,6 +595,8 @@ fn prompt_log_reaction() -> Result<ReactionConfig> {
         queries: vec!["my-query".to_string()],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({
             "routes": {}
         }),
@@ -596,6 +623,8 @@ fn prompt_http_reaction() -> Result<ReactionConfig> {
         queries: vec!["my-query".to_string()],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({
             "baseUrl": base_url,
             "timeoutMs": 5000,
@@ -629,6 +658,8 @@ fn prompt_sse_reaction() -> Result<ReactionConfig> {
         queries: vec!["my-query".to_string()],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({
             "host": host,
             "port": port,
@@ -659,6 +690,8 @@ fn prompt_grpc_reaction() -> Result<ReactionConfig> {
         queries: vec!["my-query".to_string()],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({
             "endpoint": endpoint,
             "timeoutMs": 5000,
@@ -843,6 +876,8 @@ fn prompt_generic_source(kind: &str) -> Result<SourceConfig> {
         id,
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: bootstrap_provider.map(BootstrapProviderRef::Inline),
         config,
     })
@@ -943,6 +978,8 @@ fn prompt_generic_reaction(kind: &str) -> Result<ReactionConfig> {
         queries: vec!["my-query".to_string()],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config,
     })
 }
@@ -1097,7 +1134,7 @@ mod tests {
         };
 
         assert!(settings.state_store.is_some());
-        assert_eq!(settings.state_store.as_ref().unwrap().kind(), "redb");
+        assert_eq!(settings.state_store.as_ref().unwrap().kind, "redb");
     }
 
     #[test]
@@ -12,6 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Where each instance keeps its data on disk.
+
+use std::path::{Path, PathBuf};
+
+/// Directory, relative to the working directory, under which every
+/// instance's index and WAL are stored.
+pub const DATA_ROOT: &str = "./data";
+
 /// Converts an arbitrary instance ID into a filesystem-safe storage key.
 ///
 /// Each byte of the ID is hex-encoded as two lowercase digits and prefixed with
@@ -21,7 +29,7 @@
 /// `a_b` map to distinct keys), and always yields a valid single-segment
 /// directory name on every platform. Used to derive the per-instance index and
 /// WAL directories under `./data/`.
-pub(crate) fn instance_storage_key(instance_id: &str) -> String {
+pub fn instance_storage_key(instance_id: &str) -> String {
     let mut key = String::with_capacity(3 + instance_id.len() * 2);
     key.push_str("id-");
 
@@ -35,6 +43,11 @@ pub(crate) fn instance_storage_key(instance_id: &str) -> String {
     key
 }
 
+/// The directory under `data_root` that holds an instance's index and WAL.
+pub fn instance_data_dir(data_root: &Path, instance_id: &str) -> PathBuf {
+    data_root.join(instance_storage_key(instance_id))
+}
+
 #[cfg(test)]
 mod tests {
     use super::instance_storage_key;
@@ -22,9 +22,16 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use drasi_lib::state_store::StateStoreProvider;
 use drasi_lib::DrasiLib;
 
-use crate::api::models::BootstrapProviderConfig;
+use crate::api::models::{BootstrapProviderConfig, RestartPolicy};
+use crate::archive_retention::ArchiveRetention;
+use crate::component_revisions::ComponentRevisions;
+use crate::plugin_isolation::PluginCircuitBreakers;
+use crate::restart_supervisor::{RestartPolicies, RestartSupervisor};
+use crate::secret_rotation::{SecretConsumers, SecretRotationWatcher};
+use crate::storage_usage::InstanceStorage;
 
 /// Thread-safe registry for managing DrasiLib instances.
 ///
@@ -39,6 +46,31 @@ pub struct InstanceRegistry {
     /// a top-level bootstrap provider (`bootstrapProvider: <id>`) can be
     /// resolved and wired live.
     bootstrap_providers: Arc<RwLock<IndexMap<String, HashMap<String, BootstrapProviderConfig>>>>,
+    /// Per-instance restart supervisors enforcing source/reaction
+    /// `restartPolicy`. Created at startup for configured instances and
+    /// lazily (on first policy registration) for dynamic ones.
+    restart_supervisors: Arc<RwLock<IndexMap<String, Arc<RestartSupervisor>>>>,
+    /// Per-instance watchers that reconfigure components whose secrets were
+    /// rotated. Only instances whose `secretStore` sets
+    /// `refreshIntervalSecs` have one.
+    secret_rotation_watchers: Arc<RwLock<IndexMap<String, Arc<SecretRotationWatcher>>>>,
+    /// Per-instance state store providers, kept so the REST API can inspect
+    /// and repair what components persisted. Instances the server did not
+    /// build (e.g. via `DrasiServer::from_core`) have none.
+    state_stores: Arc<RwLock<IndexMap<String, Arc<dyn StateStoreProvider>>>>,
+    /// Per-instance archive retention tasks. Only instances whose
+    /// `archiveRetention` applies to a persistent archive have one.
+    archive_retention: Arc<RwLock<IndexMap<String, Arc<ArchiveRetention>>>>,
+    /// Where each instance keeps its index, WAL and state store, for storage
+    /// usage reporting. Instances the server did not build have none.
+    storage: Arc<RwLock<IndexMap<String, Arc<InstanceStorage>>>>,
+    /// Revision numbers of instances and their components, exposed as
+    /// `ETag`s by the REST API.
+    revisions: ComponentRevisions,
+    /// Which secrets each instance's components consumed, for rotation.
+    secret_consumers: SecretConsumers,
+    /// Circuit breakers of the plugins the components are created from.
+    plugin_breakers: PluginCircuitBreakers,
 }
 
 impl InstanceRegistry {
@@ -47,17 +79,56 @@ impl InstanceRegistry {
         Self {
             instances: Arc::new(RwLock::new(IndexMap::new())),
             bootstrap_providers: Arc::new(RwLock::new(IndexMap::new())),
+            restart_supervisors: Arc::new(RwLock::new(IndexMap::new())),
+            secret_rotation_watchers: Arc::new(RwLock::new(IndexMap::new())),
+            state_stores: Arc::new(RwLock::new(IndexMap::new())),
+            archive_retention: Arc::new(RwLock::new(IndexMap::new())),
+            storage: Arc::new(RwLock::new(IndexMap::new())),
+            revisions: ComponentRevisions::new(),
+            secret_consumers: SecretConsumers::new(),
+            plugin_breakers: PluginCircuitBreakers::default(),
         }
     }
 
     /// Create a registry from an existing instance map.
     pub fn from_map(instances: IndexMap<String, Arc<DrasiLib>>) -> Self {
+        Self::from_map_with_revisions(instances, ComponentRevisions::new())
+    }
+
+    /// Create a registry from an existing instance map, sharing `revisions`
+    /// with tasks that were started before the registry existed.
+    pub fn from_map_with_revisions(
+        instances: IndexMap<String, Arc<DrasiLib>>,
+        revisions: ComponentRevisions,
+    ) -> Self {
         Self {
             instances: Arc::new(RwLock::new(instances)),
             bootstrap_providers: Arc::new(RwLock::new(IndexMap::new())),
+            restart_supervisors: Arc::new(RwLock::new(IndexMap::new())),
+            secret_rotation_watchers: Arc::new(RwLock::new(IndexMap::new())),
+            state_stores: Arc::new(RwLock::new(IndexMap::new())),
+            archive_retention: Arc::new(RwLock::new(IndexMap::new())),
+            storage: Arc::new(RwLock::new(IndexMap::new())),
+            revisions,
+            secret_consumers: SecretConsumers::new(),
+            plugin_breakers: PluginCircuitBreakers::default(),
         }
     }
 
+    /// Share `secret_consumers` with the components created before the
+    /// registry existed.
+    pub fn with_secret_consumers(mut self, secret_consumers: SecretConsumers) -> Self {
+        self.secret_consumers = secret_consumers;
+        self
+    }
+
+    /// Share `plugin_breakers` with the components created before the
+    /// registry existed.
+    pub fn with_plugin_breakers(mut self, plugin_breakers: PluginCircuitBreakers) -> Self {
+        self.plugin_breakers = plugin_breakers;
+        self
+    }
+
     /// Get an instance by ID.
     pub async fn get(&self, id: &str) -> Option<Arc<DrasiLib>> {
         let instances = self.instances.read().await;
@@ -107,6 +178,13 @@ impl InstanceRegistry {
     ///
     /// Returns the removed instance if it existed.
     pub async fn remove(&self, id: &str) -> Option<Arc<DrasiLib>> {
+        self.restart_supervisors.write().await.shift_remove(id);
+        self.secret_rotation_watchers.write().await.shift_remove(id);
+        self.state_stores.write().await.shift_remove(id);
+        self.archive_retention.write().await.shift_remove(id);
+        self.storage.write().await.shift_remove(id);
+        self.secret_consumers.forget_instance(id);
+        self.revisions.remove_instance(id);
         let mut instances = self.instances.write().await;
         instances.shift_remove(id)
     }
@@ -123,6 +201,22 @@ impl InstanceRegistry {
         instances.is_empty()
     }
 
+    /// Revision numbers of instances and their components.
+    pub fn revisions(&self) -> &ComponentRevisions {
+        &self.revisions
+    }
+
+    /// Which secrets each instance's components consumed. Pass it to the
+    /// component factories so rotation can find the consumers.
+    pub fn secret_consumers(&self) -> &SecretConsumers {
+        &self.secret_consumers
+    }
+
+    /// The circuit breakers plugin-backed components are created with.
+    pub fn plugin_breakers(&self) -> &PluginCircuitBreakers {
+        &self.plugin_breakers
+    }
+
     /// Record the top-level bootstrap provider configs for an instance.
     ///
     /// Keyed by bootstrap provider `id`. Overwrites any existing entry for
@@ -147,6 +241,146 @@ impl InstanceRegistry {
         let map = self.bootstrap_providers.read().await;
         map.get(instance_id).cloned().unwrap_or_default()
     }
+
+    /// Record the restart supervisor started for an instance.
+    pub async fn set_restart_supervisor(
+        &self,
+        instance_id: String,
+        supervisor: Arc<RestartSupervisor>,
+    ) {
+        let mut map = self.restart_supervisors.write().await;
+        map.insert(instance_id, supervisor);
+    }
+
+    /// Record the secret rotation watcher started for an instance.
+    pub async fn set_secret_rotation_watcher(
+        &self,
+        instance_id: String,
+        watcher: Arc<SecretRotationWatcher>,
+    ) {
+        let mut map = self.secret_rotation_watchers.write().await;
+        map.insert(instance_id, watcher);
+    }
+
+    /// Record the state store provider an instance was built with.
+    pub async fn set_state_store(&self, instance_id: String, store: Arc<dyn StateStoreProvider>) {
+        let mut map = self.state_stores.write().await;
+        map.insert(instance_id, store);
+    }
+
+    /// Get the state store provider of an instance, if the server built it.
+    pub async fn state_store(&self, instance_id: &str) -> Option<Arc<dyn StateStoreProvider>> {
+        self.state_stores.read().await.get(instance_id).cloned()
+    }
+
+    /// Record the archive retention task of an instance.
+    pub async fn set_archive_retention(
+        &self,
+        instance_id: String,
+        retention: Arc<ArchiveRetention>,
+    ) {
+        let mut map = self.archive_retention.write().await;
+        map.insert(instance_id, retention);
+    }
+
+    /// Get the archive retention task of an instance, if it has one.
+    pub async fn archive_retention(&self, instance_id: &str) -> Option<Arc<ArchiveRetention>> {
+        self.archive_retention
+            .read()
+            .await
+            .get(instance_id)
+            .cloned()
+    }
+
+    /// Record where an instance keeps its durable data.
+    pub async fn set_storage(&self, instance_id: String, storage: Arc<InstanceStorage>) {
+        let mut map = self.storage.write().await;
+        map.insert(instance_id, storage);
+    }
+
+    /// Get where an instance keeps its durable data, if the server built it.
+    pub async fn storage(&self, instance_id: &str) -> Option<Arc<InstanceStorage>> {
+        self.storage.read().await.get(instance_id).cloned()
+    }
+
+    /// Get the restart supervisor for an instance, starting one with no
+    /// policies if the instance exists but is not yet supervised.
+    pub async fn restart_supervisor(&self, instance_id: &str) -> Option<Arc<RestartSupervisor>> {
+        if let Some(supervisor) = self.restart_supervisors.read().await.get(instance_id) {
+            return Some(supervisor.clone());
+        }
+        let core = self.get(instance_id).await?;
+        let mut map = self.restart_supervisors.write().await;
+        let supervisor = map
+            .entry(instance_id.to_string())
+            .or_insert_with(|| {
+                Arc::new(RestartSupervisor::spawn(
+                    &core,
+                    instance_id,
+                    self.revisions.clone(),
+                    RestartPolicies::default(),
+                ))
+            })
+            .clone();
+        Some(supervisor)
+    }
+
+    /// Set or clear (`None`) a source's restart policy. A supervisor is only
+    /// started for the instance when there is a policy to enforce.
+    pub async fn set_source_restart_policy(
+        &self,
+        instance_id: &str,
+        source_id: &str,
+        policy: Option<RestartPolicy>,
+    ) {
+        if let Some(supervisor) = self.supervisor_for(instance_id, policy.is_some()).await {
+            supervisor.set_source_policy(source_id, policy).await;
+        }
+    }
+
+    /// Set or clear (`None`) a reaction's restart policy. See
+    /// [`Self::set_source_restart_policy`].
+    pub async fn set_reaction_restart_policy(
+        &self,
+        instance_id: &str,
+        reaction_id: &str,
+        policy: Option<RestartPolicy>,
+    ) {
+        if let Some(supervisor) = self.supervisor_for(instance_id, policy.is_some()).await {
+            supervisor.set_reaction_policy(reaction_id, policy).await;
+        }
+    }
+
+    async fn supervisor_for(
+        &self,
+        instance_id: &str,
+        create: bool,
+    ) -> Option<Arc<RestartSupervisor>> {
+        if create {
+            self.restart_supervisor(instance_id).await
+        } else {
+            self.restart_supervisors
+                .read()
+                .await
+                .get(instance_id)
+                .cloned()
+        }
+    }
+
+    /// Restart policies currently enforced for an instance. Empty when the
+    /// instance has no supervisor.
+    pub async fn restart_policies(&self, instance_id: &str) -> RestartPolicies {
+        let supervisor = self
+            .restart_supervisors
+            .read()
+            .await
+            .get(instance_id)
+            .cloned();
+        match supervisor {
+            Some(supervisor) => supervisor.policies().await,
+            None => RestartPolicies::default(),
+        }
+    }
 }
 
 impl Default for InstanceRegistry {
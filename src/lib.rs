@@ -13,32 +13,47 @@
 // limitations under the License.
 
 pub mod api;
+pub mod archive_retention;
+pub mod backup;
 pub mod builder;
 pub mod builder_result;
+pub mod component_revisions;
 pub mod config;
+pub mod config_revisions;
 pub mod dynamic_loading;
 pub mod factories;
 pub mod index_provider;
-mod instance_paths;
+pub mod instance_paths;
 pub mod instance_registry;
 pub mod persistence;
+pub mod plugin_compatibility;
 pub mod plugin_install;
+pub mod plugin_isolation;
+pub mod plugin_key_verification;
 pub mod plugin_lockfile;
 pub mod plugin_operations;
 pub mod plugin_orchestrator;
 pub mod plugin_registry;
+pub mod restart_supervisor;
+pub mod secret_rotation;
+pub mod secret_stores;
 pub mod server;
+pub mod source_wal;
+pub mod state_stores;
+pub mod storage_usage;
 pub mod ui_assets;
 
 // Main exports for library users
 pub use builder::DrasiServerBuilder;
 pub use builder_result::DrasiServerWithHandles;
 pub use config::{
-    default_plugin_registry, load_config_file, save_config_file, ConfigError,
+    default_plugin_registry, load_config_file, load_config_layers, save_config_file, ConfigError,
     DrasiLibInstanceConfig, DrasiServerConfig, PluginDependency, ReactionConfig,
     ResolvedInstanceConfig, SourceConfig, StateStoreConfig,
 };
-pub use factories::{create_reaction, create_source, create_state_store_provider};
+pub use factories::{
+    create_reaction, create_source, create_state_store_from_registry, create_state_store_provider,
+};
 pub use index_provider::PERSISTENT_INDEX_PROVIDER_NAME;
 pub use plugin_registry::PluginRegistry;
 pub use server::register_core_plugins;
@@ -15,7 +15,7 @@
 // Allow println! in main.rs for CLI user-facing output (validate, doctor, init commands)
 #![allow(clippy::print_stdout)]
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use log::{debug, info, warn};
 use std::fs;
@@ -25,9 +25,13 @@ use std::process::Command;
 use drasi_lib::get_or_init_global_registry;
 use drasi_server::api::mappings::{map_server_settings, DtoMapper};
 use drasi_server::api::models::ConfigValue;
-use drasi_server::{load_config_file, save_config_file, DrasiServer, DrasiServerConfig};
+use drasi_server::api::shared::config_export::secret_value_paths;
+use drasi_server::config::{ConfigOverrides, LoadOptions, ServerMode};
+use drasi_server::secret_stores::settings_mapper;
+use drasi_server::{load_config_layers, save_config_file, DrasiServer, DrasiServerConfig};
 
 mod cli_styles;
+mod client;
 mod init;
 mod plugin;
 
@@ -50,6 +54,21 @@ struct Cli {
     #[arg(short, long, default_value = "config/server.yaml", global = true)]
     config: PathBuf,
 
+    /// Environment overlay to layer on the config file, e.g. `prod` for
+    /// `server.prod.yaml` next to `server.yaml` (defaults to $DRASI_ENV)
+    #[arg(long, global = true)]
+    env: Option<String>,
+
+    /// Override a config key, e.g. `--set sources.my-pg.host=db.internal`
+    /// (repeatable; applied after DRASI__* environment variables)
+    #[arg(long = "set", global = true, value_name = "PATH=VALUE")]
+    set: Vec<String>,
+
+    /// Which changes the REST API accepts: readWrite, locked or readOnly
+    /// (overrides `mode` in the config file)
+    #[arg(long, global = true, value_name = "MODE")]
+    mode: Option<ServerMode>,
+
     /// Override the server port
     #[arg(short, long, global = true)]
     port: Option<u16>,
@@ -98,7 +117,7 @@ enum Commands {
         #[arg(short, long, default_value = "config/server.yaml")]
         config: PathBuf,
 
-        /// Show resolved configuration with environment variables expanded
+        /// Show resolved settings and where each config value came from
         #[arg(long)]
         show_resolved: bool,
 
@@ -107,6 +126,17 @@ enum Commands {
         plugins_dir: Option<PathBuf>,
     },
 
+    /// Print the JSON Schema of the config file, including every installed plugin
+    Schema {
+        /// Directory to scan for plugin shared libraries
+        #[arg(long)]
+        plugins_dir: Option<PathBuf>,
+
+        /// Write the schema to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
     /// Check system dependencies and requirements
     Doctor {
         /// Check for optional dependencies (Docker, etc.)
@@ -125,16 +155,42 @@ enum Commands {
         force: bool,
     },
 
+    /// Restore an instance from a backup taken with `drasi-server backup`
+    ///
+    /// Run on the target host while the server is stopped.
+    Restore {
+        /// Backup archive to restore
+        file: PathBuf,
+
+        /// Config file that defines the instance; created from the backup if
+        /// it does not exist
+        #[arg(short, long, default_value = "config/server.yaml")]
+        config: PathBuf,
+
+        /// Replace the instance's existing index and WAL
+        #[arg(long)]
+        force: bool,
+    },
+
     /// Manage plugins from OCI registries
     Plugin {
         #[command(subcommand)]
         action: plugin::PluginAction,
     },
+
+    #[command(flatten)]
+    Client(client::ClientCommand),
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
+    let config_env = cli.env.or_else(|| std::env::var("DRASI_ENV").ok());
+    let mut config_sets = cli.set;
+    // `--mode` is shorthand for `--set mode=...`, applied last
+    if let Some(mode) = cli.mode {
+        config_sets.push(format!("mode={mode}"));
+    }
 
     match cli.command {
         Some(Commands::Run {
@@ -150,7 +206,16 @@ async fn main() -> Result<()> {
             } else {
                 None
             };
-            run_server(config, port, plugins_dir, skip_verification, ui_override).await
+            run_server(
+                config,
+                config_env,
+                config_sets,
+                port,
+                plugins_dir,
+                skip_verification,
+                ui_override,
+            )
+            .await
         }
         Some(Commands::Validate {
             config,
@@ -158,15 +223,42 @@ async fn main() -> Result<()> {
             plugins_dir,
         }) => {
             let effective_plugins_dir = plugins_dir.or(cli.plugins_dir);
-            validate_config(config, show_resolved, effective_plugins_dir)
+            validate_config(
+                config,
+                config_env,
+                config_sets,
+                show_resolved,
+                effective_plugins_dir,
+            )
+            .await
         }
+        Some(Commands::Schema {
+            plugins_dir,
+            output,
+        }) => write_config_schema(plugins_dir.or(cli.plugins_dir), output),
         Some(Commands::Doctor { all }) => run_doctor(all),
         Some(Commands::Init { output, force }) => {
             init::run_init(output, force, cli.plugins_dir).await
         }
+        Some(Commands::Restore {
+            file,
+            config,
+            force,
+        }) => {
+            restore_backup(
+                file,
+                config,
+                config_env,
+                config_sets,
+                cli.plugins_dir,
+                force,
+            )
+            .await
+        }
         Some(Commands::Plugin { action }) => {
             plugin::run_plugin_command(action, cli.config, cli.plugins_dir).await
         }
+        Some(Commands::Client(command)) => client::run_client_command(command).await,
         None => {
             // Default behavior: run the server (backward compatible)
             let ui_override = if cli.enable_ui {
@@ -178,6 +270,8 @@ async fn main() -> Result<()> {
             };
             run_server(
                 cli.config,
+                config_env,
+                config_sets,
                 cli.port,
                 cli.plugins_dir,
                 cli.skip_verification,
@@ -191,6 +285,8 @@ async fn main() -> Result<()> {
 /// Run the Drasi Server
 async fn run_server(
     config_path: PathBuf,
+    config_env: Option<String>,
+    config_sets: Vec<String>,
     port_override: Option<u16>,
     plugins_dir: Option<PathBuf>,
     skip_verification: bool,
@@ -215,6 +311,12 @@ async fn run_server(
         false
     };
 
+    // DRASI__* variables may come from the .env file loaded above
+    let load_options = LoadOptions {
+        env: config_env,
+        overrides: ConfigOverrides::from_env().with_sets(&config_sets)?,
+    };
+
     // Check if config file exists, create default if it doesn't
     let (config, tracing_initialized) = if !config_path.exists() {
         // Initialize tracing first since we don't have a config yet
@@ -256,11 +358,11 @@ async fn run_server(
         (default_config, true)
     } else {
         // Load config first to get log level
-        (load_config_file(&config_path)?, false)
+        (load_config_layers(&config_path, &load_options)?.0, false)
     };
 
     // Resolve server settings for use in main
-    let mapper = DtoMapper::new();
+    let mapper = settings_mapper(&config)?;
     let resolved_settings = map_server_settings(&config, &mapper)?;
 
     // Initialize tracing if not already done
@@ -286,6 +388,12 @@ async fn run_server(
     }
 
     info!("Config file: {}", config_path.display());
+    if let Some(env) = &load_options.env {
+        info!("Config environment: {env}");
+    }
+    for o in load_options.overrides.iter() {
+        info!("Config override: {} ({})", o.path.join("."), o.source);
+    }
 
     let final_port = port_override.unwrap_or(resolved_settings.port);
     let final_enable_ui = ui_override.unwrap_or(resolved_settings.enable_ui);
@@ -313,8 +421,9 @@ async fn run_server(
     };
     info!("Plugins directory: {}", plugins_dir.display());
 
-    let server = DrasiServer::new(
+    let server = DrasiServer::new_with_options(
         config_path,
+        load_options,
         final_port,
         plugins_dir,
         skip_verification,
@@ -327,8 +436,10 @@ async fn run_server(
 }
 
 /// Validate a configuration file
-fn validate_config(
+async fn validate_config(
     config_path: PathBuf,
+    config_env: Option<String>,
+    config_sets: Vec<String>,
     show_resolved: bool,
     plugins_dir: Option<PathBuf>,
 ) -> Result<()> {
@@ -355,20 +466,42 @@ fn validate_config(
     }
 
     // Try to load and parse the config
-    let config = match load_config_file(&config_path) {
-        Ok(c) => c,
-        Err(e) => {
-            println!("Structure:");
-            println!("  [ERR] {e}");
-            std::process::exit(1);
-        }
-    };
+    let load_options = ConfigOverrides::from_env()
+        .with_sets(&config_sets)
+        .map(|overrides| LoadOptions {
+            env: config_env,
+            overrides,
+        });
+    let (config, layers) =
+        match load_options.and_then(|options| load_config_layers(&config_path, &options)) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                println!("Structure:");
+                println!("  [ERR] {e}");
+                std::process::exit(1);
+            }
+        };
 
     // Phase 1: structure & server settings
     println!("Structure:");
     println!("  [OK] YAML syntax valid");
+    if layers.is_layered() {
+        println!("  [OK] Merged {} files:", layers.layers().len());
+        for layer in layers.layers() {
+            println!("         {}", layer.path.display());
+        }
+    }
+    if !layers.overrides().is_empty() {
+        println!("  [OK] Applied {} overrides:", layers.overrides().len());
+        for applied in layers.overrides() {
+            println!("         {} ({})", applied.path, applied.source);
+        }
+    }
 
-    let mapper = DtoMapper::new();
+    let mapper = settings_mapper(&config).unwrap_or_else(|e| {
+        println!("  [WARN] Could not create secret store: {e}");
+        DtoMapper::new()
+    });
     match map_server_settings(&config, &mapper) {
         Ok(resolved) => {
             println!(
@@ -390,7 +523,7 @@ fn validate_config(
     });
 
     // Phase 2-5: plugin-aware validation
-    let result: FullValidationResult =
+    let mut result: FullValidationResult =
         validate_with_plugins(&config, effective_plugins_dir.as_deref());
 
     // Environment references
@@ -426,6 +559,47 @@ fn validate_config(
     }
     println!();
 
+    // Plugin compatibility with this host build
+    let mut incompatible_count = 0;
+    if let Some(report) = result.compatibility.as_mut() {
+        if !report.plugins.is_empty() {
+            println!("Compatibility:");
+            if report.has_errors() {
+                let registry_url = config
+                    .plugin_registry
+                    .as_deref()
+                    .unwrap_or("ghcr.io/drasi-project");
+                report.suggest_upgrades(registry_url).await;
+            }
+            for line in report.render_table().lines() {
+                println!("  {line}");
+            }
+            incompatible_count = report.incompatible().count();
+            println!();
+        }
+    }
+
+    // Config migrations to the installed plugins' schema versions
+    if !result.migrations.is_empty() {
+        println!("Config migrations:");
+        for m in &result.migrations.migrated {
+            println!(
+                "  [MIGRATED] {} '{}' ({}): configVersion {} -> {}",
+                m.component_type, m.component_id, m.kind, m.from_version, m.to_version
+            );
+            for change in &m.changes {
+                println!("        - {change}");
+            }
+        }
+        for e in &result.migrations.failed {
+            println!(
+                "  [ERR] {} '{}' ({}): {}",
+                e.component_type, e.component_id, e.kind, e.message
+            );
+        }
+        println!();
+    }
+
     // Config validation
     println!("Config validation:");
     let instances = config.resolved_instances(&mapper).unwrap_or_default();
@@ -459,9 +633,38 @@ fn validate_config(
     }
     println!();
 
+    // How queries, sources and reactions fit together
+    println!("Pipeline:");
+    if result.semantic_issues.is_empty() {
+        println!("  [OK] All references resolve and every component is used");
+    }
+    for issue in &result.semantic_issues {
+        let tag = if issue.is_error() { "ERR" } else { "WARN" };
+        let instance = issue
+            .instance_id
+            .as_deref()
+            .map(|id| format!("instance '{id}' "))
+            .unwrap_or_default();
+        println!(
+            "  [{tag}] {instance}{} '{}': {}",
+            issue.component_type, issue.component_id, issue.message
+        );
+    }
+    println!();
+    let semantic_errors = result
+        .semantic_issues
+        .iter()
+        .filter(|issue| issue.is_error())
+        .count();
+
     // Summary
-    let error_count = result.env_warnings.len() + result.config_errors.len();
-    let warning_count = result.missing_plugins.len();
+    let error_count = result.env_warnings.len()
+        + result.config_errors.len()
+        + incompatible_count
+        + result.migrations.failed.len()
+        + semantic_errors;
+    let warning_count =
+        result.missing_plugins.len() + (result.semantic_issues.len() - semantic_errors);
     let instance_count = instances.len();
 
     print!(
@@ -495,6 +698,20 @@ fn validate_config(
                 println!("  [WARN] Could not resolve: {e}");
             }
         }
+
+        println!();
+        println!("Value origins:");
+        let secrets = secret_value_paths(&layers.resolved(), &result.secret_fields);
+        for (path, value, origin) in layers.value_origins() {
+            let value = match value {
+                _ if secrets.contains(&path) => "<redacted>".to_string(),
+                serde_yaml::Value::String(s) => s,
+                other => serde_yaml::to_string(&other)
+                    .map(|s| s.trim_end().replace('\n', " "))
+                    .unwrap_or_default(),
+            };
+            println!("  {path} = {value}  ({origin})");
+        }
     }
 
     if error_count > 0 {
@@ -532,6 +749,132 @@ fn collect_all_reactions_for_display(config: &DrasiServerConfig) -> Vec<(String,
     out
 }
 
+/// Print (or write to `output`) the JSON Schema of the config file for the
+/// core plugins plus those found in `plugins_dir`.
+fn write_config_schema(plugins_dir: Option<PathBuf>, output: Option<PathBuf>) -> Result<()> {
+    use drasi_server::api::v1::config_json_schema;
+    use drasi_server::PluginRegistry;
+
+    // Resolve plugins_dir: provided > default (binary dir + /plugins)
+    let plugins_dir = plugins_dir.or_else(|| {
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|p| p.join("plugins")))
+    });
+
+    let mut registry = PluginRegistry::new();
+    drasi_server::register_core_plugins(&mut registry);
+    if let Some(dir) = &plugins_dir {
+        let stats = drasi_server::dynamic_loading::load_plugins(dir, &mut registry, None, None)
+            .with_context(|| format!("Failed to load plugins from {}", dir.display()))?;
+        debug!(
+            "Loaded {} plugins from {} for the config schema",
+            stats.plugins_loaded,
+            dir.display()
+        );
+    }
+
+    let schema = serde_json::to_string_pretty(&config_json_schema(&registry))?;
+    match output {
+        Some(path) => {
+            fs::write(&path, format!("{schema}\n"))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Wrote config schema to {}", path.display());
+        }
+        None => println!("{schema}"),
+    }
+    Ok(())
+}
+
+/// Restore a backup into `./data` and the state store of its instance
+async fn restore_backup(
+    file: PathBuf,
+    config_path: PathBuf,
+    config_env: Option<String>,
+    config_sets: Vec<String>,
+    plugins_dir: Option<PathBuf>,
+    force: bool,
+) -> Result<()> {
+    use drasi_server::backup::{BackupArchive, DATA_ROOT};
+    use drasi_server::state_stores::StateStoreRegistry;
+
+    let backup = BackupArchive::open(&file)?;
+    let instance_id = backup.manifest.instance_id.clone();
+    println!(
+        "Restoring instance '{instance_id}' from {} (taken {})",
+        file.display(),
+        backup.manifest.created_at
+    );
+
+    if !config_path.exists() {
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&config_path, &backup.config)
+            .with_context(|| format!("Failed to write {}", config_path.display()))?;
+        println!("  Wrote its configuration to {}", config_path.display());
+        println!("  Secrets in it are ${{VAR}} references; set them before starting the server.");
+    }
+    let load_options = LoadOptions {
+        env: config_env,
+        overrides: ConfigOverrides::from_env().with_sets(&config_sets)?,
+    };
+    let config = load_config_layers(&config_path, &load_options)?.0;
+    let mapper = settings_mapper(&config)?;
+    let instance = config
+        .resolved_instances(&mapper)?
+        .into_iter()
+        .find(|instance| instance.id == instance_id)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} does not define instance '{instance_id}'; add it, or pass --config with a new file",
+                config_path.display()
+            )
+        })?;
+
+    backup.restore_data(std::path::Path::new(DATA_ROOT), force)?;
+    println!(
+        "  Restored the index and WAL to {}",
+        backup.data_dir(std::path::Path::new(DATA_ROOT)).display()
+    );
+    if backup.manifest.index && !instance.persist_index {
+        println!("  Note: persistIndex is off for the instance, so the index will not be used.");
+    }
+
+    match instance.state_store {
+        Some(state_store) => {
+            // The kinds the server would have: built-in plus plugin-provided.
+            let mut state_stores = StateStoreRegistry::new();
+            let plugins_dir = plugins_dir.or_else(|| {
+                std::env::current_exe()
+                    .ok()
+                    .and_then(|exe| exe.parent().map(|p| p.join("plugins")))
+            });
+            if let Some(dir) = &plugins_dir {
+                let mut registry = drasi_server::PluginRegistry::new();
+                drasi_server::dynamic_loading::load_plugins(dir, &mut registry, None, None)
+                    .with_context(|| format!("Failed to load plugins from {}", dir.display()))?
+                    .register_state_stores(&mut state_stores);
+            }
+            let store =
+                drasi_server::create_state_store_from_registry(&state_stores, &state_store)?;
+            match backup.restore_state(store.as_ref()).await? {
+                Some(keys) => println!("  Restored {keys} state store keys"),
+                None => println!("  The backup holds no state store contents"),
+            }
+        }
+        None if backup.manifest.state => {
+            println!(
+                "  Note: the instance has no stateStore, so the backed-up state was not restored."
+            );
+        }
+        None => {}
+    }
+    println!();
+    println!("Instance '{instance_id}' restored. Start the server to resume it.");
+    Ok(())
+}
+
 /// Check system dependencies
 fn run_doctor(check_all: bool) -> Result<()> {
     println!("Drasi Server Dependency Check");
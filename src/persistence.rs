@@ -16,14 +16,20 @@ use crate::api::models::bootstrap::{
     BootstrapProviderConfig, BootstrapProviderRef, TopLevelBootstrapProviderConfig,
 };
 use crate::api::models::{ConfigValue, IdentityProviderConfig, QueryConfigDto};
+use crate::config::layers::{config_components, ComponentKey, ConfigLayers};
 use crate::config::{
-    DrasiLibInstanceConfig, DrasiServerConfig, PluginDependency, ReactionConfig, SourceConfig,
-    TrustedIdentity,
+    DrasiLibInstanceConfig, DrasiServerConfig, PluginCircuitBreakerConfig, PluginDependency,
+    ReactionConfig, ServerMode, SourceConfig, TrustedIdentity, TrustedKey,
 };
+use crate::config_revisions::RevisionStore;
 use crate::instance_registry::InstanceRegistry;
+use crate::plugin_registry::PluginRegistry;
+use crate::restart_supervisor::RestartPolicies;
 use anyhow::Result;
+use drasi_lib::ConfigurationSnapshot;
 use indexmap::IndexMap;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -35,12 +41,18 @@ use tokio::sync::RwLock;
 /// configuration on the first persist operation.
 #[derive(Clone)]
 struct PreservedServerSettings {
+    /// `persistConfig` as loaded, which may be set even though this
+    /// instance does not save (a read-only config file).
+    persist_config: bool,
+    mode: ServerMode,
     enable_ui: bool,
     plugin_registry: Option<String>,
     auto_install_plugins: bool,
     plugins: Vec<PluginDependency>,
     verify_plugins: bool,
     trusted_identities: Vec<TrustedIdentity>,
+    trusted_keys: Vec<TrustedKey>,
+    plugin_circuit_breaker: Option<PluginCircuitBreakerConfig>,
     hot_reload_plugins: bool,
     hot_reload_debounce_ms: u64,
     cors_allowed_origins: Vec<String>,
@@ -67,6 +79,98 @@ struct PreservedServerSettings {
     bootstrap_providers_by_instance: IndexMap<String, Vec<TopLevelBootstrapProviderConfig>>,
 }
 
+/// The sources, queries, and reactions of one instance in config form.
+#[derive(Debug, Clone, Default)]
+pub struct ComponentConfigs {
+    pub sources: Vec<SourceConfig>,
+    pub queries: Vec<QueryConfigDto>,
+    pub reactions: Vec<ReactionConfig>,
+}
+
+impl ComponentConfigs {
+    /// Rebuild component configs from a runtime snapshot.
+    ///
+    /// `restartPolicy` lives in the instance's restart supervisor rather
+    /// than on the runtime component, so it is taken from
+    /// `restart_policies`. Internal sources (`__` prefix) are skipped.
+    /// `identityProvider` and `configVersion` are left unset; see
+    /// [`ConfigPersistence::component_configs`].
+    pub fn from_snapshot(
+        snapshot: &ConfigurationSnapshot,
+        restart_policies: &RestartPolicies,
+    ) -> Self {
+        let sources = snapshot
+            .sources
+            .iter()
+            .filter(|s| !s.id.starts_with("__"))
+            .map(|s| {
+                let mut config_map = serde_json::Map::new();
+                for (k, v) in &s.properties {
+                    config_map.insert(k.clone(), v.clone());
+                }
+                SourceConfig {
+                    kind: s.source_type.clone(),
+                    id: s.id.clone(),
+                    auto_start: s.auto_start,
+                    identity_provider: None,
+                    restart_policy: restart_policies.sources.get(&s.id).cloned(),
+                    config_version: None,
+                    bootstrap_provider: s.bootstrap_provider.as_ref().map(|bp| {
+                        let mut bp_config = serde_json::Map::new();
+                        for (k, v) in &bp.properties {
+                            bp_config.insert(k.clone(), v.clone());
+                        }
+                        BootstrapProviderRef::Inline(BootstrapProviderConfig {
+                            kind: bp.kind.clone(),
+                            config: serde_json::Value::Object(bp_config),
+                        })
+                    }),
+                    config: serde_json::Value::Object(config_map),
+                }
+            })
+            .collect();
+
+        let queries = snapshot
+            .queries
+            .iter()
+            .filter_map(|q| match QueryConfigDto::try_from(q.config.clone()) {
+                Ok(dto) => Some(dto),
+                Err(e) => {
+                    log::error!("Failed to serialize query '{}' config: {e}", q.id);
+                    None
+                }
+            })
+            .collect();
+
+        let reactions = snapshot
+            .reactions
+            .iter()
+            .map(|r| {
+                let mut config_map = serde_json::Map::new();
+                for (k, v) in &r.properties {
+                    config_map.insert(k.clone(), v.clone());
+                }
+                ReactionConfig {
+                    kind: r.reaction_type.clone(),
+                    id: r.id.clone(),
+                    queries: r.queries.clone(),
+                    auto_start: r.auto_start,
+                    identity_provider: None,
+                    restart_policy: restart_policies.reactions.get(&r.id).cloned(),
+                    config_version: None,
+                    config: serde_json::Value::Object(config_map),
+                }
+            })
+            .collect();
+
+        Self {
+            sources,
+            queries,
+            reactions,
+        }
+    }
+}
+
 /// Snapshot-based persistence for DrasiServerConfig.
 ///
 /// Uses a single-source-of-truth approach: all component state lives in the
@@ -115,6 +219,19 @@ pub struct ConfigPersistence {
     /// `bootstrapProvider: <id>` references. Seeded from the original config
     /// and kept current by the source API handlers.
     source_bootstrap_provider: Arc<RwLock<IndexMap<(String, String), BootstrapProviderRef>>>,
+    /// Registered plugins, used to stamp each saved source/reaction with its
+    /// plugin's current `configVersion`. Without it, saved components carry
+    /// no version.
+    plugin_registry: Option<Arc<RwLock<PluginRegistry>>>,
+    /// History of the content written by each save, kept next to the
+    /// config file.
+    revisions: RevisionStore,
+    /// Files the config was loaded from, when it spans more than one. Saves
+    /// then update each file in place instead of rewriting the main file.
+    layers: Option<ConfigLayers>,
+    /// Components as last written to the layered files; only components that
+    /// differ from it are written back.
+    layer_baseline: Arc<RwLock<HashMap<ComponentKey, serde_yaml::Value>>>,
 }
 
 impl ConfigPersistence {
@@ -198,6 +315,7 @@ impl ConfigPersistence {
         }
 
         Self {
+            revisions: RevisionStore::for_config_file(&config_file_path),
             config_file_path,
             registry,
             host,
@@ -208,12 +326,16 @@ impl ConfigPersistence {
             archive_settings,
             solutions_dir,
             preserved: PreservedServerSettings {
+                persist_config: original_config.persist_config,
+                mode: original_config.mode,
                 enable_ui: original_config.enable_ui,
                 plugin_registry: original_config.plugin_registry.clone(),
                 auto_install_plugins: original_config.auto_install_plugins,
                 plugins: original_config.plugins.clone(),
                 verify_plugins: original_config.verify_plugins,
                 trusted_identities: original_config.trusted_identities.clone(),
+                trusted_keys: original_config.trusted_keys.clone(),
+                plugin_circuit_breaker: original_config.plugin_circuit_breaker.clone(),
                 hot_reload_plugins: original_config.hot_reload_plugins,
                 hot_reload_debounce_ms: original_config.hot_reload_debounce_ms,
                 cors_allowed_origins: original_config.cors_allowed_origins.clone(),
@@ -279,24 +401,51 @@ impl ConfigPersistence {
                 reaction_identity_provider_by_instance,
             )),
             source_bootstrap_provider: Arc::new(RwLock::new(source_bootstrap_provider_by_instance)),
+            plugin_registry: None,
+            layers: None,
+            layer_baseline: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Record the config schema version of each plugin in saved configs.
+    pub fn with_plugin_registry(mut self, plugin_registry: Arc<RwLock<PluginRegistry>>) -> Self {
+        self.plugin_registry = Some(plugin_registry);
+        self
+    }
+
+    /// Save into the files the config was loaded from (includes, `conf.d/`,
+    /// environment overlay) rather than collapsing them into the main file.
+    pub fn with_layers(mut self, layers: ConfigLayers) -> Self {
+        self.layer_baseline = Arc::new(RwLock::new(layers.components()));
+        self.layers = Some(layers);
+        self
+    }
+
+    /// Take the running configuration as what the layered files hold.
+    ///
+    /// Call once the initial instances are registered. A saved component
+    /// carries fields its file may leave out (defaults, `configVersion`);
+    /// without this, the first save would rewrite every component.
+    pub async fn capture_layer_baseline(&self) -> Result<()> {
+        if self.layers.is_none() {
+            return Ok(());
+        }
+        let config = self.current_config().await?;
+        *self.layer_baseline.write().await = config_components(&config)?;
+        Ok(())
+    }
+
     /// Register an `identityProvider` reference for a source.
     ///
     /// Called by the source create/upsert API handlers so that the reference
     /// survives the next `save()` (since `snapshot_configuration()` does not
-    /// carry it). A `None` value removes the entry. No-op when persistence
-    /// is disabled.
+    /// carry it). A `None` value removes the entry.
     pub async fn register_source_identity_provider(
         &self,
         instance_id: &str,
         source_id: &str,
         identity_provider: Option<&str>,
     ) {
-        if !self.persist_config {
-            return;
-        }
         let mut map = self.source_identity_provider.write().await;
         match identity_provider {
             Some(ip) => {
@@ -314,9 +463,6 @@ impl ConfigPersistence {
     /// Remove any preserved `identityProvider` reference for a source.
     /// Called by the source delete handler.
     pub async fn unregister_source_identity_provider(&self, instance_id: &str, source_id: &str) {
-        if !self.persist_config {
-            return;
-        }
         let mut map = self.source_identity_provider.write().await;
         map.shift_remove(&(instance_id.to_string(), source_id.to_string()));
     }
@@ -329,9 +475,6 @@ impl ConfigPersistence {
         reaction_id: &str,
         identity_provider: Option<&str>,
     ) {
-        if !self.persist_config {
-            return;
-        }
         let mut map = self.reaction_identity_provider.write().await;
         match identity_provider {
             Some(ip) => {
@@ -352,9 +495,6 @@ impl ConfigPersistence {
         instance_id: &str,
         reaction_id: &str,
     ) {
-        if !self.persist_config {
-            return;
-        }
         let mut map = self.reaction_identity_provider.write().await;
         map.shift_remove(&(instance_id.to_string(), reaction_id.to_string()));
     }
@@ -364,17 +504,13 @@ impl ConfigPersistence {
     /// Accepts the full [`BootstrapProviderRef`] — either a top-level reference
     /// or an inline definition — because drasi-lib's `snapshot_configuration()`
     /// does not reliably carry it (this is what caused issue #105 for inline
-    /// providers). A `None` value removes any existing entry. No-op when
-    /// persistence is disabled.
+    /// providers). A `None` value removes any existing entry.
     pub async fn register_source_bootstrap_provider(
         &self,
         instance_id: &str,
         source_id: &str,
         bootstrap_provider: Option<&BootstrapProviderRef>,
     ) {
-        if !self.persist_config {
-            return;
-        }
         let mut map = self.source_bootstrap_provider.write().await;
         match bootstrap_provider {
             Some(bp) => {
@@ -389,18 +525,12 @@ impl ConfigPersistence {
     /// Remove any preserved `bootstrapProvider` reference for a source.
     /// Called by the source delete handler.
     pub async fn unregister_source_bootstrap_provider(&self, instance_id: &str, source_id: &str) {
-        if !self.persist_config {
-            return;
-        }
         let mut map = self.source_bootstrap_provider.write().await;
         map.shift_remove(&(instance_id.to_string(), source_id.to_string()));
     }
 
     /// Register a new instance config for persistence
     pub async fn register_instance(&self, config: DrasiLibInstanceConfig) {
-        if !self.persist_config {
-            return;
-        }
         let mut instance_configs = self.instance_configs.write().await;
         // Extract the ID from the ConfigValue
         let id = match &config.id {
@@ -413,10 +543,68 @@ impl ConfigPersistence {
         instance_configs.insert(id, config);
     }
 
+    /// Current sources, queries, and reactions of one instance in config
+    /// form, as `save()` writes them.
+    ///
+    /// Extends [`ComponentConfigs::from_snapshot`] with what the runtime
+    /// snapshot cannot carry: tracked `identityProvider` and
+    /// `bootstrapProvider` references and each plugin's `configVersion`.
+    pub async fn component_configs(
+        &self,
+        instance_id: &str,
+        snapshot: &ConfigurationSnapshot,
+    ) -> ComponentConfigs {
+        let restart_policies = self.registry.restart_policies(instance_id).await;
+        let mut configs = ComponentConfigs::from_snapshot(snapshot, &restart_policies);
+
+        let source_identity_provider = self.source_identity_provider.read().await;
+        let reaction_identity_provider = self.reaction_identity_provider.read().await;
+        let source_bootstrap_provider = self.source_bootstrap_provider.read().await;
+        let plugin_registry = match &self.plugin_registry {
+            Some(registry) => Some(registry.read().await),
+            None => None,
+        };
+
+        for source in &mut configs.sources {
+            let key = (instance_id.to_string(), source.id.clone());
+            source.identity_provider = source_identity_provider.get(&key).cloned();
+            // Prefer the tracked bootstrapProvider (inline or reference) so
+            // it round-trips faithfully; the snapshot's inline form is only
+            // a fallback for providers attached out-of-band.
+            if let Some(bootstrap_provider) = source_bootstrap_provider.get(&key) {
+                source.bootstrap_provider = Some(bootstrap_provider.clone());
+            }
+            source.config_version = plugin_registry
+                .as_ref()
+                .and_then(|r| r.get_source(&source.kind))
+                .map(|d| d.config_version().to_string());
+        }
+        for reaction in &mut configs.reactions {
+            let key = (instance_id.to_string(), reaction.id.clone());
+            reaction.identity_provider = reaction_identity_provider.get(&key).cloned();
+            reaction.config_version = plugin_registry
+                .as_ref()
+                .and_then(|r| r.get_reaction(&reaction.kind))
+                .map(|d| d.config_version().to_string());
+        }
+        configs
+    }
+
+    /// Revision history of the config file.
+    pub fn revisions(&self) -> &RevisionStore {
+        &self.revisions
+    }
+
     /// Save the current configuration to the config file using atomic writes.
     /// Uses `snapshot_configuration()` to get current state from each DrasiLib instance.
     /// Uses single-instance format when there's 1 instance, multi-instance format otherwise.
     pub async fn save(&self) -> Result<()> {
+        self.save_for("save").await
+    }
+
+    /// [`Self::save`], recording `operation` (e.g. "creating source") as the
+    /// reason for the write in the revision history.
+    pub async fn save_for(&self, operation: &str) -> Result<()> {
         if !self.persist_config {
             debug!("Persistence disabled (persist_config: false), skipping save");
             return Ok(());
@@ -427,10 +615,67 @@ impl ConfigPersistence {
             self.config_file_path.display()
         );
 
+        let wrapper_config = self.current_config().await?;
+
+        // Validate before saving
+        wrapper_config.validate()?;
+
+        // Serialize to YAML
+        let yaml_content = serde_yaml::to_string(&wrapper_config)?;
+
+        let previous_content = match self.layers.as_ref().filter(|l| l.writes_in_place()) {
+            Some(layers) => {
+                self.write_layers(layers, &wrapper_config).await?;
+                // The merged files as they were at startup are the diff base
+                // for the first recorded revision.
+                serde_yaml::to_string(&layers.merged()).ok()
+            }
+            None => {
+                // The file as it was before this save is the diff base for the
+                // first recorded revision.
+                let previous_content = std::fs::read_to_string(&self.config_file_path).ok();
+                write_atomically(&self.config_file_path, &yaml_content)?;
+                previous_content
+            }
+        };
+
+        info!(
+            "Configuration saved successfully to {}",
+            self.config_file_path.display()
+        );
+
+        // The config file is already written; a history failure must not
+        // turn a successful save into an error.
+        match self
+            .revisions
+            .record(&yaml_content, operation, previous_content.as_deref())
+        {
+            Ok(Some(revision)) => debug!(
+                "Recorded config revision {} ({operation})",
+                revision.revision
+            ),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to record config revision after {operation}: {e}"),
+        }
+        Ok(())
+    }
+
+    /// Write the changes in `config` back to the files of a layered
+    /// configuration, each component to the file that defines it.
+    async fn write_layers(&self, layers: &ConfigLayers, config: &DrasiServerConfig) -> Result<()> {
+        let mut baseline = self.layer_baseline.write().await;
+        for (path, doc) in layers.write_back(config, &baseline)? {
+            debug!("Writing configuration layer {}", path.display());
+            write_atomically(&path, &serde_yaml::to_string(&doc)?)?;
+        }
+        *baseline = config_components(config)?;
+        Ok(())
+    }
+
+    /// The configuration `save()` writes: server settings plus each
+    /// registered instance's current components.
+    pub async fn current_config(&self) -> Result<DrasiServerConfig> {
         let dynamic_instance_configs = self.instance_configs.read().await;
-        let source_identity_provider = self.source_identity_provider.read().await;
-        let reaction_identity_provider = self.reaction_identity_provider.read().await;
-        let source_bootstrap_provider = self.source_bootstrap_provider.read().await;
 
         let mut instance_configs = Vec::new();
 
@@ -439,93 +684,23 @@ impl ConfigPersistence {
                 .snapshot_configuration()
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to snapshot instance '{id}': {e}"))?;
+            let ComponentConfigs {
+                sources,
+                queries,
+                reactions,
+            } = self.component_configs(&id, &snapshot).await;
 
             let persist_index = *self.persist_settings.get(&id).unwrap_or(&false);
             let enable_archive = *self.archive_settings.get(&id).unwrap_or(&false);
 
-            // Map snapshot sources to SourceConfig, filtering internal sources
-            let sources: Vec<SourceConfig> = snapshot
-                .sources
-                .iter()
-                .filter(|s| !s.id.starts_with("__"))
-                .map(|s| {
-                    let mut config_map = serde_json::Map::new();
-                    for (k, v) in &s.properties {
-                        config_map.insert(k.clone(), v.clone());
-                    }
-                    SourceConfig {
-                        kind: s.source_type.clone(),
-                        id: s.id.clone(),
-                        auto_start: s.auto_start,
-                        identity_provider: source_identity_provider
-                            .get(&(id.clone(), s.id.clone()))
-                            .cloned(),
-                        // Prefer the tracked bootstrapProvider (inline or
-                        // reference) so it round-trips faithfully. Fall back to
-                        // reconstructing an inline form from the runtime
-                        // snapshot only if nothing was tracked (e.g. a source
-                        // whose provider was attached out-of-band).
-                        bootstrap_provider: source_bootstrap_provider
-                            .get(&(id.clone(), s.id.clone()))
-                            .cloned()
-                            .or_else(|| {
-                                s.bootstrap_provider.as_ref().map(|bp| {
-                                    let mut bp_config = serde_json::Map::new();
-                                    for (k, v) in &bp.properties {
-                                        bp_config.insert(k.clone(), v.clone());
-                                    }
-                                    BootstrapProviderRef::Inline(BootstrapProviderConfig {
-                                        kind: bp.kind.clone(),
-                                        config: serde_json::Value::Object(bp_config),
-                                    })
-                                })
-                            }),
-                        config: serde_json::Value::Object(config_map),
-                    }
-                })
-                .collect();
-
-            // Map snapshot queries to QueryConfigDto
-            let queries: Vec<QueryConfigDto> = snapshot
-                .queries
-                .iter()
-                .filter_map(|q| match QueryConfigDto::try_from(q.config.clone()) {
-                    Ok(dto) => Some(dto),
-                    Err(e) => {
-                        log::error!("Failed to serialize query '{}' config: {e}", q.id);
-                        None
-                    }
-                })
-                .collect();
-
-            // Map snapshot reactions to ReactionConfig
-            let reactions: Vec<ReactionConfig> = snapshot
-                .reactions
-                .iter()
-                .map(|r| {
-                    let mut config_map = serde_json::Map::new();
-                    for (k, v) in &r.properties {
-                        config_map.insert(k.clone(), v.clone());
-                    }
-                    ReactionConfig {
-                        kind: r.reaction_type.clone(),
-                        id: r.id.clone(),
-                        queries: r.queries.clone(),
-                        auto_start: r.auto_start,
-                        identity_provider: reaction_identity_provider
-                            .get(&(id.clone(), r.id.clone()))
-                            .cloned(),
-                        config: serde_json::Value::Object(config_map),
-                    }
-                })
-                .collect();
-
             // Check if this is a dynamically created instance
             let instance_config = if let Some(dynamic_config) = dynamic_instance_configs.get(&id) {
                 DrasiLibInstanceConfig {
                     id: ConfigValue::Static(snapshot.instance_id.clone()),
                     persist_index: dynamic_config.persist_index,
                     enable_archive: dynamic_config.enable_archive,
+                    archive_retention: dynamic_config.archive_retention.clone(),
+                    wal_retention: dynamic_config.wal_retention.clone(),
                     state_store: dynamic_config.state_store.clone(),
                     secret_store: dynamic_config.secret_store.clone(),
                     default_priority_queue_capacity: dynamic_config
@@ -567,6 +742,8 @@ impl ConfigPersistence {
                     id: ConfigValue::Static(snapshot.instance_id.clone()),
                     persist_index,
                     enable_archive,
+                    archive_retention: None,
+                    wal_retention: None,
                     state_store: None,
                     secret_store: None,
                     default_priority_queue_capacity: None,
@@ -616,9 +793,12 @@ impl ConfigPersistence {
                 host: ConfigValue::Static(self.host.clone()),
                 port: ConfigValue::Static(self.port),
                 log_level: ConfigValue::Static(self.log_level.clone()),
-                persist_config: self.persist_config,
+                persist_config: self.preserved.persist_config,
+                mode: self.preserved.mode,
                 persist_index: instance.persist_index,
                 enable_archive: instance.enable_archive,
+                archive_retention: instance.archive_retention,
+                wal_retention: instance.wal_retention,
                 enable_ui: self.preserved.enable_ui,
                 solutions_dir: self.solutions_dir.clone(),
                 state_store: instance.state_store,
@@ -630,6 +810,8 @@ impl ConfigPersistence {
                 plugins: self.preserved.plugins.clone(),
                 verify_plugins: self.preserved.verify_plugins,
                 trusted_identities: self.preserved.trusted_identities.clone(),
+                trusted_keys: self.preserved.trusted_keys.clone(),
+                plugin_circuit_breaker: self.preserved.plugin_circuit_breaker.clone(),
                 hot_reload_plugins: self.preserved.hot_reload_plugins,
                 hot_reload_debounce_ms: self.preserved.hot_reload_debounce_ms,
                 cors_allowed_origins: self.preserved.cors_allowed_origins.clone(),
@@ -656,9 +838,12 @@ impl ConfigPersistence {
                 host: ConfigValue::Static(self.host.clone()),
                 port: ConfigValue::Static(self.port),
                 log_level: ConfigValue::Static(self.log_level.clone()),
-                persist_config: self.persist_config,
+                persist_config: self.preserved.persist_config,
+                mode: self.preserved.mode,
                 persist_index: false, // Per-instance setting in multi-instance mode
                 enable_archive: false, // Per-instance setting in multi-instance mode
+                archive_retention: None,
+                wal_retention: None,
                 enable_ui: self.preserved.enable_ui,
                 solutions_dir: self.solutions_dir.clone(),
                 state_store: None,  // Per-instance setting in multi-instance mode
@@ -670,6 +855,8 @@ impl ConfigPersistence {
                 plugins: self.preserved.plugins.clone(),
                 verify_plugins: self.preserved.verify_plugins,
                 trusted_identities: self.preserved.trusted_identities.clone(),
+                trusted_keys: self.preserved.trusted_keys.clone(),
+                plugin_circuit_breaker: self.preserved.plugin_circuit_breaker.clone(),
                 hot_reload_plugins: self.preserved.hot_reload_plugins,
                 hot_reload_debounce_ms: self.preserved.hot_reload_debounce_ms,
                 cors_allowed_origins: self.preserved.cors_allowed_origins.clone(),
@@ -689,41 +876,13 @@ impl ConfigPersistence {
             }
         };
 
-        // Validate before saving
-        wrapper_config.validate()?;
-
-        // Use atomic write: write to temp file, then rename
-        let temp_path = self.config_file_path.with_extension("tmp");
-
-        // Serialize to YAML
-        let yaml_content = serde_yaml::to_string(&wrapper_config)?;
-
-        // Write to temp file
-        std::fs::write(&temp_path, yaml_content).map_err(|e| {
-            error!(
-                "Failed to write temp config file {}: {e}",
-                temp_path.display()
-            );
-            anyhow::anyhow!("Failed to write temp config file: {e}")
-        })?;
-
-        // Atomically rename temp file to actual config file
-        std::fs::rename(&temp_path, &self.config_file_path).map_err(|e| {
-            error!(
-                "Failed to rename temp config file {} to {}: {e}",
-                temp_path.display(),
-                self.config_file_path.display()
-            );
-            // Clean up temp file if rename fails
-            let _ = std::fs::remove_file(&temp_path);
-            anyhow::anyhow!("Failed to rename config file: {e}")
-        })?;
+        Ok(wrapper_config)
+    }
 
-        info!(
-            "Configuration saved successfully to {}",
-            self.config_file_path.display()
-        );
-        Ok(())
+    /// Whether `save()` writes the config file. When false, components are
+    /// still tracked so [`Self::current_config`] reflects the running state.
+    pub fn persists(&self) -> bool {
+        self.persist_config
     }
 
     /// Check if the config file is writable
@@ -738,6 +897,32 @@ impl ConfigPersistence {
     }
 }
 
+/// Write `content` to `path` atomically: write to a temp file, then rename.
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let temp_path = path.with_extension("tmp");
+
+    // Write to temp file
+    std::fs::write(&temp_path, content).map_err(|e| {
+        error!(
+            "Failed to write temp config file {}: {e}",
+            temp_path.display()
+        );
+        anyhow::anyhow!("Failed to write temp config file: {e}")
+    })?;
+
+    // Atomically rename temp file to actual config file
+    std::fs::rename(&temp_path, path).map_err(|e| {
+        error!(
+            "Failed to rename temp config file {} to {}: {e}",
+            temp_path.display(),
+            path.display()
+        );
+        // Clean up temp file if rename fails
+        let _ = std::fs::remove_file(&temp_path);
+        anyhow::anyhow!("Failed to rename config file: {e}")
+    })
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -1049,6 +1234,8 @@ mod tests {
                 id: "src1".to_string(),
                 auto_start: false,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 bootstrap_provider: Some(BootstrapProviderRef::Inline(BootstrapProviderConfig {
                     kind: "postgres".to_string(),
                     config: serde_json::json!({ "host": "db.local", "tables": ["Message"] }),
@@ -1103,6 +1290,8 @@ mod tests {
                 id: "src1".to_string(),
                 auto_start: false,
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
                 bootstrap_provider: Some(BootstrapProviderRef::Reference(
                     "pg-bootstrap".to_string(),
                 )),
@@ -1282,6 +1471,99 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_save_for_records_revisions() {
+        let tmp = TempDir::new().unwrap();
+        let cfg_path = tmp.path().join("server.yaml");
+        std::fs::write(&cfg_path, "id: inst1\n").unwrap();
+
+        let core = build_core(
+            "inst1",
+            vec![TestSource::new("src1", "mock")],
+            vec![],
+            vec![],
+        )
+        .await;
+
+        let p = make_persistence(core, "inst1", cfg_path.clone(), true);
+        p.save_for("creating source").await.unwrap();
+        // Saving unchanged state does not add a revision.
+        p.save_for("creating source").await.unwrap();
+
+        let revisions = p.revisions().list().unwrap();
+        let operations: Vec<&str> = revisions.iter().map(|r| r.operation.as_str()).collect();
+        assert_eq!(operations, vec!["initial", "creating source"]);
+        assert_eq!(
+            p.revisions().content(1).unwrap().unwrap(),
+            "id: inst1\n",
+            "the file the server started with is kept as revision 1"
+        );
+        assert_eq!(
+            p.revisions().content(2).unwrap().unwrap(),
+            std::fs::read_to_string(&cfg_path).unwrap()
+        );
+        assert!(p
+            .revisions()
+            .diff(2)
+            .unwrap()
+            .unwrap()
+            .contains("-id: inst1"));
+    }
+
+    #[tokio::test]
+    async fn test_save_writes_back_to_layered_files() {
+        let tmp = TempDir::new().unwrap();
+        let cfg_path = tmp.path().join("server.yaml");
+        std::fs::write(
+            &cfg_path,
+            "id: inst1\nport: 9999\nsources:\n  - {id: src1, kind: mock}\n",
+        )
+        .unwrap();
+        let conf_d = tmp.path().join(crate::config::layers::CONF_D_DIR);
+        std::fs::create_dir(&conf_d).unwrap();
+        std::fs::write(
+            conf_d.join("extra.yaml"),
+            "sources:\n  - {id: src2, kind: mock}\n",
+        )
+        .unwrap();
+        let (config, layers) = crate::config::load_config_layers(&cfg_path, None).unwrap();
+
+        let core = build_core(
+            "inst1",
+            vec![
+                TestSource::new("src1", "mock"),
+                TestSource::new("src2", "mock"),
+            ],
+            vec![],
+            vec![],
+        )
+        .await;
+        let p =
+            make_persistence_with_config(core.clone(), "inst1", cfg_path.clone(), true, &config)
+                .with_layers(layers);
+        p.capture_layer_baseline().await.unwrap();
+
+        core.remove_source("src2", false).await.unwrap();
+        core.add_source(TestSource::new("src3", "mock"))
+            .await
+            .unwrap();
+        p.save().await.unwrap();
+
+        // src2 is removed from the file that defined it; the new source goes
+        // to the main file, whose settings are left as written.
+        let extra = read_yaml(&conf_d.join("extra.yaml"));
+        assert!(extra["sources"].as_sequence().unwrap().is_empty());
+        let main = read_yaml(&cfg_path);
+        let ids: Vec<&str> = main["sources"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .map(|s| s["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, vec!["src1", "src3"]);
+        assert_eq!(main["port"].as_u64(), Some(9999));
+    }
+
     #[tokio::test]
     async fn test_save_single_instance_format() {
         let tmp = TempDir::new().unwrap();
@@ -1468,6 +1750,11 @@ mod tests {
                 issuer: "https://accounts.google.com".to_string(),
                 subject_pattern: "builder@my-org.iam.gserviceaccount.com".to_string(),
             }],
+            trusted_keys: vec![TrustedKey {
+                name: "in-house".to_string(),
+                public_key: None,
+                public_key_path: Some("keys/signing.pub".to_string()),
+            }],
             hot_reload_plugins: true,
             hot_reload_debounce_ms: 500,
             cors_allowed_origins: vec![
@@ -1504,6 +1791,8 @@ mod tests {
             parsed.trusted_identities[0].issuer,
             "https://accounts.google.com"
         );
+        assert_eq!(parsed.trusted_keys.len(), 1);
+        assert_eq!(parsed.trusted_keys[0].name, "in-house");
         assert!(parsed.hot_reload_plugins);
         assert_eq!(parsed.hot_reload_debounce_ms, 500);
         assert_eq!(parsed.cors_allowed_origins.len(), 2);
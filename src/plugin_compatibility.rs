@@ -0,0 +1,675 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compatibility checks between cdylib plugins and the host build.
+//!
+//! A plugin is compatible when its drasi-plugin-sdk, drasi-core and drasi-lib
+//! versions share the host's `major.minor` (the same rule the registry
+//! resolver applies) and it was built for the host's target triple. A
+//! differing rustc version is reported as a warning only: plugins talk to the
+//! host through a C ABI, so it is not fatal on its own.
+//!
+//! Build information comes from two places: `plugins.lock` entries (available
+//! without opening the library, so incompatible files can be skipped before
+//! any FFI call) and the `drasi_plugin_metadata()` string of loaded plugins.
+
+use drasi_host_sdk::registry::{
+    target_triple_to_oci_platform, HostVersionInfo, PluginResolver, RegistryConfig,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::plugin_lockfile::{LockedPlugin, PluginLockfile};
+use crate::plugin_operations::PluginOperations;
+
+/// How long to wait for the registry when looking up upgrade suggestions.
+const SUGGESTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Versions and platform of the running host.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostCompatibility {
+    pub sdk_version: String,
+    pub core_version: String,
+    pub lib_version: String,
+    pub target_triple: String,
+    /// Bare rustc version (e.g. `1.85.0`), if known.
+    pub rustc_version: Option<String>,
+}
+
+impl HostCompatibility {
+    /// Build information for this server binary.
+    pub fn current() -> Self {
+        let HostVersionInfo {
+            sdk_version,
+            core_version,
+            lib_version,
+            target_triple,
+        } = PluginOperations::host_version_info();
+        Self {
+            sdk_version,
+            core_version,
+            lib_version,
+            target_triple,
+            rustc_version: parse_rustc_version(env!("DRASI_RUSTC_VERSION")),
+        }
+    }
+}
+
+/// Build information reported by (or recorded for) a single plugin.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginBuildInfo {
+    /// Lockfile reference, or the file name for plugins not in the lockfile.
+    pub name: String,
+    pub filename: Option<String>,
+    /// Lockfile reference usable with `drasi-server plugin upgrade`.
+    pub reference: Option<String>,
+    pub plugin_version: Option<String>,
+    pub sdk_version: Option<String>,
+    pub core_version: Option<String>,
+    pub lib_version: Option<String>,
+    pub target_triple: Option<String>,
+    /// OCI platform (e.g. `linux/amd64`) when no target triple is known.
+    pub platform: Option<String>,
+    pub rustc_version: Option<String>,
+}
+
+impl PluginBuildInfo {
+    /// Parse a loader metadata string (`sdk=X core=Y plugin=Z target=T ...`).
+    pub fn from_metadata(name: &str, metadata: &str) -> Self {
+        let field = |key: &str| {
+            metadata
+                .split_whitespace()
+                .find_map(|token| token.strip_prefix(key)?.strip_prefix('='))
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            name: name.to_string(),
+            filename: Some(name.to_string()),
+            plugin_version: field("plugin"),
+            sdk_version: field("sdk"),
+            core_version: field("core"),
+            lib_version: field("lib"),
+            target_triple: field("target"),
+            rustc_version: field("rustc"),
+            ..Default::default()
+        }
+    }
+
+    /// Build information recorded in a `plugins.lock` entry.
+    pub fn from_lockfile(key: &str, entry: &LockedPlugin) -> Self {
+        let non_empty = |v: &str| (!v.is_empty()).then(|| v.to_string());
+        Self {
+            name: key.to_string(),
+            filename: non_empty(&entry.filename),
+            reference: Some(key.to_string()),
+            plugin_version: non_empty(&entry.version),
+            sdk_version: non_empty(&entry.sdk_version),
+            core_version: non_empty(&entry.core_version),
+            lib_version: non_empty(&entry.lib_version),
+            platform: non_empty(&entry.platform),
+            ..Default::default()
+        }
+    }
+
+    /// Fill fields this record lacks from `other` (e.g. the lockfile entry
+    /// for a plugin whose metadata was read at load time).
+    pub fn merge_missing(&mut self, other: &PluginBuildInfo) {
+        fn fill(target: &mut Option<String>, source: &Option<String>) {
+            if target.is_none() {
+                target.clone_from(source);
+            }
+        }
+        fill(&mut self.reference, &other.reference);
+        fill(&mut self.plugin_version, &other.plugin_version);
+        fill(&mut self.sdk_version, &other.sdk_version);
+        fill(&mut self.core_version, &other.core_version);
+        fill(&mut self.lib_version, &other.lib_version);
+        fill(&mut self.target_triple, &other.target_triple);
+        fill(&mut self.platform, &other.platform);
+        fill(&mut self.rustc_version, &other.rustc_version);
+        if other.reference.is_some() {
+            self.name.clone_from(&other.name);
+        }
+    }
+}
+
+/// How serious a compatibility finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    /// The plugin must not be loaded.
+    Error,
+    /// The plugin can be loaded but may misbehave.
+    Warning,
+}
+
+/// A single failed compatibility check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityIssue {
+    /// Which check failed: `sdk`, `core`, `lib`, `target` or `rustc`.
+    pub check: &'static str,
+    pub plugin_value: String,
+    /// What the host accepts (a semver range or exact value).
+    pub host_requirement: String,
+    pub severity: IssueSeverity,
+}
+
+/// Compatibility verdict for one plugin.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginCompatibility {
+    pub plugin: PluginBuildInfo,
+    pub issues: Vec<CompatibilityIssue>,
+    /// How to get a compatible build, when the plugin has errors.
+    pub suggestion: Option<String>,
+}
+
+impl PluginCompatibility {
+    /// Check `plugin` against `host`.
+    pub fn check(host: &HostCompatibility, plugin: PluginBuildInfo) -> Self {
+        Self {
+            issues: check_plugin(host, &plugin),
+            plugin,
+            suggestion: None,
+        }
+    }
+
+    /// Whether the plugin passed every error-level check.
+    pub fn is_compatible(&self) -> bool {
+        !self
+            .issues
+            .iter()
+            .any(|i| i.severity == IssueSeverity::Error)
+    }
+
+    /// One-line description of the findings, e.g.
+    /// `sdk 0.10.3 (host requires >=0.11.0, <0.12.0)`.
+    pub fn summary(&self) -> String {
+        self.issues
+            .iter()
+            .map(|i| {
+                format!(
+                    "{} {} (host requires {})",
+                    i.check, i.plugin_value, i.host_requirement
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Compatibility matrix of a set of plugins against the host.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityReport {
+    pub host: HostCompatibility,
+    pub plugins: Vec<PluginCompatibility>,
+}
+
+impl Default for CompatibilityReport {
+    fn default() -> Self {
+        Self::check(HostCompatibility::current(), Vec::new())
+    }
+}
+
+impl CompatibilityReport {
+    /// Check every plugin against `host`.
+    pub fn check(host: HostCompatibility, plugins: Vec<PluginBuildInfo>) -> Self {
+        let plugins = plugins
+            .into_iter()
+            .map(|plugin| PluginCompatibility::check(&host, plugin))
+            .collect();
+        Self { host, plugins }
+    }
+
+    /// Plugins that failed at least one error-level check.
+    pub fn incompatible(&self) -> impl Iterator<Item = &PluginCompatibility> {
+        self.plugins.iter().filter(|p| !p.is_compatible())
+    }
+
+    /// Whether any plugin is incompatible.
+    pub fn has_errors(&self) -> bool {
+        self.incompatible().next().is_some()
+    }
+
+    /// Whether any plugin has a finding of any severity.
+    pub fn has_issues(&self) -> bool {
+        self.plugins.iter().any(|p| !p.issues.is_empty())
+    }
+
+    /// File names of incompatible plugins.
+    pub fn incompatible_files(&self) -> Vec<String> {
+        self.incompatible()
+            .filter_map(|p| p.plugin.filename.clone())
+            .collect()
+    }
+
+    /// Look up a compatible version in the registry for each incompatible
+    /// plugin that came from an OCI reference. Falls back to a rebuild hint
+    /// when the registry has none or cannot be reached.
+    pub async fn suggest_upgrades(&mut self, registry_url: &str) {
+        let host_info = HostVersionInfo {
+            sdk_version: self.host.sdk_version.clone(),
+            core_version: self.host.core_version.clone(),
+            lib_version: self.host.lib_version.clone(),
+            target_triple: self.host.target_triple.clone(),
+        };
+        let client = PluginOperations::build_registry_client(RegistryConfig {
+            default_registry: registry_url.to_string(),
+            auth: PluginOperations::registry_auth(),
+        });
+        let resolver = PluginResolver::new(&client, &host_info);
+        let rebuild_hint = format!(
+            "rebuild against drasi-plugin-sdk {} for {}",
+            self.host.sdk_version, self.host.target_triple
+        );
+
+        for plugin in self.plugins.iter_mut().filter(|p| !p.is_compatible()) {
+            let Some(reference) = plugin
+                .plugin
+                .reference
+                .as_deref()
+                .and_then(oci_base_reference)
+            else {
+                plugin.suggestion = Some(rebuild_hint.clone());
+                continue;
+            };
+            let lookup = tokio::time::timeout(
+                SUGGESTION_TIMEOUT,
+                resolver.resolve(&reference, registry_url),
+            )
+            .await;
+            plugin.suggestion = Some(match lookup {
+                Ok(Ok(resolved)) => format!(
+                    "upgrade to {} (drasi-server plugin upgrade {reference})",
+                    resolved.version
+                ),
+                Ok(Err(e)) => {
+                    log::debug!("No compatible version of '{reference}' found: {e}");
+                    format!("no compatible version in {registry_url}; {rebuild_hint}")
+                }
+                Err(_) => format!("registry lookup timed out; {rebuild_hint}"),
+            });
+        }
+    }
+
+    /// Render the report as a plain-text table, one row per finding.
+    /// Compatible plugins without findings get a single `ok` row.
+    pub fn render_table(&self) -> String {
+        let host = &self.host;
+        let mut rows: Vec<[String; 5]> = Vec::new();
+        for plugin in &self.plugins {
+            let version = plugin
+                .plugin
+                .plugin_version
+                .clone()
+                .unwrap_or_else(|| "?".to_string());
+            if plugin.issues.is_empty() {
+                rows.push([
+                    plugin.plugin.name.clone(),
+                    version,
+                    "ok".to_string(),
+                    String::new(),
+                    String::new(),
+                ]);
+                continue;
+            }
+            for issue in &plugin.issues {
+                let status = match issue.severity {
+                    IssueSeverity::Error => "INCOMPATIBLE",
+                    IssueSeverity::Warning => "warning",
+                };
+                rows.push([
+                    plugin.plugin.name.clone(),
+                    version.clone(),
+                    status.to_string(),
+                    issue.check.to_string(),
+                    format!(
+                        "plugin {} / host {}",
+                        issue.plugin_value, issue.host_requirement
+                    ),
+                ]);
+            }
+        }
+
+        let headers = ["PLUGIN", "VERSION", "STATUS", "CHECK", "DETAIL"];
+        let mut widths = headers.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Host: sdk {}, core {}, lib {}, target {}, rustc {}",
+            host.sdk_version,
+            host.core_version,
+            host.lib_version,
+            host.target_triple,
+            host.rustc_version.as_deref().unwrap_or("unknown")
+        );
+        let write_row = |out: &mut String, cells: [&str; 5]| {
+            let line = cells
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            let _ = writeln!(out, "{}", line.trim_end());
+        };
+        write_row(&mut out, headers);
+        for row in &rows {
+            write_row(&mut out, row.each_ref().map(String::as_str));
+        }
+        for plugin in self.plugins.iter().filter(|p| p.suggestion.is_some()) {
+            let _ = writeln!(
+                out,
+                "  {}: {}",
+                plugin.plugin.name,
+                plugin.suggestion.as_deref().unwrap_or_default()
+            );
+        }
+        out
+    }
+}
+
+/// Run every compatibility check for one plugin.
+pub fn check_plugin(host: &HostCompatibility, plugin: &PluginBuildInfo) -> Vec<CompatibilityIssue> {
+    let mut issues = Vec::new();
+    for (check, plugin_version, host_version) in [
+        ("sdk", &plugin.sdk_version, &host.sdk_version),
+        ("core", &plugin.core_version, &host.core_version),
+        ("lib", &plugin.lib_version, &host.lib_version),
+    ] {
+        if let Some(plugin_version) = plugin_version {
+            issues.extend(check_version(check, plugin_version, host_version));
+        }
+    }
+
+    if let Some(target) = &plugin.target_triple {
+        if *target != host.target_triple {
+            issues.push(CompatibilityIssue {
+                check: "target",
+                plugin_value: target.clone(),
+                host_requirement: host.target_triple.clone(),
+                severity: IssueSeverity::Error,
+            });
+        }
+    } else if let Some(platform) = &plugin.platform {
+        if let Some(host_platform) = target_triple_to_oci_platform(&host.target_triple) {
+            let host_platform = host_platform.to_string();
+            if *platform != host_platform {
+                issues.push(CompatibilityIssue {
+                    check: "target",
+                    plugin_value: platform.clone(),
+                    host_requirement: host_platform,
+                    severity: IssueSeverity::Error,
+                });
+            }
+        }
+    }
+
+    if let (Some(plugin_rustc), Some(host_rustc)) = (&plugin.rustc_version, &host.rustc_version) {
+        if plugin_rustc != host_rustc {
+            issues.push(CompatibilityIssue {
+                check: "rustc",
+                plugin_value: plugin_rustc.clone(),
+                host_requirement: host_rustc.clone(),
+                severity: IssueSeverity::Warning,
+            });
+        }
+    }
+
+    issues
+}
+
+/// Collect build information for the plugins recorded in `dir`'s lockfile
+/// whose files are present on disk.
+pub fn lockfile_build_infos(dir: &Path) -> Vec<PluginBuildInfo> {
+    let lockfile = match PluginLockfile::read(dir) {
+        Ok(Some(lockfile)) => lockfile,
+        Ok(None) => return Vec::new(),
+        Err(e) => {
+            log::warn!("Failed to read plugin lockfile in {}: {e}", dir.display());
+            return Vec::new();
+        }
+    };
+    lockfile
+        .iter()
+        .filter(|(_, entry)| !entry.filename.is_empty() && dir.join(&entry.filename).exists())
+        .map(|(key, entry)| PluginBuildInfo::from_lockfile(key, entry))
+        .collect()
+}
+
+/// Index build infos by file name, for merging with load-time metadata.
+pub(crate) fn by_filename(infos: &[PluginBuildInfo]) -> HashMap<String, PluginBuildInfo> {
+    infos
+        .iter()
+        .filter_map(|info| Some((info.filename.clone()?, info.clone())))
+        .collect()
+}
+
+/// The `major.minor` range of versions compatible with `host_version`,
+/// e.g. `>=0.11.0, <0.12.0`.
+pub fn compatible_range(host_version: &str) -> Option<semver::VersionReq> {
+    let host = semver::Version::parse(host_version).ok()?;
+    semver::VersionReq::parse(&format!("~{}.{}", host.major, host.minor)).ok()
+}
+
+fn check_version(
+    check: &'static str,
+    plugin_version: &str,
+    host_version: &str,
+) -> Option<CompatibilityIssue> {
+    // A host built without version info cannot judge compatibility.
+    let host = semver::Version::parse(host_version).ok()?;
+    let requirement = compatible_range(host_version)
+        .map(|req| req.to_string())
+        .unwrap_or_else(|| host_version.to_string());
+    let compatible = semver::Version::parse(plugin_version)
+        .map(|plugin| plugin.major == host.major && plugin.minor == host.minor)
+        .unwrap_or(false);
+    (!compatible).then(|| CompatibilityIssue {
+        check,
+        plugin_value: plugin_version.to_string(),
+        host_requirement: requirement,
+        severity: IssueSeverity::Error,
+    })
+}
+
+/// Extract the bare version from `rustc --version` output.
+fn parse_rustc_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .nth(1)
+        .filter(|v| v.chars().next().is_some_and(|c| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+/// Strip the tag or digest from an OCI lockfile reference so the resolver
+/// looks for the latest compatible version. Local and HTTP installs have no
+/// registry to consult.
+fn oci_base_reference(reference: &str) -> Option<String> {
+    if reference.starts_with("file://")
+        || reference.starts_with("http://")
+        || reference.starts_with("https://")
+    {
+        return None;
+    }
+    let without_digest = reference.split('@').next().unwrap_or(reference);
+    let last_segment_start = without_digest.rfind('/').map_or(0, |i| i + 1);
+    let base = match without_digest[last_segment_start..].find(':') {
+        Some(colon) => &without_digest[..last_segment_start + colon],
+        None => without_digest,
+    };
+    Some(base.to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn host() -> HostCompatibility {
+        HostCompatibility {
+            sdk_version: "0.11.2".to_string(),
+            core_version: "0.5.8".to_string(),
+            lib_version: "0.9.0".to_string(),
+            target_triple: "x86_64-unknown-linux-gnu".to_string(),
+            rustc_version: Some("1.85.0".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_from_metadata_parses_all_fields() {
+        let info = PluginBuildInfo::from_metadata(
+            "libdrasi_source_mock.so",
+            "sdk=0.11.0 core=0.5.8 plugin=0.2.1 target=x86_64-unknown-linux-gnu commit= built=",
+        );
+        assert_eq!(info.sdk_version.as_deref(), Some("0.11.0"));
+        assert_eq!(info.core_version.as_deref(), Some("0.5.8"));
+        assert_eq!(info.plugin_version.as_deref(), Some("0.2.1"));
+        assert_eq!(
+            info.target_triple.as_deref(),
+            Some("x86_64-unknown-linux-gnu")
+        );
+        assert_eq!(info.rustc_version, None);
+        assert_eq!(info.lib_version, None);
+    }
+
+    #[test]
+    fn test_compatible_plugin_has_no_issues() {
+        let info = PluginBuildInfo::from_metadata(
+            "libdrasi_source_mock.so",
+            "sdk=0.11.0 core=0.5.1 plugin=0.2.1 target=x86_64-unknown-linux-gnu rustc=1.85.0",
+        );
+        assert!(check_plugin(&host(), &info).is_empty());
+    }
+
+    #[test]
+    fn test_version_mismatch_reports_range() {
+        let info = PluginBuildInfo::from_metadata(
+            "libdrasi_source_mock.so",
+            "sdk=0.10.3 core=0.5.8 plugin=0.2.1",
+        );
+        let issues = check_plugin(&host(), &info);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].check, "sdk");
+        assert_eq!(issues[0].plugin_value, "0.10.3");
+        assert_eq!(issues[0].host_requirement, ">=0.11.0, <0.12.0");
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+    }
+
+    #[test]
+    fn test_target_and_platform_mismatch() {
+        let info = PluginBuildInfo::from_metadata(
+            "libdrasi_source_mock.so",
+            "sdk=0.11.0 target=aarch64-apple-darwin",
+        );
+        let issues = check_plugin(&host(), &info);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].check, "target");
+
+        let locked = PluginBuildInfo {
+            name: "source/mock:0.2.1".to_string(),
+            platform: Some("linux/arm64".to_string()),
+            ..Default::default()
+        };
+        let issues = check_plugin(&host(), &locked);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].host_requirement, "linux/amd64");
+    }
+
+    #[test]
+    fn test_rustc_mismatch_is_a_warning() {
+        let info = PluginBuildInfo::from_metadata("p.so", "sdk=0.11.0 rustc=1.80.0");
+        let report = CompatibilityReport::check(host(), vec![info]);
+        assert!(report.has_issues());
+        assert!(!report.has_errors());
+        assert_eq!(report.plugins[0].issues[0].check, "rustc");
+    }
+
+    #[test]
+    fn test_render_table_lists_each_finding() {
+        let report = CompatibilityReport::check(
+            host(),
+            vec![
+                PluginBuildInfo::from_metadata("good.so", "sdk=0.11.0 plugin=1.0.0"),
+                PluginBuildInfo::from_metadata("bad.so", "sdk=0.9.0 core=0.4.0 plugin=0.1.0"),
+            ],
+        );
+        let table = report.render_table();
+        assert!(table.contains("PLUGIN"));
+        assert!(table
+            .lines()
+            .any(|l| l.starts_with("good.so") && l.contains("ok")));
+        let bad_rows = table
+            .lines()
+            .filter(|l| l.starts_with("bad.so") && l.contains("INCOMPATIBLE"))
+            .count();
+        assert_eq!(bad_rows, 2);
+        assert_eq!(report.incompatible_files(), vec!["bad.so".to_string()]);
+    }
+
+    #[test]
+    fn test_merge_missing_prefers_existing_values() {
+        let mut loaded = PluginBuildInfo::from_metadata("libdrasi_source_pg.so", "sdk=0.11.0");
+        let locked = PluginBuildInfo {
+            name: "source/postgres:0.1.8".to_string(),
+            reference: Some("source/postgres:0.1.8".to_string()),
+            sdk_version: Some("0.10.0".to_string()),
+            lib_version: Some("0.9.0".to_string()),
+            ..Default::default()
+        };
+        loaded.merge_missing(&locked);
+        assert_eq!(loaded.sdk_version.as_deref(), Some("0.11.0"));
+        assert_eq!(loaded.lib_version.as_deref(), Some("0.9.0"));
+        assert_eq!(loaded.name, "source/postgres:0.1.8");
+    }
+
+    #[test]
+    fn test_oci_base_reference() {
+        assert_eq!(
+            oci_base_reference("source/postgres:0.1.8").as_deref(),
+            Some("source/postgres")
+        );
+        assert_eq!(
+            oci_base_reference("ghcr.io/acme/source/pg@sha256:abc").as_deref(),
+            Some("ghcr.io/acme/source/pg")
+        );
+        assert_eq!(
+            oci_base_reference("localhost:5000/source/pg:1.0").as_deref(),
+            Some("localhost:5000/source/pg")
+        );
+        assert_eq!(oci_base_reference("file:///tmp/plugin.so"), None);
+    }
+
+    #[test]
+    fn test_parse_rustc_version() {
+        assert_eq!(
+            parse_rustc_version("rustc 1.85.0 (4d91de4e4 2025-02-17)").as_deref(),
+            Some("1.85.0")
+        );
+        assert_eq!(parse_rustc_version("unknown"), None);
+    }
+}
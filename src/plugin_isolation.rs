@@ -0,0 +1,1116 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Containment of misbehaving cdylib plugins.
+//!
+//! Plugin components run in-process, so a plugin that panics or hangs inside a
+//! lifecycle call must not take the rest of the server down with it. Sources
+//! and reactions created from a dynamically loaded plugin are wrapped in
+//! [`IsolatedSource`] / [`IsolatedReaction`], which:
+//!
+//! - turn plugin panics into errors, so drasi-lib marks only the affected
+//!   component `Error`. The plugin SDK already catches panics at the FFI
+//!   boundary and the host SDK's proxies return them as errors, so those
+//!   errors are recognised and counted as panics. Faults outside `start` and
+//!   `stop`, which drasi-lib does not see as status changes, are reported to
+//!   the component graph, so the component's status and events show them;
+//! - run the proxies' blocking FFI calls (`start`, `stop`, `subscribe`,
+//!   `deprovision`, reaction `bootstrap`) on the blocking pool, bounding all
+//!   but `bootstrap` with a timeout, so a hung plugin cannot block a runtime
+//!   worker or the caller forever;
+//! - report failures to a per-plugin circuit breaker. Once a plugin fails
+//!   `maxFailures` times within `windowSecs`, the breaker opens and further
+//!   start attempts for any of that plugin's components are refused until
+//!   the window has elapsed (or the breaker is reset).
+//!
+//! Each server owns its breakers, shared through its
+//! [`crate::instance_registry::InstanceRegistry`] and
+//! [`crate::plugin_orchestrator::PluginOrchestrator`], which surfaces them on
+//! [`crate::plugin_orchestrator::PluginInfo`].
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::FutureExt;
+use log::{error, warn};
+
+use drasi_lib::channels::{ComponentStatus, QueryResult};
+use drasi_lib::component_graph::{ComponentUpdate, ComponentUpdateSender};
+use drasi_lib::config::SourceSubscriptionSettings;
+use drasi_lib::identity::IdentityProvider;
+use drasi_lib::reactions::BootstrapContext;
+use drasi_lib::{
+    BootstrapProvider, DispatchMode, Reaction, ReactionRecoveryPolicy, ReactionRuntimeContext,
+    Source, SourceRuntimeContext, SubscriptionResponse,
+};
+use drasi_plugin_sdk::ffi::FfiResult;
+
+use crate::config::PluginCircuitBreakerConfig;
+use crate::factories::SecretScope;
+
+/// Whether a plugin's circuit breaker currently allows component starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CircuitState {
+    /// Components may be started normally.
+    Closed,
+    /// Too many recent failures; component starts are refused.
+    Open,
+}
+
+/// Snapshot of a plugin's circuit breaker, exposed through `PluginInfo`.
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CircuitBreakerStatus {
+    /// Current breaker state.
+    pub state: CircuitState,
+    /// Failures counted within the current window.
+    pub recent_failures: u32,
+    /// Failures recorded since the server started.
+    pub total_failures: u64,
+    /// Panics caught since the server started (a subset of `total_failures`).
+    pub total_panics: u64,
+    /// Description of the most recent failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failure: Option<String>,
+    /// When the most recent failure happened.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_failure_at: Option<DateTime<Utc>>,
+    /// When the breaker last opened, if it is open.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opened_at: Option<DateTime<Utc>>,
+}
+
+impl Default for CircuitBreakerStatus {
+    fn default() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            recent_failures: 0,
+            total_failures: 0,
+            total_panics: 0,
+            last_failure: None,
+            last_failure_at: None,
+            opened_at: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct BreakerEntry {
+    failures: VecDeque<Instant>,
+    opened: Option<Instant>,
+    status: CircuitBreakerStatus,
+}
+
+impl BreakerEntry {
+    /// Drop failures outside the window and close the breaker once its open
+    /// period has elapsed.
+    fn refresh(&mut self, settings: &PluginCircuitBreakerConfig, now: Instant) {
+        let window = settings.window();
+        while self
+            .failures
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > window)
+        {
+            self.failures.pop_front();
+        }
+        if self
+            .opened
+            .is_some_and(|opened| now.duration_since(opened) > window)
+        {
+            self.opened = None;
+            self.failures.clear();
+            self.status.state = CircuitState::Closed;
+            self.status.opened_at = None;
+        }
+        self.status.recent_failures = u32::try_from(self.failures.len()).unwrap_or(u32::MAX);
+    }
+}
+
+/// Per-plugin circuit breakers keyed by plugin id (e.g. `source/postgres`).
+///
+/// Cheap to clone; clones share the same breakers.
+#[derive(Clone)]
+pub struct PluginCircuitBreakers {
+    settings: Arc<RwLock<PluginCircuitBreakerConfig>>,
+    entries: Arc<Mutex<HashMap<String, BreakerEntry>>>,
+}
+
+impl Default for PluginCircuitBreakers {
+    fn default() -> Self {
+        Self::new(PluginCircuitBreakerConfig::default())
+    }
+}
+
+impl PluginCircuitBreakers {
+    /// Create a breaker registry with the given thresholds.
+    pub fn new(settings: PluginCircuitBreakerConfig) -> Self {
+        Self {
+            settings: Arc::new(RwLock::new(settings)),
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Current breaker thresholds.
+    pub fn settings(&self) -> PluginCircuitBreakerConfig {
+        self.settings.read().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Record a failure for `plugin_id`, opening the breaker when the
+    /// threshold is reached.
+    pub fn record_failure(&self, plugin_id: &str, reason: &str, panicked: bool) {
+        self.record_failure_at(plugin_id, reason, panicked, Instant::now());
+    }
+
+    fn record_failure_at(&self, plugin_id: &str, reason: &str, panicked: bool, now: Instant) {
+        let settings = self.settings();
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        let entry = entries.entry(plugin_id.to_string()).or_default();
+        entry.refresh(&settings, now);
+
+        entry.failures.push_back(now);
+        entry.status.total_failures += 1;
+        if panicked {
+            entry.status.total_panics += 1;
+        }
+        entry.status.last_failure = Some(reason.to_string());
+        entry.status.last_failure_at = Some(Utc::now());
+        entry.status.recent_failures = u32::try_from(entry.failures.len()).unwrap_or(u32::MAX);
+
+        if entry.opened.is_none() && entry.status.recent_failures >= settings.max_failures {
+            entry.opened = Some(now);
+            entry.status.state = CircuitState::Open;
+            entry.status.opened_at = Some(Utc::now());
+            error!(
+                "Circuit breaker opened for plugin '{plugin_id}' after {} failure(s) within {}s; \
+                 its components will not be restarted until the breaker closes",
+                entry.status.recent_failures, settings.window_secs
+            );
+        }
+    }
+
+    /// Return an error when the breaker for `plugin_id` is open.
+    pub fn check(&self, plugin_id: &str) -> Result<()> {
+        self.check_at(plugin_id, Instant::now())
+    }
+
+    fn check_at(&self, plugin_id: &str, now: Instant) -> Result<()> {
+        let settings = self.settings();
+        let Ok(mut entries) = self.entries.lock() else {
+            return Ok(());
+        };
+        let Some(entry) = entries.get_mut(plugin_id) else {
+            return Ok(());
+        };
+        entry.refresh(&settings, now);
+        if entry.status.state == CircuitState::Open {
+            return Err(anyhow!(
+                "Circuit breaker is open for plugin '{plugin_id}' after repeated failures \
+                 (last: {})",
+                entry.status.last_failure.as_deref().unwrap_or("unknown")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Current breaker status for `plugin_id` (closed if it never failed).
+    pub fn status(&self, plugin_id: &str) -> CircuitBreakerStatus {
+        let settings = self.settings();
+        let Ok(mut entries) = self.entries.lock() else {
+            return CircuitBreakerStatus::default();
+        };
+        match entries.get_mut(plugin_id) {
+            Some(entry) => {
+                entry.refresh(&settings, Instant::now());
+                entry.status.clone()
+            }
+            None => CircuitBreakerStatus::default(),
+        }
+    }
+
+    /// Close the breaker for `plugin_id` and clear its failure history.
+    pub fn reset(&self, plugin_id: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.remove(plugin_id);
+        }
+    }
+}
+
+/// Extract a readable message from a caught panic payload.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Errors with which the host SDK's proxies report that the thread they ran
+/// an FFI call on panicked.
+const PROXY_THREAD_PANICS: &[&str] = &[
+    "Thread panicked",
+    "ChangeReceiverProxy::new thread panicked",
+    "BootstrapReceiverProxy::new thread panicked",
+];
+
+/// The message [`FfiResult::from_panic`] starts a caught panic with.
+///
+/// An `FfiResult` carries the same error code for a panic as for an error;
+/// the plugin SDK marks a panic caught at the FFI boundary only by encoding
+/// it with `from_panic`. The prefix is taken from that encoding, so it
+/// follows the SDK.
+fn ffi_panic_prefix() -> &'static str {
+    static PREFIX: OnceLock<String> = OnceLock::new();
+    PREFIX.get_or_init(|| {
+        // SAFETY: the result is freshly created and consumed once.
+        unsafe { FfiResult::from_panic(Box::new("")).into_result() }
+            .err()
+            .unwrap_or_default()
+    })
+}
+
+/// Whether `error` is a panic the host SDK's proxy reported for an FFI call:
+/// its thread panicked, or the call returned an `FfiResult` encoding a
+/// caught panic.
+///
+/// The proxies return the FFI result's message as the whole error, so only
+/// that is matched; an ordinary error that merely mentions a panic is not
+/// counted as one.
+fn is_plugin_panic(error: &anyhow::Error) -> bool {
+    let message = error.to_string();
+    PROXY_THREAD_PANICS.contains(&message.as_str()) || message.starts_with(ffi_panic_prefix())
+}
+
+/// Shared guard state for one plugin-backed component.
+struct Guard {
+    plugin_id: String,
+    component_id: String,
+    breakers: PluginCircuitBreakers,
+    /// Set when a call panicked, hung, or failed in a way that counts
+    /// against the breaker; the component reports `Error` until it next
+    /// starts successfully.
+    faulted: AtomicBool,
+    /// The component graph's update channel, captured in `initialize`.
+    updates: Mutex<Option<ComponentUpdateSender>>,
+}
+
+impl Guard {
+    fn new(plugin_id: String, component_id: String, breakers: PluginCircuitBreakers) -> Self {
+        Self {
+            plugin_id,
+            component_id,
+            breakers,
+            faulted: AtomicBool::new(false),
+            updates: Mutex::new(None),
+        }
+    }
+
+    /// Report status changes through `updates` from now on.
+    fn attach(&self, updates: ComponentUpdateSender) {
+        if let Ok(mut current) = self.updates.lock() {
+            *current = Some(updates);
+        }
+    }
+
+    /// Mark the component faulted, record the failure with the breaker and
+    /// return it as an error.
+    ///
+    /// drasi-lib records a failed `start` or `stop` as `Error` itself; any
+    /// other fault is sent to the component graph here, which emits the
+    /// `Error` event that restart supervision reacts to.
+    fn fault(&self, op: &str, reason: String, panicked: bool) -> anyhow::Error {
+        self.faulted.store(true, Ordering::SeqCst);
+        if panicked {
+            error!("Plugin '{}' {reason}", self.plugin_id);
+        } else {
+            warn!("Plugin '{}' {reason}", self.plugin_id);
+        }
+        self.breakers
+            .record_failure(&self.plugin_id, &reason, panicked);
+        let message = format!("Plugin '{}' {reason}", self.plugin_id);
+        if op != "start" && op != "stop" {
+            self.report_error(message.clone());
+        }
+        anyhow!(message)
+    }
+
+    /// Send an `Error` status change for the component to the graph.
+    fn report_error(&self, message: String) {
+        let Some(updates) = self.updates.lock().ok().and_then(|u| u.clone()) else {
+            return;
+        };
+        let update = ComponentUpdate::Status {
+            component_id: self.component_id.clone(),
+            status: ComponentStatus::Error,
+            message: Some(message),
+        };
+        // The channel applies backpressure; never block the faulting call on it.
+        if let Err(tokio::sync::mpsc::error::TrySendError::Full(update)) = updates.try_send(update)
+        {
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                runtime.spawn(async move {
+                    let _ = updates.send(update).await;
+                });
+            }
+        }
+    }
+
+    fn on_panic(&self, op: &str, message: &str) -> anyhow::Error {
+        let reason = format!("{op} of '{}' panicked: {message}", self.component_id);
+        self.fault(op, reason, true)
+    }
+
+    /// Classify the result of a plugin call. Panics reported by the host
+    /// SDK always count against the breaker; other errors only when
+    /// `count_errors` is set.
+    fn settle<T>(&self, op: &str, count_errors: bool, result: Result<T>) -> Result<T> {
+        match result {
+            Ok(value) => Ok(value),
+            Err(e) if is_plugin_panic(&e) => Err(self.on_panic(op, &format!("{e:#}"))),
+            Err(e) if count_errors => {
+                let reason = format!("{op} of '{}' failed: {e:#}", self.component_id);
+                Err(self.fault(op, reason, false))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Run a fallible plugin call that does not block, converting panics
+    /// into errors.
+    async fn call<T>(
+        &self,
+        op: &str,
+        count_errors: bool,
+        fut: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        match AssertUnwindSafe(fut).catch_unwind().await {
+            Ok(result) => self.settle(op, count_errors, result),
+            Err(payload) => Err(self.on_panic(op, &panic_message(payload.as_ref()))),
+        }
+    }
+
+    /// Run a plugin call that may block its thread.
+    ///
+    /// The host SDK's proxies make FFI calls synchronously inside their async
+    /// methods, so a hung plugin never yields. The call runs on the blocking
+    /// pool instead of a runtime worker, and `timeout`, when set, bounds the
+    /// wait for it. A call that times out is left running on its blocking
+    /// thread; it cannot be cancelled.
+    async fn call_blocking<T, F, Fut>(
+        &self,
+        op: &str,
+        timeout: Option<Duration>,
+        count_errors: bool,
+        call: F,
+    ) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T>>,
+    {
+        let runtime = tokio::runtime::Handle::current();
+        let task = tokio::task::spawn_blocking(move || runtime.block_on(call()));
+        let joined = match timeout {
+            Some(limit) => match tokio::time::timeout(limit, task).await {
+                Ok(joined) => joined,
+                Err(_) => {
+                    let reason = format!(
+                        "{op} of '{}' timed out after {}s",
+                        self.component_id,
+                        limit.as_secs()
+                    );
+                    return Err(self.fault(op, reason, false));
+                }
+            },
+            None => task.await,
+        };
+        match joined {
+            Ok(result) => self.settle(op, count_errors, result),
+            Err(e) if e.is_panic() => {
+                Err(self.on_panic(op, &panic_message(e.into_panic().as_ref())))
+            }
+            Err(e) => Err(anyhow!(
+                "Plugin '{}' {op} of '{}' was cancelled: {e}",
+                self.plugin_id,
+                self.component_id
+            )),
+        }
+    }
+
+    /// Run an infallible plugin call, logging and recording any panic.
+    async fn call_infallible<T>(&self, op: &str, fallback: T, fut: impl Future<Output = T>) -> T {
+        match AssertUnwindSafe(fut).catch_unwind().await {
+            Ok(value) => value,
+            Err(payload) => {
+                let _ = self.on_panic(op, &panic_message(payload.as_ref()));
+                fallback
+            }
+        }
+    }
+
+    /// Run a synchronous plugin call, logging and recording any panic.
+    fn call_sync<T>(&self, op: &str, fallback: T, f: impl FnOnce() -> T) -> T {
+        match std::panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(value) => value,
+            Err(payload) => {
+                let _ = self.on_panic(op, &panic_message(payload.as_ref()));
+                fallback
+            }
+        }
+    }
+
+    fn call_timeout(&self) -> Duration {
+        self.breakers.settings().call_timeout()
+    }
+
+    /// Breaker check plus start under the configured timeout.
+    async fn start<F, Fut>(&self, call: F) -> Result<()>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>>,
+    {
+        self.breakers.check(&self.plugin_id)?;
+        self.call_blocking("start", Some(self.call_timeout()), true, call)
+            .await?;
+        self.faulted.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn status(&self, fut: impl Future<Output = ComponentStatus>) -> ComponentStatus {
+        let status = self
+            .call_infallible("status", ComponentStatus::Error, fut)
+            .await;
+        if self.faulted.load(Ordering::SeqCst) {
+            ComponentStatus::Error
+        } else {
+            status
+        }
+    }
+}
+
+/// A plugin-backed [`Source`] with panic containment and breaker accounting.
+pub struct IsolatedSource {
+    inner: Arc<dyn Source + 'static>,
+    guard: Guard,
+    /// Keeps the component's secrets resolvable while it exists.
+    secret_scope: Option<SecretScope<'static>>,
+}
+
+impl IsolatedSource {
+    /// Wrap `inner`, attributing failures to `plugin_id`.
+    pub fn new(
+        inner: Box<dyn Source + 'static>,
+        plugin_id: impl Into<String>,
+        breakers: &PluginCircuitBreakers,
+    ) -> Self {
+        let component_id = inner.id().to_string();
+        Self {
+            inner: Arc::from(inner),
+            guard: Guard::new(plugin_id.into(), component_id, breakers.clone()),
+            secret_scope: None,
+        }
+    }
+
+    /// Keep `scope`, which resolves the secrets of the config `inner` was
+    /// created from, open until this component is dropped.
+    pub fn with_secret_scope(mut self, scope: SecretScope<'static>) -> Self {
+        self.secret_scope = Some(scope);
+        self
+    }
+}
+
+#[async_trait]
+impl Source for IsolatedSource {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn type_name(&self) -> &str {
+        self.inner.type_name()
+    }
+
+    fn properties(&self) -> HashMap<String, serde_json::Value> {
+        self.guard
+            .call_sync("properties", HashMap::new(), || self.inner.properties())
+    }
+
+    fn dispatch_mode(&self) -> DispatchMode {
+        self.inner.dispatch_mode()
+    }
+
+    fn auto_start(&self) -> bool {
+        self.inner.auto_start()
+    }
+
+    fn supports_replay(&self) -> bool {
+        self.inner.supports_replay()
+    }
+
+    fn describe_schema(&self) -> Option<drasi_lib::schema::SourceSchema> {
+        self.guard
+            .call_sync("describe_schema", None, || self.inner.describe_schema())
+    }
+
+    async fn start(&self) -> Result<()> {
+        let _call = self.secret_scope.as_ref().map(SecretScope::enter);
+        let inner = self.inner.clone();
+        self.guard
+            .start(move || async move { inner.start().await })
+            .await
+    }
+
+    async fn stop(&self) -> Result<()> {
+        let _call = self.secret_scope.as_ref().map(SecretScope::enter);
+        let inner = self.inner.clone();
+        self.guard
+            .call_blocking(
+                "stop",
+                Some(self.guard.call_timeout()),
+                false,
+                move || async move { inner.stop().await },
+            )
+            .await
+    }
+
+    async fn status(&self) -> ComponentStatus {
+        self.guard.status(self.inner.status()).await
+    }
+
+    async fn subscribe(
+        &self,
+        settings: SourceSubscriptionSettings,
+    ) -> Result<SubscriptionResponse> {
+        let inner = self.inner.clone();
+        self.guard
+            .call_blocking(
+                "subscribe",
+                Some(self.guard.call_timeout()),
+                true,
+                move || async move { inner.subscribe(settings).await },
+            )
+            .await
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self.inner.as_any()
+    }
+
+    async fn deprovision(&self) -> Result<()> {
+        let inner = self.inner.clone();
+        self.guard
+            .call_blocking(
+                "deprovision",
+                Some(self.guard.call_timeout()),
+                false,
+                move || async move { inner.deprovision().await },
+            )
+            .await
+    }
+
+    async fn initialize(&self, context: SourceRuntimeContext) {
+        self.guard.attach(context.update_tx.clone());
+        self.guard
+            .call_infallible("initialize", (), self.inner.initialize(context))
+            .await
+    }
+
+    async fn set_bootstrap_provider(&self, provider: Box<dyn BootstrapProvider + 'static>) {
+        self.guard
+            .call_infallible(
+                "set_bootstrap_provider",
+                (),
+                self.inner.set_bootstrap_provider(provider),
+            )
+            .await
+    }
+
+    async fn remove_position_handle(&self, query_id: &str) {
+        self.guard
+            .call_infallible(
+                "remove_position_handle",
+                (),
+                self.inner.remove_position_handle(query_id),
+            )
+            .await
+    }
+
+    async fn on_subscriptions_complete(&self) {
+        self.guard
+            .call_infallible(
+                "on_subscriptions_complete",
+                (),
+                self.inner.on_subscriptions_complete(),
+            )
+            .await
+    }
+
+    async fn set_identity_provider(&self, provider: std::sync::Arc<dyn IdentityProvider>) {
+        self.guard
+            .call_infallible(
+                "set_identity_provider",
+                (),
+                self.inner.set_identity_provider(provider),
+            )
+            .await
+    }
+}
+
+/// A plugin-backed [`Reaction`] with panic containment and breaker accounting.
+pub struct IsolatedReaction {
+    inner: Arc<dyn Reaction + 'static>,
+    guard: Guard,
+    /// Keeps the component's secrets resolvable while it exists.
+    secret_scope: Option<SecretScope<'static>>,
+}
+
+impl IsolatedReaction {
+    /// Wrap `inner`, attributing failures to `plugin_id`.
+    pub fn new(
+        inner: Box<dyn Reaction + 'static>,
+        plugin_id: impl Into<String>,
+        breakers: &PluginCircuitBreakers,
+    ) -> Self {
+        let component_id = inner.id().to_string();
+        Self {
+            inner: Arc::from(inner),
+            guard: Guard::new(plugin_id.into(), component_id, breakers.clone()),
+            secret_scope: None,
+        }
+    }
+
+    /// Keep `scope`, which resolves the secrets of the config `inner` was
+    /// created from, open until this component is dropped.
+    pub fn with_secret_scope(mut self, scope: SecretScope<'static>) -> Self {
+        self.secret_scope = Some(scope);
+        self
+    }
+}
+
+#[async_trait]
+impl Reaction for IsolatedReaction {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn type_name(&self) -> &str {
+        self.inner.type_name()
+    }
+
+    fn properties(&self) -> HashMap<String, serde_json::Value> {
+        self.guard
+            .call_sync("properties", HashMap::new(), || self.inner.properties())
+    }
+
+    fn query_ids(&self) -> Vec<String> {
+        self.inner.query_ids()
+    }
+
+    fn auto_start(&self) -> bool {
+        self.inner.auto_start()
+    }
+
+    async fn initialize(&self, context: ReactionRuntimeContext) {
+        self.guard.attach(context.update_tx.clone());
+        self.guard
+            .call_infallible("initialize", (), self.inner.initialize(context))
+            .await
+    }
+
+    async fn start(&self) -> Result<()> {
+        let _call = self.secret_scope.as_ref().map(SecretScope::enter);
+        let inner = self.inner.clone();
+        self.guard
+            .start(move || async move { inner.start().await })
+            .await
+    }
+
+    async fn stop(&self) -> Result<()> {
+        let _call = self.secret_scope.as_ref().map(SecretScope::enter);
+        let inner = self.inner.clone();
+        self.guard
+            .call_blocking(
+                "stop",
+                Some(self.guard.call_timeout()),
+                false,
+                move || async move { inner.stop().await },
+            )
+            .await
+    }
+
+    async fn status(&self) -> ComponentStatus {
+        self.guard.status(self.inner.status()).await
+    }
+
+    async fn enqueue_query_result(&self, result: QueryResult) -> Result<()> {
+        self.guard
+            .call(
+                "enqueue_query_result",
+                true,
+                self.inner.enqueue_query_result(result),
+            )
+            .await
+    }
+
+    async fn deprovision(&self) -> Result<()> {
+        let inner = self.inner.clone();
+        self.guard
+            .call_blocking(
+                "deprovision",
+                Some(self.guard.call_timeout()),
+                false,
+                move || async move { inner.deprovision().await },
+            )
+            .await
+    }
+
+    async fn set_identity_provider(&self, provider: std::sync::Arc<dyn IdentityProvider>) {
+        self.guard
+            .call_infallible(
+                "set_identity_provider",
+                (),
+                self.inner.set_identity_provider(provider),
+            )
+            .await
+    }
+
+    fn is_durable(&self) -> bool {
+        self.inner.is_durable()
+    }
+
+    fn needs_snapshot_on_fresh_start(&self) -> bool {
+        self.inner.needs_snapshot_on_fresh_start()
+    }
+
+    fn default_recovery_policy(&self) -> ReactionRecoveryPolicy {
+        self.inner.default_recovery_policy()
+    }
+
+    async fn bootstrap(&self, ctx: BootstrapContext) -> Result<()> {
+        let inner = self.inner.clone();
+        self.guard
+            .call_blocking("bootstrap", None, false, move || async move {
+                inner.bootstrap(ctx).await
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn breakers(max_failures: u32, window_secs: u64) -> PluginCircuitBreakers {
+        PluginCircuitBreakers::new(PluginCircuitBreakerConfig {
+            max_failures,
+            window_secs,
+            call_timeout_secs: 1,
+        })
+    }
+
+    #[test]
+    fn test_breaker_opens_after_threshold() {
+        let b = breakers(3, 60);
+        let now = Instant::now();
+        b.record_failure_at("source/x", "boom", false, now);
+        b.record_failure_at("source/x", "boom", true, now);
+        assert!(b.check_at("source/x", now).is_ok());
+        b.record_failure_at("source/x", "boom", false, now);
+
+        assert!(b.check_at("source/x", now).is_err());
+        let status = b.status("source/x");
+        assert_eq!(status.state, CircuitState::Open);
+        assert_eq!(status.total_failures, 3);
+        assert_eq!(status.total_panics, 1);
+        assert!(status.opened_at.is_some());
+    }
+
+    #[test]
+    fn test_failures_outside_window_are_forgotten() {
+        let b = breakers(2, 10);
+        let start = Instant::now();
+        b.record_failure_at("reaction/y", "boom", false, start);
+        b.record_failure_at("reaction/y", "boom", false, start + Duration::from_secs(11));
+        assert!(b
+            .check_at("reaction/y", start + Duration::from_secs(11))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_breaker_closes_after_window() {
+        let b = breakers(1, 10);
+        let start = Instant::now();
+        b.record_failure_at("source/x", "boom", false, start);
+        assert!(b.check_at("source/x", start).is_err());
+        assert!(b
+            .check_at("source/x", start + Duration::from_secs(11))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_reset_clears_state() {
+        let b = breakers(1, 60);
+        b.record_failure("source/x", "boom", false);
+        assert!(b.check("source/x").is_err());
+        b.reset("source/x");
+        assert!(b.check("source/x").is_ok());
+        assert_eq!(b.status("source/x").total_failures, 0);
+    }
+
+    #[test]
+    fn test_breakers_are_per_plugin() {
+        let b = breakers(1, 60);
+        b.record_failure("source/x", "boom", false);
+        assert!(b.check("source/x").is_err());
+        assert!(b.check("source/other").is_ok());
+        assert_eq!(b.status("source/other").state, CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_guard_converts_panic_to_error() {
+        let b = breakers(5, 60);
+        let guard = Guard::new("source/x".to_string(), "s1".to_string(), b.clone());
+        let result: Result<()> = guard
+            .call("start", true, async { panic!("plugin exploded") })
+            .await;
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("plugin exploded"), "{err}");
+        assert_eq!(b.status("source/x").total_panics, 1);
+        assert_eq!(
+            guard.status(async { ComponentStatus::Running }).await,
+            ComponentStatus::Error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_guard_start_refused_when_open() {
+        let b = breakers(1, 60);
+        b.record_failure("source/x", "boom", false);
+        let guard = Guard::new("source/x".to_string(), "s1".to_string(), b.clone());
+        let result = guard.start(|| async { Ok(()) }).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Circuit breaker is open"));
+    }
+
+    #[tokio::test]
+    async fn test_guard_errors_only_counted_when_requested() {
+        let b = breakers(5, 60);
+        let guard = Guard::new("source/x".to_string(), "s1".to_string(), b.clone());
+        let _ = guard
+            .call::<()>("stop", false, async { Err(anyhow!("nope")) })
+            .await;
+        assert_eq!(b.status("source/x").total_failures, 0);
+        let _ = guard
+            .call::<()>("start", true, async { Err(anyhow!("nope")) })
+            .await;
+        assert_eq!(b.status("source/x").total_failures, 1);
+        assert_eq!(b.status("source/x").total_panics, 0);
+    }
+
+    /// How [`ProxyLikeSource`] misbehaves.
+    enum Fault {
+        /// The FFI call's thread panics, as the proxy reports it.
+        ThreadPanic,
+        /// The vtable caught a panic and returned it as an error.
+        VtablePanic,
+        /// The FFI call blocks until the test releases it.
+        Hang(Mutex<std::sync::mpsc::Receiver<()>>),
+        /// Starts cleanly; only `subscribe` fails.
+        SubscribeOnly,
+    }
+
+    /// A source shaped like the host SDK's `SourceProxy`: its async methods
+    /// make blocking calls and report plugin panics as errors.
+    struct ProxyLikeSource {
+        fault: Fault,
+    }
+
+    impl ProxyLikeSource {
+        fn ffi_call(&self) -> Result<()> {
+            match &self.fault {
+                Fault::ThreadPanic => std::thread::spawn(|| -> Result<()> { panic!("boom") })
+                    .join()
+                    .map_err(|_| anyhow!("Thread panicked"))?,
+                Fault::VtablePanic => Err(anyhow!("plugin panic: boom")),
+                Fault::Hang(release) => {
+                    let _ = release
+                        .lock()
+                        .unwrap()
+                        .recv_timeout(Duration::from_secs(30));
+                    Ok(())
+                }
+                Fault::SubscribeOnly => Ok(()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Source for ProxyLikeSource {
+        fn id(&self) -> &str {
+            "s1"
+        }
+
+        fn type_name(&self) -> &str {
+            "proxy-like"
+        }
+
+        fn properties(&self) -> HashMap<String, serde_json::Value> {
+            HashMap::new()
+        }
+
+        async fn start(&self) -> Result<()> {
+            self.ffi_call()
+        }
+
+        async fn stop(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn status(&self) -> ComponentStatus {
+            ComponentStatus::Running
+        }
+
+        async fn subscribe(
+            &self,
+            _settings: SourceSubscriptionSettings,
+        ) -> Result<SubscriptionResponse> {
+            Err(anyhow!("Subscribe returned null"))
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        async fn initialize(&self, _context: SourceRuntimeContext) {}
+    }
+
+    fn isolated(fault: Fault, b: &PluginCircuitBreakers) -> IsolatedSource {
+        IsolatedSource::new(Box::new(ProxyLikeSource { fault }), "source/x", b)
+    }
+
+    #[tokio::test]
+    async fn test_proxy_panics_count_as_panics() {
+        let b = breakers(5, 60);
+        for fault in [Fault::ThreadPanic, Fault::VtablePanic] {
+            let source = isolated(fault, &b);
+            let err = source.start().await.unwrap_err().to_string();
+            assert!(err.contains("panicked"), "{err}");
+            assert_eq!(source.status().await, ComponentStatus::Error);
+        }
+        let status = b.status("source/x");
+        assert_eq!(status.total_panics, 2);
+        assert_eq!(status.total_failures, 2);
+    }
+
+    fn subscription_settings() -> SourceSubscriptionSettings {
+        SourceSubscriptionSettings {
+            source_id: "s1".to_string(),
+            enable_bootstrap: false,
+            query_id: "q1".to_string(),
+            nodes: Default::default(),
+            relations: Default::default(),
+            resume_from: None,
+            request_position_handle: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_proxy_subscribe_failure_marks_error() {
+        let b = breakers(5, 60);
+        let source = isolated(Fault::VtablePanic, &b);
+        assert!(source.subscribe(subscription_settings()).await.is_err());
+        let status = b.status("source/x");
+        assert_eq!(status.total_failures, 1);
+        assert_eq!(status.total_panics, 0);
+        assert_eq!(source.status().await, ComponentStatus::Error);
+    }
+
+    #[test]
+    fn test_only_panic_results_count_as_panics() {
+        let caught = unsafe { FfiResult::from_panic(Box::new("boom")).into_result() };
+        assert!(is_plugin_panic(&anyhow!(caught.unwrap_err())));
+        assert!(is_plugin_panic(&anyhow!("Thread panicked")));
+
+        assert!(!is_plugin_panic(&anyhow!("Subscribe returned null")));
+        assert!(!is_plugin_panic(&anyhow!(
+            "query failed: upstream reported plugin panic: boom"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_proxy_hang_times_out_without_blocking_runtime() {
+        let b = breakers(5, 60);
+        let (release, hang) = std::sync::mpsc::channel();
+        let source = isolated(Fault::Hang(Mutex::new(hang)), &b);
+
+        let started = Instant::now();
+        let err = source.start().await.unwrap_err().to_string();
+        assert!(err.contains("timed out"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(b.status("source/x").total_failures, 1);
+        assert_eq!(source.status().await, ComponentStatus::Error);
+        release.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fault_after_start_reaches_component_graph() {
+        let b = breakers(5, 60);
+        let core = drasi_lib::DrasiLib::builder()
+            .with_id("isolation-test")
+            .build()
+            .await
+            .unwrap();
+        core.start().await.unwrap();
+        core.add_source(isolated(Fault::SubscribeOnly, &b))
+            .await
+            .unwrap();
+
+        // Adding the source to a running instance started it. drasi-lib
+        // keeps the added source to itself; a second wrapper for the same
+        // id, initialized with the graph's update channel, faults the way
+        // the added one would when a query subscribes.
+        let source = isolated(Fault::SubscribeOnly, &b);
+        let updates = core.component_graph().read().await.update_sender();
+        source
+            .initialize(SourceRuntimeContext::new(
+                "isolation-test",
+                "s1",
+                None,
+                updates,
+                None,
+            ))
+            .await;
+        assert!(source.subscribe(subscription_settings()).await.is_err());
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while core.get_source_status("s1").await.unwrap() != ComponentStatus::Error {
+            assert!(Instant::now() < deadline, "source never reported Error");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+}
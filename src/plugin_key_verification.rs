@@ -0,0 +1,411 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Offline plugin signature verification with locally configured public keys.
+//!
+//! Complements the cosign keyless flow (which needs the OCI registry at every
+//! startup) for plugins signed with an in-house key. Signatures are produced
+//! with `cosign sign-blob --key` (or `openssl dgst -sha256 -sign`) over the
+//! plugin file and stored either:
+//!
+//! - next to the plugin as `<plugin filename>.sig`, or
+//! - in `plugins.lock` as a `key_signature` entry on the plugin's table.
+//!
+//! Both base64 (cosign's default output) and raw DER signatures are accepted.
+//! RSA and ECDSA keys sign a SHA-256 digest; Ed25519 keys sign the file
+//! contents directly.
+
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{Id, PKey, Public};
+use openssl::sign::Verifier;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::TrustedKey;
+
+/// File extension of detached signature files stored next to a plugin.
+pub const SIGNATURE_FILE_EXTENSION: &str = "sig";
+
+/// Lockfile key holding a base64 signature for a plugin entry.
+const LOCKFILE_SIGNATURE_KEY: &str = "key_signature";
+
+/// A trusted public key parsed from configuration.
+pub struct LoadedTrustedKey {
+    /// Name used in logs and verification results.
+    pub name: String,
+    key: PKey<Public>,
+}
+
+impl std::fmt::Debug for LoadedTrustedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadedTrustedKey")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Outcome of verifying one plugin file against the trusted keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySignatureStatus {
+    /// The signature matches the file under the named trusted key.
+    Verified { key_name: String },
+    /// A signature exists but no trusted key validates it.
+    Invalid(String),
+    /// No signature file or lockfile signature was found.
+    Unsigned,
+}
+
+/// Parse the configured trusted keys.
+///
+/// Relative `publicKeyPath` entries are resolved against `base_dir` (the
+/// directory containing the config file). A key that cannot be read or parsed
+/// is an error: silently ignoring it would quietly reject every plugin it
+/// signed.
+pub fn load_trusted_keys(keys: &[TrustedKey], base_dir: &Path) -> Result<Vec<LoadedTrustedKey>> {
+    keys.iter()
+        .map(|k| {
+            let pem = match (&k.public_key, &k.public_key_path) {
+                (Some(inline), None) => inline.clone(),
+                (None, Some(path)) => {
+                    let path = resolve_key_path(base_dir, path);
+                    std::fs::read_to_string(&path).with_context(|| {
+                        format!(
+                            "Failed to read public key '{}' from {}",
+                            k.name,
+                            path.display()
+                        )
+                    })?
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "Trusted key '{}' must set exactly one of publicKey or publicKeyPath",
+                        k.name
+                    ))
+                }
+            };
+            let key = PKey::public_key_from_pem(pem.as_bytes()).with_context(|| {
+                format!("Trusted key '{}' is not a valid PEM public key", k.name)
+            })?;
+            Ok(LoadedTrustedKey {
+                name: k.name.clone(),
+                key,
+            })
+        })
+        .collect()
+}
+
+fn resolve_key_path(base_dir: &Path, path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        path
+    } else {
+        base_dir.join(path)
+    }
+}
+
+/// Verify `data` against `signature` with each trusted key in turn.
+pub fn verify_signature(
+    data: &[u8],
+    signature: &[u8],
+    keys: &[LoadedTrustedKey],
+) -> KeySignatureStatus {
+    let signature = decode_signature(signature);
+    for key in keys {
+        match verify_with_key(&key.key, data, &signature) {
+            Ok(true) => {
+                return KeySignatureStatus::Verified {
+                    key_name: key.name.clone(),
+                }
+            }
+            Ok(false) => {}
+            Err(e) => debug!("Key '{}' could not check signature: {e}", key.name),
+        }
+    }
+    KeySignatureStatus::Invalid("signature does not match any trusted key".to_string())
+}
+
+fn verify_with_key(key: &PKey<Public>, data: &[u8], signature: &[u8]) -> Result<bool> {
+    let ok = if key.id() == Id::ED25519 {
+        let mut verifier = Verifier::new_without_digest(key)?;
+        verifier.verify_oneshot(signature, data)?
+    } else {
+        let mut verifier = Verifier::new(MessageDigest::sha256(), key)?;
+        verifier.update(data)?;
+        verifier.verify(signature)?
+    };
+    Ok(ok)
+}
+
+/// Signatures are usually base64 text (cosign output); fall back to treating
+/// the bytes as a raw DER/binary signature when they don't decode.
+fn decode_signature(raw: &[u8]) -> Vec<u8> {
+    let text: String = String::from_utf8_lossy(raw)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    match openssl::base64::decode_block(&text) {
+        Ok(decoded) if !decoded.is_empty() => decoded,
+        _ => raw.to_vec(),
+    }
+}
+
+/// Read `key_signature` values from `plugins.lock`, keyed by plugin filename.
+///
+/// The typed lockfile model does not carry this field, so the file is read as
+/// raw TOML. Missing or unparseable lockfiles yield an empty map.
+pub fn lockfile_key_signatures(plugins_dir: &Path) -> HashMap<String, String> {
+    let path = plugins_dir.join("plugins.lock");
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
+    let Ok(doc) = content.parse::<toml::Table>() else {
+        debug!("Could not parse {} for key signatures", path.display());
+        return HashMap::new();
+    };
+    let Some(plugins) = doc.get("plugins").and_then(|p| p.as_table()) else {
+        return HashMap::new();
+    };
+    plugins
+        .values()
+        .filter_map(|entry| {
+            let entry = entry.as_table()?;
+            let filename = entry.get("filename")?.as_str()?;
+            let signature = entry.get(LOCKFILE_SIGNATURE_KEY)?.as_str()?;
+            Some((filename.to_string(), signature.to_string()))
+        })
+        .collect()
+}
+
+/// Verify every plugin file in `plugins_dir` against the trusted keys.
+///
+/// `is_plugin_file` selects which directory entries are plugins. A sidecar
+/// `.sig` file takes precedence over a lockfile signature for the same plugin.
+pub fn verify_plugins_dir(
+    plugins_dir: &Path,
+    keys: &[LoadedTrustedKey],
+    is_plugin_file: impl Fn(&str) -> bool,
+) -> Result<HashMap<String, KeySignatureStatus>> {
+    let lockfile_signatures = lockfile_key_signatures(plugins_dir);
+    let mut results = HashMap::new();
+
+    let entries = std::fs::read_dir(plugins_dir)
+        .with_context(|| format!("Failed to read plugins directory {}", plugins_dir.display()))?;
+    for entry in entries.flatten() {
+        let filename = entry.file_name().to_string_lossy().to_string();
+        if !entry.path().is_file() || !is_plugin_file(&filename) {
+            continue;
+        }
+
+        let sig_path = plugins_dir.join(format!("{filename}.{SIGNATURE_FILE_EXTENSION}"));
+        let signature = match std::fs::read(&sig_path) {
+            Ok(bytes) => Some(bytes),
+            Err(_) => lockfile_signatures
+                .get(&filename)
+                .map(|s| s.as_bytes().to_vec()),
+        };
+
+        let status = match signature {
+            None => KeySignatureStatus::Unsigned,
+            Some(signature) => match std::fs::read(entry.path()) {
+                Ok(data) => verify_signature(&data, &signature, keys),
+                Err(e) => KeySignatureStatus::Invalid(format!("failed to read plugin file: {e}")),
+            },
+        };
+        results.insert(filename, status);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::nid::Nid;
+    use openssl::pkey::Private;
+    use openssl::sign::Signer;
+
+    fn ec_keypair() -> (PKey<Private>, String) {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let pem = String::from_utf8(key.public_key_to_pem().unwrap()).unwrap();
+        (key, pem)
+    }
+
+    fn sign_b64(key: &PKey<Private>, data: &[u8]) -> String {
+        let mut signer = Signer::new(MessageDigest::sha256(), key).unwrap();
+        signer.update(data).unwrap();
+        openssl::base64::encode_block(&signer.sign_to_vec().unwrap())
+    }
+
+    fn trusted(name: &str, pem: &str) -> TrustedKey {
+        TrustedKey {
+            name: name.to_string(),
+            public_key: Some(pem.to_string()),
+            public_key_path: None,
+        }
+    }
+
+    fn is_plugin(name: &str) -> bool {
+        name.starts_with("libdrasi_") && !name.ends_with(".sig")
+    }
+
+    #[test]
+    fn test_sidecar_signature_verifies() {
+        let dir = tempfile::tempdir().unwrap();
+        let (key, pem) = ec_keypair();
+        let data = b"plugin bytes";
+        std::fs::write(dir.path().join("libdrasi_source_a.so"), data).unwrap();
+        std::fs::write(
+            dir.path().join("libdrasi_source_a.so.sig"),
+            sign_b64(&key, data),
+        )
+        .unwrap();
+
+        let keys = load_trusted_keys(&[trusted("in-house", &pem)], dir.path()).unwrap();
+        let results = verify_plugins_dir(dir.path(), &keys, is_plugin).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results["libdrasi_source_a.so"],
+            KeySignatureStatus::Verified {
+                key_name: "in-house".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_tampered_plugin_is_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let (key, pem) = ec_keypair();
+        std::fs::write(dir.path().join("libdrasi_source_a.so"), b"modified").unwrap();
+        std::fs::write(
+            dir.path().join("libdrasi_source_a.so.sig"),
+            sign_b64(&key, b"original"),
+        )
+        .unwrap();
+
+        let keys = load_trusted_keys(&[trusted("in-house", &pem)], dir.path()).unwrap();
+        let results = verify_plugins_dir(dir.path(), &keys, is_plugin).unwrap();
+        assert!(matches!(
+            results["libdrasi_source_a.so"],
+            KeySignatureStatus::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn test_untrusted_key_is_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let (signing_key, _) = ec_keypair();
+        let (_, other_pem) = ec_keypair();
+        let data = b"plugin bytes";
+        std::fs::write(dir.path().join("libdrasi_source_a.so"), data).unwrap();
+        std::fs::write(
+            dir.path().join("libdrasi_source_a.so.sig"),
+            sign_b64(&signing_key, data),
+        )
+        .unwrap();
+
+        let keys = load_trusted_keys(&[trusted("other", &other_pem)], dir.path()).unwrap();
+        let results = verify_plugins_dir(dir.path(), &keys, is_plugin).unwrap();
+        assert!(matches!(
+            results["libdrasi_source_a.so"],
+            KeySignatureStatus::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn test_missing_signature_is_unsigned() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, pem) = ec_keypair();
+        std::fs::write(dir.path().join("libdrasi_source_a.so"), b"bytes").unwrap();
+
+        let keys = load_trusted_keys(&[trusted("in-house", &pem)], dir.path()).unwrap();
+        let results = verify_plugins_dir(dir.path(), &keys, is_plugin).unwrap();
+        assert_eq!(
+            results["libdrasi_source_a.so"],
+            KeySignatureStatus::Unsigned
+        );
+    }
+
+    #[test]
+    fn test_lockfile_signature_verifies() {
+        let dir = tempfile::tempdir().unwrap();
+        let (key, pem) = ec_keypair();
+        let data = b"plugin bytes";
+        std::fs::write(dir.path().join("libdrasi_reaction_b.so"), data).unwrap();
+        let lockfile = format!(
+            "version = 1\n\n[plugins.\"reaction/b\"]\nreference = \"local\"\nfilename = \"libdrasi_reaction_b.so\"\nkey_signature = \"{}\"\n",
+            sign_b64(&key, data)
+        );
+        std::fs::write(dir.path().join("plugins.lock"), lockfile).unwrap();
+
+        let keys = load_trusted_keys(&[trusted("in-house", &pem)], dir.path()).unwrap();
+        let results = verify_plugins_dir(dir.path(), &keys, is_plugin).unwrap();
+        assert_eq!(
+            results["libdrasi_reaction_b.so"],
+            KeySignatureStatus::Verified {
+                key_name: "in-house".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_ed25519_key_verifies() {
+        let key = PKey::generate_ed25519().unwrap();
+        let pem = String::from_utf8(key.public_key_to_pem().unwrap()).unwrap();
+        let data = b"plugin bytes";
+        let mut signer = Signer::new_without_digest(&key).unwrap();
+        let signature = signer.sign_oneshot_to_vec(data).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let keys = load_trusted_keys(&[trusted("ed", &pem)], dir.path()).unwrap();
+        assert_eq!(
+            verify_signature(data, &signature, &keys),
+            KeySignatureStatus::Verified {
+                key_name: "ed".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_public_key_path_is_relative_to_base_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, pem) = ec_keypair();
+        std::fs::write(dir.path().join("signing.pub"), &pem).unwrap();
+
+        let key = TrustedKey {
+            name: "file".to_string(),
+            public_key: None,
+            public_key_path: Some("signing.pub".to_string()),
+        };
+        let keys = load_trusted_keys(&[key], dir.path()).unwrap();
+        assert_eq!(keys[0].name, "file");
+    }
+
+    #[test]
+    fn test_invalid_key_definitions_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let neither = TrustedKey {
+            name: "none".to_string(),
+            public_key: None,
+            public_key_path: None,
+        };
+        assert!(load_trusted_keys(&[neither], dir.path()).is_err());
+        assert!(load_trusted_keys(&[trusted("bad", "not a pem")], dir.path()).is_err());
+    }
+}
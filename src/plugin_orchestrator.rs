@@ -34,6 +34,7 @@ use drasi_host_sdk::registry::VerificationConfig;
 use drasi_host_sdk::CallbackContext;
 
 use crate::dynamic_loading::StartupPluginRecord;
+use crate::plugin_isolation::{CircuitBreakerStatus, PluginCircuitBreakers};
 use crate::plugin_operations::PluginOperations;
 
 /// Server-level operational record for a loaded plugin.
@@ -61,6 +62,8 @@ pub struct PluginInfo {
     pub kinds: Vec<PluginKindEntry>,
     /// Number of active component instances using this plugin.
     pub dependent_count: usize,
+    /// Crash-loop circuit breaker state for this plugin's components.
+    pub circuit_breaker: CircuitBreakerStatus,
 }
 
 /// Server-level plugin orchestrator.
@@ -81,6 +84,9 @@ pub struct PluginOrchestrator {
     /// Verification policy applied to all runtime loading paths.
     /// When `enabled == true`, plugins are verified before loading.
     verification_config: VerificationConfig,
+    /// Circuit breakers of the plugins' components, shared with the
+    /// component factories of the same server.
+    circuit_breakers: PluginCircuitBreakers,
 }
 
 impl PluginOrchestrator {
@@ -95,6 +101,7 @@ impl PluginOrchestrator {
             plugin_ops: None,
             dir_mutex: Mutex::new(()),
             verification_config: VerificationConfig::default(),
+            circuit_breakers: PluginCircuitBreakers::default(),
         }
     }
 
@@ -109,6 +116,7 @@ impl PluginOrchestrator {
             plugin_ops: None,
             dir_mutex: Mutex::new(()),
             verification_config: VerificationConfig::default(),
+            circuit_breakers: PluginCircuitBreakers::default(),
         }
     }
 
@@ -128,9 +136,22 @@ impl PluginOrchestrator {
             plugin_ops: Some(plugin_ops),
             dir_mutex: Mutex::new(()),
             verification_config,
+            circuit_breakers: PluginCircuitBreakers::default(),
         }
     }
 
+    /// Report `circuit_breakers`, which the server's component factories
+    /// record plugin failures in, instead of a breaker set of its own.
+    pub fn with_circuit_breakers(mut self, circuit_breakers: PluginCircuitBreakers) -> Self {
+        self.circuit_breakers = circuit_breakers;
+        self
+    }
+
+    /// The circuit breakers of the plugins' components.
+    pub fn circuit_breakers(&self) -> &PluginCircuitBreakers {
+        &self.circuit_breakers
+    }
+
     /// Get the configured plugins directory, if any.
     pub fn plugins_dir(&self) -> Option<&Path> {
         self.plugins_dir.as_deref()
@@ -300,6 +321,7 @@ impl PluginOrchestrator {
             loaded_at: Utc::now(),
             kinds,
             dependent_count: 0,
+            circuit_breaker: CircuitBreakerStatus::default(),
         };
 
         self.plugin_infos
@@ -312,12 +334,34 @@ impl PluginOrchestrator {
 
     /// Get information about a specific loaded plugin.
     pub async fn get_plugin_info(&self, plugin_id: &str) -> Option<PluginInfo> {
-        self.plugin_infos.read().await.get(plugin_id).cloned()
+        self.plugin_infos
+            .read()
+            .await
+            .get(plugin_id)
+            .cloned()
+            .map(|info| self.with_breaker_status(info))
     }
 
     /// List all loaded plugins with their operational state.
     pub async fn list_plugins(&self) -> Vec<PluginInfo> {
-        self.plugin_infos.read().await.values().cloned().collect()
+        self.plugin_infos
+            .read()
+            .await
+            .values()
+            .cloned()
+            .map(|info| self.with_breaker_status(info))
+            .collect()
+    }
+
+    /// Close a plugin's circuit breaker so its components can be started
+    /// again. Returns `false` when the plugin is not loaded.
+    pub async fn reset_circuit_breaker(&self, plugin_id: &str) -> bool {
+        if !self.plugin_infos.read().await.contains_key(plugin_id) {
+            return false;
+        }
+        self.circuit_breakers.reset(plugin_id);
+        info!("Circuit breaker reset for plugin '{plugin_id}'");
+        true
     }
 
     /// Update the dependent count for a plugin (called when components are created/removed).
@@ -355,6 +399,7 @@ impl PluginOrchestrator {
                 loaded_at: Utc::now(),
                 kinds: record.kinds.clone(),
                 dependent_count: 0,
+                circuit_breaker: CircuitBreakerStatus::default(),
             };
 
             self.plugin_infos
@@ -383,6 +428,13 @@ impl PluginOrchestrator {
             );
         }
     }
+
+    /// Overlay the live circuit breaker state, which the component factories
+    /// track in the breakers rather than on the stored record.
+    fn with_breaker_status(&self, mut info: PluginInfo) -> PluginInfo {
+        info.circuit_breaker = self.circuit_breakers.status(&info.id);
+        info
+    }
 }
 
 #[cfg(test)]
@@ -420,6 +472,7 @@ mod tests {
                     loaded_at: Utc::now(),
                     kinds: vec![],
                     dependent_count: 0,
+                    circuit_breaker: CircuitBreakerStatus::default(),
                 },
             );
         }
@@ -523,6 +576,7 @@ mod tests {
                     loaded_at: Utc::now(),
                     kinds: vec![],
                     dependent_count: 0,
+                    circuit_breaker: CircuitBreakerStatus::default(),
                 },
             );
         }
@@ -621,6 +675,49 @@ mod tests {
         assert!(orchestrator_no_dir.plugins_dir().is_none());
     }
 
+    #[tokio::test]
+    async fn test_plugin_info_reports_circuit_breaker() {
+        use crate::plugin_isolation::CircuitState;
+
+        let registry = Arc::new(RwLock::new(PluginRegistry::new()));
+        let lifecycle = Arc::new(PluginLifecycleManager::new(registry));
+        let orchestrator = PluginOrchestrator::new(lifecycle);
+
+        let records = vec![StartupPluginRecord {
+            plugin_id: "source/breaker-test".to_string(),
+            file_path: PathBuf::from("libdrasi_source_breaker_test.so"),
+            kinds: vec![],
+            plugin_version: "0.1.0".to_string(),
+            sdk_version: "0.1.0".to_string(),
+        }];
+        orchestrator.record_startup_plugins(&records).await;
+
+        let breakers = orchestrator.circuit_breakers();
+        let max_failures = breakers.settings().max_failures;
+        for _ in 0..max_failures {
+            breakers.record_failure("source/breaker-test", "start panicked", true);
+        }
+
+        let info = orchestrator
+            .get_plugin_info("source/breaker-test")
+            .await
+            .expect("exists");
+        assert_eq!(info.circuit_breaker.state, CircuitState::Open);
+        assert_eq!(info.circuit_breaker.total_panics, u64::from(max_failures));
+
+        assert!(
+            orchestrator
+                .reset_circuit_breaker("source/breaker-test")
+                .await
+        );
+        let info = orchestrator
+            .get_plugin_info("source/breaker-test")
+            .await
+            .expect("exists");
+        assert_eq!(info.circuit_breaker.state, CircuitState::Closed);
+        assert!(!orchestrator.reset_circuit_breaker("nonexistent").await);
+    }
+
     #[tokio::test]
     async fn test_get_plugin_info_nonexistent() {
         let registry = Arc::new(RwLock::new(PluginRegistry::new()));
@@ -0,0 +1,508 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Enforcement of source and reaction `restartPolicy` settings.
+//!
+//! One [`RestartSupervisor`] runs per DrasiLib instance. It subscribes to the
+//! instance's component event stream and, when a source or reaction with an
+//! `onFailure` policy transitions to `Error`, schedules a restart with
+//! exponential backoff. Each scheduled attempt (and giving up) is recorded in
+//! the component's event history so it shows up in `/sources/{id}/events`
+//! and `/reactions/{id}/events`, without replacing the failure as the
+//! component's last error.
+
+use drasi_lib::channels::ComponentEventBroadcastReceiver;
+use drasi_lib::{ComponentEvent, ComponentStatus, ComponentType, DrasiLib};
+use log::{info, warn};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+use crate::api::models::{ReactionConfig, RestartPolicy, SourceConfig};
+use crate::component_revisions::ComponentRevisions;
+
+/// Restart policies for the sources and reactions of one instance, keyed by
+/// component id. Components without an entry are never restarted.
+#[derive(Debug, Clone, Default)]
+pub struct RestartPolicies {
+    pub sources: HashMap<String, RestartPolicy>,
+    pub reactions: HashMap<String, RestartPolicy>,
+}
+
+impl RestartPolicies {
+    /// Collect the `restartPolicy` of every configured source and reaction.
+    pub fn from_configs(sources: &[SourceConfig], reactions: &[ReactionConfig]) -> Self {
+        Self {
+            sources: sources
+                .iter()
+                .filter_map(|s| s.restart_policy().map(|p| (s.id.clone(), p.clone())))
+                .collect(),
+            reactions: reactions
+                .iter()
+                .filter_map(|r| r.restart_policy().map(|p| (r.id.clone(), p.clone())))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RestartableKind {
+    Source,
+    Reaction,
+}
+
+impl fmt::Display for RestartableKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Source => f.write_str("source"),
+            Self::Reaction => f.write_str("reaction"),
+        }
+    }
+}
+
+/// What to do about a component that just failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RestartDecision {
+    Retry { attempt: u32, delay: Duration },
+    GiveUp { attempts: u32 },
+}
+
+/// Consecutive-failure bookkeeping for a single component.
+#[derive(Debug, Default)]
+struct RestartTracker {
+    attempts: u32,
+    running_since: Option<Instant>,
+}
+
+impl RestartTracker {
+    fn on_running(&mut self, now: Instant) {
+        self.running_since = Some(now);
+    }
+
+    fn on_failure(&mut self, policy: &RestartPolicy, now: Instant) -> RestartDecision {
+        // A component that stayed up for the reset window starts over with a
+        // fresh attempt budget.
+        if let Some(since) = self.running_since.take() {
+            if now.duration_since(since) >= policy.reset_after() {
+                self.attempts = 0;
+            }
+        }
+        if !policy.allows_attempt(self.attempts) {
+            return RestartDecision::GiveUp {
+                attempts: self.attempts,
+            };
+        }
+        self.attempts += 1;
+        RestartDecision::Retry {
+            attempt: self.attempts,
+            delay: policy.backoff(self.attempts),
+        }
+    }
+}
+
+struct SupervisorState {
+    core: Weak<DrasiLib>,
+    instance_id: String,
+    /// Holds the instance's write lock while restarting, so a restart does
+    /// not interleave with API writes (see [`ComponentRevisions::lock_writes`]).
+    revisions: ComponentRevisions,
+    policies: RwLock<RestartPolicies>,
+    trackers: Mutex<HashMap<(RestartableKind, String), RestartTracker>>,
+}
+
+/// Watches one instance's component events and restarts failed components
+/// according to their `restartPolicy`. The background task is aborted when
+/// the supervisor is dropped.
+pub struct RestartSupervisor {
+    state: Arc<SupervisorState>,
+    task: JoinHandle<()>,
+}
+
+impl RestartSupervisor {
+    /// Start supervising `core`, whose id is `instance_id`. Subscribe before
+    /// starting the instance so failures during the initial auto-start are
+    /// seen too.
+    pub fn spawn(
+        core: &Arc<DrasiLib>,
+        instance_id: &str,
+        revisions: ComponentRevisions,
+        policies: RestartPolicies,
+    ) -> Self {
+        let state = Arc::new(SupervisorState {
+            core: Arc::downgrade(core),
+            instance_id: instance_id.to_string(),
+            revisions,
+            policies: RwLock::new(policies),
+            trackers: Mutex::new(HashMap::new()),
+        });
+        let receiver = core.subscribe_all_component_events();
+        let task = tokio::spawn(run(state.clone(), receiver));
+        Self { state, task }
+    }
+
+    /// Set or clear (`None`) the restart policy for a source.
+    pub async fn set_source_policy(&self, source_id: &str, policy: Option<RestartPolicy>) {
+        self.set_policy(RestartableKind::Source, source_id, policy)
+            .await;
+    }
+
+    /// Set or clear (`None`) the restart policy for a reaction.
+    pub async fn set_reaction_policy(&self, reaction_id: &str, policy: Option<RestartPolicy>) {
+        self.set_policy(RestartableKind::Reaction, reaction_id, policy)
+            .await;
+    }
+
+    /// Current policies for every supervised component.
+    pub async fn policies(&self) -> RestartPolicies {
+        self.state.policies.read().await.clone()
+    }
+
+    async fn set_policy(&self, kind: RestartableKind, id: &str, policy: Option<RestartPolicy>) {
+        {
+            let mut policies = self.state.policies.write().await;
+            let map = match kind {
+                RestartableKind::Source => &mut policies.sources,
+                RestartableKind::Reaction => &mut policies.reactions,
+            };
+            match policy {
+                Some(policy) => {
+                    map.insert(id.to_string(), policy);
+                }
+                None => {
+                    map.remove(id);
+                }
+            }
+        }
+        self.state
+            .trackers
+            .lock()
+            .await
+            .remove(&(kind, id.to_string()));
+    }
+}
+
+impl Drop for RestartSupervisor {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(state: Arc<SupervisorState>, mut receiver: ComponentEventBroadcastReceiver) {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => state.handle_event(event).await,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Restart supervisor missed {skipped} component event(s)");
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+impl SupervisorState {
+    async fn handle_event(self: &Arc<Self>, event: ComponentEvent) {
+        let kind = match event.component_type {
+            ComponentType::Source => RestartableKind::Source,
+            ComponentType::Reaction => RestartableKind::Reaction,
+            _ => return,
+        };
+        let key = (kind, event.component_id);
+
+        match event.status {
+            ComponentStatus::Running => {
+                self.trackers
+                    .lock()
+                    .await
+                    .entry(key)
+                    .or_default()
+                    .on_running(Instant::now());
+            }
+            // A deliberate stop or removal ends the current failure streak.
+            ComponentStatus::Stopped | ComponentStatus::Removed => {
+                self.trackers.lock().await.remove(&key);
+            }
+            ComponentStatus::Error => {
+                let Some(policy) = self.policy(kind, &key.1).await else {
+                    return;
+                };
+                let decision = self
+                    .trackers
+                    .lock()
+                    .await
+                    .entry(key.clone())
+                    .or_default()
+                    .on_failure(&policy, Instant::now());
+                let (_, id) = key;
+                self.apply(kind, id, &policy, decision, event.message.as_deref())
+                    .await;
+            }
+            _ => {}
+        }
+    }
+
+    async fn policy(&self, kind: RestartableKind, id: &str) -> Option<RestartPolicy> {
+        let policies = self.policies.read().await;
+        let map = match kind {
+            RestartableKind::Source => &policies.sources,
+            RestartableKind::Reaction => &policies.reactions,
+        };
+        map.get(id).filter(|p| p.restarts_on_failure()).cloned()
+    }
+
+    async fn apply(
+        self: &Arc<Self>,
+        kind: RestartableKind,
+        id: String,
+        policy: &RestartPolicy,
+        decision: RestartDecision,
+        cause: Option<&str>,
+    ) {
+        let (status, message) = decision_event(&decision, policy, cause);
+        match decision {
+            RestartDecision::Retry { attempt, delay } => {
+                info!("{kind} '{id}' failed; {message}");
+                self.record(kind, &id, status, message).await;
+
+                let state = self.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    state.restart(kind, &id, attempt).await;
+                });
+            }
+            RestartDecision::GiveUp { .. } => {
+                warn!("{kind} '{id}': {message}");
+                self.record(kind, &id, status, message).await;
+            }
+        }
+    }
+
+    async fn restart(&self, kind: RestartableKind, id: &str, attempt: u32) {
+        let Some(core) = self.core.upgrade() else {
+            return;
+        };
+        let _guard = self.revisions.lock_writes(&self.instance_id).await;
+        // A manual start, stop or delete during the backoff wins.
+        let status = match kind {
+            RestartableKind::Source => core.get_source_status(id).await,
+            RestartableKind::Reaction => core.get_reaction_status(id).await,
+        };
+        if !matches!(status, Ok(ComponentStatus::Error)) {
+            return;
+        }
+
+        info!("Restarting {kind} '{id}' (attempt {attempt})");
+        let result = match kind {
+            RestartableKind::Source => core.start_source(id).await,
+            RestartableKind::Reaction => core.start_reaction(id).await,
+        };
+        // A failed start moves the component back to Error, which schedules
+        // the next attempt through the event stream.
+        if let Err(e) = result {
+            warn!("Restart attempt {attempt} for {kind} '{id}' failed: {e}");
+        }
+    }
+
+    async fn record(
+        &self,
+        kind: RestartableKind,
+        id: &str,
+        status: ComponentStatus,
+        message: String,
+    ) {
+        let Some(core) = self.core.upgrade() else {
+            return;
+        };
+        let component_type = match kind {
+            RestartableKind::Source => ComponentType::Source,
+            RestartableKind::Reaction => ComponentType::Reaction,
+        };
+        let graph = core.component_graph();
+        graph.write().await.record_event(ComponentEvent {
+            component_id: id.to_string(),
+            component_type,
+            status,
+            timestamp: chrono::Utc::now(),
+            message: Some(message),
+        });
+    }
+}
+
+/// The event recorded in a component's history for `decision`.
+///
+/// A scheduled attempt is recorded as `Starting`, so the failure that caused
+/// it stays the component's last error. Giving up is recorded as `Error` and
+/// repeats that failure, `cause`.
+fn decision_event(
+    decision: &RestartDecision,
+    policy: &RestartPolicy,
+    cause: Option<&str>,
+) -> (ComponentStatus, String) {
+    match decision {
+        RestartDecision::Retry { attempt, delay } => {
+            let limit = match policy.max_attempts {
+                0 => String::new(),
+                max => format!("/{max}"),
+            };
+            (
+                ComponentStatus::Starting,
+                format!(
+                    "Restart attempt {attempt}{limit} in {}ms (restartPolicy: onFailure)",
+                    delay.as_millis()
+                ),
+            )
+        }
+        RestartDecision::GiveUp { attempts } => {
+            let message =
+                format!("Giving up after {attempts} restart attempt(s) (restartPolicy: onFailure)");
+            let message = match cause {
+                Some(cause) => format!("{message}; last error: {cause}"),
+                None => message,
+            };
+            (ComponentStatus::Error, message)
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn policy(max_attempts: u32) -> RestartPolicy {
+        RestartPolicy {
+            max_attempts,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 10_000,
+            reset_after_secs: 60,
+            ..RestartPolicy::on_failure()
+        }
+    }
+
+    #[test]
+    fn test_tracker_backs_off_until_max_attempts() {
+        let policy = policy(3);
+        let mut tracker = RestartTracker::default();
+        let now = Instant::now();
+
+        assert_eq!(
+            tracker.on_failure(&policy, now),
+            RestartDecision::Retry {
+                attempt: 1,
+                delay: Duration::from_millis(100)
+            }
+        );
+        assert_eq!(
+            tracker.on_failure(&policy, now),
+            RestartDecision::Retry {
+                attempt: 2,
+                delay: Duration::from_millis(200)
+            }
+        );
+        assert_eq!(
+            tracker.on_failure(&policy, now),
+            RestartDecision::Retry {
+                attempt: 3,
+                delay: Duration::from_millis(400)
+            }
+        );
+        assert_eq!(
+            tracker.on_failure(&policy, now),
+            RestartDecision::GiveUp { attempts: 3 }
+        );
+    }
+
+    #[test]
+    fn test_tracker_resets_after_stable_run() {
+        let policy = policy(2);
+        let mut tracker = RestartTracker::default();
+        let start = Instant::now();
+
+        tracker.on_failure(&policy, start);
+        tracker.on_failure(&policy, start);
+
+        // Running briefly does not reset the budget.
+        tracker.on_running(start);
+        assert_eq!(
+            tracker.on_failure(&policy, start + Duration::from_secs(5)),
+            RestartDecision::GiveUp { attempts: 2 }
+        );
+
+        // Running past the reset window does.
+        tracker.on_running(start);
+        assert_eq!(
+            tracker.on_failure(&policy, start + Duration::from_secs(61)),
+            RestartDecision::Retry {
+                attempt: 1,
+                delay: Duration::from_millis(100)
+            }
+        );
+    }
+
+    #[test]
+    fn test_policies_from_configs() {
+        let sources: Vec<SourceConfig> = serde_yaml::from_str(
+            r#"
+            - kind: mock
+              id: with-policy
+              restartPolicy:
+                mode: onFailure
+            - kind: mock
+              id: without-policy
+            "#,
+        )
+        .unwrap();
+        let reactions: Vec<ReactionConfig> = serde_yaml::from_str(
+            r#"
+            - kind: log
+              id: r1
+              queries: [q1]
+              restartPolicy:
+                mode: never
+            "#,
+        )
+        .unwrap();
+
+        let policies = RestartPolicies::from_configs(&sources, &reactions);
+        assert_eq!(policies.sources.len(), 1);
+        assert!(policies.sources["with-policy"].restarts_on_failure());
+        assert!(!policies.reactions["r1"].restarts_on_failure());
+    }
+
+    #[test]
+    fn test_attempts_do_not_replace_last_error() {
+        let policy = policy(3);
+        let retry = RestartDecision::Retry {
+            attempt: 1,
+            delay: Duration::from_millis(100),
+        };
+        let (status, message) = decision_event(&retry, &policy, Some("connection refused"));
+        assert_eq!(status, ComponentStatus::Starting);
+        assert_eq!(
+            message,
+            "Restart attempt 1/3 in 100ms (restartPolicy: onFailure)"
+        );
+
+        let give_up = RestartDecision::GiveUp { attempts: 3 };
+        let (status, message) = decision_event(&give_up, &policy, Some("connection refused"));
+        assert_eq!(status, ComponentStatus::Error);
+        assert!(
+            message.ends_with("last error: connection refused"),
+            "{message}"
+        );
+    }
+}
@@ -0,0 +1,728 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reconfiguration of components whose secrets were rotated.
+//!
+//! Secrets are resolved once, while a component is created. The factories
+//! record in the server's [`SecretConsumers`] which secrets each source,
+//! reaction and identity provider consumed, with a digest of each value. For
+//! an instance whose `secretStore` sets `refreshIntervalSecs`, a
+//! [`SecretRotationWatcher`] re-reads those secrets on that interval. When a
+//! value changed, it recreates only the components that consumed it and
+//! swaps them in place, like `PUT`, and records the rotation in each
+//! component's event history.
+//! An identity provider whose secrets changed is rebuilt, and every source
+//! and reaction that references it is recreated with the new provider.
+//!
+//! Each swap holds the instance's write lock (see
+//! [`ComponentRevisions::lock_writes`]) and re-reads the component's record
+//! under it, so a rotation never overwrites a component that a concurrent
+//! `PUT` or `DELETE` just replaced or removed.
+
+use drasi_lib::identity::IdentityProvider;
+use drasi_lib::secret_store::SecretStoreProvider;
+use drasi_lib::{ComponentEvent, ComponentStatus, ComponentType, DrasiLib};
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+
+use crate::api::models::{IdentityProviderConfig, ReactionConfig, SourceConfig};
+use crate::component_revisions::ComponentRevisions;
+use crate::factories::{
+    create_identity_provider_unrecorded, create_reaction_locked, create_source_locked,
+    ConfigResolverContext,
+};
+use crate::plugin_isolation::PluginCircuitBreakers;
+use crate::plugin_registry::PluginRegistry;
+
+/// Digest of each consumed secret value, keyed by secret name. Values
+/// themselves are never kept.
+pub type SecretDigests = HashMap<String, Vec<u8>>;
+
+/// Digest of a secret value, for change detection.
+pub fn secret_digest(value: &str) -> Vec<u8> {
+    Sha256::digest(value.as_bytes()).to_vec()
+}
+
+/// A component that consumed secrets, with the config it was created from.
+#[derive(Debug, Clone)]
+pub enum SecretConsumer {
+    Source(SourceConfig),
+    Reaction(ReactionConfig),
+    IdentityProvider(IdentityProviderConfig),
+}
+
+/// Identity providers sort first, so a rotation rebuilds them before the
+/// components that reference them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum ConsumerKind {
+    IdentityProvider,
+    Source,
+    Reaction,
+}
+
+impl ConsumerKind {
+    /// The kind as reported in [`RotatedComponent::kind`].
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::IdentityProvider => "identityProvider",
+            Self::Source => "source",
+            Self::Reaction => "reaction",
+        }
+    }
+}
+
+impl fmt::Display for ConsumerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IdentityProvider => f.write_str("identity provider"),
+            Self::Source => f.write_str("source"),
+            Self::Reaction => f.write_str("reaction"),
+        }
+    }
+}
+
+impl SecretConsumer {
+    fn key(&self) -> (ConsumerKind, String) {
+        match self {
+            Self::Source(config) => (ConsumerKind::Source, config.id.clone()),
+            Self::Reaction(config) => (ConsumerKind::Reaction, config.id.clone()),
+            Self::IdentityProvider(config) => (ConsumerKind::IdentityProvider, config.id.clone()),
+        }
+    }
+
+    /// The identity provider a source or reaction references.
+    fn identity_provider(&self) -> Option<&str> {
+        match self {
+            Self::Source(config) => config.identity_provider(),
+            Self::Reaction(config) => config.identity_provider(),
+            Self::IdentityProvider(_) => None,
+        }
+    }
+}
+
+struct Consumption {
+    consumer: SecretConsumer,
+    digests: SecretDigests,
+}
+
+/// Which components of each instance consumed which secrets.
+///
+/// Each server keeps its own record, shared through its
+/// [`crate::instance_registry::InstanceRegistry`], so servers in one process
+/// never see each other's components. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct SecretConsumers {
+    by_instance:
+        Arc<std::sync::RwLock<HashMap<String, HashMap<(ConsumerKind, String), Consumption>>>>,
+}
+
+impl SecretConsumers {
+    /// An empty record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the secrets `consumer` resolved when it was created, replacing
+    /// what it consumed before. Identity providers, and the components that
+    /// reference one, are kept even without secrets so that a rotation can
+    /// rebuild them; any other component without secrets is forgotten.
+    pub fn record(&self, instance_id: &str, consumer: SecretConsumer, digests: SecretDigests) {
+        let mut by_instance = self.by_instance.write().unwrap_or_else(|e| e.into_inner());
+        let key = consumer.key();
+        let kept =
+            key.0 == ConsumerKind::IdentityProvider || consumer.identity_provider().is_some();
+        if digests.is_empty() && !kept {
+            if let Some(consumers) = by_instance.get_mut(instance_id) {
+                consumers.remove(&key);
+            }
+            return;
+        }
+        by_instance
+            .entry(instance_id.to_string())
+            .or_default()
+            .insert(key, Consumption { consumer, digests });
+    }
+
+    /// Forget every component of `instance_id`.
+    pub fn forget_instance(&self, instance_id: &str) {
+        self.by_instance
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(instance_id);
+    }
+
+    /// Names of the secrets consumed by any component of `instance_id`.
+    pub fn secret_names(&self, instance_id: &str) -> BTreeSet<String> {
+        self.by_instance
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(instance_id)
+            .into_iter()
+            .flat_map(|consumers| consumers.values())
+            .flat_map(|c| c.digests.keys().cloned())
+            .collect()
+    }
+
+    /// The components of `instance_id` that consumed a secret whose digest
+    /// differs from `current`, each with the names of its changed secrets.
+    /// Secrets missing from `current` count as unchanged.
+    fn stale(
+        &self,
+        instance_id: &str,
+        current: &SecretDigests,
+    ) -> Vec<(SecretConsumer, SecretDigests, Vec<String>)> {
+        let by_instance = self.by_instance.read().unwrap_or_else(|e| e.into_inner());
+        let Some(consumers) = by_instance.get(instance_id) else {
+            return Vec::new();
+        };
+        let mut stale: Vec<_> = consumers
+            .values()
+            .filter_map(|c| {
+                let mut changed: Vec<String> = c
+                    .digests
+                    .iter()
+                    .filter(|(name, digest)| current.get(*name).is_some_and(|d| d != *digest))
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                if changed.is_empty() {
+                    return None;
+                }
+                changed.sort();
+                Some((c.consumer.clone(), c.digests.clone(), changed))
+            })
+            .collect();
+        stale.sort_by_key(|(consumer, _, _)| consumer.key());
+        stale
+    }
+
+    /// The current record of one consumer.
+    fn consumption(
+        &self,
+        instance_id: &str,
+        key: &(ConsumerKind, String),
+    ) -> Option<(SecretConsumer, SecretDigests)> {
+        self.by_instance
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(instance_id)?
+            .get(key)
+            .map(|c| (c.consumer.clone(), c.digests.clone()))
+    }
+
+    /// The config of identity provider `id` of `instance_id`.
+    fn identity_provider(&self, instance_id: &str, id: &str) -> Option<IdentityProviderConfig> {
+        match self.consumption(
+            instance_id,
+            &(ConsumerKind::IdentityProvider, id.to_string()),
+        ) {
+            Some((SecretConsumer::IdentityProvider(config), _)) => Some(config),
+            _ => None,
+        }
+    }
+
+    /// The sources and reactions of `instance_id` that reference identity
+    /// provider `id`, sorted.
+    fn dependents(&self, instance_id: &str, id: &str) -> Vec<(SecretConsumer, SecretDigests)> {
+        let by_instance = self.by_instance.read().unwrap_or_else(|e| e.into_inner());
+        let Some(consumers) = by_instance.get(instance_id) else {
+            return Vec::new();
+        };
+        let mut dependents: Vec<_> = consumers
+            .values()
+            .filter(|c| c.consumer.identity_provider() == Some(id))
+            .map(|c| (c.consumer.clone(), c.digests.clone()))
+            .collect();
+        dependents.sort_by_key(|(consumer, _)| consumer.key());
+        dependents
+    }
+
+    fn forget(&self, instance_id: &str, key: &(ConsumerKind, String)) {
+        if let Some(consumers) = self
+            .by_instance
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(instance_id)
+        {
+            consumers.remove(key);
+        }
+    }
+}
+
+/// A component reconfigured because its secrets, or the secrets of the
+/// identity provider it references, changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotatedComponent {
+    /// `source`, `reaction` or `identityProvider`.
+    pub kind: &'static str,
+    pub id: String,
+    /// The rotated secrets it consumes.
+    pub secrets: Vec<String>,
+}
+
+/// Re-reads the secrets consumed by one instance's components on an
+/// interval and reconfigures the components whose secrets changed. The
+/// background task is aborted when the watcher is dropped.
+pub struct SecretRotationWatcher {
+    task: JoinHandle<()>,
+}
+
+impl SecretRotationWatcher {
+    /// Start watching the components of `core`, whose id is `instance_id`,
+    /// as recorded in `consumers`; plugin components it recreates report to
+    /// `breakers`. Secrets are read through the instance's
+    /// current secret store, and components are swapped under the instance's
+    /// lock in `revisions`.
+    pub fn spawn(
+        core: &Arc<DrasiLib>,
+        instance_id: &str,
+        plugin_registry: Arc<RwLock<PluginRegistry>>,
+        revisions: ComponentRevisions,
+        consumers: SecretConsumers,
+        breakers: PluginCircuitBreakers,
+        interval: Duration,
+    ) -> Self {
+        let core = Arc::downgrade(core);
+        let instance_id = instance_id.to_string();
+        let task = tokio::spawn(run(
+            core,
+            instance_id,
+            plugin_registry,
+            revisions,
+            consumers,
+            breakers,
+            interval,
+        ));
+        Self { task }
+    }
+}
+
+impl Drop for SecretRotationWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(
+    core: Weak<DrasiLib>,
+    instance_id: String,
+    plugin_registry: Arc<RwLock<PluginRegistry>>,
+    revisions: ComponentRevisions,
+    consumers: SecretConsumers,
+    breakers: PluginCircuitBreakers,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // The first tick completes immediately; components were just created.
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let Some(core) = core.upgrade() else {
+            break;
+        };
+        let Some(store) = ConfigResolverContext::global().instance_store(&instance_id) else {
+            continue;
+        };
+        rotate_secrets(
+            &core,
+            &instance_id,
+            &plugin_registry,
+            &revisions,
+            &consumers,
+            &breakers,
+            &store,
+        )
+        .await;
+    }
+}
+
+/// Re-read every secret consumed by `instance_id`'s components, as recorded
+/// in `consumers`, through `store` and reconfigure the components whose
+/// secrets changed.
+///
+/// Each swap holds the instance's write lock in `revisions` and goes ahead
+/// only if the component's record still holds the secrets found stale; a
+/// component replaced or deleted in the meantime already resolved the
+/// current values, or is gone. A component whose reconfiguration fails
+/// keeps its old secrets on record, so the next check tries again.
+pub async fn rotate_secrets(
+    core: &DrasiLib,
+    instance_id: &str,
+    plugin_registry: &RwLock<PluginRegistry>,
+    revisions: &ComponentRevisions,
+    consumers: &SecretConsumers,
+    breakers: &PluginCircuitBreakers,
+    store: &Arc<dyn SecretStoreProvider>,
+) -> Vec<RotatedComponent> {
+    let mut current = SecretDigests::new();
+    for name in consumers.secret_names(instance_id) {
+        match store.get_secret(&name).await {
+            Ok(value) => {
+                current.insert(name, secret_digest(&value));
+            }
+            Err(e) => warn!("Instance '{instance_id}': cannot re-read secret '{name}': {e:#}"),
+        }
+    }
+
+    let mut rotated = Vec::new();
+    for (consumer, previous, secrets) in consumers.stale(instance_id, &current) {
+        let _guard = revisions.lock_writes(instance_id).await;
+        let consumer = match consumers.consumption(instance_id, &consumer.key()) {
+            Some((consumer, digests)) if digests == previous => consumer,
+            // Replaced or removed since the check.
+            _ => continue,
+        };
+        match consumer {
+            SecretConsumer::IdentityProvider(config) => {
+                rotated.extend(
+                    rotate_identity_provider(
+                        core,
+                        instance_id,
+                        plugin_registry,
+                        consumers,
+                        breakers,
+                        config,
+                        secrets,
+                    )
+                    .await,
+                );
+            }
+            consumer => {
+                let result = rotate_component(
+                    core,
+                    instance_id,
+                    plugin_registry,
+                    consumers,
+                    breakers,
+                    consumer,
+                    previous,
+                    secrets,
+                    None,
+                )
+                .await;
+                if let Ok(Some(component)) = result {
+                    rotated.push(component);
+                }
+            }
+        }
+    }
+    rotated
+}
+
+/// Rebuild identity provider `config` and recreate every source and
+/// reaction that references it with the new provider. The provider keeps
+/// its old secrets on record unless every swap succeeded, so failed swaps
+/// are retried on the next check.
+async fn rotate_identity_provider(
+    core: &DrasiLib,
+    instance_id: &str,
+    plugin_registry: &RwLock<PluginRegistry>,
+    consumers: &SecretConsumers,
+    breakers: &PluginCircuitBreakers,
+    config: IdentityProviderConfig,
+    secrets: Vec<String>,
+) -> Vec<RotatedComponent> {
+    let id = config.id.clone();
+    let names = secrets.join(", ");
+    info!(
+        "Secret(s) {names} rotated; rebuilding identity provider '{id}' in instance '{instance_id}'"
+    );
+    let (provider, digests) =
+        match create_identity_provider_unrecorded(plugin_registry, instance_id, &config).await {
+            Ok(built) => built,
+            Err(e) => {
+                warn!("Failed to rebuild identity provider '{id}' after secret rotation: {e:#}");
+                return Vec::new();
+            }
+        };
+
+    let mut rotated = Vec::new();
+    let mut failed = false;
+    for (dependent, previous) in consumers.dependents(instance_id, &id) {
+        match rotate_component(
+            core,
+            instance_id,
+            plugin_registry,
+            consumers,
+            breakers,
+            dependent,
+            previous,
+            secrets.clone(),
+            Some(provider.clone()),
+        )
+        .await
+        {
+            Ok(Some(component)) => rotated.push(component),
+            Ok(None) => {}
+            Err(_) => failed = true,
+        }
+    }
+    if !failed {
+        consumers.record(
+            instance_id,
+            SecretConsumer::IdentityProvider(config),
+            digests,
+        );
+        rotated.insert(
+            0,
+            RotatedComponent {
+                kind: ConsumerKind::IdentityProvider.as_str(),
+                id,
+                secrets,
+            },
+        );
+    }
+    rotated
+}
+
+/// Recreate source or reaction `consumer` and swap it in, recording the
+/// outcome in its event history. Returns `None` if the component no longer
+/// exists. On failure, which is already logged and recorded, `previous`
+/// stays on record.
+#[allow(clippy::too_many_arguments)]
+async fn rotate_component(
+    core: &DrasiLib,
+    instance_id: &str,
+    plugin_registry: &RwLock<PluginRegistry>,
+    consumers: &SecretConsumers,
+    breakers: &PluginCircuitBreakers,
+    consumer: SecretConsumer,
+    previous: SecretDigests,
+    secrets: Vec<String>,
+    identity_provider: Option<Arc<dyn IdentityProvider>>,
+) -> anyhow::Result<Option<RotatedComponent>> {
+    let (kind, id) = consumer.key();
+    let exists = match kind {
+        ConsumerKind::Source => core.get_source_status(&id).await.is_ok(),
+        ConsumerKind::Reaction => core.get_reaction_status(&id).await.is_ok(),
+        ConsumerKind::IdentityProvider => false,
+    };
+    if !exists {
+        consumers.forget(instance_id, &(kind, id));
+        return Ok(None);
+    }
+
+    let names = secrets.join(", ");
+    info!("Secret(s) {names} rotated; reconfiguring {kind} '{id}' in instance '{instance_id}'");
+    let result = reconfigure(
+        core,
+        instance_id,
+        plugin_registry,
+        consumers,
+        breakers,
+        consumer.clone(),
+        identity_provider,
+    )
+    .await;
+    match result {
+        Ok(()) => {
+            record_event(
+                core,
+                kind,
+                &id,
+                ComponentStatus::Reconfiguring,
+                format!("Reconfigured after secret rotation: {names}"),
+            )
+            .await;
+            Ok(Some(RotatedComponent {
+                kind: kind.as_str(),
+                id,
+                secrets,
+            }))
+        }
+        Err(e) => {
+            warn!("Failed to reconfigure {kind} '{id}' after secret rotation: {e:#}");
+            consumers.record(instance_id, consumer, previous);
+            record_event(
+                core,
+                kind,
+                &id,
+                ComponentStatus::Error,
+                format!("Reconfiguration after secret rotation ({names}) failed: {e:#}"),
+            )
+            .await;
+            Err(e)
+        }
+    }
+}
+
+/// Recreate `consumer` from its config, resolving its secrets again, and
+/// swap it in place of the running component. A component that references
+/// an identity provider is given `identity_provider`, or else a provider
+/// rebuilt from the config recorded in `consumers`.
+async fn reconfigure(
+    core: &DrasiLib,
+    instance_id: &str,
+    plugin_registry: &RwLock<PluginRegistry>,
+    consumers: &SecretConsumers,
+    breakers: &PluginCircuitBreakers,
+    consumer: SecretConsumer,
+    identity_provider: Option<Arc<dyn IdentityProvider>>,
+) -> anyhow::Result<()> {
+    let identity_provider = match (identity_provider, consumer.identity_provider()) {
+        (Some(provider), _) => Some(provider),
+        (None, Some(id)) => match consumers.identity_provider(instance_id, id) {
+            Some(config) => {
+                let (provider, _) =
+                    create_identity_provider_unrecorded(plugin_registry, instance_id, &config)
+                        .await?;
+                Some(provider)
+            }
+            None => None,
+        },
+        (None, None) => None,
+    };
+    match consumer {
+        SecretConsumer::Source(config) => {
+            let id = config.id.clone();
+            let (source, _) =
+                create_source_locked(plugin_registry, instance_id, consumers, breakers, config)
+                    .await?;
+            if let Some(provider) = identity_provider {
+                source.set_identity_provider(provider).await;
+            }
+            core.update_source(&id, source).await?;
+        }
+        SecretConsumer::Reaction(config) => {
+            let id = config.id.clone();
+            let (reaction, _) =
+                create_reaction_locked(plugin_registry, instance_id, consumers, breakers, config)
+                    .await?;
+            if let Some(provider) = identity_provider {
+                reaction.set_identity_provider(provider).await;
+            }
+            core.update_reaction(&id, reaction).await?;
+        }
+        // Rebuilt together with its dependents by `rotate_identity_provider`.
+        SecretConsumer::IdentityProvider(_) => {}
+    }
+    Ok(())
+}
+
+async fn record_event(
+    core: &DrasiLib,
+    kind: ConsumerKind,
+    id: &str,
+    status: ComponentStatus,
+    message: String,
+) {
+    let component_type = match kind {
+        ConsumerKind::Source => ComponentType::Source,
+        ConsumerKind::Reaction => ComponentType::Reaction,
+        // Identity providers are not in the component graph.
+        ConsumerKind::IdentityProvider => return,
+    };
+    let graph = core.component_graph();
+    graph.write().await.record_event(ComponentEvent {
+        component_id: id.to_string(),
+        component_type,
+        status,
+        timestamp: chrono::Utc::now(),
+        message: Some(message),
+    });
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn source(id: &str) -> SecretConsumer {
+        SecretConsumer::Source(
+            serde_json::from_value(serde_json::json!({"kind": "mock", "id": id})).unwrap(),
+        )
+    }
+
+    fn digests(secrets: &[(&str, &str)]) -> SecretDigests {
+        secrets
+            .iter()
+            .map(|(name, value)| (name.to_string(), secret_digest(value)))
+            .collect()
+    }
+
+    #[test]
+    fn test_only_consumers_of_changed_secrets_are_stale() {
+        let consumers = SecretConsumers::default();
+        consumers.record(
+            "i1",
+            source("orders"),
+            digests(&[("DB_PASSWORD", "old"), ("API_KEY", "k")]),
+        );
+        consumers.record("i1", source("audit"), digests(&[("API_KEY", "k")]));
+        consumers.record("i2", source("orders"), digests(&[("DB_PASSWORD", "old")]));
+
+        let names: Vec<String> = consumers.secret_names("i1").into_iter().collect();
+        assert_eq!(names, ["API_KEY", "DB_PASSWORD"]);
+
+        let current = digests(&[("DB_PASSWORD", "new"), ("API_KEY", "k")]);
+        let stale = consumers.stale("i1", &current);
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].0.key(), (ConsumerKind::Source, "orders".into()));
+        assert_eq!(stale[0].2, ["DB_PASSWORD"]);
+
+        // A secret that could not be re-read counts as unchanged.
+        assert!(consumers.stale("i1", &SecretDigests::new()).is_empty());
+    }
+
+    #[test]
+    fn test_recreating_without_secrets_forgets_the_consumer() {
+        let consumers = SecretConsumers::default();
+        consumers.record("i1", source("orders"), digests(&[("DB_PASSWORD", "a")]));
+        consumers.record("i1", source("orders"), SecretDigests::new());
+        assert!(consumers.secret_names("i1").is_empty());
+
+        consumers.record("i1", source("orders"), digests(&[("DB_PASSWORD", "a")]));
+        consumers.forget_instance("i1");
+        assert!(consumers.secret_names("i1").is_empty());
+    }
+
+    #[test]
+    fn test_identity_providers_and_their_dependents_are_kept_without_secrets() {
+        let consumers = SecretConsumers::default();
+        let idp: IdentityProviderConfig = serde_json::from_value(serde_json::json!({
+            "kind": "password", "id": "login", "username": "u", "password": "p"
+        }))
+        .unwrap();
+        consumers.record(
+            "i1",
+            SecretConsumer::IdentityProvider(idp),
+            SecretDigests::new(),
+        );
+        let dependent = SecretConsumer::Source(
+            serde_json::from_value(
+                serde_json::json!({"kind": "mock", "id": "orders", "identityProvider": "login"}),
+            )
+            .unwrap(),
+        );
+        consumers.record("i1", dependent, SecretDigests::new());
+        consumers.record("i1", source("audit"), SecretDigests::new());
+
+        assert!(consumers.identity_provider("i1", "login").is_some());
+        let dependents = consumers.dependents("i1", "login");
+        assert_eq!(dependents.len(), 1);
+        assert_eq!(
+            dependents[0].0.key(),
+            (ConsumerKind::Source, "orders".into())
+        );
+        assert!(consumers
+            .consumption("i1", &(ConsumerKind::Source, "audit".into()))
+            .is_none());
+    }
+}
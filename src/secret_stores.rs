@@ -0,0 +1,289 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Built-in secret stores that need no plugin.
+//!
+//! | Kind        | Secret `NAME` is read from                                   |
+//! |-------------|--------------------------------------------------------------|
+//! | `file`      | key `NAME` of a flat JSON or YAML file at `path`             |
+//! | `directory` | the file `<path>/NAME`, as mounted by Kubernetes and Docker  |
+//! | `env`       | the environment variable `<prefix>NAME`                      |
+//!
+//! Built-in kinds take precedence over secret store plugins of the same kind,
+//! like the built-in `password` identity provider. Files are read on every
+//! lookup, so a rotated secret is picked up without a restart.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use drasi_lib::secret_store::SecretStoreProvider;
+use serde::Deserialize;
+
+use crate::api::mappings::DtoMapper;
+use crate::api::models::{ConfigValue, SecretStoreConfig};
+use crate::config::DrasiServerConfig;
+
+/// Kind of the built-in store that reads a flat JSON or YAML file.
+pub const FILE_KIND: &str = "file";
+/// Kind of the built-in store that reads one file per secret.
+pub const DIRECTORY_KIND: &str = "directory";
+/// Kind of the built-in store that reads prefixed environment variables.
+pub const ENV_KIND: &str = "env";
+/// Every built-in secret store kind.
+pub const BUILTIN_KINDS: [&str; 3] = [FILE_KIND, DIRECTORY_KIND, ENV_KIND];
+
+/// Config of the `file` and `directory` kinds.
+#[derive(Deserialize)]
+struct PathConfig {
+    path: ConfigValue<String>,
+}
+
+/// Config of the `env` kind.
+#[derive(Deserialize)]
+struct EnvConfig {
+    #[serde(default)]
+    prefix: ConfigValue<String>,
+}
+
+/// Create the built-in store `config` describes, or `None` when its kind is
+/// not built in.
+///
+/// The store's own settings may use `${VAR}` references but not secrets,
+/// which would need a store to resolve them.
+pub fn create_builtin_secret_store(
+    config: &SecretStoreConfig,
+) -> Result<Option<Arc<dyn SecretStoreProvider>>> {
+    let mapper = DtoMapper::new();
+    let kind = config.kind.as_str();
+    let provider: Arc<dyn SecretStoreProvider> = match kind {
+        FILE_KIND | DIRECTORY_KIND => {
+            let dto: PathConfig = serde_json::from_value(config.config.clone())
+                .with_context(|| format!("secret store '{kind}': expected a 'path' field"))?;
+            let path = PathBuf::from(
+                mapper
+                    .resolve_string(&dto.path)
+                    .with_context(|| format!("secret store '{kind}': failed to resolve 'path'"))?,
+            );
+            if kind == FILE_KIND {
+                Arc::new(FileSecretStore { path })
+            } else {
+                Arc::new(DirectorySecretStore { dir: path })
+            }
+        }
+        ENV_KIND => {
+            let dto: EnvConfig = serde_json::from_value(config.config.clone())
+                .with_context(|| format!("secret store '{kind}': invalid configuration"))?;
+            let prefix = mapper
+                .resolve_string(&dto.prefix)
+                .with_context(|| format!("secret store '{kind}': failed to resolve 'prefix'"))?;
+            Arc::new(EnvSecretStore { prefix })
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(provider))
+}
+
+/// The mapper for server-level settings.
+///
+/// `{kind: Secret}` values resolve through the top-level `secretStore` when
+/// it is a built-in kind. Settings are resolved before plugins are loaded,
+/// so a plugin store cannot serve them.
+pub fn settings_mapper(config: &DrasiServerConfig) -> Result<DtoMapper> {
+    let store = match &config.secret_store {
+        Some(store_config) => create_builtin_secret_store(store_config)?,
+        None => None,
+    };
+    Ok(match store {
+        Some(store) => DtoMapper::new().with_secret_store(store),
+        None => DtoMapper::new(),
+    })
+}
+
+/// Reads secrets from a flat JSON or YAML map of name to value.
+struct FileSecretStore {
+    path: PathBuf,
+}
+
+#[async_trait]
+impl SecretStoreProvider for FileSecretStore {
+    async fn get_secret(&self, name: &str) -> Result<String> {
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .with_context(|| format!("Failed to read secrets file {}", self.path.display()))?;
+        // YAML is a superset of JSON, so one parser reads both formats.
+        let secrets: serde_yaml::Mapping = serde_yaml::from_str(&content)
+            .with_context(|| format!("Secrets file {} is not a map", self.path.display()))?;
+        match secrets.get(name) {
+            Some(serde_yaml::Value::String(value)) => Ok(value.clone()),
+            Some(serde_yaml::Value::Number(value)) => Ok(value.to_string()),
+            Some(serde_yaml::Value::Bool(value)) => Ok(value.to_string()),
+            Some(_) => anyhow::bail!(
+                "Secret '{name}' in {} is not a string, number or boolean",
+                self.path.display()
+            ),
+            None => anyhow::bail!("Secret '{name}' not found in {}", self.path.display()),
+        }
+    }
+}
+
+/// Reads each secret from the file of the same name in a directory.
+struct DirectorySecretStore {
+    dir: PathBuf,
+}
+
+#[async_trait]
+impl SecretStoreProvider for DirectorySecretStore {
+    async fn get_secret(&self, name: &str) -> Result<String> {
+        if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+            anyhow::bail!("Invalid secret name '{name}': must be a plain file name");
+        }
+        let path = self.dir.join(name);
+        let content = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                anyhow::bail!("Secret '{name}' not found in {}", self.dir.display())
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        // Mounted secrets often end with the newline of the editor or
+        // `echo` that wrote them; it is never part of the value.
+        let value = content.strip_suffix('\n').unwrap_or(&content);
+        Ok(value.strip_suffix('\r').unwrap_or(value).to_string())
+    }
+}
+
+/// Reads secret `NAME` from the environment variable `<prefix>NAME`.
+struct EnvSecretStore {
+    prefix: String,
+}
+
+#[async_trait]
+impl SecretStoreProvider for EnvSecretStore {
+    async fn get_secret(&self, name: &str) -> Result<String> {
+        let var = format!("{}{name}", self.prefix);
+        std::env::var(&var)
+            .with_context(|| format!("Environment variable '{var}' for secret '{name}' is not set"))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store(kind: &str, config: serde_json::Value) -> Arc<dyn SecretStoreProvider> {
+        create_builtin_secret_store(&SecretStoreConfig {
+            kind: kind.to_string(),
+            refresh_interval_secs: None,
+            config,
+        })
+        .unwrap()
+        .expect("kind should be built in")
+    }
+
+    #[tokio::test]
+    async fn test_file_store_reads_json_and_yaml() {
+        let dir = TempDir::new().unwrap();
+        let json = dir.path().join("secrets.json");
+        std::fs::write(&json, r#"{"DB_PASSWORD": "hunter2", "PORT": 5432}"#).unwrap();
+        let yaml = dir.path().join("secrets.yaml");
+        std::fs::write(&yaml, "DB_PASSWORD: from-yaml\n").unwrap();
+
+        let json_store = store(FILE_KIND, serde_json::json!({ "path": json }));
+        assert_eq!(
+            json_store.get_secret("DB_PASSWORD").await.unwrap(),
+            "hunter2"
+        );
+        assert_eq!(json_store.get_secret("PORT").await.unwrap(), "5432");
+        let err = json_store.get_secret("MISSING").await.unwrap_err();
+        assert!(err.to_string().contains("'MISSING' not found"), "{err}");
+
+        let yaml_store = store(FILE_KIND, serde_json::json!({ "path": yaml }));
+        assert_eq!(
+            yaml_store.get_secret("DB_PASSWORD").await.unwrap(),
+            "from-yaml"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_directory_store_trims_the_trailing_newline_and_rejects_paths() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("db-password"), "s3cret\n").unwrap();
+        std::fs::write(dir.path().join("api-key"), "line1\nline2").unwrap();
+
+        let store = store(DIRECTORY_KIND, serde_json::json!({ "path": dir.path() }));
+        assert_eq!(store.get_secret("db-password").await.unwrap(), "s3cret");
+        assert_eq!(store.get_secret("api-key").await.unwrap(), "line1\nline2");
+        assert!(store.get_secret("../db-password").await.is_err());
+        assert!(store.get_secret("missing").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_env_store_reads_prefixed_variables() {
+        std::env::set_var("DRASI_SECRET_STORE_TEST_TOKEN", "t0ken");
+
+        let store = store(
+            ENV_KIND,
+            serde_json::json!({ "prefix": "DRASI_SECRET_STORE_TEST_" }),
+        );
+        assert_eq!(store.get_secret("TOKEN").await.unwrap(), "t0ken");
+        let err = store.get_secret("MISSING").await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("'DRASI_SECRET_STORE_TEST_MISSING'"),
+            "{err}"
+        );
+
+        std::env::remove_var("DRASI_SECRET_STORE_TEST_TOKEN");
+    }
+
+    #[test]
+    fn test_plugin_kinds_are_not_built_in() {
+        let config = SecretStoreConfig {
+            kind: "azure-keyvault".to_string(),
+            refresh_interval_secs: None,
+            config: serde_json::json!({}),
+        };
+        assert!(create_builtin_secret_store(&config).unwrap().is_none());
+
+        let config = SecretStoreConfig {
+            kind: FILE_KIND.to_string(),
+            refresh_interval_secs: None,
+            config: serde_json::json!({}),
+        };
+        assert!(create_builtin_secret_store(&config).is_err());
+    }
+
+    #[test]
+    fn test_settings_resolve_secrets_through_the_top_level_store() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("HOST"), "10.0.0.5\n").unwrap();
+        let config: DrasiServerConfig = serde_yaml::from_str(&format!(
+            "host: {{kind: Secret, name: HOST}}\n\
+             secretStore:\n  kind: directory\n  path: {}\n",
+            dir.path().display()
+        ))
+        .unwrap();
+
+        let settings =
+            crate::api::mappings::map_server_settings(&config, &settings_mapper(&config).unwrap())
+                .unwrap();
+        assert_eq!(settings.host, "10.0.0.5");
+    }
+}
@@ -20,6 +20,7 @@ use std::collections::HashMap;
 use std::fs::OpenOptions;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
@@ -27,23 +28,32 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::api;
-use crate::api::mappings::{map_server_settings, DtoMapper};
-use crate::api::models::BootstrapProviderConfig;
-use crate::config::{DrasiLibInstanceConfig, SecretStoreConfig};
+use crate::api::mappings::map_server_settings;
+use crate::api::models::{ArchiveRetentionConfig, BootstrapProviderConfig};
+use crate::archive_retention::{applicable_retention, ArchiveRetention};
+use crate::component_revisions::ComponentRevisions;
+use crate::config::{DrasiLibInstanceConfig, LoadOptions, ServerMode};
 use crate::factories::{
-    build_bootstrap_provider_config_map, build_config_resolver_context,
-    build_identity_provider_map, config_resolver_callback, create_reaction_locked,
-    create_secret_store_from_registry, create_source_locked, create_state_store_provider,
-    resolve_source_bootstrap_provider,
+    build_bootstrap_provider_config_map, build_identity_provider_map, config_resolver_callback,
+    create_reaction_locked, create_secret_store_from_registry, create_source_locked,
+    create_state_store_from_registry, resolve_source_bootstrap_provider, ConfigResolverContext,
 };
+use crate::index_provider::IndexDatabases;
 use crate::instance_paths::instance_storage_key;
 use crate::instance_registry::InstanceRegistry;
-use crate::load_config_file;
+use crate::load_config_layers;
 use crate::persistence::ConfigPersistence;
+use crate::plugin_isolation::PluginCircuitBreakers;
 use crate::plugin_orchestrator::PluginOrchestrator;
 use crate::plugin_registry::PluginRegistry;
+use crate::restart_supervisor::{RestartPolicies, RestartSupervisor};
+use crate::secret_rotation::{SecretConsumers, SecretRotationWatcher};
+use crate::secret_stores::settings_mapper;
+use crate::source_wal::WalEventTimes;
+use crate::state_stores::StateStoreRegistry;
+use crate::storage_usage::{IndexCountCache, InstanceStorage};
 use drasi_host_sdk::lifecycle::PluginLifecycleManager;
-use drasi_lib::secret_store::SecretStoreProvider;
+use drasi_lib::state_store::{MemoryStateStoreProvider, StateStoreProvider};
 use drasi_lib::DrasiLib;
 use drasi_plugin_sdk::{BootstrapPluginDescriptor, ReactionPluginDescriptor};
 use drasi_wal_redb::RedbWalProvider;
@@ -55,11 +65,23 @@ pub struct DrasiServer {
     host: String,
     port: u16,
     config_file_path: Option<String>,
-    read_only: Arc<bool>,
+    /// Environment overlay and key overrides applied to the config file.
+    load_options: LoadOptions,
+    /// Which changes the REST API accepts.
+    mode: ServerMode,
     plugin_registry: Arc<RwLock<PluginRegistry>>,
     plugin_orchestrator: Arc<PluginOrchestrator>,
     cors_allowed_origins: Vec<String>,
     watcher_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Whether component configs were migrated to newer plugin schema
+    /// versions at startup, so `run()` should persist them.
+    config_migrated: bool,
+    /// Which components consumed which secrets, filled in as the configured
+    /// components are created and handed to the registry in `run()`.
+    secret_consumers: SecretConsumers,
+    /// Circuit breakers of the plugin-backed components, shared by the
+    /// plugin orchestrator and the registry built in `run()`.
+    plugin_breakers: PluginCircuitBreakers,
 }
 
 struct PreparedInstance {
@@ -72,6 +94,21 @@ struct PreparedInstance {
     /// runtime-created sources can resolve `bootstrapProvider: <id>`
     /// references. Empty for programmatically built instances.
     bootstrap_providers: HashMap<String, BootstrapProviderConfig>,
+    /// `restartPolicy` settings of the instance's configured sources and
+    /// reactions. Empty for programmatically built instances.
+    restart_policies: RestartPolicies,
+    /// How often to check the instance's secrets for rotation, from its
+    /// `secretStore`. `None` when rotation is not enabled.
+    secret_refresh_interval: Option<Duration>,
+    /// The provider the core was built with, registered so the REST API can
+    /// inspect it. `None` for programmatically built instances.
+    state_store: Option<Arc<dyn StateStoreProvider>>,
+    /// Where the instance keeps its index, WAL and state store. `None` for
+    /// programmatically built instances.
+    storage: Option<InstanceStorage>,
+    /// The instance's `archiveRetention`. `None` when the instance has no
+    /// persistent archive to bound.
+    archive_retention: Option<ArchiveRetentionConfig>,
 }
 
 impl DrasiServer {
@@ -83,7 +120,52 @@ impl DrasiServer {
         skip_verification: bool,
         enable_ui: bool,
     ) -> Result<Self> {
-        let mut config = load_config_file(&config_path)?;
+        Self::new_with_options(
+            config_path,
+            LoadOptions::default(),
+            port,
+            plugins_dir,
+            skip_verification,
+            enable_ui,
+        )
+        .await
+    }
+
+    /// Create a new DrasiServer from a configuration file with an environment
+    /// overlay (e.g. `server.prod.yaml` for `prod`) and key overrides applied.
+    pub async fn new_with_options(
+        config_path: PathBuf,
+        load_options: LoadOptions,
+        port: u16,
+        plugins_dir: PathBuf,
+        skip_verification: bool,
+        enable_ui: bool,
+    ) -> Result<Self> {
+        Self::new_with_state_stores(
+            config_path,
+            load_options,
+            port,
+            plugins_dir,
+            skip_verification,
+            enable_ui,
+            StateStoreRegistry::new(),
+        )
+        .await
+    }
+
+    /// Like [`Self::new_with_options`], creating each instance's `stateStore`
+    /// from the kinds registered in `state_stores` and those exported by the
+    /// plugins in `plugins_dir`.
+    pub async fn new_with_state_stores(
+        config_path: PathBuf,
+        load_options: LoadOptions,
+        port: u16,
+        plugins_dir: PathBuf,
+        skip_verification: bool,
+        enable_ui: bool,
+        mut state_stores: StateStoreRegistry,
+    ) -> Result<Self> {
+        let (mut config, _) = load_config_layers(&config_path, &load_options)?;
         config.validate()?;
 
         // CLI --skip-verification flag overrides config (disables when set)
@@ -91,6 +173,10 @@ impl DrasiServer {
             config.verify_plugins = false;
         }
 
+        // Crash-loop protection for plugin-backed components
+        let plugin_breakers =
+            PluginCircuitBreakers::new(config.plugin_circuit_breaker.clone().unwrap_or_default());
+
         // Create and populate the plugin registry
         let mut plugin_registry = PluginRegistry::new();
         register_core_plugins(&mut plugin_registry);
@@ -100,24 +186,69 @@ impl DrasiServer {
             crate::plugin_install::auto_install_plugins(&config, &plugins_dir, false).await?;
         }
 
-        // When verify_plugins is enabled, re-verify plugin signatures against the
-        // OCI registry (not the lockfile cache, which could be tampered with).
-        // Verifications run in parallel for speed.
+        // When verify_plugins is enabled, plugins signed with a configured
+        // trusted key are verified offline first. Remaining lockfile entries are
+        // re-verified against the OCI registry (not the lockfile cache, which
+        // could be tampered with). Registry verifications run in parallel.
         let mut verified_files = if config.verify_plugins {
+            use crate::plugin_key_verification::{
+                load_trusted_keys, verify_plugins_dir, KeySignatureStatus,
+            };
             use drasi_host_sdk::registry::{
                 matches_trusted_identity, CosignVerifier, RegistryAuth, SignatureStatus,
                 TrustedIdentity, VerificationConfig,
             };
 
+            let mut allowed: std::collections::HashSet<String> = std::collections::HashSet::new();
+            // Plugins whose key signature failed are not given a second
+            // chance via keyless verification.
+            let mut rejected: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+            if !config.trusted_keys.is_empty() && plugins_dir.exists() {
+                let base_dir = config_path
+                    .parent()
+                    .map(|p| p.to_path_buf())
+                    .unwrap_or_default();
+                let keys = load_trusted_keys(&config.trusted_keys, &base_dir)?;
+                let results = verify_plugins_dir(
+                    &plugins_dir,
+                    &keys,
+                    crate::dynamic_loading::is_plugin_file_name,
+                )?;
+                for (filename, status) in results {
+                    match status {
+                        KeySignatureStatus::Verified { key_name } => {
+                            info!("✓ {filename} — trusted (key={key_name})");
+                            allowed.insert(filename);
+                        }
+                        KeySignatureStatus::Invalid(reason) => {
+                            log::error!("⚠ {filename} — key signature rejected: {reason}");
+                            rejected.insert(filename);
+                        }
+                        KeySignatureStatus::Unsigned => {
+                            debug!("{filename} — no key signature");
+                        }
+                    }
+                }
+            }
+
             let lockfile = crate::plugin_lockfile::PluginLockfile::read(&plugins_dir)
                 .ok()
                 .flatten()
                 .unwrap_or_default();
 
-            if lockfile.is_empty() {
+            // Build batch: (oci_reference, filename) from lockfile entries that
+            // were not already settled by key verification.
+            let batch: Vec<(String, String)> = lockfile
+                .plugins
+                .values()
+                .filter(|p| !allowed.contains(&p.filename) && !rejected.contains(&p.filename))
+                .map(|p| (p.reference.clone(), p.filename.clone()))
+                .collect();
+
+            if lockfile.is_empty() && allowed.is_empty() {
                 warn!("verify_plugins enabled but no lockfile found — no plugins will be loaded");
-                Some(std::collections::HashSet::new())
-            } else {
+            } else if !batch.is_empty() {
                 // Build the list of trusted identities from config,
                 // defaulting to drasi-project if none configured.
                 let trusted: Vec<TrustedIdentity> = if config.trusted_identities.is_empty() {
@@ -150,19 +281,11 @@ impl DrasiServer {
                     }
                 };
 
-                // Build batch: (oci_reference, filename) from lockfile
-                let batch: Vec<(String, String)> = lockfile
-                    .plugins
-                    .values()
-                    .map(|p| (p.reference.clone(), p.filename.clone()))
-                    .collect();
-
-                // Verify all plugins in parallel against the registry
+                // Verify remaining plugins in parallel against the registry
                 let results = verifier.verify_batch(batch, &oci_auth).await;
 
-                let allowed: std::collections::HashSet<String> = results
-                    .into_iter()
-                    .filter_map(|(filename, status)| match status {
+                allowed.extend(results.into_iter().filter_map(|(filename, status)| {
+                    match status {
                         SignatureStatus::Verified(v)
                             if matches_trusted_identity(&v, &trusted) =>
                         {
@@ -186,11 +309,11 @@ impl DrasiServer {
                             None
                         }
                         SignatureStatus::Unsigned => None,
-                    })
-                    .collect();
-
-                Some(allowed)
+                    }
+                }));
             }
+
+            Some(allowed)
         } else {
             None
         };
@@ -255,6 +378,18 @@ impl DrasiServer {
                 verified_files.as_ref(),
             )?;
             startup_plugin_records = load_stats.loaded_plugins.clone();
+            load_stats.register_state_stores(&mut state_stores);
+            if load_stats.compatibility.has_issues() {
+                let mut report = load_stats.compatibility.clone();
+                if report.has_errors() {
+                    let registry_url = config
+                        .plugin_registry
+                        .as_deref()
+                        .unwrap_or("ghcr.io/drasi-project");
+                    report.suggest_upgrades(registry_url).await;
+                }
+                warn!("Plugin compatibility issues:\n{}", report.render_table());
+            }
             plugin_load_stats = Some(load_stats);
         }
 
@@ -266,12 +401,15 @@ impl DrasiServer {
             crate::plugin_operations::PluginOperations::verification_config(&config);
         let plugin_ops =
             crate::plugin_operations::PluginOperations::from_config(&config, plugins_dir.clone());
-        let plugin_orchestrator = Arc::new(PluginOrchestrator::with_ops(
-            lifecycle,
-            plugins_dir.clone(),
-            plugin_ops,
-            verification_config,
-        ));
+        let plugin_orchestrator = Arc::new(
+            PluginOrchestrator::with_ops(
+                lifecycle,
+                plugins_dir.clone(),
+                plugin_ops,
+                verification_config,
+            )
+            .with_circuit_breakers(plugin_breakers.clone()),
+        );
 
         // Register startup-loaded plugins in the orchestrator
         plugin_orchestrator
@@ -346,21 +484,44 @@ impl DrasiServer {
             None
         };
 
+        // Migrate component configs written against older plugin schemas
+        let migration_report = {
+            let reg = plugin_registry.read().await;
+            crate::config::ConfigMigrations::from_registry(&reg).migrate_config(&mut config, &reg)
+        };
+        if migration_report.has_errors() {
+            return Err(anyhow::anyhow!(
+                "Component configs could not be migrated to the installed plugin versions:\n{migration_report}"
+            ));
+        }
+        if !migration_report.migrated.is_empty() {
+            info!(
+                "Migrated component configs to current plugin schema versions:\n{migration_report}"
+            );
+        }
+
         // Resolve server settings using the mapper
-        let mapper = DtoMapper::new();
+        let mapper = settings_mapper(&config)?;
         let resolved_settings = map_server_settings(&config, &mapper)?;
         let resolved_instances = config.resolved_instances(&mapper)?;
 
-        // Determine persistence and read-only status
-        // Read-only mode is ONLY enabled when the config file is not writable
-        // persist_config: false means "don't save changes" but still allows API mutations
+        // Determine the API mode and persistence status.
+        // `mode` decides which changes the API accepts; persist_config only
+        // decides whether they are saved. A config file that cannot be
+        // written would lose every change, so a read-write server is locked.
         let file_writable = Self::check_write_access(&config_path);
         let persistence_enabled = resolved_settings.persist_config;
-        let read_only = !file_writable; // Only read-only if file is not writable
+        let mode = if !file_writable && config.mode.is_read_write() {
+            ServerMode::Locked
+        } else {
+            config.mode
+        };
 
+        if !mode.is_read_write() {
+            warn!("API in {mode} mode: {}.", mode.summary());
+        }
         if !file_writable {
-            warn!("Config file is not writable. API in READ-ONLY mode.");
-            warn!("Cannot create or delete components via API.");
+            warn!("Config file is not writable. API changes cannot be saved.");
         } else if !persistence_enabled {
             info!("Persistence disabled by configuration (persist_config: false).");
             warn!("API modifications will not persist across restarts.");
@@ -402,55 +563,22 @@ impl DrasiServer {
             }
         }
 
-        // Resolve the process-wide secret store provider (if any instance configures one).
-        // The FFI config resolver is a single global callback — only one secret store
-        // provider can be active. Validate that all instances agree on the same config.
-        let process_secret_store: Option<Arc<dyn SecretStoreProvider>> = {
-            let mut chosen: Option<(&str, &SecretStoreConfig)> = None;
-            for inst in &resolved_instances {
-                if let Some(ref ss) = inst.secret_store {
-                    if let Some((prev_id, prev_cfg)) = chosen {
-                        if prev_cfg != ss {
-                            return Err(anyhow::anyhow!(
-                                "Conflicting secret store configurations: instance '{}' and '{}' \
-                                 specify different secret store configs. Only one process-wide \
-                                 secret store is supported because the FFI config resolver is global.",
-                                prev_id,
-                                inst.id,
-                            ));
-                        }
-                    } else {
-                        chosen = Some((&inst.id, ss));
-                    }
-                }
-            }
-            if let Some((inst_id, ss_config)) = chosen {
-                info!(
-                    "Enabling process-wide secret store with '{}' provider (from instance '{}')",
-                    ss_config.kind, inst_id
-                );
-                let provider =
-                    create_secret_store_from_registry(&plugin_registry, ss_config).await?;
-
-                // Inject the config resolver callback into all loaded plugins so their
-                // DtoMapper can resolve ConfigValue::Secret references through the host.
-                if let Some(ref stats) = plugin_load_stats {
-                    let resolver_ctx = build_config_resolver_context(
-                        provider.clone(),
-                        tokio::runtime::Handle::current(),
-                    );
-                    stats.inject_config_resolver_into_all(resolver_ctx, config_resolver_callback());
-                    info!(
-                        "Injected config resolver into {} loaded plugin(s)",
-                        stats.plugins_loaded,
-                    );
-                }
-                Some(provider)
-            } else {
-                None
-            }
-        };
+        // Inject the config resolver callback into all loaded plugins so their
+        // DtoMapper can resolve ConfigValue::Secret references through the
+        // host. Each reference is resolved by the secret store of the instance
+        // that owns the component, including instances created later.
+        if let Some(ref stats) = plugin_load_stats {
+            stats.inject_config_resolver_into_all(
+                ConfigResolverContext::global().as_ptr(),
+                config_resolver_callback(),
+            );
+            info!(
+                "Injected config resolver into {} loaded plugin(s)",
+                stats.plugins_loaded,
+            );
+        }
 
+        let secret_consumers = SecretConsumers::new();
         for instance in resolved_instances {
             let mut builder = DrasiLib::builder().with_id(&instance.id);
 
@@ -467,47 +595,74 @@ impl DrasiServer {
 
             // Register the persistent RocksDB index provider as the instance
             // default when persist_index is enabled.
-            if instance.persist_index {
+            let index_databases = instance.persist_index.then(IndexDatabases::new);
+            if let Some(databases) = &index_databases {
                 builder = crate::index_provider::apply_rocksdb_index(
                     builder,
                     &instance.id,
                     instance.enable_archive,
+                    databases.clone(),
                 );
             }
 
-            // Create and add state store provider if configured
-            if let Some(state_store_config) = instance.state_store.clone() {
-                info!(
-                    "Enabling persistent state store for instance '{}' with {} provider",
-                    instance.id,
-                    state_store_config.kind()
-                );
-                let state_store_provider = create_state_store_provider(state_store_config)?;
-                builder = builder.with_state_store_provider(state_store_provider);
-            }
+            // Create the state store provider: the configured one, or the
+            // in-memory store DrasiLib would otherwise create internally.
+            let state_store: Arc<dyn StateStoreProvider> = match &instance.state_store {
+                Some(state_store_config) => {
+                    info!(
+                        "Enabling persistent state store for instance '{}' with {} provider",
+                        instance.id, state_store_config.kind
+                    );
+                    create_state_store_from_registry(&state_stores, state_store_config)?
+                }
+                None => Arc::new(MemoryStateStoreProvider::new()),
+            };
+            builder = builder.with_state_store_provider(state_store.clone());
+            let state_store_files = instance
+                .state_store
+                .as_ref()
+                .map(|config| state_stores.data_files(config))
+                .transpose()?;
 
             // Create WAL provider for durable source event persistence
-            {
-                let wal_path = PathBuf::from(format!("./data/{safe_id}/wal"));
+            let wal_path = PathBuf::from(format!("./data/{safe_id}/wal"));
+            info!(
+                "Enabling WAL provider for instance '{}' at: {}",
+                instance.id,
+                wal_path.display()
+            );
+            let wal_times = WalEventTimes::new();
+            let wal_provider = crate::source_wal::with_retention(
+                &instance.id,
+                Arc::new(RedbWalProvider::new(&wal_path)),
+                instance.wal_retention.as_ref(),
+                &wal_times,
+            );
+            builder = builder.with_wal_provider(wal_provider.clone());
+
+            // Create this instance's secret store; its components' secrets
+            // are resolved through it.
+            if let Some(ref secret_store_config) = instance.secret_store {
                 info!(
-                    "Enabling WAL provider for instance '{}' at: {}",
-                    instance.id,
-                    wal_path.display()
+                    "Enabling secret store for instance '{}' with '{}' provider",
+                    instance.id, secret_store_config.kind
                 );
-                let wal_provider = Arc::new(RedbWalProvider::new(&wal_path));
-                builder = builder.with_wal_provider(wal_provider);
-            }
-            // Attach the process-wide secret store provider to this instance's builder
-            if instance.secret_store.is_some() {
-                if let Some(ref provider) = process_secret_store {
-                    builder = builder.with_secret_store_provider(provider.clone());
-                }
+                let provider =
+                    create_secret_store_from_registry(&plugin_registry, secret_store_config)
+                        .await?;
+                ConfigResolverContext::global().set_instance_store(&instance.id, provider.clone());
+                builder = builder.with_secret_store_provider(provider);
             }
 
             // Build the identity-provider map for this instance. Sources and
             // reactions can reference entries here via `identityProvider: <id>`.
-            let identity_providers =
-                build_identity_provider_map(&plugin_registry, &instance.identity_providers).await?;
+            let identity_providers = build_identity_provider_map(
+                &plugin_registry,
+                &instance.id,
+                &secret_consumers,
+                &instance.identity_providers,
+            )
+            .await?;
             // Build the bootstrap-provider config map for this instance. Sources
             // can reference entries here via `bootstrapProvider: <id>`; each
             // referencing source instantiates its own provider from the config.
@@ -523,8 +678,14 @@ impl DrasiServer {
                 let source_config =
                     resolve_source_bootstrap_provider(source_config, &bootstrap_providers)?;
                 let identity_ref = source_config.identity_provider().map(str::to_string);
-                let (source, plugin_meta) =
-                    create_source_locked(&plugin_registry, source_config).await?;
+                let (source, plugin_meta) = create_source_locked(
+                    &plugin_registry,
+                    &instance.id,
+                    &secret_consumers,
+                    &plugin_breakers,
+                    source_config,
+                )
+                .await?;
                 if let Some(id) = identity_ref {
                     let provider = identity_providers.get(&id).cloned().ok_or_else(|| {
                         anyhow::anyhow!(
@@ -546,8 +707,14 @@ impl DrasiServer {
             // Create and add reactions from config
             for reaction_config in instance.reactions.clone() {
                 let identity_ref = reaction_config.identity_provider().map(str::to_string);
-                let (reaction, plugin_meta) =
-                    create_reaction_locked(&plugin_registry, reaction_config).await?;
+                let (reaction, plugin_meta) = create_reaction_locked(
+                    &plugin_registry,
+                    &instance.id,
+                    &secret_consumers,
+                    &plugin_breakers,
+                    reaction_config,
+                )
+                .await?;
                 if let Some(id) = identity_ref {
                     let provider = identity_providers.get(&id).cloned().ok_or_else(|| {
                         anyhow::anyhow!(
@@ -561,18 +728,43 @@ impl DrasiServer {
                 builder = builder.with_reaction_metadata(reaction, plugin_meta);
             }
 
+            let restart_policies =
+                RestartPolicies::from_configs(&instance.sources, &instance.reactions);
+
             // Build and initialize the core
             let core = builder
                 .build()
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to create DrasiLib: {e}"))?;
 
+            let archive_retention = applicable_retention(
+                &instance.id,
+                instance.persist_index,
+                instance.enable_archive,
+                instance.archive_retention.as_ref(),
+            );
             instances.push(PreparedInstance {
                 id_hint: Some(instance.id),
                 persist_index: instance.persist_index,
                 enable_archive: instance.enable_archive,
                 core,
                 bootstrap_providers,
+                restart_policies,
+                secret_refresh_interval: instance
+                    .secret_store
+                    .as_ref()
+                    .and_then(|store| store.refresh_interval()),
+                state_store: Some(state_store),
+                storage: Some(InstanceStorage {
+                    index: index_databases,
+                    wal: wal_provider,
+                    wal_times,
+                    wal_dir: wal_path,
+                    wal_retention: instance.wal_retention,
+                    state_store_files,
+                    index_counts: IndexCountCache::new(),
+                }),
+                archive_retention,
             });
         }
 
@@ -583,11 +775,15 @@ impl DrasiServer {
             host: resolved_settings.host,
             port,
             config_file_path: Some(config_path.to_string_lossy().to_string()),
-            read_only: Arc::new(read_only),
+            load_options,
+            mode,
             plugin_registry,
             plugin_orchestrator,
             cors_allowed_origins: config.cors_allowed_origins.clone(),
             watcher_handle,
+            config_migrated: !migration_report.migrated.is_empty(),
+            secret_consumers,
+            plugin_breakers,
         })
     }
 
@@ -604,7 +800,10 @@ impl DrasiServer {
         register_core_plugins(&mut plugin_registry);
         let plugin_registry = Arc::new(RwLock::new(plugin_registry));
         let lifecycle = Arc::new(PluginLifecycleManager::new(plugin_registry.clone()));
-        let plugin_orchestrator = Arc::new(PluginOrchestrator::new(lifecycle));
+        let plugin_breakers = PluginCircuitBreakers::default();
+        let plugin_orchestrator = Arc::new(
+            PluginOrchestrator::new(lifecycle).with_circuit_breakers(plugin_breakers.clone()),
+        );
         Self {
             instances: vec![PreparedInstance {
                 id_hint: None,
@@ -612,17 +811,26 @@ impl DrasiServer {
                 enable_archive: false,
                 core,
                 bootstrap_providers: HashMap::new(),
+                restart_policies: RestartPolicies::default(),
+                secret_refresh_interval: None,
+                state_store: None,
+                storage: None,
+                archive_retention: None,
             }],
             enable_api,
             enable_ui,
             host,
             port,
             config_file_path,
-            read_only: Arc::new(false), // Programmatic mode assumes write access
+            load_options: LoadOptions::default(),
+            mode: ServerMode::ReadWrite, // Programmatic mode assumes write access
             plugin_registry,
             plugin_orchestrator,
             cors_allowed_origins: Vec::new(), // Permissive by default for programmatic usage
             watcher_handle: None,
+            config_migrated: false,
+            secret_consumers: SecretConsumers::new(),
+            plugin_breakers,
         }
     }
 
@@ -643,6 +851,11 @@ impl DrasiServer {
                 enable_archive: false,
                 core,
                 bootstrap_providers: HashMap::new(),
+                restart_policies: RestartPolicies::default(),
+                secret_refresh_interval: None,
+                state_store: None,
+                storage: None,
+                archive_retention: None,
             })
             .collect();
 
@@ -650,7 +863,10 @@ impl DrasiServer {
         register_core_plugins(&mut plugin_registry);
         let plugin_registry = Arc::new(RwLock::new(plugin_registry));
         let lifecycle = Arc::new(PluginLifecycleManager::new(plugin_registry.clone()));
-        let plugin_orchestrator = Arc::new(PluginOrchestrator::new(lifecycle));
+        let plugin_breakers = PluginCircuitBreakers::default();
+        let plugin_orchestrator = Arc::new(
+            PluginOrchestrator::new(lifecycle).with_circuit_breakers(plugin_breakers.clone()),
+        );
         Self {
             instances,
             enable_api,
@@ -658,11 +874,15 @@ impl DrasiServer {
             host,
             port,
             config_file_path,
-            read_only: Arc::new(false),
+            load_options: LoadOptions::default(),
+            mode: ServerMode::ReadWrite,
             plugin_registry,
             plugin_orchestrator,
             cors_allowed_origins: Vec::new(), // Permissive by default for programmatic usage
             watcher_handle: None,
+            config_migrated: false,
+            secret_consumers: SecretConsumers::new(),
+            plugin_breakers,
         }
     }
 
@@ -693,6 +913,14 @@ impl DrasiServer {
         let mut archive_settings: IndexMap<String, bool> = IndexMap::new();
         let mut bootstrap_providers_by_id: Vec<(String, HashMap<String, BootstrapProviderConfig>)> =
             Vec::new();
+        let mut supervisors_by_id: Vec<(String, Arc<RestartSupervisor>)> = Vec::new();
+        let mut secret_watchers_by_id: Vec<(String, Arc<SecretRotationWatcher>)> = Vec::new();
+        let mut state_stores_by_id: Vec<(String, Arc<dyn StateStoreProvider>)> = Vec::new();
+        let mut archive_retention_by_id: Vec<(String, Arc<ArchiveRetention>)> = Vec::new();
+        let mut storage_by_id: Vec<(String, Arc<InstanceStorage>)> = Vec::new();
+        // Shared with the registry built below; supervisors and secret
+        // watchers started first take the same per-instance write locks.
+        let revisions = ComponentRevisions::new();
 
         // Take ownership of instances to avoid partial move of self
         let instances = std::mem::take(&mut self.instances);
@@ -709,10 +937,41 @@ impl DrasiServer {
             };
 
             let core = Arc::new(core);
+            // Subscribe before starting so auto-start failures are restarted too.
+            let supervisor = Arc::new(RestartSupervisor::spawn(
+                &core,
+                &id,
+                revisions.clone(),
+                instance.restart_policies,
+            ));
             core.start().await?;
             persist_settings.insert(id.clone(), instance.persist_index);
             archive_settings.insert(id.clone(), instance.enable_archive);
             bootstrap_providers_by_id.push((id.clone(), bootstrap_providers));
+            supervisors_by_id.push((id.clone(), supervisor));
+            if let Some(interval) = instance.secret_refresh_interval {
+                let watcher = SecretRotationWatcher::spawn(
+                    &core,
+                    &id,
+                    self.plugin_registry.clone(),
+                    revisions.clone(),
+                    self.secret_consumers.clone(),
+                    self.plugin_breakers.clone(),
+                    interval,
+                );
+                secret_watchers_by_id.push((id.clone(), Arc::new(watcher)));
+            }
+            if let Some(state_store) = instance.state_store {
+                state_stores_by_id.push((id.clone(), state_store));
+            }
+            if let Some(storage) = instance.storage {
+                let databases = storage.index.clone();
+                if let (Some(config), Some(databases)) = (instance.archive_retention, databases) {
+                    let retention = ArchiveRetention::spawn(&id, config, databases);
+                    archive_retention_by_id.push((id.clone(), Arc::new(retention)));
+                }
+                storage_by_id.push((id.clone(), Arc::new(storage)));
+            }
             instance_map.insert(id, core);
         }
 
@@ -726,7 +985,9 @@ impl DrasiServer {
         let instances = Arc::new(instance_map);
 
         // Create the instance registry from the map
-        let registry = InstanceRegistry::from_map((*instances).clone());
+        let registry = InstanceRegistry::from_map_with_revisions((*instances).clone(), revisions)
+            .with_secret_consumers(self.secret_consumers.clone())
+            .with_plugin_breakers(self.plugin_breakers.clone());
 
         // Record each instance's top-level bootstrap provider configs so the
         // source create/upsert handlers can resolve `bootstrapProvider: <id>`
@@ -734,71 +995,111 @@ impl DrasiServer {
         for (id, providers) in bootstrap_providers_by_id {
             registry.set_bootstrap_providers(id, providers).await;
         }
+        for (id, supervisor) in supervisors_by_id {
+            registry.set_restart_supervisor(id, supervisor).await;
+        }
+        for (id, watcher) in secret_watchers_by_id {
+            registry.set_secret_rotation_watcher(id, watcher).await;
+        }
+        for (id, state_store) in state_stores_by_id {
+            registry.set_state_store(id, state_store).await;
+        }
+        for (id, retention) in archive_retention_by_id {
+            registry.set_archive_retention(id, retention).await;
+        }
+        for (id, storage) in storage_by_id {
+            registry.set_storage(id, storage).await;
+        }
 
         // Initialize persistence and extract solutions_dir if config file is provided
         let (config_persistence, solutions_dir) = if let Some(config_file) = &self.config_file_path
         {
-            if !*self.read_only {
-                // Need to reload config to check persist_config flag and get initial configs
-                let config = load_config_file(PathBuf::from(config_file))?;
-                let solutions_dir = config.solutions_dir.clone();
-                let mapper = DtoMapper::new();
-                let resolved_settings = map_server_settings(&config, &mapper)?;
-                let persistence_enabled = resolved_settings.persist_config;
-
-                if persistence_enabled {
-                    // Persistence is enabled - create ConfigPersistence instance
-                    let persistence = Arc::new(ConfigPersistence::new(
-                        PathBuf::from(config_file),
-                        registry.clone(),
-                        self.host.clone(),
-                        self.port,
-                        resolved_settings.log_level,
-                        true, // persist_config = true
-                        persist_settings.clone(),
-                        archive_settings.clone(),
-                        config.solutions_dir.clone(),
-                        &config,
-                    ));
-                    // Register initial instance configs so save() preserves
-                    // per-instance settings (secret_store, state_store, etc.)
-                    let initial_instances: Vec<DrasiLibInstanceConfig> =
-                        if config.instances.is_empty() {
-                            vec![DrasiLibInstanceConfig {
-                                id: config.id.clone(),
-                                persist_index: config.persist_index,
-                                enable_archive: config.enable_archive,
-                                state_store: config.state_store.clone(),
-                                secret_store: config.secret_store.clone(),
-                                default_priority_queue_capacity: config
-                                    .default_priority_queue_capacity
-                                    .clone(),
-                                default_dispatch_buffer_capacity: config
-                                    .default_dispatch_buffer_capacity
-                                    .clone(),
-                                sources: config.sources.clone(),
-                                queries: config.queries.clone(),
-                                reactions: config.reactions.clone(),
-                                identity_providers: config.identity_providers.clone(),
-                                bootstrap_providers: config.bootstrap_providers.clone(),
-                            }]
-                        } else {
-                            config.instances.clone()
-                        };
-                    for inst in initial_instances {
-                        persistence.register_instance(inst).await;
+            let writable = Self::check_write_access(&PathBuf::from(config_file));
+            // Need to reload config to check persist_config flag and get initial configs
+            let (config, mut layers) =
+                load_config_layers(PathBuf::from(config_file), &self.load_options)?;
+            // A config without an `id` gets a new one on every load; top-level
+            // components belong to the instance that is actually running.
+            if config.instances.is_empty() {
+                if let [id] = registry.list_ids().await.as_slice() {
+                    layers.set_top_level_id(id);
+                }
+            }
+            let mapper = settings_mapper(&config)?;
+            let resolved_settings = map_server_settings(&config, &mapper)?;
+            let persistence_enabled = writable && resolved_settings.persist_config;
+
+            // Built even when saves are disabled: it tracks the loaded config
+            // and runtime changes so the config export shows the running state.
+            let persistence = Arc::new(
+                ConfigPersistence::new(
+                    PathBuf::from(config_file),
+                    registry.clone(),
+                    self.host.clone(),
+                    self.port,
+                    resolved_settings.log_level,
+                    persistence_enabled,
+                    persist_settings.clone(),
+                    archive_settings.clone(),
+                    config.solutions_dir.clone(),
+                    &config,
+                )
+                .with_plugin_registry(self.plugin_registry.clone())
+                .with_layers(layers),
+            );
+            // Register initial instance configs so save() preserves
+            // per-instance settings (secret_store, state_store, etc.)
+            let initial_instances: Vec<DrasiLibInstanceConfig> = if config.instances.is_empty() {
+                vec![DrasiLibInstanceConfig {
+                    id: config.id.clone(),
+                    persist_index: config.persist_index,
+                    enable_archive: config.enable_archive,
+                    archive_retention: config.archive_retention.clone(),
+                    wal_retention: config.wal_retention.clone(),
+                    state_store: config.state_store.clone(),
+                    secret_store: config.secret_store.clone(),
+                    default_priority_queue_capacity: config.default_priority_queue_capacity.clone(),
+                    default_dispatch_buffer_capacity: config
+                        .default_dispatch_buffer_capacity
+                        .clone(),
+                    sources: config.sources.clone(),
+                    queries: config.queries.clone(),
+                    reactions: config.reactions.clone(),
+                    identity_providers: config.identity_providers.clone(),
+                    bootstrap_providers: config.bootstrap_providers.clone(),
+                }]
+            } else {
+                config.instances.clone()
+            };
+            for inst in initial_instances {
+                persistence.register_instance(inst).await;
+            }
+
+            if persistence_enabled {
+                // Components migrated at startup must still differ from the
+                // baseline so the save below writes them to their files.
+                if !self.config_migrated {
+                    if let Err(e) = persistence.capture_layer_baseline().await {
+                        warn!("Failed to capture the layered config baseline: {e}");
                     }
+                }
 
-                    info!("Configuration persistence enabled");
-                    (Some(persistence), solutions_dir)
-                } else {
-                    info!("Configuration persistence disabled (persist_config: false)");
-                    (None, solutions_dir)
+                // Write configs migrated at startup back so the file
+                // records the schema versions they now match.
+                if self.config_migrated {
+                    if let Err(e) = persistence.save_for("migrating component configs").await {
+                        warn!("Failed to persist migrated component configs: {e}");
+                    }
                 }
+
+                info!("Configuration persistence enabled");
+            } else if writable {
+                info!("Configuration persistence disabled (persist_config: false)");
             } else {
-                info!("Configuration persistence disabled (read-only mode)");
-                (None, None)
+                info!("Configuration persistence disabled (config file is not writable)");
             }
+            let solutions_dir = if writable { config.solutions_dir } else { None };
+            (Some(persistence), solutions_dir)
         } else {
             info!("No config file provided - persistence disabled");
             (None, None)
@@ -867,7 +1168,7 @@ impl DrasiServer {
         // Build the v1 API router
         let v1_router = api::build_v1_router(
             registry.clone(),
-            self.read_only.clone(),
+            self.mode,
             config_persistence.clone(),
             self.plugin_registry.clone(),
             solutions_dir,
@@ -877,7 +1178,7 @@ impl DrasiServer {
         let plugin_router = api::v1::build_plugin_router(
             self.plugin_orchestrator.clone(),
             registry.clone(),
-            self.read_only.clone(),
+            self.mode,
         );
 
         // Build the main application router
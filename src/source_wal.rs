@@ -0,0 +1,529 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Retention, inspection and replay for source WALs.
+//!
+//! Sources that write a WAL prune it themselves once every subscribed query
+//! has checkpointed an event. [`RetainingWalProvider`] wraps an instance's
+//! provider and holds back the events `walRetention` asks to keep, so they
+//! remain available to replay. It also records each event's transaction time
+//! in [`WalEventTimes`], so inspection and replay by time never load the
+//! whole WAL.
+//!
+//! A persistent query records the last WAL sequence it processed per source
+//! in the checkpoint store of its RocksDB database, and resumes from there
+//! when started. Replay rewinds that checkpoint through
+//! [`RocksDbCheckpointStore`] while the query is stopped; on restart the
+//! source re-delivers everything after it.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bytes::Bytes;
+use drasi_core::interface::{CheckpointStore, SessionControl};
+use drasi_core::models::SourceChange;
+use drasi_index_rocksdb::{
+    IndexDb, RocksDbCheckpointStore, RocksDbSessionControl, RocksDbSessionState,
+};
+use drasi_lib::wal::{WalError, WalProvider, WriteAheadLogConfig};
+use log::info;
+
+use crate::api::models::WalRetentionConfig;
+
+/// Wrap `provider` in a [`RetainingWalProvider`] that records event times in
+/// `times` and holds back the events the instance's `walRetention`, if any,
+/// keeps.
+pub(crate) fn with_retention(
+    instance_id: &str,
+    provider: Arc<dyn WalProvider>,
+    retention: Option<&WalRetentionConfig>,
+    times: &WalEventTimes,
+) -> Arc<dyn WalProvider> {
+    if let Some(retention) = retention {
+        info!("Retaining WAL events of instance '{instance_id}' for replay: {retention:?}");
+    }
+    Arc::new(RetainingWalProvider::new(
+        provider,
+        retention.cloned().unwrap_or_default(),
+        times.clone(),
+    ))
+}
+
+/// [`WalProvider`] that keeps the events its [`WalRetentionConfig`] covers
+/// when a source prunes its WAL.
+///
+/// Every call is passed to the inner provider; only the sequence given to
+/// `prune_up_to` is lowered. Append times are remembered in memory, so
+/// events appended before the server started are not held back by
+/// `keepSecs`.
+pub struct RetainingWalProvider {
+    inner: Arc<dyn WalProvider>,
+    retention: WalRetentionConfig,
+    /// Per source, the sequences appended within the `keepSecs` window,
+    /// oldest first.
+    recent: Mutex<HashMap<String, VecDeque<(u64, Instant)>>>,
+    times: WalEventTimes,
+}
+
+impl RetainingWalProvider {
+    pub fn new(
+        inner: Arc<dyn WalProvider>,
+        retention: WalRetentionConfig,
+        times: WalEventTimes,
+    ) -> Self {
+        Self {
+            inner,
+            retention,
+            recent: Mutex::new(HashMap::new()),
+            times,
+        }
+    }
+
+    fn window(&self) -> Option<Duration> {
+        self.retention.keep_secs.map(Duration::from_secs)
+    }
+
+    /// Oldest sequence of `source_id` still inside the `keepSecs` window.
+    fn oldest_recent(&self, source_id: &str, window: Duration) -> Option<u64> {
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let appended = recent.get_mut(source_id)?;
+        if let Some(cutoff) = Instant::now().checked_sub(window) {
+            while appended.front().is_some_and(|(_, at)| *at < cutoff) {
+                appended.pop_front();
+            }
+        }
+        appended.front().map(|(sequence, _)| *sequence)
+    }
+}
+
+#[async_trait]
+impl WalProvider for RetainingWalProvider {
+    async fn register(&self, source_id: &str, config: WriteAheadLogConfig) -> Result<(), WalError> {
+        self.inner.register(source_id, config).await
+    }
+
+    async fn append(&self, source_id: &str, event: &SourceChange) -> Result<u64, WalError> {
+        let sequence = self.inner.append(source_id, event).await?;
+        self.times
+            .record(source_id, sequence, event.get_transaction_time());
+        if self.window().is_some() {
+            self.recent
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(source_id.to_string())
+                .or_default()
+                .push_back((sequence, Instant::now()));
+        }
+        Ok(sequence)
+    }
+
+    async fn read_from(
+        &self,
+        source_id: &str,
+        sequence: u64,
+    ) -> Result<Vec<(u64, SourceChange)>, WalError> {
+        self.inner.read_from(source_id, sequence).await
+    }
+
+    async fn prune_up_to(&self, source_id: &str, sequence: u64) -> Result<u64, WalError> {
+        let mut limit = sequence;
+        if let Some(keep) = self.retention.keep_events {
+            let head = self.inner.head_sequence(source_id).await?;
+            limit = limit.min(head.saturating_sub(keep));
+        }
+        if let Some(window) = self.window() {
+            if let Some(oldest) = self.oldest_recent(source_id, window) {
+                limit = limit.min(oldest.saturating_sub(1));
+            }
+        }
+        if limit == 0 {
+            return Ok(0);
+        }
+        let pruned = self.inner.prune_up_to(source_id, limit).await?;
+        self.times.forget_up_to(source_id, limit);
+        Ok(pruned)
+    }
+
+    async fn head_sequence(&self, source_id: &str) -> Result<u64, WalError> {
+        self.inner.head_sequence(source_id).await
+    }
+
+    async fn oldest_sequence(&self, source_id: &str) -> Result<Option<u64>, WalError> {
+        self.inner.oldest_sequence(source_id).await
+    }
+
+    async fn event_count(&self, source_id: &str) -> Result<u64, WalError> {
+        self.inner.event_count(source_id).await
+    }
+
+    async fn delete_wal(&self, source_id: &str) -> Result<(), WalError> {
+        self.recent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(source_id);
+        self.times.forget_source(source_id);
+        self.inner.delete_wal(source_id).await
+    }
+}
+
+/// Transaction times (milliseconds) of the events in each source's WAL, by
+/// sequence, oldest first. Two integers are kept per retained event.
+///
+/// [`RetainingWalProvider`] records the events it appends and forgets those
+/// it prunes. Events appended before the server started are read from the
+/// WAL once, the first time a lookup needs them. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct WalEventTimes {
+    by_source: Arc<Mutex<HashMap<String, VecDeque<(u64, u64)>>>>,
+}
+
+impl WalEventTimes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, source_id: &str, sequence: u64, time: u64) {
+        self.lock()
+            .entry(source_id.to_string())
+            .or_default()
+            .push_back((sequence, time));
+    }
+
+    fn forget_up_to(&self, source_id: &str, sequence: u64) {
+        if let Some(times) = self.lock().get_mut(source_id) {
+            while times.front().is_some_and(|(seq, _)| *seq <= sequence) {
+                times.pop_front();
+            }
+        }
+    }
+
+    fn forget_source(&self, source_id: &str) {
+        self.lock().remove(source_id);
+    }
+
+    /// Make the record of `source_id` start at the WAL's oldest event,
+    /// reading the events it is missing from `wal`. Returns `false` if the
+    /// WAL is empty.
+    async fn cover(&self, wal: &dyn WalProvider, source_id: &str) -> Result<bool, WalError> {
+        let Some(oldest) = wal.oldest_sequence(source_id).await? else {
+            return Ok(false);
+        };
+        let first = {
+            let mut by_source = self.lock();
+            let times = by_source.entry(source_id.to_string()).or_default();
+            // Read before a concurrent prune and added after it.
+            while times.front().is_some_and(|(seq, _)| *seq < oldest) {
+                times.pop_front();
+            }
+            times.front().map(|(seq, _)| *seq)
+        };
+        if first == Some(oldest) {
+            return Ok(true);
+        }
+        let events = wal.read_from(source_id, oldest).await?;
+        let mut by_source = self.lock();
+        let times = by_source.entry(source_id.to_string()).or_default();
+        let first = times.front().map_or(u64::MAX, |(seq, _)| *seq);
+        for (sequence, change) in events.iter().rev() {
+            if *sequence < first {
+                times.push_front((*sequence, change.get_transaction_time()));
+            }
+        }
+        Ok(true)
+    }
+
+    /// The first recorded event of `source_id` whose time `matches`, as
+    /// `(sequence, time)`.
+    fn find(&self, source_id: &str, matches: impl Fn(u64) -> bool) -> Option<(u64, u64)> {
+        self.lock()
+            .get(source_id)?
+            .iter()
+            .find(|(_, time)| matches(*time))
+            .copied()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, VecDeque<(u64, u64)>>> {
+        self.by_source.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Transaction time (milliseconds) of the oldest retained event.
+pub async fn oldest_event_time(
+    wal: &dyn WalProvider,
+    times: &WalEventTimes,
+    source_id: &str,
+) -> Result<Option<u64>, WalError> {
+    if !times.cover(wal, source_id).await? {
+        return Ok(None);
+    }
+    Ok(times.find(source_id, |_| true).map(|(_, time)| time))
+}
+
+/// Sequence of the first retained event whose transaction time is at or
+/// after `since` (milliseconds).
+pub async fn sequence_since(
+    wal: &dyn WalProvider,
+    times: &WalEventTimes,
+    source_id: &str,
+    since: u64,
+) -> Result<Option<u64>, WalError> {
+    if !times.cover(wal, source_id).await? {
+        return Ok(None);
+    }
+    Ok(times
+        .find(source_id, |time| time >= since)
+        .map(|(sequence, _)| sequence))
+}
+
+/// The last WAL sequence of `source_id` a query checkpointed, if any.
+pub async fn read_checkpoint(db: &Arc<IndexDb>, source_id: &str) -> Result<Option<u64>> {
+    let session_state = Arc::new(RocksDbSessionState::new(db.clone()));
+    let checkpoint = RocksDbCheckpointStore::new(db.clone(), session_state)
+        .read_checkpoint(source_id)
+        .await?;
+    Ok(checkpoint.map(|checkpoint| checkpoint.sequence))
+}
+
+/// Rewind a stopped query's checkpoint of `source_id` to `sequence`, so it
+/// resumes with the event after it. A checkpoint already at or before
+/// `sequence` is left as it is; moving it forward would skip the events in
+/// between. Returns the checkpoint the query now has, or `None`, changing
+/// nothing, if it has no checkpoint for the source.
+///
+/// WAL sources resume from the big-endian sequence stored as the position,
+/// so the checkpoint is staged with it as its position and committed the
+/// way the query commits its own.
+pub async fn rewind_checkpoint(
+    db: &Arc<IndexDb>,
+    source_id: &str,
+    sequence: u64,
+) -> Result<Option<u64>> {
+    let session_state = Arc::new(RocksDbSessionState::new(db.clone()));
+    let store = RocksDbCheckpointStore::new(db.clone(), session_state.clone());
+    let Some(current) = store.read_checkpoint(source_id).await? else {
+        return Ok(None);
+    };
+    if current.sequence <= sequence {
+        return Ok(Some(current.sequence));
+    }
+    let session = RocksDbSessionControl::new(session_state);
+    session.begin().await?;
+    let position = Bytes::copy_from_slice(&sequence.to_be_bytes());
+    if let Err(e) = store
+        .stage_checkpoint(source_id, sequence, Some(&position))
+        .await
+    {
+        session.rollback()?;
+        return Err(e.into());
+    }
+    session.commit().await?;
+    Ok(Some(sequence))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use drasi_core::models::{ElementMetadata, ElementReference};
+    use drasi_index_rocksdb::{open_unified_db, RocksDbMemoryBudget, RocksIndexOptions};
+    use drasi_wal_redb::RedbWalProvider;
+
+    const SOURCE: &str = "orders";
+
+    fn delete(id: &str, effective_from: u64) -> SourceChange {
+        SourceChange::Delete {
+            metadata: ElementMetadata {
+                reference: ElementReference::new(SOURCE, id),
+                labels: Arc::from(vec![Arc::from("Order")]),
+                effective_from,
+            },
+        }
+    }
+
+    async fn provider(
+        dir: &tempfile::TempDir,
+        retention: WalRetentionConfig,
+        events: u64,
+    ) -> RetainingWalProvider {
+        let wal = RetainingWalProvider::new(
+            Arc::new(RedbWalProvider::new(dir.path())),
+            retention,
+            WalEventTimes::new(),
+        );
+        wal.register(SOURCE, WriteAheadLogConfig::default())
+            .await
+            .unwrap();
+        for i in 1..=events {
+            wal.append(SOURCE, &delete(&format!("o{i}"), i * 1_000))
+                .await
+                .unwrap();
+        }
+        wal
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_newest_events() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let retention = WalRetentionConfig {
+            keep_events: Some(3),
+            keep_secs: None,
+        };
+        let wal = provider(&dir, retention, 10).await;
+
+        assert_eq!(wal.prune_up_to(SOURCE, 10).await.unwrap(), 7);
+        assert_eq!(wal.oldest_sequence(SOURCE).await.unwrap(), Some(8));
+        // Queries that are behind still decide.
+        assert_eq!(wal.prune_up_to(SOURCE, 5).await.unwrap(), 0);
+        assert_eq!(wal.event_count(SOURCE).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_recent_events() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let retention = WalRetentionConfig {
+            keep_events: None,
+            keep_secs: Some(3_600),
+        };
+        let wal = provider(&dir, retention, 5).await;
+        assert_eq!(wal.prune_up_to(SOURCE, 5).await.unwrap(), 0);
+        assert_eq!(wal.event_count(SOURCE).await.unwrap(), 5);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let retention = WalRetentionConfig {
+            keep_events: None,
+            keep_secs: Some(0),
+        };
+        let wal = provider(&dir, retention, 5).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(wal.prune_up_to(SOURCE, 5).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_event_times() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let wal = provider(&dir, WalRetentionConfig::default(), 5).await;
+        wal.prune_up_to(SOURCE, 1).await.unwrap();
+
+        let times = &wal.times;
+        assert_eq!(
+            oldest_event_time(&wal, times, SOURCE).await.unwrap(),
+            Some(2_000)
+        );
+        assert_eq!(
+            sequence_since(&wal, times, SOURCE, 3_500).await.unwrap(),
+            Some(4)
+        );
+        assert_eq!(
+            sequence_since(&wal, times, SOURCE, 0).await.unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            sequence_since(&wal, times, SOURCE, 9_000).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_event_times_of_events_appended_before_start() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let inner: Arc<dyn WalProvider> = Arc::new(RedbWalProvider::new(dir.path()));
+        inner
+            .register(SOURCE, WriteAheadLogConfig::default())
+            .await
+            .unwrap();
+        for i in 1..=3 {
+            inner
+                .append(SOURCE, &delete(&format!("o{i}"), i * 1_000))
+                .await
+                .unwrap();
+        }
+        // The server starts and wraps the provider; later events are recorded.
+        let times = WalEventTimes::new();
+        let wal = RetainingWalProvider::new(inner, WalRetentionConfig::default(), times.clone());
+        wal.append(SOURCE, &delete("o4", 4_000)).await.unwrap();
+
+        assert_eq!(
+            oldest_event_time(&wal, &times, SOURCE).await.unwrap(),
+            Some(1_000)
+        );
+        assert_eq!(
+            sequence_since(&wal, &times, SOURCE, 2_500).await.unwrap(),
+            Some(3)
+        );
+        wal.prune_up_to(SOURCE, 3).await.unwrap();
+        assert_eq!(
+            oldest_event_time(&wal, &times, SOURCE).await.unwrap(),
+            Some(4_000)
+        );
+        wal.delete_wal(SOURCE).await.unwrap();
+        assert!(times.find(SOURCE, |_| true).is_none());
+    }
+
+    fn checkpoint_store(db: &Arc<IndexDb>) -> (RocksDbCheckpointStore, RocksDbSessionControl) {
+        let session_state = Arc::new(RocksDbSessionState::new(db.clone()));
+        (
+            RocksDbCheckpointStore::new(db.clone(), session_state.clone()),
+            RocksDbSessionControl::new(session_state),
+        )
+    }
+
+    async fn checkpoint(db: &Arc<IndexDb>, sequence: u64) {
+        let (store, session) = checkpoint_store(db);
+        session.begin().await.unwrap();
+        store
+            .stage_checkpoint(SOURCE, sequence, None)
+            .await
+            .unwrap();
+        session.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rewind_checkpoint() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let options = RocksIndexOptions::new(false, false, RocksDbMemoryBudget::default());
+        let db = open_unified_db(dir.path().to_str().unwrap(), "q1", &options).unwrap();
+        assert_eq!(read_checkpoint(&db, SOURCE).await.unwrap(), None);
+        assert_eq!(rewind_checkpoint(&db, SOURCE, 3).await.unwrap(), None);
+
+        checkpoint(&db, 9).await;
+        assert_eq!(read_checkpoint(&db, SOURCE).await.unwrap(), Some(9));
+
+        assert_eq!(rewind_checkpoint(&db, SOURCE, 3).await.unwrap(), Some(3));
+        let (store, _) = checkpoint_store(&db);
+        let rewound = store.read_checkpoint(SOURCE).await.unwrap().unwrap();
+        assert_eq!(rewound.sequence, 3);
+        assert_eq!(
+            rewound.source_position.as_deref(),
+            Some(&3u64.to_be_bytes()[..])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rewind_checkpoint_never_moves_forward() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let options = RocksIndexOptions::new(false, false, RocksDbMemoryBudget::default());
+        let db = open_unified_db(dir.path().to_str().unwrap(), "q1", &options).unwrap();
+        checkpoint(&db, 2).await;
+
+        // A lagging query's checkpoint is below the replay position.
+        assert_eq!(rewind_checkpoint(&db, SOURCE, 6).await.unwrap(), Some(2));
+        let (store, _) = checkpoint_store(&db);
+        let unchanged = store.read_checkpoint(SOURCE).await.unwrap().unwrap();
+        assert_eq!(unchanged.sequence, 2);
+        assert_eq!(unchanged.source_position, None);
+    }
+}
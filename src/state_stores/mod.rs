@@ -0,0 +1,353 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! State store backends, selected by the `kind` of a `stateStore` config.
+//!
+//! | Kind     | Storage                                                          |
+//! |----------|------------------------------------------------------------------|
+//! | `redb`   | a single redb file, locked by the server while it runs           |
+//! | `sqlite` | a SQLite database in WAL mode, readable by other processes       |
+//!
+//! Both are built in. A server looks kinds up in the [`StateStoreRegistry`]
+//! it is created with, which it keeps next to its `PluginRegistry` and uses
+//! the way it uses secret store descriptors there. `PluginRegistry` comes
+//! from `drasi-host-sdk` and has no state store slot, so plugin libraries
+//! export their kinds through [`plugin`], and loading the plugins directory
+//! registers them here ([`crate::dynamic_loading::PluginLoadStats::register_state_stores`]).
+//! Applications embedding the server add kinds by registering a
+//! [`StateStorePluginDescriptor`] and passing the registry to
+//! [`crate::DrasiServer::new_with_state_stores`].
+
+pub mod plugin;
+mod sqlite;
+
+pub use sqlite::SqliteStateStoreProvider;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use drasi_lib::state_store::StateStoreProvider;
+use drasi_state_store_redb::RedbStateStoreProvider;
+use log::info;
+use serde::de::DeserializeOwned;
+
+use crate::api::mappings::DtoMapper;
+use crate::api::models::{
+    ConfigValue, RedbStateStoreConfigDto, SqliteStateStoreConfigDto, StateStoreConfig,
+};
+
+/// Kind of the built-in redb state store.
+pub const REDB_KIND: &str = "redb";
+/// Kind of the built-in SQLite state store.
+pub const SQLITE_KIND: &str = "sqlite";
+/// Every built-in state store kind.
+pub const BUILTIN_KINDS: [&str; 2] = [REDB_KIND, SQLITE_KIND];
+
+/// Descriptor for a state store backend: the state store counterpart of the
+/// SDK's `SecretStorePluginDescriptor`.
+pub trait StateStorePluginDescriptor: Send + Sync {
+    /// The `kind` this descriptor creates stores for.
+    fn kind(&self) -> &str;
+
+    /// The semver version of this kind's configuration DTO.
+    fn config_version(&self) -> &str;
+
+    /// Create a provider from every field of the `stateStore` config except
+    /// `kind`.
+    fn create_state_store(
+        &self,
+        config_json: &serde_json::Value,
+    ) -> Result<Arc<dyn StateStoreProvider>>;
+
+    /// Check `config_json` without creating a store, for `drasi-server
+    /// validate`. Kinds that do not say accept any config here and report
+    /// problems from `create_state_store`.
+    fn validate_config(&self, _config_json: &serde_json::Value) -> Result<()> {
+        Ok(())
+    }
+
+    /// Files a store created from `config_json` keeps its data in, for disk
+    /// usage reporting. Kinds that do not say report no files.
+    fn data_files(&self, _config_json: &serde_json::Value) -> Result<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+}
+
+/// The state store kinds a server can create, by kind.
+pub struct StateStoreRegistry {
+    state_stores: BTreeMap<String, Arc<dyn StateStorePluginDescriptor>>,
+}
+
+impl Default for StateStoreRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateStoreRegistry {
+    /// A registry holding the built-in kinds.
+    pub fn new() -> Self {
+        let mut registry = Self {
+            state_stores: BTreeMap::new(),
+        };
+        registry.register_state_store(Arc::new(RedbStateStoreDescriptor));
+        registry.register_state_store(Arc::new(SqliteStateStoreDescriptor));
+        registry
+    }
+
+    /// Register `descriptor`, replacing any descriptor of the same kind.
+    pub fn register_state_store(&mut self, descriptor: Arc<dyn StateStorePluginDescriptor>) {
+        self.state_stores
+            .insert(descriptor.kind().to_string(), descriptor);
+    }
+
+    /// The descriptor registered for `kind`.
+    pub fn get_state_store(&self, kind: &str) -> Option<&Arc<dyn StateStorePluginDescriptor>> {
+        self.state_stores.get(kind)
+    }
+
+    /// Every registered kind, sorted.
+    pub fn state_store_kinds(&self) -> Vec<&str> {
+        self.state_stores.keys().map(String::as_str).collect()
+    }
+
+    /// Check that `config` names a registered kind and that its descriptor
+    /// accepts the remaining fields.
+    pub fn validate(&self, config: &StateStoreConfig) -> Result<()> {
+        let descriptor = self.get_state_store(&config.kind).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown state store kind: '{}'. Available: {:?}",
+                config.kind,
+                self.state_store_kinds()
+            )
+        })?;
+        descriptor
+            .validate_config(&config.config)
+            .with_context(|| format!("in stateStore (kind={})", config.kind))
+    }
+
+    /// Files the store `config` describes keeps its data in.
+    pub fn data_files(&self, config: &StateStoreConfig) -> Result<Vec<PathBuf>> {
+        match self.get_state_store(&config.kind) {
+            Some(descriptor) => descriptor.data_files(&config.config),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Parse the config DTO of a built-in kind and resolve its `path`.
+fn resolve_path<T: DeserializeOwned>(
+    config_json: &serde_json::Value,
+    path: impl FnOnce(&T) -> &ConfigValue<String>,
+) -> Result<String> {
+    let dto: T = serde_json::from_value(config_json.clone())?;
+    Ok(DtoMapper::new().resolve_string(path(&dto))?)
+}
+
+struct RedbStateStoreDescriptor;
+
+impl StateStorePluginDescriptor for RedbStateStoreDescriptor {
+    fn kind(&self) -> &str {
+        REDB_KIND
+    }
+
+    fn config_version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn validate_config(&self, config_json: &serde_json::Value) -> Result<()> {
+        serde_json::from_value::<RedbStateStoreConfigDto>(config_json.clone())?;
+        Ok(())
+    }
+
+    fn create_state_store(
+        &self,
+        config_json: &serde_json::Value,
+    ) -> Result<Arc<dyn StateStoreProvider>> {
+        let path = resolve_path(config_json, |dto: &RedbStateStoreConfigDto| &dto.path)?;
+        info!("Creating REDB state store provider with path: {path}");
+        Ok(Arc::new(RedbStateStoreProvider::new(&path)?))
+    }
+
+    fn data_files(&self, config_json: &serde_json::Value) -> Result<Vec<PathBuf>> {
+        let path = resolve_path(config_json, |dto: &RedbStateStoreConfigDto| &dto.path)?;
+        Ok(vec![PathBuf::from(path)])
+    }
+}
+
+struct SqliteStateStoreDescriptor;
+
+impl StateStorePluginDescriptor for SqliteStateStoreDescriptor {
+    fn kind(&self) -> &str {
+        SQLITE_KIND
+    }
+
+    fn config_version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn validate_config(&self, config_json: &serde_json::Value) -> Result<()> {
+        serde_json::from_value::<SqliteStateStoreConfigDto>(config_json.clone())?;
+        Ok(())
+    }
+
+    fn create_state_store(
+        &self,
+        config_json: &serde_json::Value,
+    ) -> Result<Arc<dyn StateStoreProvider>> {
+        let path = resolve_path(config_json, |dto: &SqliteStateStoreConfigDto| &dto.path)?;
+        info!("Creating SQLite state store provider with path: {path}");
+        Ok(Arc::new(SqliteStateStoreProvider::new(&path)?))
+    }
+
+    fn data_files(&self, config_json: &serde_json::Value) -> Result<Vec<PathBuf>> {
+        let path = resolve_path(config_json, |dto: &SqliteStateStoreConfigDto| &dto.path)?;
+        // WAL mode keeps recent writes in side files until a checkpoint.
+        Ok(["", "-wal", "-shm"]
+            .iter()
+            .map(|suffix| PathBuf::from(format!("{path}{suffix}")))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::factories::create_state_store_from_registry;
+    use drasi_lib::state_store::MemoryStateStoreProvider;
+    use tempfile::TempDir;
+
+    struct MemoryDescriptor;
+
+    impl StateStorePluginDescriptor for MemoryDescriptor {
+        fn kind(&self) -> &str {
+            "memory"
+        }
+
+        fn config_version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn create_state_store(
+            &self,
+            _config_json: &serde_json::Value,
+        ) -> Result<Arc<dyn StateStoreProvider>> {
+            Ok(Arc::new(MemoryStateStoreProvider::new()))
+        }
+    }
+
+    #[test]
+    fn test_builtin_kinds_are_registered() {
+        assert_eq!(
+            StateStoreRegistry::new().state_store_kinds(),
+            ["redb", "sqlite"]
+        );
+    }
+
+    #[test]
+    fn test_registered_kinds_can_be_created() {
+        let mut registry = StateStoreRegistry::new();
+        registry.register_state_store(Arc::new(MemoryDescriptor));
+
+        let config = StateStoreConfig {
+            kind: "memory".to_string(),
+            config: serde_json::json!({}),
+        };
+        let provider = create_state_store_from_registry(&registry, &config).unwrap();
+        assert!(!provider.is_durable());
+        assert_eq!(registry.state_store_kinds(), ["memory", "redb", "sqlite"]);
+    }
+
+    #[test]
+    fn test_unknown_kind_lists_the_available_kinds() {
+        let config = StateStoreConfig {
+            kind: "unknown".to_string(),
+            config: serde_json::json!({ "path": "./data/state.redb" }),
+        };
+        let err = create_state_store_from_registry(&StateStoreRegistry::new(), &config)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("'unknown'"), "{err}");
+        assert!(err.contains("sqlite"), "{err}");
+    }
+
+    #[test]
+    fn test_builtin_configs_reject_unknown_fields() {
+        let dir = TempDir::new().unwrap();
+        for kind in BUILTIN_KINDS {
+            let config = StateStoreConfig {
+                kind: kind.to_string(),
+                config: serde_json::json!({
+                    "path": dir.path().join("state").to_string_lossy(),
+                    "file_path": "./data/state"
+                }),
+            };
+            let err = create_state_store_from_registry(&StateStoreRegistry::new(), &config)
+                .err()
+                .unwrap();
+            let message = format!("{err:#}");
+            assert!(
+                message.contains(&format!("in stateStore (kind={kind})")),
+                "{message}"
+            );
+            assert!(message.contains("file_path"), "{message}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_kind_creates_a_durable_store() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.db");
+        let config = StateStoreConfig::sqlite(path.to_string_lossy());
+
+        let provider =
+            create_state_store_from_registry(&StateStoreRegistry::new(), &config).unwrap();
+        assert!(provider.is_durable());
+        provider
+            .set("plugin", "key", b"value".to_vec())
+            .await
+            .unwrap();
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_data_files_name_the_store_files() {
+        let registry = StateStoreRegistry::new();
+        assert_eq!(
+            registry
+                .data_files(&StateStoreConfig::redb("./data/state.redb"))
+                .unwrap(),
+            vec![PathBuf::from("./data/state.redb")]
+        );
+        assert_eq!(
+            registry
+                .data_files(&StateStoreConfig::sqlite("./data/state.db"))
+                .unwrap(),
+            vec![
+                PathBuf::from("./data/state.db"),
+                PathBuf::from("./data/state.db-wal"),
+                PathBuf::from("./data/state.db-shm"),
+            ]
+        );
+        let unknown = StateStoreConfig {
+            kind: "unknown".to_string(),
+            config: serde_json::json!({}),
+        };
+        assert!(registry.data_files(&unknown).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,422 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! State store kinds provided by dynamically loaded plugins.
+//!
+//! The plugin SDK's `FfiPluginRegistration` has no state store slot, so a
+//! plugin provides state stores by also exporting
+//! [`STATE_STORE_PLUGINS_SYMBOL`], which returns one
+//! [`StateStorePluginVtable`] per kind:
+//!
+//! ```ignore
+//! #[no_mangle]
+//! pub extern "C" fn drasi_state_store_plugins() -> FfiStateStorePlugins {
+//!     export_state_store_plugins(vec![Arc::new(MyStateStoreDescriptor)])
+//! }
+//! ```
+//!
+//! A created store crosses the boundary as the SDK's `StateStoreVtable`, the
+//! same vtable the host uses to lend its own store to plugins, built with the
+//! host SDK's `StateStoreVtableBuilder`.
+
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use drasi_host_sdk::StateStoreVtableBuilder;
+use drasi_lib::state_store::{StateStoreError, StateStoreProvider, StateStoreResult};
+use drasi_plugin_sdk::ffi::{FfiCreateResult, FfiResult, FfiStr, StateStoreVtable};
+use libloading::{Library, Symbol};
+
+use super::StateStorePluginDescriptor;
+
+/// Symbol a plugin library exports to provide state store kinds.
+pub const STATE_STORE_PLUGINS_SYMBOL: &[u8] = b"drasi_state_store_plugins";
+
+/// FFI-safe vtable for a [`StateStorePluginDescriptor`].
+#[repr(C)]
+pub struct StateStorePluginVtable {
+    pub state: *mut c_void,
+    pub kind_fn: extern "C" fn(state: *const c_void) -> FfiStr,
+    pub config_version_fn: extern "C" fn(state: *const c_void) -> FfiStr,
+    pub validate_config_fn: extern "C" fn(state: *const c_void, config_json: FfiStr) -> FfiResult,
+    /// Factory: on success `store.ptr` is a boxed `StateStoreVtable` the host
+    /// takes ownership of.
+    pub create_state_store_fn:
+        extern "C" fn(state: *const c_void, config_json: FfiStr) -> FfiCreatedStateStore,
+}
+
+unsafe impl Send for StateStorePluginVtable {}
+unsafe impl Sync for StateStorePluginVtable {}
+
+/// A store created by [`StateStorePluginVtable::create_state_store_fn`].
+#[repr(C)]
+pub struct FfiCreatedStateStore {
+    pub store: FfiCreateResult,
+    /// `StateStoreProvider::is_durable` of the created store.
+    pub durable: bool,
+}
+
+/// The descriptors returned by [`STATE_STORE_PLUGINS_SYMBOL`]: a boxed slice
+/// the host takes ownership of.
+#[repr(C)]
+pub struct FfiStateStorePlugins {
+    pub plugins: *mut StateStorePluginVtable,
+    pub count: usize,
+}
+
+/// Build the value a plugin's [`STATE_STORE_PLUGINS_SYMBOL`] returns.
+pub fn export_state_store_plugins(
+    descriptors: Vec<Arc<dyn StateStorePluginDescriptor>>,
+) -> FfiStateStorePlugins {
+    let vtables: Box<[StateStorePluginVtable]> = descriptors
+        .into_iter()
+        .map(|descriptor| StateStorePluginVtable {
+            state: Box::into_raw(Box::new(descriptor)) as *mut c_void,
+            kind_fn: descriptor_kind,
+            config_version_fn: descriptor_config_version,
+            validate_config_fn: descriptor_validate_config,
+            create_state_store_fn: descriptor_create_state_store,
+        })
+        .collect();
+    let count = vtables.len();
+    FfiStateStorePlugins {
+        plugins: Box::into_raw(vtables) as *mut StateStorePluginVtable,
+        count,
+    }
+}
+
+fn descriptor_ref(state: *const c_void) -> &'static dyn StateStorePluginDescriptor {
+    let descriptor = unsafe { &*(state as *const Arc<dyn StateStorePluginDescriptor>) };
+    descriptor.as_ref()
+}
+
+extern "C" fn descriptor_kind(state: *const c_void) -> FfiStr {
+    FfiStr::from_str(descriptor_ref(state).kind())
+}
+
+extern "C" fn descriptor_config_version(state: *const c_void) -> FfiStr {
+    FfiStr::from_str(descriptor_ref(state).config_version())
+}
+
+extern "C" fn descriptor_validate_config(state: *const c_void, config_json: FfiStr) -> FfiResult {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let config_json: serde_json::Value = serde_json::from_str(unsafe { config_json.as_str() })?;
+        descriptor_ref(state).validate_config(&config_json)
+    }));
+    match result {
+        Ok(Ok(())) => FfiResult::ok(),
+        Ok(Err(e)) => FfiResult::err(format!("{e:#}")),
+        Err(payload) => FfiResult::from_panic(payload),
+    }
+}
+
+extern "C" fn descriptor_create_state_store(
+    state: *const c_void,
+    config_json: FfiStr,
+) -> FfiCreatedStateStore {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let config_json: serde_json::Value = serde_json::from_str(unsafe { config_json.as_str() })?;
+        descriptor_ref(state).create_state_store(&config_json)
+    }));
+    match result {
+        Ok(Ok(provider)) => FfiCreatedStateStore {
+            durable: provider.is_durable(),
+            store: FfiCreateResult::ok(Box::into_raw(Box::new(StateStoreVtableBuilder::build(
+                provider,
+            )))),
+        },
+        Ok(Err(e)) => FfiCreatedStateStore {
+            store: FfiCreateResult::err(format!("{e:#}")),
+            durable: false,
+        },
+        Err(_) => FfiCreatedStateStore {
+            store: FfiCreateResult::err("create_state_store panicked".to_string()),
+            durable: false,
+        },
+    }
+}
+
+/// Load the state store kinds the plugin library at `path` provides. A
+/// library that does not export [`STATE_STORE_PLUGINS_SYMBOL`] provides none.
+pub(crate) fn load_state_store_plugins(
+    path: &Path,
+) -> Result<Vec<Arc<dyn StateStorePluginDescriptor>>> {
+    // The host SDK's loader already opened the library, so this only takes
+    // another reference to it.
+    let library = unsafe { Library::new(path) }
+        .map_err(|e| anyhow!("Failed to open {}: {e}", path.display()))?;
+    let plugins = {
+        let symbol: Symbol<unsafe extern "C" fn() -> FfiStateStorePlugins> =
+            match unsafe { library.get(STATE_STORE_PLUGINS_SYMBOL) } {
+                Ok(symbol) => symbol,
+                Err(_) => return Ok(Vec::new()),
+            };
+        unsafe { symbol() }
+    };
+    // Like the host SDK, never unload a plugin: stores created from it run
+    // its code for as long as the server does.
+    std::mem::forget(library);
+    Ok(unsafe { descriptors_from_ffi(plugins) })
+}
+
+/// Take ownership of the descriptors in `plugins`.
+///
+/// # Safety
+///
+/// `plugins` must come from [`export_state_store_plugins`].
+unsafe fn descriptors_from_ffi(
+    plugins: FfiStateStorePlugins,
+) -> Vec<Arc<dyn StateStorePluginDescriptor>> {
+    if plugins.plugins.is_null() || plugins.count == 0 {
+        return Vec::new();
+    }
+    let vtables = Vec::from_raw_parts(plugins.plugins, plugins.count, plugins.count);
+    vtables
+        .into_iter()
+        .map(|vtable| {
+            let kind = (vtable.kind_fn)(vtable.state).to_string();
+            let config_version = (vtable.config_version_fn)(vtable.state).to_string();
+            Arc::new(StateStorePluginProxy {
+                vtable,
+                kind,
+                config_version,
+            }) as Arc<dyn StateStorePluginDescriptor>
+        })
+        .collect()
+}
+
+/// Host-side [`StateStorePluginDescriptor`] over a plugin's vtable.
+struct StateStorePluginProxy {
+    vtable: StateStorePluginVtable,
+    kind: String,
+    config_version: String,
+}
+
+impl StateStorePluginDescriptor for StateStorePluginProxy {
+    fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    fn config_version(&self) -> &str {
+        &self.config_version
+    }
+
+    fn create_state_store(
+        &self,
+        config_json: &serde_json::Value,
+    ) -> Result<Arc<dyn StateStoreProvider>> {
+        let config_json = serde_json::to_string(config_json)?;
+        let created =
+            (self.vtable.create_state_store_fn)(self.vtable.state, FfiStr::from_str(&config_json));
+        let vtable = unsafe { created.store.into_result::<StateStoreVtable>() }
+            .map_err(|e| anyhow!("state store '{}': {e}", self.kind))?;
+        if vtable.is_null() {
+            return Err(anyhow!("state store '{}' returned no store", self.kind));
+        }
+        Ok(Arc::new(PluginStateStoreProvider {
+            vtable,
+            durable: created.durable,
+        }))
+    }
+
+    fn validate_config(&self, config_json: &serde_json::Value) -> Result<()> {
+        let config_json = serde_json::to_string(config_json)?;
+        let result =
+            (self.vtable.validate_config_fn)(self.vtable.state, FfiStr::from_str(&config_json));
+        unsafe { result.into_result() }.map_err(|e| anyhow!(e))
+    }
+}
+
+/// A store created by a plugin, used through its `StateStoreVtable`.
+struct PluginStateStoreProvider {
+    vtable: *mut StateStoreVtable,
+    durable: bool,
+}
+
+unsafe impl Send for PluginStateStoreProvider {}
+unsafe impl Sync for PluginStateStoreProvider {}
+
+impl PluginStateStoreProvider {
+    fn vtable(&self) -> &StateStoreVtable {
+        unsafe { &*self.vtable }
+    }
+}
+
+impl Drop for PluginStateStoreProvider {
+    fn drop(&mut self) {
+        let vtable = unsafe { Box::from_raw(self.vtable) };
+        (vtable.drop_fn)(vtable.state);
+    }
+}
+
+#[async_trait]
+impl StateStoreProvider for PluginStateStoreProvider {
+    async fn get(&self, store_id: &str, key: &str) -> StateStoreResult<Option<Vec<u8>>> {
+        let vtable = self.vtable();
+        let result = (vtable.get_fn)(
+            vtable.state,
+            FfiStr::from_str(store_id),
+            FfiStr::from_str(key),
+        );
+        unsafe { result.into_result() }.map_err(StateStoreError::Other)
+    }
+
+    async fn set(&self, store_id: &str, key: &str, value: Vec<u8>) -> StateStoreResult<()> {
+        let vtable = self.vtable();
+        let result = (vtable.set_fn)(
+            vtable.state,
+            FfiStr::from_str(store_id),
+            FfiStr::from_str(key),
+            value.as_ptr(),
+            value.len(),
+        );
+        unsafe { result.into_result() }.map_err(StateStoreError::Other)
+    }
+
+    async fn delete(&self, store_id: &str, key: &str) -> StateStoreResult<bool> {
+        // The vtable does not report whether the key existed.
+        let existed = self.contains_key(store_id, key).await?;
+        let vtable = self.vtable();
+        let result = (vtable.delete_fn)(
+            vtable.state,
+            FfiStr::from_str(store_id),
+            FfiStr::from_str(key),
+        );
+        unsafe { result.into_result() }.map_err(StateStoreError::Other)?;
+        Ok(existed)
+    }
+
+    async fn contains_key(&self, store_id: &str, key: &str) -> StateStoreResult<bool> {
+        let vtable = self.vtable();
+        let result = (vtable.contains_key_fn)(
+            vtable.state,
+            FfiStr::from_str(store_id),
+            FfiStr::from_str(key),
+        );
+        Ok(unsafe { result.into_result() }.is_ok())
+    }
+
+    async fn get_many(
+        &self,
+        store_id: &str,
+        keys: &[&str],
+    ) -> StateStoreResult<HashMap<String, Vec<u8>>> {
+        let mut values = HashMap::new();
+        for key in keys {
+            if let Some(value) = self.get(store_id, key).await? {
+                values.insert(key.to_string(), value);
+            }
+        }
+        Ok(values)
+    }
+
+    async fn set_many(&self, store_id: &str, entries: &[(&str, &[u8])]) -> StateStoreResult<()> {
+        for (key, value) in entries {
+            self.set(store_id, key, value.to_vec()).await?;
+        }
+        Ok(())
+    }
+
+    async fn delete_many(&self, store_id: &str, keys: &[&str]) -> StateStoreResult<usize> {
+        let mut deleted = 0;
+        for key in keys {
+            if self.delete(store_id, key).await? {
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn clear_store(&self, store_id: &str) -> StateStoreResult<usize> {
+        let vtable = self.vtable();
+        let cleared = (vtable.clear_store_fn)(vtable.state, FfiStr::from_str(store_id));
+        usize::try_from(cleared)
+            .map_err(|_| StateStoreError::Other(format!("clear_store failed for '{store_id}'")))
+    }
+
+    async fn list_keys(&self, store_id: &str) -> StateStoreResult<Vec<String>> {
+        let vtable = self.vtable();
+        let keys = (vtable.list_keys_fn)(vtable.state, FfiStr::from_str(store_id));
+        Ok(unsafe { keys.into_vec() })
+    }
+
+    async fn store_exists(&self, store_id: &str) -> StateStoreResult<bool> {
+        let vtable = self.vtable();
+        let result = (vtable.store_exists_fn)(vtable.state, FfiStr::from_str(store_id));
+        Ok(unsafe { result.into_result() }.is_ok())
+    }
+
+    async fn key_count(&self, store_id: &str) -> StateStoreResult<usize> {
+        let vtable = self.vtable();
+        let count = (vtable.key_count_fn)(vtable.state, FfiStr::from_str(store_id));
+        usize::try_from(count)
+            .map_err(|_| StateStoreError::Other(format!("key_count failed for '{store_id}'")))
+    }
+
+    async fn sync(&self) -> StateStoreResult<()> {
+        let vtable = self.vtable();
+        unsafe { (vtable.sync_fn)(vtable.state).into_result() }.map_err(StateStoreError::Other)
+    }
+
+    fn is_durable(&self) -> bool {
+        self.durable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{SqliteStateStoreDescriptor, SQLITE_KIND};
+    use super::*;
+    use futures_util::FutureExt;
+
+    // The store runs its own runtime to serve each call, as it would inside
+    // a plugin library, so calls are made outside of one here.
+    fn run<T>(call: impl std::future::Future<Output = T>) -> T {
+        call.now_or_never().unwrap()
+    }
+
+    /// Round-trips the built-in SQLite kind through the FFI the way a
+    /// plugin-provided kind is loaded.
+    #[test]
+    fn test_exported_descriptor_creates_a_working_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.db");
+        let exported = export_state_store_plugins(vec![Arc::new(SqliteStateStoreDescriptor)]);
+        let descriptors = unsafe { descriptors_from_ffi(exported) };
+        assert_eq!(descriptors.len(), 1);
+        let descriptor = &descriptors[0];
+        assert_eq!(descriptor.kind(), SQLITE_KIND);
+
+        let config = serde_json::json!({ "path": path.to_string_lossy() });
+        descriptor.validate_config(&config).unwrap();
+        assert!(descriptor
+            .validate_config(&serde_json::json!({ "path": 1 }))
+            .is_err());
+
+        let store = descriptor.create_state_store(&config).unwrap();
+        assert!(store.is_durable());
+        run(store.set("s", "k", b"v".to_vec())).unwrap();
+        assert_eq!(run(store.get("s", "k")).unwrap(), Some(b"v".to_vec()));
+        assert!(run(store.contains_key("s", "k")).unwrap());
+        assert_eq!(run(store.list_keys("s")).unwrap(), vec!["k".to_string()]);
+        assert!(run(store.delete("s", "k")).unwrap());
+        assert!(!run(store.delete("s", "k")).unwrap());
+        assert_eq!(run(store.key_count("s")).unwrap(), 0);
+    }
+}
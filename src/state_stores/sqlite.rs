@@ -0,0 +1,323 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! SQLite-backed state store.
+//!
+//! All partitions share one `state` table keyed by `(store_id, key)`. The
+//! database runs in WAL mode, so other processes (the `sqlite3` shell, a
+//! backup job using `.backup` or `VACUUM INTO`) can read it while the server
+//! writes, and writes wait up to [`BUSY_TIMEOUT`] for their locks.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use drasi_lib::state_store::{StateStoreError, StateStoreProvider, StateStoreResult};
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// How long a write waits for a lock held by another connection.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = "
+    PRAGMA journal_mode = WAL;
+    PRAGMA synchronous = FULL;
+    CREATE TABLE IF NOT EXISTS state (
+        store_id TEXT NOT NULL,
+        key      TEXT NOT NULL,
+        value    BLOB NOT NULL,
+        PRIMARY KEY (store_id, key)
+    ) WITHOUT ROWID;
+";
+
+const UPSERT: &str = "INSERT INTO state (store_id, key, value) VALUES (?1, ?2, ?3)
+     ON CONFLICT (store_id, key) DO UPDATE SET value = excluded.value";
+
+/// State store provider backed by a SQLite database file.
+pub struct SqliteStateStoreProvider {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStateStoreProvider {
+    /// Open (or create) the database at `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> StateStoreResult<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path)
+            .and_then(|conn| {
+                conn.busy_timeout(BUSY_TIMEOUT)?;
+                conn.execute_batch(SCHEMA)?;
+                Ok(conn)
+            })
+            .map_err(|e| {
+                StateStoreError::StorageError(format!(
+                    "Failed to open SQLite database at {}: {e}",
+                    path.display()
+                ))
+            })?;
+
+        info!("Opened SQLite state store at {}", path.display());
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Run `f` on the connection on the blocking pool.
+    async fn with_conn<T, F>(&self, f: F) -> StateStoreResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+            f(&mut conn)
+        })
+        .await
+        .map_err(|e| StateStoreError::Other(format!("SQLite state store task failed: {e}")))?
+        .map_err(|e| StateStoreError::StorageError(format!("SQLite state store: {e}")))
+    }
+}
+
+#[async_trait]
+impl StateStoreProvider for SqliteStateStoreProvider {
+    async fn get(&self, store_id: &str, key: &str) -> StateStoreResult<Option<Vec<u8>>> {
+        let (store_id, key) = (store_id.to_string(), key.to_string());
+        self.with_conn(move |conn| {
+            conn.query_row(
+                "SELECT value FROM state WHERE store_id = ?1 AND key = ?2",
+                params![store_id, key],
+                |row| row.get(0),
+            )
+            .optional()
+        })
+        .await
+    }
+
+    async fn set(&self, store_id: &str, key: &str, value: Vec<u8>) -> StateStoreResult<()> {
+        let (store_id, key) = (store_id.to_string(), key.to_string());
+        self.with_conn(move |conn| {
+            conn.execute(UPSERT, params![store_id, key, value])?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete(&self, store_id: &str, key: &str) -> StateStoreResult<bool> {
+        let (store_id, key) = (store_id.to_string(), key.to_string());
+        self.with_conn(move |conn| {
+            let deleted = conn.execute(
+                "DELETE FROM state WHERE store_id = ?1 AND key = ?2",
+                params![store_id, key],
+            )?;
+            Ok(deleted > 0)
+        })
+        .await
+    }
+
+    async fn contains_key(&self, store_id: &str, key: &str) -> StateStoreResult<bool> {
+        let (store_id, key) = (store_id.to_string(), key.to_string());
+        self.with_conn(move |conn| {
+            conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM state WHERE store_id = ?1 AND key = ?2)",
+                params![store_id, key],
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+
+    async fn get_many(
+        &self,
+        store_id: &str,
+        keys: &[&str],
+    ) -> StateStoreResult<HashMap<String, Vec<u8>>> {
+        let store_id = store_id.to_string();
+        let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        self.with_conn(move |conn| {
+            let mut stmt =
+                conn.prepare_cached("SELECT value FROM state WHERE store_id = ?1 AND key = ?2")?;
+            let mut values = HashMap::new();
+            for key in keys {
+                let value: Option<Vec<u8>> = stmt
+                    .query_row(params![store_id, key], |row| row.get(0))
+                    .optional()?;
+                if let Some(value) = value {
+                    values.insert(key, value);
+                }
+            }
+            Ok(values)
+        })
+        .await
+    }
+
+    async fn set_many(&self, store_id: &str, entries: &[(&str, &[u8])]) -> StateStoreResult<()> {
+        let store_id = store_id.to_string();
+        let entries: Vec<(String, Vec<u8>)> = entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_vec()))
+            .collect();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(UPSERT)?;
+                for (key, value) in entries {
+                    stmt.execute(params![store_id, key, value])?;
+                }
+            }
+            tx.commit()
+        })
+        .await
+    }
+
+    async fn delete_many(&self, store_id: &str, keys: &[&str]) -> StateStoreResult<usize> {
+        let store_id = store_id.to_string();
+        let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            let mut deleted = 0;
+            {
+                let mut stmt =
+                    tx.prepare_cached("DELETE FROM state WHERE store_id = ?1 AND key = ?2")?;
+                for key in keys {
+                    deleted += stmt.execute(params![store_id, key])?;
+                }
+            }
+            tx.commit()?;
+            Ok(deleted)
+        })
+        .await
+    }
+
+    async fn clear_store(&self, store_id: &str) -> StateStoreResult<usize> {
+        let store_id = store_id.to_string();
+        self.with_conn(move |conn| {
+            conn.execute("DELETE FROM state WHERE store_id = ?1", params![store_id])
+        })
+        .await
+    }
+
+    async fn list_keys(&self, store_id: &str) -> StateStoreResult<Vec<String>> {
+        let store_id = store_id.to_string();
+        self.with_conn(move |conn| {
+            let mut stmt =
+                conn.prepare_cached("SELECT key FROM state WHERE store_id = ?1 ORDER BY key")?;
+            let keys = stmt.query_map(params![store_id], |row| row.get(0))?;
+            keys.collect()
+        })
+        .await
+    }
+
+    async fn store_exists(&self, store_id: &str) -> StateStoreResult<bool> {
+        let store_id = store_id.to_string();
+        self.with_conn(move |conn| {
+            conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM state WHERE store_id = ?1)",
+                params![store_id],
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+
+    async fn key_count(&self, store_id: &str) -> StateStoreResult<usize> {
+        let store_id = store_id.to_string();
+        self.with_conn(move |conn| {
+            conn.query_row(
+                "SELECT COUNT(*) FROM state WHERE store_id = ?1",
+                params![store_id],
+                |row| row.get(0),
+            )
+        })
+        .await
+    }
+
+    async fn sync(&self) -> StateStoreResult<()> {
+        // Commits are already durable (synchronous = FULL); checkpointing
+        // also moves them from the WAL into the main database file, so a
+        // plain copy of that file is complete.
+        self.with_conn(|conn| conn.query_row("PRAGMA wal_checkpoint(FULL)", [], |_| Ok(())))
+            .await
+    }
+
+    fn is_durable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_partitions_are_independent() {
+        let dir = TempDir::new().unwrap();
+        let store = SqliteStateStoreProvider::new(dir.path().join("state.db")).unwrap();
+
+        store.set("a", "k1", b"v1".to_vec()).await.unwrap();
+        store
+            .set_many("a", &[("k2", b"v2".as_slice()), ("k1", b"new".as_slice())])
+            .await
+            .unwrap();
+        store.set("b", "k1", b"other".to_vec()).await.unwrap();
+
+        assert_eq!(store.get("a", "k1").await.unwrap(), Some(b"new".to_vec()));
+        assert_eq!(store.list_keys("a").await.unwrap(), ["k1", "k2"]);
+        assert_eq!(store.key_count("b").await.unwrap(), 1);
+        let many = store.get_many("a", &["k2", "missing"]).await.unwrap();
+        assert_eq!(many.len(), 1);
+        assert!(store.contains_key("a", "k2").await.unwrap());
+
+        assert!(store.delete("a", "k2").await.unwrap());
+        assert!(!store.delete("a", "k2").await.unwrap());
+        assert_eq!(store.delete_many("a", &["k1", "k2"]).await.unwrap(), 1);
+        assert!(!store.store_exists("a").await.unwrap());
+        assert_eq!(store.clear_store("b").await.unwrap(), 1);
+        assert_eq!(store.get("b", "k1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_state_is_readable_by_other_connections_and_survives_reopen() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("state.db");
+        let store = SqliteStateStoreProvider::new(&path).unwrap();
+        store
+            .set("source-1", "offset", b"42".to_vec())
+            .await
+            .unwrap();
+
+        // An external reader, like the sqlite3 shell, while the store is open.
+        let reader = Connection::open(&path).unwrap();
+        let value: Vec<u8> = reader
+            .query_row(
+                "SELECT value FROM state WHERE store_id = 'source-1' AND key = 'offset'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(value, b"42");
+
+        store.sync().await.unwrap();
+        drop(store);
+        let reopened = SqliteStateStoreProvider::new(&path).unwrap();
+        assert_eq!(
+            reopened.get("source-1", "offset").await.unwrap(),
+            Some(b"42".to_vec())
+        );
+    }
+}
@@ -0,0 +1,306 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Disk usage of an instance's durable data.
+//!
+//! An instance built by the server keeps the RocksDB databases of its
+//! persistent queries under `./data/<instanceId>/index`, one redb file per
+//! source under `./data/<instanceId>/wal`, and its state store wherever the
+//! store's `path` points. [`InstanceStorage`] records where those live so the
+//! REST API can measure them.
+//!
+//! RocksDB's property API is not available on the transactional databases
+//! the index uses, so entry counts come from scanning the column families.
+//! `/metrics` serves the counts of the latest background scan from the
+//! instance's [`IndexCountCache`] instead of scanning on every scrape.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use drasi_index_rocksdb::IndexDb;
+use drasi_lib::wal::WalProvider;
+use log::warn;
+use rocksdb::IteratorMode;
+
+use crate::api::models::WalRetentionConfig;
+use crate::archive_retention::ARCHIVE_CF;
+use crate::index_provider::IndexDatabases;
+use crate::source_wal::WalEventTimes;
+
+/// Column family holding one entry per element in `drasi-index-rocksdb`.
+const ELEMENTS_CF: &str = "elements";
+
+/// How long cached index counts are served before a scrape starts a recount.
+const INDEX_COUNTS_MAX_AGE: Duration = Duration::from_secs(60);
+
+/// Where an instance built by the server keeps its durable data.
+#[derive(Clone)]
+pub struct InstanceStorage {
+    /// Databases of the instance's persistent queries; `None` without
+    /// `persistIndex`.
+    pub index: Option<IndexDatabases>,
+    /// The instance's WAL provider.
+    pub wal: Arc<dyn WalProvider>,
+    /// Transaction times of the events `wal` retains.
+    pub wal_times: WalEventTimes,
+    /// Directory the WAL provider writes its per-source files to.
+    pub wal_dir: PathBuf,
+    /// The instance's `walRetention`.
+    pub wal_retention: Option<WalRetentionConfig>,
+    /// Files of the configured state store; `None` for the in-memory store.
+    pub state_store_files: Option<Vec<PathBuf>>,
+    /// Index counts of the latest background scan, served by `/metrics`.
+    pub index_counts: IndexCountCache,
+}
+
+impl InstanceStorage {
+    /// The redb file `RedbWalProvider` keeps `source_id`'s events in.
+    pub fn wal_file(&self, source_id: &str) -> PathBuf {
+        self.wal_dir.join(format!("{source_id}.redb"))
+    }
+}
+
+/// Entry counts of one query's RocksDB index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexCounts {
+    /// Elements in the element index.
+    pub elements: u64,
+    /// Archived element versions; `None` if the database has no archive.
+    pub archive_versions: Option<u64>,
+    /// Key and value bytes of those versions, before RocksDB compression.
+    pub archive_bytes: Option<u64>,
+}
+
+/// Count the elements and archived versions in a query's database.
+pub fn count_index(db: &IndexDb) -> Result<IndexCounts> {
+    let mut counts = count_elements(db)?;
+    if let Some(cf) = db.cf_handle(ARCHIVE_CF) {
+        let (mut versions, mut bytes) = (0, 0);
+        for item in db.iterator_cf(&cf, IteratorMode::Start) {
+            let (key, value) = item?;
+            versions += 1;
+            bytes += (key.len() + value.len()) as u64;
+        }
+        counts.archive_versions = Some(versions);
+        counts.archive_bytes = Some(bytes);
+    }
+    Ok(counts)
+}
+
+/// Count only the elements in a query's database, leaving the archive
+/// counts unknown.
+fn count_elements(db: &IndexDb) -> Result<IndexCounts> {
+    let mut counts = IndexCounts::default();
+    if let Some(cf) = db.cf_handle(ELEMENTS_CF) {
+        for item in db.iterator_cf(&cf, IteratorMode::Start) {
+            item?;
+            counts.elements += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Index counts of an instance's persistent queries, as of the latest
+/// background scan. Cheap to clone.
+#[derive(Clone, Default)]
+pub struct IndexCountCache {
+    inner: Arc<Mutex<CachedCounts>>,
+}
+
+#[derive(Default)]
+struct CachedCounts {
+    /// Counts, or the error counting failed with, by query id.
+    counts: HashMap<String, std::result::Result<IndexCounts, String>>,
+    counted_at: Option<Instant>,
+    counting: bool,
+}
+
+impl IndexCountCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The counts of `query_id` from the latest scan, if it has been counted.
+    pub fn get(&self, query_id: &str) -> Option<std::result::Result<IndexCounts, String>> {
+        self.lock().counts.get(query_id).cloned()
+    }
+
+    /// Start a background scan of `databases` unless one is running or the
+    /// cached counts are recent. With `count_archive` false only elements
+    /// are counted, for instances whose archive retention pass already
+    /// measures the archive.
+    pub fn refresh(&self, databases: &IndexDatabases, count_archive: bool) {
+        {
+            let mut cached = self.lock();
+            let recent = cached
+                .counted_at
+                .is_some_and(|at| at.elapsed() < INDEX_COUNTS_MAX_AGE);
+            if cached.counting || recent {
+                return;
+            }
+            cached.counting = true;
+        }
+        let cache = self.clone();
+        let databases = databases.clone();
+        tokio::spawn(async move {
+            let mut counts = HashMap::new();
+            for query_id in databases.query_ids() {
+                // Hold the database only while this query is counted.
+                let Some(db) = databases.get(&query_id) else {
+                    continue;
+                };
+                let result = tokio::task::spawn_blocking(move || {
+                    if count_archive {
+                        count_index(&db)
+                    } else {
+                        count_elements(&db)
+                    }
+                })
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result)
+                .map_err(|e| {
+                    warn!("Cannot count the index of query '{query_id}': {e}");
+                    e.to_string()
+                });
+                counts.insert(query_id, result);
+            }
+            let mut cached = cache.lock();
+            cached.counts = counts;
+            cached.counted_at = Some(Instant::now());
+            cached.counting = false;
+        });
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CachedCounts> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Total size of the files under `path`, or `None` if it does not exist.
+pub(crate) fn directory_size(path: &Path) -> Option<u64> {
+    fn walk(path: &Path) -> u64 {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return 0;
+        };
+        entries
+            .flatten()
+            .map(|entry| match entry.file_type() {
+                Ok(kind) if kind.is_dir() => walk(&entry.path()),
+                Ok(_) => entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+                Err(_) => 0,
+            })
+            .sum()
+    }
+    path.is_dir().then(|| walk(path))
+}
+
+/// Total size of those of `paths` that exist.
+pub(crate) fn files_size(paths: &[PathBuf]) -> u64 {
+    paths
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use drasi_index_rocksdb::{open_unified_db, RocksDbMemoryBudget, RocksIndexOptions};
+
+    #[test]
+    fn test_count_index() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let options = RocksIndexOptions::new(false, false, RocksDbMemoryBudget::default());
+        let db = open_unified_db(path, "plain", &options).unwrap();
+        let cf = db.cf_handle(ELEMENTS_CF).unwrap();
+        db.put_cf(&cf, b"e1", b"v").unwrap();
+        db.put_cf(&cf, b"e2", b"v").unwrap();
+        assert_eq!(
+            count_index(&db).unwrap(),
+            IndexCounts {
+                elements: 2,
+                archive_versions: None,
+                archive_bytes: None,
+            }
+        );
+
+        let options = RocksIndexOptions::new(true, false, RocksDbMemoryBudget::default());
+        let db = open_unified_db(path, "archived", &options).unwrap();
+        let cf = db.cf_handle(ARCHIVE_CF).unwrap();
+        db.put_cf(&cf, b"key", b"value").unwrap();
+        let counts = count_index(&db).unwrap();
+        assert_eq!(counts.elements, 0);
+        assert_eq!(counts.archive_versions, Some(1));
+        assert_eq!(counts.archive_bytes, Some(8));
+    }
+
+    #[tokio::test]
+    async fn test_index_count_cache_serves_the_latest_scan() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let options = RocksIndexOptions::new(true, false, RocksDbMemoryBudget::default());
+        let db = open_unified_db(dir.path().to_str().unwrap(), "q1", &options).unwrap();
+        let cf = db.cf_handle(ELEMENTS_CF).unwrap();
+        db.put_cf(&cf, b"e1", b"v").unwrap();
+        let databases = IndexDatabases::new();
+        databases.track("q1", &db, &options);
+
+        let cache = IndexCountCache::new();
+        assert!(cache.get("q1").is_none());
+        cache.refresh(&databases, false);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let counts = loop {
+            if let Some(counts) = cache.get("q1") {
+                break counts.unwrap();
+            }
+            assert!(Instant::now() < deadline, "never counted");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        };
+        // The archive was left to the retention pass.
+        assert_eq!(
+            counts,
+            IndexCounts {
+                elements: 1,
+                archive_versions: None,
+                archive_bytes: None,
+            }
+        );
+
+        // Recent counts are served without another scan.
+        db.put_cf(&cf, b"e2", b"v").unwrap();
+        cache.refresh(&databases, true);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(cache.get("q1").unwrap().unwrap().elements, 1);
+    }
+
+    #[test]
+    fn test_directory_and_file_sizes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(directory_size(&dir.path().join("missing")), None);
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("a"), [0u8; 10]).unwrap();
+        std::fs::write(dir.path().join("sub").join("b"), [0u8; 5]).unwrap();
+        assert_eq!(directory_size(dir.path()), Some(15));
+        assert_eq!(
+            files_size(&[dir.path().join("a"), dir.path().join("missing")]),
+            10
+        );
+    }
+}
@@ -31,6 +31,7 @@ use axum::{
 };
 use drasi_lib::Query;
 use drasi_server::api::v1::handlers;
+use drasi_server::config::ServerMode;
 use drasi_server::instance_registry::InstanceRegistry;
 use futures_util::StreamExt;
 use std::sync::Arc;
@@ -99,7 +100,7 @@ async fn create_test_router_with_id(
     // Start the core
     core.start().await.expect("Failed to start core");
 
-    let read_only = Arc::new(false);
+    let mode = ServerMode::ReadWrite;
     let config_persistence: Option<Arc<drasi_server::persistence::ConfigPersistence>> = None;
 
     // Create registry with the test instance
@@ -113,7 +114,7 @@ async fn create_test_router_with_id(
     let solutions_dir = None;
     let v1_router = build_v1_router(
         registry,
-        read_only,
+        mode,
         config_persistence,
         Arc::new(tokio::sync::RwLock::new(plugin_registry)),
         solutions_dir,
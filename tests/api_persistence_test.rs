@@ -96,6 +96,8 @@ async fn test_persistence_saves_complete_configuration() {
         id: "test-source-1".to_string(),
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: default_mock_config(),
     };
@@ -104,6 +106,8 @@ async fn test_persistence_saves_complete_configuration() {
         id: "test-source-2".to_string(),
         auto_start: false,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: default_mock_config(),
     };
@@ -139,6 +143,8 @@ async fn test_persistence_saves_complete_configuration() {
         queries: vec!["test-query-1".to_string()],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({"routes": {}}),
     };
 
@@ -228,6 +234,8 @@ async fn test_persistence_atomic_write() {
         id: "new-source".to_string(),
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: default_mock_config(),
     };
@@ -18,11 +18,11 @@
 //! routes are nested under the root app router separately from the rest of
 //! the v1 router and therefore do not inherit Axum `Extension` layers added
 //! to the v1 router. Without these tests, handlers like `install_plugin`
-//! and `load_plugin` (which extract `Extension<Arc<bool>>` for read-only
+//! and `load_plugin` (which extract `Extension<ServerMode>` for mode
 //! enforcement) would fail at request time with:
 //!
-//!     Missing request extension: Extension of type `alloc::sync::Arc<bool>`
-//!     was not found.
+//!     Missing request extension: Extension of type
+//!     `drasi_server::config::mode::ServerMode` was not found.
 //!
 //! Any future handler added to `plugin_routes()` that extracts a new
 //! `Extension<T>` MUST also have `T` added in `build_plugin_router` —
@@ -40,6 +40,7 @@ use axum::{
 use drasi_host_sdk::lifecycle::PluginLifecycleManager;
 use drasi_host_sdk::plugin_registry::PluginRegistry;
 use drasi_server::api::v1::build_plugin_router;
+use drasi_server::config::ServerMode;
 use drasi_server::instance_registry::InstanceRegistry;
 use drasi_server::plugin_orchestrator::PluginOrchestrator;
 use tokio::sync::RwLock;
@@ -48,13 +49,13 @@ use tower::ServiceExt;
 /// Build a router that mirrors the production wiring in `server.rs`:
 /// the plugin sub-router is nested separately under `/api/v1/plugins`,
 /// reproducing the conditions that caused the missing-extension bug.
-fn build_test_app(read_only: bool) -> Router {
+fn build_test_app(mode: ServerMode) -> Router {
     let registry = Arc::new(RwLock::new(PluginRegistry::new()));
     let lifecycle = Arc::new(PluginLifecycleManager::new(registry));
     let orchestrator = Arc::new(PluginOrchestrator::new(lifecycle));
     let instances = InstanceRegistry::new();
 
-    let plugin_router = build_plugin_router(orchestrator, instances, Arc::new(read_only));
+    let plugin_router = build_plugin_router(orchestrator, instances, mode);
 
     Router::new().nest("/api/v1/plugins", plugin_router)
 }
@@ -62,11 +63,11 @@ fn build_test_app(read_only: bool) -> Router {
 /// Regression test: `POST /api/v1/plugins/install` must not fail with the
 /// "Missing request extension" error introduced when the plugin sub-router
 /// was nested separately from the v1 router without re-adding the
-/// `Arc<bool>` (read-only) extension. When the server is in read-only mode
+/// `ServerMode` extension. When the server is in read-only mode
 /// the handler must return a structured `CONFIG_READ_ONLY` error.
 #[tokio::test]
 async fn install_plugin_returns_read_only_error_when_read_only() {
-    let app = build_test_app(true);
+    let app = build_test_app(ServerMode::ReadOnly);
 
     let response = app
         .oneshot(
@@ -108,7 +109,7 @@ async fn install_plugin_returns_read_only_error_when_read_only() {
 /// handler actually executed.
 #[tokio::test]
 async fn install_plugin_reaches_handler_when_writable() {
-    let app = build_test_app(false);
+    let app = build_test_app(ServerMode::ReadWrite);
 
     let response = app
         .oneshot(
@@ -140,12 +141,36 @@ async fn install_plugin_reaches_handler_when_writable() {
     assert_eq!(json["code"], "PLUGIN_INSTALL_FAILED", "body={body_str}");
 }
 
+/// `locked` mode freezes plugins along with the components, so an install
+/// is rejected before it reaches the orchestrator.
+#[tokio::test]
+async fn install_plugin_returns_locked_error_when_locked() {
+    let app = build_test_app(ServerMode::Locked);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/v1/plugins/install")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"ref":"reaction/sse"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["code"], "SERVER_LOCKED", "body={json}");
+}
+
 /// Regression test: `POST /api/v1/plugins/load` is the second handler that
-/// extracts `Extension<Arc<bool>>` and would have hit the same missing-
+/// extracts `Extension<ServerMode>` and would have hit the same missing-
 /// extension bug. Verify it also reaches the handler successfully.
 #[tokio::test]
 async fn load_plugin_returns_read_only_error_when_read_only() {
-    let app = build_test_app(true);
+    let app = build_test_app(ServerMode::ReadOnly);
 
     let response = app
         .oneshot(
@@ -178,11 +203,11 @@ async fn load_plugin_returns_read_only_error_when_read_only() {
 }
 
 /// `GET /api/v1/plugins` must remain reachable through the plugin sub-router
-/// (this handler does not need `read_only`, but it relies on the orchestrator
+/// (this handler does not need the mode, but it relies on the orchestrator
 /// extension being present).
 #[tokio::test]
 async fn list_plugins_reaches_handler() {
-    let app = build_test_app(false);
+    let app = build_test_app(ServerMode::ReadWrite);
 
     let response = app
         .oneshot(
@@ -10,6 +10,7 @@ use drasi_lib::{
 use drasi_server::api::models::query::{QueryConfigDto, SourceSubscriptionConfigDto};
 use drasi_server::api::shared::extractor::ConfigBody;
 use drasi_server::api::shared::handlers::create_query;
+use drasi_server::config::ServerMode;
 use std::sync::Arc;
 use test_support::mock_components::create_mock_source;
 
@@ -84,7 +85,7 @@ async fn test_create_query_with_joins_via_handler() {
     // Start the core
     core.start().await.expect("Failed to start core");
 
-    let read_only = Arc::new(false);
+    let mode = ServerMode::ReadWrite;
     let config_persistence: Option<Arc<drasi_server::persistence::ConfigPersistence>> = None;
 
     let cfg = build_query_config();
@@ -93,7 +94,7 @@ async fn test_create_query_with_joins_via_handler() {
     // Invoke handler
     let response = create_query(
         Extension(core.clone()),
-        Extension(read_only.clone()),
+        Extension(mode),
         Extension(config_persistence),
         Extension("test-server".to_string()),
         ConfigBody(cfg_dto),
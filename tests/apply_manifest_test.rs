@@ -0,0 +1,416 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration tests for the declarative apply and validate endpoints.
+//!
+//! POST /instances/{instanceId}/apply[?dryRun=true][&prune=true]
+//! POST /instances/{instanceId}/validate[?prune=true]
+//! Body: { "sources": [...], "queries": [...], "reactions": [...] }
+
+#![allow(clippy::unwrap_used)]
+
+mod test_support;
+
+use test_support::{create_mock_reaction, create_mock_source};
+
+use async_trait::async_trait;
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use drasi_lib::DrasiLib;
+use drasi_plugin_sdk::{ReactionPluginDescriptor, SourcePluginDescriptor};
+use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
+use drasi_server::instance_registry::InstanceRegistry;
+use drasi_server::plugin_registry::PluginRegistry;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+const INSTANCE: &str = "apply-instance";
+
+struct MockSourceDescriptor;
+
+#[async_trait]
+impl SourcePluginDescriptor for MockSourceDescriptor {
+    fn kind(&self) -> &str {
+        "mock"
+    }
+    fn config_version(&self) -> &str {
+        "1.0.0"
+    }
+    fn config_schema_json(&self) -> String {
+        r#"{"type":"object"}"#.to_string()
+    }
+    fn config_schema_name(&self) -> &str {
+        "MockSourceConfig"
+    }
+    async fn create_source(
+        &self,
+        id: &str,
+        _config_json: &serde_json::Value,
+        _auto_start: bool,
+    ) -> anyhow::Result<Box<dyn drasi_lib::sources::Source>> {
+        Ok(Box::new(create_mock_source(id)))
+    }
+}
+
+/// Reaction descriptor that fails for ids starting with "broken", to
+/// exercise rollback.
+struct MockReactionDescriptor;
+
+#[async_trait]
+impl ReactionPluginDescriptor for MockReactionDescriptor {
+    fn kind(&self) -> &str {
+        "log"
+    }
+    fn config_version(&self) -> &str {
+        "1.0.0"
+    }
+    fn config_schema_json(&self) -> String {
+        r#"{"type":"object"}"#.to_string()
+    }
+    fn config_schema_name(&self) -> &str {
+        "MockReactionConfig"
+    }
+    async fn create_reaction(
+        &self,
+        id: &str,
+        query_ids: Vec<String>,
+        _config_json: &serde_json::Value,
+        _auto_start: bool,
+    ) -> anyhow::Result<Box<dyn drasi_lib::reactions::Reaction>> {
+        if id.starts_with("broken") {
+            anyhow::bail!("reaction '{id}' cannot be created");
+        }
+        Ok(Box::new(create_mock_reaction(id, query_ids)))
+    }
+}
+
+async fn create_test_router() -> Router {
+    let core = DrasiLib::builder()
+        .with_id(INSTANCE)
+        .build()
+        .await
+        .expect("Failed to build instance");
+    let core = Arc::new(core);
+    core.start().await.expect("Failed to start instance");
+
+    let mut instances = indexmap::IndexMap::new();
+    instances.insert(INSTANCE.to_string(), core);
+    let registry = InstanceRegistry::from_map(instances);
+
+    let mut plugin_registry = PluginRegistry::new();
+    drasi_server::register_core_plugins(&mut plugin_registry);
+    plugin_registry.register_source(Arc::new(MockSourceDescriptor));
+    plugin_registry.register_reaction(Arc::new(MockReactionDescriptor));
+
+    build_v1_router(
+        registry,
+        ServerMode::ReadWrite,
+        None,
+        Arc::new(tokio::sync::RwLock::new(plugin_registry)),
+        None,
+    )
+}
+
+fn manifest() -> Value {
+    json!({
+        "sources": [{"kind": "mock", "id": "sensors"}],
+        "queries": [{
+            "id": "hot-sensors",
+            "query": "MATCH (s:Sensor) WHERE s.temperature > 75 RETURN s",
+            "sources": [{"sourceId": "sensors"}]
+        }],
+        "reactions": [{"kind": "log", "id": "alerts", "queries": ["hot-sensors"]}]
+    })
+}
+
+async fn apply(router: Router, query: &str, body: &Value) -> (StatusCode, Value) {
+    post(router, "apply", query, body).await
+}
+
+async fn validate(router: Router, query: &str, body: &Value) -> (StatusCode, Value) {
+    post(router, "validate", query, body).await
+}
+
+async fn post(router: Router, action: &str, query: &str, body: &Value) -> (StatusCode, Value) {
+    let response = router
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/instances/{INSTANCE}/{action}{query}"))
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(body).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+/// Ids of the user-visible components of `resource` (internal `__` sources
+/// are skipped).
+async fn list_ids(router: Router, resource: &str) -> Vec<String> {
+    let response = router
+        .oneshot(
+            Request::builder()
+                .uri(format!("/instances/{INSTANCE}/{resource}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: Value = serde_json::from_slice(&bytes).unwrap();
+    json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["id"].as_str().unwrap().to_string())
+        .filter(|id| !id.starts_with("__"))
+        .collect()
+}
+
+/// `(componentType, id, action)` triples from an apply response.
+fn actions(data: &Value) -> Vec<(String, String, String)> {
+    data["actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| {
+            (
+                a["componentType"].as_str().unwrap().to_string(),
+                a["id"].as_str().unwrap().to_string(),
+                a["action"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+fn action(component_type: &str, id: &str, action: &str) -> (String, String, String) {
+    (component_type.into(), id.into(), action.into())
+}
+
+#[tokio::test]
+async fn test_dry_run_plans_without_changing_anything() {
+    let router = create_test_router().await;
+
+    let (status, json) = apply(router.clone(), "?dryRun=true", &manifest()).await;
+    assert_eq!(status, StatusCode::OK);
+    let data = &json["data"];
+    assert_eq!(data["dryRun"], true);
+    assert_eq!(data["applied"], false);
+    assert_eq!(
+        actions(data),
+        vec![
+            action("source", "sensors", "create"),
+            action("query", "hot-sensors", "create"),
+            action("reaction", "alerts", "create"),
+        ]
+    );
+
+    assert!(list_ids(router.clone(), "sources").await.is_empty());
+    assert!(list_ids(router, "queries").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_apply_creates_then_reports_unchanged() {
+    let router = create_test_router().await;
+
+    let (status, json) = apply(router.clone(), "", &manifest()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["data"]["applied"], true, "{json}");
+    assert_eq!(list_ids(router.clone(), "sources").await, vec!["sensors"]);
+    assert_eq!(
+        list_ids(router.clone(), "queries").await,
+        vec!["hot-sensors"]
+    );
+    assert_eq!(list_ids(router.clone(), "reactions").await, vec!["alerts"]);
+
+    let (status, json) = apply(router, "?dryRun=true", &manifest()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        actions(&json["data"]),
+        vec![
+            action("source", "sensors", "unchanged"),
+            action("query", "hot-sensors", "unchanged"),
+            action("reaction", "alerts", "unchanged"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_prune_deletes_components_missing_from_manifest() {
+    let router = create_test_router().await;
+    let (status, _) = apply(router.clone(), "", &manifest()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let only_source = json!({"sources": [{"kind": "mock", "id": "sensors"}]});
+
+    // Without prune, components outside the manifest are left alone.
+    let (_, json) = apply(router.clone(), "?dryRun=true", &only_source).await;
+    assert_eq!(
+        actions(&json["data"]),
+        vec![action("source", "sensors", "unchanged")]
+    );
+
+    let (status, json) = apply(router.clone(), "?prune=true", &only_source).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        actions(&json["data"]),
+        vec![
+            action("source", "sensors", "unchanged"),
+            action("reaction", "alerts", "delete"),
+            action("query", "hot-sensors", "delete"),
+        ]
+    );
+    assert!(list_ids(router.clone(), "reactions").await.is_empty());
+    assert!(list_ids(router.clone(), "queries").await.is_empty());
+    assert_eq!(list_ids(router, "sources").await, vec!["sensors"]);
+}
+
+#[tokio::test]
+async fn test_failed_apply_rolls_back() {
+    let router = create_test_router().await;
+
+    let mut body = manifest();
+    body["reactions"] = json!([{"kind": "log", "id": "broken-rx", "queries": ["hot-sensors"]}]);
+
+    let (status, json) = apply(router.clone(), "", &body).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR, "{json}");
+    assert_eq!(json["code"], "APPLY_FAILED");
+    assert!(
+        json["message"].as_str().unwrap().contains("broken-rx"),
+        "{json}"
+    );
+    let details = &json["details"];
+    assert_eq!(details["component_type"], "reaction");
+    assert_eq!(details["component_id"], "broken-rx");
+    assert_eq!(
+        details["technical_details"],
+        "rolled back 2 completed action(s)"
+    );
+
+    assert!(list_ids(router.clone(), "sources").await.is_empty());
+    assert!(list_ids(router.clone(), "queries").await.is_empty());
+    assert!(list_ids(router, "reactions").await.is_empty());
+}
+
+#[tokio::test]
+async fn test_invalid_references_are_rejected_before_applying() {
+    let router = create_test_router().await;
+
+    let body = json!({
+        "reactions": [{"kind": "log", "id": "alerts", "queries": ["missing-query"]}]
+    });
+    let (status, json) = apply(router.clone(), "", &body).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(
+        json["message"].as_str().unwrap().contains("missing-query"),
+        "{json}"
+    );
+    assert!(list_ids(router, "reactions").await.is_empty());
+}
+
+/// `(componentType, componentId, code)` of each issue in a validate response.
+fn issue_codes(json: &Value) -> Vec<(String, String, String)> {
+    json["data"]["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| {
+            (
+                issue["componentType"].as_str().unwrap().to_string(),
+                issue["componentId"].as_str().unwrap().to_string(),
+                issue["code"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_validate_merges_manifest_with_current_components() {
+    let router = create_test_router().await;
+    let (status, _) = apply(router.clone(), "", &manifest()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // The applied pipeline is consistent.
+    let (status, json) = validate(router.clone(), "", &json!({})).await;
+    assert_eq!(status, StatusCode::OK, "{json}");
+    assert_eq!(json["data"]["valid"], true, "{json}");
+    assert_eq!(json["data"]["errors"], 0);
+
+    // A new reaction may use the existing query, but not a missing one.
+    let body = json!({
+        "reactions": [{"kind": "log", "id": "audit", "queries": ["hot-sensors", "cold-sensors"]}]
+    });
+    let (status, json) = validate(router.clone(), "", &body).await;
+    assert_eq!(status, StatusCode::OK, "{json}");
+    assert_eq!(json["data"]["valid"], false);
+    assert_eq!(
+        issue_codes(&json),
+        vec![(
+            "reaction".to_string(),
+            "audit".to_string(),
+            "UNKNOWN_QUERY".to_string()
+        )]
+    );
+
+    // With prune the manifest stands alone, so the query is missing too.
+    let (_, json) = validate(router.clone(), "?prune=true", &body).await;
+    assert_eq!(json["data"]["errors"], 2, "{json}");
+
+    // Nothing was applied.
+    assert_eq!(list_ids(router, "reactions").await, vec!["alerts"]);
+}
+
+#[tokio::test]
+async fn test_validate_reports_query_wiring_problems() {
+    let router = create_test_router().await;
+
+    let body = json!({
+        "sources": [{"kind": "mock", "id": "sensors"}],
+        "queries": [{
+            "id": "hot-sensors",
+            "query": "MATCH (s:Sensor)-[:LOCATED_IN]->(r:Room) RETURN s.id, r.name",
+            "queryLanguage": "Cypher",
+            "sources": [{"sourceId": "sensors", "nodes": ["Sensor"], "pipeline": ["decode"]}]
+        }],
+        "reactions": [{"kind": "log", "id": "alerts", "queries": ["hot-sensors"]}]
+    });
+    let (status, json) = validate(router, "", &body).await;
+    assert_eq!(status, StatusCode::OK, "{json}");
+    assert_eq!(
+        issue_codes(&json),
+        vec![
+            (
+                "query".to_string(),
+                "hot-sensors".to_string(),
+                "UNKNOWN_MIDDLEWARE".to_string()
+            ),
+            (
+                "query".to_string(),
+                "hot-sensors".to_string(),
+                "UNMATCHED_LABEL".to_string()
+            ),
+        ]
+    );
+    assert_eq!(json["data"]["errors"], 1);
+    assert_eq!(json["data"]["warnings"], 1);
+}
@@ -0,0 +1,86 @@
+//! Tests for `POST /instances/{id}/backup` and restoring its archive.
+
+#![allow(clippy::unwrap_used)]
+
+mod test_support;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use drasi_lib::state_store::{MemoryStateStoreProvider, StateStoreProvider};
+use drasi_lib::{ComponentStatus, DrasiLib};
+use drasi_server::backup::BackupArchive;
+use drasi_server::config::ServerMode;
+use std::sync::Arc;
+use test_support::{build_state_test_router, wait_for_source_status};
+use tower::ServiceExt;
+
+const INSTANCE_ID: &str = "backup-api";
+
+async fn build_backup_test_router(mode: ServerMode) -> (Router, Arc<DrasiLib>) {
+    let store: Arc<dyn StateStoreProvider> = Arc::new(MemoryStateStoreProvider::new());
+    store.set("src", "cursor", b"42".to_vec()).await.unwrap();
+    build_state_test_router(INSTANCE_ID, mode, store).await
+}
+
+async fn post_backup(router: Router) -> (StatusCode, Option<String>, Vec<u8>) {
+    let response = router
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/api/v1/instances/backup-api/backup")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|v| v.to_str().unwrap().to_string());
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, content_type, body.to_vec())
+}
+
+#[tokio::test]
+async fn test_backup_archive_restores_state_and_config() {
+    let (router, core) = build_backup_test_router(ServerMode::ReadWrite).await;
+
+    let (status, content_type, archive) = post_backup(router).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("application/gzip"));
+
+    // The source was stopped for the copy and started again.
+    wait_for_source_status(&core, "src", ComponentStatus::Running).await;
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("backup.tar.gz");
+    std::fs::write(&path, archive).unwrap();
+    let backup = BackupArchive::open(&path).unwrap();
+    assert_eq!(backup.manifest.instance_id, INSTANCE_ID);
+    assert!(backup.manifest.state);
+    assert!(backup.config.contains("src"), "{}", backup.config);
+
+    let restored = MemoryStateStoreProvider::new();
+    assert_eq!(backup.restore_state(&restored).await.unwrap(), Some(1));
+    assert_eq!(
+        restored.get("src", "cursor").await.unwrap(),
+        Some(b"42".to_vec())
+    );
+}
+
+#[tokio::test]
+async fn test_backup_rejected_in_read_only_mode() {
+    let (router, core) = build_backup_test_router(ServerMode::ReadOnly).await;
+
+    let (status, _, _) = post_backup(router).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(
+        core.get_source_status("src").await.unwrap(),
+        ComponentStatus::Running
+    );
+}
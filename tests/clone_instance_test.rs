@@ -36,6 +36,7 @@ use drasi_lib::{DrasiLib, Query};
 use drasi_plugin_sdk::{ReactionPluginDescriptor, SourcePluginDescriptor};
 use drasi_server::api::v1::handlers;
 use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
 use drasi_server::instance_registry::InstanceRegistry;
 use drasi_server::plugin_registry::PluginRegistry;
 use std::sync::Arc;
@@ -146,7 +147,7 @@ async fn create_clone_test_router() -> Router {
     instances.insert(TARGET_INSTANCE.to_string(), target_core);
     let registry = InstanceRegistry::from_map(instances);
 
-    let read_only = Arc::new(false);
+    let mode = ServerMode::ReadWrite;
     let config_persistence = None;
     let mut plugin_registry = PluginRegistry::new();
     drasi_server::register_core_plugins(&mut plugin_registry);
@@ -156,7 +157,7 @@ async fn create_clone_test_router() -> Router {
 
     let v1_router = build_v1_router(
         registry,
-        read_only,
+        mode,
         config_persistence,
         Arc::new(tokio::sync::RwLock::new(plugin_registry)),
         solutions_dir,
@@ -0,0 +1,235 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration tests for optimistic concurrency on components.
+//!
+//! GET returns the component's revision as an `ETag`; PUT and DELETE honour
+//! `If-Match` and answer `412 Precondition Failed` on a stale revision.
+
+#![allow(clippy::unwrap_used)]
+
+mod test_support;
+
+use test_support::create_mock_source;
+
+use async_trait::async_trait;
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use drasi_lib::DrasiLib;
+use drasi_plugin_sdk::SourcePluginDescriptor;
+use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
+use drasi_server::instance_registry::InstanceRegistry;
+use drasi_server::plugin_registry::PluginRegistry;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+const INSTANCE: &str = "etag-instance";
+
+struct MockSourceDescriptor;
+
+#[async_trait]
+impl SourcePluginDescriptor for MockSourceDescriptor {
+    fn kind(&self) -> &str {
+        "mock"
+    }
+    fn config_version(&self) -> &str {
+        "1.0.0"
+    }
+    fn config_schema_json(&self) -> String {
+        r#"{"type":"object"}"#.to_string()
+    }
+    fn config_schema_name(&self) -> &str {
+        "MockSourceConfig"
+    }
+    async fn create_source(
+        &self,
+        id: &str,
+        _config_json: &serde_json::Value,
+        _auto_start: bool,
+    ) -> anyhow::Result<Box<dyn drasi_lib::sources::Source>> {
+        Ok(Box::new(create_mock_source(id)))
+    }
+}
+
+async fn create_test_router() -> Router {
+    let core = DrasiLib::builder()
+        .with_id(INSTANCE)
+        .build()
+        .await
+        .expect("Failed to build instance");
+    let core = Arc::new(core);
+    core.start().await.expect("Failed to start instance");
+
+    let mut instances = indexmap::IndexMap::new();
+    instances.insert(INSTANCE.to_string(), core);
+
+    let mut plugin_registry = PluginRegistry::new();
+    plugin_registry.register_source(Arc::new(MockSourceDescriptor));
+
+    build_v1_router(
+        InstanceRegistry::from_map(instances),
+        ServerMode::ReadWrite,
+        None,
+        Arc::new(tokio::sync::RwLock::new(plugin_registry)),
+        None,
+    )
+}
+
+/// Send a request, returning the status, `ETag` header, and JSON body.
+async fn request(
+    router: Router,
+    method: &str,
+    uri: &str,
+    if_match: Option<&str>,
+    body: Option<&Value>,
+) -> (StatusCode, Option<String>, Value) {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(tag) = if_match {
+        builder = builder.header("if-match", tag);
+    }
+    let request = match body {
+        Some(body) => builder
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(body).unwrap())),
+        None => builder.body(Body::empty()),
+    }
+    .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    let status = response.status();
+    let etag = response
+        .headers()
+        .get("etag")
+        .map(|v| v.to_str().unwrap().to_string());
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, etag, json)
+}
+
+fn source_uri() -> String {
+    format!("/instances/{INSTANCE}/sources/sensors")
+}
+
+fn source_body() -> Value {
+    json!({"kind": "mock", "id": "sensors"})
+}
+
+#[tokio::test]
+async fn test_put_and_delete_honour_if_match() {
+    let router = create_test_router().await;
+    let uri = source_uri();
+
+    // If-Match never matches a component that does not exist.
+    let (status, _, json) =
+        request(router.clone(), "PUT", &uri, Some("*"), Some(&source_body())).await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    assert_eq!(json["code"], "PRECONDITION_FAILED");
+
+    let (status, created, _) =
+        request(router.clone(), "PUT", &uri, None, Some(&source_body())).await;
+    assert_eq!(status, StatusCode::OK);
+    let created = created.expect("PUT should return an ETag");
+
+    let (status, current, _) = request(router.clone(), "GET", &uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(current.as_deref(), Some(created.as_str()));
+
+    // A stale revision is rejected and the current one reported.
+    let (status, reported, json) = request(
+        router.clone(),
+        "PUT",
+        &uri,
+        Some("\"0\""),
+        Some(&source_body()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED, "{json}");
+    assert_eq!(reported.as_deref(), Some(created.as_str()));
+
+    // The current revision is accepted and replaced by a new one.
+    let (status, updated, json) = request(
+        router.clone(),
+        "PUT",
+        &uri,
+        Some(&created),
+        Some(&source_body()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{json}");
+    let updated = updated.unwrap();
+    assert_ne!(updated, created);
+
+    // The old revision can no longer delete it; the new one can.
+    let (status, _, _) = request(router.clone(), "DELETE", &uri, Some(&created), None).await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    let (status, _, json) = request(router.clone(), "DELETE", &uri, Some(&updated), None).await;
+    assert_eq!(status, StatusCode::OK, "{json}");
+    let (status, _, _) = request(router, "GET", &uri, None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_events_carry_component_revision() {
+    let router = create_test_router().await;
+    let uri = source_uri();
+
+    let (status, etag, _) = request(router.clone(), "PUT", &uri, None, Some(&source_body())).await;
+    assert_eq!(status, StatusCode::OK);
+    let revision: u64 = etag.unwrap().trim_matches('"').parse().unwrap();
+
+    let (status, _, json) =
+        request(router.clone(), "GET", &format!("{uri}/events"), None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let events = json["data"].as_array().unwrap();
+    assert!(!events.is_empty());
+    for event in events {
+        assert_eq!(event["revision"].as_u64(), Some(revision), "{event}");
+    }
+}
+
+#[tokio::test]
+async fn test_snapshot_etag_moves_with_changes() {
+    let router = create_test_router().await;
+    let snapshot_uri = format!("/instances/{INSTANCE}/snapshot");
+
+    let (status, before, _) = request(router.clone(), "GET", &snapshot_uri, None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let before = before.unwrap();
+
+    request(
+        router.clone(),
+        "PUT",
+        &source_uri(),
+        None,
+        Some(&source_body()),
+    )
+    .await;
+    let (_, after, _) = request(router.clone(), "GET", &snapshot_uri, None, None).await;
+    assert_ne!(after.unwrap(), before);
+
+    // Apply is guarded by the instance revision.
+    let (status, _, _) = request(
+        router,
+        "POST",
+        &format!("/instances/{INSTANCE}/apply"),
+        Some(&before),
+        Some(&json!({"sources": [source_body()]})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+}
@@ -0,0 +1,190 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for `GET /config` and `GET /instances/{id}/config`: the export is a
+//! loadable config file in the format `Accept` asks for.
+
+#![allow(clippy::unwrap_used)]
+
+mod test_support;
+
+use test_support::create_mock_source;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    Router,
+};
+use drasi_lib::DrasiLib;
+use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::{DrasiServerConfig, ServerMode};
+use drasi_server::instance_registry::InstanceRegistry;
+use drasi_server::persistence::ConfigPersistence;
+use drasi_server::plugin_registry::PluginRegistry;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+async fn build_app() -> Router {
+    let mut instances_map = indexmap::IndexMap::new();
+    for id in ["edge", "cloud"] {
+        let core = DrasiLib::builder()
+            .with_id(id)
+            .with_source(create_mock_source(&format!("{id}-sensors")))
+            .build()
+            .await
+            .expect("build core");
+        instances_map.insert(id.to_string(), Arc::new(core));
+    }
+    let registry = InstanceRegistry::from_map(instances_map);
+
+    let v1_router = build_v1_router(
+        registry,
+        ServerMode::ReadWrite,
+        None,
+        Arc::new(tokio::sync::RwLock::new(PluginRegistry::new())),
+        None,
+    );
+    Router::new().nest("/api/v1", v1_router)
+}
+
+async fn get(app: &Router, path: &str, accept: &str) -> (StatusCode, String, String) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(path)
+                .header(header::ACCEPT, accept)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_export_returns_loadable_yaml_for_all_instances() {
+    let app = build_app().await;
+
+    let (status, content_type, body) = get(&app, "/api/v1/config", "application/yaml").await;
+    assert_eq!(status, StatusCode::OK, "body={body}");
+    assert_eq!(content_type, "application/yaml");
+
+    let config: DrasiServerConfig = serde_yaml::from_str(&body).unwrap();
+    assert_eq!(config.instances.len(), 2);
+    let sources: Vec<&str> = config
+        .instances
+        .iter()
+        .flat_map(|i| i.sources.iter().map(|s| s.id.as_str()))
+        .collect();
+    assert_eq!(sources, ["edge-sensors", "cloud-sensors"]);
+}
+
+#[tokio::test]
+async fn test_instance_export_uses_single_instance_format() {
+    let app = build_app().await;
+
+    let (status, content_type, body) =
+        get(&app, "/api/v1/instances/cloud/config", "application/json").await;
+    assert_eq!(status, StatusCode::OK, "body={body}");
+    assert_eq!(content_type, "application/json");
+
+    let config: DrasiServerConfig = serde_json::from_str(&body).unwrap();
+    assert!(config.instances.is_empty());
+    assert_eq!(config.sources.len(), 1);
+    assert_eq!(config.sources[0].id, "cloud-sensors");
+
+    let (status, _, body) = get(&app, "/api/v1/instances/missing/config", "*/*").await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "body={body}");
+    let error: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(error["code"], "INSTANCE_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_export_keeps_loaded_settings_when_persistence_is_disabled() {
+    let loaded: DrasiServerConfig = serde_yaml::from_str(
+        r#"
+id: edge
+persistConfig: false
+persistIndex: true
+identityProviders:
+  - kind: azure
+    id: workload
+    authMethod: workload_identity
+sources:
+  - kind: mock
+    id: edge-sensors
+    identityProvider: workload
+"#,
+    )
+    .unwrap();
+
+    let core = DrasiLib::builder()
+        .with_id("edge")
+        .with_source(create_mock_source("edge-sensors"))
+        .build()
+        .await
+        .expect("build core");
+    let mut instances_map = indexmap::IndexMap::new();
+    instances_map.insert("edge".to_string(), Arc::new(core));
+    let registry = InstanceRegistry::from_map(instances_map);
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let persistence = ConfigPersistence::new(
+        dir.path().join("server.yaml"),
+        registry.clone(),
+        "0.0.0.0".to_string(),
+        8080,
+        "info".to_string(),
+        false,
+        indexmap::IndexMap::from([("edge".to_string(), true)]),
+        indexmap::IndexMap::new(),
+        None,
+        &loaded,
+    );
+    let v1_router = build_v1_router(
+        registry,
+        ServerMode::ReadWrite,
+        Some(Arc::new(persistence)),
+        Arc::new(tokio::sync::RwLock::new(PluginRegistry::new())),
+        None,
+    );
+    let app = Router::new().nest("/api/v1", v1_router);
+
+    let (status, _, body) = get(&app, "/api/v1/config", "application/yaml").await;
+    assert_eq!(status, StatusCode::OK, "body={body}");
+
+    let config: DrasiServerConfig = serde_yaml::from_str(&body).unwrap();
+    assert!(!config.persist_config);
+    assert!(config.persist_index);
+    assert_eq!(config.identity_providers.len(), 1);
+    assert_eq!(config.identity_providers[0].id, "workload");
+    assert_eq!(config.sources.len(), 1);
+    assert_eq!(
+        config.sources[0].identity_provider.as_deref(),
+        Some("workload")
+    );
+    assert!(!dir.path().join("server.yaml").exists());
+}
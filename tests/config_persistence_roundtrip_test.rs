@@ -58,6 +58,8 @@ fn test_secret_reference_survives_source_config_roundtrip() {
                 "password": { "kind": "secret", "name": "DB_PASSWORD" }
             }),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
         }],
         ..DrasiServerConfig::default()
     };
@@ -100,6 +102,8 @@ fn test_secret_reference_survives_reaction_config_roundtrip() {
                 "authToken": { "kind": "secret", "name": "API_TOKEN" }
             }),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
         }],
         ..DrasiServerConfig::default()
     };
@@ -139,6 +143,8 @@ fn test_env_var_reference_survives_roundtrip() {
                 "password": "static-pass"
             }),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
         }],
         ..DrasiServerConfig::default()
     };
@@ -170,6 +176,8 @@ fn test_env_var_without_default_survives_roundtrip() {
                 "port": 5432
             }),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
         }],
         ..DrasiServerConfig::default()
     };
@@ -208,6 +216,8 @@ fn test_camelcase_keys_preserved_in_source_config_roundtrip() {
                 "tables": [{ "name": "users", "keys": ["id"] }]
             }),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
         }],
         ..DrasiServerConfig::default()
     };
@@ -260,6 +270,8 @@ fn test_camelcase_keys_preserved_in_reaction_config_roundtrip() {
                 }
             }),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
         }],
         ..DrasiServerConfig::default()
     };
@@ -308,6 +320,8 @@ fn test_mixed_static_secret_and_env_var_values_roundtrip() {
                 ]
             }),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
         }],
         ..DrasiServerConfig::default()
     };
@@ -364,6 +378,8 @@ fn test_password_field_preserved_not_stripped() {
                 "password": "my-secret-password"
             }),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
         }],
         ..DrasiServerConfig::default()
     };
@@ -393,6 +409,8 @@ fn test_password_as_secret_preserved() {
                 "password": { "kind": "secret", "name": "PG_PASSWORD" }
             }),
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
         }],
         ..DrasiServerConfig::default()
     };
@@ -427,6 +445,8 @@ fn test_multiple_sources_and_reactions_all_preserve_config_values() {
                     "password": { "kind": "secret", "name": "S1_PASS" }
                 }),
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
             },
             SourceConfig {
                 kind: "http".to_string(),
@@ -438,6 +458,8 @@ fn test_multiple_sources_and_reactions_all_preserve_config_values() {
                     "authHeader": { "kind": "secret", "name": "API_KEY" }
                 }),
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
             },
         ],
         reactions: vec![
@@ -448,6 +470,8 @@ fn test_multiple_sources_and_reactions_all_preserve_config_values() {
                 auto_start: true,
                 config: serde_json::json!({"routes": {}}),
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
             },
             ReactionConfig {
                 kind: "http".to_string(),
@@ -459,6 +483,8 @@ fn test_multiple_sources_and_reactions_all_preserve_config_values() {
                     "bearerToken": { "kind": "secret", "name": "WEBHOOK_TOKEN" }
                 }),
                 identity_provider: None,
+                restart_policy: None,
+                config_version: None,
             },
         ],
         ..DrasiServerConfig::default()
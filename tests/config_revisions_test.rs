@@ -0,0 +1,348 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration tests for the config revision history endpoints.
+//!
+//! GET  /config/revisions
+//! GET  /config/revisions/{revision}/diff
+//! POST /config/revisions/{revision}/rollback[?dryRun=true]
+
+#![allow(clippy::unwrap_used)]
+
+mod test_support;
+
+use test_support::{create_mock_reaction, create_mock_source};
+
+use async_trait::async_trait;
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use drasi_lib::DrasiLib;
+use drasi_plugin_sdk::{ReactionPluginDescriptor, SourcePluginDescriptor};
+use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
+use drasi_server::instance_registry::InstanceRegistry;
+use drasi_server::persistence::ConfigPersistence;
+use drasi_server::plugin_registry::PluginRegistry;
+use drasi_server::DrasiServerConfig;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+const INSTANCE: &str = "revisions-instance";
+
+struct MockSourceDescriptor;
+
+#[async_trait]
+impl SourcePluginDescriptor for MockSourceDescriptor {
+    fn kind(&self) -> &str {
+        "mock"
+    }
+    fn config_version(&self) -> &str {
+        "1.0.0"
+    }
+    fn config_schema_json(&self) -> String {
+        r#"{"type":"object"}"#.to_string()
+    }
+    fn config_schema_name(&self) -> &str {
+        "MockSourceConfig"
+    }
+    async fn create_source(
+        &self,
+        id: &str,
+        _config_json: &serde_json::Value,
+        _auto_start: bool,
+    ) -> anyhow::Result<Box<dyn drasi_lib::sources::Source>> {
+        Ok(Box::new(create_mock_source(id)))
+    }
+}
+
+struct MockReactionDescriptor;
+
+#[async_trait]
+impl ReactionPluginDescriptor for MockReactionDescriptor {
+    fn kind(&self) -> &str {
+        "log"
+    }
+    fn config_version(&self) -> &str {
+        "1.0.0"
+    }
+    fn config_schema_json(&self) -> String {
+        r#"{"type":"object"}"#.to_string()
+    }
+    fn config_schema_name(&self) -> &str {
+        "MockReactionConfig"
+    }
+    async fn create_reaction(
+        &self,
+        id: &str,
+        query_ids: Vec<String>,
+        _config_json: &serde_json::Value,
+        _auto_start: bool,
+    ) -> anyhow::Result<Box<dyn drasi_lib::reactions::Reaction>> {
+        Ok(Box::new(create_mock_reaction(id, query_ids)))
+    }
+}
+
+/// Router with config persistence writing to `dir/server.yaml`.
+async fn create_test_router(dir: &TempDir) -> Router {
+    let core = DrasiLib::builder()
+        .with_id(INSTANCE)
+        .build()
+        .await
+        .expect("Failed to build instance");
+    let core = Arc::new(core);
+    core.start().await.expect("Failed to start instance");
+
+    let mut instances = indexmap::IndexMap::new();
+    instances.insert(INSTANCE.to_string(), core);
+    let registry = InstanceRegistry::from_map(instances);
+
+    let persistence = ConfigPersistence::new(
+        dir.path().join("server.yaml"),
+        registry.clone(),
+        "0.0.0.0".to_string(),
+        8080,
+        "info".to_string(),
+        true,
+        indexmap::IndexMap::new(),
+        indexmap::IndexMap::new(),
+        None,
+        &DrasiServerConfig::default(),
+    );
+
+    let mut plugin_registry = PluginRegistry::new();
+    drasi_server::register_core_plugins(&mut plugin_registry);
+    plugin_registry.register_source(Arc::new(MockSourceDescriptor));
+    plugin_registry.register_reaction(Arc::new(MockReactionDescriptor));
+
+    build_v1_router(
+        registry,
+        ServerMode::ReadWrite,
+        Some(Arc::new(persistence)),
+        Arc::new(tokio::sync::RwLock::new(plugin_registry)),
+        None,
+    )
+}
+
+fn manifest() -> Value {
+    json!({
+        "sources": [{"kind": "mock", "id": "sensors"}],
+        "queries": [{
+            "id": "hot-sensors",
+            "query": "MATCH (s:Sensor) WHERE s.temperature > 75 RETURN s",
+            "sources": [{"sourceId": "sensors"}]
+        }],
+        "reactions": [{"kind": "log", "id": "alerts", "queries": ["hot-sensors"]}]
+    })
+}
+
+async fn request(
+    router: Router,
+    method: &str,
+    uri: &str,
+    body: Option<&Value>,
+) -> (StatusCode, Value) {
+    let builder = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => builder
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(body).unwrap())),
+        None => builder.body(Body::empty()),
+    }
+    .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+/// Ids of the user-visible components of `resource`.
+async fn list_ids(router: Router, resource: &str) -> Vec<String> {
+    let (_, json) = request(
+        router,
+        "GET",
+        &format!("/instances/{INSTANCE}/{resource}"),
+        None,
+    )
+    .await;
+    json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["id"].as_str().unwrap().to_string())
+        .filter(|id| !id.starts_with("__"))
+        .collect()
+}
+
+/// `(revision, operation)` pairs, newest first.
+async fn revisions(router: Router) -> Vec<(u64, String)> {
+    let (status, json) = request(router, "GET", "/config/revisions", None).await;
+    assert_eq!(status, StatusCode::OK, "{json}");
+    json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["revision"].as_u64().unwrap(),
+                r["operation"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_saves_are_recorded_with_diffs() {
+    let dir = TempDir::new().unwrap();
+    let router = create_test_router(&dir).await;
+    let apply_uri = format!("/instances/{INSTANCE}/apply");
+
+    let (status, _) = request(router.clone(), "POST", &apply_uri, Some(&manifest())).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = request(
+        router.clone(),
+        "DELETE",
+        &format!("/instances/{INSTANCE}/reactions/alerts"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    assert_eq!(
+        revisions(router.clone()).await,
+        vec![
+            (2, "deleting reaction".to_string()),
+            (1, "applying manifest".to_string()),
+        ]
+    );
+
+    let (status, json) = request(router.clone(), "GET", "/config/revisions/2/diff", None).await;
+    assert_eq!(status, StatusCode::OK);
+    let diff = json["data"]["diff"].as_str().unwrap();
+    assert!(
+        diff.lines()
+            .any(|line| line.starts_with('-') && line.contains("alerts")),
+        "{diff}"
+    );
+    assert!(json["data"]["linesRemoved"].as_u64().unwrap() > 0);
+
+    let (status, json) = request(router, "GET", "/config/revisions/99/diff", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(json["code"], "CONFIG_REVISION_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_revision_diffs_redact_secrets() {
+    let dir = TempDir::new().unwrap();
+    let router = create_test_router(&dir).await;
+    let apply_uri = format!("/instances/{INSTANCE}/apply");
+
+    let mut manifest = manifest();
+    manifest["sources"][0]["password"] = json!("hunter2");
+    let (status, _) = request(router.clone(), "POST", &apply_uri, Some(&manifest)).await;
+    assert_eq!(status, StatusCode::OK);
+    manifest["sources"][0]["password"] = json!("correct-horse");
+    let (status, _) = request(router.clone(), "POST", &apply_uri, Some(&manifest)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    for revision in [1, 2] {
+        let (status, json) = request(
+            router.clone(),
+            "GET",
+            &format!("/config/revisions/{revision}/diff"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let diff = json["data"]["diff"].as_str().unwrap();
+        assert!(!diff.contains("hunter2"), "{diff}");
+        assert!(!diff.contains("correct-horse"), "{diff}");
+    }
+}
+
+#[tokio::test]
+async fn test_rollback_reconciles_instance_to_revision() {
+    let dir = TempDir::new().unwrap();
+    let router = create_test_router(&dir).await;
+    let apply_uri = format!("/instances/{INSTANCE}/apply");
+
+    request(router.clone(), "POST", &apply_uri, Some(&manifest())).await;
+    let only_source = json!({"sources": [{"kind": "mock", "id": "sensors"}]});
+    let (status, _) = request(
+        router.clone(),
+        "POST",
+        &format!("{apply_uri}?prune=true"),
+        Some(&only_source),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(list_ids(router.clone(), "reactions").await.is_empty());
+
+    // A dry run only plans.
+    let (status, json) = request(
+        router.clone(),
+        "POST",
+        "/config/revisions/1/rollback?dryRun=true",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{json}");
+    let actions = &json["data"]["instances"][0]["result"]["actions"];
+    assert!(actions
+        .as_array()
+        .unwrap()
+        .contains(&json!({"componentType": "reaction", "id": "alerts", "action": "create"})));
+    assert!(list_ids(router.clone(), "reactions").await.is_empty());
+
+    let (status, json) =
+        request(router.clone(), "POST", "/config/revisions/1/rollback", None).await;
+    assert_eq!(status, StatusCode::OK, "{json}");
+    assert_eq!(json["data"]["applied"], true);
+    assert_eq!(
+        list_ids(router.clone(), "queries").await,
+        vec!["hot-sensors"]
+    );
+    assert_eq!(list_ids(router.clone(), "reactions").await, vec!["alerts"]);
+
+    let history = revisions(router.clone()).await;
+    assert_eq!(
+        history[0],
+        (3, "rolling back to config revision 1".to_string())
+    );
+
+    let (status, _) = request(router, "POST", "/config/revisions/42/rollback", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_revisions_unavailable_without_persistence() {
+    let core = Arc::new(DrasiLib::builder().with_id(INSTANCE).build().await.unwrap());
+    let mut instances = indexmap::IndexMap::new();
+    instances.insert(INSTANCE.to_string(), core);
+    let router = build_v1_router(
+        InstanceRegistry::from_map(instances),
+        ServerMode::ReadWrite,
+        None,
+        Arc::new(tokio::sync::RwLock::new(PluginRegistry::new())),
+        None,
+    );
+
+    let (status, json) = request(router, "GET", "/config/revisions", None).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(json["code"], "PERSISTENCE_DISABLED");
+}
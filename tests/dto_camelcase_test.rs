@@ -87,6 +87,7 @@ fn test_component_event_dto_serializes_camelcase() {
         status: ComponentStatusDto::Running,
         timestamp: chrono::Utc::now(),
         message: Some("started".to_string()),
+        revision: Some(3),
     };
 
     let json = serde_json::to_value(&dto).unwrap();
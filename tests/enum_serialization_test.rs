@@ -11,6 +11,8 @@ fn test_source_config_mock_serializes_camelcase() {
         id: "test-mock".to_string(),
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: json!({"dataType": {"type": "sensorReading", "sensorCount": 5}, "intervalMs": 1000}),
     };
@@ -58,6 +60,8 @@ fn test_source_config_postgres_serializes_camelcase() {
         id: "test-postgres".to_string(),
         auto_start: false,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: json!({
             "host": "localhost",
@@ -116,6 +120,8 @@ fn test_source_config_http_serializes_camelcase() {
         id: "test-http".to_string(),
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: json!({
             "host": "localhost",
@@ -164,6 +170,8 @@ fn test_reaction_config_log_serializes_camelcase() {
         queries: vec!["query1".to_string()],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: json!({"routes": {}}),
     };
 
@@ -189,6 +197,8 @@ fn test_reaction_config_http_serializes_camelcase() {
         queries: vec!["query1".to_string()],
         auto_start: false,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: json!({
             "baseUrl": "http://localhost:8080",
             "timeoutMs": 5000,
@@ -227,6 +237,8 @@ fn test_reaction_config_grpc_serializes_camelcase() {
         queries: vec!["query1".to_string()],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: json!({
             "endpoint": "localhost:50051",
             "timeoutMs": 3000,
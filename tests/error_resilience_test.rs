@@ -27,6 +27,8 @@ async fn test_unknown_source_kind_returns_helpful_error() {
         id: "test".to_string(),
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: serde_json::json!({}),
     };
@@ -52,6 +54,8 @@ async fn test_unknown_reaction_kind_returns_helpful_error() {
         queries: vec!["q1".to_string()],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({}),
     };
 
@@ -96,6 +100,8 @@ async fn test_empty_registry_rejects_all_sources() {
         id: "test".to_string(),
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: serde_json::json!({}),
     };
@@ -116,6 +122,8 @@ async fn test_empty_registry_rejects_all_reactions() {
         queries: vec![],
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({}),
     };
 
@@ -173,6 +181,46 @@ fn test_dynamic_loading_skips_non_library_files() {
     );
 }
 
+#[test]
+fn test_dynamic_loading_skips_incompatible_locked_plugin() {
+    use drasi_server::plugin_lockfile::{LockedPlugin, PluginLockfile};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let filename = "libdrasi_source_stale.so";
+    std::fs::write(temp_dir.path().join(filename), b"not a real library").unwrap();
+
+    let mut lockfile = PluginLockfile::new();
+    lockfile.insert(
+        "source/stale:0.1.0".to_string(),
+        LockedPlugin {
+            reference: "ghcr.io/drasi-project/source/stale@sha256:abc".to_string(),
+            version: "0.1.0".to_string(),
+            digest: "sha256:abc".to_string(),
+            sdk_version: "0.0.1".to_string(),
+            core_version: String::new(),
+            lib_version: String::new(),
+            platform: String::new(),
+            filename: filename.to_string(),
+            file_hash: None,
+            git_commit: None,
+            build_timestamp: None,
+            signature: None,
+        },
+    );
+    lockfile.write(temp_dir.path()).unwrap();
+
+    let mut registry = PluginRegistry::new();
+    let stats =
+        drasi_server::dynamic_loading::load_plugins(temp_dir.path(), &mut registry, None, None)
+            .unwrap();
+
+    assert_eq!(stats.plugins_loaded, 0);
+    assert_eq!(stats.plugins_failed, 1);
+    assert!(stats.compatibility.has_errors());
+    assert_eq!(stats.compatibility.incompatible_files(), vec![filename]);
+    assert!(stats.compatibility.render_table().contains("INCOMPATIBLE"));
+}
+
 // ==========================================================================
 // Config deserialization edge cases
 // ==========================================================================
@@ -31,6 +31,7 @@ use axum::Router;
 use drasi_lib::DrasiLib;
 use drasi_server::api::v1::handlers;
 use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
 use drasi_server::instance_registry::InstanceRegistry;
 use drasi_server::plugin_registry::PluginRegistry;
 use std::sync::Arc;
@@ -61,7 +62,7 @@ async fn start_real_server() -> String {
 
     let v1_router = build_v1_router(
         registry,
-        Arc::new(false),
+        ServerMode::ReadWrite,
         None,
         Arc::new(tokio::sync::RwLock::new(plugin_registry)),
         None,
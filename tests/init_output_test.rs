@@ -24,6 +24,7 @@
 
 use drasi_lib::config::QueryLanguage;
 use drasi_server::api::models::*;
+use drasi_server::config::ServerMode;
 use drasi_server::DrasiServerConfig;
 use serde_json::json;
 
@@ -135,8 +136,11 @@ fn test_empty_config_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -151,6 +155,8 @@ fn test_empty_config_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -176,12 +182,13 @@ fn test_config_with_state_store_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: true,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
-        state_store: Some(StateStoreConfig::Redb {
-            path: ConfigValue::Static("./data/state.redb".to_string()),
-        }),
+        state_store: Some(StateStoreConfig::redb("./data/state.redb")),
         secret_store: None,
         default_priority_queue_capacity: Some(ConfigValue::Static(5000)),
         default_dispatch_buffer_capacity: Some(ConfigValue::Static(500)),
@@ -194,6 +201,8 @@ fn test_config_with_state_store_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -226,8 +235,11 @@ fn test_mock_source_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -238,6 +250,8 @@ fn test_mock_source_generates_valid_yaml() {
             id: "mock-source".to_string(),
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             bootstrap_provider: None,
             config: json!({"dataType": {"type": "sensorReading", "sensorCount": 5}, "intervalMs": 5000}),
         }],
@@ -249,6 +263,8 @@ fn test_mock_source_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -277,8 +293,11 @@ fn test_http_source_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -289,6 +308,8 @@ fn test_http_source_generates_valid_yaml() {
             id: "http-source".to_string(),
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             bootstrap_provider: None,
             config: json!({"host": "0.0.0.0", "port": 9000, "timeoutMs": 10000}),
         }],
@@ -300,6 +321,8 @@ fn test_http_source_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -327,8 +350,11 @@ fn test_grpc_source_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -339,6 +365,8 @@ fn test_grpc_source_generates_valid_yaml() {
             id: "grpc-source".to_string(),
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             bootstrap_provider: None,
             config: json!({"host": "0.0.0.0", "port": 50051, "timeoutMs": 5000}),
         }],
@@ -350,6 +378,8 @@ fn test_grpc_source_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -376,8 +406,11 @@ fn test_postgres_source_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -388,6 +421,8 @@ fn test_postgres_source_generates_valid_yaml() {
             id: "postgres-source".to_string(),
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             bootstrap_provider: Some(BootstrapProviderRef::Inline(BootstrapProviderConfig {
                 kind: "postgres".to_string(),
                 config: serde_json::json!({
@@ -424,6 +459,8 @@ fn test_postgres_source_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -471,8 +508,11 @@ fn test_postgres_bootstrap_provider_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -483,6 +523,8 @@ fn test_postgres_bootstrap_provider_generates_valid_yaml() {
             id: "mock-source".to_string(),
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             bootstrap_provider: Some(BootstrapProviderRef::Inline(BootstrapProviderConfig {
                 kind: "postgres".to_string(),
                 config: serde_json::json!({
@@ -508,6 +550,8 @@ fn test_postgres_bootstrap_provider_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -548,8 +592,11 @@ fn test_scriptfile_bootstrap_provider_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -560,6 +607,8 @@ fn test_scriptfile_bootstrap_provider_generates_valid_yaml() {
             id: "mock-source".to_string(),
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             bootstrap_provider: Some(BootstrapProviderRef::Inline(BootstrapProviderConfig {
                 kind: "scriptfile".to_string(),
                 config: serde_json::json!({
@@ -576,6 +625,8 @@ fn test_scriptfile_bootstrap_provider_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -611,8 +662,11 @@ fn test_noop_bootstrap_provider_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -623,6 +677,8 @@ fn test_noop_bootstrap_provider_generates_valid_yaml() {
             id: "mock-source".to_string(),
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             bootstrap_provider: Some(BootstrapProviderRef::Inline(BootstrapProviderConfig {
                 kind: "noop".to_string(),
                 config: serde_json::json!({}),
@@ -637,6 +693,8 @@ fn test_noop_bootstrap_provider_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -670,8 +728,11 @@ fn test_log_reaction_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -685,6 +746,8 @@ fn test_log_reaction_generates_valid_yaml() {
             queries: vec!["my-query".to_string()],
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: json!({"routes": {}}),
         }],
         instances: vec![],
@@ -693,6 +756,8 @@ fn test_log_reaction_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -720,8 +785,11 @@ fn test_http_reaction_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -735,6 +803,8 @@ fn test_http_reaction_generates_valid_yaml() {
             queries: vec!["my-query".to_string()],
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: json!({"baseUrl": "https://api.example.com", "token": "secret-token", "timeoutMs": 5000, "routes": {}}),
         }],
         instances: vec![],
@@ -743,6 +813,8 @@ fn test_http_reaction_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -771,8 +843,11 @@ fn test_sse_reaction_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -786,6 +861,8 @@ fn test_sse_reaction_generates_valid_yaml() {
             queries: vec!["my-query".to_string()],
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: json!({"host": "0.0.0.0", "port": 8081, "ssePath": "/events", "heartbeatIntervalMs": 30000, "routes": {}}),
         }],
         instances: vec![],
@@ -794,6 +871,8 @@ fn test_sse_reaction_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -825,8 +904,11 @@ fn test_grpc_reaction_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -840,6 +922,8 @@ fn test_grpc_reaction_generates_valid_yaml() {
             queries: vec!["my-query".to_string()],
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: json!({"endpoint": "grpc://localhost:50052", "timeoutMs": 5000, "batchSize": 100, "batchFlushTimeoutMs": 1000, "maxRetries": 3, "connectionRetryAttempts": 5, "initialConnectionTimeoutMs": 10000, "metadata": {}}),
         }],
         instances: vec![],
@@ -848,6 +932,8 @@ fn test_grpc_reaction_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -884,8 +970,11 @@ fn test_query_generates_valid_yaml() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: false,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
         state_store: None,
         secret_store: None,
@@ -921,6 +1010,8 @@ fn test_query_generates_valid_yaml() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -963,12 +1054,13 @@ fn test_full_config_roundtrip() {
         port: ConfigValue::Static(8080),
         log_level: ConfigValue::Static("info".to_string()),
         persist_config: true,
+        mode: ServerMode::ReadWrite,
         persist_index: true,
         enable_archive: false,
+        archive_retention: None,
+        wal_retention: None,
         enable_ui: true,
-        state_store: Some(StateStoreConfig::Redb {
-            path: ConfigValue::Static("./data/state.redb".to_string()),
-        }),
+        state_store: Some(StateStoreConfig::redb("./data/state.redb")),
         secret_store: None,
         default_priority_queue_capacity: Some(ConfigValue::Static(5000)),
         default_dispatch_buffer_capacity: Some(ConfigValue::Static(500)),
@@ -977,6 +1069,8 @@ fn test_full_config_roundtrip() {
             id: "mock-source".to_string(),
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             bootstrap_provider: Some(BootstrapProviderRef::Inline(BootstrapProviderConfig {
                 kind: "scriptfile".to_string(),
                 config: serde_json::json!({
@@ -1013,6 +1107,8 @@ fn test_full_config_roundtrip() {
             queries: vec!["sensor-query".to_string()],
             auto_start: true,
             identity_provider: None,
+            restart_policy: None,
+            config_version: None,
             config: json!({"routes": {}}),
         }],
         instances: vec![],
@@ -1021,6 +1117,8 @@ fn test_full_config_roundtrip() {
         plugins: vec![],
         verify_plugins: false,
         trusted_identities: vec![],
+        trusted_keys: vec![],
+        plugin_circuit_breaker: None,
         hot_reload_plugins: false,
         hot_reload_debounce_ms: 2000,
         cors_allowed_origins: Vec::new(),
@@ -9,6 +9,7 @@ use axum::{
 };
 use drasi_server::api::v1::openapi::ApiDocV1;
 use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
 use drasi_server::instance_registry::InstanceRegistry;
 use drasi_server::plugin_registry::PluginRegistry;
 use std::sync::Arc;
@@ -17,7 +18,7 @@ use utoipa::OpenApi;
 
 async fn create_test_router() -> Router {
     let registry = InstanceRegistry::new();
-    let read_only = Arc::new(false);
+    let mode = ServerMode::ReadWrite;
     let config_persistence = None;
     let mut plugin_registry = PluginRegistry::new();
     drasi_server::register_core_plugins(&mut plugin_registry);
@@ -25,7 +26,7 @@ async fn create_test_router() -> Router {
 
     build_v1_router(
         registry,
-        read_only,
+        mode,
         config_persistence,
         Arc::new(tokio::sync::RwLock::new(plugin_registry)),
         solutions_dir,
@@ -272,3 +273,46 @@ fn test_openapi_spec_has_plugins_tag() {
         "Should have 'Plugins' tag, found: {tag_names:?}",
     );
 }
+
+#[tokio::test]
+async fn test_config_schema_endpoint_returns_json_schema() {
+    let router = create_test_router().await;
+
+    let response = router
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/config/schema")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let schema: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+    assert_eq!(
+        schema["properties"]["sources"]["items"]["$ref"],
+        "#/definitions/SourceConfig"
+    );
+    let reaction_kinds: Vec<&str> = schema["definitions"]["ReactionConfig"]["oneOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|variant| variant["properties"]["kind"]["const"].as_str())
+        .collect();
+    assert!(
+        reaction_kinds.contains(&"application"),
+        "Reaction variants should include the core application reaction, got: {reaction_kinds:?}"
+    );
+}
+
+#[test]
+fn test_openapi_spec_includes_config_schema_path() {
+    let spec = ApiDocV1::openapi();
+    let json = serde_json::to_value(&spec).unwrap();
+    assert!(json["paths"]["/api/v1/config/schema"]["get"].is_object());
+}
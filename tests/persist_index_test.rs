@@ -21,10 +21,17 @@
 //! - DrasiServerBuilder with_default_index_provider method works correctly
 
 use anyhow::Result;
+use axum::Router;
 use drasi_index_rocksdb::RocksDbIndexProvider;
 use drasi_lib::DrasiLib;
 use drasi_lib::IndexBackendPlugin;
+use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
+use drasi_server::instance_paths::{instance_data_dir, DATA_ROOT};
+use drasi_server::instance_registry::InstanceRegistry;
+use drasi_server::plugin_registry::PluginRegistry;
 use drasi_server::DrasiServerConfig;
+use std::path::Path;
 use std::sync::Arc;
 use tempfile::TempDir;
 
@@ -506,6 +513,27 @@ impl Drop for DataDirGuard {
     }
 }
 
+/// A v1 router over an empty registry, for creating `persistIndex`
+/// instances over HTTP. The registry reaches the instances the handler
+/// creates; the guard removes `instance_id`'s data directory, which is
+/// cleared first in case an earlier run left it behind.
+fn persistent_instance_router(instance_id: &str) -> (Router, InstanceRegistry, DataDirGuard) {
+    let data_dir = instance_data_dir(Path::new(DATA_ROOT), instance_id);
+    let _ = std::fs::remove_dir_all(&data_dir);
+
+    let registry = InstanceRegistry::new();
+    let mut plugin_registry = PluginRegistry::new();
+    drasi_server::register_core_plugins(&mut plugin_registry);
+    let router = build_v1_router(
+        registry.clone(),
+        ServerMode::ReadWrite,
+        None,
+        Arc::new(tokio::sync::RwLock::new(plugin_registry)),
+        None,
+    );
+    (router, registry, DataDirGuard(data_dir))
+}
+
 /// The `POST /instances` handler must honor `persistIndex: true` end-to-end: a
 /// query added to the created instance is backed by RocksDB on disk. This guards
 /// against a regression in the JSON `persistIndex` -> `persist_index` mapping,
@@ -514,44 +542,15 @@ impl Drop for DataDirGuard {
 async fn test_create_instance_persist_index_via_http() -> Result<()> {
     use axum::body::{to_bytes, Body};
     use axum::http::{Request, StatusCode};
-    use axum::Router;
     use drasi_lib::Query;
     use drasi_server::api::v1::handlers;
-    use drasi_server::api::v1::routes::build_v1_router;
-    use drasi_server::instance_registry::InstanceRegistry;
-    use drasi_server::plugin_registry::PluginRegistry;
     use tower::ServiceExt;
 
     const INSTANCE_ID: &str = "http-persist-index-instance";
-    // The server derives the on-disk directory from a filesystem-safe storage
-    // key (`id-` followed by the hex encoding of the instance id), shared by the
-    // index and WAL paths. Replicate that here to locate the index directory.
-    let storage_key = {
-        let mut key = String::from("id-");
-        for byte in INSTANCE_ID.bytes() {
-            key.push(char::from(b"0123456789abcdef"[usize::from(byte >> 4)]));
-            key.push(char::from(b"0123456789abcdef"[usize::from(byte & 0x0f)]));
-        }
-        key
-    };
-    let data_dir = std::path::PathBuf::from(format!("./data/{storage_key}"));
-    let _ = std::fs::remove_dir_all(&data_dir);
-    let _guard = DataDirGuard(data_dir.clone());
-
-    // Empty registry; retain a clone to reach the instance created by the handler.
-    let registry = InstanceRegistry::new();
-    let mut plugin_registry = PluginRegistry::new();
-    drasi_server::register_core_plugins(&mut plugin_registry);
-
+    let (router, registry, data_dir) = persistent_instance_router(INSTANCE_ID);
     let router = Router::new()
         .route("/health", axum::routing::get(handlers::health_check))
-        .merge(build_v1_router(
-            registry.clone(),
-            Arc::new(false),
-            None,
-            Arc::new(tokio::sync::RwLock::new(plugin_registry)),
-            None,
-        ));
+        .merge(router);
 
     // Create the instance with persistent indexing enabled.
     let body = serde_json::json!({ "id": INSTANCE_ID, "persistIndex": true });
@@ -597,7 +596,7 @@ async fn test_create_instance_persist_index_via_http() -> Result<()> {
     .expect("query should reach Running");
     core.stop().await?;
 
-    let index_path = data_dir.join("index");
+    let index_path = data_dir.0.join("index");
     let populated = std::fs::read_dir(&index_path)
         .map(|dir| dir.count() > 0)
         .unwrap_or(false);
@@ -609,3 +608,369 @@ async fn test_create_instance_persist_index_via_http() -> Result<()> {
 
     Ok(())
 }
+
+/// An instance created with `archiveRetention` runs the retention task over
+/// its queries' archives and reports the result and index size through
+/// `GET /instances/{id}/archive`.
+#[tokio::test]
+async fn test_archive_retention_reported_via_http() -> Result<()> {
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use drasi_lib::Query;
+    use tower::ServiceExt;
+
+    const INSTANCE_ID: &str = "http-archive-retention-instance";
+    let (router, registry, _guard) = persistent_instance_router(INSTANCE_ID);
+    let post = |body: serde_json::Value| {
+        Request::builder()
+            .method("POST")
+            .uri("/instances")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("valid request")
+    };
+
+    // A policy that would empty the archive is rejected.
+    let response = router
+        .clone()
+        .oneshot(post(serde_json::json!({
+            "id": INSTANCE_ID,
+            "persistIndex": true,
+            "enableArchive": true,
+            "archiveRetention": { "maxVersions": 0 }
+        })))
+        .await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = router
+        .clone()
+        .oneshot(post(serde_json::json!({
+            "id": INSTANCE_ID,
+            "persistIndex": true,
+            "enableArchive": true,
+            "archiveRetention": { "maxVersions": 1, "intervalSecs": 1 }
+        })))
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let core = registry
+        .get(INSTANCE_ID)
+        .await
+        .expect("created instance should be registered");
+    core.add_query(
+        Query::cypher("archived-query")
+            .query("MATCH (n) RETURN n")
+            .auto_start(true)
+            .build(),
+    )
+    .await?;
+
+    let uri = format!("/instances/{INSTANCE_ID}/archive");
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+    let body = loop {
+        let response = router
+            .clone()
+            .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = to_bytes(response.into_body(), usize::MAX).await?;
+        let body: serde_json::Value = serde_json::from_slice(&bytes)?;
+        let ran = body["data"]["queries"]
+            .as_array()
+            .is_some_and(|queries| queries.iter().any(|q| q["lastRun"].is_string()));
+        if ran {
+            break body;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "retention never ran: {body}"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    };
+
+    let data = &body["data"];
+    assert_eq!(data["retention"]["maxVersions"], 1);
+    assert_eq!(data["queries"][0]["queryId"], "archived-query");
+    assert_eq!(data["queries"][0]["limits"]["maxVersions"], 1);
+    assert!(data["queries"][0]["lastError"].is_null());
+    assert!(data["indexBytes"].as_u64().unwrap_or(0) > 0);
+
+    core.stop().await?;
+    registry.remove(INSTANCE_ID).await;
+    Ok(())
+}
+
+/// Test that GET /instances/{id}/storage and GET /metrics report the
+/// persistent index of an instance created over HTTP.
+#[tokio::test]
+async fn test_storage_usage_reported_via_http() -> Result<()> {
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Request, StatusCode};
+    use drasi_lib::Query;
+    use tower::ServiceExt;
+
+    const INSTANCE_ID: &str = "http-storage-usage-instance";
+    let (router, registry, _guard) = persistent_instance_router(INSTANCE_ID);
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/instances")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "id": INSTANCE_ID, "persistIndex": true }).to_string(),
+                ))?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let core = registry
+        .get(INSTANCE_ID)
+        .await
+        .expect("created instance should be registered");
+    core.add_query(
+        Query::cypher("stored-query")
+            .query("MATCH (n) RETURN n")
+            .auto_start(true)
+            .build(),
+    )
+    .await?;
+
+    let uri = format!("/instances/{INSTANCE_ID}/storage");
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+    let body = loop {
+        let response = router
+            .clone()
+            .oneshot(Request::builder().uri(&uri).body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = to_bytes(response.into_body(), usize::MAX).await?;
+        let body: serde_json::Value = serde_json::from_slice(&bytes)?;
+        if body["data"]["queries"][0]["persistent"] == true {
+            break body;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "query index never opened: {body}"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    };
+
+    let data = &body["data"];
+    assert_eq!(data["instanceId"], INSTANCE_ID);
+    assert!(data["indexBytes"].as_u64().unwrap_or(0) > 0);
+    assert!(data["walBytes"].is_u64());
+    assert!(data["archiveBytes"].is_null());
+    assert!(data["stateStoreBytes"].is_null());
+    assert_eq!(data["queries"][0]["queryId"], "stored-query");
+    assert_eq!(data["queries"][0]["elementCount"], 0);
+    assert!(data["queries"][0]["archiveVersions"].is_null());
+
+    // The first scrape starts a background count; later ones serve it.
+    let gauge =
+        format!("drasi_query_elements{{instance=\"{INSTANCE_ID}\",query=\"stored-query\"}} 0\n");
+    loop {
+        let response = router
+            .clone()
+            .oneshot(Request::builder().uri("/metrics").body(Body::empty())?)
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        assert!(content_type.starts_with("text/plain"), "{content_type}");
+        let bytes = to_bytes(response.into_body(), usize::MAX).await?;
+        let text = String::from_utf8(bytes.to_vec())?;
+        if text.contains(&gauge) {
+            break;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "index never counted: {text}"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+
+    core.stop().await?;
+    registry.remove(INSTANCE_ID).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wal_status_reported_via_http() -> Result<()> {
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    const INSTANCE_ID: &str = "http-wal-status-instance";
+    let (router, registry, _guard) = persistent_instance_router(INSTANCE_ID);
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/instances")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "id": INSTANCE_ID,
+                        "persistIndex": true,
+                        "walRetention": { "keepEvents": 100 },
+                    })
+                    .to_string(),
+                ))?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/instances/{INSTANCE_ID}/wal"))
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = to_bytes(response.into_body(), usize::MAX).await?;
+    let body: serde_json::Value = serde_json::from_slice(&bytes)?;
+    assert_eq!(body["data"]["instanceId"], INSTANCE_ID);
+    assert_eq!(body["data"]["retention"]["keepEvents"], 100);
+    assert_eq!(body["data"]["sources"], serde_json::json!([]));
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/instances/{INSTANCE_ID}/sources/missing/replay"))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"fromSequence": 1}"#))?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let core = registry
+        .get(INSTANCE_ID)
+        .await
+        .expect("created instance should be registered");
+    core.stop().await?;
+    registry.remove(INSTANCE_ID).await;
+    Ok(())
+}
+
+/// Test that POST /instances/{id}/queries/{id}/rebootstrap restarts a
+/// running persistent query, which comes back up after bootstrapping, and
+/// refuses a query backed by another persistent storage backend.
+#[tokio::test]
+async fn test_rebootstrap_query_via_http() -> Result<()> {
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use drasi_lib::{ComponentStatus, Query, StorageBackendRef};
+    use tower::ServiceExt;
+
+    const INSTANCE_ID: &str = "http-rebootstrap-instance";
+    let (router, registry, _guard) = persistent_instance_router(INSTANCE_ID);
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/instances")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "id": INSTANCE_ID, "persistIndex": true }).to_string(),
+                ))?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let core = registry
+        .get(INSTANCE_ID)
+        .await
+        .expect("created instance should be registered");
+    core.add_query(
+        Query::cypher("rebootstrapped-query")
+            .query("MATCH (n) RETURN n")
+            .auto_start(true)
+            .build(),
+    )
+    .await?;
+    let wait_until_running = || async {
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !matches!(
+            core.get_query_status("rebootstrapped-query").await,
+            Ok(ComponentStatus::Running)
+        ) {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "query never reached Running"
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        }
+    };
+    wait_until_running().await;
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/instances/{INSTANCE_ID}/queries/rebootstrapped-query/rebootstrap"
+                ))
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = to_bytes(response.into_body(), usize::MAX).await?;
+    let body: serde_json::Value = serde_json::from_slice(&bytes)?;
+    assert_eq!(body["data"]["queryId"], "rebootstrapped-query");
+    assert_eq!(body["data"]["restarted"], true);
+    wait_until_running().await;
+
+    core.add_query(
+        Query::cypher("elsewhere-query")
+            .query("MATCH (n) RETURN n")
+            .auto_start(false)
+            .with_storage_backend(StorageBackendRef::Named("elsewhere".to_string()))
+            .build(),
+    )
+    .await?;
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/instances/{INSTANCE_ID}/queries/elsewhere-query/rebootstrap"
+                ))
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/instances/{INSTANCE_ID}/queries/missing/rebootstrap"
+                ))
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    core.stop().await?;
+    registry.remove(INSTANCE_ID).await;
+    Ok(())
+}
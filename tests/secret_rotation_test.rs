@@ -0,0 +1,534 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for secret rotation: when a consumed secret changes, only the
+//! components that resolved it are recreated, and the rotation shows up in
+//! their events. Also covers plugins resolving secrets after their
+//! component was created.
+
+#![allow(clippy::unwrap_used)]
+
+mod test_support;
+
+use test_support::create_mock_source;
+
+use async_trait::async_trait;
+use drasi_lib::secret_store::{MemorySecretStoreProvider, SecretStoreProvider};
+use drasi_lib::{ComponentStatus, DrasiLib, Source, SourceRuntimeContext, SubscriptionResponse};
+use drasi_plugin_sdk::ffi::FfiStr;
+use drasi_plugin_sdk::SourcePluginDescriptor;
+use drasi_server::component_revisions::ComponentRevisions;
+use drasi_server::factories::{
+    create_identity_provider_locked, create_source_locked, host_resolve_config_value,
+    ConfigResolverContext,
+};
+use drasi_server::plugin_isolation::PluginCircuitBreakers;
+use drasi_server::plugin_registry::PluginRegistry;
+use drasi_server::secret_rotation::{rotate_secrets, RotatedComponent, SecretConsumers};
+use futures::StreamExt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+
+const INSTANCE: &str = "rotation-instance";
+
+/// Resolve a `{kind: Secret}` reference through the host callback.
+fn resolve_through_host(reference: &serde_json::Value) -> anyhow::Result<String> {
+    let json = reference.to_string();
+    let result = host_resolve_config_value(
+        ConfigResolverContext::global().as_ptr(),
+        FfiStr::from_str(&json),
+    );
+    unsafe { result.into_result() }
+}
+
+/// Source descriptor that resolves its `password` field through the host,
+/// like a dynamically loaded plugin, and remembers each value it saw.
+/// Registered with a plugin id, so the host tags the secret references it is
+/// given as it does for loaded plugins.
+struct SecretSourceDescriptor {
+    seen: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl SourcePluginDescriptor for SecretSourceDescriptor {
+    fn kind(&self) -> &str {
+        "secret-mock"
+    }
+    fn config_version(&self) -> &str {
+        "1.0.0"
+    }
+    fn config_schema_json(&self) -> String {
+        r#"{"type":"object"}"#.to_string()
+    }
+    fn config_schema_name(&self) -> &str {
+        "SecretMockSourceConfig"
+    }
+    async fn create_source(
+        &self,
+        id: &str,
+        config_json: &serde_json::Value,
+        _auto_start: bool,
+    ) -> anyhow::Result<Box<dyn drasi_lib::sources::Source>> {
+        if let Some(password) = config_json.get("password") {
+            let value = resolve_through_host(password)?;
+            self.seen.lock().unwrap().push(value);
+        }
+        Ok(Box::new(create_mock_source(id)))
+    }
+}
+
+/// Source that resolves its `password` only when it starts, like a plugin
+/// that connects lazily.
+struct LazySecretSource {
+    inner: test_support::MockSource,
+    password: serde_json::Value,
+    seen: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Source for LazySecretSource {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+    fn type_name(&self) -> &str {
+        "lazy-secret-mock"
+    }
+    fn properties(&self) -> std::collections::HashMap<String, serde_json::Value> {
+        self.inner.properties()
+    }
+    async fn start(&self) -> anyhow::Result<()> {
+        let value = resolve_through_host(&self.password)?;
+        self.seen.lock().unwrap().push(value);
+        self.inner.start().await
+    }
+    async fn stop(&self) -> anyhow::Result<()> {
+        self.inner.stop().await
+    }
+    async fn status(&self) -> ComponentStatus {
+        self.inner.status().await
+    }
+    async fn subscribe(
+        &self,
+        settings: drasi_lib::config::SourceSubscriptionSettings,
+    ) -> anyhow::Result<SubscriptionResponse> {
+        self.inner.subscribe(settings).await
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    async fn initialize(&self, context: SourceRuntimeContext) {
+        self.inner.initialize(context).await
+    }
+}
+
+struct LazySecretSourceDescriptor {
+    seen: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl SourcePluginDescriptor for LazySecretSourceDescriptor {
+    fn kind(&self) -> &str {
+        "lazy-secret-mock"
+    }
+    fn config_version(&self) -> &str {
+        "1.0.0"
+    }
+    fn config_schema_json(&self) -> String {
+        r#"{"type":"object"}"#.to_string()
+    }
+    fn config_schema_name(&self) -> &str {
+        "LazySecretMockSourceConfig"
+    }
+    async fn create_source(
+        &self,
+        id: &str,
+        config_json: &serde_json::Value,
+        _auto_start: bool,
+    ) -> anyhow::Result<Box<dyn drasi_lib::sources::Source>> {
+        Ok(Box::new(LazySecretSource {
+            inner: create_mock_source(id),
+            password: config_json["password"].clone(),
+            seen: self.seen.clone(),
+        }))
+    }
+}
+
+fn source_config(id: &str, password: Option<&str>) -> drasi_server::SourceConfig {
+    let mut config = serde_json::json!({"kind": "secret-mock", "id": id});
+    if let Some(name) = password {
+        config["password"] = serde_json::json!({"kind": "Secret", "name": name});
+    }
+    serde_json::from_value(config).unwrap()
+}
+
+fn registry_with_secret_source(seen: &Arc<Mutex<Vec<String>>>) -> RwLock<PluginRegistry> {
+    let mut plugin_registry = PluginRegistry::new();
+    plugin_registry.register_source_with_metadata(
+        Arc::new(SecretSourceDescriptor { seen: seen.clone() }),
+        "secret-mock-plugin",
+    );
+    RwLock::new(plugin_registry)
+}
+
+#[tokio::test]
+async fn test_rotation_reconfigures_only_the_consumers_of_the_changed_secret() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let plugin_registry = registry_with_secret_source(&seen);
+
+    let store = Arc::new(MemorySecretStoreProvider::new().with_secret("DB_PASSWORD", "v1"));
+    let provider: Arc<dyn SecretStoreProvider> = store.clone();
+    ConfigResolverContext::global().set_instance_store(INSTANCE, provider.clone());
+
+    let revisions = ComponentRevisions::new();
+    let breakers = PluginCircuitBreakers::default();
+    let consumers = SecretConsumers::new();
+    let core = DrasiLib::builder().with_id(INSTANCE).build().await.unwrap();
+    core.start().await.unwrap();
+    for config in [
+        source_config("orders", Some("DB_PASSWORD")),
+        source_config("sensors", None),
+    ] {
+        let (source, _) =
+            create_source_locked(&plugin_registry, INSTANCE, &consumers, &breakers, config)
+                .await
+                .unwrap();
+        core.add_source(source).await.unwrap();
+    }
+    assert_eq!(*seen.lock().unwrap(), ["v1"]);
+
+    let rotated = rotate_secrets(
+        &core,
+        INSTANCE,
+        &plugin_registry,
+        &revisions,
+        &consumers,
+        &breakers,
+        &provider,
+    )
+    .await;
+    assert!(rotated.is_empty(), "nothing changed yet: {rotated:?}");
+
+    store.set_secret("DB_PASSWORD", "v2").await;
+    let rotated = rotate_secrets(
+        &core,
+        INSTANCE,
+        &plugin_registry,
+        &revisions,
+        &consumers,
+        &breakers,
+        &provider,
+    )
+    .await;
+    assert_eq!(
+        rotated,
+        [RotatedComponent {
+            kind: "source",
+            id: "orders".to_string(),
+            secrets: vec!["DB_PASSWORD".to_string()],
+        }]
+    );
+    assert_eq!(*seen.lock().unwrap(), ["v1", "v2"]);
+
+    let messages: Vec<String> = core
+        .get_source_events("orders")
+        .await
+        .unwrap()
+        .filter_map(|event| async move { event.message })
+        .collect()
+        .await;
+    assert!(
+        messages
+            .iter()
+            .any(|m| m == "Reconfigured after secret rotation: DB_PASSWORD"),
+        "{messages:?}"
+    );
+
+    // The new value is now the baseline.
+    let rotated = rotate_secrets(
+        &core,
+        INSTANCE,
+        &plugin_registry,
+        &revisions,
+        &consumers,
+        &breakers,
+        &provider,
+    )
+    .await;
+    assert!(rotated.is_empty(), "{rotated:?}");
+
+    ConfigResolverContext::global().remove_instance_store(INSTANCE);
+}
+
+#[tokio::test]
+async fn test_rotation_only_sees_consumers_recorded_by_its_own_server() {
+    const INSTANCE: &str = "rotation-scoped-instance";
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let plugin_registry = registry_with_secret_source(&seen);
+    let store = Arc::new(MemorySecretStoreProvider::new().with_secret("DB_PASSWORD", "v1"));
+    let provider: Arc<dyn SecretStoreProvider> = store.clone();
+    ConfigResolverContext::global().set_instance_store(INSTANCE, provider.clone());
+
+    let revisions = ComponentRevisions::new();
+    let breakers = PluginCircuitBreakers::default();
+    let core = DrasiLib::builder().with_id(INSTANCE).build().await.unwrap();
+    core.start().await.unwrap();
+    let consumers = SecretConsumers::new();
+    let (source, _) = create_source_locked(
+        &plugin_registry,
+        INSTANCE,
+        &consumers,
+        &breakers,
+        source_config("orders", Some("DB_PASSWORD")),
+    )
+    .await
+    .unwrap();
+    core.add_source(source).await.unwrap();
+
+    // Another server hosting an instance with the same id has no record of
+    // `orders`, so its rotation leaves the source alone.
+    let other_server = SecretConsumers::new();
+    store.set_secret("DB_PASSWORD", "v2").await;
+    let rotated = rotate_secrets(
+        &core,
+        INSTANCE,
+        &plugin_registry,
+        &revisions,
+        &other_server,
+        &breakers,
+        &provider,
+    )
+    .await;
+    assert!(rotated.is_empty(), "{rotated:?}");
+    assert_eq!(*seen.lock().unwrap(), ["v1"]);
+
+    let rotated = rotate_secrets(
+        &core,
+        INSTANCE,
+        &plugin_registry,
+        &revisions,
+        &consumers,
+        &breakers,
+        &provider,
+    )
+    .await;
+    assert_eq!(rotated.len(), 1, "{rotated:?}");
+    assert_eq!(*seen.lock().unwrap(), ["v1", "v2"]);
+
+    ConfigResolverContext::global().remove_instance_store(INSTANCE);
+}
+
+#[tokio::test]
+async fn test_rotation_skips_a_component_replaced_while_it_waited_for_the_lock() {
+    const INSTANCE: &str = "rotation-race-instance";
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let plugin_registry = registry_with_secret_source(&seen);
+    let store = Arc::new(MemorySecretStoreProvider::new().with_secret("DB_PASSWORD", "v1"));
+    let provider: Arc<dyn SecretStoreProvider> = store.clone();
+    ConfigResolverContext::global().set_instance_store(INSTANCE, provider.clone());
+
+    let revisions = ComponentRevisions::new();
+    let breakers = PluginCircuitBreakers::default();
+    let consumers = SecretConsumers::new();
+    let core = DrasiLib::builder().with_id(INSTANCE).build().await.unwrap();
+    core.start().await.unwrap();
+    let (source, _) = create_source_locked(
+        &plugin_registry,
+        INSTANCE,
+        &consumers,
+        &breakers,
+        source_config("orders", Some("DB_PASSWORD")),
+    )
+    .await
+    .unwrap();
+    core.add_source(source).await.unwrap();
+
+    store.set_secret("DB_PASSWORD", "v2").await;
+    // A write holds the instance lock while rotation finds `orders` stale,
+    // and replaces the source, resolving the new value itself.
+    let guard = revisions.lock_writes(INSTANCE).await;
+    let (rotated, ()) = tokio::join!(
+        rotate_secrets(
+            &core,
+            INSTANCE,
+            &plugin_registry,
+            &revisions,
+            &consumers,
+            &breakers,
+            &provider,
+        ),
+        async {
+            let (source, _) = create_source_locked(
+                &plugin_registry,
+                INSTANCE,
+                &consumers,
+                &breakers,
+                source_config("orders", Some("DB_PASSWORD")),
+            )
+            .await
+            .unwrap();
+            core.update_source("orders", source).await.unwrap();
+            drop(guard);
+        }
+    );
+    assert!(rotated.is_empty(), "{rotated:?}");
+    assert_eq!(*seen.lock().unwrap(), ["v1", "v2"]);
+
+    ConfigResolverContext::global().remove_instance_store(INSTANCE);
+}
+
+#[tokio::test]
+async fn test_rotating_an_identity_provider_secret_recreates_its_dependents() {
+    const INSTANCE: &str = "rotation-idp-instance";
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let plugin_registry = registry_with_secret_source(&seen);
+    let store = Arc::new(MemorySecretStoreProvider::new().with_secret("IDP_PASSWORD", "p1"));
+    let provider: Arc<dyn SecretStoreProvider> = store.clone();
+    ConfigResolverContext::global().set_instance_store(INSTANCE, provider.clone());
+
+    let revisions = ComponentRevisions::new();
+    let breakers = PluginCircuitBreakers::default();
+    let consumers = SecretConsumers::new();
+    let core = DrasiLib::builder().with_id(INSTANCE).build().await.unwrap();
+    core.start().await.unwrap();
+    let idp: drasi_server::api::models::IdentityProviderConfig =
+        serde_json::from_value(serde_json::json!({
+            "kind": "password",
+            "id": "db-login",
+            "username": "app",
+            "password": {"kind": "Secret", "name": "IDP_PASSWORD"},
+        }))
+        .unwrap();
+    let identity = create_identity_provider_locked(&plugin_registry, INSTANCE, &consumers, &idp)
+        .await
+        .unwrap();
+    for id in ["orders", "sensors"] {
+        let mut config = source_config(id, None);
+        if id == "orders" {
+            config.identity_provider = Some("db-login".to_string());
+        }
+        let (source, _) =
+            create_source_locked(&plugin_registry, INSTANCE, &consumers, &breakers, config)
+                .await
+                .unwrap();
+        if id == "orders" {
+            source.set_identity_provider(identity.clone()).await;
+        }
+        core.add_source(source).await.unwrap();
+    }
+
+    let rotated = rotate_secrets(
+        &core,
+        INSTANCE,
+        &plugin_registry,
+        &revisions,
+        &consumers,
+        &breakers,
+        &provider,
+    )
+    .await;
+    assert!(rotated.is_empty(), "nothing changed yet: {rotated:?}");
+
+    store.set_secret("IDP_PASSWORD", "p2").await;
+    let rotated = rotate_secrets(
+        &core,
+        INSTANCE,
+        &plugin_registry,
+        &revisions,
+        &consumers,
+        &breakers,
+        &provider,
+    )
+    .await;
+    let secrets = vec!["IDP_PASSWORD".to_string()];
+    assert_eq!(
+        rotated,
+        [
+            RotatedComponent {
+                kind: "identityProvider",
+                id: "db-login".to_string(),
+                secrets: secrets.clone(),
+            },
+            RotatedComponent {
+                kind: "source",
+                id: "orders".to_string(),
+                secrets,
+            },
+        ]
+    );
+
+    let rotated = rotate_secrets(
+        &core,
+        INSTANCE,
+        &plugin_registry,
+        &revisions,
+        &consumers,
+        &breakers,
+        &provider,
+    )
+    .await;
+    assert!(rotated.is_empty(), "{rotated:?}");
+
+    ConfigResolverContext::global().remove_instance_store(INSTANCE);
+}
+
+#[tokio::test]
+async fn test_secrets_resolve_when_a_source_starts() {
+    const LAZY_INSTANCE: &str = "lazy-secret-instance";
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut plugin_registry = PluginRegistry::new();
+    plugin_registry.register_source_with_metadata(
+        Arc::new(LazySecretSourceDescriptor { seen: seen.clone() }),
+        "lazy-secret-plugin",
+    );
+    let plugin_registry = RwLock::new(plugin_registry);
+    let store = Arc::new(MemorySecretStoreProvider::new().with_secret("DB_PASSWORD", "v1"));
+    let provider: Arc<dyn SecretStoreProvider> = store.clone();
+    ConfigResolverContext::global().set_instance_store(LAZY_INSTANCE, provider);
+
+    let core = DrasiLib::builder()
+        .with_id(LAZY_INSTANCE)
+        .build()
+        .await
+        .unwrap();
+    core.start().await.unwrap();
+    let config: drasi_server::SourceConfig = serde_json::from_value(serde_json::json!({
+        "kind": "lazy-secret-mock",
+        "id": "orders",
+        "autoStart": false,
+        "password": {"kind": "Secret", "name": "DB_PASSWORD"},
+    }))
+    .unwrap();
+    let (source, _) = create_source_locked(
+        &plugin_registry,
+        LAZY_INSTANCE,
+        &SecretConsumers::new(),
+        &PluginCircuitBreakers::default(),
+        config,
+    )
+    .await
+    .unwrap();
+    core.add_source(source).await.unwrap();
+    assert!(seen.lock().unwrap().is_empty());
+
+    // Creation has long finished; the source still resolves its secret.
+    core.start_source("orders").await.unwrap();
+    assert_eq!(*seen.lock().unwrap(), ["v1"]);
+
+    core.stop_source("orders").await.unwrap();
+    store.set_secret("DB_PASSWORD", "v2").await;
+    core.start_source("orders").await.unwrap();
+    assert_eq!(*seen.lock().unwrap(), ["v1", "v2"]);
+
+    ConfigResolverContext::global().remove_instance_store(LAZY_INSTANCE);
+}
@@ -0,0 +1,127 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the server `mode`: `locked` allows start/stop but rejects
+//! deletes, `readOnly` rejects both, and reads work in every mode.
+
+#![allow(clippy::unwrap_used)]
+
+mod test_support;
+
+use test_support::create_mock_source;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    Router,
+};
+use drasi_lib::DrasiLib;
+use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
+use drasi_server::instance_registry::InstanceRegistry;
+use drasi_server::plugin_registry::PluginRegistry;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+const INSTANCE: &str = "mode-test";
+
+async fn build_app(mode: ServerMode) -> Router {
+    let core = DrasiLib::builder()
+        .with_id(INSTANCE)
+        .with_source(create_mock_source("sensors"))
+        .build()
+        .await
+        .expect("build core");
+    let core = Arc::new(core);
+    core.start().await.expect("start core");
+    drasi_lib::wait_for_status(
+        &core.component_graph(),
+        "sensors",
+        &[drasi_lib::channels::ComponentStatus::Running],
+        std::time::Duration::from_secs(5),
+    )
+    .await
+    .expect("sensors should reach Running");
+
+    let mut instances_map = indexmap::IndexMap::new();
+    instances_map.insert(INSTANCE.to_string(), core);
+    let registry = InstanceRegistry::from_map(instances_map);
+
+    let v1_router = build_v1_router(
+        registry,
+        mode,
+        None,
+        Arc::new(tokio::sync::RwLock::new(PluginRegistry::new())),
+        None,
+    );
+    Router::new().nest("/api/v1", v1_router)
+}
+
+async fn send(app: &Router, method: &str, path: &str) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(format!("/api/v1/instances/{INSTANCE}{path}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_locked_mode_allows_stop_but_rejects_delete() {
+    let app = build_app(ServerMode::Locked).await;
+
+    let (status, body) = send(&app, "POST", "/sources/sensors/stop").await;
+    assert_eq!(status, StatusCode::OK, "body={body}");
+
+    let (status, body) = send(&app, "DELETE", "/sources/sensors").await;
+    assert_eq!(status, StatusCode::CONFLICT, "body={body}");
+    assert_eq!(body["code"], "SERVER_LOCKED");
+    assert_eq!(
+        body["message"],
+        "Server is in locked mode. Cannot delete sources."
+    );
+    let details = body["details"]["technical_details"].as_str().unwrap();
+    assert!(details.starts_with("mode 'locked'"), "{details}");
+
+    let (status, _) = send(&app, "GET", "/sources/sensors").await;
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "the source must not have been deleted"
+    );
+}
+
+#[tokio::test]
+async fn test_read_only_mode_rejects_stop_and_still_serves_reads() {
+    let app = build_app(ServerMode::ReadOnly).await;
+
+    let (status, body) = send(&app, "POST", "/sources/sensors/stop").await;
+    assert_eq!(status, StatusCode::CONFLICT, "body={body}");
+    assert_eq!(body["code"], "CONFIG_READ_ONLY");
+    assert_eq!(
+        body["message"],
+        "Server is in read-only mode. Cannot stop sources."
+    );
+
+    let (status, body) = send(&app, "GET", "/sources").await;
+    assert_eq!(status, StatusCode::OK, "body={body}");
+}
@@ -9,6 +9,7 @@ use axum::{
 };
 use drasi_lib::{DrasiLib, Query, QueryConfig};
 use drasi_server::api::v1::{handlers, routes::build_v1_router};
+use drasi_server::config::ServerMode;
 use drasi_server::instance_registry::InstanceRegistry;
 use drasi_server::plugin_registry::PluginRegistry;
 use std::sync::Arc;
@@ -47,7 +48,7 @@ async fn build_snapshot_test_router(
 
     let v1_router = build_v1_router(
         registry,
-        Arc::new(false),
+        ServerMode::ReadWrite,
         None,
         Arc::new(tokio::sync::RwLock::new(plugin_registry)),
         None,
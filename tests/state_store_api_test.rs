@@ -0,0 +1,173 @@
+//! Tests for the state store inspection and maintenance endpoints.
+
+#![allow(clippy::unwrap_used)]
+
+mod test_support;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Method, Request, StatusCode},
+    Router,
+};
+use drasi_lib::state_store::{MemoryStateStoreProvider, StateStoreProvider};
+use drasi_lib::{ComponentStatus, DrasiLib};
+use drasi_server::config::ServerMode;
+use std::sync::Arc;
+use test_support::{build_state_test_router, wait_for_source_status};
+use tower::ServiceExt;
+
+const INSTANCE_ID: &str = "state-api";
+
+/// Build a router over one running instance with a mock source `src` whose
+/// partition holds a text value and a binary value.
+async fn build_state_api_router(mode: ServerMode) -> (Router, Arc<DrasiLib>) {
+    let store: Arc<dyn StateStoreProvider> = Arc::new(MemoryStateStoreProvider::new());
+    store.set("src", "cursor", b"42".to_vec()).await.unwrap();
+    store.set("src", "blob", vec![0xff, 0, 1]).await.unwrap();
+    build_state_test_router(INSTANCE_ID, mode, store).await
+}
+
+async fn send(router: &Router, method: Method, uri: &str) -> (StatusCode, serde_json::Value) {
+    let response = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+    (status, json)
+}
+
+#[tokio::test]
+async fn test_list_state_stores_reports_key_counts() {
+    let (router, _core) = build_state_api_router(ServerMode::ReadWrite).await;
+
+    let (status, body) = send(&router, Method::GET, "/api/v1/instances/state-api/state").await;
+
+    assert_eq!(status, StatusCode::OK);
+    let partitions = body["data"].as_array().unwrap();
+    assert_eq!(partitions.len(), 1);
+    assert_eq!(partitions[0]["storeId"], "src");
+    assert_eq!(partitions[0]["componentType"], "source");
+    assert_eq!(partitions[0]["keyCount"], 2);
+}
+
+#[tokio::test]
+async fn test_get_state_store_dumps_sorted_entries() {
+    let (router, _core) = build_state_api_router(ServerMode::ReadWrite).await;
+
+    let (status, body) = send(
+        &router,
+        Method::GET,
+        "/api/v1/instances/state-api/state/src",
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    let entries = body["data"]["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["key"], "blob");
+    assert_eq!(entries[0]["value"], "/wAB");
+    assert_eq!(entries[0]["encoding"], "base64");
+    assert_eq!(entries[1]["key"], "cursor");
+    assert_eq!(entries[1]["value"], "42");
+    assert_eq!(entries[1]["encoding"], "utf8");
+}
+
+#[tokio::test]
+async fn test_get_unknown_state_store_is_not_found() {
+    let (router, _core) = build_state_api_router(ServerMode::ReadWrite).await;
+
+    let (status, _) = send(
+        &router,
+        Method::GET,
+        "/api/v1/instances/state-api/state/missing",
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_reset_requires_stopped_component() {
+    let (router, core) = build_state_api_router(ServerMode::ReadWrite).await;
+
+    let (status, body) = send(
+        &router,
+        Method::POST,
+        "/api/v1/instances/state-api/state/src/reset",
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "COMPONENT_RUNNING");
+
+    core.stop_source("src").await.unwrap();
+    wait_for_source_status(&core, "src", ComponentStatus::Stopped).await;
+
+    let (status, body) = send(
+        &router,
+        Method::POST,
+        "/api/v1/instances/state-api/state/src/reset",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["deletedKeys"], 2);
+
+    let (_, body) = send(&router, Method::GET, "/api/v1/instances/state-api/state").await;
+    assert_eq!(body["data"][0]["keyCount"], 0);
+}
+
+#[tokio::test]
+async fn test_delete_state_key() {
+    let (router, core) = build_state_api_router(ServerMode::ReadWrite).await;
+    core.stop_source("src").await.unwrap();
+    wait_for_source_status(&core, "src", ComponentStatus::Stopped).await;
+
+    let (status, _) = send(
+        &router,
+        Method::DELETE,
+        "/api/v1/instances/state-api/state/src/keys/cursor",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(
+        &router,
+        Method::DELETE,
+        "/api/v1/instances/state-api/state/src/keys/cursor",
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "STATE_KEY_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_state_changes_rejected_in_read_only_mode() {
+    let (router, core) = build_state_api_router(ServerMode::ReadOnly).await;
+    core.stop_source("src").await.unwrap();
+    wait_for_source_status(&core, "src", ComponentStatus::Stopped).await;
+
+    let (status, _) = send(
+        &router,
+        Method::POST,
+        "/api/v1/instances/state-api/state/src/reset",
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = send(
+        &router,
+        Method::GET,
+        "/api/v1/instances/state-api/state/src",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -48,7 +48,7 @@ fn test_redb_state_store_provider_creation() {
 #[test]
 fn test_state_store_config_redb_helper() {
     let config = StateStoreConfig::redb("./data/test.redb");
-    assert_eq!(config.kind(), "redb");
+    assert_eq!(config.kind, "redb");
 }
 
 /// Test that create_state_store_provider factory works for REDB
@@ -57,9 +57,7 @@ fn test_create_state_store_provider_redb() {
     let temp_dir = TempDir::new().expect("Failed to create temp directory");
     let path = temp_dir.path().join("state.redb");
 
-    let config = StateStoreConfig::Redb {
-        path: ConfigValue::Static(path.to_string_lossy().to_string()),
-    };
+    let config = StateStoreConfig::redb(path.to_string_lossy());
 
     let provider = create_state_store_provider(config).expect("Failed to create provider");
 
@@ -67,6 +65,29 @@ fn test_create_state_store_provider_redb() {
     drop(provider);
 }
 
+/// Test that create_state_store_provider factory works for SQLite
+#[tokio::test]
+async fn test_create_state_store_provider_sqlite() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let path = temp_dir.path().join("state.db");
+
+    let config: StateStoreConfig =
+        serde_yaml::from_str(&format!("kind: sqlite\npath: {}\n", path.display()))
+            .expect("Failed to parse config");
+    let provider = create_state_store_provider(config).expect("Failed to create provider");
+
+    provider
+        .set("source-1", "cursor", b"42".to_vec())
+        .await
+        .expect("Failed to set value");
+    assert_eq!(
+        provider.get("source-1", "cursor").await.expect("get"),
+        Some(b"42".to_vec())
+    );
+    assert!(provider.is_durable());
+    assert!(path.exists(), "SQLite file should be created");
+}
+
 /// Test DrasiLib builder with REDB state store provider
 #[tokio::test]
 async fn test_drasi_lib_builder_with_redb_provider() -> Result<()> {
@@ -151,7 +172,7 @@ fn test_state_store_config_deserialization() {
         config.state_store.is_some(),
         "state_store should be present"
     );
-    assert_eq!(config.state_store.as_ref().unwrap().kind(), "redb");
+    assert_eq!(config.state_store.as_ref().unwrap().kind, "redb");
 }
 
 /// Test that state_store defaults to None when not specified
@@ -191,7 +212,7 @@ fn test_state_store_with_full_config() {
     let config: DrasiServerConfig = serde_yaml::from_str(yaml).expect("Failed to parse config");
 
     assert!(config.state_store.is_some());
-    assert_eq!(config.state_store.as_ref().unwrap().kind(), "redb");
+    assert_eq!(config.state_store.as_ref().unwrap().kind, "redb");
     assert!(config.persist_index);
     assert!(config.persist_config);
 
@@ -228,7 +249,7 @@ fn test_state_store_serialization_roundtrip() {
         deserialized.state_store.is_some(),
         "Deserialized config should have state_store"
     );
-    assert_eq!(deserialized.state_store.as_ref().unwrap().kind(), "redb");
+    assert_eq!(deserialized.state_store.as_ref().unwrap().kind, "redb");
 }
 
 /// Test that state store file is created when REDB provider is used
@@ -418,13 +439,12 @@ fn test_multi_instance_state_store_config() {
         &config.instances[0].state_store,
         &config.instances[1].state_store,
     ) {
-        (Some(StateStoreConfig::Redb { path: p1 }), Some(StateStoreConfig::Redb { path: p2 })) => {
-            if let (ConfigValue::Static(path1), ConfigValue::Static(path2)) = (p1, p2) {
-                assert_ne!(
-                    path1, path2,
-                    "Each instance should have its own state store path"
-                );
-            }
+        (Some(s1), Some(s2)) => {
+            assert_eq!((s1.kind.as_str(), s2.kind.as_str()), ("redb", "redb"));
+            assert_ne!(
+                s1.config["path"], s2.config["path"],
+                "Each instance should have its own state store path"
+            );
         }
         _ => panic!("Expected REDB state stores for both instances"),
     }
@@ -28,6 +28,8 @@ pub fn mock_source(id: impl Into<String>) -> SourceConfig {
         id: id.into(),
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: serde_json::json!({"dataType": {"type": "generic"}, "intervalMs": 5000}),
     }
@@ -40,6 +42,8 @@ pub fn mock_source_manual(id: impl Into<String>) -> SourceConfig {
         id: id.into(),
         auto_start: false,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         bootstrap_provider: None,
         config: serde_json::json!({"dataType": {"type": "generic"}, "intervalMs": 5000}),
     }
@@ -68,6 +72,8 @@ pub fn log_reaction(id: impl Into<String>, queries: Vec<String>) -> ReactionConf
         queries,
         auto_start: true,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({"routes": {}}),
     }
 }
@@ -80,6 +86,8 @@ pub fn log_reaction_manual(id: impl Into<String>, queries: Vec<String>) -> React
         queries,
         auto_start: false,
         identity_provider: None,
+        restart_policy: None,
+        config_version: None,
         config: serde_json::json!({"routes": {}}),
     }
 }
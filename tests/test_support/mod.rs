@@ -20,6 +20,8 @@ pub mod config_helpers;
 pub mod mock_components;
 #[allow(dead_code)]
 pub mod solution_helpers;
+#[allow(dead_code)]
+pub mod state_helpers;
 
 // Re-export commonly used helpers
 #[allow(unused_imports)]
@@ -28,3 +30,5 @@ pub use config_helpers::*;
 pub use mock_components::*;
 #[allow(unused_imports)]
 pub use solution_helpers::*;
+#[allow(unused_imports)]
+pub use state_helpers::*;
@@ -24,6 +24,7 @@ use axum::Router;
 use drasi_lib::{DrasiLib, Query};
 use drasi_server::api::v1::handlers;
 use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
 use drasi_server::instance_registry::InstanceRegistry;
 use drasi_server::plugin_registry::PluginRegistry;
 use std::path::{Path, PathBuf};
@@ -267,7 +268,7 @@ pub async fn create_test_router_with_solutions(
     let core = Arc::new(core);
     core.start().await.expect("Failed to start core");
 
-    let read_only = Arc::new(false);
+    let mode = ServerMode::ReadWrite;
     let config_persistence = None;
 
     // Create registry
@@ -279,7 +280,7 @@ pub async fn create_test_router_with_solutions(
     let plugin_registry = create_test_plugin_registry();
     let v1_router = build_v1_router(
         registry,
-        read_only,
+        mode,
         config_persistence,
         Arc::new(tokio::sync::RwLock::new(plugin_registry)),
         solutions_dir,
@@ -349,14 +350,14 @@ pub async fn create_multi_instance_test_router(
         instances_data.push((instance_id.to_string(), core, components));
     }
 
-    let read_only = Arc::new(false);
+    let mode = ServerMode::ReadWrite;
     let config_persistence = None;
     let registry = InstanceRegistry::from_map(instances_map);
 
     let plugin_registry = create_test_plugin_registry();
     let v1_router = build_v1_router(
         registry,
-        read_only,
+        mode,
         config_persistence,
         Arc::new(tokio::sync::RwLock::new(plugin_registry)),
         solutions_dir,
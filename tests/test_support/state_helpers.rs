@@ -0,0 +1,77 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for tests of the endpoints that work on an instance's state
+//! store, such as state inspection and backup.
+
+use axum::Router;
+use drasi_lib::state_store::StateStoreProvider;
+use drasi_lib::{ComponentStatus, DrasiLib};
+use drasi_server::api::v1::routes::build_v1_router;
+use drasi_server::config::ServerMode;
+use drasi_server::instance_registry::InstanceRegistry;
+use drasi_server::plugin_registry::PluginRegistry;
+use std::sync::Arc;
+
+use super::create_mock_source;
+
+/// Build a router over one running instance `instance_id` with a mock
+/// source `src` and `store` as its state store.
+pub async fn build_state_test_router(
+    instance_id: &str,
+    mode: ServerMode,
+    store: Arc<dyn StateStoreProvider>,
+) -> (Router, Arc<DrasiLib>) {
+    let core = DrasiLib::builder()
+        .with_id(instance_id)
+        .with_source(create_mock_source("src"))
+        .with_state_store_provider(store.clone())
+        .build()
+        .await
+        .expect("Failed to build test core");
+    let core = Arc::new(core);
+    core.start().await.expect("Failed to start core");
+    wait_for_source_status(&core, "src", ComponentStatus::Running).await;
+
+    let mut instances_map = indexmap::IndexMap::new();
+    instances_map.insert(instance_id.to_string(), core.clone());
+    let registry = InstanceRegistry::from_map(instances_map);
+    registry
+        .set_state_store(instance_id.to_string(), store)
+        .await;
+
+    let mut plugin_registry = PluginRegistry::new();
+    drasi_server::register_core_plugins(&mut plugin_registry);
+
+    let router = build_v1_router(
+        registry,
+        mode,
+        None,
+        Arc::new(tokio::sync::RwLock::new(plugin_registry)),
+        None,
+    );
+    (router, core)
+}
+
+/// Wait until source `id` reports `status`; component status updates arrive
+/// asynchronously after start and stop.
+pub async fn wait_for_source_status(core: &DrasiLib, id: &str, status: ComponentStatus) {
+    for _ in 0..100 {
+        if core.get_source_status(id).await.unwrap() == status {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    panic!("source '{id}' never reached {status:?}");
+}
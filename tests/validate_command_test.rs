@@ -235,6 +235,62 @@ reactions: []
 // Invalid Configuration Tests
 // =============================================================================
 
+#[test]
+fn test_validate_rejects_unknown_state_store_kind() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+
+    let config = r#"
+id: test-server
+host: 0.0.0.0
+port: 8080
+logLevel: info
+stateStore:
+  kind: postgres
+  path: ./data/state.redb
+sources: []
+queries: []
+reactions: []
+"#;
+    fs::write(&config_path, config).unwrap();
+
+    let (success, stdout, _stderr) = run_validate(config_path.to_str().unwrap());
+
+    assert!(!success, "Unknown state store kind should fail validation");
+    assert!(
+        stdout.contains("Unknown state store kind: 'postgres'"),
+        "Output: {stdout}"
+    );
+}
+
+#[test]
+fn test_validate_rejects_state_store_snake_case_field() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+
+    let config = r#"
+id: test-server
+host: 0.0.0.0
+port: 8080
+logLevel: info
+stateStore:
+  kind: redb
+  file_path: ./data/state.redb
+sources: []
+queries: []
+reactions: []
+"#;
+    fs::write(&config_path, config).unwrap();
+
+    let (success, stdout, _stderr) = run_validate(config_path.to_str().unwrap());
+
+    assert!(
+        !success,
+        "snake_case state store field should fail validation"
+    );
+    assert!(stdout.contains("file_path"), "Output: {stdout}");
+}
+
 #[test]
 fn test_validate_rejects_snake_case_fields() {
     let temp_dir = TempDir::new().unwrap();
@@ -408,6 +464,45 @@ reactions: []
 // Missing File Tests
 // =============================================================================
 
+#[test]
+fn test_validate_fails_on_failed_config_migration() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.yaml");
+
+    // The built-in application reaction is at configVersion 1.0.0 and ships
+    // no migration from 0.1.0.
+    let config = r#"
+id: test-server
+host: 0.0.0.0
+port: 8080
+logLevel: info
+sources:
+  - kind: mock
+    id: test-source
+queries:
+  - id: my-query
+    query: "MATCH (n) RETURN n"
+    sources:
+      - sourceId: test-source
+reactions:
+  - kind: application
+    id: test-app
+    queries: [my-query]
+    configVersion: "0.1.0"
+"#;
+    fs::write(&config_path, config).unwrap();
+
+    let (success, stdout, _stderr) = run_validate(config_path.to_str().unwrap());
+
+    assert!(
+        !success,
+        "Config whose migration fails should fail validation. Output: {stdout}"
+    );
+    assert!(stdout.contains("no migration for reaction kind 'application'"));
+    assert!(stdout.contains("1 error(s)"), "Output: {stdout}");
+    assert!(!stdout.contains("all valid"));
+}
+
 #[test]
 fn test_validate_missing_file() {
     let (success, stdout, _stderr) = run_validate("/nonexistent/path/config.yaml");